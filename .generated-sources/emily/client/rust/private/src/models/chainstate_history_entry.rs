@@ -0,0 +1,51 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// ChainstateHistoryEntry : A chainstate entry in the history of the canonical chain.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChainstateHistoryEntry {
+    /// Bitcoin block height
+    #[serde(
+        rename = "bitcoinBlockHeight",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitcoin_block_height: Option<Option<u64>>,
+    /// Stacks block hash at the height.
+    #[serde(rename = "stacksBlockHash")]
+    pub stacks_block_hash: String,
+    /// Stacks block height.
+    #[serde(rename = "stacksBlockHeight")]
+    pub stacks_block_height: u64,
+    /// Hash of the conflicting stacks block that this block replaced at the same height during a reorg, if any.
+    #[serde(
+        rename = "supersededHash",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub superseded_hash: Option<Option<String>>,
+}
+
+impl ChainstateHistoryEntry {
+    /// A chainstate entry in the history of the canonical chain.
+    pub fn new(stacks_block_hash: String, stacks_block_height: u64) -> ChainstateHistoryEntry {
+        ChainstateHistoryEntry {
+            bitcoin_block_height: None,
+            stacks_block_hash,
+            stacks_block_height,
+            superseded_hash: None,
+        }
+    }
+}
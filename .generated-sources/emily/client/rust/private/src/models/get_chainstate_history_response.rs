@@ -0,0 +1,29 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetChainstateHistoryResponse : Response to get chainstate history request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetChainstateHistoryResponse {
+    /// Chainstate entries in descending height order, starting at the chain tip.
+    #[serde(rename = "chainstates")]
+    pub chainstates: Vec<models::ChainstateHistoryEntry>,
+}
+
+impl GetChainstateHistoryResponse {
+    /// Response to get chainstate history request.
+    pub fn new(
+        chainstates: Vec<models::ChainstateHistoryEntry>,
+    ) -> GetChainstateHistoryResponse {
+        GetChainstateHistoryResponse { chainstates }
+    }
+}
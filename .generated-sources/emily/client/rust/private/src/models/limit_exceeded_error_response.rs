@@ -0,0 +1,37 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// LimitExceededErrorResponse : Body of the response to a deposit request that falls outside of the configured deposit limits.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LimitExceededErrorResponse {
+    /// Always `limit_exceeded`.
+    #[serde(rename = "error")]
+    pub error: String,
+    /// The limit that the deposit exceeded, one of `perDepositMinimum`, `perDepositCap` or `rollingDepositCap`.
+    #[serde(rename = "limit")]
+    pub limit: String,
+    /// The value the deposit amount was checked against. For the rolling deposit cap this is the amount still available in the window.
+    #[serde(rename = "value")]
+    pub value: u64,
+}
+
+impl LimitExceededErrorResponse {
+    /// Body of the response to a deposit request that falls outside of the configured deposit limits.
+    pub fn new(error: String, limit: String, value: u64) -> LimitExceededErrorResponse {
+        LimitExceededErrorResponse {
+            error,
+            limit,
+            value,
+        }
+    }
+}
@@ -1,7 +1,13 @@
 pub mod account_limits;
 pub use self::account_limits::AccountLimits;
+pub mod bulk_chainstate_conflict_error_response;
+pub use self::bulk_chainstate_conflict_error_response::BulkChainstateConflictErrorResponse;
+pub mod chain_gap_error_response;
+pub use self::chain_gap_error_response::ChainGapErrorResponse;
 pub mod chainstate;
 pub use self::chainstate::Chainstate;
+pub mod chainstate_history_entry;
+pub use self::chainstate_history_entry::ChainstateHistoryEntry;
 pub mod create_deposit_request_body;
 pub use self::create_deposit_request_body::CreateDepositRequestBody;
 pub mod create_withdrawal_request_body;
@@ -10,6 +16,8 @@ pub mod deposit;
 pub use self::deposit::Deposit;
 pub mod deposit_info;
 pub use self::deposit_info::DepositInfo;
+pub mod deposit_key;
+pub use self::deposit_key::DepositKey;
 pub mod deposit_parameters;
 pub use self::deposit_parameters::DepositParameters;
 pub mod deposit_update;
@@ -18,18 +26,42 @@ pub mod error_response;
 pub use self::error_response::ErrorResponse;
 pub mod fulfillment;
 pub use self::fulfillment::Fulfillment;
+pub mod get_chainstate_history_response;
+pub use self::get_chainstate_history_response::GetChainstateHistoryResponse;
+pub mod get_deposit_history_response;
+pub use self::get_deposit_history_response::GetDepositHistoryResponse;
 pub mod get_deposits_for_transaction_response;
 pub use self::get_deposits_for_transaction_response::GetDepositsForTransactionResponse;
 pub mod get_deposits_response;
 pub use self::get_deposits_response::GetDepositsResponse;
+pub mod get_withdrawal_history_response;
+pub use self::get_withdrawal_history_response::GetWithdrawalHistoryResponse;
 pub mod get_withdrawals_response;
 pub use self::get_withdrawals_response::GetWithdrawalsResponse;
 pub mod health_data;
 pub use self::health_data::HealthData;
+pub mod limit_exceeded_error_response;
+pub use self::limit_exceeded_error_response::LimitExceededErrorResponse;
 pub mod limits;
 pub use self::limits::Limits;
+pub mod lookup_deposits_request_body;
+pub use self::lookup_deposits_request_body::LookupDepositsRequestBody;
+pub mod lookup_deposits_response;
+pub use self::lookup_deposits_response::LookupDepositsResponse;
+pub mod not_found_error_response;
+pub use self::not_found_error_response::NotFoundErrorResponse;
+pub mod set_chainstates_bulk_request_body;
+pub use self::set_chainstates_bulk_request_body::SetChainstatesBulkRequestBody;
+pub mod set_chainstates_bulk_response;
+pub use self::set_chainstates_bulk_response::SetChainstatesBulkResponse;
 pub mod status;
 pub use self::status::Status;
+pub mod status_history_entry;
+pub use self::status_history_entry::StatusHistoryEntry;
+pub mod subscription;
+pub use self::subscription::Subscription;
+pub mod subscription_operation;
+pub use self::subscription_operation::SubscriptionOperation;
 pub mod update_deposits_request_body;
 pub use self::update_deposits_request_body::UpdateDepositsRequestBody;
 pub mod update_deposits_response;
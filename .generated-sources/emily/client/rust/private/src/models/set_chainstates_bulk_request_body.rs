@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// SetChainstatesBulkRequestBody : Request structure for the bulk chainstate submission.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SetChainstatesBulkRequestBody {
+    /// Chainstates in ascending height order. The first must be one height above the chain tip, or at any height if there is no chain tip yet, and each following chainstate must be one height above the previous one. At most 1000 chainstates are accepted.
+    #[serde(rename = "chainstates")]
+    pub chainstates: Vec<models::Chainstate>,
+}
+
+impl SetChainstatesBulkRequestBody {
+    /// Request structure for the bulk chainstate submission.
+    pub fn new(chainstates: Vec<models::Chainstate>) -> SetChainstatesBulkRequestBody {
+        SetChainstatesBulkRequestBody { chainstates }
+    }
+}
@@ -0,0 +1,36 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// Subscription : A subscription to status changes of deposits or withdrawals.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    #[serde(rename = "operation")]
+    pub operation: models::SubscriptionOperation,
+    /// Only notify about operations that change to one of these statuses. Every status change is notified about if this is empty.
+    #[serde(rename = "statuses", skip_serializing_if = "Option::is_none")]
+    pub statuses: Option<Vec<models::Status>>,
+    /// The URL that notifications are sent to with an HTTP POST.
+    #[serde(rename = "url")]
+    pub url: String,
+}
+
+impl Subscription {
+    /// A subscription to status changes of deposits or withdrawals.
+    pub fn new(operation: models::SubscriptionOperation, url: String) -> Subscription {
+        Subscription {
+            operation,
+            statuses: None,
+            url,
+        }
+    }
+}
@@ -29,6 +29,14 @@ pub struct WithdrawalUpdate {
     /// The status message of the withdrawal.
     #[serde(rename = "statusMessage")]
     pub status_message: String,
+    /// Public key of the signer making the update. Until updates are authenticated per signer this is taken as given, so it is only meant for auditing conflicting updates.
+    #[serde(
+        rename = "updatedBy",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated_by: Option<Option<String>>,
 }
 
 impl WithdrawalUpdate {
@@ -43,6 +51,7 @@ impl WithdrawalUpdate {
             request_id,
             status,
             status_message,
+            updated_by: None,
         }
     }
 }
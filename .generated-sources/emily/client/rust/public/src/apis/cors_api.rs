@@ -20,6 +20,13 @@ pub enum ChainstateHeightOptionsError {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed errors of method [`chainstate_history_options`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChainstateHistoryOptionsError {
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed errors of method [`chainstate_options`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -48,6 +55,13 @@ pub enum DepositReclaimPubkeysReclaimPubkeysOptionsError {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed errors of method [`deposit_txid_index_history_options`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DepositTxidIndexHistoryOptionsError {
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed errors of method [`deposit_txid_index_options`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -83,6 +97,13 @@ pub enum LimitsOptionsError {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed errors of method [`withdrawal_id_history_options`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WithdrawalIdHistoryOptionsError {
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed errors of method [`withdrawal_id_options`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -153,6 +174,43 @@ pub async fn chainstate_height_options(
     }
 }
 
+/// Handles CORS preflight requests
+pub async fn chainstate_history_options(
+    configuration: &configuration::Configuration,
+) -> Result<(), Error<ChainstateHistoryOptionsError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/chainstate/history", local_var_configuration.base_path);
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::OPTIONS, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        Ok(())
+    } else {
+        let local_var_entity: Option<ChainstateHistoryOptionsError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 /// Handles CORS preflight requests
 pub async fn chainstate_options(
     configuration: &configuration::Configuration,
@@ -311,6 +369,50 @@ pub async fn deposit_reclaim_pubkeys_reclaim_pubkeys_options(
     }
 }
 
+/// Handles CORS preflight requests
+pub async fn deposit_txid_index_history_options(
+    configuration: &configuration::Configuration,
+    txid: &str,
+    index: &str,
+) -> Result<(), Error<DepositTxidIndexHistoryOptionsError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!(
+        "{}/deposit/{txid}/{index}/history",
+        local_var_configuration.base_path,
+        txid = crate::apis::urlencode(txid),
+        index = crate::apis::urlencode(index)
+    );
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::OPTIONS, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        Ok(())
+    } else {
+        let local_var_entity: Option<DepositTxidIndexHistoryOptionsError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 /// Handles CORS preflight requests
 pub async fn deposit_txid_index_options(
     configuration: &configuration::Configuration,
@@ -513,6 +615,48 @@ pub async fn limits_options(
     }
 }
 
+/// Handles CORS preflight requests
+pub async fn withdrawal_id_history_options(
+    configuration: &configuration::Configuration,
+    id: u64,
+) -> Result<(), Error<WithdrawalIdHistoryOptionsError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!(
+        "{}/withdrawal/{id}/history",
+        local_var_configuration.base_path,
+        id = id
+    );
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::OPTIONS, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        Ok(())
+    } else {
+        let local_var_entity: Option<WithdrawalIdHistoryOptionsError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 /// Handles CORS preflight requests
 pub async fn withdrawal_id_options(
     configuration: &configuration::Configuration,
@@ -20,6 +20,7 @@ pub enum CreateDepositError {
     Status400(models::ErrorResponse),
     Status404(models::ErrorResponse),
     Status405(models::ErrorResponse),
+    Status409(models::ErrorResponse),
     Status500(models::ErrorResponse),
     UnknownValue(serde_json::Value),
 }
@@ -29,7 +30,18 @@ pub enum CreateDepositError {
 #[serde(untagged)]
 pub enum GetDepositError {
     Status400(models::ErrorResponse),
-    Status404(models::ErrorResponse),
+    Status404(models::NotFoundErrorResponse),
+    Status405(models::ErrorResponse),
+    Status500(models::ErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_deposit_history`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetDepositHistoryError {
+    Status400(models::ErrorResponse),
+    Status404(models::NotFoundErrorResponse),
     Status405(models::ErrorResponse),
     Status500(models::ErrorResponse),
     UnknownValue(serde_json::Value),
@@ -79,6 +91,17 @@ pub enum GetDepositsForTransactionError {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed errors of method [`lookup_deposits`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LookupDepositsError {
+    Status400(models::ErrorResponse),
+    Status404(models::ErrorResponse),
+    Status405(models::ErrorResponse),
+    Status500(models::ErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
 /// struct for typed errors of method [`update_deposits`]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -172,11 +195,66 @@ pub async fn get_deposit(
     }
 }
 
+pub async fn get_deposit_history(
+    configuration: &configuration::Configuration,
+    txid: &str,
+    index: &str,
+    next_token: Option<&str>,
+    page_size: Option<u32>,
+) -> Result<models::GetDepositHistoryResponse, Error<GetDepositHistoryError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!(
+        "{}/deposit/{txid}/{index}/history",
+        local_var_configuration.base_path,
+        txid = crate::apis::urlencode(txid),
+        index = crate::apis::urlencode(index)
+    );
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = next_token {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("nextToken", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = page_size {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("pageSize", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        serde_json::from_str(&local_var_content).map_err(Error::from)
+    } else {
+        let local_var_entity: Option<GetDepositHistoryError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn get_deposits(
     configuration: &configuration::Configuration,
     status: models::Status,
     next_token: Option<&str>,
     page_size: Option<u32>,
+    from_height: Option<u64>,
+    to_height: Option<u64>,
 ) -> Result<models::GetDepositsResponse, Error<GetDepositsError>> {
     let local_var_configuration = configuration;
 
@@ -195,6 +273,14 @@ pub async fn get_deposits(
         local_var_req_builder =
             local_var_req_builder.query(&[("pageSize", &local_var_str.to_string())]);
     }
+    if let Some(ref local_var_str) = from_height {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("fromHeight", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = to_height {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("toHeight", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder =
             local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
@@ -223,6 +309,7 @@ pub async fn get_deposits(
 pub async fn get_deposits_for_recipient(
     configuration: &configuration::Configuration,
     recipient: &str,
+    status: Option<models::Status>,
     next_token: Option<&str>,
     page_size: Option<u32>,
 ) -> Result<models::GetDepositsResponse, Error<GetDepositsForRecipientError>> {
@@ -238,6 +325,10 @@ pub async fn get_deposits_for_recipient(
     let mut local_var_req_builder =
         local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
 
+    if let Some(ref local_var_str) = status {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("status", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_str) = next_token {
         local_var_req_builder =
             local_var_req_builder.query(&[("nextToken", &local_var_str.to_string())]);
@@ -373,6 +464,44 @@ pub async fn get_deposits_for_transaction(
     }
 }
 
+pub async fn lookup_deposits(
+    configuration: &configuration::Configuration,
+    lookup_deposits_request_body: models::LookupDepositsRequestBody,
+) -> Result<models::LookupDepositsResponse, Error<LookupDepositsError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!("{}/deposit/lookup", local_var_configuration.base_path);
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::POST, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+    local_var_req_builder = local_var_req_builder.json(&lookup_deposits_request_body);
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        serde_json::from_str(&local_var_content).map_err(Error::from)
+    } else {
+        let local_var_entity: Option<LookupDepositsError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn update_deposits(
     configuration: &configuration::Configuration,
     update_deposits_request_body: models::UpdateDepositsRequestBody,
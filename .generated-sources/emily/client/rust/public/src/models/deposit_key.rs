@@ -0,0 +1,33 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// DepositKey : The key of a single deposit.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositKey {
+    /// Output index on the bitcoin transaction associated with this specific deposit.
+    #[serde(rename = "bitcoinTxOutputIndex")]
+    pub bitcoin_tx_output_index: u32,
+    /// Bitcoin transaction id.
+    #[serde(rename = "bitcoinTxid")]
+    pub bitcoin_txid: String,
+}
+
+impl DepositKey {
+    /// The key of a single deposit.
+    pub fn new(bitcoin_tx_output_index: u32, bitcoin_txid: String) -> DepositKey {
+        DepositKey {
+            bitcoin_tx_output_index,
+            bitcoin_txid,
+        }
+    }
+}
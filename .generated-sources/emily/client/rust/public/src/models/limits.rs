@@ -17,6 +17,14 @@ pub struct Limits {
     /// Represents the individual limits for requests coming from different accounts.
     #[serde(rename = "accountCaps")]
     pub account_caps: std::collections::HashMap<String, models::AccountLimits>,
+    /// Total amount of sBTC that can still be minted by deposits in the current window. Only confirmed deposits are counted here.
+    #[serde(
+        rename = "availableToDeposit",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub available_to_deposit: Option<Option<u64>>,
     /// Total amount sBTC still available for withdrawals in current window. All withdrawals except rejected counted here
     #[serde(
         rename = "availableToWithdraw",
@@ -57,6 +65,22 @@ pub struct Limits {
         skip_serializing_if = "Option::is_none"
     )]
     pub per_withdrawal_cap: Option<Option<u64>>,
+    /// Number of blocks that define the rolling deposit window.
+    #[serde(
+        rename = "rollingDepositBlocks",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_deposit_blocks: Option<Option<u64>>,
+    /// Maximum total sBTC that can be minted by deposits within the rolling deposit window.
+    #[serde(
+        rename = "rollingDepositCap",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_deposit_cap: Option<Option<u64>>,
     /// Number of blocks that define the rolling withdrawal window.
     #[serde(
         rename = "rollingWithdrawalBlocks",
@@ -80,11 +104,14 @@ impl Limits {
     pub fn new(account_caps: std::collections::HashMap<String, models::AccountLimits>) -> Limits {
         Limits {
             account_caps,
+            available_to_deposit: None,
             available_to_withdraw: None,
             peg_cap: None,
             per_deposit_cap: None,
             per_deposit_minimum: None,
             per_withdrawal_cap: None,
+            rolling_deposit_blocks: None,
+            rolling_deposit_cap: None,
             rolling_withdrawal_blocks: None,
             rolling_withdrawal_cap: None,
         }
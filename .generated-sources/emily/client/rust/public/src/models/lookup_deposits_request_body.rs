@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// LookupDepositsRequestBody : Request structure for the lookup deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LookupDepositsRequestBody {
+    /// The keys of the deposits to look up.
+    #[serde(rename = "deposits")]
+    pub deposits: Vec<models::DepositKey>,
+}
+
+impl LookupDepositsRequestBody {
+    /// Request structure for the lookup deposits request.
+    pub fn new(deposits: Vec<models::DepositKey>) -> LookupDepositsRequestBody {
+        LookupDepositsRequestBody { deposits }
+    }
+}
@@ -0,0 +1,54 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// StatusHistoryEntry : An event in the status history of a deposit or withdrawal.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusHistoryEntry {
+    /// The message that came with the status update.
+    #[serde(rename = "message")]
+    pub message: String,
+    /// Stacks block hash of the chain tip when the event happened.
+    #[serde(rename = "stacksBlockHash")]
+    pub stacks_block_hash: String,
+    /// Stacks block height of the chain tip when the event happened.
+    #[serde(rename = "stacksBlockHeight")]
+    pub stacks_block_height: u64,
+    #[serde(rename = "status")]
+    pub status: models::Status,
+    /// Public key of the signer that made the update, if known.
+    #[serde(
+        rename = "updatedBy",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated_by: Option<Option<String>>,
+}
+
+impl StatusHistoryEntry {
+    /// An event in the status history of a deposit or withdrawal.
+    pub fn new(
+        message: String,
+        stacks_block_hash: String,
+        stacks_block_height: u64,
+        status: models::Status,
+    ) -> StatusHistoryEntry {
+        StatusHistoryEntry {
+            message,
+            stacks_block_hash,
+            stacks_block_height,
+            status,
+            updated_by: None,
+        }
+    }
+}
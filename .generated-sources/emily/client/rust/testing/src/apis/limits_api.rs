@@ -132,7 +132,7 @@ pub async fn get_limits_for_account(
     }
 }
 
-/// Note, that `available_to_withdraw` is not settable, but is calculated based on the other fields. Value of `available_to_withdraw` passed to this endpoint will be ignored.
+/// Note, that `available_to_withdraw` and `available_to_deposit` are not settable, but are calculated based on the other fields. Values of `available_to_withdraw` and `available_to_deposit` passed to this endpoint will be ignored.
 pub async fn set_limits(
     configuration: &configuration::Configuration,
     limits: models::Limits,
@@ -98,6 +98,7 @@ pub mod deposit_api;
 pub mod health_api;
 pub mod limits_api;
 pub mod new_block_api;
+pub mod subscription_api;
 pub mod testing_api;
 pub mod withdrawal_api;
 
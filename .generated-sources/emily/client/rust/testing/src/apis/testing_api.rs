@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 #[serde(untagged)]
 pub enum WipeDatabasesError {
     Status400(),
+    Status403(),
     Status404(),
     Status405(),
     Status500(),
@@ -26,7 +27,7 @@ pub enum WipeDatabasesError {
 
 pub async fn wipe_databases(
     configuration: &configuration::Configuration,
-) -> Result<(), Error<WipeDatabasesError>> {
+) -> Result<models::WipeDatabasesResponse, Error<WipeDatabasesError>> {
     let local_var_configuration = configuration;
 
     let local_var_client = &local_var_configuration.client;
@@ -55,7 +56,7 @@ pub async fn wipe_databases(
     let local_var_content = local_var_resp.text().await?;
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
-        Ok(())
+        serde_json::from_str(&local_var_content).map_err(Error::from)
     } else {
         let local_var_entity: Option<WipeDatabasesError> =
             serde_json::from_str(&local_var_content).ok();
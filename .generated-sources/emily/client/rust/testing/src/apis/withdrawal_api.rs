@@ -29,7 +29,18 @@ pub enum CreateWithdrawalError {
 #[serde(untagged)]
 pub enum GetWithdrawalError {
     Status400(models::ErrorResponse),
-    Status404(models::ErrorResponse),
+    Status404(models::NotFoundErrorResponse),
+    Status405(models::ErrorResponse),
+    Status500(models::ErrorResponse),
+    UnknownValue(serde_json::Value),
+}
+
+/// struct for typed errors of method [`get_withdrawal_history`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetWithdrawalHistoryError {
+    Status400(models::ErrorResponse),
+    Status404(models::NotFoundErrorResponse),
     Status405(models::ErrorResponse),
     Status500(models::ErrorResponse),
     UnknownValue(serde_json::Value),
@@ -167,6 +178,57 @@ pub async fn get_withdrawal(
     }
 }
 
+pub async fn get_withdrawal_history(
+    configuration: &configuration::Configuration,
+    id: u64,
+    next_token: Option<&str>,
+    page_size: Option<u32>,
+) -> Result<models::GetWithdrawalHistoryResponse, Error<GetWithdrawalHistoryError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!(
+        "{}/withdrawal/{id}/history",
+        local_var_configuration.base_path,
+        id = id
+    );
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_str) = next_token {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("nextToken", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = page_size {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("pageSize", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        serde_json::from_str(&local_var_content).map_err(Error::from)
+    } else {
+        let local_var_entity: Option<GetWithdrawalHistoryError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
 pub async fn get_withdrawals(
     configuration: &configuration::Configuration,
     status: models::Status,
@@ -218,6 +280,7 @@ pub async fn get_withdrawals(
 pub async fn get_withdrawals_for_recipient(
     configuration: &configuration::Configuration,
     recipient: &str,
+    status: Option<models::Status>,
     next_token: Option<&str>,
     page_size: Option<u32>,
 ) -> Result<models::GetWithdrawalsResponse, Error<GetWithdrawalsForRecipientError>> {
@@ -233,6 +296,10 @@ pub async fn get_withdrawals_for_recipient(
     let mut local_var_req_builder =
         local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
 
+    if let Some(ref local_var_str) = status {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("status", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_str) = next_token {
         local_var_req_builder =
             local_var_req_builder.query(&[("nextToken", &local_var_str.to_string())]);
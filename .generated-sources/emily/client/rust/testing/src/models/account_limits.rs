@@ -46,6 +46,22 @@ pub struct AccountLimits {
         skip_serializing_if = "Option::is_none"
     )]
     pub per_withdrawal_cap: Option<Option<u64>>,
+    /// Number of blocks that define the rolling deposit window.
+    #[serde(
+        rename = "rollingDepositBlocks",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_deposit_blocks: Option<Option<u64>>,
+    /// Maximum total sBTC that can be minted by deposits within the rolling deposit window.
+    #[serde(
+        rename = "rollingDepositCap",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_deposit_cap: Option<Option<u64>>,
     /// Number of blocks that define the rolling withdrawal window.
     #[serde(
         rename = "rollingWithdrawalBlocks",
@@ -72,6 +88,8 @@ impl AccountLimits {
             per_deposit_cap: None,
             per_deposit_minimum: None,
             per_withdrawal_cap: None,
+            rolling_deposit_blocks: None,
+            rolling_deposit_cap: None,
             rolling_withdrawal_blocks: None,
             rolling_withdrawal_cap: None,
         }
@@ -0,0 +1,33 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// BulkChainstateConflictErrorResponse : Body of the response to a bulk chainstate submission that does not extend the chain one height at a time. None of the chainstates in the submission are applied.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BulkChainstateConflictErrorResponse {
+    /// Always `bulk_chainstate_conflict`.
+    #[serde(rename = "error")]
+    pub error: String,
+    /// The index of the first offending chainstate in the submission.
+    #[serde(rename = "index")]
+    pub index: u64,
+    /// Why the chainstate was rejected.
+    #[serde(rename = "message")]
+    pub message: String,
+}
+
+impl BulkChainstateConflictErrorResponse {
+    /// Body of the response to a bulk chainstate submission that does not extend the chain one height at a time. None of the chainstates in the submission are applied.
+    pub fn new(error: String, index: u64, message: String) -> BulkChainstateConflictErrorResponse {
+        BulkChainstateConflictErrorResponse { error, index, message }
+    }
+}
@@ -0,0 +1,41 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// ChainGapErrorResponse : Body of the response to a chainstate submission that is too far above the current chain tip. The client should submit the missing heights first.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChainGapErrorResponse {
+    /// Always `chain_gap`.
+    #[serde(rename = "error")]
+    pub error: String,
+    /// The first height missing between the chain tip and the submitted chainstate.
+    #[serde(rename = "firstMissingHeight")]
+    pub first_missing_height: u64,
+    /// The last height missing between the chain tip and the submitted chainstate.
+    #[serde(rename = "lastMissingHeight")]
+    pub last_missing_height: u64,
+}
+
+impl ChainGapErrorResponse {
+    /// Body of the response to a chainstate submission that is too far above the current chain tip. The client should submit the missing heights first.
+    pub fn new(
+        error: String,
+        first_missing_height: u64,
+        last_missing_height: u64,
+    ) -> ChainGapErrorResponse {
+        ChainGapErrorResponse {
+            error,
+            first_missing_height,
+            last_missing_height,
+        }
+    }
+}
@@ -19,7 +19,7 @@ pub struct CreateWithdrawalRequestBody {
     pub amount: u64,
     #[serde(rename = "parameters")]
     pub parameters: Box<models::WithdrawalParameters>,
-    /// The recipient's Bitcoin hex-encoded scriptPubKey.
+    /// The recipient's hex-encoded Bitcoin scriptPubKey or Bitcoin address. It is stored as the lowercase hex-encoded scriptPubKey.
     #[serde(rename = "recipient")]
     pub recipient: String,
     /// The id of the Stacks withdrawal request that initiated the sBTC operation.
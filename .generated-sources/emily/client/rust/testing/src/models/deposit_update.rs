@@ -32,6 +32,14 @@ pub struct DepositUpdate {
     /// The status message of the deposit.
     #[serde(rename = "statusMessage")]
     pub status_message: String,
+    /// Public key of the signer making the update. Until updates are authenticated per signer this is taken as given, so it is only meant for auditing conflicting updates.
+    #[serde(
+        rename = "updatedBy",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated_by: Option<Option<String>>,
 }
 
 impl DepositUpdate {
@@ -48,6 +56,7 @@ impl DepositUpdate {
             fulfillment: None,
             status,
             status_message,
+            updated_by: None,
         }
     }
 }
@@ -0,0 +1,35 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetWithdrawalHistoryResponse : Response to get withdrawal history request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetWithdrawalHistoryResponse {
+    /// The events in the history of the withdrawal, oldest first.
+    #[serde(rename = "history")]
+    pub history: Vec<models::StatusHistoryEntry>,
+    /// Next token for the search.
+    #[serde(
+        rename = "nextToken",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_token: Option<Option<String>>,
+}
+
+impl GetWithdrawalHistoryResponse {
+    /// Response to get withdrawal history request.
+    pub fn new(history: Vec<models::StatusHistoryEntry>) -> GetWithdrawalHistoryResponse {
+        GetWithdrawalHistoryResponse { history, next_token: None }
+    }
+}
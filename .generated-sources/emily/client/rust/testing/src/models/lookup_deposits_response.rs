@@ -0,0 +1,33 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// LookupDepositsResponse : Response to lookup deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LookupDepositsResponse {
+    /// The deposits that were found, in the order they were requested.
+    #[serde(rename = "deposits")]
+    pub deposits: Vec<models::Deposit>,
+    /// The keys of the requested deposits that were not found.
+    #[serde(rename = "notFound")]
+    pub not_found: Vec<models::DepositKey>,
+}
+
+impl LookupDepositsResponse {
+    /// Response to lookup deposits request.
+    pub fn new(
+        deposits: Vec<models::Deposit>,
+        not_found: Vec<models::DepositKey>,
+    ) -> LookupDepositsResponse {
+        LookupDepositsResponse { deposits, not_found }
+    }
+}
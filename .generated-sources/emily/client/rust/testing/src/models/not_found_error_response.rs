@@ -0,0 +1,37 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// NotFoundErrorResponse : Body of the response to a request for a resource that does not exist.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NotFoundErrorResponse {
+    /// Always `not_found`.
+    #[serde(rename = "error")]
+    pub error: String,
+    /// The key the resource was requested by.
+    #[serde(rename = "key")]
+    pub key: String,
+    /// The kind of resource that was requested.
+    #[serde(rename = "resource")]
+    pub resource: String,
+}
+
+impl NotFoundErrorResponse {
+    /// Body of the response to a request for a resource that does not exist.
+    pub fn new(error: String, key: String, resource: String) -> NotFoundErrorResponse {
+        NotFoundErrorResponse {
+            error,
+            key,
+            resource,
+        }
+    }
+}
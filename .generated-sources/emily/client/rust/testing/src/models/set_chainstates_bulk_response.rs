@@ -0,0 +1,32 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// SetChainstatesBulkResponse : Response to the bulk chainstate submission.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SetChainstatesBulkResponse {
+    /// Number of chainstates that were applied.
+    #[serde(rename = "applied")]
+    pub applied: u64,
+    #[serde(rename = "chaintip")]
+    pub chaintip: Box<models::Chainstate>,
+}
+
+impl SetChainstatesBulkResponse {
+    /// Response to the bulk chainstate submission.
+    pub fn new(applied: u64, chaintip: models::Chainstate) -> SetChainstatesBulkResponse {
+        SetChainstatesBulkResponse {
+            applied,
+            chaintip: Box::new(chaintip),
+        }
+    }
+}
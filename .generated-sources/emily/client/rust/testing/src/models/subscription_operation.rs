@@ -0,0 +1,37 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// SubscriptionOperation : The kind of sBTC operation that a subscription is notified about.
+/// The kind of sBTC operation that a subscription is notified about.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum SubscriptionOperation {
+    #[serde(rename = "deposit")]
+    Deposit,
+    #[serde(rename = "withdrawal")]
+    Withdrawal,
+}
+
+impl std::fmt::Display for SubscriptionOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "deposit"),
+            Self::Withdrawal => write!(f, "withdrawal"),
+        }
+    }
+}
+
+impl Default for SubscriptionOperation {
+    fn default() -> SubscriptionOperation {
+        Self::Deposit
+    }
+}
@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// WipeDatabasesResponse : Response to wipe databases request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WipeDatabasesResponse {
+    /// The wiped tables.
+    #[serde(rename = "tables")]
+    pub tables: Vec<models::WipedTable>,
+}
+
+impl WipeDatabasesResponse {
+    /// Response to wipe databases request.
+    pub fn new(tables: Vec<models::WipedTable>) -> WipeDatabasesResponse {
+        WipeDatabasesResponse { tables }
+    }
+}
@@ -0,0 +1,33 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// WipedTable : A table wiped by the wipe databases request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WipedTable {
+    /// Number of items deleted from the table.
+    #[serde(rename = "itemsDeleted")]
+    pub items_deleted: u64,
+    /// Name of the wiped table.
+    #[serde(rename = "tableName")]
+    pub table_name: String,
+}
+
+impl WipedTable {
+    /// A table wiped by the wipe databases request.
+    pub fn new(items_deleted: u64, table_name: String) -> WipedTable {
+        WipedTable {
+            items_deleted,
+            table_name,
+        }
+    }
+}
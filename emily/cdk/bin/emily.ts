@@ -13,5 +13,6 @@ new EmilyStack(app, 'EmilyStack', {
         region: EmilyStackUtils.getAwsRegion()
     },
     trustedReorgApiKey: EmilyStackUtils.getTrustedReorgApiKey(),
+    apiKeys: EmilyStackUtils.getApiKeys(),
     deployerAddress: EmilyStackUtils.getDeployerAddress(),
 });
@@ -17,6 +17,11 @@ export interface EmilyStackProps extends cdk.StackProps {
    */
   readonly trustedReorgApiKey: string;
 
+  /**
+   * The API keys allowed to call the authenticated endpoints, formatted as
+   * a comma separated list of `<key>:<role>` pairs.
+   */
+  readonly apiKeys?: string;
 
   /**
    * The address of the deployer of the sBTC smart contracts.
@@ -56,6 +56,11 @@ export class EmilyStackUtils {
      */
     private static trustedReorgApiKey?: string;
 
+    /*
+     * The API keys and their roles.
+     */
+    private static apiKeys?: string;
+
     /*
      * The address of the deployer of the sBTC smart contracts.
      */
@@ -146,6 +151,14 @@ export class EmilyStackUtils {
         return this.trustedReorgApiKey;
     }
 
+    /*
+     * Returns the API keys and their roles, or undefined if none are set.
+     */
+    public static getApiKeys(): string | undefined {
+        this.apiKeys ??= process.env.API_KEYS;
+        return this.apiKeys;
+    }
+
 
     /*
      * Returns the address of the deployer of the sBTC smart contracts.
@@ -77,12 +77,22 @@ export class EmilyStack extends cdk.Stack {
             pointInTimeRecovery,
         );
 
+        const notificationTableId: string = 'NotificationTable';
+        const notificationTableName: string = EmilyStackUtils.getResourceName(notificationTableId, props);
+        const notificationTable: dynamodb.Table = this.createOrUpdateNotificationTable(
+            notificationTableId,
+            notificationTableName,
+            persistentResourceRemovalPolicy,
+            pointInTimeRecovery,
+        );
+
         if (!EmilyStackUtils.isTablesOnly()) {
             const operationLambda: lambda.Function = this.createOrUpdateOperationLambda(
                 depositTableName,
                 withdrawalTableName,
                 chainstateTableName,
                 limitTableName,
+                notificationTableName,
                 persistentResourceRemovalPolicy,
                 props
             );
@@ -98,6 +108,7 @@ export class EmilyStack extends cdk.Stack {
             withdrawalTable.grantReadWriteData(operationLambda);
             chainstateTable.grantReadWriteData(operationLambda);
             limitTable.grantReadWriteData(operationLambda);
+            notificationTable.grantReadWriteData(operationLambda);
 
             const emilyApis: apig.SpecRestApi[] = this.createOrUpdateApi(
                 alias,
@@ -389,11 +400,43 @@ export class EmilyStack extends cdk.Stack {
         });
     }
 
+    /**
+     * Creates or updates a DynamoDB table for the subscriptions to deposit and
+     * withdrawal status changes.
+     * @param {string} tableId The id of the table AWS resource.
+     * @param {string} tableName The name of the DynamoDB table.
+     * @returns {dynamodb.Table} The created or updated DynamoDB table.
+     * @post A DynamoDB table is returned without additional configuration.
+     */
+    createOrUpdateNotificationTable(
+        tableId: string,
+        tableName: string,
+        removalPolicy: cdk.RemovalPolicy,
+        pointInTimeRecovery: undefined | boolean,
+    ): dynamodb.Table {
+        // Create DynamoDB table to store the subscriptions. Encrypted by default.
+        return new dynamodb.Table(this, tableId, {
+            tableName: tableName,
+            partitionKey: {
+                name: 'Operation',
+                type: dynamodb.AttributeType.STRING,
+            },
+            sortKey: {
+                name: 'Url',
+                type: dynamodb.AttributeType.STRING,
+            },
+            removalPolicy: removalPolicy,
+            billingMode: dynamodb.BillingMode.PAY_PER_REQUEST, // On-demand provisioning
+            pointInTimeRecovery: pointInTimeRecovery,
+        });
+    }
+
     /**
      * Creates or updates the operation Lambda function.
      * @param {string} depositTableName The name of the deposit DynamoDB table.
      * @param {string} withdrawalTableName The name of the withdrawal DynamoDB table.
      * @param {string} chainstateTableName The name of the chainstate DynamoDB table.
+     * @param {string} notificationTableName The name of the notification DynamoDB table.
      * @param {EmilyStackProps} props The stack properties.
      * @returns {lambda.Function} The created or updated Lambda function.
      * @post Lambda function with environment variables set and permissions for DynamoDB access is returned.
@@ -403,6 +446,7 @@ export class EmilyStack extends cdk.Stack {
         withdrawalTableName: string,
         chainstateTableName: string,
         limitTableName: string,
+        notificationTableName: string,
         removalPolicy: cdk.RemovalPolicy,
         props: EmilyStackProps,
     ): lambda.Function {
@@ -426,14 +470,19 @@ export class EmilyStack extends cdk.Stack {
                 WITHDRAWAL_TABLE_NAME: withdrawalTableName,
                 CHAINSTATE_TABLE_NAME: chainstateTableName,
                 LIMIT_TABLE_NAME: limitTableName,
+                NOTIFICATION_TABLE_NAME: notificationTableName,
                 // Declare an environment variable that will be overwritten in local SAM
                 // deployments the AWS stack. SAM can only set environment variables that are
                 // already expected to be present in the lambda.
                 IS_LOCAL: "false",
                 TRUSTED_REORG_API_KEY: props.trustedReorgApiKey,
+                API_KEYS: props.apiKeys ?? "",
                 IS_MAINNET: props.stageName == Constants.PROD_STAGE_NAME || props.stageName == Constants.PRIVATE_MAINNET_STAGE_NAME ? "true" : "false",
                 VERSION: EmilyStackUtils.getLambdaGitIdentifier(),
                 DEPLOYER_ADDRESS: props.deployerAddress,
+                // Only local deployments mount the testing routes and allow them to wipe the tables.
+                DEPLOYMENT_ENVIRONMENT: props.stageName == Constants.LOCAL_STAGE_NAME ? "testing" : props.stageName,
+                ENABLE_TESTING_ROUTES: props.stageName == Constants.LOCAL_STAGE_NAME ? "true" : "false",
             },
             description: `Emily Api Handler. ${EmilyStackUtils.getLambdaGitIdentifier()}`,
             currentVersionOptions: {
@@ -2,18 +2,23 @@
 use crate::{
     api::{
         handlers::internal::{ExecuteReorgRequest, execute_reorg_handler},
-        models::chainstate::Chainstate,
+        models::chainstate::{
+            Chainstate, ChainstateHistoryEntry, GetChainstateHistoryQuery,
+            GetChainstateHistoryResponse, MAX_BULK_CHAINSTATES, SetChainstatesBulkRequestBody,
+            SetChainstatesBulkResponse,
+        },
     },
     common::error::{Error, Inconsistency},
     context::EmilyContext,
-    database::{accessors, entries::chainstate::ChainstateEntry},
+    database::{
+        accessors,
+        entries::chainstate::{ChainstateEntry, ChainstateEntryKey},
+    },
 };
 use tracing::{debug, info, instrument, warn};
 use warp::http::StatusCode;
 use warp::reply::{Reply, json, with_status};
 
-// TODO(TBD): Add conflict handling to the chainstate endpoint.
-
 /// Get chain tip handler.
 #[utoipa::path(
     get,
@@ -55,7 +60,7 @@ pub async fn get_chain_tip(context: EmilyContext) -> impl warp::reply::Reply {
     responses(
         (status = 200, description = "Chainstate retrieved successfully", body = Chainstate),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
-        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 404, description = "Chainstate not found", body = NotFoundErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
@@ -70,7 +75,8 @@ pub async fn get_chainstate_at_height(
     async fn handler(context: EmilyContext, height: u64) -> Result<impl warp::reply::Reply, Error> {
         // Get chainstate at height.
         let chainstate: Chainstate = accessors::get_chainstate_entry_at_height(&context, &height)
-            .await?
+            .await
+            .map_err(|error| error.for_resource("chainstate", height.to_string()))?
             .into();
         // Respond.
         Ok(with_status(json(&chainstate), StatusCode::OK))
@@ -81,6 +87,49 @@ pub async fn get_chainstate_at_height(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Get chainstate history handler.
+#[utoipa::path(
+    get,
+    operation_id = "getChainstateHistory",
+    path = "/chainstate/history",
+    params(
+        ("limit" = Option<u16>, Query, description = "the maximum number of chainstates to return, capped by the server."),
+    ),
+    tag = "chainstate",
+    responses(
+        (status = 200, description = "Chainstate history retrieved successfully", body = GetChainstateHistoryResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_chainstate_history(
+    context: EmilyContext,
+    query: GetChainstateHistoryQuery,
+) -> impl warp::reply::Reply {
+    debug!("Attempting to get chainstate history: {query:?}");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        query: GetChainstateHistoryQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let chainstates: Vec<ChainstateHistoryEntry> =
+            accessors::get_chainstate_history(&context, query.limit())
+                .await?
+                .into_iter()
+                .map(|entry| entry.into())
+                .collect();
+        let response = GetChainstateHistoryResponse { chainstates };
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
 /// Set chainstate handler.
 #[utoipa::path(
     post,
@@ -89,10 +138,12 @@ pub async fn get_chainstate_at_height(
     tag = "chainstate",
     request_body = Chainstate,
     responses(
+        (status = 200, description = "Chainstate already recorded", body = Chainstate),
         (status = 201, description = "Chainstate updated successfully", body = Chainstate),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 409, description = "Chainstate too far above the chain tip", body = ChainGapErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(("ApiGatewayKey" = []))
@@ -113,9 +164,9 @@ pub async fn set_chainstate(
         // Convert body to the correct type.
         let chainstate: Chainstate = body;
         let can_reorg = context.settings.trusted_reorg_api_key == api_key;
-        add_chainstate_entry_or_reorg(&context, can_reorg, &chainstate).await?;
+        let status = add_chainstate_entry_or_reorg(&context, can_reorg, &chainstate).await?;
         // Respond.
-        Ok(with_status(json(&chainstate), StatusCode::CREATED))
+        Ok(with_status(json(&chainstate), status))
     }
     // Handle and respond.
     handler(context, api_key, body)
@@ -135,10 +186,12 @@ pub async fn set_chainstate(
     tag = "chainstate",
     request_body = Chainstate,
     responses(
+        (status = 200, description = "Chainstate already recorded", body = Chainstate),
         (status = 201, description = "Chainstate updated successfully", body = Chainstate),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 409, description = "Chainstate too far above the chain tip", body = ChainGapErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(("ApiGatewayKey" = []))
@@ -159,9 +212,9 @@ pub async fn update_chainstate(
         // Convert body to the correct type.
         let chainstate: Chainstate = body;
         let can_reorg = context.settings.trusted_reorg_api_key == api_key;
-        add_chainstate_entry_or_reorg(&context, can_reorg, &chainstate).await?;
+        let status = add_chainstate_entry_or_reorg(&context, can_reorg, &chainstate).await?;
         // Respond.
-        Ok(with_status(json(&chainstate), StatusCode::CREATED))
+        Ok(with_status(json(&chainstate), status))
     }
     // Handle and respond.
     handler(context, api_key, request)
@@ -169,19 +222,190 @@ pub async fn update_chainstate(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Set chainstates in bulk handler.
+#[utoipa::path(
+    post,
+    operation_id = "setChainstatesBulk",
+    path = "/chainstates/bulk",
+    tag = "chainstate",
+    request_body = SetChainstatesBulkRequestBody,
+    responses(
+        (status = 200, description = "No chainstates to apply", body = SetChainstatesBulkResponse),
+        (status = 201, description = "Chainstates applied successfully", body = SetChainstatesBulkResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 409, description = "Chainstates do not extend the chain tip", body = BulkChainstateConflictErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, body))]
+pub async fn set_chainstates_bulk(
+    context: EmilyContext,
+    body: SetChainstatesBulkRequestBody,
+) -> impl warp::reply::Reply {
+    debug!(
+        "Attempting to set {} chainstates in bulk",
+        body.chainstates.len()
+    );
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        body: SetChainstatesBulkRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let chainstates = body.chainstates;
+        if chainstates.len() > MAX_BULK_CHAINSTATES {
+            return Err(Error::BadRequest(format!(
+                "at most {MAX_BULK_CHAINSTATES} chainstates can be set at once, got {}",
+                chainstates.len()
+            )));
+        }
+        // Validate the whole submission before writing any of it.
+        let api_state = accessors::get_api_state(&context).await?;
+        validate_bulk_chainstates(&api_state.chaintip().key, &chainstates)?;
+        let entries: Vec<ChainstateEntry> = chainstates.into_iter().map(Into::into).collect();
+        let chaintip = accessors::extend_chainstate_entries(&context, api_state, &entries).await?;
+        // Respond.
+        let status = if entries.is_empty() {
+            StatusCode::OK
+        } else {
+            StatusCode::CREATED
+        };
+        let response = SetChainstatesBulkResponse {
+            applied: entries.len() as u64,
+            chaintip: chaintip.into(),
+        };
+        Ok(with_status(json(&response), status))
+    }
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_err(|error| {
+            warn!("Failed to set chainstates in bulk with error: {}", error);
+            error
+        })
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Checks that the chainstates of a bulk submission extend the chain tip
+/// one height at a time, failing with the index of the first chainstate
+/// that doesn't. Without a chain tip the first chainstate may be at any
+/// height, since it starts the chain.
+fn validate_bulk_chainstates(
+    chaintip: &ChainstateEntryKey,
+    chainstates: &[Chainstate],
+) -> Result<(), Error> {
+    let mut previous_height = (chaintip.height != 0).then_some(chaintip.height);
+    for (index, chainstate) in chainstates.iter().enumerate() {
+        let height = chainstate.stacks_block_height;
+        if let Some(previous_height) = previous_height {
+            if previous_height.checked_add(1) != Some(height) {
+                let message = if index == 0 {
+                    format!(
+                        "height {height} does not extend the chain tip at height {previous_height}"
+                    )
+                } else {
+                    format!(
+                        "height {height} does not follow the previous chainstate at height {previous_height}"
+                    )
+                };
+                return Err(Error::BulkChainstateConflict(index, message));
+            }
+        }
+        previous_height = Some(height);
+    }
+    Ok(())
+}
+
+/// How a submitted chainstate relates to the chain that the API knows about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChainstateSubmission {
+    /// The chainstate is already recorded at or below the chain tip.
+    AlreadyRecorded,
+    /// The chainstate is too far above the chain tip. Holds the first and
+    /// the last of the missing heights.
+    ChainGap(u64, u64),
+    /// The chainstate either extends the chain or conflicts with it, in
+    /// which case adding it may reorganize the API.
+    Add,
+}
+
+/// Classifies the submitted chainstate given the current chain tip and, for
+/// a submission at or below the chain tip, the chainstate recorded at its
+/// height.
+fn classify_chainstate_submission(
+    chaintip: &ChainstateEntryKey,
+    recorded: Option<&ChainstateEntryKey>,
+    submitted: &ChainstateEntryKey,
+    max_chainstate_gap: u64,
+) -> ChainstateSubmission {
+    // Without a chain tip the first chainstate may be at any height.
+    if chaintip.height == 0 {
+        return ChainstateSubmission::Add;
+    }
+    if submitted.height <= chaintip.height {
+        return match recorded {
+            Some(recorded) if recorded == submitted => ChainstateSubmission::AlreadyRecorded,
+            _ => ChainstateSubmission::Add,
+        };
+    }
+    let missing_heights = submitted.height - chaintip.height - 1;
+    if missing_heights > max_chainstate_gap {
+        ChainstateSubmission::ChainGap(chaintip.height + 1, submitted.height - 1)
+    } else {
+        ChainstateSubmission::Add
+    }
+}
+
 /// Adds the chainstate to the table, and reorganizes the API if there's a
 /// conflict that suggests it needs a reorg in order for this entry to be
-/// consistent.
+/// consistent. Returns the status code to respond with, which is
+/// [`StatusCode::OK`] if the chainstate was already recorded.
 ///
 /// TODO(TBD): Consider moving this logic into database accessor structures.
 pub async fn add_chainstate_entry_or_reorg(
     context: &EmilyContext,
     can_reorg: bool,
     chainstate: &Chainstate,
-) -> Result<(), Error> {
+) -> Result<StatusCode, Error> {
     // Get chainstate as entry.
     let entry: ChainstateEntry = chainstate.clone().into();
     debug!("Attempting to add chainstate: {entry:?}");
+
+    // Chainstates submitted out of order by different signers should not
+    // reorganize the API, so check the submission against the chain tip
+    // first.
+    let chaintip = accessors::get_api_state(context).await?.chaintip();
+    let recorded = if entry.key.height <= chaintip.key.height {
+        // Conflicting entries at the height are handled when adding the
+        // entry below, so an error here only means that the entry is new.
+        accessors::get_chainstate_at_height(context, entry.key.height)
+            .await
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+    match classify_chainstate_submission(
+        &chaintip.key,
+        recorded.as_ref().map(|recorded| &recorded.key),
+        &entry.key,
+        context.settings.max_chainstate_gap,
+    ) {
+        ChainstateSubmission::AlreadyRecorded => {
+            debug!("Chainstate is already recorded: {entry:?}");
+            return Ok(StatusCode::OK);
+        }
+        ChainstateSubmission::ChainGap(first_missing_height, last_missing_height) => {
+            warn!(
+                "Chainstate is too far above the chain tip {chaintip:?}, missing heights {first_missing_height} to {last_missing_height}: {entry:?}"
+            );
+            return Err(Error::ChainGap(first_missing_height, last_missing_height));
+        }
+        ChainstateSubmission::Add => {}
+    }
+
     match accessors::add_chainstate_entry_with_retry(context, &entry, 15).await {
         Err(Error::InconsistentState(Inconsistency::Chainstates(conflicting_chainstates))) => {
             if can_reorg {
@@ -199,11 +423,82 @@ pub async fn add_chainstate_entry_or_reorg(
                 debug!("Inconsistent chainstate found for {entry:?} but we pretend it's okay.");
             }
         }
-        e @ Err(_) => return e,
+        Err(error) => return Err(error),
         _ => {}
     };
     // Return.
-    Ok(())
+    Ok(StatusCode::CREATED)
 }
 
-// TODO(393): Add handler unit tests.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn key(height: u64, hash: &str) -> ChainstateEntryKey {
+        ChainstateEntryKey { hash: hash.to_string(), height }
+    }
+
+    #[test_case(key(10, "a"), Some(key(10, "a")), ChainstateSubmission::AlreadyRecorded; "replay_of_tip")]
+    #[test_case(key(7, "a"), Some(key(7, "a")), ChainstateSubmission::AlreadyRecorded; "replay_below_tip")]
+    #[test_case(key(10, "b"), Some(key(10, "a")), ChainstateSubmission::Add; "conflict_at_tip")]
+    #[test_case(key(7, "b"), Some(key(7, "a")), ChainstateSubmission::Add; "conflict_below_tip")]
+    #[test_case(key(7, "a"), None, ChainstateSubmission::Add; "unrecorded_below_tip")]
+    #[test_case(key(11, "a"), None, ChainstateSubmission::Add; "next_height")]
+    #[test_case(key(15, "a"), None, ChainstateSubmission::Add; "largest_allowed_gap")]
+    #[test_case(key(16, "a"), None, ChainstateSubmission::ChainGap(11, 15); "gap_too_large")]
+    fn chainstate_submissions_are_classified(
+        submitted: ChainstateEntryKey,
+        recorded: Option<ChainstateEntryKey>,
+        expected: ChainstateSubmission,
+    ) {
+        let chaintip = key(10, "a");
+        let submission =
+            classify_chainstate_submission(&chaintip, recorded.as_ref(), &submitted, 4);
+        assert_eq!(submission, expected);
+    }
+
+    #[test]
+    fn first_chainstate_may_be_at_any_height() {
+        let submission = classify_chainstate_submission(&key(0, ""), None, &key(1123, "a"), 4);
+        assert_eq!(submission, ChainstateSubmission::Add);
+    }
+
+    fn chainstates(heights: &[u64]) -> Vec<Chainstate> {
+        heights
+            .iter()
+            .map(|height| Chainstate {
+                stacks_block_height: *height,
+                stacks_block_hash: format!("hash-{height}"),
+                bitcoin_block_height: Some(*height),
+            })
+            .collect()
+    }
+
+    #[test_case(0, &[]; "nothing_to_apply")]
+    #[test_case(0, &[1123, 1124, 1125]; "empty_table_at_any_height")]
+    #[test_case(10, &[11]; "single_chainstate")]
+    #[test_case(10, &[11, 12, 13]; "extends_the_chain_tip")]
+    fn bulk_chainstates_are_accepted(chaintip_height: u64, heights: &[u64]) {
+        let chaintip = key(chaintip_height, "tip");
+        assert!(validate_bulk_chainstates(&chaintip, &chainstates(heights)).is_ok());
+    }
+
+    #[test_case(10, &[12, 13], 0; "gap_above_the_chain_tip")]
+    #[test_case(10, &[10, 11], 0; "replay_of_the_chain_tip")]
+    #[test_case(10, &[11, 13], 1; "gap_in_the_submission")]
+    #[test_case(10, &[11, 12, 12], 2; "repeated_height")]
+    #[test_case(10, &[11, 12, 11], 2; "decreasing_height")]
+    #[test_case(0, &[1123, 1125], 1; "gap_in_the_submission_on_an_empty_table")]
+    fn bulk_chainstates_are_rejected_at_the_first_inconsistency(
+        chaintip_height: u64,
+        heights: &[u64],
+        expected_index: usize,
+    ) {
+        let chaintip = key(chaintip_height, "tip");
+        match validate_bulk_chainstates(&chaintip, &chainstates(heights)) {
+            Err(Error::BulkChainstateConflict(index, _)) => assert_eq!(index, expected_index),
+            result => panic!("expected a bulk chainstate conflict, got {result:?}"),
+        }
+    }
+}
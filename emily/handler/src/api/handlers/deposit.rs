@@ -1,6 +1,9 @@
 //! Handlers for Deposit endpoints.
+use std::collections::HashMap;
+
 use bitcoin::ScriptBuf;
 use bitcoin::opcodes::all::{self as opcodes};
+use clarity::vm::types::PrincipalData;
 use sbtc::deposits::ReclaimScriptInputs;
 use sha2::{Digest, Sha256};
 use stacks_common::codec::StacksMessageCodec as _;
@@ -8,16 +11,19 @@ use tracing::instrument;
 use warp::http::StatusCode;
 use warp::reply::{Reply, json, with_status};
 
+use crate::api::handlers::subscription::notify_subscribers;
 use crate::api::models::common::Status;
 use crate::api::models::common::requests::BasicPaginationQuery;
 use crate::api::models::deposit::responses::{
-    GetDepositsForTransactionResponse, UpdateDepositsResponse,
+    GetDepositHistoryResponse, GetDepositsForTransactionResponse, LookupDepositsResponse,
+    UpdateDepositsResponse,
 };
 use crate::api::models::deposit::{Deposit, DepositInfo};
+use crate::api::models::subscription::Notification;
 use crate::api::models::{
     deposit::requests::{
-        CreateDepositRequestBody, GetDepositsForTransactionQuery, GetDepositsQuery,
-        UpdateDepositsRequestBody,
+        CreateDepositRequestBody, GetDepositsForRecipientQuery, GetDepositsForTransactionQuery,
+        GetDepositsQuery, LookupDepositsRequestBody, UpdateDepositsRequestBody,
     },
     deposit::responses::GetDepositsResponse,
 };
@@ -43,7 +49,7 @@ use crate::database::entries::deposit::{
     responses(
         (status = 200, description = "Deposit retrieved successfully", body = Deposit),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
-        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = NotFoundErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
@@ -68,7 +74,13 @@ pub async fn get_deposit(
         };
         // Get deposit.
         let deposit: Deposit = accessors::get_deposit_entry(&context, &key)
-            .await?
+            .await
+            .map_err(|error| {
+                error.for_resource(
+                    "deposit",
+                    format!("{}:{}", key.bitcoin_txid, key.bitcoin_tx_output_index),
+                )
+            })?
             .try_into()?;
 
         // Respond.
@@ -81,6 +93,72 @@ pub async fn get_deposit(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Get deposit history handler.
+#[utoipa::path(
+    get,
+    operation_id = "getDepositHistory",
+    path = "/deposit/{txid}/{index}/history",
+    params(
+        ("txid" = String, Path, description = "txid associated with the Deposit."),
+        ("index" = String, Path, description = "output index associated with the Deposit."),
+        ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
+        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
+    ),
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposit history retrieved successfully", body = GetDepositHistoryResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = NotFoundErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposit_history(
+    context: EmilyContext,
+    bitcoin_txid: String,
+    bitcoin_tx_output_index: u32,
+    query: BasicPaginationQuery,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in get deposit history");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        bitcoin_txid: String,
+        bitcoin_tx_output_index: u32,
+        query: BasicPaginationQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        // Make key.
+        let key = DepositEntryKey {
+            bitcoin_txid,
+            bitcoin_tx_output_index,
+        };
+        // Get deposit.
+        let entry = accessors::get_deposit_entry(&context, &key)
+            .await
+            .map_err(|error| {
+                error.for_resource(
+                    "deposit",
+                    format!("{}:{}", key.bitcoin_txid, key.bitcoin_tx_output_index),
+                )
+            })?;
+        // The history is stored oldest first, so it can be paged through as is.
+        let (events, next_token) = query.page_of(entry.history)?;
+        let response = GetDepositHistoryResponse {
+            next_token,
+            history: events.into_iter().map(Into::into).collect(),
+        };
+
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+
+    // Handle and respond.
+    handler(context, bitcoin_txid, bitcoin_tx_output_index, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
 /// Get deposits for transaction handler.
 #[utoipa::path(
     get,
@@ -146,7 +224,9 @@ pub async fn get_deposits_for_transaction(
     params(
         ("status" = Status, Query, description = "the status to search by when getting all deposits."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
-        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
+        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list."),
+        ("fromHeight" = Option<u64>, Query, description = "only return deposits last updated at or above this stacks block height."),
+        ("toHeight" = Option<u64>, Query, description = "only return deposits last updated at or below this stacks block height.")
     ),
     tag = "deposit",
     responses(
@@ -169,13 +249,28 @@ pub async fn get_deposits(
         query: GetDepositsQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
         // Deserialize next token into the exclusive start key if present/
-        let (entries, next_token) = accessors::get_deposit_entries(
-            &context,
-            &query.status,
-            query.next_token,
-            query.page_size,
-        )
-        .await?;
+        let (entries, next_token) = match query.height_range()? {
+            Some((from_height, to_height)) => {
+                accessors::get_deposit_entries_in_height_range(
+                    &context,
+                    &query.status,
+                    from_height,
+                    to_height,
+                    query.next_token,
+                    query.page_size,
+                )
+                .await?
+            }
+            None => {
+                accessors::get_deposit_entries(
+                    &context,
+                    &query.status,
+                    query.next_token,
+                    query.page_size,
+                )
+                .await?
+            }
+        };
         // Convert data into resource types.
         let deposits: Vec<DepositInfo> = entries.into_iter().map(|entry| entry.into()).collect();
         // Create response.
@@ -195,7 +290,8 @@ pub async fn get_deposits(
     operation_id = "getDepositsForRecipient",
     path = "/deposit/recipient/{recipient}",
     params(
-        ("recipient" = String, Path, description = "the recipient to search by when getting all deposits."),
+        ("recipient" = String, Path, description = "the hex-encoded, consensus-serialized recipient principal to search by when getting all deposits."),
+        ("status" = Option<Status>, Query, description = "only return deposits with this status. Pages may hold fewer than pageSize items when set."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
         ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
     ),
@@ -212,15 +308,16 @@ pub async fn get_deposits(
 pub async fn get_deposits_for_recipient(
     context: EmilyContext,
     recipient: String,
-    query: BasicPaginationQuery,
+    query: GetDepositsForRecipientQuery,
 ) -> impl warp::reply::Reply {
     tracing::debug!("in get deposits for recipient: {recipient}");
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
         recipient: String,
-        query: BasicPaginationQuery,
+        query: GetDepositsForRecipientQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
+        validate_recipient(&recipient)?;
         let (entries, next_token) = accessors::get_deposit_entries_by_recipient(
             &context,
             &recipient,
@@ -228,8 +325,19 @@ pub async fn get_deposits_for_recipient(
             query.page_size,
         )
         .await?;
-        // Convert data into resource types.
-        let deposits: Vec<DepositInfo> = entries.into_iter().map(|entry| entry.into()).collect();
+        // Convert data into resource types. The status filter is applied
+        // after the page is read, so the next token still points past
+        // every entry that was examined.
+        let deposits: Vec<DepositInfo> = entries
+            .into_iter()
+            .filter(|entry| {
+                query
+                    .status
+                    .as_ref()
+                    .is_none_or(|status| &entry.status == status)
+            })
+            .map(|entry| entry.into())
+            .collect();
         // Create response.
         let response = GetDepositsResponse { deposits, next_token };
         // Respond.
@@ -295,6 +403,58 @@ pub async fn get_deposits_for_reclaim_pubkeys(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Lookup deposits handler.
+#[utoipa::path(
+    post,
+    operation_id = "lookupDeposits",
+    path = "/deposit/lookup",
+    tag = "deposit",
+    request_body = LookupDepositsRequestBody,
+    responses(
+        (status = 200, description = "Deposits looked up successfully", body = LookupDepositsResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context, body))]
+pub async fn lookup_deposits(
+    context: EmilyContext,
+    body: LookupDepositsRequestBody,
+) -> impl warp::reply::Reply {
+    tracing::debug!("in lookup deposits");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        body: LookupDepositsRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let keys = body.try_into_entry_keys()?;
+        // Get the deposits that exist, keyed so that the response can
+        // follow the order of the request.
+        let mut entries: HashMap<DepositEntryKey, DepositEntry> =
+            accessors::get_deposit_entries_by_keys(&context, &keys)
+                .await?
+                .into_iter()
+                .map(|entry| (entry.key.clone(), entry))
+                .collect();
+        // Split the keys into found deposits and missing keys.
+        let mut response = LookupDepositsResponse::default();
+        for key in keys {
+            match entries.remove(&key) {
+                Some(entry) => response.deposits.push(entry.try_into()?),
+                None => response.not_found.push(key.into()),
+            }
+        }
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
 /// Create deposit handler.
 #[utoipa::path(
     post,
@@ -305,9 +465,10 @@ pub async fn get_deposits_for_reclaim_pubkeys(
     responses(
         (status = 200, description = "Deposit already exists", body = Deposit),
         (status = 201, description = "Deposit created successfully", body = Deposit),
-        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 400, description = "Invalid request body or deposit limit exceeded", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 409, description = "Deposit conflicts with an existing deposit", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
@@ -336,25 +497,6 @@ pub async fn create_deposit(
 
         let deposit_info = body.validate(context.settings.is_mainnet)?;
 
-        // Check if deposit with such txid and outindex already exists.
-        let entry = accessors::get_deposit_entry(
-            &context,
-            &DepositEntryKey {
-                bitcoin_txid: body.bitcoin_txid.clone(),
-                bitcoin_tx_output_index: body.bitcoin_tx_output_index,
-            },
-        )
-        .await;
-
-        match entry {
-            Ok(deposit_entry) => {
-                // The deposit already exists, return it.
-                let response: Deposit = deposit_entry.try_into()?;
-                return Ok(with_status(json(&response), StatusCode::OK));
-            }
-            Err(Error::NotFound) => {}
-            Err(e) => return Err(e),
-        }
         let reclaim_pubkeys_hash = extract_reclaim_pubkeys_hash(&deposit_info.reclaim_script);
         if reclaim_pubkeys_hash.is_none() {
             tracing::warn!(
@@ -378,6 +520,7 @@ pub async fn create_deposit(
                 status: StatusEntry::Pending,
                 message: "Just received deposit".to_string(),
                 stacks_block_hash: stacks_block_hash.clone(),
+                updated_by: None,
                 stacks_block_height,
             }],
             status: Status::Pending,
@@ -391,8 +534,31 @@ pub async fn create_deposit(
         };
         // Validate deposit entry.
         deposit_entry.validate()?;
-        // Add entry to the table.
-        accessors::add_deposit_entry(&context, &deposit_entry).await?;
+
+        // Check if deposit with such txid and outindex already exists.
+        match accessors::get_deposit_entry(&context, &deposit_entry.key).await {
+            Ok(existing_entry) => {
+                let response = existing_deposit(existing_entry, &deposit_entry)?;
+                return Ok(with_status(json(&response), StatusCode::OK));
+            }
+            Err(Error::ItemNotFound) => {}
+            Err(e) => return Err(e),
+        }
+        // Only new deposits are checked against the deposit limits, so that
+        // resubmitting an existing deposit keeps returning it.
+        check_deposit_limits(&context, deposit_entry.amount).await?;
+        // Add entry to the table. The write only succeeds if no other
+        // request created the deposit since the check above.
+        match accessors::add_deposit_entry(&context, &deposit_entry).await {
+            Ok(()) => {}
+            Err(Error::VersionConflict) => {
+                let existing_entry =
+                    accessors::get_deposit_entry(&context, &deposit_entry.key).await?;
+                let response = existing_deposit(existing_entry, &deposit_entry)?;
+                return Ok(with_status(json(&response), StatusCode::OK));
+            }
+            Err(e) => return Err(e),
+        }
         // Respond.
         let response: Deposit = deposit_entry.try_into()?;
         Ok(with_status(json(&response), StatusCode::CREATED))
@@ -403,6 +569,51 @@ pub async fn create_deposit(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Checks a new deposit amount against the global deposit limits. Absent
+/// limits are not enforced.
+async fn check_deposit_limits(context: &EmilyContext, amount: u64) -> Result<(), Error> {
+    let limits = accessors::get_global_limits(context).await?;
+    if let Some(minimum) = limits.per_deposit_minimum {
+        if amount < minimum {
+            return Err(Error::LimitExceeded("perDepositMinimum", minimum));
+        }
+    }
+    if let Some(cap) = limits.per_deposit_cap {
+        if amount > cap {
+            return Err(Error::LimitExceeded("perDepositCap", cap));
+        }
+    }
+    let available = accessors::calculate_sbtc_left_for_deposits(
+        context,
+        limits.rolling_deposit_blocks,
+        limits.rolling_deposit_cap,
+    )
+    .await?;
+    if let Some(available) = available {
+        if amount > available {
+            return Err(Error::LimitExceeded("rollingDepositCap", available));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the existing deposit for a create request that was already
+/// made, or an error if the submitted deposit differs from it in any field
+/// that cannot change after creation.
+fn existing_deposit(existing: DepositEntry, submitted: &DepositEntry) -> Result<Deposit, Error> {
+    let conflicts = existing.conflicting_fields(submitted);
+    if !conflicts.is_empty() {
+        tracing::warn!(
+            bitcoin_txid = %existing.key.bitcoin_txid,
+            bitcoin_tx_output_index = existing.key.bitcoin_tx_output_index,
+            ?conflicts,
+            "create deposit request conflicts with the existing deposit"
+        );
+        return Err(Error::DepositConflict(conflicts));
+    }
+    existing.try_into()
+}
+
 /// Update deposits handler.
 #[utoipa::path(
     put,
@@ -461,6 +672,7 @@ pub async fn update_deposits(
         // Create aggregator.
         let mut updated_deposits: Vec<(usize, Deposit)> =
             Vec::with_capacity(validated_request.deposits.len());
+        let mut notifications: Vec<Notification> = Vec::new();
 
         // Loop through all updates and execute.
         for (index, update) in validated_request.deposits {
@@ -473,7 +685,7 @@ pub async fn update_deposits(
                 "updating deposit"
             );
 
-            let updated_deposit =
+            let (updated_deposit, status_changed) =
                 accessors::pull_and_update_deposit_with_retry(&context, update, 15, is_trusted_key)
                     .await
                     .inspect_err(|error| {
@@ -494,9 +706,15 @@ pub async fn update_deposits(
                     "failed to convert deposit"
                 );
             })?;
+            if status_changed {
+                notifications.push(Notification::Deposit(deposit.clone()));
+            }
             updated_deposits.push((index, deposit));
         }
 
+        // Let the subscribers know about the deposits that changed status.
+        notify_subscribers(&context, notifications).await;
+
         updated_deposits.sort_by_key(|(index, _)| *index);
         let deposits = updated_deposits
             .into_iter()
@@ -574,6 +792,22 @@ fn extract_reclaim_pubkeys_hash(reclaim_script: &ScriptBuf) -> Option<String> {
     .map(sorted_sha256)
 }
 
+/// Check that the recipient is a hex-encoded, consensus-serialized Stacks
+/// principal in the same form that is stored for deposits.
+fn validate_recipient(recipient: &str) -> Result<(), Error> {
+    let invalid_recipient =
+        || Error::HttpRequest(StatusCode::BAD_REQUEST, "invalid recipient".to_string());
+    let bytes = hex::decode(recipient).map_err(|_| invalid_recipient())?;
+    let principal = PrincipalData::consensus_deserialize(&mut bytes.as_slice())
+        .map_err(|_| invalid_recipient())?;
+    // Reject trailing bytes and uppercase hex, which would never match a
+    // stored recipient.
+    if hex::encode(principal.serialize_to_vec()) != recipient {
+        return Err(invalid_recipient());
+    }
+    Ok(())
+}
+
 /// Parse a dash-separated list of hex-encoded pubkeys into a Vec<[u8; 32]>.
 fn validate_reclaim_pubkeys(reclaim_pubkeys: &str) -> Result<Vec<[u8; 32]>, Error> {
     reclaim_pubkeys
@@ -599,6 +833,7 @@ mod tests {
         key::rand::rngs::OsRng,
         secp256k1::{SECP256K1, SecretKey},
     };
+    use stacks_common::types::chainstate::StacksAddress;
     use test_case::test_case;
 
     fn make_reclaim_script(pubkey: &[u8; 32]) -> ScriptBuf {
@@ -734,6 +969,46 @@ mod tests {
         let reclaim_pubkeys_hash = extract_reclaim_pubkeys_hash(&reclaim_script).unwrap();
         assert_eq!(query_pubkeys_hash, reclaim_pubkeys_hash);
     }
+
+    #[test_case(PrincipalData::from(StacksAddress::burn_address(false)); "standard principal")]
+    #[test_case(PrincipalData::parse("ST1RQHF4VE5CZ6EK3MZPZVQBA0JVSMM9H5PMHMS1Y.contract-name").unwrap(); "contract principal")]
+    fn validate_recipient_accepts_serialized_principals(principal: PrincipalData) {
+        let recipient = hex::encode(principal.serialize_to_vec());
+        assert!(validate_recipient(&recipient).is_ok());
+    }
+
+    #[test_case(""; "empty")]
+    #[test_case("invalid"; "not hex")]
+    #[test_case("0000"; "not a principal")]
+    #[test_case("051a000000000000000000000000000000000000000000"; "trailing bytes")]
+    #[test_case("051A0000000000000000000000000000000000000000"; "uppercase hex")]
+    fn validate_recipient_rejects_invalid_input(input: &str) {
+        let result = validate_recipient(input);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "HTTP request failed with status code 400 Bad Request: invalid recipient",
+        );
+    }
+
+    #[test]
+    fn existing_deposit_conflicts_on_different_amount() {
+        let existing = DepositEntry {
+            amount: 1000,
+            history: vec![DepositEvent::default()],
+            ..Default::default()
+        };
+        let submitted = DepositEntry {
+            amount: 2000,
+            ..existing.clone()
+        };
+
+        let error = existing_deposit(existing.clone(), &submitted).unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::CONFLICT);
+        assert!(matches!(error, Error::DepositConflict(fields) if fields == ["amount"]));
+
+        let deposit = existing_deposit(existing.clone(), &existing.clone()).unwrap();
+        assert_eq!(deposit.amount, 1000);
+    }
 }
 
 // TODO(393): Add handler unit tests.
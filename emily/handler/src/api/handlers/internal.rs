@@ -8,12 +8,16 @@ use crate::api::models::chainstate::Chainstate;
 use crate::common::error::{Error, Inconsistency};
 use crate::context::EmilyContext;
 use crate::database::accessors;
-use crate::database::entries::chainstate::{ApiStateEntry, ApiStatus};
-use crate::database::entries::deposit::DepositEntry;
-use crate::database::entries::withdrawal::WithdrawalEntry;
+use crate::database::entries::chainstate::{ApiStateEntry, ApiStatus, SpecialApiStateIndex};
+use crate::database::entries::deposit::{
+    DepositEntryKey, DepositInfoEntry, DepositTablePrimaryIndex,
+};
+use crate::database::entries::withdrawal::{
+    WithdrawalEntryKey, WithdrawalInfoEntry, WithdrawalTablePrimaryIndex,
+};
 
-const MAX_SET_API_STATE_ATTEMPTS_DURING_REORG: u32 = 20;
-const ENTRY_UPDATE_RETRIES: u32 = 4;
+const MAX_SET_API_STATE_ATTEMPTS_DURING_REORG: u16 = 20;
+const ENTRY_UPDATE_RETRIES: u16 = 4;
 
 /// Request for executing a reorg.
 #[derive(Debug, Deserialize, Serialize)]
@@ -38,61 +42,92 @@ async fn set_api_state_status(
     context: &EmilyContext,
     new_status: &ApiStatus,
 ) -> Result<Option<ApiStateEntry>, Error> {
-    let mut api_state: ApiStateEntry;
-    for attempt_number in 0..MAX_SET_API_STATE_ATTEMPTS_DURING_REORG {
-        let original_api_state = accessors::get_api_state(context).await?;
-        api_state = original_api_state.clone();
-
-        // Update the api status.
-        api_state.api_status = match (new_status, &original_api_state.api_status) {
-            (ApiStatus::Reorg(_), ApiStatus::Stable(_))
-            | (ApiStatus::Stable(_), ApiStatus::Reorg(_)) => new_status.clone(),
-            (ApiStatus::Stable(new_tip), ApiStatus::Stable(old_tip)) => {
-                if new_tip == old_tip {
-                    return Ok(None);
-                } else {
-                    new_status.clone()
+    // Make sure the API state exists so that it can be updated.
+    accessors::get_api_state(context).await?;
+    let api_state = accessors::with_version_retry::<SpecialApiStateIndex, _>(
+        context,
+        &ApiStateEntry::key(),
+        MAX_SET_API_STATE_ATTEMPTS_DURING_REORG,
+        |api_state| {
+            // Update the api status.
+            match (new_status, &api_state.api_status) {
+                (ApiStatus::Reorg(_), ApiStatus::Stable(_))
+                | (ApiStatus::Stable(_), ApiStatus::Reorg(_)) => {}
+                (ApiStatus::Stable(new_tip), ApiStatus::Stable(old_tip)) => {
+                    if new_tip == old_tip {
+                        return Ok(false);
+                    }
                 }
-            }
-            // Handle trying to set the api status to reorganizing.
-            (ApiStatus::Reorg(new_reorg_tip), ApiStatus::Reorg(current_reorg_tip)) => {
-                // Compare only `.key` (stacks height and hash) since old chainstate entries lack
-                // `bitcoin_block_height`. A Stacks block always has the same Bitcoin height, so
-                // ignoring it prevents false mismatches for old data.
-                if new_reorg_tip.key == current_reorg_tip.key {
-                    return Ok(None);
-                } else {
-                    let err_msg: String = format!(
-                        "Trying to reorg with new chaintip {new_reorg_tip:?} while the api is reorganizing around the chaintip {current_reorg_tip:?}"
-                    );
-                    warn!(err_msg);
-                    return Err(Error::InconsistentState(Inconsistency::ItemUpdate(err_msg)));
+                // Handle trying to set the api status to reorganizing.
+                (ApiStatus::Reorg(new_reorg_tip), ApiStatus::Reorg(current_reorg_tip)) => {
+                    // Compare only `.key` (stacks height and hash) since old chainstate entries lack
+                    // `bitcoin_block_height`. A Stacks block always has the same Bitcoin height, so
+                    // ignoring it prevents false mismatches for old data.
+                    if new_reorg_tip.key == current_reorg_tip.key {
+                        return Ok(false);
+                    } else {
+                        let err_msg: String = format!(
+                            "Trying to reorg with new chaintip {new_reorg_tip:?} while the api is reorganizing around the chaintip {current_reorg_tip:?}"
+                        );
+                        warn!(err_msg);
+                        return Err(Error::InconsistentState(Inconsistency::ItemUpdate(err_msg)));
+                    }
                 }
-            }
-        };
+            };
+            debug!("Changing Api state from [{api_state:?}] to status [{new_status:?}].");
+            api_state.api_status = new_status.clone();
+            Ok(true)
+        },
+    )
+    .await?;
 
-        debug!(
-            "Changing Api state from [{original_api_state:?}] to [{api_state:?}]. Attempt {attempt_number} of maximum {MAX_SET_API_STATE_ATTEMPTS_DURING_REORG}."
-        );
+    if let Some(api_state) = &api_state {
+        info!("Successfully set api state: {:?}.", api_state);
+    }
+    Ok(api_state)
+}
 
-        // Attempt to set the API state.
-        match accessors::set_api_state(context, &api_state).await {
-            // We successfully set the API state.
-            Ok(()) => {
-                info!("Successfully set api state: {:?}.", api_state);
-                return Ok(Some(api_state));
-            }
-            // Retry if there was a version conflict.
-            Err(Error::VersionConflict) => {
-                debug!("Failed to update API state - retrying: {api_state:?}")
-            }
-            // If some other error occurred then return from here; this shouldn't
-            // happen and something has actually gone wrong.
-            e @ Err(_) => e?,
-        }
+/// The number of entries in a table that a reorg examined and modified.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorgCounts {
+    /// Entries last modified at or above the height of the new canonical tip.
+    pub entries_examined: u64,
+    /// Entries whose history was changed by the reorg.
+    pub entries_modified: u64,
+    /// Entries that could not be updated within `ENTRY_UPDATE_RETRIES`
+    /// attempts because of concurrent modifications.
+    pub retries_exhausted: u64,
+}
+
+impl ReorgCounts {
+    /// Adds the counts from another set of counts to this one.
+    fn add(&mut self, other: ReorgCounts) {
+        self.entries_examined += other.entries_examined;
+        self.entries_modified += other.entries_modified;
+        self.retries_exhausted += other.retries_exhausted;
     }
-    // Return.
-    Err(Error::InternalServer)
+}
+
+/// Summary of a reorg, returned by the reorg handler.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorgSummary {
+    /// Counts for the deposit table.
+    pub deposits: ReorgCounts,
+    /// Counts for the withdrawal table.
+    pub withdrawals: ReorgCounts,
+}
+
+/// The outcome of reorganizing a single entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EntryReorgOutcome {
+    /// The entry did not need to change.
+    Unchanged,
+    /// The entry was updated.
+    Modified,
+    /// The entry could not be updated within the retry limit.
+    RetriesExhausted,
 }
 
 /// Handler that executes a reorg.
@@ -100,108 +135,281 @@ async fn set_api_state_status(
 /// This function isn't intended to be exposed into any specific endpoint
 /// outside of what could maybe be a testing endpoint one day. It handles
 /// the internal requests to execute a reorg.
+///
+/// Impacted entries are read and updated one page at a time so that the
+/// memory used does not grow with the size of the tables. The reorg fails
+/// if any entry could not be updated within `ENTRY_UPDATE_RETRIES` attempts.
 pub async fn execute_reorg_handler(
     context: &EmilyContext,
     request: ExecuteReorgRequest,
 ) -> Result<impl warp::reply::Reply, Error> {
     info!("Executing a reorg with request {request:?}.");
-    let empty_reply = warp::reply::with_status(warp::reply(), StatusCode::NO_CONTENT);
+    let mut summary = ReorgSummary::default();
 
     let new_status = ApiStatus::Reorg(request.canonical_tip.clone().into());
     match set_api_state_status(context, &new_status).await? {
         // Do nothing if we claimed the api correctly.
         Some(_) => {}
         None => {
-            return Ok(empty_reply);
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&summary),
+                StatusCode::OK,
+            ));
         }
     };
 
     // We have control of the API at this point. For each entry of the deposit
     // and withdrawal table we'll wipe out all the history that's no longer relevant.
+    let canonical_tip = &request.canonical_tip;
+    let minimum_height = canonical_tip.stacks_block_height;
 
-    // Get all deposits that would be impacted by this reorg.
-    let all_deposits = accessors::get_all_deposit_entries_modified_from_height(
-        context,
-        request.canonical_tip.stacks_block_height,
-        None,
-    )
-    .await?;
+    for status in accessors::ALL_STATUSES {
+        let counts = reorganize_pages(
+            |next_token| {
+                accessors::get_deposit_entries_modified_from_height_with_status(
+                    context,
+                    status,
+                    minimum_height,
+                    next_token,
+                    None,
+                )
+            },
+            |deposit: DepositInfoEntry| async move {
+                reorganize_deposit(context, &deposit.primary_index_key, canonical_tip).await
+            },
+        )
+        .await?;
+        summary.deposits.add(counts);
+    }
 
-    // Setup debug modified deposit list.
-    let mut debug_modified_deposit_entries: Vec<DepositEntry> =
-        Vec::with_capacity(all_deposits.len());
-
-    // Kill the history from all the deposits.
-    for deposit in all_deposits {
-        for attempt in 0..ENTRY_UPDATE_RETRIES {
-            let mut entry =
-                accessors::get_deposit_entry(context, &deposit.primary_index_key).await?;
-            entry.reorganize_around(&request.canonical_tip)?;
-            match accessors::set_deposit_entry(context, &mut entry).await {
-                Ok(_) => break,
-                Err(Error::VersionConflict) => {
-                    debug!(
-                        "Encountered race condition in updating entry {:?}. Attempt {}/{}",
-                        entry, attempt, ENTRY_UPDATE_RETRIES
-                    );
-                }
-                e @ Err(_) => e?,
+    for status in accessors::ALL_STATUSES {
+        let counts = reorganize_pages(
+            |next_token| {
+                accessors::get_withdrawal_entries_modified_from_height_with_status(
+                    context,
+                    status,
+                    minimum_height,
+                    next_token,
+                    None,
+                )
+            },
+            |withdrawal: WithdrawalInfoEntry| async move {
+                reorganize_withdrawal(context, &withdrawal.primary_index_key, canonical_tip).await
+            },
+        )
+        .await?;
+        summary.withdrawals.add(counts);
+    }
+
+    info!(?summary, "Reorganized deposits and withdrawals.");
+    if summary.deposits.retries_exhausted > 0 || summary.withdrawals.retries_exhausted > 0 {
+        warn!(
+            ?summary,
+            "Failed to reorganize some entries within {ENTRY_UPDATE_RETRIES} attempts."
+        );
+        return Err(Error::TooManyInternalRetries);
+    }
+
+    // Make the canonical tip the only chainstate at its height so that the
+    // chainstate history records the hash it replaced.
+    accessors::replace_chainstate_entries_at_height(context, &canonical_tip.clone().into()).await?;
+
+    // Cleanup API state.
+    set_api_state_status(context, &ApiStatus::Stable(request.canonical_tip.into())).await?;
+
+    // All good.
+    Ok(warp::reply::with_status(
+        warp::reply::json(&summary),
+        StatusCode::OK,
+    ))
+}
+
+/// Fetches pages with `get_page` until there is no next token, passes each
+/// entry to `reorganize_entry`, and counts the outcomes. Only the current
+/// page is held in memory.
+async fn reorganize_pages<E, GetPage, PageFuture, Reorganize, ReorganizeFuture>(
+    mut get_page: GetPage,
+    mut reorganize_entry: Reorganize,
+) -> Result<ReorgCounts, Error>
+where
+    GetPage: FnMut(Option<String>) -> PageFuture,
+    PageFuture: Future<Output = Result<(Vec<E>, Option<String>), Error>>,
+    Reorganize: FnMut(E) -> ReorganizeFuture,
+    ReorganizeFuture: Future<Output = Result<EntryReorgOutcome, Error>>,
+{
+    let mut counts = ReorgCounts::default();
+    let mut next_token: Option<String> = None;
+    loop {
+        let (entries, maybe_next_token) = get_page(next_token).await?;
+        for entry in entries {
+            counts.entries_examined += 1;
+            match reorganize_entry(entry).await? {
+                EntryReorgOutcome::Unchanged => {}
+                EntryReorgOutcome::Modified => counts.entries_modified += 1,
+                EntryReorgOutcome::RetriesExhausted => counts.retries_exhausted += 1,
             }
-            // Add modified deposit entries.
-            debug_modified_deposit_entries.push(entry);
+        }
+        next_token = maybe_next_token;
+        if next_token.is_none() {
+            return Ok(counts);
         }
     }
+}
 
-    // Show updated deposits when in debug mode.
-    debug!(
-        "Reorganized deposits: {}",
-        serde_json::to_string_pretty(&debug_modified_deposit_entries)?
-    );
+/// Removes the history of a deposit that is no longer canonical, retrying
+/// when the entry is modified concurrently.
+async fn reorganize_deposit(
+    context: &EmilyContext,
+    key: &DepositEntryKey,
+    canonical_tip: &Chainstate,
+) -> Result<EntryReorgOutcome, Error> {
+    let result = accessors::with_version_retry::<DepositTablePrimaryIndex, _>(
+        context,
+        key,
+        ENTRY_UPDATE_RETRIES,
+        |entry| {
+            let original = entry.clone();
+            entry.reorganize_around(canonical_tip)?;
+            Ok(*entry != original)
+        },
+    )
+    .await;
+    match result {
+        Ok(Some(_)) => Ok(EntryReorgOutcome::Modified),
+        Ok(None) => Ok(EntryReorgOutcome::Unchanged),
+        Err(Error::VersionConflict) => {
+            warn!("Failed to reorganize deposit {key:?} within {ENTRY_UPDATE_RETRIES} attempts.");
+            Ok(EntryReorgOutcome::RetriesExhausted)
+        }
+        Err(error) => Err(error),
+    }
+}
 
-    // Get all withdrawals that would be impacted by this reorg.
-    let all_withdrawals = accessors::get_all_withdrawal_entries_modified_from_height(
+/// Removes the history of a withdrawal that is no longer canonical,
+/// retrying when the entry is modified concurrently.
+async fn reorganize_withdrawal(
+    context: &EmilyContext,
+    key: &WithdrawalEntryKey,
+    canonical_tip: &Chainstate,
+) -> Result<EntryReorgOutcome, Error> {
+    let result = accessors::with_version_retry::<WithdrawalTablePrimaryIndex, _>(
         context,
-        request.canonical_tip.stacks_block_height,
-        None,
+        key,
+        ENTRY_UPDATE_RETRIES,
+        |entry| {
+            let original = entry.clone();
+            entry.reorganize_around(canonical_tip)?;
+            Ok(*entry != original)
+        },
     )
-    .await?;
+    .await;
+    match result {
+        Ok(Some(_)) => Ok(EntryReorgOutcome::Modified),
+        Ok(None) => Ok(EntryReorgOutcome::Unchanged),
+        Err(Error::VersionConflict) => {
+            warn!(
+                "Failed to reorganize withdrawal {} within {ENTRY_UPDATE_RETRIES} attempts.",
+                key.request_id
+            );
+            Ok(EntryReorgOutcome::RetriesExhausted)
+        }
+        Err(error) => Err(error),
+    }
+}
 
-    // Setup debug modified withdrawal list.
-    let mut debug_modified_withdrawal_entries: Vec<WithdrawalEntry> =
-        Vec::with_capacity(all_withdrawals.len());
-
-    // Kill the history from all the withdrawals.
-    for withdrawal in all_withdrawals {
-        for attempt in 0..ENTRY_UPDATE_RETRIES {
-            let request_id = withdrawal.primary_index_key.request_id;
-            let mut entry = accessors::get_withdrawal_entry(context, &request_id).await?;
-            entry.reorganize_around(&request.canonical_tip)?;
-            match accessors::set_withdrawal_entry(context, &mut entry).await {
-                Ok(_) => break,
-                Err(Error::VersionConflict) => {
-                    debug!(
-                        "Encountered race condition in updating entry {:?}. Attempt {}/{}",
-                        entry, attempt, ENTRY_UPDATE_RETRIES
-                    );
-                }
-                e @ Err(_) => e?,
-            }
-            // Add modified withdrawal entries.
-            debug_modified_withdrawal_entries.push(entry);
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    /// Serves the given pages in order, using the page index as the next token.
+    fn paged(
+        pages: Vec<Vec<u32>>,
+    ) -> impl FnMut(Option<String>) -> std::future::Ready<Result<(Vec<u32>, Option<String>), Error>>
+    {
+        let num_pages = pages.len();
+        let mut pages: VecDeque<Vec<u32>> = pages.into();
+        let mut expected_token: Option<String> = None;
+        move |next_token| {
+            assert_eq!(next_token, expected_token);
+            let page = pages.pop_front().unwrap_or_default();
+            let served = num_pages - pages.len();
+            expected_token = (served < num_pages).then(|| served.to_string());
+            std::future::ready(Ok((page, expected_token.clone())))
         }
     }
 
-    // Show updated withdrawals when in debug mode.
-    debug!(
-        "Reorganized withdrawals: {}",
-        serde_json::to_string_pretty(&debug_modified_withdrawal_entries)?
-    );
+    fn outcome(entry: u32) -> std::future::Ready<Result<EntryReorgOutcome, Error>> {
+        let outcome = match entry % 3 {
+            0 => EntryReorgOutcome::Unchanged,
+            1 => EntryReorgOutcome::Modified,
+            _ => EntryReorgOutcome::RetriesExhausted,
+        };
+        std::future::ready(Ok(outcome))
+    }
 
-    // Cleanup API state.
-    set_api_state_status(context, &ApiStatus::Stable(request.canonical_tip.into())).await?;
+    #[tokio::test]
+    async fn reorganize_pages_counts_single_page() {
+        let counts = reorganize_pages(paged(vec![vec![0, 1, 3, 4]]), outcome)
+            .await
+            .unwrap();
+        let expected = ReorgCounts {
+            entries_examined: 4,
+            entries_modified: 2,
+            retries_exhausted: 0,
+        };
+        assert_eq!(counts, expected);
+    }
 
-    // All good.
-    Ok(empty_reply)
-}
+    #[tokio::test]
+    async fn reorganize_pages_counts_across_pages() {
+        let pages = vec![vec![0, 1, 2], vec![], vec![4, 5], vec![6, 7, 8, 9]];
+        let counts = reorganize_pages(paged(pages), outcome).await.unwrap();
+        let expected = ReorgCounts {
+            entries_examined: 9,
+            entries_modified: 3,
+            retries_exhausted: 3,
+        };
+        assert_eq!(counts, expected);
+    }
+
+    #[tokio::test]
+    async fn reorganize_pages_handles_empty_table() {
+        let counts = reorganize_pages(paged(vec![]), outcome).await.unwrap();
+        assert_eq!(counts, ReorgCounts::default());
+    }
+
+    #[tokio::test]
+    async fn reorganize_pages_stops_at_first_error() {
+        let mut examined = Vec::new();
+        let result = reorganize_pages(paged(vec![vec![1, 2], vec![3, 4]]), |entry| {
+            examined.push(entry);
+            let result = if entry == 3 {
+                Err(Error::InternalServer)
+            } else {
+                Ok(EntryReorgOutcome::Modified)
+            };
+            std::future::ready(result)
+        })
+        .await;
+        assert!(matches!(result, Err(Error::InternalServer)));
+        assert_eq!(examined, vec![1, 2, 3]);
+    }
 
-// TODO: Unit tests.
+    #[test]
+    fn reorg_summary_serializes_counts() {
+        let summary = ReorgSummary {
+            deposits: ReorgCounts {
+                entries_examined: 3,
+                entries_modified: 2,
+                retries_exhausted: 0,
+            },
+            withdrawals: ReorgCounts::default(),
+        };
+        let json = serde_json::to_value(summary).unwrap();
+        assert_eq!(json["deposits"]["entriesExamined"], 3);
+        assert_eq!(json["deposits"]["entriesModified"], 2);
+        assert_eq!(json["withdrawals"]["retriesExhausted"], 0);
+    }
+}
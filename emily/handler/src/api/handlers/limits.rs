@@ -40,8 +40,9 @@ pub async fn get_limits(context: EmilyContext) -> impl warp::reply::Reply {
 }
 
 /// Set limits handler.
-/// Note, that `available_to_withdraw` is not settable, but is calculated based on the other fields.
-/// Value of `available_to_withdraw` passed to this endpoint will be ignored.
+/// Note, that `available_to_withdraw` and `available_to_deposit` are not settable, but are calculated
+/// based on the other fields. Values of `available_to_withdraw` and `available_to_deposit` passed to
+/// this endpoint will be ignored.
 #[utoipa::path(
     post,
     operation_id = "setLimits",
@@ -64,7 +65,7 @@ pub async fn set_limits(context: EmilyContext, limits: Limits) -> impl warp::rep
         context: EmilyContext,
         limits: Limits,
     ) -> Result<impl warp::reply::Reply, Error> {
-        // Validate the withdrawal limit configuration.
+        // Validate the rolling limit configuration.
         limits.validate()?;
         // Set the global limits.
         accessors::set_limit_for_account(
@@ -79,6 +80,8 @@ pub async fn set_limits(context: EmilyContext, limits: Limits) -> impl warp::rep
                     per_withdrawal_cap: limits.per_withdrawal_cap,
                     rolling_withdrawal_blocks: limits.rolling_withdrawal_blocks,
                     rolling_withdrawal_cap: limits.rolling_withdrawal_cap,
+                    rolling_deposit_blocks: limits.rolling_deposit_blocks,
+                    rolling_deposit_cap: limits.rolling_deposit_cap,
                 },
             ),
         )
@@ -1,6 +1,9 @@
 //! Handlers for the emily API
 
-use crate::common::error::ErrorResponse;
+use crate::common::error::{
+    BulkChainstateConflictErrorResponse, ChainGapErrorResponse, Error, ErrorResponse,
+    LimitExceededErrorResponse, NotFoundErrorResponse,
+};
 
 use std::convert::Infallible;
 use tracing::error;
@@ -18,6 +21,8 @@ pub mod internal;
 pub mod limits;
 /// New block handlers.
 pub mod new_block;
+/// Subscription handlers.
+pub mod subscription;
 /// Testing handlers.
 #[cfg(feature = "testing")]
 pub mod testing;
@@ -41,6 +46,13 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
         return Ok(warp::reply::with_status(json, StatusCode::BAD_REQUEST));
     }
 
+    if let Some(e) = err.find::<warp::reject::InvalidQuery>() {
+        let json = warp::reply::json(&ErrorResponse {
+            message: format!("Invalid Query: {}", e),
+        });
+        return Ok(warp::reply::with_status(json, StatusCode::BAD_REQUEST));
+    }
+
     if let Some(e) = err.find::<warp::reject::MethodNotAllowed>() {
         let json = warp::reply::json(&ErrorResponse {
             message: format!("Method Not Allowed: {e:?}"),
@@ -51,6 +63,34 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
         ));
     }
 
+    if let Some(Error::NotFound(resource, key)) = err.find::<Error>() {
+        let json = warp::reply::json(&NotFoundErrorResponse::new(resource, key));
+        return Ok(warp::reply::with_status(json, StatusCode::NOT_FOUND));
+    }
+
+    if let Some(Error::LimitExceeded(limit, value)) = err.find::<Error>() {
+        let json = warp::reply::json(&LimitExceededErrorResponse::new(limit, *value));
+        return Ok(warp::reply::with_status(json, StatusCode::BAD_REQUEST));
+    }
+
+    if let Some(Error::ChainGap(first_missing_height, last_missing_height)) = err.find::<Error>() {
+        let json = warp::reply::json(&ChainGapErrorResponse::new(
+            *first_missing_height,
+            *last_missing_height,
+        ));
+        return Ok(warp::reply::with_status(json, StatusCode::CONFLICT));
+    }
+
+    if let Some(Error::BulkChainstateConflict(index, message)) = err.find::<Error>() {
+        let json = warp::reply::json(&BulkChainstateConflictErrorResponse::new(*index, message));
+        return Ok(warp::reply::with_status(json, StatusCode::CONFLICT));
+    }
+
+    if let Some(e) = err.find::<Error>() {
+        let json = warp::reply::json(&ErrorResponse { message: e.to_string() });
+        return Ok(warp::reply::with_status(json, e.status_code()));
+    }
+
     error!("Unhandled error: {:?}", err);
     let json = warp::reply::json(&ErrorResponse {
         message: format!("Internal Server Error: {err:?}"),
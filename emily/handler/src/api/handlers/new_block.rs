@@ -279,6 +279,7 @@ async fn handle_completed_deposit(
             stacks_txid: hex::encode(event.txid.0),
         }),
         status_message: format!("Included in block {}", event.block_id.to_hex()),
+        updated_by: None,
     })
 }
 
@@ -308,6 +309,7 @@ fn handle_withdrawal_accept(event: WithdrawalAcceptEvent) -> WithdrawalUpdate {
             stacks_txid: hex::encode(event.txid.0),
         }),
         status_message: format!("Included in block {}", event.block_id.to_hex()),
+        updated_by: None,
     }
 }
 
@@ -360,6 +362,7 @@ fn handle_withdrawal_reject(event: WithdrawalRejectEvent) -> WithdrawalUpdate {
         request_id: event.request_id,
         status: Status::Failed,
         status_message: "Rejected".to_string(),
+        updated_by: None,
     }
 }
 
@@ -423,6 +426,7 @@ mod test {
             status: Status::Failed,
             fulfillment: None,
             status_message: "Rejected".to_string(),
+            updated_by: None,
         };
 
         let res = handle_withdrawal_reject(event);
@@ -457,6 +461,7 @@ mod test {
                 stacks_txid: event.txid.to_string(),
             }),
             status_message: format!("Included in block {}", event.block_id.to_hex()),
+            updated_by: None,
         };
 
         let res = handle_withdrawal_accept(event);
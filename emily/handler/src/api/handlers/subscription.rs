@@ -0,0 +1,371 @@
+//! Handlers for subscription endpoints and the notifications sent to
+//! subscribers.
+use std::time::Duration;
+
+use crate::{
+    api::models::subscription::{
+        DeleteSubscriptionQuery, Notification, Subscription, SubscriptionOperation,
+    },
+    common::error::Error,
+    context::EmilyContext,
+    database::{
+        accessors,
+        entries::subscription::{SubscriptionEntry, SubscriptionEntryKey},
+    },
+};
+use tracing::instrument;
+use warp::http::StatusCode;
+use warp::reply::{Reply, json, with_status};
+
+/// The number of times a notification is sent to a subscriber before
+/// giving up on it.
+const NOTIFICATION_ATTEMPTS: u32 = 3;
+
+/// How long to wait for a subscriber to respond to a notification.
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The delay before the first retry of a notification. It doubles with
+/// every following retry.
+const NOTIFICATION_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// Create subscription handler.
+#[utoipa::path(
+    post,
+    operation_id = "createSubscription",
+    path = "/subscriptions",
+    tag = "subscription",
+    request_body = Subscription,
+    responses(
+        (status = 201, description = "Subscription created successfully", body = Subscription),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context))]
+pub async fn create_subscription(
+    context: EmilyContext,
+    subscription: Subscription,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        subscription: Subscription,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        subscription.validate()?;
+        let entry: SubscriptionEntry = subscription.clone().into();
+        accessors::set_subscription_entry(&context, &entry).await?;
+        Ok(with_status(json(&subscription), StatusCode::CREATED))
+    }
+    // Handle and respond.
+    handler(context, subscription)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Delete subscription handler.
+#[utoipa::path(
+    delete,
+    operation_id = "deleteSubscription",
+    path = "/subscriptions",
+    params(
+        ("url" = String, Query, description = "The URL of the subscription."),
+        ("operation" = SubscriptionOperation, Query, description = "The kind of operation of the subscription."),
+    ),
+    tag = "subscription",
+    responses(
+        (status = 200, description = "Subscription deleted successfully"),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Subscription not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context))]
+pub async fn delete_subscription(
+    context: EmilyContext,
+    query: DeleteSubscriptionQuery,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        query: DeleteSubscriptionQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let key = SubscriptionEntryKey {
+            operation: query.operation,
+            url: query.url,
+        };
+        accessors::delete_subscription_entry(&context, &key).await?;
+        Ok(StatusCode::OK)
+    }
+    // Handle and respond.
+    handler(context, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Sends the notifications to every subscriber that is interested in them.
+///
+/// Delivery is best effort: failures are logged and never returned, so a
+/// misbehaving subscriber can't fail the update that caused the
+/// notification.
+pub async fn notify_subscribers(context: &EmilyContext, notifications: Vec<Notification>) {
+    let mut subscriptions: Vec<Subscription> = Vec::new();
+    for operation in [
+        SubscriptionOperation::Deposit,
+        SubscriptionOperation::Withdrawal,
+    ] {
+        if !notifications
+            .iter()
+            .any(|notification| notification.operation() == operation)
+        {
+            continue;
+        }
+        match accessors::get_subscription_entries_for_operation(context, &operation).await {
+            Ok(entries) => subscriptions.extend(entries.into_iter().map(Subscription::from)),
+            Err(error) => tracing::error!(
+                ?operation,
+                %error,
+                "failed to get subscriptions; notifications will not be sent"
+            ),
+        }
+    }
+    dispatch_notifications(&subscriptions, notifications).await;
+}
+
+/// Sends each notification to the subscriptions that match it, all at once,
+/// and waits for every delivery to either succeed or give up.
+async fn dispatch_notifications(subscriptions: &[Subscription], notifications: Vec<Notification>) {
+    if subscriptions.is_empty() || notifications.is_empty() {
+        return;
+    }
+    let client = match reqwest::Client::builder()
+        .timeout(NOTIFICATION_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(error) => {
+            tracing::error!(%error, "failed to build the notification client");
+            return;
+        }
+    };
+
+    let mut deliveries = Vec::new();
+    for notification in notifications {
+        for subscription in subscriptions.iter().filter(|subscription| {
+            subscription.matches(notification.operation(), notification.status())
+        }) {
+            deliveries.push(tokio::spawn(deliver(
+                client.clone(),
+                subscription.url.clone(),
+                notification.clone(),
+            )));
+        }
+    }
+    for delivery in deliveries {
+        if let Err(error) = delivery.await {
+            tracing::error!(%error, "notification delivery task failed");
+        }
+    }
+}
+
+/// Posts a notification to a subscriber, retrying with exponential backoff
+/// up to `NOTIFICATION_ATTEMPTS` times. Returns whether the subscriber
+/// accepted the notification.
+async fn deliver(client: reqwest::Client, url: String, notification: Notification) -> bool {
+    for attempt in 0..NOTIFICATION_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(NOTIFICATION_RETRY_BASE_DELAY * 2u32.pow(attempt - 1)).await;
+        }
+        let result = client
+            .post(&url)
+            .json(&notification)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => return true,
+            Err(error) => tracing::warn!(
+                %url,
+                attempt,
+                %error,
+                "failed to deliver notification"
+            ),
+        }
+    }
+    tracing::error!(
+        %url,
+        operation = ?notification.operation(),
+        status = ?notification.status(),
+        "giving up on delivering notification"
+    );
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use test_case::test_case;
+    use warp::Filter;
+
+    use crate::api::models::common::Status;
+    use crate::api::models::deposit::Deposit;
+    use crate::api::models::withdrawal::Withdrawal;
+
+    use super::*;
+
+    /// The bodies of the requests received by a mocked subscriber.
+    type Received = Arc<Mutex<Vec<serde_json::Value>>>;
+
+    /// Starts a mocked subscriber that records every notification it gets
+    /// and responds with the given status, and returns its URL.
+    fn mock_subscriber(status: StatusCode) -> (String, Received) {
+        let received: Received = Arc::default();
+        let state = received.clone();
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |body: serde_json::Value| {
+                state.lock().unwrap().push(body);
+                status
+            });
+        let (address, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (format!("http://{address}/notify"), received)
+    }
+
+    fn subscription(
+        url: &str,
+        operation: SubscriptionOperation,
+        statuses: Vec<Status>,
+    ) -> Subscription {
+        Subscription {
+            url: url.to_string(),
+            operation,
+            statuses,
+        }
+    }
+
+    fn deposit_notification(status: Status) -> Notification {
+        Notification::Deposit(Deposit {
+            bitcoin_txid: "txid".to_string(),
+            bitcoin_tx_output_index: 1,
+            status,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_the_updated_resource() {
+        let (url, received) = mock_subscriber(StatusCode::OK);
+        let subscriptions = vec![subscription(&url, SubscriptionOperation::Deposit, vec![])];
+
+        dispatch_notifications(&subscriptions, vec![deposit_notification(Status::Accepted)]).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["operation"], "deposit");
+        assert_eq!(received[0]["resource"]["bitcoinTxid"], "txid");
+        assert_eq!(received[0]["resource"]["bitcoinTxOutputIndex"], 1);
+        assert_eq!(received[0]["resource"]["status"], "accepted");
+    }
+
+    #[tokio::test]
+    async fn failing_subscriber_does_not_affect_others() {
+        let (failing_url, failing_received) = mock_subscriber(StatusCode::INTERNAL_SERVER_ERROR);
+        let (url, received) = mock_subscriber(StatusCode::OK);
+        let subscriptions = vec![
+            subscription(&failing_url, SubscriptionOperation::Deposit, vec![]),
+            subscription(&url, SubscriptionOperation::Deposit, vec![]),
+        ];
+
+        dispatch_notifications(
+            &subscriptions,
+            vec![deposit_notification(Status::Confirmed)],
+        )
+        .await;
+
+        assert_eq!(
+            failing_received.lock().unwrap().len(),
+            NOTIFICATION_ATTEMPTS as usize
+        );
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unreachable_subscriber_is_given_up_on() {
+        let notification = deposit_notification(Status::Failed);
+        let client = reqwest::Client::builder()
+            .timeout(NOTIFICATION_TIMEOUT)
+            .build()
+            .unwrap();
+        // Nothing listens on the discard port.
+        let url = "http://127.0.0.1:9/notify".to_string();
+
+        assert!(!deliver(client, url, notification).await);
+    }
+
+    #[tokio::test]
+    async fn only_matching_subscriptions_are_notified() {
+        let (url, received) = mock_subscriber(StatusCode::OK);
+        let (withdrawal_url, withdrawal_received) = mock_subscriber(StatusCode::OK);
+        let subscriptions = vec![
+            subscription(
+                &url,
+                SubscriptionOperation::Deposit,
+                vec![Status::Confirmed],
+            ),
+            subscription(&withdrawal_url, SubscriptionOperation::Withdrawal, vec![]),
+        ];
+        let notifications = vec![
+            deposit_notification(Status::Accepted),
+            deposit_notification(Status::Confirmed),
+        ];
+
+        dispatch_notifications(&subscriptions, notifications).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["resource"]["status"], "confirmed");
+        assert!(withdrawal_received.lock().unwrap().is_empty());
+    }
+
+    #[test_case(SubscriptionOperation::Deposit, Status::Accepted, vec![], true; "any status")]
+    #[test_case(SubscriptionOperation::Deposit, Status::Accepted, vec![Status::Accepted], true; "listed status")]
+    #[test_case(SubscriptionOperation::Deposit, Status::Failed, vec![Status::Accepted], false; "unlisted status")]
+    #[test_case(SubscriptionOperation::Withdrawal, Status::Accepted, vec![], false; "other operation")]
+    fn subscription_matches(
+        operation: SubscriptionOperation,
+        status: Status,
+        statuses: Vec<Status>,
+        expected: bool,
+    ) {
+        let subscription =
+            subscription("http://localhost", SubscriptionOperation::Deposit, statuses);
+        assert_eq!(subscription.matches(operation, &status), expected);
+    }
+
+    #[test_case("http://localhost:8080/notify", true; "http")]
+    #[test_case("https://example.com/notify", true; "https")]
+    #[test_case("ftp://example.com/notify", false; "other scheme")]
+    #[test_case("not a url", false; "not a url")]
+    fn subscription_url_is_validated(url: &str, is_valid: bool) {
+        let subscription = subscription(url, SubscriptionOperation::Withdrawal, vec![]);
+        assert_eq!(subscription.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn withdrawal_notification_payload() {
+        let notification = Notification::Withdrawal(Withdrawal {
+            request_id: 7,
+            status: Status::Pending,
+            ..Default::default()
+        });
+        let payload = serde_json::to_value(&notification).unwrap();
+        assert_eq!(payload["operation"], "withdrawal");
+        assert_eq!(payload["resource"]["requestId"], 7);
+        assert_eq!(payload["resource"]["status"], "pending");
+    }
+}
@@ -1,9 +1,10 @@
 //! Handlers for testing endpoint endpoints.
 
 use reqwest::StatusCode;
-use tracing::instrument;
-use warp::reply::Reply;
+use tracing::{instrument, warn};
+use warp::reply::{Reply, json, with_status};
 
+use crate::api::models::testing::WipeDatabasesResponse;
 use crate::common::error::Error;
 use crate::context::EmilyContext;
 use crate::database::accessors;
@@ -15,8 +16,9 @@ use crate::database::accessors;
     path = "/testing/wipe",
     tag = "testing",
     responses(
-        (status = 204, description = "Successfully wiped databases."),
+        (status = 200, description = "Successfully wiped databases.", body = WipeDatabasesResponse),
         (status = 400, description = "Invalid request body"),
+        (status = 403, description = "Forbidden outside of a testing deployment"),
         (status = 404, description = "Address not found"),
         (status = 405, description = "Method not allowed"),
         (status = 500, description = "Internal server error")
@@ -27,11 +29,18 @@ use crate::database::accessors;
 pub async fn wipe_databases(context: EmilyContext) -> impl warp::reply::Reply {
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(context: EmilyContext) -> Result<impl warp::reply::Reply, Error> {
-        accessors::wipe_all_tables(&context).await?;
-        Ok(warp::reply::with_status(
-            warp::reply(),
-            StatusCode::NO_CONTENT,
-        ))
+        // Never wipe the tables of a deployment that isn't meant for testing,
+        // even if the testing routes were mounted by mistake.
+        if !context.settings.is_testing_deployment() {
+            warn!(
+                "Refusing to wipe the databases in the {} deployment environment.",
+                context.settings.deployment_environment
+            );
+            return Err(Error::Forbidden);
+        }
+        let tables = accessors::wipe_all_tables(&context).await?;
+        let response = WipeDatabasesResponse { tables };
+        Ok(with_status(json(&response), StatusCode::OK))
     }
 
     // Handle and respond.
@@ -1,13 +1,21 @@
 //! Handlers for withdrawal endpoints.
+use std::str::FromStr as _;
+
+use bitcoin::{Address, Network, ScriptBuf};
 use tracing::{debug, instrument};
 use warp::reply::{Reply, json, with_status};
 
+use crate::api::handlers::subscription::notify_subscribers;
 use crate::api::models::common::Status;
 use crate::api::models::common::requests::BasicPaginationQuery;
+use crate::api::models::subscription::Notification;
 use crate::api::models::withdrawal::{Withdrawal, WithdrawalInfo};
 use crate::api::models::withdrawal::{
-    requests::{CreateWithdrawalRequestBody, GetWithdrawalsQuery, UpdateWithdrawalsRequestBody},
-    responses::{GetWithdrawalsResponse, UpdateWithdrawalsResponse},
+    requests::{
+        CreateWithdrawalRequestBody, GetWithdrawalsForRecipientQuery, GetWithdrawalsQuery,
+        UpdateWithdrawalsRequestBody,
+    },
+    responses::{GetWithdrawalHistoryResponse, GetWithdrawalsResponse, UpdateWithdrawalsResponse},
 };
 use crate::common::error::Error;
 use crate::context::EmilyContext;
@@ -31,7 +39,7 @@ use warp::http::StatusCode;
     responses(
         (status = 200, description = "Withdrawal retrieved successfully", body = Withdrawal),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
-        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 404, description = "Withdrawal not found", body = NotFoundErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
@@ -45,7 +53,8 @@ pub async fn get_withdrawal(context: EmilyContext, request_id: u64) -> impl warp
     ) -> Result<impl warp::reply::Reply, Error> {
         // Get withdrawal.
         let withdrawal: Withdrawal = accessors::get_withdrawal_entry(&context, &request_id)
-            .await?
+            .await
+            .map_err(|error| error.for_resource("withdrawal", request_id.to_string()))?
             .try_into()?;
 
         // Respond.
@@ -57,6 +66,57 @@ pub async fn get_withdrawal(context: EmilyContext, request_id: u64) -> impl warp
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Get withdrawal history handler.
+#[utoipa::path(
+    get,
+    operation_id = "getWithdrawalHistory",
+    path = "/withdrawal/{id}/history",
+    params(
+        ("id" = u64, Path, description = "id associated with the Withdrawal"),
+        ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
+        ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
+    ),
+    tag = "withdrawal",
+    responses(
+        (status = 200, description = "Withdrawal history retrieved successfully", body = GetWithdrawalHistoryResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Withdrawal not found", body = NotFoundErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_withdrawal_history(
+    context: EmilyContext,
+    request_id: u64,
+    query: BasicPaginationQuery,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        request_id: u64,
+        query: BasicPaginationQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        // Get withdrawal.
+        let entry = accessors::get_withdrawal_entry(&context, &request_id)
+            .await
+            .map_err(|error| error.for_resource("withdrawal", request_id.to_string()))?;
+        // The history is stored oldest first, so it can be paged through as is.
+        let (events, next_token) = query.page_of(entry.history)?;
+        let response = GetWithdrawalHistoryResponse {
+            next_token,
+            history: events.into_iter().map(Into::into).collect(),
+        };
+
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, request_id, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
 /// Get withdrawals handler.
 #[utoipa::path(
     get,
@@ -114,7 +174,8 @@ pub async fn get_withdrawals(
     operation_id = "getWithdrawalsForRecipient",
     path = "/withdrawal/recipient/{recipient}",
     params(
-        ("recipient" = String, Path, description = "The recipient's hex-encoded scriptPubKey, used to filter withdrawals."),
+        ("recipient" = String, Path, description = "The recipient's hex-encoded scriptPubKey or Bitcoin address, used to filter withdrawals."),
+        ("status" = Option<Status>, Query, description = "only return withdrawals with this status. Pages may hold fewer than pageSize items when set."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
         ("pageSize" = Option<u16>, Query, description = "the maximum number of items in the response list.")
     ),
@@ -131,15 +192,16 @@ pub async fn get_withdrawals(
 pub async fn get_withdrawals_for_recipient(
     context: EmilyContext,
     recipient: String,
-    query: BasicPaginationQuery,
+    query: GetWithdrawalsForRecipientQuery,
 ) -> impl warp::reply::Reply {
     debug!("in get_withdrawals_for_recipient: {recipient}");
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
         recipient: String,
-        query: BasicPaginationQuery,
+        query: GetWithdrawalsForRecipientQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
+        let recipient = recipient_script_pubkey(&recipient, context.settings.is_mainnet)?;
         let (entries, next_token) = accessors::get_withdrawal_entries_by_recipient(
             &context,
             &recipient,
@@ -147,9 +209,19 @@ pub async fn get_withdrawals_for_recipient(
             query.page_size,
         )
         .await?;
-        // Convert data into resource types.
-        let withdrawals: Vec<WithdrawalInfo> =
-            entries.into_iter().map(|entry| entry.into()).collect();
+        // Convert data into resource types. The status filter is applied
+        // after the page is read, so the next token still points past
+        // every entry that was examined.
+        let withdrawals: Vec<WithdrawalInfo> = entries
+            .into_iter()
+            .filter(|entry| {
+                query
+                    .status
+                    .as_ref()
+                    .is_none_or(|status| &entry.status == status)
+            })
+            .map(|entry| entry.into())
+            .collect();
         // Create response.
         let response = GetWithdrawalsResponse { withdrawals, next_token };
         // Respond.
@@ -259,6 +331,7 @@ pub async fn create_withdrawal(
             txid,
         } = body;
 
+        let recipient = recipient_script_pubkey(&recipient, context.settings.is_mainnet)?;
         let status = Status::Pending;
 
         // Make table entry.
@@ -276,6 +349,7 @@ pub async fn create_withdrawal(
                 status: StatusEntry::Pending,
                 message: "Just received withdrawal".to_string(),
                 stacks_block_hash: stacks_block_hash.clone(),
+                updated_by: None,
                 stacks_block_height,
             }],
             status,
@@ -356,26 +430,28 @@ pub async fn update_withdrawals(
         // Create aggregator.
         let mut updated_withdrawals: Vec<(usize, Withdrawal)> =
             Vec::with_capacity(validated_request.withdrawals.len());
+        let mut notifications: Vec<Notification> = Vec::new();
 
         // Loop through all updates and execute.
         for (index, update) in validated_request.withdrawals {
             let request_id = update.request_id;
             debug!(request_id, "updating withdrawal");
 
-            let updated_withdrawal = accessors::pull_and_update_withdrawal_with_retry(
-                &context,
-                update,
-                15,
-                is_trusted_key,
-            )
-            .await
-            .inspect_err(|error| {
-                tracing::error!(
-                    request_id,
-                    %error,
-                    "failed to update withdrawal",
-                );
-            })?;
+            let (updated_withdrawal, status_changed) =
+                accessors::pull_and_update_withdrawal_with_retry(
+                    &context,
+                    update,
+                    15,
+                    is_trusted_key,
+                )
+                .await
+                .inspect_err(|error| {
+                    tracing::error!(
+                        request_id,
+                        %error,
+                        "failed to update withdrawal",
+                    );
+                })?;
 
             let withdrawal: Withdrawal = updated_withdrawal.try_into().inspect_err(|error| {
                 // This should never happen, because the withdrawal was
@@ -387,8 +463,15 @@ pub async fn update_withdrawals(
                 );
             })?;
 
+            if status_changed {
+                notifications.push(Notification::Withdrawal(withdrawal.clone()));
+            }
             updated_withdrawals.push((index, withdrawal));
         }
+
+        // Let the subscribers know about the withdrawals that changed status.
+        notify_subscribers(&context, notifications).await;
+
         updated_withdrawals.sort_by_key(|(index, _)| *index);
         let withdrawals = updated_withdrawals
             .into_iter()
@@ -403,4 +486,73 @@ pub async fn update_withdrawals(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Returns the canonical lowercase hex-encoded scriptPubKey for the
+/// recipient of a withdrawal, which may be given either as a hex-encoded
+/// scriptPubKey or as a Bitcoin address for the network that Emily is
+/// configured for. Scripts that do not correspond to a standard address
+/// type are rejected, since the signers cannot pay out to them.
+fn recipient_script_pubkey(recipient: &str, is_mainnet: bool) -> Result<String, Error> {
+    let invalid_recipient =
+        || Error::HttpRequest(StatusCode::BAD_REQUEST, "invalid recipient".to_string());
+
+    if let Ok(address) = Address::from_str(recipient) {
+        let networks: &[Network] = if is_mainnet {
+            &[Network::Bitcoin]
+        } else {
+            &[Network::Testnet, Network::Regtest]
+        };
+        if !networks
+            .iter()
+            .any(|network| address.is_valid_for_network(*network))
+        {
+            return Err(invalid_recipient());
+        }
+        let script_pubkey = address.assume_checked().script_pubkey();
+        return Ok(hex::encode(script_pubkey.as_bytes()));
+    }
+
+    let script_pubkey = ScriptBuf::from_hex(recipient).map_err(|_| invalid_recipient())?;
+    // The network only affects how the address would be encoded, any
+    // standard script converts to an address on every network.
+    if Address::from_script(&script_pubkey, Network::Bitcoin).is_err() {
+        return Err(invalid_recipient());
+    }
+    Ok(hex::encode(script_pubkey.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    const SCRIPT_PUBKEY: &str = "0014751e76e8199196d454941c45d1b3a323f1433bd6";
+
+    #[test_case(SCRIPT_PUBKEY, true; "hex on mainnet")]
+    #[test_case(SCRIPT_PUBKEY, false; "hex on testnet")]
+    #[test_case("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", true; "mainnet address")]
+    #[test_case("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", false; "testnet address")]
+    #[test_case("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080", false; "regtest address")]
+    #[test_case("0014751E76E8199196D454941C45D1B3A323F1433BD6", true; "uppercase hex")]
+    #[test_case("0014751e76E8199196d454941C45d1b3a323F1433bd6", false; "mixed case hex")]
+    fn recipient_script_pubkey_accepts_hex_and_addresses(recipient: &str, is_mainnet: bool) {
+        let script_pubkey = recipient_script_pubkey(recipient, is_mainnet).unwrap();
+        assert_eq!(script_pubkey, SCRIPT_PUBKEY);
+    }
+
+    #[test_case("", true; "empty")]
+    #[test_case("not-a-recipient", true; "neither hex nor address")]
+    #[test_case("0014751e76e8199196d454941c45d1b3a323f1433b", true; "truncated witness program")]
+    #[test_case("6a0474657374", true; "op return script")]
+    #[test_case("0014751e76e8199196d454941c45d1b3a323f1433bd", true; "odd length hex")]
+    #[test_case("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", true; "testnet address on mainnet")]
+    #[test_case("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", false; "mainnet address on testnet")]
+    fn recipient_script_pubkey_rejects_invalid_input(recipient: &str, is_mainnet: bool) {
+        let result = recipient_script_pubkey(recipient, is_mainnet);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "HTTP request failed with status code 400 Bad Request: invalid recipient",
+        );
+    }
+}
+
 // TODO(393): Add handler unit tests.
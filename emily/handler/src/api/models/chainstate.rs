@@ -27,3 +27,99 @@ pub struct Chainstate {
     /// Bitcoin block height
     pub bitcoin_block_height: Option<u64>,
 }
+
+/// Default number of entries returned by the chainstate history endpoint.
+pub const DEFAULT_CHAINSTATE_HISTORY_LIMIT: u16 = 20;
+
+/// Maximum number of entries returned by the chainstate history endpoint.
+pub const MAX_CHAINSTATE_HISTORY_LIMIT: u16 = 100;
+
+/// Query structure for the get chainstate history request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainstateHistoryQuery {
+    /// Maximum number of entries to return.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u16>,
+}
+
+impl GetChainstateHistoryQuery {
+    /// The number of entries to return, defaulting to
+    /// [`DEFAULT_CHAINSTATE_HISTORY_LIMIT`] and capped at
+    /// [`MAX_CHAINSTATE_HISTORY_LIMIT`].
+    pub fn limit(&self) -> u16 {
+        self.limit
+            .unwrap_or(DEFAULT_CHAINSTATE_HISTORY_LIMIT)
+            .clamp(1, MAX_CHAINSTATE_HISTORY_LIMIT)
+    }
+}
+
+/// A chainstate entry in the history of the canonical chain.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainstateHistoryEntry {
+    /// Stacks block height.
+    pub stacks_block_height: u64,
+    /// Stacks block hash at the height.
+    pub stacks_block_hash: String,
+    /// Bitcoin block height
+    pub bitcoin_block_height: Option<u64>,
+    /// Hash of the conflicting stacks block that this block replaced at the
+    /// same height during a reorg, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub superseded_hash: Option<String>,
+}
+
+/// Response to get chainstate history request.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GetChainstateHistoryResponse {
+    /// Chainstate entries in descending height order, starting at the chain tip.
+    pub chainstates: Vec<ChainstateHistoryEntry>,
+}
+
+/// Maximum number of chainstates accepted in a single bulk submission.
+pub const MAX_BULK_CHAINSTATES: usize = 1000;
+
+/// Request structure for the bulk chainstate submission.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetChainstatesBulkRequestBody {
+    /// Chainstates in ascending height order. The first must be one height
+    /// above the chain tip, or at any height if there is no chain tip yet,
+    /// and each following chainstate must be one height above the previous
+    /// one. At most 1000 chainstates are accepted.
+    pub chainstates: Vec<Chainstate>,
+}
+
+/// Response to the bulk chainstate submission.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SetChainstatesBulkResponse {
+    /// Number of chainstates that were applied.
+    pub applied: u64,
+    /// The chain tip after applying the chainstates.
+    pub chaintip: Chainstate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(None, DEFAULT_CHAINSTATE_HISTORY_LIMIT; "default")]
+    #[test_case(Some(0), 1; "zero")]
+    #[test_case(Some(5), 5; "within the cap")]
+    #[test_case(Some(MAX_CHAINSTATE_HISTORY_LIMIT), MAX_CHAINSTATE_HISTORY_LIMIT; "at the cap")]
+    #[test_case(Some(u16::MAX), MAX_CHAINSTATE_HISTORY_LIMIT; "above the cap")]
+    fn chainstate_history_limit(limit: Option<u16>, expected: u16) {
+        let query = GetChainstateHistoryQuery { limit };
+        assert_eq!(query.limit(), expected);
+    }
+}
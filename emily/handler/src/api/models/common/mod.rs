@@ -83,3 +83,22 @@ pub struct Fulfillment {
     /// Satoshis consumed to fulfill the sBTC operation.
     pub btc_fee: u64,
 }
+
+/// An event in the status history of a deposit or withdrawal.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusHistoryEntry {
+    /// The status of the operation after this event.
+    pub status: Status,
+    /// The message that came with the status update.
+    pub message: String,
+    /// Stacks block height of the chain tip when the event happened.
+    pub stacks_block_height: u64,
+    /// Stacks block hash of the chain tip when the event happened.
+    pub stacks_block_hash: String,
+    /// Public key of the signer that made the update, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+}
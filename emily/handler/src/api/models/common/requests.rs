@@ -4,6 +4,13 @@ use utoipa::ToSchema;
 
 use serde::{Deserialize, Serialize};
 
+use crate::common::error::Error;
+
+/// The number of items in a page of results that are paginated in memory,
+/// like the history of a deposit or withdrawal, if the query does not
+/// specify a page size.
+pub const DEFAULT_IN_MEMORY_PAGE_SIZE: u16 = 100;
+
 /// Generic paginated query representation.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -27,3 +34,62 @@ pub struct BasicPaginationQuery {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u16>,
 }
+
+impl BasicPaginationQuery {
+    /// Returns the page of the given items that this query asks for, along
+    /// with the token for the next page if there is one. This is for items
+    /// that are already in memory, where the next token is the index of
+    /// the first item of the next page.
+    pub fn page_of<T>(&self, mut items: Vec<T>) -> Result<(Vec<T>, Option<String>), Error> {
+        let start = match &self.next_token {
+            Some(next_token) => next_token
+                .parse::<usize>()
+                .map_err(|err| Error::BadRequest(format!("malformed next token: {err}")))?,
+            None => 0,
+        };
+        let page_size = self.page_size.unwrap_or(DEFAULT_IN_MEMORY_PAGE_SIZE).max(1) as usize;
+        let end = start.saturating_add(page_size);
+        let next_token = (end < items.len()).then(|| end.to_string());
+        items.truncate(end);
+        let page = items.into_iter().skip(start).collect();
+        Ok((page, next_token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(None, None, &[0, 1, 2, 3, 4], None; "everything by default")]
+    #[test_case(None, Some(2), &[0, 1], Some("2"); "first page")]
+    #[test_case(Some("2"), Some(2), &[2, 3], Some("4"); "middle page")]
+    #[test_case(Some("4"), Some(2), &[4], None; "last page")]
+    #[test_case(Some("3"), Some(2), &[3, 4], None; "page ending with the items")]
+    #[test_case(Some("9"), Some(2), &[], None; "past the end")]
+    #[test_case(None, Some(0), &[0], Some("1"); "empty pages are not served")]
+    fn in_memory_pages(
+        next_token: Option<&str>,
+        page_size: Option<u16>,
+        expected_page: &[u32],
+        expected_next_token: Option<&str>,
+    ) {
+        let query = BasicPaginationQuery {
+            next_token: next_token.map(str::to_string),
+            page_size,
+        };
+        let (page, next_token) = query.page_of(vec![0, 1, 2, 3, 4]).unwrap();
+        assert_eq!(page, expected_page);
+        assert_eq!(next_token.as_deref(), expected_next_token);
+    }
+
+    #[test]
+    fn in_memory_pages_reject_malformed_next_token() {
+        let query = BasicPaginationQuery {
+            next_token: Some("not a number".to_string()),
+            page_size: None,
+        };
+        let result = query.page_of(vec![0, 1, 2]);
+        assert!(matches!(result, Err(Error::BadRequest(_))));
+    }
+}
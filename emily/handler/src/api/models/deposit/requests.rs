@@ -1,5 +1,6 @@
 //! Request structures for deposit api calls.
 
+use std::collections::HashSet;
 use std::str::FromStr;
 
 use bitcoin::blockdata::transaction::Transaction;
@@ -31,6 +32,21 @@ pub struct GetDepositsForTransactionQuery {
     pub page_size: Option<u16>,
 }
 
+/// Query structure for the get deposits for recipient request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositsForRecipientQuery {
+    /// Only return deposits with this status, if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// Next token for the search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+    /// Maximum number of results to show.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u16>,
+}
+
 /// Query structure for the GetDepositsQuery struct.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -43,6 +59,36 @@ pub struct GetDepositsQuery {
     /// Maximum number of results to show.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u16>,
+    /// Only return deposits last updated at or above this stacks block height.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_height: Option<u64>,
+    /// Only return deposits last updated at or below this stacks block height.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_height: Option<u64>,
+}
+
+impl GetDepositsQuery {
+    /// The inclusive range of last update heights to search, or `None` if
+    /// the query has neither height bound. A missing bound leaves that side
+    /// of the range open.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::HttpRequest`: If `fromHeight` is greater than `toHeight`.
+    pub fn height_range(&self) -> Result<Option<(u64, u64)>, Error> {
+        if self.from_height.is_none() && self.to_height.is_none() {
+            return Ok(None);
+        }
+        let from_height = self.from_height.unwrap_or(0);
+        let to_height = self.to_height.unwrap_or(u64::MAX);
+        if from_height > to_height {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                format!("fromHeight {from_height} is greater than toHeight {to_height}"),
+            ));
+        }
+        Ok(Some((from_height, to_height)))
+    }
 }
 
 /// Request structure for create deposit request.
@@ -130,6 +176,11 @@ pub struct DepositUpdate {
     /// Details about the on chain artifacts that fulfilled the deposit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// Public key of the signer making the update. Until updates are
+    /// authenticated per signer this is taken as given, so it is only
+    /// meant for auditing conflicting updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
 }
 
 impl DepositUpdate {
@@ -169,6 +220,7 @@ impl DepositUpdate {
             message: self.status_message,
             stacks_block_height: chainstate.stacks_block_height,
             stacks_block_hash: chainstate.stacks_block_hash,
+            updated_by: self.updated_by,
         };
         // Return the validated update.
         Ok(ValidatedDepositUpdate { key, event })
@@ -225,6 +277,71 @@ impl UpdateDepositsRequestBody {
     }
 }
 
+/// The largest number of deposits that can be looked up in a single
+/// lookup deposits request.
+pub const MAX_DEPOSIT_LOOKUP_KEYS: usize = 100;
+
+/// The key of a single deposit.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositKey {
+    /// Bitcoin transaction id.
+    pub bitcoin_txid: String,
+    /// Output index on the bitcoin transaction associated with this specific deposit.
+    pub bitcoin_tx_output_index: u32,
+}
+
+impl From<DepositKey> for DepositEntryKey {
+    fn from(key: DepositKey) -> Self {
+        DepositEntryKey {
+            bitcoin_txid: key.bitcoin_txid,
+            bitcoin_tx_output_index: key.bitcoin_tx_output_index,
+        }
+    }
+}
+
+impl From<DepositEntryKey> for DepositKey {
+    fn from(key: DepositEntryKey) -> Self {
+        DepositKey {
+            bitcoin_txid: key.bitcoin_txid,
+            bitcoin_tx_output_index: key.bitcoin_tx_output_index,
+        }
+    }
+}
+
+/// Request structure for the lookup deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupDepositsRequestBody {
+    /// The keys of the deposits to look up.
+    pub deposits: Vec<DepositKey>,
+}
+
+impl LookupDepositsRequestBody {
+    /// Try to convert the request body into the unique deposit keys to
+    /// look up, preserving the order of the request.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::HttpRequest`: If the request holds more than
+    ///   [`MAX_DEPOSIT_LOOKUP_KEYS`] deposits.
+    pub fn try_into_entry_keys(self) -> Result<Vec<DepositEntryKey>, Error> {
+        if self.deposits.len() > MAX_DEPOSIT_LOOKUP_KEYS {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                format!("cannot look up more than {MAX_DEPOSIT_LOOKUP_KEYS} deposits at once"),
+            ));
+        }
+        let mut seen = HashSet::with_capacity(self.deposits.len());
+        Ok(self
+            .deposits
+            .into_iter()
+            .filter(|key| seen.insert(key.clone()))
+            .map(DepositEntryKey::from)
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,4 +408,74 @@ mod tests {
             format!("HTTP request failed with status code 400 Bad Request: {expected_error}")
         );
     }
+
+    fn deposit_key(index: u32) -> DepositKey {
+        DepositKey {
+            bitcoin_txid: "txid".to_string(),
+            bitcoin_tx_output_index: index,
+        }
+    }
+
+    fn get_deposits_query(from_height: Option<u64>, to_height: Option<u64>) -> GetDepositsQuery {
+        GetDepositsQuery {
+            status: Status::Accepted,
+            from_height,
+            to_height,
+            ..Default::default()
+        }
+    }
+
+    #[test_case(None, None, None; "no bounds")]
+    #[test_case(Some(5), None, Some((5, u64::MAX)); "only from height")]
+    #[test_case(None, Some(5), Some((0, 5)); "only to height")]
+    #[test_case(Some(5), Some(9), Some((5, 9)); "both bounds")]
+    #[test_case(Some(5), Some(5), Some((5, 5)); "single height")]
+    fn get_deposits_query_height_range(
+        from_height: Option<u64>,
+        to_height: Option<u64>,
+        expected: Option<(u64, u64)>,
+    ) {
+        let query = get_deposits_query(from_height, to_height);
+        assert_eq!(query.height_range().unwrap(), expected);
+    }
+
+    #[test]
+    fn get_deposits_query_rejects_reversed_heights() {
+        let query = get_deposits_query(Some(10), Some(9));
+        assert_eq!(
+            query.height_range().unwrap_err().to_string(),
+            "HTTP request failed with status code 400 Bad Request: fromHeight 10 is greater than toHeight 9"
+        );
+    }
+
+    #[test]
+    fn lookup_deposits_request_rejects_too_many_keys() {
+        let request = LookupDepositsRequestBody {
+            deposits: (0..=MAX_DEPOSIT_LOOKUP_KEYS as u32)
+                .map(deposit_key)
+                .collect(),
+        };
+
+        let result = request.try_into_entry_keys();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            format!(
+                "HTTP request failed with status code 400 Bad Request: cannot look up more than {MAX_DEPOSIT_LOOKUP_KEYS} deposits at once"
+            )
+        );
+    }
+
+    #[test]
+    fn lookup_deposits_request_deduplicates_keys_in_order() {
+        let request = LookupDepositsRequestBody {
+            deposits: [2, 0, 2, 1, 0].into_iter().map(deposit_key).collect(),
+        };
+
+        let keys = request.try_into_entry_keys().unwrap();
+        let expected: Vec<DepositEntryKey> = [2, 0, 1]
+            .into_iter()
+            .map(|index| deposit_key(index).into())
+            .collect();
+        assert_eq!(keys, expected);
+    }
 }
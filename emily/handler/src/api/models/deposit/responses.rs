@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use utoipa::{ToResponse, ToSchema};
 
+use crate::api::models::common::StatusHistoryEntry;
+use crate::api::models::deposit::requests::DepositKey;
 use crate::api::models::deposit::{Deposit, DepositInfo};
 
 /// Response to get deposits for transaction request.
@@ -25,6 +27,16 @@ pub struct GetDepositsResponse {
     pub deposits: Vec<DepositInfo>,
 }
 
+/// Response to get deposit history request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositHistoryResponse {
+    /// Next token for the search.
+    pub next_token: Option<String>,
+    /// The events in the history of the deposit, oldest first.
+    pub history: Vec<StatusHistoryEntry>,
+}
+
 /// Response to update deposits request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
 #[serde(rename_all = "camelCase")]
@@ -32,3 +44,13 @@ pub struct UpdateDepositsResponse {
     /// Deposit infos: deposits with a little less data.
     pub deposits: Vec<Deposit>,
 }
+
+/// Response to lookup deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupDepositsResponse {
+    /// The deposits that were found, in the order they were requested.
+    pub deposits: Vec<Deposit>,
+    /// The keys of the requested deposits that were not found.
+    pub not_found: Vec<DepositKey>,
+}
@@ -26,29 +26,42 @@ pub struct Limits {
     /// Total amount sBTC still available for withdrawals in current window. All withdrawals except rejected
     /// counted here
     pub available_to_withdraw: Option<u64>,
+    /// Number of blocks that define the rolling deposit window.
+    pub rolling_deposit_blocks: Option<u64>,
+    /// Maximum total sBTC that can be minted by deposits within the rolling deposit window.
+    pub rolling_deposit_cap: Option<u64>,
+    /// Total amount of sBTC that can still be minted by deposits in the current window. Only
+    /// confirmed deposits are counted here.
+    pub available_to_deposit: Option<u64>,
     /// Represents the individual limits for requests coming from different accounts.
     pub account_caps: HashMap<String, AccountLimits>,
 }
 
 impl Limits {
-    /// Validates the withdrawal limit configuration.
+    /// Validates the rolling limit configuration.
     ///
-    /// This function checks if both `rolling_withdrawal_blocks` and `rolling_withdrawal_cap` are provided together.
-    /// If one is provided without the other, it returns an error indicating that the configuration is incomplete.
+    /// This function checks if both `rolling_withdrawal_blocks` and `rolling_withdrawal_cap` are provided together,
+    /// and likewise for `rolling_deposit_blocks` and `rolling_deposit_cap`. If one is provided without the other,
+    /// it returns an error indicating that the configuration is incomplete.
     ///
     /// # Returns
     ///
-    /// - `Ok(())`: If both `rolling_withdrawal_blocks` and `rolling_withdrawal_cap` are provided together.
-    /// - `Err(ValidationError::IncompleteWithdrawalLimitConfig)`: If one of the fields is missing while the other is set.
+    /// - `Ok(())`: If the fields of each rolling window are provided together.
+    /// - `Err(ValidationError::IncompleteWithdrawalLimitConfig)`: If one of the withdrawal fields is missing while the other is set.
+    /// - `Err(ValidationError::IncompleteDepositLimitConfig)`: If one of the deposit fields is missing while the other is set.
     ///
     /// # Errors
     ///
-    /// See [`ValidationError::IncompleteWithdrawalLimitConfig`].
+    /// See [`ValidationError::IncompleteWithdrawalLimitConfig`] and [`ValidationError::IncompleteDepositLimitConfig`].
     pub fn validate(&self) -> Result<(), ValidationError> {
         match (self.rolling_withdrawal_blocks, self.rolling_withdrawal_cap) {
             (Some(_), None) | (None, Some(_)) => {
-                Err(ValidationError::IncompleteWithdrawalLimitConfig)
+                return Err(ValidationError::IncompleteWithdrawalLimitConfig);
             }
+            _ => {}
+        }
+        match (self.rolling_deposit_blocks, self.rolling_deposit_cap) {
+            (Some(_), None) | (None, Some(_)) => Err(ValidationError::IncompleteDepositLimitConfig),
             _ => Ok(()),
         }
     }
@@ -83,4 +96,8 @@ pub struct AccountLimits {
     pub rolling_withdrawal_blocks: Option<u64>,
     /// Maximum total sBTC that can be withdrawn within the rolling withdrawal window.
     pub rolling_withdrawal_cap: Option<u64>,
+    /// Number of blocks that define the rolling deposit window.
+    pub rolling_deposit_blocks: Option<u64>,
+    /// Maximum total sBTC that can be minted by deposits within the rolling deposit window.
+    pub rolling_deposit_cap: Option<u64>,
 }
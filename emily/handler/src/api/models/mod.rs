@@ -12,5 +12,10 @@ pub mod health;
 pub mod limits;
 /// Api structures for new block events.
 pub mod new_block;
+/// Api structures for subscriptions.
+pub mod subscription;
+/// Api structures for testing.
+#[cfg(feature = "testing")]
+pub mod testing;
 /// Api structures for withdrawals.
 pub mod withdrawal;
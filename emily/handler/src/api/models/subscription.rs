@@ -0,0 +1,113 @@
+//! Request structures for subscription api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+use crate::api::models::common::Status;
+use crate::api::models::deposit::Deposit;
+use crate::api::models::withdrawal::Withdrawal;
+use crate::common::error::Error;
+
+/// The kind of sBTC operation that a subscription is notified about.
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionOperation {
+    /// Deposits.
+    #[default]
+    Deposit,
+    /// Withdrawals.
+    Withdrawal,
+}
+
+/// A subscription to status changes of deposits or withdrawals.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    /// The URL that notifications are sent to with an HTTP POST.
+    pub url: String,
+    /// The kind of operation to be notified about.
+    pub operation: SubscriptionOperation,
+    /// Only notify about operations that change to one of these statuses.
+    /// Every status change is notified about if this is empty.
+    #[serde(default)]
+    pub statuses: Vec<Status>,
+}
+
+impl Subscription {
+    /// Validates the subscription.
+    ///
+    /// # Errors
+    ///
+    /// - `Error::BadRequest`: If the URL is not an http or https URL.
+    pub fn validate(&self) -> Result<(), Error> {
+        match reqwest::Url::parse(&self.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+            _ => Err(Error::BadRequest(format!(
+                "invalid subscription url: {}",
+                self.url
+            ))),
+        }
+    }
+
+    /// Returns true if the subscription wants to be notified about an
+    /// operation of the given kind changing to the given status.
+    pub fn matches(&self, operation: SubscriptionOperation, status: &Status) -> bool {
+        self.operation == operation && (self.statuses.is_empty() || self.statuses.contains(status))
+    }
+}
+
+/// Query structure for the delete subscription request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSubscriptionQuery {
+    /// The URL of the subscription.
+    pub url: String,
+    /// The kind of operation of the subscription.
+    pub operation: SubscriptionOperation,
+}
+
+/// The body of the HTTP POST sent to subscribers when a deposit or
+/// withdrawal changes status. It has the kind of operation and the
+/// updated resource, as returned by the get endpoints.
+#[derive(Clone, Debug, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(tag = "operation", content = "resource", rename_all = "lowercase")]
+pub enum Notification {
+    /// A deposit changed status.
+    Deposit(Deposit),
+    /// A withdrawal changed status.
+    Withdrawal(Withdrawal),
+}
+
+impl Notification {
+    /// The kind of operation that changed status.
+    pub fn operation(&self) -> SubscriptionOperation {
+        match self {
+            Notification::Deposit(_) => SubscriptionOperation::Deposit,
+            Notification::Withdrawal(_) => SubscriptionOperation::Withdrawal,
+        }
+    }
+
+    /// The new status of the operation.
+    pub fn status(&self) -> &Status {
+        match self {
+            Notification::Deposit(deposit) => &deposit.status,
+            Notification::Withdrawal(withdrawal) => &withdrawal.status,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Response structures for testing api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+/// A table wiped by the wipe databases request.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WipedTable {
+    /// Name of the wiped table.
+    pub table_name: String,
+    /// Number of items deleted from the table.
+    pub items_deleted: u64,
+}
+
+/// Response to wipe databases request.
+#[derive(
+    Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WipeDatabasesResponse {
+    /// The wiped tables.
+    pub tables: Vec<WipedTable>,
+}
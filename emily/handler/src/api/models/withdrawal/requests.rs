@@ -26,6 +26,21 @@ pub struct GetWithdrawalsQuery {
     pub page_size: Option<u16>,
 }
 
+/// Query structure for the get withdrawals for recipient request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetWithdrawalsForRecipientQuery {
+    /// Only return withdrawals with this status, if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    /// Next token for the search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+    /// Maximum number of results to show.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u16>,
+}
+
 /// Request structure for the create withdrawal request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -36,7 +51,7 @@ pub struct CreateWithdrawalRequestBody {
     pub stacks_block_hash: String,
     /// The stacks block hash in which this request id was initiated.
     pub stacks_block_height: u64,
-    /// The recipient's Bitcoin hex-encoded scriptPubKey.
+    /// The recipient's hex-encoded Bitcoin scriptPubKey or Bitcoin address. It is stored as the lowercase hex-encoded scriptPubKey.
     pub recipient: String,
     /// The sender's Stacks principal.
     pub sender: String,
@@ -63,6 +78,11 @@ pub struct WithdrawalUpdate {
     /// Details about the on chain artifacts that fulfilled the withdrawal.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// Public key of the signer making the update. Until updates are
+    /// authenticated per signer this is taken as given, so it is only
+    /// meant for auditing conflicting updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
 }
 
 impl WithdrawalUpdate {
@@ -96,6 +116,7 @@ impl WithdrawalUpdate {
             message: self.status_message,
             stacks_block_height: chainstate.stacks_block_height,
             stacks_block_hash: chainstate.stacks_block_hash,
+            updated_by: self.updated_by,
         };
         // Return the validated update.
         Ok(ValidatedWithdrawalUpdate {
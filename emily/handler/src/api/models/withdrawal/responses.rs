@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 use utoipa::{ToResponse, ToSchema};
 
+use crate::api::models::common::StatusHistoryEntry;
 use crate::api::models::withdrawal::{Withdrawal, WithdrawalInfo};
 
 /// Response to get withdrawals request.
@@ -15,6 +16,16 @@ pub struct GetWithdrawalsResponse {
     pub withdrawals: Vec<WithdrawalInfo>,
 }
 
+/// Response to get withdrawal history request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetWithdrawalHistoryResponse {
+    /// Next token for the search.
+    pub next_token: Option<String>,
+    /// The events in the history of the withdrawal, oldest first.
+    pub history: Vec<StatusHistoryEntry>,
+}
+
 /// Response to update withdrawals request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
 #[serde(rename_all = "camelCase")]
@@ -2,7 +2,7 @@
 
 use warp::Filter;
 
-use crate::context::EmilyContext;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 
@@ -11,8 +11,10 @@ pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     get_chainstate_at_height(context.clone())
+        .or(get_chainstate_history(context.clone()))
         .or(set_chainstate(context.clone()))
         .or(update_chainstate(context.clone()))
+        .or(set_chainstates_bulk(context.clone()))
         .or(get_chain_tip(context))
 }
 
@@ -38,14 +40,28 @@ fn get_chainstate_at_height(
         .then(handlers::chainstate::get_chainstate_at_height)
 }
 
+/// Get chainstate history endpoint.
+fn get_chainstate_history(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("chainstate" / "history"))
+        .and(warp::get())
+        .and(warp::query())
+        .then(handlers::chainstate::get_chainstate_history)
+}
+
 /// Set chainstate endpoint.
 fn set_chainstate(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("chainstate"))
         .and(warp::post())
+        .and(authorized)
         .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::chainstate::set_chainstate)
@@ -55,13 +71,29 @@ fn set_chainstate(
 fn update_chainstate(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("chainstate"))
         .and(warp::put())
+        .and(authorized)
         .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::chainstate::update_chainstate)
 }
 
+/// Set chainstates in bulk endpoint.
+fn set_chainstates_bulk(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("chainstates" / "bulk"))
+        .and(warp::post())
+        .and(authorized)
+        .and(warp::body::json())
+        .then(handlers::chainstate::set_chainstates_bulk)
+}
+
 // TODO(387): Add route unit tests.
@@ -1,7 +1,7 @@
 //! Route definitions for the deposit endpoint.
 use warp::Filter;
 
-use crate::context::EmilyContext;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 
@@ -10,10 +10,12 @@ pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     get_deposit(context.clone())
+        .or(get_deposit_history(context.clone()))
         .or(get_deposits_for_transaction(context.clone()))
         .or(get_deposits(context.clone()))
         .or(get_deposits_for_recipient(context.clone()))
         .or(get_deposits_for_reclaim_pubkeys(context.clone()))
+        .or(lookup_deposits(context.clone()))
         .or(create_deposit(context.clone()))
         .or(update_deposits(context))
 }
@@ -29,6 +31,18 @@ fn get_deposit(
         .then(handlers::deposit::get_deposit)
 }
 
+/// Get deposit history endpoint.
+fn get_deposit_history(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / String / u32 / "history"))
+        .and(warp::get())
+        .and(warp::query())
+        .then(handlers::deposit::get_deposit_history)
+}
+
 /// Get deposits for transaction endpoint.
 fn get_deposits_for_transaction(
     context: EmilyContext,
@@ -77,6 +91,18 @@ fn get_deposits_for_reclaim_pubkeys(
         .then(handlers::deposit::get_deposits_for_reclaim_pubkeys)
 }
 
+/// Lookup deposits endpoint.
+fn lookup_deposits(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "lookup"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .then(handlers::deposit::lookup_deposits)
+}
+
 /// Create deposit endpoint.
 fn create_deposit(
     context: EmilyContext,
@@ -93,10 +119,12 @@ fn create_deposit(
 fn update_deposits(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("deposit"))
         .and(warp::put())
+        .and(authorized)
         .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::deposit::update_deposits)
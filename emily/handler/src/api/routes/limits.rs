@@ -2,7 +2,7 @@
 
 use warp::Filter;
 
-use crate::context::EmilyContext;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 
@@ -31,10 +31,12 @@ fn get_limits(
 fn set_limits(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Admin);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("limits"))
         .and(warp::post())
+        .and(authorized)
         .and(warp::body::json())
         .then(handlers::limits::set_limits)
 }
@@ -43,10 +45,12 @@ fn set_limits(
 fn set_limits_for_account(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Admin);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("limits" / String))
         .and(warp::post())
+        .and(authorized)
         .and(warp::body::json())
         .then(handlers::limits::set_limits_for_account)
 }
@@ -1,6 +1,9 @@
 //! Route definitions for the Emily API.
 
-use crate::context::EmilyContext;
+use std::collections::HashMap;
+
+use crate::common::error::Error;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 use tracing::debug;
@@ -16,6 +19,8 @@ mod health;
 mod limits;
 /// NewBlock routes.
 mod new_block;
+/// Subscription routes.
+mod subscription;
 /// Testing routes.
 #[cfg(feature = "testing")]
 mod testing;
@@ -37,6 +42,42 @@ where
     (as_response,)
 }
 
+/// Filter that authenticates the caller by the key in the `x-api-key`
+/// header and rejects the request unless that key has at least the
+/// `required` role. Missing and unknown keys are rejected with a 401 and
+/// keys without the required role are rejected with a 403.
+///
+/// The filter must come after the path and method filters of a route so
+/// that it never rejects requests meant for another route.
+fn require_role(
+    api_keys: &HashMap<String, ApiKeyRole>,
+    required: ApiKeyRole,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    let api_keys = api_keys.clone();
+    warp::header::optional::<String>("x-api-key")
+        .and_then(move |api_key: Option<String>| {
+            let result =
+                authorize(&api_keys, api_key.as_deref(), required).map_err(warp::reject::custom);
+            async move { result }
+        })
+        .untuple_one()
+}
+
+/// Checks that the API key is known and has at least the `required` role.
+fn authorize(
+    api_keys: &HashMap<String, ApiKeyRole>,
+    api_key: Option<&str>,
+    required: ApiKeyRole,
+) -> Result<(), Error> {
+    let role = api_key
+        .and_then(|api_key| api_keys.get(api_key))
+        .ok_or(Error::Unauthorized)?;
+    if *role < required {
+        return Err(Error::Forbidden);
+    }
+    Ok(())
+}
+
 /// This function sets up the Warp filters for handling all requests.
 #[cfg(feature = "testing")]
 pub fn routes(
@@ -55,6 +96,8 @@ pub fn routes(
         .boxed()
         .or(limits::routes(context.clone()))
         .boxed()
+        .or(subscription::routes(context.clone()))
+        .boxed()
         .or(testing::routes(context))
         .boxed()
         .or(verbose_not_found_route())
@@ -78,7 +121,9 @@ pub fn routes(
         .boxed()
         .or(withdrawal::routes(context.clone()))
         .boxed()
-        .or(limits::routes(context))
+        .or(limits::routes(context.clone()))
+        .boxed()
+        .or(subscription::routes(context))
         .boxed()
         // Convert reply to tuple to that more routes can be added to the returned filter.
         .map(|reply| (reply,))
@@ -122,3 +167,56 @@ fn verbose_not_found_route()
             )
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn api_keys() -> HashMap<String, ApiKeyRole> {
+        HashMap::from([
+            ("readonly-key".to_string(), ApiKeyRole::Readonly),
+            ("signer-key".to_string(), ApiKeyRole::Signer),
+            ("admin-key".to_string(), ApiKeyRole::Admin),
+        ])
+    }
+
+    async fn check(api_key: Option<&str>, required: ApiKeyRole) -> Result<(), warp::Rejection> {
+        let filter = require_role(&api_keys(), required);
+        let mut request = warp::test::request();
+        if let Some(api_key) = api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        request.filter(&filter).await
+    }
+
+    #[test_case(None, ApiKeyRole::Readonly; "missing_key")]
+    #[test_case(Some("wrong-key"), ApiKeyRole::Signer; "wrong_key")]
+    #[test_case(Some(""), ApiKeyRole::Readonly; "empty_key")]
+    #[tokio::test]
+    async fn unknown_key_is_unauthorized(api_key: Option<&str>, required: ApiKeyRole) {
+        let rejection = check(api_key, required).await.unwrap_err();
+        assert!(matches!(
+            rejection.find::<Error>(),
+            Some(Error::Unauthorized)
+        ));
+    }
+
+    #[test_case("readonly-key", ApiKeyRole::Signer; "readonly_on_signer_route")]
+    #[test_case("readonly-key", ApiKeyRole::Admin; "readonly_on_admin_route")]
+    #[test_case("signer-key", ApiKeyRole::Admin; "signer_on_admin_route")]
+    #[tokio::test]
+    async fn role_mismatch_is_forbidden(api_key: &str, required: ApiKeyRole) {
+        let rejection = check(Some(api_key), required).await.unwrap_err();
+        assert!(matches!(rejection.find::<Error>(), Some(Error::Forbidden)));
+    }
+
+    #[test_case("readonly-key", ApiKeyRole::Readonly; "readonly_on_readonly_route")]
+    #[test_case("signer-key", ApiKeyRole::Signer; "signer_on_signer_route")]
+    #[test_case("admin-key", ApiKeyRole::Signer; "admin_on_signer_route")]
+    #[test_case("admin-key", ApiKeyRole::Admin; "admin_on_admin_route")]
+    #[tokio::test]
+    async fn sufficient_role_is_accepted(api_key: &str, required: ApiKeyRole) {
+        check(Some(api_key), required).await.unwrap();
+    }
+}
@@ -2,7 +2,7 @@
 
 use warp::Filter;
 
-use crate::context::EmilyContext;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 
@@ -17,10 +17,12 @@ pub fn routes(
 fn new_block(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("new_block"))
         .and(warp::post())
+        .and(authorized)
         .and(warp::body::json())
         .then(handlers::new_block::new_block)
 }
@@ -0,0 +1,42 @@
+//! Route definitions for the subscription endpoint.
+
+use warp::Filter;
+
+use crate::context::{ApiKeyRole, EmilyContext};
+
+use super::handlers;
+
+/// Subscription routes.
+pub fn routes(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    create_subscription(context.clone()).or(delete_subscription(context))
+}
+
+/// Create subscription endpoint.
+fn create_subscription(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Admin);
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("subscriptions"))
+        .and(warp::post())
+        .and(authorized)
+        .and(warp::body::json())
+        .then(handlers::subscription::create_subscription)
+}
+
+/// Delete subscription endpoint.
+fn delete_subscription(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Admin);
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("subscriptions"))
+        .and(warp::delete())
+        .and(authorized)
+        .and(warp::query())
+        .then(handlers::subscription::delete_subscription)
+}
@@ -1,24 +1,113 @@
 //! Route definitions for the testing endpoint.
 use warp::Filter;
 
-use crate::context::EmilyContext;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 
-/// Testing routes
+/// Testing routes. These are only mounted when the testing routes are
+/// enabled in the settings; otherwise every request is rejected as not found.
 pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    wipe_databases(context)
+    enabled(context.settings.enable_testing_routes).and(wipe_databases(context))
+}
+
+/// Filter that rejects every request as not found unless `enabled` is set.
+fn enabled(enabled: bool) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::any()
+        .and_then(move || async move {
+            if enabled {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
 }
 
 /// Wipe databases
 fn wipe_databases(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Admin);
     warp::any()
         .map(move || context.clone())
         .and(warp::path!("testing" / "wipe"))
         .and(warp::post())
+        .and(authorized)
         .then(handlers::testing::wipe_databases)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_config::{BehaviorVersion, Region};
+    use clarity::vm::types::PrincipalData;
+    use test_case::test_case;
+    use warp::http::StatusCode;
+
+    use crate::api::handlers::handle_rejection;
+    use crate::api::models::limits::AccountLimits;
+    use crate::context::{Settings, TESTING_DEPLOYMENT_ENVIRONMENT};
+
+    use super::*;
+
+    /// Returns a context whose DynamoDB client is never reached by the
+    /// requests in these tests.
+    fn context(deployment_environment: &str, enable_testing_routes: bool) -> EmilyContext {
+        let config = aws_sdk_dynamodb::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-west-2"))
+            .build();
+        EmilyContext {
+            settings: Settings {
+                is_local: true,
+                deposit_table_name: "DepositTable".to_string(),
+                withdrawal_table_name: "WithdrawalTable".to_string(),
+                chainstate_table_name: "ChainstateTable".to_string(),
+                limit_table_name: "LimitTable".to_string(),
+                notification_table_name: "NotificationTable".to_string(),
+                default_limits: AccountLimits::default(),
+                trusted_reorg_api_key: "admin-key".to_string(),
+                api_keys: HashMap::from([
+                    ("signer-key".to_string(), ApiKeyRole::Signer),
+                    ("admin-key".to_string(), ApiKeyRole::Admin),
+                ]),
+                max_history_events: 100,
+                max_chainstate_gap: 100,
+                is_mainnet: false,
+                version: "test".to_string(),
+                deployer_address: PrincipalData::parse_standard_principal(
+                    "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS",
+                )
+                .unwrap(),
+                deployment_environment: deployment_environment.to_string(),
+                enable_testing_routes,
+            },
+            dynamodb_client: aws_sdk_dynamodb::Client::from_conf(config),
+        }
+    }
+
+    #[test_case(TESTING_DEPLOYMENT_ENVIRONMENT, false, "admin-key", StatusCode::NOT_FOUND; "routes_disabled")]
+    #[test_case(TESTING_DEPLOYMENT_ENVIRONMENT, true, "signer-key", StatusCode::FORBIDDEN; "non_admin_key")]
+    #[test_case("production", true, "admin-key", StatusCode::FORBIDDEN; "non_testing_deployment")]
+    #[tokio::test]
+    async fn wipe_databases_is_refused(
+        deployment_environment: &str,
+        enable_testing_routes: bool,
+        api_key: &str,
+        expected_status: StatusCode,
+    ) {
+        let filter = routes(context(deployment_environment, enable_testing_routes))
+            .recover(handle_rejection);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/testing/wipe")
+            .header("x-api-key", api_key)
+            .reply(&filter)
+            .await;
+        assert_eq!(response.status(), expected_status);
+    }
+}
@@ -1,7 +1,7 @@
 //! Route definitions for the withdrawal endpoint.
 use warp::Filter;
 
-use crate::context::EmilyContext;
+use crate::context::{ApiKeyRole, EmilyContext};
 
 use super::handlers;
 
@@ -10,6 +10,7 @@ pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     get_withdrawal(context.clone())
+        .or(get_withdrawal_history(context.clone()))
         .or(get_withdrawals(context.clone()))
         .or(get_withdrawals_for_recipient(context.clone()))
         .or(get_withdrawals_for_sender(context.clone()))
@@ -28,6 +29,18 @@ fn get_withdrawal(
         .then(handlers::withdrawal::get_withdrawal)
 }
 
+/// Get withdrawal history endpoint.
+fn get_withdrawal_history(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("withdrawal" / u64 / "history"))
+        .and(warp::get())
+        .and(warp::query())
+        .then(handlers::withdrawal::get_withdrawal_history)
+}
+
 /// Get withdrawals endpoint.
 fn get_withdrawals(
     context: EmilyContext,
@@ -68,10 +81,12 @@ fn get_withdrawals_for_sender(
 fn create_withdrawal(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
     warp::any()
         .map(move || context.clone())
         .and(warp::path("withdrawal"))
         .and(warp::post())
+        .and(authorized)
         .and(warp::body::json())
         .then(handlers::withdrawal::create_withdrawal)
 }
@@ -80,10 +95,12 @@ fn create_withdrawal(
 fn update_withdrawals(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let authorized = super::require_role(&context.settings.api_keys, ApiKeyRole::Signer);
     warp::any()
         .map(move || context.clone())
         .and(warp::path("withdrawal"))
         .and(warp::put())
+        .and(authorized)
         .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::withdrawal::update_withdrawals)
@@ -5,9 +5,9 @@ use std::env;
 use aws_sdk_dynamodb::{
     error::SdkError,
     operation::{
-        batch_write_item::BatchWriteItemError, delete_item::DeleteItemError,
-        get_item::GetItemError, put_item::PutItemError, query::QueryError, scan::ScanError,
-        update_item::UpdateItemError,
+        batch_get_item::BatchGetItemError, batch_write_item::BatchWriteItemError,
+        delete_item::DeleteItemError, get_item::GetItemError, put_item::PutItemError,
+        query::QueryError, scan::ScanError, update_item::UpdateItemError,
     },
 };
 use bitcoin::hex::HexToBytesError;
@@ -53,6 +53,13 @@ pub enum ValidationError {
         "incomplete withdrawal limit configuration: rolling_withdrawal_blocks and rolling_withdrawal_cap must be provided together"
     )]
     IncompleteWithdrawalLimitConfig,
+
+    /// One of rolling_deposit_blocks or rolling_deposit_cap is missing while the other is set.
+    /// Fields must be provided together to configure deposit limits.
+    #[error(
+        "incomplete deposit limit configuration: rolling_deposit_blocks and rolling_deposit_cap must be provided together"
+    )]
+    IncompleteDepositLimitConfig,
 }
 
 /// Errors from the internal API logic.
@@ -86,9 +93,19 @@ pub enum Error {
     Forbidden,
 
     /// This may be because you either requested a nonexistent endpoint
-    /// or referenced a user that does not exist
+    /// or referenced an item that does not exist
     #[error("Resource not found")]
-    NotFound,
+    ItemNotFound,
+
+    /// The requested resource does not exist. Holds the kind of resource
+    /// and the key it was requested by.
+    #[error("{0} not found: {1}")]
+    NotFound(&'static str, String),
+
+    /// The deposit amount falls outside of the configured deposit limits.
+    /// Holds the name of the limit and the value it was checked against.
+    #[error("{0} limit exceeded: {1}")]
+    LimitExceeded(&'static str, u64),
 
     /// You requested a response format that the API cannot produce
     /// We currently only support JSON output
@@ -103,6 +120,18 @@ pub enum Error {
     #[error("Request conflict")]
     Conflict,
 
+    /// The submitted chainstate is too far above the current chain tip.
+    /// Holds the first and the last of the heights that are missing
+    /// between the chain tip and the submitted chainstate.
+    #[error("Chainstate is too far above the chain tip, missing heights {0} to {1}")]
+    ChainGap(u64, u64),
+
+    /// A chainstate in a bulk submission does not extend the chain one
+    /// height at a time. Holds the index of the offending chainstate in
+    /// the submission and the reason it was rejected.
+    #[error("Chainstate at index {0} of the bulk submission is inconsistent: {1}")]
+    BulkChainstateConflict(usize, String),
+
     /// Internal error
     #[error("Internal server error")]
     InternalServer,
@@ -140,6 +169,11 @@ pub enum Error {
     #[error("Bad request {0}")]
     BadRequest(String),
 
+    /// A deposit with the same key already exists, but its immutable
+    /// fields differ from the submitted deposit in the listed fields.
+    #[error("Deposit conflicts with the existing deposit in fields: {0:?}")]
+    DepositConflict(Vec<&'static str>),
+
     /// Deserialization error
     #[error("Deserialization error: {0}")]
     Deserialization(String),
@@ -156,10 +190,14 @@ impl Error {
             Error::InvalidApiResponse => StatusCode::BAD_REQUEST,
             Error::Unauthorized => StatusCode::UNAUTHORIZED,
             Error::Forbidden => StatusCode::FORBIDDEN,
-            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::ItemNotFound => StatusCode::NOT_FOUND,
+            Error::NotFound(_, _) => StatusCode::NOT_FOUND,
+            Error::LimitExceeded(_, _) => StatusCode::BAD_REQUEST,
             Error::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Error::NotImplemented => StatusCode::NOT_IMPLEMENTED,
             Error::Conflict => StatusCode::CONFLICT,
+            Error::ChainGap(_, _) => StatusCode::CONFLICT,
+            Error::BulkChainstateConflict(_, _) => StatusCode::CONFLICT,
             Error::InternalServer => StatusCode::INTERNAL_SERVER_ERROR,
             Error::Debug(_) => StatusCode::IM_A_TEAPOT,
             Error::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
@@ -168,17 +206,51 @@ impl Error {
             Error::InconsistentState(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::Reorganizing(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Error::DepositConflict(_) => StatusCode::CONFLICT,
             Error::VersionConflict => StatusCode::INTERNAL_SERVER_ERROR,
             Error::Deserialization(_) => StatusCode::BAD_REQUEST,
         }
     }
     /// Converts the error into a warp response.
     pub fn into_response(self) -> warp::reply::Response {
-        warp::reply::with_status(
-            warp::reply::json(&ErrorResponse { message: format!("{self:?}") }),
-            self.status_code(),
-        )
-        .into_response()
+        match &self {
+            Error::NotFound(resource, key) => warp::reply::with_status(
+                warp::reply::json(&NotFoundErrorResponse::new(resource, key)),
+                self.status_code(),
+            )
+            .into_response(),
+            Error::LimitExceeded(limit, value) => warp::reply::with_status(
+                warp::reply::json(&LimitExceededErrorResponse::new(limit, *value)),
+                self.status_code(),
+            )
+            .into_response(),
+            Error::ChainGap(first_missing_height, last_missing_height) => warp::reply::with_status(
+                warp::reply::json(&ChainGapErrorResponse::new(
+                    *first_missing_height,
+                    *last_missing_height,
+                )),
+                self.status_code(),
+            )
+            .into_response(),
+            Error::BulkChainstateConflict(index, message) => warp::reply::with_status(
+                warp::reply::json(&BulkChainstateConflictErrorResponse::new(*index, message)),
+                self.status_code(),
+            )
+            .into_response(),
+            _ => warp::reply::with_status(
+                warp::reply::json(&ErrorResponse { message: format!("{self:?}") }),
+                self.status_code(),
+            )
+            .into_response(),
+        }
+    }
+    /// Converts an item missing from its table into a [`Error::NotFound`]
+    /// error for the given resource and key, leaving other errors as is.
+    pub fn for_resource(self, resource: &'static str, key: impl Into<String>) -> Self {
+        match self {
+            Error::ItemNotFound => Error::NotFound(resource, key.into()),
+            error => error,
+        }
     }
     /// Convert error into a presentable version of the error that can be
     /// provided to a client in production.
@@ -226,6 +298,11 @@ impl From<SdkError<GetItemError>> for Error {
         Error::Debug(format!("SdkError<GetItemError> - {err:?}"))
     }
 }
+impl From<SdkError<BatchGetItemError>> for Error {
+    fn from(err: SdkError<BatchGetItemError>) -> Self {
+        Error::Debug(format!("SdkError<BatchGetItemError> - {err:?}"))
+    }
+}
 impl From<SdkError<PutItemError>> for Error {
     fn from(err: SdkError<PutItemError>) -> Self {
         match err.into_service_error() {
@@ -322,6 +399,105 @@ pub struct ErrorResponse {
     pub(crate) message: String,
 }
 
+/// Body of the response to a request for a resource that does not exist.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NotFoundErrorResponse {
+    /// Always `not_found`.
+    pub(crate) error: String,
+    /// The kind of resource that was requested.
+    pub(crate) resource: String,
+    /// The key the resource was requested by.
+    pub(crate) key: String,
+}
+
+impl NotFoundErrorResponse {
+    /// Creates the response body for the resource with the given key.
+    pub fn new(resource: &str, key: &str) -> Self {
+        NotFoundErrorResponse {
+            error: "not_found".to_string(),
+            resource: resource.to_string(),
+            key: key.to_string(),
+        }
+    }
+}
+
+/// Body of the response to a deposit request that falls outside of the
+/// configured deposit limits.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LimitExceededErrorResponse {
+    /// Always `limit_exceeded`.
+    pub(crate) error: String,
+    /// The limit that the deposit exceeded, one of `perDepositMinimum`,
+    /// `perDepositCap` or `rollingDepositCap`.
+    pub(crate) limit: String,
+    /// The value the deposit amount was checked against. For the rolling
+    /// deposit cap this is the amount still available in the window.
+    pub(crate) value: u64,
+}
+
+impl LimitExceededErrorResponse {
+    /// Creates the response body for the given limit and value.
+    pub fn new(limit: &str, value: u64) -> Self {
+        LimitExceededErrorResponse {
+            error: "limit_exceeded".to_string(),
+            limit: limit.to_string(),
+            value,
+        }
+    }
+}
+
+/// Body of the response to a chainstate submission that is too far above
+/// the current chain tip. The client should submit the missing heights
+/// first.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainGapErrorResponse {
+    /// Always `chain_gap`.
+    pub(crate) error: String,
+    /// The first height missing between the chain tip and the submitted
+    /// chainstate.
+    pub(crate) first_missing_height: u64,
+    /// The last height missing between the chain tip and the submitted
+    /// chainstate.
+    pub(crate) last_missing_height: u64,
+}
+
+impl ChainGapErrorResponse {
+    /// Creates the response body for the given range of missing heights.
+    pub fn new(first_missing_height: u64, last_missing_height: u64) -> Self {
+        ChainGapErrorResponse {
+            error: "chain_gap".to_string(),
+            first_missing_height,
+            last_missing_height,
+        }
+    }
+}
+
+/// Body of the response to a bulk chainstate submission that does not
+/// extend the chain one height at a time. None of the chainstates in the
+/// submission are applied.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkChainstateConflictErrorResponse {
+    /// Always `bulk_chainstate_conflict`.
+    pub(crate) error: String,
+    /// The index of the first offending chainstate in the submission.
+    pub(crate) index: u64,
+    /// Why the chainstate was rejected.
+    pub(crate) message: String,
+}
+
+impl BulkChainstateConflictErrorResponse {
+    /// Creates the response body for the chainstate at the given index.
+    pub fn new(index: usize, message: &str) -> Self {
+        BulkChainstateConflictErrorResponse {
+            error: "bulk_chainstate_conflict".to_string(),
+            index: index as u64,
+            message: message.to_string(),
+        }
+    }
+}
+
 /// Implement reject for error.
 impl Reject for Error {}
 
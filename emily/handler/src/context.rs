@@ -5,6 +5,7 @@
 //! and the Signer that grabs the configuration values from a local default
 //! toml and potentially overwrites the fields with environment values.
 
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
 
@@ -18,6 +19,23 @@ use serde::Serialize;
 use crate::api::models::limits::AccountLimits;
 use crate::common::error::Error;
 
+/// Default maximum number of recent events kept in an entry's history. Each
+/// event is a few hundred bytes, which keeps entries far below DynamoDB's
+/// 400KB item size limit.
+pub const DEFAULT_MAX_HISTORY_EVENTS: usize = 100;
+
+/// Default maximum number of heights that may be missing between the chain
+/// tip and a newly submitted chainstate. Submissions further ahead are
+/// rejected so that the submitter fills in the missing heights first.
+pub const DEFAULT_MAX_CHAINSTATE_GAP: u64 = 100;
+
+/// The only deployment environment in which the testing endpoints may
+/// modify the tables.
+pub const TESTING_DEPLOYMENT_ENVIRONMENT: &str = "testing";
+
+/// Deployment environment assumed when none is configured.
+const DEFAULT_DEPLOYMENT_ENVIRONMENT: &str = "production";
+
 /// Emily lambda settings.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Settings {
@@ -31,16 +49,45 @@ pub struct Settings {
     pub chainstate_table_name: String,
     /// Limit table name.
     pub limit_table_name: String,
+    /// Notification table name.
+    pub notification_table_name: String,
     /// The default global limits for the system.
     pub default_limits: AccountLimits,
     /// The API key for the Bitcoin Layer 2 API.
     pub trusted_reorg_api_key: String,
+    /// The API keys that may call the authenticated endpoints, along with
+    /// the role granted to each of them.
+    pub api_keys: HashMap<String, ApiKeyRole>,
+    /// The maximum number of most recent events kept in the history of a
+    /// deposit or withdrawal entry, not counting its first event.
+    pub max_history_events: usize,
+    /// The maximum number of heights that may be missing between the chain
+    /// tip and a newly submitted chainstate.
+    pub max_chainstate_gap: u64,
     /// Whether the lambda is expecting transactions on mainnet.
     pub is_mainnet: bool,
     /// The version of the lambda.
     pub version: String,
     /// The address of the deployer of the sBTC smart contracts.
     pub deployer_address: StandardPrincipalData,
+    /// The environment the lambda is deployed in.
+    pub deployment_environment: String,
+    /// Whether the testing routes are mounted.
+    pub enable_testing_routes: bool,
+}
+
+/// The role granted to an API key. Roles are ordered from the least to
+/// the most privileged, and each role may call everything that the roles
+/// below it may call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyRole {
+    /// May only call the read endpoints.
+    Readonly,
+    /// May update deposits, withdrawals and the chainstate.
+    Signer,
+    /// May call every endpoint, including the limit and testing endpoints.
+    Admin,
 }
 
 /// Emily Context
@@ -68,14 +115,29 @@ impl fmt::Debug for EmilyContext {
                 &self.settings.chainstate_table_name,
             )
             .field("limit_table_name", &self.settings.limit_table_name)
+            .field(
+                "notification_table_name",
+                &self.settings.notification_table_name,
+            )
             .field("default_limits", &self.settings.default_limits)
+            .field("max_history_events", &self.settings.max_history_events)
+            .field("max_chainstate_gap", &self.settings.max_chainstate_gap)
             .field("is_mainnet", &self.settings.is_mainnet)
             .field("version", &self.settings.version)
             .field(
                 "deployer_address",
                 &self.settings.deployer_address.to_string(),
             )
+            .field(
+                "deployment_environment",
+                &self.settings.deployment_environment,
+            )
+            .field(
+                "enable_testing_routes",
+                &self.settings.enable_testing_routes,
+            )
             .field("trusted_reorg_api_key", &"[REDACTED]")
+            .field("api_keys", &"[REDACTED]")
             .finish()
     }
 }
@@ -96,6 +158,7 @@ impl Settings {
             withdrawal_table_name: env::var("WITHDRAWAL_TABLE_NAME")?,
             chainstate_table_name: env::var("CHAINSTATE_TABLE_NAME")?,
             limit_table_name: env::var("LIMIT_TABLE_NAME")?,
+            notification_table_name: env::var("NOTIFICATION_TABLE_NAME")?,
             default_limits: AccountLimits {
                 peg_cap: env::var("DEFAULT_PEG_CAP")
                     .ok()
@@ -121,13 +184,68 @@ impl Settings {
                     .ok()
                     .map(|v| v.parse())
                     .transpose()?,
+                rolling_deposit_blocks: env::var("DEFAULT_ROLLING_DEPOSIT_BLOCKS")
+                    .ok()
+                    .map(|v| v.parse())
+                    .transpose()?,
+                rolling_deposit_cap: env::var("DEFAULT_ROLLING_DEPOSIT_CAP")
+                    .ok()
+                    .map(|v| v.parse())
+                    .transpose()?,
             },
             trusted_reorg_api_key: env::var("TRUSTED_REORG_API_KEY")?,
+            api_keys: env::var("API_KEYS")
+                .ok()
+                .map(|v| parse_api_keys(&v))
+                .transpose()?
+                .unwrap_or_default(),
+            max_history_events: env::var("MAX_HISTORY_EVENTS")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()?
+                .unwrap_or(DEFAULT_MAX_HISTORY_EVENTS),
+            max_chainstate_gap: env::var("MAX_CHAINSTATE_GAP")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()?
+                .unwrap_or(DEFAULT_MAX_CHAINSTATE_GAP),
             is_mainnet: env::var("IS_MAINNET")?.to_lowercase() == "true",
             version: env::var("VERSION")?,
             deployer_address,
+            deployment_environment: env::var("DEPLOYMENT_ENVIRONMENT")
+                .unwrap_or_else(|_| DEFAULT_DEPLOYMENT_ENVIRONMENT.to_string()),
+            enable_testing_routes: env::var("ENABLE_TESTING_ROUTES")
+                .is_ok_and(|v| v.to_lowercase() == "true"),
         })
     }
+
+    /// Whether the lambda is deployed in the testing environment, which is
+    /// the only environment where the tables may be wiped.
+    pub fn is_testing_deployment(&self) -> bool {
+        self.deployment_environment == TESTING_DEPLOYMENT_ENVIRONMENT
+    }
+}
+
+/// Parses API keys from a comma separated list of `<key>:<role>` pairs,
+/// for example `key1:signer,key2:admin`.
+pub fn parse_api_keys(value: &str) -> Result<HashMap<String, ApiKeyRole>, Error> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (key, role) = entry
+                .rsplit_once(':')
+                .ok_or_else(|| Error::Debug("API key entry is missing its role".to_string()))?;
+            let role = match role.trim().to_lowercase().as_str() {
+                "readonly" => ApiKeyRole::Readonly,
+                "signer" => ApiKeyRole::Signer,
+                "admin" => ApiKeyRole::Admin,
+                role => return Err(Error::Debug(format!("Unknown API key role: {role}"))),
+            };
+            Ok((key.trim().to_string(), role))
+        })
+        .collect()
 }
 
 /// Implementation of Context.
@@ -156,8 +274,6 @@ impl EmilyContext {
     /// Create a local testing instance.
     #[cfg(feature = "testing")]
     pub async fn local_instance(dynamodb_endpoint: &str) -> Result<Self, Error> {
-        use std::collections::HashMap;
-
         // Get config that always points to the dynamodb table directly
         // from outside of a docker compose setup.
         let sdk_config = aws_config::load_defaults(BehaviorVersion::latest())
@@ -181,7 +297,13 @@ impl EmilyContext {
         // Attempt to get all the tables by searching the output of the
         // list tables operation.
         let mut table_name_map: HashMap<&str, String> = HashMap::new();
-        let tables_to_find: Vec<&str> = vec!["Deposit", "Chainstate", "Withdrawal", "Limit"];
+        let tables_to_find: Vec<&str> = vec![
+            "Deposit",
+            "Chainstate",
+            "Withdrawal",
+            "Limit",
+            "Notification",
+        ];
         for name in table_names {
             for table_to_find in &tables_to_find {
                 if name.contains(table_to_find) {
@@ -210,16 +332,56 @@ impl EmilyContext {
                     .get("Limit")
                     .expect("Couldn't find valid limit table table in existing table list.")
                     .to_string(),
+                notification_table_name: table_name_map
+                    .get("Notification")
+                    .expect("Couldn't find valid notification table in existing table list.")
+                    .to_string(),
                 default_limits: AccountLimits::default(),
                 trusted_reorg_api_key: "testApiKey".to_string(),
+                // The untrusted key has the signer role so that the integration
+                // tests can exercise the updates allowed to keys that are not the
+                // trusted reorg key.
+                api_keys: HashMap::from([
+                    ("testApiKey".to_string(), ApiKeyRole::Admin),
+                    ("untrusted_api_key".to_string(), ApiKeyRole::Signer),
+                ]),
+                max_history_events: DEFAULT_MAX_HISTORY_EVENTS,
+                max_chainstate_gap: DEFAULT_MAX_CHAINSTATE_GAP,
                 is_mainnet: false,
                 version: "local-instance".to_string(),
                 deployer_address: PrincipalData::parse_standard_principal(
                     "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS",
                 )
                 .unwrap(),
+                deployment_environment: TESTING_DEPLOYMENT_ENVIRONMENT.to_string(),
+                enable_testing_routes: true,
             },
             dynamodb_client,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_api_keys_reads_roles() {
+        let api_keys = parse_api_keys("key1:signer, key2:Admin,key3:readonly,").unwrap();
+        assert_eq!(
+            api_keys,
+            HashMap::from([
+                ("key1".to_string(), ApiKeyRole::Signer),
+                ("key2".to_string(), ApiKeyRole::Admin),
+                ("key3".to_string(), ApiKeyRole::Readonly),
+            ])
+        );
+        assert!(parse_api_keys("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_api_keys_rejects_bad_entries() {
+        assert!(parse_api_keys("key1").is_err());
+        assert!(parse_api_keys("key1:superuser").is_err());
+    }
+}
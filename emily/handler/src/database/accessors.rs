@@ -1,6 +1,7 @@
 //! Accessors.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use aws_sdk_dynamodb::types::AttributeValue;
 use serde_dynamo::Item;
@@ -8,6 +9,9 @@ use serde_dynamo::Item;
 use tracing::{debug, warn};
 
 use crate::api::models::limits::{AccountLimits, Limits};
+use crate::api::models::subscription::SubscriptionOperation;
+#[cfg(feature = "testing")]
+use crate::api::models::testing::WipedTable;
 use crate::common::error::{Error, Inconsistency};
 
 use crate::{api::models::common::Status, context::EmilyContext};
@@ -20,6 +24,9 @@ use super::entries::deposit::{
 use super::entries::limits::{
     GLOBAL_CAP_ACCOUNT, LimitEntry, LimitEntryKey, LimitTablePrimaryIndex,
 };
+use super::entries::subscription::{
+    SubscriptionEntry, SubscriptionEntryKey, SubscriptionTablePrimaryIndex,
+};
 use super::entries::withdrawal::{
     ValidatedWithdrawalUpdate, WithdrawalInfoByRecipientEntry, WithdrawalInfoBySenderEntry,
     WithdrawalTableByRecipientSecondaryIndex, WithdrawalTableBySenderSecondaryIndex,
@@ -45,9 +52,10 @@ use super::entries::{
 
 // Deposit ---------------------------------------------------------------------
 
-/// Add deposit entry.
+/// Add deposit entry. Fails with `Error::VersionConflict` if a deposit
+/// with the same key already exists.
 pub async fn add_deposit_entry(context: &EmilyContext, entry: &DepositEntry) -> Result<(), Error> {
-    put_entry::<DepositTablePrimaryIndex>(context, entry).await
+    put_entry_if_absent::<DepositTablePrimaryIndex>(context, entry).await
 }
 
 /// Sets / updates an existing deposit entry.
@@ -68,6 +76,15 @@ pub async fn get_deposit_entry(
     Ok(entry)
 }
 
+/// Get the deposit entries with the given keys. Keys without a deposit
+/// entry are skipped.
+pub async fn get_deposit_entries_by_keys(
+    context: &EmilyContext,
+    keys: &[DepositEntryKey],
+) -> Result<Vec<DepositEntry>, Error> {
+    get_entries::<DepositTablePrimaryIndex>(context, keys).await
+}
+
 /// Get deposit entries.
 pub async fn get_deposit_entries(
     context: &EmilyContext,
@@ -84,6 +101,27 @@ pub async fn get_deposit_entries(
     .await
 }
 
+/// Get deposit entries with the given status that were last updated at a
+/// stacks block height within the inclusive range `[from_height, to_height]`.
+pub async fn get_deposit_entries_in_height_range(
+    context: &EmilyContext,
+    status: &Status,
+    from_height: u64,
+    to_height: u64,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+) -> Result<(Vec<DepositInfoEntry>, Option<String>), Error> {
+    query_with_partition_key_and_sort_key_between::<DepositTableSecondaryIndex>(
+        context,
+        status,
+        &from_height,
+        &to_height,
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
+}
+
 /// Get deposit entries by recipient.
 pub async fn get_deposit_entries_by_recipient(
     context: &EmilyContext,
@@ -118,7 +156,7 @@ pub async fn get_deposit_entries_by_reclaim_pubkeys_hash(
 
 /// Hacky exhaustive list of all statuses that we will iterate over in order to
 /// get every deposit present.
-const ALL_STATUSES: &[Status] = &[
+pub const ALL_STATUSES: &[Status] = &[
     Status::Accepted,
     Status::Confirmed,
     Status::Failed,
@@ -126,40 +164,21 @@ const ALL_STATUSES: &[Status] = &[
     Status::Reprocessing,
 ];
 
-/// Gets all deposit entries modified from (on or after) a given height.
-pub async fn get_all_deposit_entries_modified_from_height(
-    context: &EmilyContext,
-    minimum_height: u64,
-    maybe_page_size: Option<u16>,
-) -> Result<Vec<DepositInfoEntry>, Error> {
-    let mut all = Vec::new();
-    for status in ALL_STATUSES {
-        let mut received = get_all_deposit_entries_modified_from_height_with_status(
-            context,
-            status,
-            minimum_height,
-            maybe_page_size,
-        )
-        .await?;
-        all.append(&mut received);
-    }
-    // Return.
-    Ok(all)
-}
-
-/// Gets all deposit entries modified from (on or after) a given height.
-pub async fn get_all_deposit_entries_modified_from_height_with_status(
+/// Gets a page of deposit entries with the given status modified from (on
+/// or after) a given height.
+pub async fn get_deposit_entries_modified_from_height_with_status(
     context: &EmilyContext,
     status: &Status,
     minimum_height: u64,
+    maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
-) -> Result<Vec<DepositInfoEntry>, Error> {
-    // Make the query.
-    query_all_with_partition_and_sort_key::<DepositTableSecondaryIndex>(
+) -> Result<(Vec<DepositInfoEntry>, Option<String>), Error> {
+    query_with_partition_and_sort_key::<DepositTableSecondaryIndex>(
         context,
         status,
         &minimum_height,
         ">=",
+        maybe_next_token,
         maybe_page_size,
     )
     .await
@@ -182,7 +201,8 @@ pub async fn get_deposit_entries_for_transaction(
 }
 
 /// Pulls in a deposit entry and then updates it, retrying the specified number
-/// of times when there's a version conflict.
+/// of times with exponential backoff when there's a version conflict.
+/// Alongside the entry it returns whether the update changed its status.
 ///
 /// An untusted key can only update pending deposits.
 ///
@@ -192,13 +212,14 @@ pub async fn pull_and_update_deposit_with_retry(
     update: ValidatedDepositUpdate,
     retries: u16,
     is_trusted_key: bool,
-) -> Result<DepositEntry, Error> {
-    for _ in 0..retries {
+) -> Result<(DepositEntry, bool), Error> {
+    let update = &update;
+    retry_on_version_conflict(retries, || async move {
         // Get original deposit entry.
         let deposit_entry = get_deposit_entry(context, &update.key).await?;
         // Return the existing entry if no update is necessary.
         if update.is_unnecessary(&deposit_entry) {
-            return Ok(deposit_entry);
+            return Ok((deposit_entry, false));
         }
         if !is_trusted_key && deposit_entry.status != Status::Pending {
             return Err(Error::Forbidden);
@@ -206,19 +227,22 @@ pub async fn pull_and_update_deposit_with_retry(
         // Make the update package.
         let update_package: DepositUpdatePackage =
             DepositUpdatePackage::try_from(&deposit_entry, update.clone())?;
-        // Attempt to update the deposit.
-        match update_deposit(context, &update_package).await {
-            Err(Error::VersionConflict) => {
-                // Retry.
-                continue;
-            }
-            otherwise => {
-                return otherwise;
-            }
+        // Rewrite the whole entry if the new event makes the history too long.
+        let previous_status = deposit_entry.status.clone();
+        let mut updated_entry = deposit_entry;
+        updated_entry.history.push(update_package.event.clone());
+        if updated_entry.compact_history(context.settings.max_history_events) {
+            updated_entry.synchronize_with_history()?;
+            set_deposit_entry(context, &mut updated_entry).await?;
+            let status_changed = updated_entry.status != previous_status;
+            return Ok((updated_entry, status_changed));
         }
-    }
-    // Failed to update due to a version conflict
-    Err(Error::VersionConflict)
+        // Attempt to update the deposit.
+        let updated_entry = update_deposit(context, &update_package).await?;
+        let status_changed = updated_entry.status != previous_status;
+        Ok((updated_entry, status_changed))
+    })
+    .await
 }
 
 /// Updates a deposit.
@@ -310,7 +334,7 @@ pub async fn get_withdrawal_entry(
     .await?;
     // Return.
     match entries.as_slice() {
-        [] => Err(Error::NotFound),
+        [] => Err(Error::ItemNotFound),
         [withdrawal] =>
         {
             #[cfg(feature = "testing")]
@@ -376,25 +400,24 @@ pub async fn get_withdrawal_entries_by_sender(
     .await
 }
 
-/// Gets all withdrawal entries modified from (on or after) a given height.
-pub async fn get_all_withdrawal_entries_modified_from_height(
+/// Gets a page of withdrawal entries with the given status modified from
+/// (on or after) a given height.
+pub async fn get_withdrawal_entries_modified_from_height_with_status(
     context: &EmilyContext,
+    status: &Status,
     minimum_height: u64,
+    maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
-) -> Result<Vec<WithdrawalInfoEntry>, Error> {
-    let mut all = Vec::new();
-    for status in ALL_STATUSES {
-        let mut received = get_all_withdrawal_entries_modified_from_height_with_status(
-            context,
-            status,
-            minimum_height,
-            maybe_page_size,
-        )
-        .await?;
-        all.append(&mut received);
-    }
-    // Return.
-    Ok(all)
+) -> Result<(Vec<WithdrawalInfoEntry>, Option<String>), Error> {
+    query_with_partition_and_sort_key::<WithdrawalTableSecondaryIndex>(
+        context,
+        status,
+        &minimum_height,
+        ">=",
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
 }
 
 /// Gets all withdrawal entries modified from (on or after) a given height.
@@ -416,7 +439,8 @@ pub async fn get_all_withdrawal_entries_modified_from_height_with_status(
 }
 
 /// Pulls in a withdrawal entry and then updates it, retrying the specified number
-/// of times when there's a version conflict.
+/// of times with exponential backoff when there's a version conflict.
+/// Alongside the entry it returns whether the update changed its status.
 ///
 /// An untusted key can only update pending withdrawals.
 ///
@@ -426,13 +450,14 @@ pub async fn pull_and_update_withdrawal_with_retry(
     update: ValidatedWithdrawalUpdate,
     retries: u16,
     is_trusted_key: bool,
-) -> Result<WithdrawalEntry, Error> {
-    for _ in 0..retries {
+) -> Result<(WithdrawalEntry, bool), Error> {
+    let update = &update;
+    retry_on_version_conflict(retries, || async move {
         // Get original withdrawal entry.
         let entry = get_withdrawal_entry(context, &update.request_id).await?;
         // Return the existing entry if no update is necessary.
         if update.is_unnecessary(&entry) {
-            return Ok(entry);
+            return Ok((entry, false));
         }
 
         if !is_trusted_key && entry.status != Status::Pending {
@@ -441,19 +466,22 @@ pub async fn pull_and_update_withdrawal_with_retry(
 
         // Make the update package.
         let update_package = WithdrawalUpdatePackage::try_from(&entry, update.clone())?;
-        // Attempt to update the withdrawal.
-        match update_withdrawal(context, &update_package).await {
-            Err(Error::VersionConflict) => {
-                // Retry.
-                continue;
-            }
-            otherwise => {
-                return otherwise;
-            }
+        // Rewrite the whole entry if the new event makes the history too long.
+        let previous_status = entry.status.clone();
+        let mut updated_entry = entry;
+        updated_entry.history.push(update_package.event.clone());
+        if updated_entry.compact_history(context.settings.max_history_events) {
+            updated_entry.synchronize_with_history()?;
+            set_withdrawal_entry(context, &mut updated_entry).await?;
+            let status_changed = updated_entry.status != previous_status;
+            return Ok((updated_entry, status_changed));
         }
-    }
-    // Failed to update due to a version conflict
-    Err(Error::VersionConflict)
+        // Attempt to update the withdrawal.
+        let updated_entry = update_withdrawal(context, &update_package).await?;
+        let status_changed = updated_entry.status != previous_status;
+        Ok((updated_entry, status_changed))
+    })
+    .await
 }
 
 /// Updates a withdrawal based on the update package.
@@ -513,24 +541,19 @@ pub async fn update_withdrawal(
 
 // Chainstate ------------------------------------------------------------------
 
-/// Adds a chainstate entry to the database with the specified number of retries.
+/// Adds a chainstate entry to the database with the specified number of retries,
+/// backing off exponentially between them.
 pub async fn add_chainstate_entry_with_retry(
     context: &EmilyContext,
     entry: &ChainstateEntry,
     retries: u16,
 ) -> Result<(), Error> {
-    for _ in 0..retries {
-        match add_chainstate_entry(context, entry).await {
-            Err(Error::VersionConflict) => {
-                // Retry.
-                continue;
-            }
-            otherwise => {
-                return otherwise;
-            }
-        }
-    }
-    Err(Error::TooManyInternalRetries)
+    retry_on_version_conflict(retries, || add_chainstate_entry(context, entry))
+        .await
+        .map_err(|error| match error {
+            Error::VersionConflict => Error::TooManyInternalRetries,
+            error => error,
+        })
 }
 
 /// Add a chainstate entry.
@@ -567,9 +590,10 @@ pub async fn add_chainstate_entry(
                 }
             });
 
+    let mut superseded_hash = None;
     match current_chainstate_entry_result {
         // Fall through if there is no existing entry..
-        Err(Error::NotFound) => (),
+        Err(Error::ItemNotFound) => (),
         // If the chainstate entry is already in the table but the api believes the chaintip is behind
         // this entry, that means a reorg has occurred and the api got pulled back, but then it went
         // back to the chain it had been following before the reorg. This is a stable state, and we
@@ -592,6 +616,7 @@ pub async fn add_chainstate_entry(
                 // Remove the entry from the table.
                 let existing_entry: ChainstateEntry = chainstate.into();
                 delete_entry::<ChainstateTablePrimaryIndex>(context, &existing_entry.key).await?;
+                superseded_hash.get_or_insert(existing_entry.key.hash);
             }
         }
         // ..otherwise exit here.
@@ -600,6 +625,13 @@ pub async fn add_chainstate_entry(
         }
     };
 
+    // Record the hash this entry replaced so that the history shows where the
+    // chain was reorganized.
+    let entry = &ChainstateEntry {
+        superseded_hash: superseded_hash.or_else(|| entry.superseded_hash.clone()),
+        ..entry.clone()
+    };
+
     let chaintip: ChainstateEntry = api_state.chaintip();
     let blocks_higher_than_current_tip = (entry.key.height as i128) - (chaintip.key.height as i128);
     if blocks_higher_than_current_tip == 1 || chaintip.key.height == 0 {
@@ -636,6 +668,30 @@ pub async fn add_chainstate_entry(
     }
 }
 
+/// Writes the given chainstate entries in batches and makes the last of
+/// them the chain tip of the given API state. The entries must already be
+/// known to extend the chain tip of that API state one height at a time.
+///
+/// Fails with `Error::VersionConflict` if the API state changed since it
+/// was read, in which case the entries are left in the table above the
+/// chain tip and get picked up again when they are submitted one by one.
+/// Entries that a reorg left above the chain tip are not cleaned up, so
+/// this is meant for backfilling the chainstate table.
+pub async fn extend_chainstate_entries(
+    context: &EmilyContext,
+    mut api_state: ApiStateEntry,
+    entries: &[ChainstateEntry],
+) -> Result<ChainstateEntry, Error> {
+    api_state.error_if_reorganizing()?;
+    let Some(chaintip) = entries.last() else {
+        return Ok(api_state.chaintip());
+    };
+    put_entries::<ChainstateTablePrimaryIndex>(context, entries).await?;
+    api_state.api_status = ApiStatus::Stable(chaintip.clone());
+    set_api_state(context, &api_state).await?;
+    Ok(chaintip.clone())
+}
+
 /// Gets the chainstate at the given height, and provides a conflict error
 /// if there's a conflict.
 pub async fn get_chainstate_entry_at_height(
@@ -648,12 +704,26 @@ pub async fn get_chainstate_entry_at_height(
     // If there are multiple entries at this height report an inconsistent state
     // error.
     match entries.as_slice() {
-        [] => Err(Error::NotFound),
+        [] => Err(Error::ItemNotFound),
         [single_entry] => Ok(single_entry.clone()),
         [_, ..] => Err(Error::from_inconsistent_chainstate_entries(entries)),
     }
 }
 
+/// Looks up the chainstate at the given height, returning `None` if there
+/// is none. Like [`get_chainstate_entry_at_height`], this reports an
+/// inconsistent state error if there are multiple entries at the height.
+pub async fn get_chainstate_at_height(
+    context: &EmilyContext,
+    height: u64,
+) -> Result<Option<ChainstateEntry>, Error> {
+    match get_chainstate_entry_at_height(context, &height).await {
+        Ok(entry) => Ok(Some(entry)),
+        Err(Error::ItemNotFound) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
 /// Get all chainstate entries for a given height.
 /// Note that there should only really be one.
 pub async fn get_chainstate_entries_for_height(
@@ -671,6 +741,60 @@ pub async fn get_chainstate_entries_for_height(
     .await
 }
 
+/// Makes the given entry the only chainstate entry at its height, deleting
+/// any conflicting entries and recording the hash of the one it replaced.
+pub async fn replace_chainstate_entries_at_height(
+    context: &EmilyContext,
+    entry: &ChainstateEntry,
+) -> Result<(), Error> {
+    let (existing_entries, _) =
+        get_chainstate_entries_for_height(context, &entry.key.height, None, None).await?;
+    let (conflicting_entries, matching_entries): (Vec<_>, Vec<_>) = existing_entries
+        .into_iter()
+        .partition(|existing_entry| existing_entry.key != entry.key);
+    // Nothing to do if the entry is already the only one at this height.
+    if conflicting_entries.is_empty() && !matching_entries.is_empty() {
+        return Ok(());
+    }
+    for conflicting_entry in conflicting_entries.iter() {
+        delete_entry::<ChainstateTablePrimaryIndex>(context, &conflicting_entry.key).await?;
+    }
+    let entry = ChainstateEntry {
+        superseded_hash: conflicting_entries
+            .into_iter()
+            .next()
+            .map(|conflicting_entry| conflicting_entry.key.hash)
+            .or_else(|| entry.superseded_hash.clone()),
+        ..entry.clone()
+    };
+    put_entry::<ChainstateTablePrimaryIndex>(context, &entry).await
+}
+
+/// Gets up to `limit` chainstate entries, walking heights downward from the
+/// current chain tip. The walk stops early at the first height without any
+/// recorded chainstate.
+pub async fn get_chainstate_history(
+    context: &EmilyContext,
+    limit: u16,
+) -> Result<Vec<ChainstateEntry>, Error> {
+    let limit = limit as usize;
+    let mut height = get_api_state(context).await?.chaintip().key.height;
+    let mut history: Vec<ChainstateEntry> = Vec::with_capacity(limit);
+    while history.len() < limit {
+        let (entries, _) = get_chainstate_entries_for_height(context, &height, None, None).await?;
+        if entries.is_empty() {
+            break;
+        }
+        history.extend(entries);
+        match height.checked_sub(1) {
+            Some(next_height) => height = next_height,
+            None => break,
+        }
+    }
+    history.truncate(limit);
+    Ok(history)
+}
+
 /// Gets the state of the API.
 pub async fn get_api_state(context: &EmilyContext) -> Result<ApiStateEntry, Error> {
     let get_api_state_result =
@@ -679,7 +803,7 @@ pub async fn get_api_state(context: &EmilyContext) -> Result<ApiStateEntry, Erro
         // If the API state wasn't found then initialize it into the table.
         // TODO(390): Handle any race conditions with the version field in case
         // the entry was initialized and then updated after creation.
-        Err(Error::NotFound) => {
+        Err(Error::ItemNotFound) => {
             let initial_api_state_entry = ApiStateEntry::default();
             put_entry::<SpecialApiStateIndex>(context, &initial_api_state_entry).await?;
             Ok(initial_api_state_entry)
@@ -712,12 +836,12 @@ async fn get_oldest_stacks_block_for_bitcoin_block(
     .iter()
     .min_by_key(|entry| entry.key.stacks_block_height)
     .map(|entry| entry.key.stacks_block_height)
-    .ok_or(Error::NotFound)
+    .ok_or(Error::ItemNotFound)
 }
 
 // Returns oldest stacks block height, ancored to some block in range
 // [range_start_bitcoin_height; range_end_bitcoin_height] (both sides inclusive)
-// If no stacks block is found, returns Error::NotFound.
+// If no stacks block is found, returns Error::ItemNotFound.
 async fn get_oldest_stacks_block_in_range(
     context: &EmilyContext,
     range_start_bitcoin_height: u64,
@@ -730,7 +854,20 @@ async fn get_oldest_stacks_block_in_range(
             return Ok(stacks_height);
         }
     }
-    Err(Error::NotFound)
+    Err(Error::ItemNotFound)
+}
+
+/// Returns the oldest stacks block height within the rolling window that
+/// spans the given number of bitcoin blocks up to and including the
+/// bitcoin chain tip.
+async fn get_rolling_window_start_height(
+    context: &EmilyContext,
+    rolling_blocks: u64,
+) -> Result<u64, Error> {
+    let chaintip = get_api_state(context).await?.chaintip();
+    let bitcoin_tip = chaintip.bitcoin_height.ok_or(Error::ItemNotFound)?;
+    let bitcoin_end_block = bitcoin_tip.saturating_sub(rolling_blocks.saturating_sub(1));
+    get_oldest_stacks_block_in_range(context, bitcoin_end_block, bitcoin_tip).await
 }
 
 async fn calculate_sbtc_left_for_withdrawals(
@@ -744,12 +881,8 @@ async fn calculate_sbtc_left_for_withdrawals(
         // Note that we validate in set_limits that these values are both Some or both None
         return Ok(None);
     };
-    let chaintip = get_api_state(context).await?.chaintip();
-    let bitcoin_tip = chaintip.bitcoin_height.ok_or(Error::NotFound)?;
-    let bitcoin_end_block = bitcoin_tip.saturating_sub(rolling_withdrawal_blocks.saturating_sub(1));
-
     let minimum_stacks_height_in_window =
-        get_oldest_stacks_block_in_range(context, bitcoin_end_block, bitcoin_tip).await?;
+        get_rolling_window_start_height(context, rolling_withdrawal_blocks).await?;
 
     let all_statuses_except_failed: Vec<_> = ALL_STATUSES
         .iter()
@@ -772,6 +905,46 @@ async fn calculate_sbtc_left_for_withdrawals(
     Ok(Some(rolling_withdrawal_cap.saturating_sub(total_withdrawn)))
 }
 
+/// Gets the total amount of the deposits that were confirmed on or after
+/// the given stacks block height.
+pub async fn get_confirmed_deposit_total_since(
+    context: &EmilyContext,
+    minimum_height: u64,
+) -> Result<u64, Error> {
+    let deposits = query_all_with_partition_and_sort_key::<DepositTableSecondaryIndex>(
+        context,
+        &Status::Confirmed,
+        &minimum_height,
+        ">=",
+        None,
+    )
+    .await?;
+    Ok(deposits
+        .iter()
+        .fold(0u64, |total, deposit| total.saturating_add(deposit.amount)))
+}
+
+/// Calculates the amount of sBTC that can still be minted by deposits
+/// within the rolling deposit window, or `None` if there is no rolling
+/// deposit cap.
+pub async fn calculate_sbtc_left_for_deposits(
+    context: &EmilyContext,
+    rolling_deposit_blocks: Option<u64>,
+    rolling_deposit_cap: Option<u64>,
+) -> Result<Option<u64>, Error> {
+    let (Some(rolling_deposit_blocks), Some(rolling_deposit_cap)) =
+        (rolling_deposit_blocks, rolling_deposit_cap)
+    else {
+        // Note that we validate in set_limits that these values are both Some or both None
+        return Ok(None);
+    };
+    let minimum_stacks_height_in_window =
+        get_rolling_window_start_height(context, rolling_deposit_blocks).await?;
+    let total_deposited =
+        get_confirmed_deposit_total_since(context, minimum_stacks_height_in_window).await?;
+    Ok(Some(rolling_deposit_cap.saturating_sub(total_deposited)))
+}
+
 /// Note, this function provides the direct output structure for the api call
 /// to get the limits for the full sbtc system, and therefore is breaching the
 /// typical contract for these accessor functions. We do this here because the
@@ -798,6 +971,8 @@ pub async fn get_limits(context: &EmilyContext) -> Result<Limits, Error> {
         per_withdrawal_cap: default_global_cap.per_withdrawal_cap,
         rolling_withdrawal_blocks: default_global_cap.rolling_withdrawal_blocks,
         rolling_withdrawal_cap: default_global_cap.rolling_withdrawal_cap,
+        rolling_deposit_blocks: default_global_cap.rolling_deposit_blocks,
+        rolling_deposit_cap: default_global_cap.rolling_deposit_cap,
     };
 
     // Aggregate all the latest entries by account.
@@ -840,6 +1015,14 @@ pub async fn get_limits(context: &EmilyContext) -> Result<Limits, Error> {
     )
     .await?;
 
+    // Calculate the amount still available for deposits.
+    let available_to_deposit = calculate_sbtc_left_for_deposits(
+        context,
+        global_cap.rolling_deposit_blocks,
+        global_cap.rolling_deposit_cap,
+    )
+    .await?;
+
     // Get the global limit for the whole thing.
     Ok(Limits {
         available_to_withdraw,
@@ -849,10 +1032,23 @@ pub async fn get_limits(context: &EmilyContext) -> Result<Limits, Error> {
         per_withdrawal_cap: global_cap.per_withdrawal_cap,
         rolling_withdrawal_blocks: global_cap.rolling_withdrawal_blocks,
         rolling_withdrawal_cap: global_cap.rolling_withdrawal_cap,
+        rolling_deposit_blocks: global_cap.rolling_deposit_blocks,
+        rolling_deposit_cap: global_cap.rolling_deposit_cap,
+        available_to_deposit,
         account_caps,
     })
 }
 
+/// Gets the most recently set global limits, falling back to the default
+/// limits from the settings if none were ever set.
+pub async fn get_global_limits(context: &EmilyContext) -> Result<AccountLimits, Error> {
+    match get_limit_for_account(context, &GLOBAL_CAP_ACCOUNT.to_string()).await {
+        Ok(entry) => Ok(entry.into()),
+        Err(Error::ItemNotFound) => Ok(context.settings.default_limits.clone()),
+        Err(error) => Err(error),
+    }
+}
+
 /// Get the limit for a specific account.
 pub async fn get_limit_for_account(
     context: &EmilyContext,
@@ -871,7 +1067,7 @@ pub async fn get_limit_for_account(
     // The limit is set to 1 so there should always only be one entry returned,
     // but for the sake of redundancy also get the most recent entry.
     entries.sort_by_key(|entry| entry.key.timestamp);
-    entries.pop().ok_or(Error::NotFound)
+    entries.pop().ok_or(Error::ItemNotFound)
 }
 
 /// Set the limit for a specific account.
@@ -882,44 +1078,165 @@ pub async fn set_limit_for_account(
     put_entry::<LimitTablePrimaryIndex>(context, limit).await
 }
 
+// Subscriptions ---------------------------------------------------------------
+
+/// Adds a subscription entry, replacing any subscription with the same
+/// operation and URL.
+pub async fn set_subscription_entry(
+    context: &EmilyContext,
+    entry: &SubscriptionEntry,
+) -> Result<(), Error> {
+    put_entry::<SubscriptionTablePrimaryIndex>(context, entry).await
+}
+
+/// Deletes a subscription entry. Fails with `Error::ItemNotFound` if there is
+/// no such subscription.
+pub async fn delete_subscription_entry(
+    context: &EmilyContext,
+    key: &SubscriptionEntryKey,
+) -> Result<(), Error> {
+    get_entry::<SubscriptionTablePrimaryIndex>(context, key).await?;
+    delete_entry::<SubscriptionTablePrimaryIndex>(context, key).await
+}
+
+/// Gets all the subscriptions to the given kind of operation.
+pub async fn get_subscription_entries_for_operation(
+    context: &EmilyContext,
+    operation: &SubscriptionOperation,
+) -> Result<Vec<SubscriptionEntry>, Error> {
+    let mut entries = Vec::new();
+    let mut next_token: Option<String> = None;
+    loop {
+        let (mut page, maybe_next_token) =
+            query_with_partition_key::<SubscriptionTablePrimaryIndex>(
+                context, operation, next_token, None,
+            )
+            .await?;
+        entries.append(&mut page);
+        next_token = maybe_next_token;
+        if next_token.is_none() {
+            return Ok(entries);
+        }
+    }
+}
+
 // Testing ---------------------------------------------------------------------
 
-/// Wipes all the tables.
-/// TODO(395): Include check for whether the table is running locally.
+/// Wipes all the tables, returning the name of each wiped table along with
+/// the number of items deleted from it.
 #[cfg(feature = "testing")]
-pub async fn wipe_all_tables(context: &EmilyContext) -> Result<(), Error> {
-    wipe_deposit_table(context).await?;
-    wipe_withdrawal_table(context).await?;
-    wipe_chainstate_table(context).await?;
-    wipe_limit_table(context).await?;
-    Ok(())
+pub async fn wipe_all_tables(context: &EmilyContext) -> Result<Vec<WipedTable>, Error> {
+    Ok(vec![
+        wipe_deposit_table(context).await?,
+        wipe_withdrawal_table(context).await?,
+        wipe_chainstate_table(context).await?,
+        wipe_limit_table(context).await?,
+        wipe_notification_table(context).await?,
+    ])
 }
 
 /// Wipes the deposit table.
 #[cfg(feature = "testing")]
-async fn wipe_deposit_table(context: &EmilyContext) -> Result<(), Error> {
+async fn wipe_deposit_table(context: &EmilyContext) -> Result<WipedTable, Error> {
     wipe::<DepositTablePrimaryIndex>(context).await
 }
 
 /// Wipes the withdrawal table.
 #[cfg(feature = "testing")]
-async fn wipe_withdrawal_table(context: &EmilyContext) -> Result<(), Error> {
+async fn wipe_withdrawal_table(context: &EmilyContext) -> Result<WipedTable, Error> {
     wipe::<WithdrawalTablePrimaryIndex>(context).await
 }
 
 /// Wipes the chainstate table.
 #[cfg(feature = "testing")]
-async fn wipe_chainstate_table(context: &EmilyContext) -> Result<(), Error> {
+async fn wipe_chainstate_table(context: &EmilyContext) -> Result<WipedTable, Error> {
     delete_entry::<SpecialApiStateIndex>(context, &ApiStateEntry::key()).await?;
     wipe::<ChainstateTablePrimaryIndex>(context).await
 }
 
 /// Wipes the limit table.
 #[cfg(feature = "testing")]
-async fn wipe_limit_table(context: &EmilyContext) -> Result<(), Error> {
+async fn wipe_limit_table(context: &EmilyContext) -> Result<WipedTable, Error> {
     wipe::<LimitTablePrimaryIndex>(context).await
 }
 
+/// Wipes the notification table.
+#[cfg(feature = "testing")]
+async fn wipe_notification_table(context: &EmilyContext) -> Result<WipedTable, Error> {
+    wipe::<SubscriptionTablePrimaryIndex>(context).await
+}
+
+// Optimistic locking ----------------------------------------------------------
+
+/// Delay before the first retry of a write that failed its version check.
+const VERSION_RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+
+/// Longest delay between two retries of a write that failed its version check.
+const VERSION_RETRY_MAX_DELAY: Duration = Duration::from_millis(640);
+
+/// Delay to wait after the zero-based `attempt` failed its version check. The
+/// delay doubles with every attempt until it reaches `VERSION_RETRY_MAX_DELAY`.
+fn version_retry_delay(attempt: u16) -> Duration {
+    VERSION_RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.into()))
+        .min(VERSION_RETRY_MAX_DELAY)
+}
+
+/// Runs `attempt` up to `attempts` times, backing off exponentially after each
+/// attempt that fails with `Error::VersionConflict`. Any other result is returned
+/// as is, and `Error::VersionConflict` is returned if every attempt conflicted.
+pub async fn retry_on_version_conflict<R, F, Fut>(attempts: u16, mut attempt: F) -> Result<R, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R, Error>>,
+{
+    for attempt_number in 0..attempts {
+        if attempt_number > 0 {
+            tokio::time::sleep(version_retry_delay(attempt_number - 1)).await;
+        }
+        match attempt().await {
+            Err(Error::VersionConflict) => {
+                debug!(
+                    "Version conflict on attempt {}/{attempts}; retrying.",
+                    attempt_number + 1
+                );
+            }
+            otherwise => return otherwise,
+        }
+    }
+    Err(Error::VersionConflict)
+}
+
+/// Reads the entry at `key`, applies `update` to it and writes it back with a
+/// version check, re-reading and retrying up to `attempts` times with
+/// exponential backoff when the entry was modified concurrently.
+///
+/// `update` returns whether the entry needs to be written; when it returns
+/// `false` nothing is written and `Ok(None)` is returned. Otherwise the
+/// written entry is returned.
+pub(crate) async fn with_version_retry<T, F>(
+    context: &EmilyContext,
+    key: &<<T as TableIndexTrait>::Entry as EntryTrait>::Key,
+    attempts: u16,
+    update: F,
+) -> Result<Option<<T as TableIndexTrait>::Entry>, Error>
+where
+    T: VersionedTableIndexTrait,
+    <T as TableIndexTrait>::Entry: VersionedEntryTrait,
+    F: Fn(&mut <T as TableIndexTrait>::Entry) -> Result<bool, Error>,
+{
+    let update = &update;
+    retry_on_version_conflict(attempts, || async move {
+        let mut entry = get_entry::<T>(context, key).await?;
+        if !update(&mut entry)? {
+            return Ok(None);
+        }
+        put_entry_with_version::<T>(context, &mut entry).await?;
+        Ok(Some(entry))
+    })
+    .await
+}
+
 // Generics --------------------------------------------------------------------
 
 async fn get_entry<T: TableIndexTrait>(
@@ -929,6 +1246,13 @@ async fn get_entry<T: TableIndexTrait>(
     <T as TableIndexTrait>::get_entry(&context.dynamodb_client, &context.settings, key).await
 }
 
+async fn get_entries<T: TableIndexTrait>(
+    context: &EmilyContext,
+    keys: &[<<T as TableIndexTrait>::Entry as EntryTrait>::Key],
+) -> Result<Vec<<T as TableIndexTrait>::Entry>, Error> {
+    <T as TableIndexTrait>::get_entries(&context.dynamodb_client, &context.settings, keys).await
+}
+
 async fn put_entry<T: TableIndexTrait>(
     context: &EmilyContext,
     entry: &<T as TableIndexTrait>::Entry,
@@ -936,6 +1260,21 @@ async fn put_entry<T: TableIndexTrait>(
     <T as TableIndexTrait>::put_entry(&context.dynamodb_client, &context.settings, entry).await
 }
 
+async fn put_entries<T: TableIndexTrait>(
+    context: &EmilyContext,
+    entries: &[<T as TableIndexTrait>::Entry],
+) -> Result<(), Error> {
+    <T as TableIndexTrait>::put_entries(&context.dynamodb_client, &context.settings, entries).await
+}
+
+async fn put_entry_if_absent<T: TableIndexTrait>(
+    context: &EmilyContext,
+    entry: &<T as TableIndexTrait>::Entry,
+) -> Result<(), Error> {
+    <T as TableIndexTrait>::put_entry_if_absent(&context.dynamodb_client, &context.settings, entry)
+        .await
+}
+
 async fn put_entry_with_version<T: VersionedTableIndexTrait>(
     context: &EmilyContext,
     entry: &mut <T as TableIndexTrait>::Entry,
@@ -974,6 +1313,46 @@ async fn query_with_partition_key<T: TableIndexTrait>(
     .await
 }
 
+async fn query_with_partition_and_sort_key<T: TableIndexTrait>(
+    context: &EmilyContext,
+    partition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+    sort_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+    sort_key_operator: &str,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+) -> Result<(Vec<<T as TableIndexTrait>::Entry>, Option<String>), Error> {
+    <T as TableIndexTrait>::query_with_partition_and_sort_key(
+        &context.dynamodb_client,
+        &context.settings,
+        partition_key,
+        sort_key,
+        sort_key_operator,
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
+}
+
+async fn query_with_partition_key_and_sort_key_between<T: TableIndexTrait>(
+    context: &EmilyContext,
+    partition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+    sort_key_from: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+    sort_key_to: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+) -> Result<(Vec<<T as TableIndexTrait>::Entry>, Option<String>), Error> {
+    <T as TableIndexTrait>::query_with_partition_key_and_sort_key_between(
+        &context.dynamodb_client,
+        &context.settings,
+        partition_key,
+        sort_key_from,
+        sort_key_to,
+        maybe_next_token,
+        maybe_page_size,
+    )
+    .await
+}
+
 async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
     context: &EmilyContext,
     partition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
@@ -988,9 +1367,8 @@ async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
     // Loop over all items.
     loop {
         let mut new_items: Vec<<T as TableIndexTrait>::Entry>;
-        (new_items, next_token) = <T as TableIndexTrait>::query_with_partition_and_sort_key(
-            &context.dynamodb_client,
-            &context.settings,
+        (new_items, next_token) = query_with_partition_and_sort_key::<T>(
+            context,
             partition_key,
             sort_key,
             sort_key_operator,
@@ -1010,8 +1388,222 @@ async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
 }
 
 #[cfg(feature = "testing")]
-async fn wipe<T: TableIndexTrait>(context: &EmilyContext) -> Result<(), Error> {
-    <T as TableIndexTrait>::wipe(&context.dynamodb_client, &context.settings).await
+async fn wipe<T: TableIndexTrait>(context: &EmilyContext) -> Result<WipedTable, Error> {
+    let items_deleted =
+        <T as TableIndexTrait>::wipe(&context.dynamodb_client, &context.settings).await?;
+    Ok(WipedTable {
+        table_name: <T as TableIndexTrait>::table_name(&context.settings).to_string(),
+        items_deleted: items_deleted as u64,
+    })
 }
 
 // TODO(397): Add accessor function unit tests.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    use aws_config::{BehaviorVersion, Region};
+    use aws_sdk_dynamodb::config::Credentials;
+    use clarity::vm::types::PrincipalData;
+    use serde_json::json;
+    use test_case::test_case;
+    use warp::Filter;
+    use warp::http::{Response, StatusCode};
+
+    use crate::context::Settings;
+
+    use super::*;
+
+    /// Request counters of a mocked DynamoDB server.
+    #[derive(Default)]
+    struct MockDynamoDb {
+        /// Number of upcoming `PutItem` requests that fail their condition check.
+        conflicts: AtomicU16,
+        /// Number of `PutItem` requests received.
+        put_requests: AtomicU16,
+    }
+
+    /// Converts an attribute value into the DynamoDB JSON wire format.
+    fn to_wire(value: &AttributeValue) -> serde_json::Value {
+        match value {
+            AttributeValue::S(value) => json!({ "S": value }),
+            AttributeValue::N(value) => json!({ "N": value }),
+            AttributeValue::Bool(value) => json!({ "BOOL": value }),
+            AttributeValue::Null(value) => json!({ "NULL": value }),
+            AttributeValue::L(values) => {
+                json!({ "L": values.iter().map(to_wire).collect::<Vec<_>>() })
+            }
+            AttributeValue::M(values) => json!({ "M": to_wire_item(values) }),
+            value => unimplemented!("unsupported attribute value {value:?}"),
+        }
+    }
+
+    /// Converts an item into the DynamoDB JSON wire format.
+    fn to_wire_item(item: &HashMap<String, AttributeValue>) -> serde_json::Value {
+        item.iter()
+            .map(|(name, value)| (name.clone(), to_wire(value)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    /// Starts a mocked DynamoDB server that returns the default api state
+    /// entry for every `GetItem` request and fails the condition check of the
+    /// first `conflicts` `PutItem` requests, and returns a context pointing
+    /// at it.
+    async fn mock_dynamodb(conflicts: u16) -> (EmilyContext, Arc<MockDynamoDb>) {
+        let mock = Arc::new(MockDynamoDb {
+            conflicts: AtomicU16::new(conflicts),
+            ..Default::default()
+        });
+        let item: Item = serde_dynamo::to_item(ApiStateEntry::default()).unwrap();
+        let item: HashMap<String, AttributeValue> = item.into();
+        let get_item_response = json!({ "Item": to_wire_item(&item) }).to_string();
+
+        let state = mock.clone();
+        let route = warp::post()
+            .and(warp::header::<String>("x-amz-target"))
+            .map(move |target: String| {
+                let (status, body) = match target.as_str() {
+                    "DynamoDB_20120810.GetItem" => (StatusCode::OK, get_item_response.clone()),
+                    "DynamoDB_20120810.PutItem" => {
+                        state.put_requests.fetch_add(1, Ordering::SeqCst);
+                        let conflict = state
+                            .conflicts
+                            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                            .is_ok();
+                        if conflict {
+                            let error = json!({
+                                "__type": "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException",
+                                "message": "The conditional request failed",
+                            });
+                            (StatusCode::BAD_REQUEST, error.to_string())
+                        } else {
+                            (StatusCode::OK, "{}".to_string())
+                        }
+                    }
+                    target => panic!("unexpected DynamoDB request {target}"),
+                };
+                Response::builder()
+                    .status(status)
+                    .header("content-type", "application/x-amz-json-1.0")
+                    .body(body)
+                    .unwrap()
+            });
+        let (address, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .endpoint_url(format!("http://{address}"))
+            .region(Region::new("us-west-2"))
+            .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+            .load()
+            .await;
+        let context = EmilyContext {
+            settings: Settings {
+                is_local: true,
+                deposit_table_name: "DepositTable".to_string(),
+                withdrawal_table_name: "WithdrawalTable".to_string(),
+                chainstate_table_name: "ChainstateTable".to_string(),
+                limit_table_name: "LimitTable".to_string(),
+                notification_table_name: "NotificationTable".to_string(),
+                default_limits: AccountLimits::default(),
+                trusted_reorg_api_key: "testApiKey".to_string(),
+                api_keys: HashMap::new(),
+                max_history_events: 100,
+                max_chainstate_gap: 100,
+                is_mainnet: false,
+                version: "test".to_string(),
+                deployer_address: PrincipalData::parse_standard_principal(
+                    "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS",
+                )
+                .unwrap(),
+                deployment_environment: "test".to_string(),
+                enable_testing_routes: false,
+            },
+            dynamodb_client: aws_sdk_dynamodb::Client::new(&config),
+        };
+        (context, mock)
+    }
+
+    /// Updates the api state with `with_version_retry`, always writing it.
+    async fn bump_api_state(
+        context: &EmilyContext,
+        attempts: u16,
+    ) -> Result<Option<ApiStateEntry>, Error> {
+        with_version_retry::<SpecialApiStateIndex, _>(
+            context,
+            &ApiStateEntry::key(),
+            attempts,
+            |_| Ok(true),
+        )
+        .await
+    }
+
+    #[test_case(0; "without conflicts")]
+    #[test_case(1; "after one conflict")]
+    #[test_case(4; "on the last attempt")]
+    #[tokio::test]
+    async fn with_version_retry_writes_after_conflicts(conflicts: u16) {
+        let (context, mock) = mock_dynamodb(conflicts).await;
+
+        let entry = bump_api_state(&context, 5).await.unwrap().unwrap();
+
+        assert_eq!(entry.version, ApiStateEntry::default().version + 1);
+        assert_eq!(mock.put_requests.load(Ordering::SeqCst), conflicts + 1);
+    }
+
+    #[tokio::test]
+    async fn with_version_retry_gives_up_after_all_attempts_conflict() {
+        let (context, mock) = mock_dynamodb(u16::MAX).await;
+
+        let result = bump_api_state(&context, 3).await;
+
+        assert!(matches!(result, Err(Error::VersionConflict)));
+        assert_eq!(mock.put_requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn with_version_retry_skips_unneeded_writes() {
+        let (context, mock) = mock_dynamodb(0).await;
+
+        let result = with_version_retry::<SpecialApiStateIndex, _>(
+            &context,
+            &ApiStateEntry::key(),
+            5,
+            |_| Ok(false),
+        )
+        .await;
+
+        assert!(matches!(result, Ok(None)));
+        assert_eq!(mock.put_requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn with_version_retry_does_not_retry_other_errors() {
+        let (context, mock) = mock_dynamodb(0).await;
+
+        let result = with_version_retry::<SpecialApiStateIndex, _>(
+            &context,
+            &ApiStateEntry::key(),
+            5,
+            |_| Err(Error::Forbidden),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Forbidden)));
+        assert_eq!(mock.put_requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test_case(0, 10; "first retry")]
+    #[test_case(1, 20; "doubles")]
+    #[test_case(6, 640; "reaches the cap")]
+    #[test_case(u16::MAX, 640; "stays at the cap")]
+    fn version_retry_delay_backs_off_exponentially(attempt: u16, expected_millis: u64) {
+        assert_eq!(
+            version_retry_delay(attempt),
+            Duration::from_millis(expected_millis)
+        );
+    }
+}
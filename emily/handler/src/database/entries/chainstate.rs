@@ -4,7 +4,10 @@ use std::hash::Hash;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{api::models::chainstate::Chainstate, common::error::Error};
+use crate::{
+    api::models::chainstate::{Chainstate, ChainstateHistoryEntry},
+    common::error::Error,
+};
 
 use super::{
     EntryTrait, KeyTrait, PrimaryIndex, PrimaryIndexTrait, SecondaryIndex, SecondaryIndexTrait,
@@ -32,6 +35,10 @@ pub struct ChainstateEntry {
     pub key: ChainstateEntryKey,
     /// Bitcoin block height
     pub bitcoin_height: Option<u64>,
+    /// Hash of the conflicting block that this entry replaced at the same
+    /// height, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_hash: Option<String>,
 }
 
 /// Convert from entry to its corresponding chainstate.
@@ -54,6 +61,19 @@ impl From<Chainstate> for ChainstateEntry {
                 height: chainstate_entry.stacks_block_height,
             },
             bitcoin_height: chainstate_entry.bitcoin_block_height,
+            superseded_hash: None,
+        }
+    }
+}
+
+/// Convert from entry to its corresponding chainstate history entry.
+impl From<ChainstateEntry> for ChainstateHistoryEntry {
+    fn from(chainstate_entry: ChainstateEntry) -> Self {
+        ChainstateHistoryEntry {
+            stacks_block_hash: chainstate_entry.key.hash,
+            stacks_block_height: chainstate_entry.key.height,
+            bitcoin_block_height: chainstate_entry.bitcoin_height,
+            superseded_hash: chainstate_entry.superseded_hash,
         }
     }
 }
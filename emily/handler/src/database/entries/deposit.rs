@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     api::models::{
         chainstate::Chainstate,
-        common::{Fulfillment, Status},
+        common::{Fulfillment, Status, StatusHistoryEntry},
         deposit::{Deposit, DepositInfo, DepositParameters},
     },
     common::error::{Error, Inconsistency},
@@ -132,6 +132,11 @@ impl DepositEntry {
         let latest_event: &DepositEvent = self.history.last().ok_or(Error::Debug(format!(
             "Failed getting the last history element for deposit. {stringy_self:?}"
         )))?;
+        if latest_event.status == StatusEntry::Collapsed {
+            return Err(Error::Debug(format!(
+                "latest history element is a collapsed range of events. {stringy_self:?}"
+            )));
+        }
 
         // Verify that the latest event is the current one shown in the entry.
         if self.last_update_block_hash != latest_event.stacks_block_hash {
@@ -152,12 +157,79 @@ impl DepositEntry {
         Ok(())
     }
 
+    /// Returns the names of the fields that are fixed when the deposit is
+    /// created and that differ between `self` and `other`. Mutable fields
+    /// like the status and history are ignored.
+    pub fn conflicting_fields(&self, other: &DepositEntry) -> Vec<&'static str> {
+        let mut conflicts = Vec::new();
+        if self.recipient != other.recipient {
+            conflicts.push("recipient");
+        }
+        if self.amount != other.amount {
+            conflicts.push("amount");
+        }
+        if self.parameters.max_fee != other.parameters.max_fee {
+            conflicts.push("maxFee");
+        }
+        if self.parameters.lock_time != other.parameters.lock_time {
+            conflicts.push("lockTime");
+        }
+        if self.reclaim_script != other.reclaim_script {
+            conflicts.push("reclaimScript");
+        }
+        if self.deposit_script != other.deposit_script {
+            conflicts.push("depositScript");
+        }
+        conflicts
+    }
+
     /// Gets the latest event.
     pub fn latest_event(&self) -> Result<&DepositEvent, Error> {
-        self.history.last().ok_or(Error::Debug(format!(
+        let latest_event = self.history.last().ok_or(Error::Debug(format!(
             "Deposit entry must always have at least one event, but entry with id {:?} did not.",
             self.key(),
-        )))
+        )))?;
+        if latest_event.status == StatusEntry::Collapsed {
+            return Err(Error::Debug(format!(
+                "The latest event of deposit entry with id {:?} is a collapsed range of events.",
+                self.key(),
+            )));
+        }
+        Ok(latest_event)
+    }
+
+    /// Compacts the history so that it holds the first event, a single
+    /// `Collapsed` event in place of the dropped events, and the
+    /// `max_recent_events` most recent events. The collapsed event has the
+    /// height and hash of the earliest dropped event, so the history stays in
+    /// chronological order. Returns whether any events were dropped.
+    pub fn compact_history(&mut self, max_recent_events: usize) -> bool {
+        // Keep at least one recent event so that the latest event is never
+        // the collapsed one.
+        let max_recent_events = max_recent_events.max(1);
+        let already_collapsed = self
+            .history
+            .get(1)
+            .is_some_and(|event| event.status == StatusEntry::Collapsed);
+        let kept_prefix_length = if already_collapsed { 2 } else { 1 };
+        let history_length = self.history.len();
+        if history_length <= kept_prefix_length + max_recent_events {
+            return false;
+        }
+        let recent_events = self.history.split_off(history_length - max_recent_events);
+        // Everything between the first event and the recent events is dropped,
+        // and only the earliest of the dropped events is needed.
+        let earliest_dropped_event = self.history.swap_remove(1);
+        self.history.truncate(1);
+        self.history.push(DepositEvent {
+            status: StatusEntry::Collapsed,
+            message: "Older events collapsed to limit the history size.".to_string(),
+            stacks_block_height: earliest_dropped_event.stacks_block_height,
+            stacks_block_hash: earliest_dropped_event.stacks_block_hash,
+            updated_by: None,
+        });
+        self.history.extend(recent_events);
+        true
     }
 
     /// Reorgs around a given chainstate.
@@ -171,14 +243,20 @@ impl DepositEntry {
                 || ((chainstate.stacks_block_height == event.stacks_block_height)
                     && (chainstate.stacks_block_hash == event.stacks_block_hash))
         });
-        // If the history is empty, just say that the deposit is pending again where its
+        // If the history is empty, or the reorg reached into a collapsed range of events
+        // whose statuses are unknown, just say that the deposit is pending again where its
         // latest update is the point at which the reorg happened.
-        if self.history.is_empty() {
+        let reached_collapsed_events = self
+            .history
+            .last()
+            .is_some_and(|event| event.status == StatusEntry::Collapsed);
+        if self.history.is_empty() || reached_collapsed_events {
             self.history = vec![DepositEvent {
                 status: StatusEntry::Pending,
                 message: "Reprocessing deposit status after reorg.".to_string(),
                 stacks_block_height: chainstate.stacks_block_height,
                 stacks_block_hash: chainstate.stacks_block_hash.clone(),
+                updated_by: None,
             }]
         }
         // Synchronize self with the new history.
@@ -285,16 +363,35 @@ pub struct DepositEvent {
     pub stacks_block_height: u64,
     /// Stacks block hash associated with the height of this update.
     pub stacks_block_hash: String,
+    /// Public key of the signer that made this update, if it was made by
+    /// a signer that identified itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+}
+
+impl From<DepositEvent> for StatusHistoryEntry {
+    fn from(event: DepositEvent) -> Self {
+        StatusHistoryEntry {
+            status: (&event.status).into(),
+            message: event.message,
+            stacks_block_height: event.stacks_block_height,
+            stacks_block_hash: event.stacks_block_hash,
+            updated_by: event.updated_by,
+        }
+    }
 }
 
 /// Implementation of deposit event.
 impl DepositEvent {
     /// Errors if the next event provided could not follow the current one.
     pub fn ensure_following_event_is_valid(&self, next_event: &DepositEvent) -> Result<(), Error> {
+        let latest_updater = self.updated_by.as_deref().unwrap_or("unknown");
+        let next_updater = next_event.updated_by.as_deref().unwrap_or("unknown");
         // Determine if event is valid.
         if self.stacks_block_height > next_event.stacks_block_height {
             let err_msg = format!(
                 "Attempting to update a deposit with a block height earlier than it should be.\n
+                latest update by {latest_updater}, conflicting update by {next_updater}\n
                 latest_existing_event:\n{self:?}\n
                 newest_event:\n{next_event:?}"
             );
@@ -304,6 +401,7 @@ impl DepositEvent {
         {
             let err_msg = format!(
                 "Attempting to update a deposit with a block height and hash that conflicts with its current history.\n
+                latest update by {latest_updater}, conflicting update by {next_updater}\n
                 latest_existing_event:\n{self:?}\n
                 newest_event:\n{next_event:?}"
             );
@@ -708,6 +806,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 0,
             stacks_block_hash: "".to_string(),
+            updated_by: None,
         };
 
         let accepted = DepositEvent {
@@ -715,6 +814,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 1,
             stacks_block_hash: "".to_string(),
+            updated_by: None,
         };
 
         let deposit = DepositEntry {
@@ -748,6 +848,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 0,
             stacks_block_hash: "".to_string(),
+            updated_by: None,
         };
 
         let accepted = DepositEvent {
@@ -755,6 +856,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 1,
             stacks_block_hash: "".to_string(),
+            updated_by: None,
         };
 
         let deposit = DepositEntry {
@@ -798,6 +900,7 @@ mod tests {
             message: "initial test pending".to_string(),
             stacks_block_height: 2,
             stacks_block_hash: "hash2".to_string(),
+            updated_by: None,
         };
 
         let accepted = DepositEvent {
@@ -805,6 +908,7 @@ mod tests {
             message: "accepted".to_string(),
             stacks_block_height: 4,
             stacks_block_hash: "hash4".to_string(),
+            updated_by: None,
         };
 
         let fulfillment: Fulfillment = Default::default();
@@ -813,6 +917,7 @@ mod tests {
             message: "confirmed".to_string(),
             stacks_block_height: 6,
             stacks_block_hash: "hash6".to_string(),
+            updated_by: None,
         };
 
         let mut deposit = DepositEntry {
@@ -864,4 +969,193 @@ mod tests {
         assert_eq!(latest_event.stacks_block_hash, expected_hash);
         assert_eq!(latest_event.status, expected_status);
     }
+
+    #[test]
+    fn conflicting_fields_ignore_mutable_fields() {
+        let deposit = DepositEntry {
+            recipient: "recipient".to_string(),
+            amount: 1000,
+            reclaim_script: "reclaim".to_string(),
+            deposit_script: "deposit".to_string(),
+            ..Default::default()
+        };
+        let updated = DepositEntry {
+            status: Status::Confirmed,
+            last_update_height: 10,
+            history: vec![DepositEvent::default()],
+            version: 3,
+            ..deposit.clone()
+        };
+        assert!(deposit.conflicting_fields(&updated).is_empty());
+    }
+
+    #[test]
+    fn conflicting_fields_lists_immutable_fields() {
+        let deposit = DepositEntry {
+            amount: 1000,
+            ..Default::default()
+        };
+        let conflicting = DepositEntry {
+            amount: 2000,
+            parameters: DepositParametersEntry { max_fee: 1, lock_time: 0 },
+            ..deposit.clone()
+        };
+        assert_eq!(
+            deposit.conflicting_fields(&conflicting),
+            vec!["amount", "maxFee"]
+        );
+    }
+
+    #[test_case(5, "hash5-1"; "same height with a different hash")]
+    #[test_case(4, "hash4"; "earlier height")]
+    fn conflicting_update_error_names_both_signers(height: u64, hash: &str) {
+        let latest = DepositEvent {
+            status: StatusEntry::Confirmed(Fulfillment::default()),
+            message: "confirmed".to_string(),
+            stacks_block_height: 5,
+            stacks_block_hash: "hash5".to_string(),
+            updated_by: Some("signer-a".to_string()),
+        };
+        let conflicting = DepositEvent {
+            status: StatusEntry::Failed,
+            message: "failed".to_string(),
+            stacks_block_height: height,
+            stacks_block_hash: hash.to_string(),
+            updated_by: Some("signer-b".to_string()),
+        };
+
+        let error = latest
+            .ensure_following_event_is_valid(&conflicting)
+            .unwrap_err();
+
+        let Error::InconsistentState(Inconsistency::ItemUpdate(message)) = error else {
+            panic!("expected an item update inconsistency, got {error:?}");
+        };
+        assert!(message.contains("latest update by signer-a"), "{message}");
+        assert!(
+            message.contains("conflicting update by signer-b"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn attributed_events_keep_their_signer_in_the_history() {
+        let accepted = DepositEvent {
+            status: StatusEntry::Accepted,
+            message: "accepted".to_string(),
+            stacks_block_height: 4,
+            stacks_block_hash: "hash4".to_string(),
+            updated_by: Some("signer-a".to_string()),
+        };
+        let failed = DepositEvent {
+            status: StatusEntry::Failed,
+            message: "failed".to_string(),
+            stacks_block_height: 5,
+            stacks_block_hash: "hash5".to_string(),
+            updated_by: Some("signer-b".to_string()),
+        };
+        accepted.ensure_following_event_is_valid(&failed).unwrap();
+
+        let history: Vec<StatusHistoryEntry> = vec![accepted, failed]
+            .into_iter()
+            .map(StatusHistoryEntry::from)
+            .collect();
+        let updaters: Vec<Option<&str>> = history
+            .iter()
+            .map(|entry| entry.updated_by.as_deref())
+            .collect();
+        assert_eq!(updaters, vec![Some("signer-a"), Some("signer-b")]);
+    }
+
+    /// Builds a deposit whose history has a pending event at height 1 followed
+    /// by alternating reprocessing and accepted events up to height 10.
+    fn deposit_with_long_history() -> DepositEntry {
+        let history: Vec<DepositEvent> = (1..=10)
+            .map(|height| DepositEvent {
+                status: match height {
+                    1 => StatusEntry::Pending,
+                    height if height % 2 == 0 => StatusEntry::Accepted,
+                    _ => StatusEntry::Reprocessing,
+                },
+                message: format!("event {height}"),
+                stacks_block_height: height,
+                stacks_block_hash: format!("hash{height}"),
+                updated_by: None,
+            })
+            .collect();
+        let mut deposit = DepositEntry { history, ..Default::default() };
+        deposit.synchronize_with_history().unwrap();
+        deposit
+    }
+
+    #[test]
+    fn compacting_history_keeps_first_and_most_recent_events() {
+        let mut deposit = deposit_with_long_history();
+        let original = deposit.clone();
+
+        assert!(deposit.compact_history(3));
+
+        let heights: Vec<u64> = deposit
+            .history
+            .iter()
+            .map(|event| event.stacks_block_height)
+            .collect();
+        assert_eq!(heights, vec![1, 2, 8, 9, 10]);
+        assert_eq!(deposit.history[0], original.history[0]);
+        assert_eq!(deposit.history[1].status, StatusEntry::Collapsed);
+        assert_eq!(deposit.history[1].stacks_block_hash, "hash2");
+        assert_eq!(deposit.history[2..], original.history[7..]);
+        assert!(deposit.validate().is_ok());
+
+        // Compacting again only drops events once there are new ones, and
+        // the collapsed event keeps the earliest dropped height.
+        assert!(!deposit.compact_history(3));
+        deposit.history.push(DepositEvent {
+            status: StatusEntry::Accepted,
+            message: "event 11".to_string(),
+            stacks_block_height: 11,
+            stacks_block_hash: "hash11".to_string(),
+            updated_by: None,
+        });
+        assert!(deposit.compact_history(3));
+        let heights: Vec<u64> = deposit
+            .history
+            .iter()
+            .map(|event| event.stacks_block_height)
+            .collect();
+        assert_eq!(heights, vec![1, 2, 9, 10, 11]);
+    }
+
+    #[test_case(9, "hash9", 9, "hash9", StatusEntry::Reprocessing; "reorg within the recent events keeps the earlier ones")]
+    #[test_case(9, "hash9-1", 8, "hash8", StatusEntry::Accepted; "reorg removing all but one recent event")]
+    #[test_case(8, "hash8-1", 8, "hash8-1", StatusEntry::Pending; "reorg removing all recent events")]
+    #[test_case(5, "hash5", 5, "hash5", StatusEntry::Pending; "reorg into the collapsed events")]
+    #[test_case(2, "hash2", 2, "hash2", StatusEntry::Pending; "reorg at the earliest collapsed event")]
+    #[test_case(2, "hash2-1", 1, "hash1", StatusEntry::Pending; "reorg before the collapsed events")]
+    fn reorganizing_a_compacted_history_results_in_valid_deposit(
+        reorg_height: u64,
+        reorg_hash: &str,
+        expected_height: u64,
+        expected_hash: &str,
+        expected_status: StatusEntry,
+    ) {
+        let mut deposit = deposit_with_long_history();
+        assert!(deposit.compact_history(3));
+
+        let chainstate = Chainstate {
+            stacks_block_height: reorg_height,
+            stacks_block_hash: reorg_hash.to_string(),
+            bitcoin_block_height: Some(0),
+        };
+        deposit.reorganize_around(&chainstate).unwrap();
+
+        assert!(
+            deposit.validate().is_ok(),
+            "Deposit must be valid after reorg."
+        );
+        assert_eq!(deposit.last_update_height, expected_height);
+        assert_eq!(deposit.last_update_block_hash, expected_hash);
+        let latest_event = deposit.latest_event().unwrap();
+        assert_eq!(latest_event.status, expected_status);
+    }
 }
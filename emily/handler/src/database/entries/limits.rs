@@ -42,6 +42,10 @@ pub struct LimitEntry {
     pub rolling_withdrawal_blocks: Option<u64>,
     /// Maximum total sBTC that can be withdrawn within the rolling withdrawal window.
     pub rolling_withdrawal_cap: Option<u64>,
+    /// Number of blocks that define the rolling deposit window.
+    pub rolling_deposit_blocks: Option<u64>,
+    /// Maximum total sBTC that can be minted by deposits within the rolling deposit window.
+    pub rolling_deposit_cap: Option<u64>,
 }
 
 /// Convert from entry to its corresponding limit.
@@ -54,6 +58,8 @@ impl From<LimitEntry> for AccountLimits {
             per_withdrawal_cap: limit_entry.per_withdrawal_cap,
             rolling_withdrawal_blocks: limit_entry.rolling_withdrawal_blocks,
             rolling_withdrawal_cap: limit_entry.rolling_withdrawal_cap,
+            rolling_deposit_blocks: limit_entry.rolling_deposit_blocks,
+            rolling_deposit_cap: limit_entry.rolling_deposit_cap,
         }
     }
 }
@@ -80,6 +86,8 @@ impl LimitEntry {
             per_withdrawal_cap: account_limit.per_withdrawal_cap,
             rolling_withdrawal_blocks: account_limit.rolling_withdrawal_blocks,
             rolling_withdrawal_cap: account_limit.rolling_withdrawal_cap,
+            rolling_deposit_blocks: account_limit.rolling_deposit_blocks,
+            rolling_deposit_cap: account_limit.rolling_deposit_cap,
         }
     }
     /// Returns true if the limit entry has no limits set.
@@ -46,9 +46,9 @@
 
 use std::{collections::HashMap, fmt::Debug};
 
-use aws_sdk_dynamodb::types::AttributeValue;
 #[cfg(feature = "testing")]
-use aws_sdk_dynamodb::types::{DeleteRequest, WriteRequest};
+use aws_sdk_dynamodb::types::DeleteRequest;
+use aws_sdk_dynamodb::types::{AttributeValue, KeysAndAttributes, PutRequest, WriteRequest};
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use serde::{Deserialize, Serialize};
 use serde_dynamo::Item;
@@ -59,12 +59,32 @@ use crate::{
     context::Settings,
 };
 
+/// The largest number of entries returned by a single paginated query.
+/// Larger requested page sizes are truncated to this value.
+pub const MAX_PAGE_SIZE: u16 = 1000;
+
+/// The largest number of keys DynamoDB accepts in a single batch get.
+const BATCH_GET_ITEM_LIMIT: usize = 100;
+
+/// The number of times keys left unprocessed by a batch get are retried
+/// before giving up.
+const BATCH_GET_ITEM_RETRIES: u32 = 5;
+
+/// The largest number of items DynamoDB accepts in a single batch write.
+const BATCH_WRITE_ITEM_LIMIT: usize = 25;
+
+/// The number of times items left unprocessed by a batch write are
+/// retried before giving up.
+const BATCH_WRITE_ITEM_RETRIES: u32 = 5;
+
 /// Chainstate table entries.
 pub mod chainstate;
 /// Deposit table entries.
 pub mod deposit;
 /// Limits table entries.
 pub mod limits;
+/// Notification table entries.
+pub mod subscription;
 /// Withdrawal table entries.
 pub mod withdrawal;
 
@@ -98,6 +118,10 @@ pub enum StatusEntry {
     Confirmed(Fulfillment),
     /// The operation was not fulfilled.
     Failed,
+    /// Placeholder for a range of events that was dropped from a compacted
+    /// history. It has the height and hash of the earliest dropped event and
+    /// is never the latest event of a valid history.
+    Collapsed,
 }
 
 impl From<&StatusEntry> for Status {
@@ -108,6 +132,9 @@ impl From<&StatusEntry> for Status {
             StatusEntry::Accepted => Status::Accepted,
             StatusEntry::Confirmed(_) => Status::Confirmed,
             StatusEntry::Failed => Status::Failed,
+            // The status within a collapsed range is unknown, so it has to be
+            // processed again.
+            StatusEntry::Collapsed => Status::Reprocessing,
         }
     }
 }
@@ -228,13 +255,62 @@ pub(crate) trait TableIndexTrait {
             .send()
             .await?;
         // Get DynamoDB item.
-        let item = get_item_output.item.ok_or(Error::NotFound)?;
+        let item = get_item_output.item.ok_or(Error::ItemNotFound)?;
         // Convert item into entry.
         let entry = serde_dynamo::from_item(item)?;
         // Return.
         Ok(entry)
     }
 
+    /// Generic batched table get. Keys that are not in the table are
+    /// left out of the returned entries, which are in no particular order.
+    /// Keys that DynamoDB leaves unprocessed are retried.
+    async fn get_entries(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        keys: &[<Self::Entry as EntryTrait>::Key],
+    ) -> Result<Vec<Self::Entry>, Error> {
+        let table_name = Self::table_name(settings);
+        let mut entries: Vec<Self::Entry> = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(BATCH_GET_ITEM_LIMIT) {
+            // Convert keys into the type needed for querying.
+            let mut pending_keys = chunk
+                .iter()
+                .map(|key| serde_dynamo::to_item::<_, Item>(key).map(Into::into))
+                .collect::<Result<Vec<HashMap<String, AttributeValue>>, _>>()?;
+            let mut attempts: u32 = 0;
+            while !pending_keys.is_empty() {
+                if attempts > BATCH_GET_ITEM_RETRIES {
+                    return Err(Error::TooManyInternalRetries);
+                }
+                attempts += 1;
+                // Query the database.
+                let keys_and_attributes = KeysAndAttributes::builder()
+                    .set_keys(Some(pending_keys))
+                    .build()?;
+                let batch_get_output = dynamodb_client
+                    .batch_get_item()
+                    .request_items(table_name, keys_and_attributes)
+                    .send()
+                    .await?;
+                // Convert data into output format.
+                let items = batch_get_output
+                    .responses
+                    .and_then(|mut responses| responses.remove(table_name))
+                    .unwrap_or_default();
+                let mut new_entries: Vec<Self::Entry> = serde_dynamo::from_items(items)?;
+                entries.append(&mut new_entries);
+                pending_keys = batch_get_output
+                    .unprocessed_keys
+                    .and_then(|mut unprocessed| unprocessed.remove(table_name))
+                    .map(|unprocessed| unprocessed.keys)
+                    .unwrap_or_default();
+            }
+        }
+        // Return.
+        Ok(entries)
+    }
+
     /// Generic table query for all attributes with a given primary key.
     async fn query_with_partition_key(
         dynamodb_client: &aws_sdk_dynamodb::Client,
@@ -252,7 +328,7 @@ pub(crate) trait TableIndexTrait {
             .table_name(Self::table_name(settings))
             .set_index_name(Self::INDEX_NAME_IF_GSI.map(|s| s.to_string()))
             .set_exclusive_start_key(exclusive_start_key)
-            .set_limit(maybe_page_size.map(|u| u as i32))
+            .set_limit(maybe_page_size.map(page_size_limit))
             .key_condition_expression("#pk = :v")
             .expression_attribute_names(
                 "#pk",
@@ -292,7 +368,7 @@ pub(crate) trait TableIndexTrait {
             .table_name(Self::table_name(settings))
             .set_index_name(Self::INDEX_NAME_IF_GSI.map(|s| s.to_string()))
             .set_exclusive_start_key(exclusive_start_key)
-            .set_limit(maybe_page_size.map(|u| u as i32))
+            .set_limit(maybe_page_size.map(page_size_limit))
             .key_condition_expression(format!("#pk = :pk AND #sk {sort_key_operator} :sk"))
             .expression_attribute_names(
                 "#pk",
@@ -317,6 +393,53 @@ pub(crate) trait TableIndexTrait {
         Ok((entries, next_token))
     }
 
+    /// Generic table query for all attributes with a given primary key and a
+    /// sort key within the inclusive range `[sort_key_from, sort_key_to]`.
+    async fn query_with_partition_key_and_sort_key_between(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        partition_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+        sort_key_from: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+        sort_key_to: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<(Vec<Self::Entry>, Option<String>), Error> {
+        // Convert inputs into the types needed for querying.
+        let exclusive_start_key =
+            maybe_exclusive_start_key_from_next_token::<Self::SearchToken>(maybe_next_token)?;
+
+        // Query the database.
+        let query_output = dynamodb_client
+            .query()
+            .table_name(Self::table_name(settings))
+            .set_index_name(Self::INDEX_NAME_IF_GSI.map(|s| s.to_string()))
+            .set_exclusive_start_key(exclusive_start_key)
+            .set_limit(maybe_page_size.map(page_size_limit))
+            .key_condition_expression("#pk = :pk AND #sk BETWEEN :from AND :to")
+            .expression_attribute_names(
+                "#pk",
+                <<Self::Entry as EntryTrait>::Key as KeyTrait>::PARTITION_KEY_NAME,
+            )
+            .expression_attribute_names(
+                "#sk",
+                <<Self::Entry as EntryTrait>::Key as KeyTrait>::SORT_KEY_NAME,
+            )
+            .expression_attribute_values(":pk", serde_dynamo::to_attribute_value(partition_key)?)
+            .expression_attribute_values(":from", serde_dynamo::to_attribute_value(sort_key_from)?)
+            .expression_attribute_values(":to", serde_dynamo::to_attribute_value(sort_key_to)?)
+            .scan_index_forward(false)
+            .send()
+            .await?;
+        // Convert data into output format.
+        let entries: Vec<Self::Entry> =
+            serde_dynamo::from_items(query_output.items.unwrap_or_default())?;
+        let next_token = maybe_next_token_from_last_evaluated_key::<Self::SearchToken>(
+            query_output.last_evaluated_key,
+        )?;
+        // Return.
+        Ok((entries, next_token))
+    }
+
     /// Generic put table entry.
     async fn put_entry(
         dynamodb_client: &aws_sdk_dynamodb::Client,
@@ -338,6 +461,76 @@ pub(crate) trait TableIndexTrait {
         Ok(())
     }
 
+    /// Generic batched table put. Unlike [`Self::put_entry`] this is not
+    /// atomic, so an error may leave only some of the entries written.
+    /// Entries that DynamoDB leaves unprocessed are retried.
+    async fn put_entries(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        entries: &[Self::Entry],
+    ) -> Result<(), Error> {
+        let table_name = Self::table_name(settings);
+        for chunk in entries.chunks(BATCH_WRITE_ITEM_LIMIT) {
+            // Convert entries into the type needed for writing.
+            let mut pending_requests = chunk
+                .iter()
+                .map(|entry| {
+                    let entry_item: Item = serde_dynamo::to_item(entry)?;
+                    let put_request = PutRequest::builder()
+                        .set_item(Some(entry_item.into()))
+                        .build()?;
+                    Ok(WriteRequest::builder().put_request(put_request).build())
+                })
+                .collect::<Result<Vec<WriteRequest>, Error>>()?;
+            let mut attempts: u32 = 0;
+            while !pending_requests.is_empty() {
+                if attempts > BATCH_WRITE_ITEM_RETRIES {
+                    return Err(Error::TooManyInternalRetries);
+                }
+                attempts += 1;
+                // Add to the database.
+                let batch_write_output = dynamodb_client
+                    .batch_write_item()
+                    .request_items(table_name, pending_requests)
+                    .send()
+                    .await?;
+                pending_requests = batch_write_output
+                    .unprocessed_items
+                    .and_then(|mut unprocessed| unprocessed.remove(table_name))
+                    .unwrap_or_default();
+            }
+        }
+        // Return.
+        Ok(())
+    }
+
+    /// Generic put table entry that only succeeds when there is no entry
+    /// with the same key, failing with `Error::VersionConflict` otherwise.
+    async fn put_entry_if_absent(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        entry: &Self::Entry,
+    ) -> Result<(), Error> {
+        // Get table name.
+        let table_name = Self::table_name(settings);
+        // Convert Entry into the type needed for querying.
+        let entry_item: Item = serde_dynamo::to_item(entry)?;
+        // Add to the database.
+        dynamodb_client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(entry_item.into()))
+            .condition_expression("attribute_not_exists(#pk)")
+            .expression_attribute_names(
+                "#pk",
+                <<Self::Entry as EntryTrait>::Key as KeyTrait>::PARTITION_KEY_NAME,
+            )
+            .send()
+            .await?;
+        // Return.
+        Ok(())
+    }
+
     /// Get all entries from a dynamodb table.
     #[cfg(feature = "testing")]
     async fn get_all_entries(
@@ -371,7 +564,6 @@ pub(crate) trait TableIndexTrait {
     }
 
     /// Generic delete table entry.
-    #[cfg(feature = "testing")]
     async fn delete_entry(
         dynamodb_client: &aws_sdk_dynamodb::Client,
         settings: &Settings,
@@ -423,44 +615,46 @@ pub(crate) trait TableIndexTrait {
         Ok(())
     }
 
-    /// Wipes the table.
+    /// Wipes the table, returning the number of deleted entries.
     #[cfg(feature = "testing")]
     async fn wipe(
         dynamodb_client: &aws_sdk_dynamodb::Client,
         settings: &Settings,
-    ) -> Result<(), Error> {
+    ) -> Result<usize, Error> {
         // Get table name.
         match Self::INDEX_NAME_IF_GSI {
             // If this is secondary index, so go to primary:
             Some(_) => {
-                let keys_to_delete = <Self::PrimaryIndex as TableIndexTrait>::get_all_entries(
-                    dynamodb_client,
-                    settings,
-                )
-                .await?
-                .iter()
-                .map(<<Self::PrimaryIndex as TableIndexTrait>::Entry as EntryTrait>::key)
-                .collect();
+                let keys_to_delete: Vec<_> =
+                    <Self::PrimaryIndex as TableIndexTrait>::get_all_entries(
+                        dynamodb_client,
+                        settings,
+                    )
+                    .await?
+                    .iter()
+                    .map(<<Self::PrimaryIndex as TableIndexTrait>::Entry as EntryTrait>::key)
+                    .collect();
+                let deleted = keys_to_delete.len();
 
                 <Self::PrimaryIndex as TableIndexTrait>::delete_entries(
                     dynamodb_client,
                     settings,
                     keys_to_delete,
                 )
-                .await
+                .await?;
+                Ok(deleted)
             }
             // Otherwise this is a primary index so wipe the table.
             None => {
-                Self::delete_entries(
-                    dynamodb_client,
-                    settings,
-                    Self::get_all_entries(dynamodb_client, settings)
-                        .await?
-                        .iter()
-                        .map(<Self::Entry as EntryTrait>::key)
-                        .collect(),
-                )
-                .await
+                let keys_to_delete: Vec<_> = Self::get_all_entries(dynamodb_client, settings)
+                    .await?
+                    .iter()
+                    .map(<Self::Entry as EntryTrait>::key)
+                    .collect();
+                let deleted = keys_to_delete.len();
+
+                Self::delete_entries(dynamodb_client, settings, keys_to_delete).await?;
+                Ok(deleted)
             }
         }
     }
@@ -555,6 +749,12 @@ where
     Ok(maybe_next_token)
 }
 
+/// Converts a requested page size into the query limit, keeping it within
+/// the range DynamoDB accepts and below [`MAX_PAGE_SIZE`].
+fn page_size_limit(page_size: u16) -> i32 {
+    page_size.clamp(1, MAX_PAGE_SIZE) as i32
+}
+
 /// Turns an optional key into a token.
 fn tokenize<T>(key: T) -> Result<String, Error>
 where
@@ -588,7 +788,43 @@ fn detokenize<T>(token: String) -> Result<T, Error>
 where
     T: for<'de> Deserialize<'de>,
 {
-    let decoded = URL_SAFE_NO_PAD.decode(token)?;
-    let deserialized = serde_json::from_slice::<T>(&decoded)?;
+    let decoded = URL_SAFE_NO_PAD
+        .decode(token)
+        .map_err(|err| Error::BadRequest(format!("malformed next token: {err}")))?;
+    let deserialized = serde_json::from_slice::<T>(&decoded)
+        .map_err(|err| Error::BadRequest(format!("malformed next token: {err}")))?;
     Ok(deserialized)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deposit::DepositInfoEntrySearchToken;
+    use test_case::test_case;
+
+    #[test]
+    fn next_token_round_trips() {
+        let token_data = DepositInfoEntrySearchToken::default();
+        let token = tokenize(token_data.clone()).unwrap();
+        let detokenized: DepositInfoEntrySearchToken = detokenize(token).unwrap();
+        assert_eq!(detokenized, token_data);
+    }
+
+    #[test_case("not base64!"; "invalid base64")]
+    #[test_case("e30"; "missing keys")]
+    #[test_case("bm90IGpzb24"; "not json")]
+    fn malformed_next_token_is_bad_request(token: &str) {
+        let result = maybe_exclusive_start_key_from_next_token::<DepositInfoEntrySearchToken>(
+            Some(token.to_string()),
+        );
+        assert!(matches!(result, Err(Error::BadRequest(_))));
+    }
+
+    #[test_case(0, 1; "zero")]
+    #[test_case(20, 20; "within bounds")]
+    #[test_case(MAX_PAGE_SIZE, MAX_PAGE_SIZE as i32; "at the cap")]
+    #[test_case(u16::MAX, MAX_PAGE_SIZE as i32; "above the cap")]
+    fn page_size_is_capped(page_size: u16, expected: i32) {
+        assert_eq!(page_size_limit(page_size), expected);
+    }
+}
@@ -0,0 +1,91 @@
+//! Entries into the notification table, which holds the subscriptions to
+//! deposit and withdrawal status changes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::common::Status;
+use crate::api::models::subscription::{Subscription, SubscriptionOperation};
+
+use super::{EntryTrait, KeyTrait, PrimaryIndex, PrimaryIndexTrait};
+
+// Subscription entry ----------------------------------------------------------
+
+/// Notification table entry key. This is the primary index key.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SubscriptionEntryKey {
+    /// The kind of operation the subscription is notified about.
+    pub operation: SubscriptionOperation,
+    /// The URL that notifications are sent to.
+    pub url: String,
+}
+
+/// Notification table entry.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SubscriptionEntry {
+    /// Subscription entry key.
+    #[serde(flatten)]
+    pub key: SubscriptionEntryKey,
+    /// The statuses to notify about. Every status is notified about if
+    /// this is empty.
+    pub statuses: Vec<Status>,
+}
+
+/// Convert from subscription to its corresponding entry.
+impl From<Subscription> for SubscriptionEntry {
+    fn from(subscription: Subscription) -> Self {
+        SubscriptionEntry {
+            key: SubscriptionEntryKey {
+                operation: subscription.operation,
+                url: subscription.url,
+            },
+            statuses: subscription.statuses,
+        }
+    }
+}
+
+/// Convert from entry to its corresponding subscription.
+impl From<SubscriptionEntry> for Subscription {
+    fn from(entry: SubscriptionEntry) -> Self {
+        Subscription {
+            url: entry.key.url,
+            operation: entry.key.operation,
+            statuses: entry.statuses,
+        }
+    }
+}
+
+/// Implements the key trait for the subscription entry key.
+impl KeyTrait for SubscriptionEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = SubscriptionOperation;
+    /// the type of the sort key.
+    type SortKey = String;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "Operation";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "Url";
+}
+
+/// Implements the entry trait for the subscription entry.
+impl EntryTrait for SubscriptionEntry {
+    /// The type of the key for this entry type.
+    type Key = SubscriptionEntryKey;
+    /// Extract the key from the subscription entry.
+    fn key(&self) -> Self::Key {
+        self.key.clone()
+    }
+}
+
+/// Primary index struct.
+pub struct SubscriptionTablePrimaryIndexInner;
+/// Notification table primary index type.
+pub type SubscriptionTablePrimaryIndex = PrimaryIndex<SubscriptionTablePrimaryIndexInner>;
+/// Definition of Primary index trait.
+impl PrimaryIndexTrait for SubscriptionTablePrimaryIndexInner {
+    type Entry = SubscriptionEntry;
+    fn table_name(settings: &crate::context::Settings) -> &str {
+        &settings.notification_table_name
+    }
+}
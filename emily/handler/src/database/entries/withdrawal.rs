@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     api::models::{
         chainstate::Chainstate,
-        common::Status,
+        common::{Status, StatusHistoryEntry},
         withdrawal::{Withdrawal, WithdrawalInfo, WithdrawalParameters},
     },
     common::error::{Error, Inconsistency},
@@ -89,6 +89,11 @@ impl WithdrawalEntry {
         let latest_event: &WithdrawalEvent = self.history.last().ok_or(Error::Debug(format!(
             "Failed getting the last history element for withdrawal. {stringy_self:?}"
         )))?;
+        if latest_event.status == StatusEntry::Collapsed {
+            return Err(Error::Debug(format!(
+                "latest history element is a collapsed range of events. {stringy_self:?}"
+            )));
+        }
 
         // Verify that the latest event is the current one shown in the entry.
         if self.last_update_block_hash != latest_event.stacks_block_hash {
@@ -111,10 +116,51 @@ impl WithdrawalEntry {
 
     /// Gets the latest event.
     pub fn latest_event(&self) -> Result<&WithdrawalEvent, Error> {
-        self.history.last().ok_or(Error::Debug(format!(
+        let latest_event = self.history.last().ok_or(Error::Debug(format!(
             "Withdrawal entry must always have at least one event, but entry with id {:?} did not.",
             self.key(),
-        )))
+        )))?;
+        if latest_event.status == StatusEntry::Collapsed {
+            return Err(Error::Debug(format!(
+                "The latest event of withdrawal entry with id {:?} is a collapsed range of events.",
+                self.key(),
+            )));
+        }
+        Ok(latest_event)
+    }
+
+    /// Compacts the history so that it holds the first event, a single
+    /// `Collapsed` event in place of the dropped events, and the
+    /// `max_recent_events` most recent events. The collapsed event has the
+    /// height and hash of the earliest dropped event, so the history stays in
+    /// chronological order. Returns whether any events were dropped.
+    pub fn compact_history(&mut self, max_recent_events: usize) -> bool {
+        // Keep at least one recent event so that the latest event is never
+        // the collapsed one.
+        let max_recent_events = max_recent_events.max(1);
+        let already_collapsed = self
+            .history
+            .get(1)
+            .is_some_and(|event| event.status == StatusEntry::Collapsed);
+        let kept_prefix_length = if already_collapsed { 2 } else { 1 };
+        let history_length = self.history.len();
+        if history_length <= kept_prefix_length + max_recent_events {
+            return false;
+        }
+        let recent_events = self.history.split_off(history_length - max_recent_events);
+        // Everything between the first event and the recent events is dropped,
+        // and only the earliest of the dropped events is needed.
+        let earliest_dropped_event = self.history.swap_remove(1);
+        self.history.truncate(1);
+        self.history.push(WithdrawalEvent {
+            status: StatusEntry::Collapsed,
+            message: "Older events collapsed to limit the history size.".to_string(),
+            stacks_block_height: earliest_dropped_event.stacks_block_height,
+            stacks_block_hash: earliest_dropped_event.stacks_block_hash,
+            updated_by: None,
+        });
+        self.history.extend(recent_events);
+        true
     }
 
     /// Reorgs around a given chainstate.
@@ -128,14 +174,20 @@ impl WithdrawalEntry {
                 || ((chainstate.stacks_block_height == event.stacks_block_height)
                     && (chainstate.stacks_block_hash == event.stacks_block_hash))
         });
-        // If the history is empty, just say that the withdrawal is pending again where its
+        // If the history is empty, or the reorg reached into a collapsed range of events
+        // whose statuses are unknown, just say that the withdrawal is pending again where its
         // latest update is the point at which the reorg happened.
-        if self.history.is_empty() {
+        let reached_collapsed_events = self
+            .history
+            .last()
+            .is_some_and(|event| event.status == StatusEntry::Collapsed);
+        if self.history.is_empty() || reached_collapsed_events {
             self.history = vec![WithdrawalEvent {
                 status: StatusEntry::Pending,
                 message: "Reprocessing withdrawal status after reorg.".to_string(),
                 stacks_block_height: chainstate.stacks_block_height,
                 stacks_block_hash: chainstate.stacks_block_hash.clone(),
+                updated_by: None,
             }]
         }
         // Synchronize self with the new history.
@@ -230,6 +282,22 @@ pub struct WithdrawalEvent {
     pub stacks_block_height: u64,
     /// Stacks block hash associated with the height of this update.
     pub stacks_block_hash: String,
+    /// Public key of the signer that made this update, if it was made by
+    /// a signer that identified itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+}
+
+impl From<WithdrawalEvent> for StatusHistoryEntry {
+    fn from(event: WithdrawalEvent) -> Self {
+        StatusHistoryEntry {
+            status: (&event.status).into(),
+            message: event.message,
+            stacks_block_height: event.stacks_block_height,
+            stacks_block_hash: event.stacks_block_hash,
+            updated_by: event.updated_by,
+        }
+    }
 }
 
 /// Implementation of withdrawal event.
@@ -239,18 +307,24 @@ impl WithdrawalEvent {
         &self,
         next_event: &WithdrawalEvent,
     ) -> Result<(), Error> {
+        let latest_updater = self.updated_by.as_deref().unwrap_or("unknown");
+        let next_updater = next_event.updated_by.as_deref().unwrap_or("unknown");
         // Determine if event is valid.
         if self.stacks_block_height > next_event.stacks_block_height {
             return Err(Error::InconsistentState(Inconsistency::ItemUpdate(
-                "Attempting to update a withdrawal with a block height earlier than it should be."
-                    .into(),
+                format!(
+                    "Attempting to update a withdrawal with a block height earlier than it should be. \
+                Latest update by {latest_updater}, conflicting update by {next_updater}."
+                ),
             )));
         } else if self.stacks_block_height == next_event.stacks_block_height
             && self.stacks_block_hash != next_event.stacks_block_hash
         {
             return Err(Error::InconsistentState(Inconsistency::ItemUpdate(
-                "Attempting to update a withdrawal with a block height and hash that conflicts with the current history."
-                    .into(),
+                format!(
+                    "Attempting to update a withdrawal with a block height and hash that conflicts with the current history. \
+                Latest update by {latest_updater}, conflicting update by {next_updater}."
+                ),
             )));
         }
 
@@ -688,6 +762,7 @@ impl WithdrawalUpdatePackage {
 mod tests {
     use crate::api::models::chainstate::Chainstate;
     use crate::api::models::common::Fulfillment;
+    use crate::common::error::{Error, Inconsistency};
     use crate::database::entries::StatusEntry;
     use crate::{
         api::models::common::Status,
@@ -706,6 +781,7 @@ mod tests {
             message: "message".to_string(),
             stacks_block_height: 1,
             stacks_block_hash: "hash".to_string(),
+            updated_by: None,
         };
 
         let failed = WithdrawalEvent {
@@ -713,6 +789,7 @@ mod tests {
             message: "message".to_string(),
             stacks_block_height: 2,
             stacks_block_hash: "hash".to_string(),
+            updated_by: None,
         };
 
         let withdrawal_entry = WithdrawalEntry {
@@ -750,6 +827,7 @@ mod tests {
             message: "message".to_string(),
             stacks_block_height: 1,
             stacks_block_hash: "hash".to_string(),
+            updated_by: None,
         };
 
         let failed = WithdrawalEvent {
@@ -757,6 +835,7 @@ mod tests {
             message: "message".to_string(),
             stacks_block_height: 2,
             stacks_block_hash: "hash".to_string(),
+            updated_by: None,
         };
 
         let withdrawal_entry = WithdrawalEntry {
@@ -803,6 +882,7 @@ mod tests {
             message: "initial test pending".to_string(),
             stacks_block_height: 2,
             stacks_block_hash: "hash2".to_string(),
+            updated_by: None,
         };
 
         let accepted = WithdrawalEvent {
@@ -810,6 +890,7 @@ mod tests {
             message: "accepted".to_string(),
             stacks_block_height: 4,
             stacks_block_hash: "hash4".to_string(),
+            updated_by: None,
         };
 
         let fulfillment: Fulfillment = Default::default();
@@ -818,6 +899,7 @@ mod tests {
             message: "confirmed".to_string(),
             stacks_block_height: 6,
             stacks_block_hash: "hash6".to_string(),
+            updated_by: None,
         };
 
         let mut withdrawal_entry = WithdrawalEntry {
@@ -871,4 +953,128 @@ mod tests {
         assert_eq!(latest_event.stacks_block_hash, expected_hash);
         assert_eq!(latest_event.status, expected_status);
     }
+
+    #[test_case(5, "hash5-1"; "same height with a different hash")]
+    #[test_case(4, "hash4"; "earlier height")]
+    fn conflicting_update_error_names_both_signers(height: u64, hash: &str) {
+        let latest = WithdrawalEvent {
+            status: StatusEntry::Confirmed(Fulfillment::default()),
+            message: "confirmed".to_string(),
+            stacks_block_height: 5,
+            stacks_block_hash: "hash5".to_string(),
+            updated_by: Some("signer-a".to_string()),
+        };
+        let conflicting = WithdrawalEvent {
+            status: StatusEntry::Failed,
+            message: "failed".to_string(),
+            stacks_block_height: height,
+            stacks_block_hash: hash.to_string(),
+            updated_by: Some("signer-b".to_string()),
+        };
+
+        let error = latest
+            .ensure_following_event_is_valid(&conflicting)
+            .unwrap_err();
+
+        let Error::InconsistentState(Inconsistency::ItemUpdate(message)) = error else {
+            panic!("expected an item update inconsistency, got {error:?}");
+        };
+        assert!(message.contains("Latest update by signer-a"), "{message}");
+        assert!(
+            message.contains("conflicting update by signer-b"),
+            "{message}"
+        );
+    }
+
+    /// Builds a withdrawal whose history has a pending event at height 1 followed
+    /// by alternating reprocessing and accepted events up to height 10.
+    fn withdrawal_with_long_history() -> WithdrawalEntry {
+        let history: Vec<WithdrawalEvent> = (1..=10)
+            .map(|height| WithdrawalEvent {
+                status: match height {
+                    1 => StatusEntry::Pending,
+                    height if height % 2 == 0 => StatusEntry::Accepted,
+                    _ => StatusEntry::Reprocessing,
+                },
+                message: format!("event {height}"),
+                stacks_block_height: height,
+                stacks_block_hash: format!("hash{height}"),
+                updated_by: None,
+            })
+            .collect();
+        let mut withdrawal = WithdrawalEntry { history, ..Default::default() };
+        withdrawal.synchronize_with_history().unwrap();
+        withdrawal
+    }
+
+    #[test]
+    fn compacting_history_keeps_first_and_most_recent_events() {
+        let mut withdrawal = withdrawal_with_long_history();
+        let original = withdrawal.clone();
+
+        assert!(withdrawal.compact_history(3));
+
+        let heights: Vec<u64> = withdrawal
+            .history
+            .iter()
+            .map(|event| event.stacks_block_height)
+            .collect();
+        assert_eq!(heights, vec![1, 2, 8, 9, 10]);
+        assert_eq!(withdrawal.history[0], original.history[0]);
+        assert_eq!(withdrawal.history[1].status, StatusEntry::Collapsed);
+        assert_eq!(withdrawal.history[1].stacks_block_hash, "hash2");
+        assert_eq!(withdrawal.history[2..], original.history[7..]);
+        assert!(withdrawal.validate().is_ok());
+
+        // Compacting again only drops events once there are new ones, and
+        // the collapsed event keeps the earliest dropped height.
+        assert!(!withdrawal.compact_history(3));
+        withdrawal.history.push(WithdrawalEvent {
+            status: StatusEntry::Accepted,
+            message: "event 11".to_string(),
+            stacks_block_height: 11,
+            stacks_block_hash: "hash11".to_string(),
+            updated_by: None,
+        });
+        assert!(withdrawal.compact_history(3));
+        let heights: Vec<u64> = withdrawal
+            .history
+            .iter()
+            .map(|event| event.stacks_block_height)
+            .collect();
+        assert_eq!(heights, vec![1, 2, 9, 10, 11]);
+    }
+
+    #[test_case(9, "hash9", 9, "hash9", StatusEntry::Reprocessing; "reorg within the recent events keeps the earlier ones")]
+    #[test_case(9, "hash9-1", 8, "hash8", StatusEntry::Accepted; "reorg removing all but one recent event")]
+    #[test_case(8, "hash8-1", 8, "hash8-1", StatusEntry::Pending; "reorg removing all recent events")]
+    #[test_case(5, "hash5", 5, "hash5", StatusEntry::Pending; "reorg into the collapsed events")]
+    #[test_case(2, "hash2", 2, "hash2", StatusEntry::Pending; "reorg at the earliest collapsed event")]
+    #[test_case(2, "hash2-1", 1, "hash1", StatusEntry::Pending; "reorg before the collapsed events")]
+    fn reorganizing_a_compacted_history_results_in_valid_withdrawal(
+        reorg_height: u64,
+        reorg_hash: &str,
+        expected_height: u64,
+        expected_hash: &str,
+        expected_status: StatusEntry,
+    ) {
+        let mut withdrawal = withdrawal_with_long_history();
+        assert!(withdrawal.compact_history(3));
+
+        let chainstate = Chainstate {
+            stacks_block_height: reorg_height,
+            stacks_block_hash: reorg_hash.to_string(),
+            bitcoin_block_height: Some(0),
+        };
+        withdrawal.reorganize_around(&chainstate).unwrap();
+
+        assert!(
+            withdrawal.validate().is_ok(),
+            "Withdrawal must be valid after reorg."
+        );
+        assert_eq!(withdrawal.last_update_height, expected_height);
+        assert_eq!(withdrawal.last_update_block_hash, expected_hash);
+        let latest_event = withdrawal.latest_event().unwrap();
+        assert_eq!(latest_event.status, expected_status);
+    }
 }
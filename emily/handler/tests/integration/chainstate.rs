@@ -1,9 +1,14 @@
 use std::cmp::Ordering;
 
 use testing_emily_client::apis;
-use testing_emily_client::models::Chainstate;
+use testing_emily_client::models::{
+    BulkChainstateConflictErrorResponse, ChainGapErrorResponse, Chainstate, ChainstateHistoryEntry,
+    SetChainstatesBulkRequestBody,
+};
 
-use crate::common::{batch_set_chainstates, clean_setup, new_test_chainstate};
+use crate::common::{
+    NotFoundError, TestError, batch_set_chainstates, clean_setup, new_test_chainstate,
+};
 use test_case::test_case;
 
 /// An arbitrary fully ordered partial cmp comparator for Chainstate.
@@ -98,6 +103,125 @@ async fn create_and_get_chainstate_reorg_happy_path(
     assert_eq!(expected_post_reorg_chaintip, gotten_post_reorg_chaintip);
 }
 
+#[test_case(1123, 1128, 1133; "standard-reorg")]
+#[test_case(1123, 1133, 1133; "reorg-to-tip-at-same-height")]
+#[tokio::test]
+async fn chainstate_history_shows_reorged_hashes(
+    min_height: u64,
+    reorg_height: u64,
+    max_height: u64,
+) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let original_chainstates: Vec<Chainstate> = (min_height..max_height + 1)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+
+    // The reorged tip followed by two blocks built on top of it.
+    let reorged_chainstates: Vec<Chainstate> = (reorg_height..reorg_height + 3)
+        .map(|height| new_test_chainstate(height, height, 1))
+        .collect();
+
+    let limit: u64 = 5;
+    let expected_history: Vec<ChainstateHistoryEntry> = (0..limit)
+        .map(|offset| reorg_height + 2 - offset)
+        .map(|height| {
+            let fork_id = if height >= reorg_height { 1 } else { 0 };
+            let chainstate = new_test_chainstate(height, height, fork_id);
+            // Every reorged block that replaced an original block records its hash.
+            let superseded_hash = (fork_id == 1 && height <= max_height)
+                .then(|| new_test_chainstate(height, height, 0).stacks_block_hash);
+            ChainstateHistoryEntry {
+                bitcoin_block_height: chainstate.bitcoin_block_height,
+                stacks_block_hash: chainstate.stacks_block_hash,
+                stacks_block_height: chainstate.stacks_block_height,
+                superseded_hash: superseded_hash.map(Some),
+            }
+        })
+        .collect();
+
+    // Act.
+    // --------
+    batch_set_chainstates(&configuration, original_chainstates).await;
+    // Setting the conflicting tip executes the reorg.
+    batch_set_chainstates(&configuration, reorged_chainstates).await;
+
+    let history = apis::chainstate_api::get_chainstate_history(&configuration, Some(limit as u32))
+        .await
+        .expect("Received an error after making a valid get chainstate history api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(history.chainstates, expected_history);
+}
+
+#[tokio::test]
+async fn chainstate_history_stops_at_first_recorded_height() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let min_height = 1123;
+    let max_height = 1128;
+    let chainstates: Vec<Chainstate> = (min_height..max_height + 1)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+
+    let expected_heights: Vec<u64> = (min_height..max_height + 1).rev().collect();
+
+    // Act.
+    // --------
+    batch_set_chainstates(&configuration, chainstates).await;
+
+    let history = apis::chainstate_api::get_chainstate_history(&configuration, Some(50))
+        .await
+        .expect("Received an error after making a valid get chainstate history api call.");
+
+    // Assert.
+    // --------
+    let heights: Vec<u64> = history
+        .chainstates
+        .iter()
+        .map(|chainstate| chainstate.stacks_block_height)
+        .collect();
+    assert_eq!(heights, expected_heights);
+    assert!(
+        history
+            .chainstates
+            .iter()
+            .all(|chainstate| chainstate.superseded_hash.is_none())
+    );
+}
+
+#[tokio::test]
+async fn get_chainstate_at_missing_height_is_not_found() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let height = 1123;
+    batch_set_chainstates(&configuration, vec![new_test_chainstate(height, height, 0)]).await;
+
+    // Act.
+    // --------
+    let attempted_get: NotFoundError =
+        apis::chainstate_api::get_chainstate_at_height(&configuration, height + 1)
+            .await
+            .expect_err(
+                "Received a successful response attempting to access a nonpresent chainstate.",
+            )
+            .into();
+
+    // Assert.
+    // --------
+    assert_eq!(attempted_get.status_code, 404);
+    assert_eq!(attempted_get.body.error, "not_found");
+    assert_eq!(attempted_get.body.resource, "chainstate");
+    assert_eq!(attempted_get.body.key, (height + 1).to_string());
+}
+
 #[test_case(1123, 1128; "replay-5-chainstates-out-of-order")]
 #[tokio::test]
 async fn create_and_replay_does_not_initiate_reorg(min_height: u64, max_height: u64) {
@@ -149,3 +273,244 @@ async fn create_and_replay_does_not_initiate_reorg(min_height: u64, max_height:
     assert_eq!(expected_chainstates, gotten_chainstates);
     assert_eq!(expected_chaintip, gotten_chaintip)
 }
+
+/// The maximum number of heights that may be missing between the chain tip
+/// and a new chainstate in the local Emily server, which uses the default.
+const MAX_CHAINSTATE_GAP: u64 = 100;
+
+#[test_case(1123, 1128, 1128; "replay-tip")]
+#[test_case(1123, 1128, 1125; "replay-below-tip")]
+#[tokio::test]
+async fn replaying_recorded_chainstate_keeps_chaintip(
+    min_height: u64,
+    max_height: u64,
+    replay_height: u64,
+) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let chainstates: Vec<Chainstate> = (min_height..max_height + 1)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+    let replayed_chainstate = new_test_chainstate(replay_height, replay_height, 0);
+    let expected_chaintip = new_test_chainstate(max_height, max_height, 0);
+
+    // Act.
+    // --------
+    batch_set_chainstates(&configuration, chainstates).await;
+
+    let replayed =
+        apis::chainstate_api::set_chainstate(&configuration, replayed_chainstate.clone())
+            .await
+            .expect("Received an error after replaying a recorded chainstate.");
+
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(replayed, replayed_chainstate);
+    assert_eq!(gotten_chaintip, expected_chaintip);
+}
+
+#[test_case(0; "adjacent")]
+#[test_case(MAX_CHAINSTATE_GAP; "largest-allowed-gap")]
+#[tokio::test]
+async fn chainstate_within_gap_becomes_chaintip(missing_heights: u64) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let tip_height = 1123;
+    let new_height = tip_height + missing_heights + 1;
+    let expected_chaintip = new_test_chainstate(new_height, new_height, 0);
+
+    // Act.
+    // --------
+    batch_set_chainstates(
+        &configuration,
+        vec![
+            new_test_chainstate(tip_height, tip_height, 0),
+            expected_chaintip.clone(),
+        ],
+    )
+    .await;
+
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(gotten_chaintip, expected_chaintip);
+}
+
+#[tokio::test]
+async fn chainstate_too_far_above_chaintip_is_a_chain_gap() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let tip_height = 1123;
+    let new_height = tip_height + MAX_CHAINSTATE_GAP + 2;
+    let expected_chaintip = new_test_chainstate(tip_height, tip_height, 0);
+    batch_set_chainstates(&configuration, vec![expected_chaintip.clone()]).await;
+
+    // Act.
+    // --------
+    let error: TestError<ChainGapErrorResponse> = apis::chainstate_api::set_chainstate(
+        &configuration,
+        new_test_chainstate(new_height, new_height, 0),
+    )
+    .await
+    .expect_err("Received a successful response setting a chainstate far above the chain tip.")
+    .into();
+
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(error.status_code, 409);
+    assert_eq!(
+        error.body,
+        ChainGapErrorResponse {
+            error: "chain_gap".to_string(),
+            first_missing_height: tip_height + 1,
+            last_missing_height: new_height - 1,
+        }
+    );
+    assert_eq!(gotten_chaintip, expected_chaintip);
+}
+
+#[tokio::test]
+async fn bulk_chainstates_bootstrap_empty_table() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let chainstates: Vec<Chainstate> = (1123..1128)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+    let expected_chaintip = chainstates.last().unwrap().clone();
+
+    // Act.
+    // --------
+    let response = apis::chainstate_api::set_chainstates_bulk(
+        &configuration,
+        SetChainstatesBulkRequestBody::new(chainstates.clone()),
+    )
+    .await
+    .expect("Received an error after making a valid set chainstates in bulk api call.");
+
+    let mut gotten_chainstates: Vec<Chainstate> = Vec::new();
+    for chainstate in chainstates.iter() {
+        let gotten_chainstate = apis::chainstate_api::get_chainstate_at_height(
+            &configuration,
+            chainstate.stacks_block_height,
+        )
+        .await
+        .expect("Received an error after making a valid get chainstate at height api call.");
+        gotten_chainstates.push(gotten_chainstate);
+    }
+
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(response.applied, chainstates.len() as u64);
+    assert_eq!(*response.chaintip, expected_chaintip);
+    assert_eq!(gotten_chainstates, chainstates);
+    assert_eq!(gotten_chaintip, expected_chaintip);
+}
+
+#[tokio::test]
+async fn bulk_chainstates_continue_from_chaintip() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let recorded_chainstates: Vec<Chainstate> = (1123..1126)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+    batch_set_chainstates(&configuration, recorded_chainstates).await;
+
+    let chainstates: Vec<Chainstate> = (1126..1131)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+    let expected_chaintip = chainstates.last().unwrap().clone();
+
+    // Act.
+    // --------
+    let response = apis::chainstate_api::set_chainstates_bulk(
+        &configuration,
+        SetChainstatesBulkRequestBody::new(chainstates.clone()),
+    )
+    .await
+    .expect("Received an error after making a valid set chainstates in bulk api call.");
+
+    let history = apis::chainstate_api::get_chainstate_history(&configuration, Some(8))
+        .await
+        .expect("Received an error after making a valid get chainstate history api call.");
+
+    // Assert.
+    // --------
+    let history_heights: Vec<u64> = history
+        .chainstates
+        .iter()
+        .map(|entry| entry.stacks_block_height)
+        .collect();
+    assert_eq!(response.applied, chainstates.len() as u64);
+    assert_eq!(*response.chaintip, expected_chaintip);
+    assert_eq!(history_heights, (1123..1131).rev().collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn bulk_chainstates_stop_at_first_inconsistency() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let expected_chaintip = new_test_chainstate(1125, 1125, 0);
+    batch_set_chainstates(&configuration, vec![expected_chaintip.clone()]).await;
+
+    let chainstates = vec![
+        new_test_chainstate(1126, 1126, 0),
+        new_test_chainstate(1127, 1127, 0),
+        new_test_chainstate(1129, 1129, 0),
+    ];
+
+    // Act.
+    // --------
+    let error: TestError<BulkChainstateConflictErrorResponse> =
+        apis::chainstate_api::set_chainstates_bulk(
+            &configuration,
+            SetChainstatesBulkRequestBody::new(chainstates),
+        )
+        .await
+        .expect_err("Received a successful response setting chainstates with a gap in bulk.")
+        .into();
+
+    let not_found: NotFoundError =
+        apis::chainstate_api::get_chainstate_at_height(&configuration, 1126)
+            .await
+            .expect_err("Received a chainstate from a rejected bulk submission.")
+            .into();
+
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(error.status_code, 409);
+    assert_eq!(error.body.error, "bulk_chainstate_conflict");
+    assert_eq!(error.body.index, 2);
+    assert_eq!(not_found.status_code, 404);
+    assert_eq!(gotten_chaintip, expected_chaintip);
+}
@@ -8,12 +8,15 @@ use testing_emily_client::{
         self,
         configuration::{ApiKey, Configuration},
     },
-    models::ErrorResponse,
+    models::{ErrorResponse, NotFoundErrorResponse},
 };
 
 /// Standard error type.
 pub type StandardError = TestError<ErrorResponse>;
 
+/// Error type returned when a requested resource does not exist.
+pub type NotFoundError = TestError<NotFoundErrorResponse>;
+
 /// Setup test.
 pub async fn clean_setup() -> Configuration {
     let configuration = testing_emily_client::apis::configuration::Configuration {
@@ -13,13 +13,18 @@ use sbtc::testing::deposits::TxSetup;
 use testing_emily_client::apis::ResponseContent;
 use testing_emily_client::apis::chainstate_api::set_chainstate;
 use testing_emily_client::apis::configuration::ApiKey;
-use testing_emily_client::models::{Chainstate, Fulfillment, Status, UpdateDepositsRequestBody};
+use testing_emily_client::models::{
+    Chainstate, DepositKey, Fulfillment, LimitExceededErrorResponse, Limits,
+    LookupDepositsRequestBody, Status, StatusHistoryEntry, UpdateDepositsRequestBody,
+};
 use testing_emily_client::{
     apis::{self, configuration::Configuration},
     models::{CreateDepositRequestBody, Deposit, DepositInfo, DepositParameters, DepositUpdate},
 };
 
-use crate::common::{StandardError, clean_setup};
+use crate::common::{
+    NotFoundError, TestError, batch_set_chainstates, clean_setup, new_test_chainstate,
+};
 
 const BLOCK_HASH: &str = "";
 const BLOCK_HEIGHT: u64 = 0;
@@ -239,12 +244,12 @@ async fn wipe_databases_test() {
         .await
         .expect("Received an error after making a valid create deposit request api call.");
 
-    apis::testing_api::wipe_databases(&configuration)
+    let wiped = apis::testing_api::wipe_databases(&configuration)
         .await
         .expect("Received an error after making a valid wipe api call.");
 
     let bitcoin_tx_output_index_string = bitcoin_tx_output_index.to_string();
-    let attempted_get: StandardError = apis::deposit_api::get_deposit(
+    let attempted_get: NotFoundError = apis::deposit_api::get_deposit(
         &configuration,
         &bitcoin_txid,
         &bitcoin_tx_output_index_string,
@@ -253,9 +258,30 @@ async fn wipe_databases_test() {
     .expect_err("Received a successful response attempting to access a nonpresent deposit.")
     .into();
 
+    // Wiping again shows that every table was left empty.
+    let wiped_again = apis::testing_api::wipe_databases(&configuration)
+        .await
+        .expect("Received an error after making a valid wipe api call.");
+
     // Assert.
     // -------
     assert_eq!(attempted_get.status_code, 404);
+    assert_eq!(attempted_get.body.error, "not_found");
+    assert_eq!(attempted_get.body.resource, "deposit");
+    assert_eq!(
+        attempted_get.body.key,
+        format!("{bitcoin_txid}:{bitcoin_tx_output_index}")
+    );
+    let deposit_table = wiped
+        .tables
+        .iter()
+        .find(|table| table.table_name.contains("Deposit"))
+        .expect("The deposit table should be among the wiped tables.");
+    assert_eq!(deposit_table.items_deleted, 1);
+    assert_eq!(wiped_again.tables.len(), wiped.tables.len());
+    for table in wiped_again.tables {
+        assert_eq!(table.items_deleted, 0, "{} was not empty", table.table_name);
+    }
 }
 
 #[tokio::test]
@@ -409,6 +435,8 @@ async fn get_deposits() {
             status,
             next_token.as_deref(),
             Some(chunksize as u32),
+            None,
+            None,
         )
         .await
         .expect("Received an error after making a valid get deposits api call.");
@@ -440,6 +468,181 @@ async fn get_deposits() {
     assert_eq!(expected_deposit_infos, gotten_deposit_infos);
 }
 
+#[test_case("not base64!"; "invalid base64")]
+#[test_case("e30"; "missing keys")]
+#[tokio::test]
+async fn get_deposits_rejects_malformed_next_token(next_token: &str) {
+    let configuration = clean_setup().await;
+
+    let response = apis::deposit_api::get_deposits(
+        &configuration,
+        Status::Pending,
+        Some(next_token),
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
+/// The stacks block heights at which the deposits of the height window
+/// tests are accepted.
+const ACCEPTED_HEIGHTS: [u64; 3] = [10, 20, 30];
+
+#[test_case(Some(10), Some(30), &[10, 20, 30]; "window covering all heights")]
+#[test_case(Some(20), Some(20), &[20]; "single height window")]
+#[test_case(Some(10), Some(19), &[10]; "window ending below a height")]
+#[test_case(Some(11), Some(20), &[20]; "window starting above a height")]
+#[test_case(None, Some(20), &[10, 20]; "only to height")]
+#[test_case(Some(20), None, &[20, 30]; "only from height")]
+#[test_case(Some(11), Some(19), &[]; "empty window between heights")]
+#[test_case(Some(31), None, &[]; "empty window above all heights")]
+#[test_case(Some(0), Some(9), &[]; "empty window below all heights")]
+#[tokio::test]
+async fn get_deposits_filters_by_height_window(
+    from_height: Option<u64>,
+    to_height: Option<u64>,
+    expected_heights: &[u64],
+) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let amounts = vec![DEPOSIT_AMOUNT_SATS; ACCEPTED_HEIGHTS.len()];
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
+
+    let create_requests = (0..ACCEPTED_HEIGHTS.len())
+        .map(|index| CreateDepositRequestBody {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts[index].clone(),
+            reclaim_script: reclaim_scripts[index].clone(),
+            transaction_hex: transaction_hex.clone(),
+        })
+        .collect();
+    batch_create_deposits(&configuration, create_requests).await;
+
+    // Accept each deposit at a different chain tip so that they have
+    // different last update heights.
+    for (index, height) in ACCEPTED_HEIGHTS.into_iter().enumerate() {
+        set_chainstate(&configuration, new_test_chainstate(height, height, 0))
+            .await
+            .expect("Received an error after making a valid set chainstate api call.");
+        let deposits = vec![DepositUpdate {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: None,
+            status: Status::Accepted,
+            status_message: "foo".into(),
+            updated_by: None,
+        }];
+        apis::deposit_api::update_deposits(&configuration, UpdateDepositsRequestBody { deposits })
+            .await
+            .expect("Received an error after making a valid update deposits api call.");
+    }
+
+    // Act.
+    // ----
+    let response = apis::deposit_api::get_deposits(
+        &configuration,
+        Status::Accepted,
+        None,
+        None,
+        from_height,
+        to_height,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
+
+    // Assert.
+    // -------
+    let mut heights: Vec<u64> = response
+        .deposits
+        .iter()
+        .map(|deposit| deposit.last_update_height)
+        .collect();
+    heights.sort();
+    assert_eq!(heights, expected_heights);
+}
+
+#[tokio::test]
+async fn get_deposits_rejects_reversed_height_window() {
+    let configuration = clean_setup().await;
+
+    let response = apis::deposit_api::get_deposits(
+        &configuration,
+        Status::Accepted,
+        None,
+        None,
+        Some(20),
+        Some(10),
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
+#[test_case("fromHeight=-1"; "negative from height")]
+#[test_case("toHeight=18446744073709551616"; "to height overflow")]
+#[tokio::test]
+async fn get_deposits_rejects_out_of_range_heights(height_query: &str) {
+    let configuration = clean_setup().await;
+
+    let response = configuration
+        .client
+        .get(format!(
+            "{}/deposit?status=accepted&{height_query}",
+            configuration.base_path
+        ))
+        .send()
+        .await
+        .expect("Failed to send the get deposits request.");
+
+    assert_eq!(response.status(), 400);
+}
+
+#[test_case(None, 401; "missing_key")]
+#[test_case(Some("wrong_api_key"), 401; "wrong_key")]
+#[tokio::test]
+async fn update_deposits_requires_known_api_key(api_key: Option<&str>, expected_status: u16) {
+    let mut configuration = clean_setup().await;
+    configuration.api_key = api_key.map(|key| ApiKey {
+        prefix: None,
+        key: key.to_string(),
+    });
+
+    let response = apis::deposit_api::update_deposits(
+        &configuration,
+        UpdateDepositsRequestBody { deposits: vec![] },
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, expected_status);
+        }
+        e => panic!("Expected a {expected_status} error, got {e}"),
+    }
+}
+
 #[tokio::test]
 async fn get_deposits_for_recipient() {
     let configuration = clean_setup().await;
@@ -520,6 +723,7 @@ async fn get_deposits_for_recipient() {
             let response = apis::deposit_api::get_deposits_for_recipient(
                 &configuration,
                 recipient,
+                None,
                 next_token.as_deref(),
                 Some(chunksize),
             )
@@ -551,6 +755,190 @@ async fn get_deposits_for_recipient() {
     }
 }
 
+#[tokio::test]
+async fn get_deposits_for_recipient_filters_by_status() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let statuses = [Status::Pending, Status::Accepted, Status::Failed];
+    let amounts = vec![DEPOSIT_AMOUNT_SATS; statuses.len()];
+    let DepositTxnData {
+        recipients,
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+    } = DepositTxnData::new_with_recipient(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts, 1);
+    let recipient = recipients.first().unwrap().clone();
+
+    let create_requests = (0..statuses.len())
+        .map(|index| CreateDepositRequestBody {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts[index].clone(),
+            reclaim_script: reclaim_scripts[index].clone(),
+            transaction_hex: transaction_hex.clone(),
+        })
+        .collect();
+    batch_create_deposits(&configuration, create_requests).await;
+
+    let deposits = statuses
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, status)| DepositUpdate {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            fulfillment: None,
+            status: *status,
+            status_message: "foo".into(),
+            updated_by: None,
+        })
+        .collect();
+    apis::deposit_api::update_deposits(&configuration, UpdateDepositsRequestBody { deposits })
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // A valid principal that never received a deposit.
+    let other_recipient = hex::encode(
+        clarity::vm::types::PrincipalData::from(StacksAddress {
+            version: 0,
+            bytes: stacks_common::util::hash::Hash160([2; 20]),
+        })
+        .serialize_to_vec(),
+    );
+
+    // Act & Assert.
+    // -------------
+    let response =
+        apis::deposit_api::get_deposits_for_recipient(&configuration, &recipient, None, None, None)
+            .await
+            .expect("Received an error after making a valid get deposits for recipient api call.");
+    assert_eq!(response.deposits.len(), statuses.len());
+    assert!(response.deposits.iter().all(|d| d.recipient == recipient));
+
+    for (index, status) in statuses.iter().enumerate() {
+        let response = apis::deposit_api::get_deposits_for_recipient(
+            &configuration,
+            &recipient,
+            Some(*status),
+            None,
+            None,
+        )
+        .await
+        .expect("Received an error after making a valid get deposits for recipient api call.");
+        assert_eq!(response.deposits.len(), 1);
+        assert_eq!(response.deposits[0].bitcoin_tx_output_index, index as u32);
+        assert_eq!(response.deposits[0].status, *status);
+    }
+
+    let response = apis::deposit_api::get_deposits_for_recipient(
+        &configuration,
+        &recipient,
+        Some(Status::Confirmed),
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits for recipient api call.");
+    assert!(response.deposits.is_empty());
+
+    let response = apis::deposit_api::get_deposits_for_recipient(
+        &configuration,
+        &other_recipient,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits for recipient api call.");
+    assert!(response.deposits.is_empty());
+    assert!(response.next_token.flatten().is_none());
+
+    let response = apis::deposit_api::get_deposits_for_recipient(
+        &configuration,
+        "not-a-principal",
+        None,
+        None,
+        None,
+    )
+    .await;
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
+#[tokio::test]
+async fn lookup_deposits_returns_found_and_missing_keys() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let amounts = [DEPOSIT_AMOUNT_SATS; 2];
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &amounts);
+
+    let create_requests = (0..amounts.len())
+        .map(|index| CreateDepositRequestBody {
+            bitcoin_tx_output_index: index as u32,
+            bitcoin_txid: bitcoin_txid.clone(),
+            deposit_script: deposit_scripts[index].clone(),
+            reclaim_script: reclaim_scripts[index].clone(),
+            transaction_hex: transaction_hex.clone(),
+        })
+        .collect();
+    let created_deposits = batch_create_deposits(&configuration, create_requests).await;
+
+    // Ask for the second deposit, a missing output of the same
+    // transaction, and then the first deposit.
+    let missing_key = DepositKey::new(5, bitcoin_txid.clone());
+    let request = LookupDepositsRequestBody::new(vec![
+        DepositKey::new(1, bitcoin_txid.clone()),
+        missing_key.clone(),
+        DepositKey::new(0, bitcoin_txid.clone()),
+    ]);
+
+    // Act.
+    // ----
+    let response = apis::deposit_api::lookup_deposits(&configuration, request)
+        .await
+        .expect("Received an error after making a valid lookup deposits api call.");
+
+    // Assert.
+    // -------
+    let expected_deposits = vec![created_deposits[1].clone(), created_deposits[0].clone()];
+    assert_eq!(response.deposits, expected_deposits);
+    assert_eq!(response.not_found, vec![missing_key]);
+}
+
+#[tokio::test]
+async fn lookup_deposits_rejects_too_many_keys() {
+    let configuration = clean_setup().await;
+
+    let keys = (0..=100)
+        .map(|index| DepositKey::new(index, "txid".to_string()))
+        .collect();
+    let response =
+        apis::deposit_api::lookup_deposits(&configuration, LookupDepositsRequestBody::new(keys))
+            .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
 #[tokio::test]
 async fn get_deposits_for_reclaim_pubkeys() {
     let configuration = clean_setup().await;
@@ -756,6 +1144,7 @@ async fn update_deposits() {
                 fulfillment: Some(Some(Box::new(update_fulfillment.clone()))),
                 status: update_status,
                 status_message: update_status_message.into(),
+                updated_by: None,
             };
             deposit_updates.push(deposit_update);
 
@@ -805,6 +1194,422 @@ async fn update_deposits() {
     assert_eq!(expected_deposits, updated_deposits);
 }
 
+/// Updates the status of a single deposit using the trusted testing key.
+async fn update_deposit_status(
+    configuration: &Configuration,
+    request: &CreateDepositRequestBody,
+    status: Status,
+    status_message: &str,
+) {
+    let fulfillment = (status == Status::Confirmed).then(|| {
+        Some(Box::new(Fulfillment {
+            bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+            bitcoin_block_height: 23,
+            bitcoin_tx_index: 45,
+            bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+            btc_fee: 2314,
+            stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+        }))
+    });
+    apis::deposit_api::update_deposits(
+        configuration,
+        UpdateDepositsRequestBody {
+            deposits: vec![DepositUpdate {
+                bitcoin_tx_output_index: request.bitcoin_tx_output_index,
+                bitcoin_txid: request.bitcoin_txid.clone(),
+                fulfillment,
+                status,
+                status_message: status_message.into(),
+                updated_by: None,
+            }],
+        },
+    )
+    .await
+    .expect("Received an error after making a valid update deposits api call.");
+}
+
+/// Gets the full history of a deposit, without pagination.
+async fn get_full_deposit_history(
+    configuration: &Configuration,
+    request: &CreateDepositRequestBody,
+) -> Vec<StatusHistoryEntry> {
+    let response = apis::deposit_api::get_deposit_history(
+        configuration,
+        &request.bitcoin_txid,
+        &request.bitcoin_tx_output_index.to_string(),
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposit history api call.");
+    assert_eq!(response.next_token.flatten(), None);
+    response.history
+}
+
+/// Makes the history entry expected for an event that happened when the
+/// chain tip was the given test chainstate.
+fn new_history_entry(status: Status, message: &str, chainstate: &Chainstate) -> StatusHistoryEntry {
+    StatusHistoryEntry {
+        message: message.into(),
+        stacks_block_hash: chainstate.stacks_block_hash.clone(),
+        stacks_block_height: chainstate.stacks_block_height,
+        status,
+        updated_by: None,
+    }
+}
+
+#[tokio::test]
+async fn deposit_history_follows_status_transitions_across_reorgs() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let request = new_create_deposit_request(DEPOSIT_AMOUNT_SATS);
+    let chainstates: Vec<Chainstate> = (10..=12)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+    let reorged_chainstates: Vec<Chainstate> = (10..=12)
+        .map(|height| new_test_chainstate(height, height, 1))
+        .collect();
+
+    // Act.
+    // ----
+    // Walk the deposit from pending to confirmed, one block at a time.
+    set_chainstate(&configuration, chainstates[0].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    apis::deposit_api::create_deposit(&configuration, request.clone())
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+    set_chainstate(&configuration, chainstates[1].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    update_deposit_status(&configuration, &request, Status::Accepted, "accepted").await;
+    set_chainstate(&configuration, chainstates[2].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    update_deposit_status(&configuration, &request, Status::Confirmed, "confirmed").await;
+    let confirmed_history = get_full_deposit_history(&configuration, &request).await;
+
+    // Reorg away every block that the deposit has been seen in.
+    set_chainstate(&configuration, reorged_chainstates[0].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    let reorged_history = get_full_deposit_history(&configuration, &request).await;
+
+    // Walk the deposit to confirmed again on the new fork.
+    set_chainstate(&configuration, reorged_chainstates[1].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    update_deposit_status(&configuration, &request, Status::Accepted, "accepted again").await;
+    set_chainstate(&configuration, reorged_chainstates[2].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    update_deposit_status(
+        &configuration,
+        &request,
+        Status::Confirmed,
+        "confirmed again",
+    )
+    .await;
+    let reconfirmed_history = get_full_deposit_history(&configuration, &request).await;
+
+    // Assert.
+    // -------
+    let expected_confirmed_history = vec![
+        new_history_entry(
+            Status::Pending,
+            INITIAL_DEPOSIT_STATUS_MESSAGE,
+            &chainstates[0],
+        ),
+        new_history_entry(Status::Accepted, "accepted", &chainstates[1]),
+        new_history_entry(Status::Confirmed, "confirmed", &chainstates[2]),
+    ];
+    assert_eq!(confirmed_history, expected_confirmed_history);
+
+    let reprocessing_entry = new_history_entry(
+        Status::Pending,
+        "Reprocessing deposit status after reorg.",
+        &reorged_chainstates[0],
+    );
+    assert_eq!(reorged_history, vec![reprocessing_entry.clone()]);
+
+    let expected_reconfirmed_history = vec![
+        reprocessing_entry,
+        new_history_entry(Status::Accepted, "accepted again", &reorged_chainstates[1]),
+        new_history_entry(
+            Status::Confirmed,
+            "confirmed again",
+            &reorged_chainstates[2],
+        ),
+    ];
+    assert_eq!(reconfirmed_history, expected_reconfirmed_history);
+}
+
+#[tokio::test]
+async fn deposit_history_is_paginated() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let request = new_create_deposit_request(DEPOSIT_AMOUNT_SATS);
+    let chainstates: Vec<Chainstate> = (10..=12)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+
+    set_chainstate(&configuration, chainstates[0].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    apis::deposit_api::create_deposit(&configuration, request.clone())
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+    set_chainstate(&configuration, chainstates[1].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    update_deposit_status(&configuration, &request, Status::Accepted, "accepted").await;
+    set_chainstate(&configuration, chainstates[2].clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    update_deposit_status(&configuration, &request, Status::Confirmed, "confirmed").await;
+
+    let txid = request.bitcoin_txid.clone();
+    let index = request.bitcoin_tx_output_index.to_string();
+
+    // Act.
+    // ----
+    let first_page =
+        apis::deposit_api::get_deposit_history(&configuration, &txid, &index, None, Some(2))
+            .await
+            .expect("Received an error after making a valid get deposit history api call.");
+    let next_token = first_page
+        .next_token
+        .clone()
+        .flatten()
+        .expect("The first page should point at the rest of the history.");
+    let second_page = apis::deposit_api::get_deposit_history(
+        &configuration,
+        &txid,
+        &index,
+        Some(&next_token),
+        Some(2),
+    )
+    .await
+    .expect("Received an error after making a valid get deposit history api call.");
+
+    // Assert.
+    // -------
+    let full_history = get_full_deposit_history(&configuration, &request).await;
+    assert_eq!(full_history.len(), 3);
+    assert_eq!(first_page.history, full_history[..2]);
+    assert_eq!(second_page.history, full_history[2..]);
+    assert_eq!(second_page.next_token.flatten(), None);
+}
+
+#[tokio::test]
+async fn deposit_history_rejects_malformed_next_token() {
+    let configuration = clean_setup().await;
+
+    let request = new_create_deposit_request(DEPOSIT_AMOUNT_SATS);
+    apis::deposit_api::create_deposit(&configuration, request.clone())
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    let response = apis::deposit_api::get_deposit_history(
+        &configuration,
+        &request.bitcoin_txid,
+        &request.bitcoin_tx_output_index.to_string(),
+        Some("not a number"),
+        None,
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
+#[tokio::test]
+async fn get_missing_deposit_history_is_not_found() {
+    let configuration = clean_setup().await;
+
+    // Act.
+    // ----
+    let bitcoin_txid = "ab".repeat(32);
+    let attempted_get: NotFoundError =
+        apis::deposit_api::get_deposit_history(&configuration, &bitcoin_txid, "0", None, None)
+            .await
+            .expect_err("Received a successful response attempting to access a nonpresent deposit.")
+            .into();
+
+    // Assert.
+    // -------
+    assert_eq!(attempted_get.status_code, 404);
+    assert_eq!(attempted_get.body.resource, "deposit");
+    assert_eq!(attempted_get.body.key, format!("{bitcoin_txid}:0"));
+}
+
+/// Makes a create deposit request for a new single output deposit
+/// transaction with the given amount.
+fn new_create_deposit_request(amount: u64) -> CreateDepositRequestBody {
+    let DepositTxnData {
+        reclaim_scripts,
+        deposit_scripts,
+        bitcoin_txid,
+        transaction_hex,
+        ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, &[amount]);
+    CreateDepositRequestBody {
+        bitcoin_tx_output_index: 0,
+        bitcoin_txid,
+        reclaim_script: reclaim_scripts.first().unwrap().clone(),
+        deposit_script: deposit_scripts.first().unwrap().clone(),
+        transaction_hex,
+    }
+}
+
+/// Makes global limits with only the given deposit limits set.
+fn new_deposit_limits(
+    per_deposit_minimum: Option<u64>,
+    per_deposit_cap: Option<u64>,
+    rolling_deposit_blocks: Option<u64>,
+    rolling_deposit_cap: Option<u64>,
+) -> Limits {
+    Limits {
+        available_to_deposit: Some(None),
+        available_to_withdraw: Some(None),
+        peg_cap: Some(None),
+        per_deposit_minimum: Some(per_deposit_minimum),
+        per_deposit_cap: Some(per_deposit_cap),
+        per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(rolling_deposit_blocks),
+        rolling_deposit_cap: Some(rolling_deposit_cap),
+        rolling_withdrawal_blocks: Some(None),
+        rolling_withdrawal_cap: Some(None),
+        account_caps: HashMap::new(),
+    }
+}
+
+#[test_case(None, None, None; "unlimited")]
+#[test_case(Some(DEPOSIT_AMOUNT_SATS), None, None; "at_minimum")]
+#[test_case(Some(DEPOSIT_AMOUNT_SATS + 1), None, Some(("perDepositMinimum", DEPOSIT_AMOUNT_SATS + 1)); "below_minimum")]
+#[test_case(None, Some(DEPOSIT_AMOUNT_SATS), None; "at_cap")]
+#[test_case(None, Some(DEPOSIT_AMOUNT_SATS - 1), Some(("perDepositCap", DEPOSIT_AMOUNT_SATS - 1)); "above_cap")]
+#[tokio::test]
+async fn create_deposit_enforces_per_deposit_limits(
+    per_deposit_minimum: Option<u64>,
+    per_deposit_cap: Option<u64>,
+    expected_error: Option<(&str, u64)>,
+) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let limits = new_deposit_limits(per_deposit_minimum, per_deposit_cap, None, None);
+    apis::limits_api::set_limits(&configuration, limits)
+        .await
+        .expect("Received an error after making a valid set limits api call.");
+
+    // Act.
+    // ----
+    let result = apis::deposit_api::create_deposit(
+        &configuration,
+        new_create_deposit_request(DEPOSIT_AMOUNT_SATS),
+    )
+    .await;
+
+    // Assert.
+    // -------
+    match expected_error {
+        None => {
+            result.expect("Received an error creating a deposit within the limits.");
+        }
+        Some((limit, value)) => {
+            let error: TestError<LimitExceededErrorResponse> = result
+                .expect_err("Created a deposit outside of the limits.")
+                .into();
+            assert_eq!(error.status_code, 400);
+            assert_eq!(error.body.error, "limit_exceeded");
+            assert_eq!(error.body.limit, limit);
+            assert_eq!(error.body.value, value);
+        }
+    }
+}
+
+#[tokio::test]
+async fn create_deposit_enforces_rolling_deposit_cap() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let chainstates: Vec<Chainstate> = (0..=10)
+        .map(|height| new_test_chainstate(height, height, 0))
+        .collect();
+    let chaintip = chainstates.last().unwrap().clone();
+    batch_set_chainstates(&configuration, chainstates).await;
+
+    let rolling_deposit_cap = 2 * DEPOSIT_AMOUNT_SATS;
+    let limits = new_deposit_limits(None, None, Some(5), Some(rolling_deposit_cap));
+    apis::limits_api::set_limits(&configuration, limits)
+        .await
+        .expect("Received an error after making a valid set limits api call.");
+
+    // Confirm one deposit so that it counts towards the rolling cap.
+    let confirmed_request = new_create_deposit_request(DEPOSIT_AMOUNT_SATS);
+    let update_request = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index: confirmed_request.bitcoin_tx_output_index,
+            bitcoin_txid: confirmed_request.bitcoin_txid.clone(),
+            fulfillment: Some(Some(Box::new(Fulfillment {
+                bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+                bitcoin_block_height: chaintip.stacks_block_height,
+                bitcoin_tx_index: 0,
+                bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+                btc_fee: 0,
+                stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+            }))),
+            status: Status::Confirmed,
+            status_message: "confirmed".into(),
+            updated_by: None,
+        }],
+    };
+    apis::deposit_api::create_deposit(&configuration, confirmed_request)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+    apis::deposit_api::update_deposits(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // Act.
+    // ----
+    let limits = apis::limits_api::get_limits(&configuration)
+        .await
+        .expect("Received an error after making a valid get limits api call.");
+    let over_cap = apis::deposit_api::create_deposit(
+        &configuration,
+        new_create_deposit_request(DEPOSIT_AMOUNT_SATS + 1),
+    )
+    .await;
+    let at_cap = apis::deposit_api::create_deposit(
+        &configuration,
+        new_create_deposit_request(DEPOSIT_AMOUNT_SATS),
+    )
+    .await;
+
+    // Assert.
+    // -------
+    assert_eq!(limits.available_to_deposit, Some(Some(DEPOSIT_AMOUNT_SATS)));
+    let error: TestError<LimitExceededErrorResponse> = over_cap
+        .expect_err("Created a deposit above the rolling deposit cap.")
+        .into();
+    assert_eq!(error.status_code, 400);
+    assert_eq!(error.body.limit, "rollingDepositCap");
+    assert_eq!(error.body.value, DEPOSIT_AMOUNT_SATS);
+    at_cap.expect("Received an error creating a deposit at the rolling deposit cap.");
+}
+
 #[test_case(Status::Pending; "pending")]
 #[test_case(Status::Reprocessing; "reprocessing")]
 #[test_case(Status::Confirmed; "confirmed")]
@@ -872,6 +1677,7 @@ async fn create_deposit_handles_duplicates(status: Status) {
                 fulfillment,
                 status,
                 status_message: "foo".into(),
+                updated_by: None,
             }],
         },
     )
@@ -993,6 +1799,7 @@ async fn update_deposits_is_forbidden(
                     fulfillment,
                     status: previous_status,
                     status_message: "foo".into(),
+                    updated_by: None,
                 }],
             },
         )
@@ -1022,6 +1829,7 @@ async fn update_deposits_is_forbidden(
                 fulfillment,
                 status: new_status,
                 status_message: "foo".into(),
+                updated_by: None,
             }],
         },
     )
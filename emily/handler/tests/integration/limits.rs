@@ -17,11 +17,14 @@ async fn empty_default_is_as_expected() {
     let configuration = clean_setup().await;
 
     let expected_empty_default = models::Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(None),
         peg_cap: Some(None),
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(None),
         rolling_withdrawal_cap: Some(None),
         account_caps: HashMap::new(),
@@ -48,6 +51,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(100)),
                 per_deposit_cap: Some(Some(100)),
                 per_withdrawal_cap: Some(Some(100)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(100)),
                 rolling_withdrawal_cap: Some(Some(100)),
             },
@@ -59,6 +64,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(1200)),
                 per_deposit_cap: Some(Some(1200)),
                 per_withdrawal_cap: Some(Some(1200)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(1200)),
                 rolling_withdrawal_cap: Some(Some(1200)),
             },
@@ -70,6 +77,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(200)),
                 per_deposit_cap: Some(Some(300)),
                 per_withdrawal_cap: Some(Some(500)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(600)),
                 rolling_withdrawal_cap: Some(Some(700)),
             },
@@ -81,6 +90,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(200)),
                 per_deposit_cap: Some(Some(200)),
                 per_withdrawal_cap: Some(Some(200)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(200)),
                 rolling_withdrawal_cap: Some(Some(200)),
             },
@@ -92,6 +103,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(300)),
                 per_deposit_cap: Some(Some(300)),
                 per_withdrawal_cap: Some(Some(300)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(300)),
                 rolling_withdrawal_cap: Some(Some(300)),
             },
@@ -108,6 +121,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(200)),
                 per_deposit_cap: Some(Some(200)),
                 per_withdrawal_cap: Some(Some(200)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(200)),
                 rolling_withdrawal_cap: Some(Some(200)),
             },
@@ -119,6 +134,8 @@ async fn adding_and_then_updating_single_accout_limit_works() {
                 per_deposit_minimum: Some(Some(300)),
                 per_deposit_cap: Some(Some(300)),
                 per_withdrawal_cap: Some(Some(300)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(300)),
                 rolling_withdrawal_cap: Some(Some(300)),
             },
@@ -130,11 +147,14 @@ async fn adding_and_then_updating_single_accout_limit_works() {
 
     // The global limits should show the latest account caps.
     let expected_limits = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(None),
         peg_cap: Some(None),
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(None),
         rolling_withdrawal_cap: Some(None),
         account_caps: expected_account_caps.clone(),
@@ -189,6 +209,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(100)),
                 per_deposit_cap: Some(Some(100)),
                 per_withdrawal_cap: Some(Some(100)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(100)),
                 rolling_withdrawal_cap: Some(Some(100)),
             },
@@ -200,6 +222,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(150)),
                 per_deposit_cap: Some(Some(150)),
                 per_withdrawal_cap: Some(Some(150)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(150)),
                 rolling_withdrawal_cap: Some(Some(150)),
             },
@@ -211,6 +235,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(150)),
                 per_deposit_cap: Some(Some(150)),
                 per_withdrawal_cap: Some(Some(150)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(150)),
                 rolling_withdrawal_cap: Some(Some(150)),
             },
@@ -227,6 +253,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(200)),
                 per_deposit_cap: Some(Some(200)),
                 per_withdrawal_cap: Some(Some(200)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(200)),
                 rolling_withdrawal_cap: Some(Some(200)),
             },
@@ -238,6 +266,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(300)),
                 per_deposit_cap: Some(Some(300)),
                 per_withdrawal_cap: Some(Some(300)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(300)),
                 rolling_withdrawal_cap: Some(Some(300)),
             },
@@ -251,6 +281,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(None),
                 per_deposit_cap: Some(None),
                 per_withdrawal_cap: Some(None),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(None),
                 rolling_withdrawal_cap: Some(None),
             },
@@ -260,11 +292,14 @@ async fn test_updating_account_limits_via_global_limit_works() {
     .map(|(account_name, limits)| (account_name.to_string(), limits.clone()))
     .collect();
     let global_limits_to_set = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(None),
         peg_cap: Some(Some(123)),
         per_deposit_minimum: Some(Some(654)),
         per_deposit_cap: Some(Some(456)),
         per_withdrawal_cap: Some(Some(789)),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(Some(101)),
         rolling_withdrawal_cap: Some(Some(112)),
         account_caps: account_limits_to_set_globally.clone(),
@@ -280,6 +315,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(100)),
                 per_deposit_cap: Some(Some(100)),
                 per_withdrawal_cap: Some(Some(100)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(100)),
                 rolling_withdrawal_cap: Some(Some(100)),
             },
@@ -291,6 +328,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(200)),
                 per_deposit_cap: Some(Some(200)),
                 per_withdrawal_cap: Some(Some(200)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(200)),
                 rolling_withdrawal_cap: Some(Some(200)),
             },
@@ -302,6 +341,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
                 per_deposit_minimum: Some(Some(300)),
                 per_deposit_cap: Some(Some(300)),
                 per_withdrawal_cap: Some(Some(300)),
+                rolling_deposit_blocks: Some(None),
+                rolling_deposit_cap: Some(None),
                 rolling_withdrawal_blocks: Some(Some(300)),
                 rolling_withdrawal_cap: Some(Some(300)),
             },
@@ -311,11 +352,14 @@ async fn test_updating_account_limits_via_global_limit_works() {
     .map(|(account_name, limits)| (account_name.to_string(), limits.clone()))
     .collect();
     let expected_global_limits = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(Some(112)),
         peg_cap: Some(Some(123)),
         per_deposit_minimum: Some(Some(654)),
         per_deposit_cap: Some(Some(456)),
         per_withdrawal_cap: Some(Some(789)),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(Some(101)),
         rolling_withdrawal_cap: Some(Some(112)),
         account_caps: expected_global_account_limits.clone(),
@@ -373,6 +417,8 @@ async fn test_updating_account_limits_via_global_limit_works() {
 #[test_case(None, Some(100))]
 #[tokio::test]
 async fn test_incomplete_rolling_withdrawal_limit_config_returns_error(
+    rolling_deposit_blocks: Some(None),
+    rolling_deposit_cap: Some(None),
     rolling_withdrawal_blocks: Option<u64>,
     rolling_withdrawal_cap: Option<u64>,
 ) {
@@ -380,11 +426,14 @@ async fn test_incomplete_rolling_withdrawal_limit_config_returns_error(
 
     // Arrange.
     let limits = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(None),
         peg_cap: Some(None),
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(rolling_withdrawal_blocks),
         rolling_withdrawal_cap: Some(rolling_withdrawal_cap),
         account_caps: HashMap::new(),
@@ -404,17 +453,22 @@ async fn test_incomplete_rolling_withdrawal_limit_config_returns_error(
 #[test_case(None, None)]
 #[tokio::test]
 async fn test_complete_rolling_withdrawal_limit_config_works(
+    rolling_deposit_blocks: Some(None),
+    rolling_deposit_cap: Some(None),
     rolling_withdrawal_blocks: Option<u64>,
     rolling_withdrawal_cap: Option<u64>,
 ) {
     let configuration = clean_setup().await;
 
     let limits = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(rolling_withdrawal_cap),
         peg_cap: Some(None),
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(rolling_withdrawal_blocks),
         rolling_withdrawal_cap: Some(rolling_withdrawal_cap),
         account_caps: HashMap::new(),
@@ -443,11 +497,14 @@ async fn test_available_to_withdraw_no_chainstate_in_db_at_target_height() {
 
     // Set limits
     let limits = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(None),
         peg_cap: Some(None),
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(Some(100)),
         rolling_withdrawal_cap: Some(Some(10_000)),
         account_caps: HashMap::new(),
@@ -474,7 +531,7 @@ async fn test_available_to_withdraw_no_chainstate_in_db_at_target_height() {
     let request = CreateWithdrawalRequestBody {
         amount: 1000,
         parameters: Box::new(WithdrawalParameters { max_fee: 100 }),
-        recipient: "test_recepient".into(),
+        recipient: "0014abababababababababababababababababababab".into(),
         sender: "test_sender".into(),
         request_id: 1,
         stacks_block_hash: "test_hash".into(),
@@ -499,11 +556,14 @@ async fn test_available_to_withdraw_success() {
 
     // Set limits
     let limits = Limits {
+        available_to_deposit: Some(None),
         available_to_withdraw: Some(None),
         peg_cap: Some(None),
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_deposit_blocks: Some(None),
+        rolling_deposit_cap: Some(None),
         rolling_withdrawal_blocks: Some(Some(10)),
         rolling_withdrawal_cap: Some(Some(10_000)),
         account_caps: HashMap::new(),
@@ -549,7 +609,7 @@ async fn test_available_to_withdraw_success() {
         let request = CreateWithdrawalRequestBody {
             amount,
             parameters: Box::new(WithdrawalParameters { max_fee: 100 }),
-            recipient: "test_recepient".into(),
+            recipient: "0014abababababababababababababababababababab".into(),
             sender: "test_sender".into(),
             request_id: stacks_height,
             stacks_block_hash: "test_hash".into(),
@@ -10,5 +10,7 @@ pub mod deposit;
 pub mod limits;
 /// New block test module.
 pub mod new_block;
+/// Subscription test module.
+pub mod subscription;
 /// Withdrawal test module.
 pub mod withdrawal;
@@ -158,7 +158,7 @@ async fn test_new_blocks_sends_withdrawal_accept_update() {
     let withdrawal_request = CreateWithdrawalRequestBody {
         amount: 100,
         parameters: Box::new(WithdrawalParameters { max_fee: 10 }),
-        recipient: ScriptBuf::new_p2pk(&pubkey).to_hex_string(),
+        recipient: ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()).to_hex_string(),
         sender: "SN1Z0WW5SMN4J99A1G1725PAB8H24CWNA7Z8H7214.my-contract".to_string(),
         request_id: withdrawal_accept_event.request_id,
         stacks_block_hash: withdrawal_accept_event.block_id.to_hex(),
@@ -202,7 +202,7 @@ async fn test_new_blocks_sends_withdrawal_reject_update() {
     let withdrawal_request = CreateWithdrawalRequestBody {
         amount: 100,
         parameters: Box::new(WithdrawalParameters { max_fee: 10 }),
-        recipient: ScriptBuf::new_p2pk(&pubkey).to_hex_string(),
+        recipient: ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()).to_hex_string(),
         sender: "SN1Z0WW5SMN4J99A1G1725PAB8H24CWNA7Z8H7214.my-contract".to_string(),
         request_id: withdrawal_reject_event.request_id,
         stacks_block_hash: withdrawal_reject_event.block_id.to_hex(),
@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex};
+
+use test_case::test_case;
+use warp::Filter;
+use warp::http::StatusCode;
+
+use testing_emily_client::apis::chainstate_api::set_chainstate;
+use testing_emily_client::apis::configuration::ApiKey;
+use testing_emily_client::apis::{self, ResponseContent};
+use testing_emily_client::models::{
+    Chainstate, CreateWithdrawalRequestBody, Status, Subscription, SubscriptionOperation,
+    UpdateWithdrawalsRequestBody, WithdrawalParameters, WithdrawalUpdate,
+};
+
+use crate::common::clean_setup;
+
+/// The bodies of the notifications received by a mocked subscriber.
+type Received = Arc<Mutex<Vec<serde_json::Value>>>;
+
+/// Starts a mocked subscriber that records every notification it gets and
+/// responds with the given status, and returns its URL.
+fn mock_subscriber(status: StatusCode) -> (String, Received) {
+    let received: Received = Arc::default();
+    let state = received.clone();
+    let route = warp::post()
+        .and(warp::body::json())
+        .map(move |body: serde_json::Value| {
+            state.lock().unwrap().push(body);
+            status
+        });
+    let (address, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    (format!("http://{address}/notify"), received)
+}
+
+#[tokio::test]
+async fn failing_subscriber_does_not_fail_the_update() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let (failing_url, _) = mock_subscriber(StatusCode::INTERNAL_SERVER_ERROR);
+    let (url, received) = mock_subscriber(StatusCode::OK);
+    for url in [failing_url, url] {
+        apis::subscription_api::create_subscription(
+            &configuration,
+            Subscription::new(SubscriptionOperation::Withdrawal, url),
+        )
+        .await
+        .expect("Received an error after making a valid create subscription api call.");
+    }
+
+    let chainstate = Chainstate {
+        stacks_block_hash: "test_block_hash".to_string(),
+        stacks_block_height: 1,
+        bitcoin_block_height: Some(Some(1)),
+    };
+    set_chainstate(&configuration, chainstate.clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+
+    let request_id = 1;
+    let request = CreateWithdrawalRequestBody {
+        amount: 10000,
+        parameters: Box::new(WithdrawalParameters { max_fee: 100 }),
+        recipient: "0014abababababababababababababababababababab".into(),
+        sender: "TEST_SENDER".into(),
+        request_id,
+        stacks_block_hash: chainstate.stacks_block_hash.clone(),
+        stacks_block_height: chainstate.stacks_block_height,
+        txid: "test_txid".to_string(),
+    };
+    apis::withdrawal_api::create_withdrawal(&configuration, request)
+        .await
+        .expect("Received an error after making a valid create withdrawal request api call.");
+
+    // Act.
+    // ----
+    let response = apis::withdrawal_api::update_withdrawals(
+        &configuration,
+        UpdateWithdrawalsRequestBody {
+            withdrawals: vec![WithdrawalUpdate {
+                request_id,
+                fulfillment: None,
+                status: Status::Accepted,
+                status_message: "foo".into(),
+                updated_by: None,
+            }],
+        },
+    )
+    .await
+    .expect("Received an error after making a valid update withdrawal api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(response.withdrawals.len(), 1);
+    assert_eq!(response.withdrawals[0].status, Status::Accepted);
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0]["operation"], "withdrawal");
+    assert_eq!(received[0]["resource"]["requestId"], request_id);
+    assert_eq!(received[0]["resource"]["status"], "accepted");
+}
+
+#[tokio::test]
+async fn deleting_a_missing_subscription_is_not_found() {
+    let configuration = clean_setup().await;
+
+    let response = apis::subscription_api::delete_subscription(
+        &configuration,
+        "http://localhost:1234/notify",
+        SubscriptionOperation::Deposit,
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 404);
+        }
+        e => panic!("Expected a 404 error, got {e}"),
+    }
+}
+
+#[tokio::test]
+async fn created_subscription_can_be_deleted() {
+    let configuration = clean_setup().await;
+    let url = "http://localhost:1234/notify";
+
+    apis::subscription_api::create_subscription(
+        &configuration,
+        Subscription::new(SubscriptionOperation::Deposit, url.to_string()),
+    )
+    .await
+    .expect("Received an error after making a valid create subscription api call.");
+
+    apis::subscription_api::delete_subscription(
+        &configuration,
+        url,
+        SubscriptionOperation::Deposit,
+    )
+    .await
+    .expect("Received an error after making a valid delete subscription api call.");
+}
+
+#[test_case(None, 401; "missing_key")]
+#[test_case(Some("wrong_api_key"), 401; "wrong_key")]
+#[tokio::test]
+async fn create_subscription_requires_known_api_key(api_key: Option<&str>, expected_status: u16) {
+    let mut configuration = clean_setup().await;
+    configuration.api_key = api_key.map(|key| ApiKey {
+        prefix: None,
+        key: key.to_string(),
+    });
+
+    let response = apis::subscription_api::create_subscription(
+        &configuration,
+        Subscription::new(
+            SubscriptionOperation::Deposit,
+            "http://localhost:1234/notify".to_string(),
+        ),
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, expected_status);
+        }
+        e => panic!("Expected a {expected_status} error, got {e}"),
+    }
+}
@@ -7,13 +7,14 @@ use testing_emily_client::apis::chainstate_api::set_chainstate;
 use testing_emily_client::apis::configuration::{ApiKey, Configuration};
 use testing_emily_client::apis::{self, ResponseContent};
 use testing_emily_client::models::{
-    Chainstate, CreateWithdrawalRequestBody, Fulfillment, Status, UpdateWithdrawalsRequestBody,
-    Withdrawal, WithdrawalInfo, WithdrawalParameters, WithdrawalUpdate,
+    Chainstate, CreateWithdrawalRequestBody, Fulfillment, Status, StatusHistoryEntry,
+    UpdateWithdrawalsRequestBody, Withdrawal, WithdrawalInfo, WithdrawalParameters,
+    WithdrawalUpdate,
 };
 
-use crate::common::clean_setup;
+use crate::common::{NotFoundError, clean_setup, new_test_chainstate};
 
-const RECIPIENT: &str = "TEST_RECIPIENT";
+const RECIPIENT: &str = "0014abababababababababababababababababababab";
 const SENDER: &str = "TEST_SENDER";
 const BLOCK_HASH: &str = "TEST_BLOCK_HASH";
 const BLOCK_HEIGHT: u64 = 0;
@@ -112,6 +113,130 @@ async fn create_and_get_withdrawal_happy_path() {
     assert_eq!(expected, gotten);
 }
 
+#[tokio::test]
+async fn get_missing_withdrawal_is_not_found() {
+    let configuration = clean_setup().await;
+
+    // Act.
+    // ----
+    let attempted_get: NotFoundError = apis::withdrawal_api::get_withdrawal(&configuration, 404)
+        .await
+        .expect_err("Received a successful response attempting to access a nonpresent withdrawal.")
+        .into();
+
+    // Assert.
+    // -------
+    assert_eq!(attempted_get.status_code, 404);
+    assert_eq!(attempted_get.body.error, "not_found");
+    assert_eq!(attempted_get.body.resource, "withdrawal");
+    assert_eq!(attempted_get.body.key, "404");
+}
+
+#[tokio::test]
+async fn get_withdrawal_history() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let request_id = 1;
+    let created_chainstate = new_test_chainstate(10, 10, 0);
+    let accepted_chainstate = new_test_chainstate(11, 11, 0);
+
+    let request = CreateWithdrawalRequestBody {
+        amount: 0,
+        parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+        recipient: RECIPIENT.into(),
+        sender: SENDER.into(),
+        request_id,
+        stacks_block_hash: created_chainstate.stacks_block_hash.clone(),
+        stacks_block_height: created_chainstate.stacks_block_height,
+        txid: "test_txid".to_string(),
+    };
+    let update_request = UpdateWithdrawalsRequestBody {
+        withdrawals: vec![WithdrawalUpdate {
+            request_id,
+            fulfillment: None,
+            status: Status::Accepted,
+            status_message: "accepted".into(),
+            updated_by: None,
+        }],
+    };
+
+    // Act.
+    // ----
+    set_chainstate(&configuration, created_chainstate.clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    apis::withdrawal_api::create_withdrawal(&configuration, request)
+        .await
+        .expect("Received an error after making a valid create withdrawal request api call.");
+    set_chainstate(&configuration, accepted_chainstate.clone())
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    apis::withdrawal_api::update_withdrawals(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update withdrawals api call.");
+
+    let first_page =
+        apis::withdrawal_api::get_withdrawal_history(&configuration, request_id, None, Some(1))
+            .await
+            .expect("Received an error after making a valid get withdrawal history api call.");
+    let next_token = first_page
+        .next_token
+        .clone()
+        .flatten()
+        .expect("The first page should point at the rest of the history.");
+    let second_page = apis::withdrawal_api::get_withdrawal_history(
+        &configuration,
+        request_id,
+        Some(&next_token),
+        Some(1),
+    )
+    .await
+    .expect("Received an error after making a valid get withdrawal history api call.");
+
+    // Assert.
+    // -------
+    let expected_created = StatusHistoryEntry {
+        message: INITIAL_WITHDRAWAL_STATUS_MESSAGE.into(),
+        stacks_block_hash: created_chainstate.stacks_block_hash,
+        stacks_block_height: created_chainstate.stacks_block_height,
+        status: Status::Pending,
+        updated_by: None,
+    };
+    let expected_accepted = StatusHistoryEntry {
+        message: "accepted".into(),
+        stacks_block_hash: accepted_chainstate.stacks_block_hash,
+        stacks_block_height: accepted_chainstate.stacks_block_height,
+        status: Status::Accepted,
+        updated_by: None,
+    };
+    assert_eq!(first_page.history, vec![expected_created]);
+    assert_eq!(second_page.history, vec![expected_accepted]);
+    assert_eq!(second_page.next_token.flatten(), None);
+}
+
+#[tokio::test]
+async fn get_missing_withdrawal_history_is_not_found() {
+    let configuration = clean_setup().await;
+
+    // Act.
+    // ----
+    let attempted_get: NotFoundError =
+        apis::withdrawal_api::get_withdrawal_history(&configuration, 404, None, None)
+            .await
+            .expect_err(
+                "Received a successful response attempting to access a nonpresent withdrawal.",
+            )
+            .into();
+
+    // Assert.
+    // -------
+    assert_eq!(attempted_get.status_code, 404);
+    assert_eq!(attempted_get.body.resource, "withdrawal");
+    assert_eq!(attempted_get.body.key, "404");
+}
+
 #[tokio::test]
 async fn get_withdrawals() {
     let configuration = clean_setup().await;
@@ -203,13 +328,39 @@ async fn get_withdrawals() {
     assert_eq!(expected_withdrawal_infos, gotten_withdrawal_infos);
 }
 
+#[test_case("not base64!"; "invalid base64")]
+#[test_case("e30"; "missing keys")]
+#[tokio::test]
+async fn get_withdrawals_rejects_malformed_next_token(next_token: &str) {
+    let configuration = clean_setup().await;
+
+    let response = apis::withdrawal_api::get_withdrawals(
+        &configuration,
+        Status::Pending,
+        Some(next_token),
+        None,
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
 #[tokio::test]
 async fn get_withdrawals_by_recipient() {
     let configuration = clean_setup().await;
 
     // Arrange.
     // --------
-    let recipients = vec!["recipient_1", "recipient_2", "recipient_3"];
+    let recipients = vec![
+        "00141111111111111111111111111111111111111111",
+        "00142222222222222222222222222222222222222222",
+        "00143333333333333333333333333333333333333333",
+    ];
     let withdrawals_per_recipient = 5;
     let mut create_requests: Vec<CreateWithdrawalRequestBody> = Vec::new();
     let mut expected_recipient_data: HashMap<String, Vec<WithdrawalInfo>> = HashMap::new();
@@ -268,6 +419,7 @@ async fn get_withdrawals_by_recipient() {
             let response = apis::withdrawal_api::get_withdrawals_for_recipient(
                 &configuration,
                 recipient,
+                None,
                 next_token.as_deref(),
                 Some(chunksize as u32),
             )
@@ -302,6 +454,169 @@ async fn get_withdrawals_by_recipient() {
     }
 }
 
+/// The scriptPubKey of the recipient used in the recipient address tests,
+/// along with its regtest bech32 address.
+const RECIPIENT_SCRIPT_PUBKEY: &str = "0014751e76e8199196d454941c45d1b3a323f1433bd6";
+const RECIPIENT_ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+#[test_case(RECIPIENT_SCRIPT_PUBKEY; "script_pubkey")]
+#[test_case(RECIPIENT_ADDRESS; "address")]
+#[tokio::test]
+async fn get_withdrawals_for_recipient_accepts_script_pubkey_or_address(recipient: &str) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let create_requests: Vec<CreateWithdrawalRequestBody> = (1..=2)
+        .map(|request_id| CreateWithdrawalRequestBody {
+            amount: 0,
+            parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+            recipient: RECIPIENT_SCRIPT_PUBKEY.into(),
+            sender: SENDER.into(),
+            request_id,
+            stacks_block_hash: BLOCK_HASH.into(),
+            stacks_block_height: BLOCK_HEIGHT,
+            txid: "test_txid".to_string(),
+        })
+        .collect();
+    batch_create_withdrawals(&configuration, create_requests).await;
+
+    // Act.
+    // ----
+    let pending = apis::withdrawal_api::get_withdrawals_for_recipient(
+        &configuration,
+        recipient,
+        Some(Status::Pending),
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get withdrawal api call.");
+    let accepted = apis::withdrawal_api::get_withdrawals_for_recipient(
+        &configuration,
+        recipient,
+        Some(Status::Accepted),
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get withdrawal api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(pending.withdrawals.len(), 2);
+    assert!(
+        pending
+            .withdrawals
+            .iter()
+            .all(|withdrawal| withdrawal.recipient == RECIPIENT_SCRIPT_PUBKEY)
+    );
+    assert!(accepted.withdrawals.is_empty());
+}
+
+#[test_case(RECIPIENT_SCRIPT_PUBKEY; "script_pubkey")]
+#[test_case(RECIPIENT_ADDRESS; "address")]
+#[test_case("0014751E76E8199196d454941C45D1B3a323f1433BD6"; "mixed_case_script_pubkey")]
+#[tokio::test]
+async fn create_withdrawal_stores_canonical_recipient(recipient: &str) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let request = CreateWithdrawalRequestBody {
+        amount: 0,
+        parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+        recipient: recipient.into(),
+        sender: SENDER.into(),
+        request_id: 1,
+        stacks_block_hash: BLOCK_HASH.into(),
+        stacks_block_height: BLOCK_HEIGHT,
+        txid: "test_txid".to_string(),
+    };
+
+    // Act.
+    // ----
+    let created = apis::withdrawal_api::create_withdrawal(&configuration, request)
+        .await
+        .expect("Received an error after making a valid create withdrawal request api call.");
+    let gotten = apis::withdrawal_api::get_withdrawal(&configuration, 1)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(created.recipient, RECIPIENT_SCRIPT_PUBKEY);
+    assert_eq!(gotten.recipient, RECIPIENT_SCRIPT_PUBKEY);
+}
+
+#[test_case("not-a-recipient"; "neither_hex_nor_address")]
+#[test_case("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"; "mainnet_address")]
+#[test_case("6a0474657374"; "non_standard_script_pubkey")]
+#[tokio::test]
+async fn create_withdrawal_rejects_invalid_recipient(recipient: &str) {
+    let configuration = clean_setup().await;
+
+    let request = CreateWithdrawalRequestBody {
+        amount: 0,
+        parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+        recipient: recipient.into(),
+        sender: SENDER.into(),
+        request_id: 1,
+        stacks_block_hash: BLOCK_HASH.into(),
+        stacks_block_height: BLOCK_HEIGHT,
+        txid: "test_txid".to_string(),
+    };
+    let response = apis::withdrawal_api::create_withdrawal(&configuration, request).await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
+#[tokio::test]
+async fn get_withdrawals_for_recipient_without_withdrawals() {
+    let configuration = clean_setup().await;
+
+    let response = apis::withdrawal_api::get_withdrawals_for_recipient(
+        &configuration,
+        RECIPIENT_ADDRESS,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get withdrawal api call.");
+
+    assert!(response.withdrawals.is_empty());
+    assert!(response.next_token.flatten().is_none());
+}
+
+#[test_case("not-a-recipient"; "neither_hex_nor_address")]
+#[test_case("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"; "mainnet_address")]
+#[tokio::test]
+async fn get_withdrawals_for_recipient_rejects_invalid_recipient(recipient: &str) {
+    let configuration = clean_setup().await;
+
+    let response = apis::withdrawal_api::get_withdrawals_for_recipient(
+        &configuration,
+        recipient,
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    match response.unwrap_err() {
+        testing_emily_client::apis::Error::ResponseError(ResponseContent { status, .. }) => {
+            assert_eq!(status, 400);
+        }
+        e => panic!("Expected a 400 error, got {e}"),
+    }
+}
+
 #[tokio::test]
 async fn get_withdrawals_by_sender() {
     let configuration = clean_setup().await;
@@ -456,6 +771,7 @@ async fn update_withdrawals() {
             fulfillment: Some(Some(Box::new(update_fulfillment.clone()))),
             status: update_status,
             status_message: update_status_message.into(),
+            updated_by: None,
         };
         withdrawal_updates.push(withdrawal_update);
 
@@ -593,6 +909,7 @@ async fn update_withdrawals_is_forbidden(
                     fulfillment,
                     status: previous_status,
                     status_message: "foo".into(),
+                    updated_by: None,
                 }],
             },
         )
@@ -621,6 +938,7 @@ async fn update_withdrawals_is_forbidden(
                 fulfillment,
                 status: new_status,
                 status_message: "foo".into(),
+                updated_by: None,
             }],
         },
     )
@@ -15,14 +15,17 @@ use super::CorsSupport;
         api::handlers::health::get_health,
         // Deposit endpoints.
         api::handlers::deposit::get_deposit,
+        api::handlers::deposit::get_deposit_history,
         api::handlers::deposit::get_deposits_for_transaction,
         api::handlers::deposit::get_deposits_for_recipient,
         api::handlers::deposit::get_deposits_for_reclaim_pubkeys,
         api::handlers::deposit::get_deposits,
+        api::handlers::deposit::lookup_deposits,
         api::handlers::deposit::create_deposit,
         api::handlers::deposit::update_deposits,
         // Withdrawal endpoints.
         api::handlers::withdrawal::get_withdrawal,
+        api::handlers::withdrawal::get_withdrawal_history,
         api::handlers::withdrawal::get_withdrawals,
         api::handlers::withdrawal::get_withdrawals_for_recipient,
         api::handlers::withdrawal::get_withdrawals_for_sender,
@@ -30,6 +33,7 @@ use super::CorsSupport;
         // Chainstate endpoints.
         api::handlers::chainstate::get_chain_tip,
         api::handlers::chainstate::get_chainstate_at_height,
+        api::handlers::chainstate::get_chainstate_history,
         // Limits endpoints.
         api::handlers::limits::get_limits,
         api::handlers::limits::get_limits_for_account,
@@ -38,15 +42,21 @@ use super::CorsSupport;
     components(schemas(
         // Chainstate models.
         api::models::chainstate::Chainstate,
+        api::models::chainstate::ChainstateHistoryEntry,
+        api::models::chainstate::GetChainstateHistoryResponse,
         // Deposit models.
         api::models::deposit::Deposit,
         api::models::deposit::DepositParameters,
         api::models::deposit::DepositInfo,
         api::models::deposit::requests::CreateDepositRequestBody,
+        api::models::deposit::requests::DepositKey,
+        api::models::deposit::requests::LookupDepositsRequestBody,
         api::models::deposit::requests::DepositUpdate, // signers may update the state of deposits to Accepted.
         api::models::deposit::requests::UpdateDepositsRequestBody, // signers may update the state of deposits to Accepted.
+        api::models::deposit::responses::GetDepositHistoryResponse,
         api::models::deposit::responses::GetDepositsForTransactionResponse,
         api::models::deposit::responses::GetDepositsResponse,
+        api::models::deposit::responses::LookupDepositsResponse,
         api::models::deposit::responses::UpdateDepositsResponse, // signers may update the state of deposits to Accepted.
         // Withdrawal Models.
         api::models::withdrawal::Withdrawal,
@@ -54,6 +64,7 @@ use super::CorsSupport;
         api::models::withdrawal::WithdrawalParameters,
         api::models::withdrawal::requests::WithdrawalUpdate, // signers may update the state of withdrawals to Accepted.
         api::models::withdrawal::requests::UpdateWithdrawalsRequestBody, // signers may update the state of withdrawals to Accepted.
+        api::models::withdrawal::responses::GetWithdrawalHistoryResponse,
         api::models::withdrawal::responses::GetWithdrawalsResponse,
         api::models::withdrawal::responses::UpdateWithdrawalsResponse, // signers may update the state of withdrawals to Accepted.
         // Health check datatypes.
@@ -61,11 +72,15 @@ use super::CorsSupport;
         // Common models.
         api::models::common::Status,
         api::models::common::Fulfillment,
+        api::models::common::StatusHistoryEntry,
         // Limits models
         api::models::limits::Limits,
         api::models::limits::AccountLimits,
         // Errors.
         common::error::ErrorResponse,
+        common::error::NotFoundErrorResponse,
+        common::error::LimitExceededErrorResponse,
+        common::error::ChainGapErrorResponse,
     ))
 )]
 pub struct ApiDoc;
@@ -15,14 +15,17 @@ use super::CorsSupport;
         api::handlers::health::get_health,
         // Deposit endpoints.
         api::handlers::deposit::get_deposit,
+        api::handlers::deposit::get_deposit_history,
         api::handlers::deposit::get_deposits_for_transaction,
         api::handlers::deposit::get_deposits_for_recipient,
         api::handlers::deposit::get_deposits_for_reclaim_pubkeys,
         api::handlers::deposit::get_deposits,
+        api::handlers::deposit::lookup_deposits,
         api::handlers::deposit::create_deposit,
         api::handlers::deposit::update_deposits,
         // Withdrawal endpoints.
         api::handlers::withdrawal::get_withdrawal,
+        api::handlers::withdrawal::get_withdrawal_history,
         api::handlers::withdrawal::get_withdrawals,
         api::handlers::withdrawal::get_withdrawals_for_recipient,
         api::handlers::withdrawal::get_withdrawals_for_sender,
@@ -31,8 +34,10 @@ use super::CorsSupport;
         // Chainstate endpoints.
         api::handlers::chainstate::get_chain_tip,
         api::handlers::chainstate::get_chainstate_at_height,
+        api::handlers::chainstate::get_chainstate_history,
         api::handlers::chainstate::set_chainstate,
         api::handlers::chainstate::update_chainstate,
+        api::handlers::chainstate::set_chainstates_bulk,
         // Testing endpoints.
         api::handlers::testing::wipe_databases,
         // Limits endpoints.
@@ -40,6 +45,9 @@ use super::CorsSupport;
         api::handlers::limits::set_limits,
         api::handlers::limits::get_limits_for_account,
         api::handlers::limits::set_limits_for_account,
+        // Subscription endpoints.
+        api::handlers::subscription::create_subscription,
+        api::handlers::subscription::delete_subscription,
         /// New block endpoints.
         api::handlers::new_block::new_block,
     ),
@@ -47,15 +55,23 @@ use super::CorsSupport;
     components(schemas(
         // Chainstate models.
         api::models::chainstate::Chainstate,
+        api::models::chainstate::ChainstateHistoryEntry,
+        api::models::chainstate::GetChainstateHistoryResponse,
+        api::models::chainstate::SetChainstatesBulkRequestBody,
+        api::models::chainstate::SetChainstatesBulkResponse,
         // Deposit models.
         api::models::deposit::Deposit,
         api::models::deposit::DepositParameters,
         api::models::deposit::DepositInfo,
         api::models::deposit::requests::CreateDepositRequestBody,
+        api::models::deposit::requests::DepositKey,
+        api::models::deposit::requests::LookupDepositsRequestBody,
         api::models::deposit::requests::DepositUpdate,
         api::models::deposit::requests::UpdateDepositsRequestBody,
+        api::models::deposit::responses::GetDepositHistoryResponse,
         api::models::deposit::responses::GetDepositsForTransactionResponse,
         api::models::deposit::responses::GetDepositsResponse,
+        api::models::deposit::responses::LookupDepositsResponse,
         api::models::deposit::responses::UpdateDepositsResponse,
         // Withdrawal Models.
         api::models::withdrawal::Withdrawal,
@@ -64,6 +80,7 @@ use super::CorsSupport;
         api::models::withdrawal::requests::CreateWithdrawalRequestBody,
         api::models::withdrawal::requests::WithdrawalUpdate,
         api::models::withdrawal::requests::UpdateWithdrawalsRequestBody,
+        api::models::withdrawal::responses::GetWithdrawalHistoryResponse,
         api::models::withdrawal::responses::GetWithdrawalsResponse,
         api::models::withdrawal::responses::UpdateWithdrawalsResponse,
         // Health check datatypes.
@@ -71,13 +88,24 @@ use super::CorsSupport;
         // Common models.
         api::models::common::Status,
         api::models::common::Fulfillment,
+        api::models::common::StatusHistoryEntry,
         // Limits models
         api::models::limits::Limits,
         api::models::limits::AccountLimits,
+        // Testing models.
+        api::models::testing::WipeDatabasesResponse,
+        api::models::testing::WipedTable,
+        // Subscription models.
+        api::models::subscription::Subscription,
+        api::models::subscription::SubscriptionOperation,
         // New block models.
         api::models::new_block::NewBlockEventRaw,
         // Errors.
         common::error::ErrorResponse,
+        common::error::NotFoundErrorResponse,
+        common::error::LimitExceededErrorResponse,
+        common::error::ChainGapErrorResponse,
+        common::error::BulkChainstateConflictErrorResponse,
     ))
 )]
 pub struct ApiDoc;
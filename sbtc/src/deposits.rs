@@ -10,6 +10,7 @@ use bitcoin::Transaction;
 use bitcoin::XOnlyPublicKey;
 use bitcoin::locktime::relative::LockTime;
 use bitcoin::opcodes::all as opcodes;
+use bitcoin::script::Instruction;
 use bitcoin::script::PushBytesBuf;
 use bitcoin::taproot::LeafVersion;
 use bitcoin::taproot::NodeInfo;
@@ -71,6 +72,11 @@ const OP_PUSHDATA1: u8 = opcodes::OP_PUSHDATA1.to_u8();
 /// The OP_CHECKSEQUENCEVERIFY opcode described in
 /// https://github.com/bitcoin/bips/blob/master/bip-0112.mediawiki
 const OP_CSV: u8 = opcodes::OP_CSV.to_u8();
+/// The OP_CHECKLOCKTIMEVERIFY opcode described in
+/// https://github.com/bitcoin/bips/blob/master/bip-0065.mediawiki
+const OP_CLTV: u8 = opcodes::OP_CLTV.to_u8();
+/// Push the next 32 bytes as an array onto the stack.
+const OP_PUSHBYTES_32: u8 = opcodes::OP_PUSHBYTES_32.to_u8();
 /// Represents the number 1.
 const OP_PUSHNUM_1: u8 = opcodes::OP_PUSHNUM_1.to_u8();
 /// Represents the number 16.
@@ -112,6 +118,8 @@ pub struct DepositInfo {
     pub recipient: PrincipalData,
     /// The relative lock time in the reclaim script.
     pub lock_time: LockTime,
+    /// The shape of the reclaim script.
+    pub reclaim_script_kind: ReclaimScriptKind,
 }
 
 impl CreateDepositRequest {
@@ -178,6 +186,7 @@ impl CreateDepositRequest {
             signers_public_key: deposit.signers_public_key,
             recipient: deposit.recipient,
             lock_time: reclaim.lock_time,
+            reclaim_script_kind: reclaim.kind(),
             amount: tx_out.value.to_sat(),
             outpoint: self.outpoint,
         })
@@ -425,6 +434,18 @@ impl ReclaimScriptInputs {
         self.lock_time.to_consensus_u32()
     }
 
+    /// Return the shape of the reclaim script.
+    pub fn kind(&self) -> ReclaimScriptKind {
+        match self.script.as_bytes() {
+            [OP_DROP, OP_PUSHBYTES_32, public_key @ .., OP_CHECKSIG] => {
+                XOnlyPublicKey::from_slice(public_key)
+                    .map(ReclaimScriptKind::CsvP2pk)
+                    .unwrap_or(ReclaimScriptKind::Csv)
+            }
+            _ => ReclaimScriptKind::Csv,
+        }
+    }
+
     /// Return the user supplied part of the script.
     ///
     /// The full reclaim script has the form:
@@ -495,17 +516,97 @@ impl ReclaimScriptInputs {
                 };
                 (read_scriptint(script_num, 5)?, script)
             }
-            _ => return Err(Error::InvalidReclaimScript),
+            _ => return Err(unsupported_reclaim_script(reclaim_script)),
         };
 
         let lock_time =
             u32::try_from(lock_time).map_err(|_| Error::InvalidReclaimScriptLockTime(lock_time))?;
 
         let script = ScriptBuf::from_bytes(script.to_vec());
+        check_user_script(&script)?;
         ReclaimScriptInputs::try_new(lock_time, script)
     }
 }
 
+/// The shape of a reclaim script that was parsed by
+/// [`ReclaimScriptInputs::parse`].
+///
+/// All reclaim scripts start with `<locked-time> OP_CHECKSEQUENCEVERIFY`,
+/// where the lock-time is given in bitcoin blocks. They differ in what the
+/// user supplied part of the script that follows looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReclaimScriptKind {
+    /// The user supplied part of the script is not one that we recognize,
+    /// or there is none. This is the case for the multi-sig scripts of
+    /// some wallets.
+    Csv,
+    /// The user supplied part of the script locks the funds with a single
+    /// key, so the full reclaim script is
+    /// ```text
+    ///  <locked-time> OP_CHECKSEQUENCEVERIFY OP_DROP <x-only-public-key> OP_CHECKSIG
+    /// ```
+    CsvP2pk(XOnlyPublicKey),
+}
+
+/// Return the error for a reclaim script that does not start with
+/// `<locked-time> OP_CHECKSEQUENCEVERIFY`.
+///
+/// Scripts that start with `<locked-time> OP_CHECKLOCKTIMEVERIFY` instead
+/// get a more specific error than other malformed scripts.
+fn unsupported_reclaim_script(reclaim_script: &Script) -> Error {
+    let mut instructions = reclaim_script.instructions();
+    let lock_time = instructions
+        .next()
+        .and_then(|instruction| script_num(&instruction.ok()?));
+    let next_opcode = instructions
+        .next()
+        .and_then(|instruction| instruction.ok()?.opcode());
+
+    match (lock_time, next_opcode) {
+        (Some(lock_time), Some(opcode)) if opcode.to_u8() == OP_CLTV => {
+            Error::UnsupportedAbsoluteLockTime(lock_time)
+        }
+        _ => Error::InvalidReclaimScript,
+    }
+}
+
+/// Check the user supplied part of a reclaim script for push opcodes with
+/// missing data and for absolute lock-times, neither of which we support.
+///
+/// We do not check whether the script is otherwise spendable.
+fn check_user_script(script: &Script) -> Result<(), Error> {
+    let mut last_num = None;
+    for instruction in script.instructions() {
+        let instruction = instruction.map_err(Error::InvalidReclaimScriptPushData)?;
+        match instruction.opcode() {
+            Some(opcode) if opcode.to_u8() == OP_CLTV => {
+                // The argument of OP_CLTV is whatever was pushed onto the
+                // stack right before it. If it was not a number then we
+                // report it as zero, the script is unsupported either way.
+                return Err(Error::UnsupportedAbsoluteLockTime(last_num.unwrap_or(0)));
+            }
+            _ => last_num = script_num(&instruction),
+        }
+    }
+    Ok(())
+}
+
+/// Return the number pushed by the instruction, if it pushes a number
+/// that is acceptable as a lock-time.
+fn script_num(instruction: &Instruction) -> Option<i64> {
+    match instruction {
+        Instruction::PushBytes(bytes) => read_scriptint(bytes.as_bytes(), 5).ok(),
+        Instruction::Op(opcode) => {
+            let n = opcode.to_u8();
+            if n == OP_PUSHNUM_NEG1 || (OP_PUSHNUM_1..=OP_PUSHNUM_16).contains(&n) {
+                Some(n as i64 - OP_PUSHNUM_1 as i64 + 1)
+            } else {
+                None
+            }
+        }
+    }
+}
+
 /// Decodes an integer in script(minimal CScriptNum) format.
 ///
 /// # Notes
@@ -830,6 +931,103 @@ mod tests {
         assert!(matches!(reclaim, Error::UnsupportedLockTimeUnits(_)));
     }
 
+    #[test]
+    fn plain_csv_reclaim_script_kind() {
+        let reclaim_script = ScriptBuf::builder()
+            .push_int(150)
+            .push_opcode(opcodes::OP_CSV)
+            .into_script();
+
+        let reclaim = ReclaimScriptInputs::parse(&reclaim_script).unwrap();
+        assert_eq!(reclaim.lock_time(), 150);
+        assert_eq!(reclaim.kind(), ReclaimScriptKind::Csv);
+    }
+
+    #[test]
+    fn csv_p2pk_reclaim_script_kind() {
+        let secret_key = SecretKey::new(&mut OsRng);
+        let public_key = secret_key.x_only_public_key(SECP256K1).0;
+        let reclaim_script = ScriptBuf::builder()
+            .push_int(150)
+            .push_opcode(opcodes::OP_CSV)
+            .push_opcode(opcodes::OP_DROP)
+            .push_slice(public_key.serialize())
+            .push_opcode(opcodes::OP_CHECKSIG)
+            .into_script();
+
+        let reclaim = ReclaimScriptInputs::parse(&reclaim_script).unwrap();
+        assert_eq!(reclaim.lock_time(), 150);
+        assert_eq!(reclaim.kind(), ReclaimScriptKind::CsvP2pk(public_key));
+        assert_eq!(reclaim.reclaim_script(), reclaim_script);
+    }
+
+    #[test]
+    fn csv_multisig_reclaim_script_kind() {
+        // This is the shape of the multi-sig reclaim script used by
+        // Asigna, which we accept but do not classify further.
+        let reclaim_script = ScriptBuf::builder()
+            .push_int(150)
+            .push_opcode(opcodes::OP_CSV)
+            .push_opcode(opcodes::OP_DROP)
+            .push_slice([1; 32])
+            .push_opcode(opcodes::OP_CHECKSIG)
+            .push_slice([2; 32])
+            .push_opcode(opcodes::OP_CHECKSIGADD)
+            .push_int(2)
+            .push_opcode(opcodes::OP_NUMEQUAL)
+            .into_script();
+
+        let reclaim = ReclaimScriptInputs::parse(&reclaim_script).unwrap();
+        assert_eq!(reclaim.kind(), ReclaimScriptKind::Csv);
+    }
+
+    #[test_case(ScriptBuf::builder()
+        .push_int(800_000)
+        .push_opcode(opcodes::OP_CLTV)
+        .push_opcode(opcodes::OP_DROP)
+        .push_slice([1; 32])
+        .push_opcode(opcodes::OP_CHECKSIG)
+        .into_script(), 800_000 ; "cltv instead of csv")]
+    #[test_case(ScriptBuf::builder()
+        .push_int(150)
+        .push_opcode(opcodes::OP_CSV)
+        .push_opcode(opcodes::OP_DROP)
+        .push_int(800_000)
+        .push_opcode(opcodes::OP_CLTV)
+        .push_opcode(opcodes::OP_DROP)
+        .push_slice([1; 32])
+        .push_opcode(opcodes::OP_CHECKSIG)
+        .into_script(), 800_000 ; "csv followed by cltv")]
+    #[test_case(ScriptBuf::builder()
+        .push_int(150)
+        .push_opcode(opcodes::OP_CSV)
+        .push_opcode(opcodes::OP_DROP)
+        .push_int(5)
+        .push_opcode(opcodes::OP_CLTV)
+        .into_script(), 5 ; "csv followed by small cltv")]
+    fn cltv_reclaim_scripts_are_unsupported(reclaim_script: ScriptBuf, expected: i64) {
+        match ReclaimScriptInputs::parse(&reclaim_script) {
+            Err(Error::UnsupportedAbsoluteLockTime(lock_time)) => assert_eq!(lock_time, expected),
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test_case(&[OP_PUSHBYTES_32, 1, 2, 3] ; "pushbytes past the end")]
+    #[test_case(&[OP_DROP, OP_PUSHDATA1] ; "pushdata1 without a length")]
+    #[test_case(&[OP_DROP, OP_PUSHDATA1, 10, 1] ; "pushdata1 past the end")]
+    fn malformed_push_data_in_reclaim_script(user_script: &[u8]) {
+        let mut reclaim_script = ScriptBuf::builder()
+            .push_int(150)
+            .push_opcode(opcodes::OP_CSV)
+            .into_script()
+            .into_bytes();
+        reclaim_script.extend_from_slice(user_script);
+        let reclaim_script = ScriptBuf::from_bytes(reclaim_script);
+
+        let error = ReclaimScriptInputs::parse(&reclaim_script).unwrap_err();
+        assert!(matches!(error, Error::InvalidReclaimScriptPushData(_)));
+    }
+
     #[test]
     fn happy_path_tx_validation() {
         let max_fee: u64 = 15000;
@@ -29,6 +29,15 @@ pub enum Error {
     /// The reclaim script was invalid.
     #[error("the reclaim script format was invalid")]
     InvalidReclaimScript,
+    /// The user supplied part of the reclaim script had a push opcode
+    /// whose data runs past the end of the script.
+    #[error("the reclaim script had malformed push data: {0}")]
+    InvalidReclaimScriptPushData(#[source] bitcoin::script::Error),
+    /// The reclaim script locks the funds with an absolute lock-time,
+    /// using OP_CHECKLOCKTIMEVERIFY, either instead of or in addition to
+    /// the relative lock-time.
+    #[error("the reclaim script uses an absolute lock-time, which is not supported: {0}")]
+    UnsupportedAbsoluteLockTime(i64),
     /// The reclaim script lock time was invalid
     #[error("reclaim script lock time was either too large or non-minimal: {0}")]
     ScriptNum(#[source] bitcoin::script::Error),
@@ -103,26 +103,192 @@ pub enum EventError {
     /// Clarity buffer into a proper public key. It should never be thrown.
     #[error("Could not convert a public key in clarity event into the expected public key {0}")]
     ClarityPublicKeyConversion(#[source] secp256k1::Error),
-    /// This should never happen, but happens when one of the given topics
-    /// is not on the list of expected topics.
+    /// This happens when one of the given topics is not on the list of
+    /// expected topics.
     #[error("Got an unexpected event topic: {0}")]
     ClarityUnexpectedEventTopic(String),
-    /// This happens when we expect one clarity variant but got another.
-    #[error("Got an unexpected clarity value: {0:?}; {1}")]
-    ClarityUnexpectedValue(ClarityValue, TxInfo),
+    /// This happens when we expect one type of clarity value but got
+    /// another.
+    #[error("expected a clarity {0} but got {1}")]
+    ClarityUnexpectedType(ClarityType, Box<ClarityValue>),
     /// This should never happen, since  our witness programs are under the
     /// maximum length.
     #[error("tried to create an invalid witness program {0}")]
     InvalidWitnessProgram(#[source] bitcoin::witness_program::Error),
-    /// This a programmer error bug that should never be thrown.
-    #[error("The field {0} was missing from the print event for topic; {1}")]
-    TupleEventField(&'static str, TxInfo),
+    /// This happens when a field of the event schema is missing from the
+    /// print event.
+    #[error("the field is missing from the event")]
+    MissingField,
+    /// This happens when the print event has a field that is not part of
+    /// the event schema.
+    #[error("the field is not part of the event schema")]
+    UnexpectedField,
     /// This should never happen, we check the version in the smart
     /// contract.
     #[error("the given raw recipient is unexpected. version: {0:?}, hashbytes: {1:?} ")]
     UnhandledRecipient(Vec<u8>, Vec<u8>),
 }
 
+/// The topic used in an [`EventParseError`] when the topic of the print
+/// event could not be read.
+pub const UNKNOWN_EVENT_TOPIC: &str = "unknown";
+
+/// An error when parsing a print event from the sbtc-registry, along with
+/// the event and the field that it is for.
+#[derive(Debug)]
+pub struct EventParseError {
+    /// The topic of the print event, or [`UNKNOWN_EVENT_TOPIC`] if the
+    /// topic could not be read.
+    pub event_topic: String,
+    /// The path to the malformed field, like `recipient.version` or
+    /// `new-keys[2]`. This is `None` when the problem is with the event
+    /// as a whole.
+    pub field: Option<String>,
+    /// What was wrong with the event or field.
+    pub reason: EventError,
+    /// The transaction that emitted the event.
+    pub tx_info: TxInfo,
+}
+
+impl Display for EventParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not parse the {} event", self.event_topic)?;
+        if let Some(field) = &self.field {
+            write!(f, " at field {field}")?;
+        }
+        write!(f, ": {}; {}", self.reason, self.tx_info)
+    }
+}
+
+impl std::error::Error for EventParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+/// The type of a clarity value in a field of an sbtc-registry print
+/// event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClarityType {
+    /// A `uint`.
+    UInt,
+    /// A `(buff n)`, of any length.
+    Buffer,
+    /// A `(string-ascii n)`, of any length.
+    StringAscii,
+    /// A `principal`.
+    Principal,
+    /// A tuple with exactly the given fields.
+    Tuple(&'static [FieldSchema]),
+    /// A list where each element has the given type.
+    List(&'static ClarityType),
+}
+
+impl Display for ClarityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClarityType::UInt => f.write_str("uint"),
+            ClarityType::Buffer => f.write_str("buff"),
+            ClarityType::StringAscii => f.write_str("string-ascii"),
+            ClarityType::Principal => f.write_str("principal"),
+            ClarityType::Tuple(_) => f.write_str("tuple"),
+            ClarityType::List(element) => write!(f, "list of {element}"),
+        }
+    }
+}
+
+/// The expected name and clarity type of a field in a print event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// The name of the field in the tuple.
+    pub name: &'static str,
+    /// The type of the value of the field.
+    pub clarity_type: ClarityType,
+}
+
+impl FieldSchema {
+    const fn new(name: &'static str, clarity_type: ClarityType) -> Self {
+        Self { name, clarity_type }
+    }
+}
+
+/// The schema of a print event from the sbtc-registry. The fields are
+/// all of the fields in the event other than the topic, and the
+/// converter turns a tuple that matches the fields into the typed event.
+struct EventSchema {
+    topic: &'static str,
+    fields: &'static [FieldSchema],
+    convert: fn(RawTupleData) -> Result<RegistryEvent, EventParseError>,
+}
+
+/// The fields of the `recipient` tuple of `withdrawal-create` events.
+const RECIPIENT_FIELDS: &[FieldSchema] = &[
+    FieldSchema::new("version", ClarityType::Buffer),
+    FieldSchema::new("hashbytes", ClarityType::Buffer),
+];
+
+/// The schemas of all of the print events emitted by the sbtc-registry
+/// smart contract. These must match the print statements in the contract
+/// exactly.
+const EVENT_SCHEMAS: [EventSchema; 5] = [
+    EventSchema {
+        topic: "completed-deposit",
+        fields: &[
+            FieldSchema::new("bitcoin-txid", ClarityType::Buffer),
+            FieldSchema::new("output-index", ClarityType::UInt),
+            FieldSchema::new("amount", ClarityType::UInt),
+            FieldSchema::new("burn-hash", ClarityType::Buffer),
+            FieldSchema::new("burn-height", ClarityType::UInt),
+            FieldSchema::new("sweep-txid", ClarityType::Buffer),
+        ],
+        convert: RawTupleData::completed_deposit,
+    },
+    EventSchema {
+        topic: "withdrawal-accept",
+        fields: &[
+            FieldSchema::new("request-id", ClarityType::UInt),
+            FieldSchema::new("bitcoin-txid", ClarityType::Buffer),
+            FieldSchema::new("signer-bitmap", ClarityType::UInt),
+            FieldSchema::new("output-index", ClarityType::UInt),
+            FieldSchema::new("fee", ClarityType::UInt),
+            FieldSchema::new("burn-hash", ClarityType::Buffer),
+            FieldSchema::new("burn-height", ClarityType::UInt),
+            FieldSchema::new("sweep-txid", ClarityType::Buffer),
+        ],
+        convert: RawTupleData::withdrawal_accept,
+    },
+    EventSchema {
+        topic: "withdrawal-create",
+        fields: &[
+            FieldSchema::new("amount", ClarityType::UInt),
+            FieldSchema::new("request-id", ClarityType::UInt),
+            FieldSchema::new("sender", ClarityType::Principal),
+            FieldSchema::new("recipient", ClarityType::Tuple(RECIPIENT_FIELDS)),
+            FieldSchema::new("block-height", ClarityType::UInt),
+            FieldSchema::new("max-fee", ClarityType::UInt),
+        ],
+        convert: RawTupleData::withdrawal_create,
+    },
+    EventSchema {
+        topic: "withdrawal-reject",
+        fields: &[
+            FieldSchema::new("request-id", ClarityType::UInt),
+            FieldSchema::new("signer-bitmap", ClarityType::UInt),
+        ],
+        convert: RawTupleData::withdrawal_reject,
+    },
+    EventSchema {
+        topic: "key-rotation",
+        fields: &[
+            FieldSchema::new("new-keys", ClarityType::List(&ClarityType::Buffer)),
+            FieldSchema::new("new-address", ClarityType::Principal),
+            FieldSchema::new("new-aggregate-pubkey", ClarityType::Buffer),
+            FieldSchema::new("new-signature-threshold", ClarityType::UInt),
+        ],
+        convert: RawTupleData::key_rotation,
+    },
+];
+
 /// The print events emitted by the sbtc-registry clarity smart contract.
 #[derive(Debug)]
 pub enum RegistryEvent {
@@ -156,27 +322,44 @@ impl std::fmt::Display for TxInfo {
 impl RegistryEvent {
     /// Transform the [`ClarityValue`] from the sbtc-registry event into a
     /// proper type.
-    pub fn try_new(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
-        match value {
-            ClarityValue::Tuple(TupleData { data_map, .. }) => {
-                let mut event_map = RawTupleData::new(data_map, tx_info);
-                // Lucky for us, each sBTC print event in the sbtc-registry
-                // smart contract has a topic. We use that to match on what
-                // to expect when decomposing the event from a
-                // [`ClarityValue`] into a proper type.
-                let topic = event_map.remove_string("topic")?;
-
-                match topic.as_str() {
-                    "completed-deposit" => event_map.completed_deposit(),
-                    "withdrawal-accept" => event_map.withdrawal_accept(),
-                    "withdrawal-create" => event_map.withdrawal_create(),
-                    "withdrawal-reject" => event_map.withdrawal_reject(),
-                    "key-rotation" => event_map.key_rotation(),
-                    _ => Err(EventError::ClarityUnexpectedEventTopic(topic)),
-                }
+    ///
+    /// The print event must match the schema for its topic exactly: each
+    /// field must be present with the expected clarity type, and there
+    /// can be no other fields.
+    pub fn try_new(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventParseError> {
+        let data_map = match value {
+            ClarityValue::Tuple(TupleData { data_map, .. }) => data_map,
+            value => {
+                return Err(EventParseError {
+                    event_topic: UNKNOWN_EVENT_TOPIC.to_string(),
+                    field: None,
+                    reason: EventError::ClarityUnexpectedType(
+                        ClarityType::Tuple(&[]),
+                        Box::new(value),
+                    ),
+                    tx_info,
+                });
             }
-            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
-        }
+        };
+        let mut event_map = RawTupleData::new(data_map, UNKNOWN_EVENT_TOPIC, tx_info);
+        // Lucky for us, each sBTC print event in the sbtc-registry smart
+        // contract has a topic. We use that to match on what to expect
+        // when decomposing the event from a [`ClarityValue`] into a
+        // proper type.
+        let topic = event_map.remove_string("topic")?;
+
+        let Some(schema) = EVENT_SCHEMAS.iter().find(|schema| schema.topic == topic) else {
+            return Err(EventParseError {
+                event_topic: topic.clone(),
+                field: None,
+                reason: EventError::ClarityUnexpectedEventTopic(topic),
+                tx_info,
+            });
+        };
+
+        event_map.topic = schema.topic;
+        event_map.validate(schema.fields)?;
+        (schema.convert)(event_map)
     }
 }
 
@@ -301,58 +484,162 @@ pub struct KeyRotationEvent {
 #[derive(Debug)]
 struct RawTupleData {
     data_map: BTreeMap<ClarityName, ClarityValue>,
+    /// The topic of the event that this tuple is from.
+    topic: &'static str,
+    /// The path to this tuple within the event, empty for the event
+    /// itself.
+    path: String,
     tx_info: TxInfo,
 }
 
 impl RawTupleData {
-    fn new(data_map: BTreeMap<ClarityName, ClarityValue>, tx_info: TxInfo) -> Self {
-        Self { data_map, tx_info }
+    fn new(
+        data_map: BTreeMap<ClarityName, ClarityValue>,
+        topic: &'static str,
+        tx_info: TxInfo,
+    ) -> Self {
+        Self {
+            data_map,
+            topic,
+            path: String::new(),
+            tx_info,
+        }
+    }
+
+    /// The path to the given field of this tuple within the event.
+    fn field_path(&self, field: &str) -> String {
+        join_field_path(&self.path, field)
     }
+
+    /// Create an error for the given field of this tuple.
+    fn field_error(&self, field: &str, reason: EventError) -> EventParseError {
+        EventParseError {
+            event_topic: self.topic.to_string(),
+            field: Some(self.field_path(field)),
+            reason,
+            tx_info: self.tx_info,
+        }
+    }
+
+    /// Create an error for this tuple as a whole.
+    fn tuple_error(&self, reason: EventError) -> EventParseError {
+        EventParseError {
+            event_topic: self.topic.to_string(),
+            field: Some(self.path.clone()).filter(|path| !path.is_empty()),
+            reason,
+            tx_info: self.tx_info,
+        }
+    }
+
+    /// Check that the fields of this tuple match the given schema
+    /// exactly, including the fields of any nested tuples and the
+    /// elements of any lists.
+    fn validate(&self, fields: &[FieldSchema]) -> Result<(), EventParseError> {
+        validate_fields(&self.data_map, fields, &self.path).map_err(|(field, reason)| {
+            EventParseError {
+                event_topic: self.topic.to_string(),
+                field: Some(field),
+                reason,
+                tx_info: self.tx_info,
+            }
+        })
+    }
+
+    /// Remove the value of the given field
+    fn remove(&mut self, field: &'static str) -> Result<ClarityValue, EventParseError> {
+        self.data_map
+            .remove(field)
+            .ok_or_else(|| self.field_error(field, EventError::MissingField))
+    }
+
+    /// Create an error for a field whose value has an unexpected type
+    fn type_error(
+        &self,
+        field: &str,
+        expected: ClarityType,
+        value: ClarityValue,
+    ) -> EventParseError {
+        let reason = EventError::ClarityUnexpectedType(expected, Box::new(value));
+        self.field_error(field, reason)
+    }
+
     /// Extract the u128 value from the given field
-    fn remove_u128(&mut self, field: &'static str) -> Result<u128, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::UInt(val)) => Ok(val),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+    fn remove_u128(&mut self, field: &'static str) -> Result<u128, EventParseError> {
+        match self.remove(field)? {
+            ClarityValue::UInt(val) => Ok(val),
+            value => Err(self.type_error(field, ClarityType::UInt, value)),
         }
     }
+    /// Extract the u128 value from the given field and convert it into a
+    /// smaller integer type
+    fn remove_uint<T>(&mut self, field: &'static str) -> Result<T, EventParseError>
+    where
+        T: TryFrom<u128, Error = std::num::TryFromIntError>,
+    {
+        let value = self.remove_u128(field)?;
+        T::try_from(value)
+            .map_err(|error| self.field_error(field, EventError::ClarityIntConversion(error)))
+    }
     /// Extract the buff value from the given field
-    fn remove_buff(&mut self, field: &'static str) -> Result<Vec<u8>, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Sequence(SequenceData::Buffer(buf))) => Ok(buf.data),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+    fn remove_buff(&mut self, field: &'static str) -> Result<Vec<u8>, EventParseError> {
+        match self.remove(field)? {
+            ClarityValue::Sequence(SequenceData::Buffer(buf)) => Ok(buf.data),
+            value => Err(self.type_error(field, ClarityType::Buffer, value)),
         }
     }
+    /// Extract the 32 byte hash from the buff value of the given field
+    fn remove_hash(&mut self, field: &'static str) -> Result<[u8; 32], EventParseError> {
+        <[u8; 32]>::try_from(self.remove_buff(field)?).map_err(|bytes| {
+            self.field_error(field, EventError::ClarityHashByteLength(bytes.len()))
+        })
+    }
+    /// Extract the public key from the buff value of the given field
+    fn remove_public_key(&mut self, field: &'static str) -> Result<PublicKey, EventParseError> {
+        let bytes = self.remove_buff(field)?;
+        PublicKey::from_slice(&bytes)
+            .map_err(|error| self.field_error(field, EventError::ClarityPublicKeyConversion(error)))
+    }
     /// Extract the principal value from the given field
-    fn remove_principal(&mut self, field: &'static str) -> Result<PrincipalData, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Principal(principal)) => Ok(principal),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+    fn remove_principal(&mut self, field: &'static str) -> Result<PrincipalData, EventParseError> {
+        match self.remove(field)? {
+            ClarityValue::Principal(principal) => Ok(principal),
+            value => Err(self.type_error(field, ClarityType::Principal, value)),
         }
     }
     /// Extract the string value from the given field
-    fn remove_string(&mut self, field: &'static str) -> Result<String, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Sequence(SequenceData::String(CharType::ASCII(ascii)))) => {
-                String::from_utf8(ascii.data).map_err(EventError::ClarityStringConversion)
+    fn remove_string(&mut self, field: &'static str) -> Result<String, EventParseError> {
+        match self.remove(field)? {
+            ClarityValue::Sequence(SequenceData::String(CharType::ASCII(ascii))) => {
+                String::from_utf8(ascii.data).map_err(|error| {
+                    self.field_error(field, EventError::ClarityStringConversion(error))
+                })
             }
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+            value => Err(self.type_error(field, ClarityType::StringAscii, value)),
         }
     }
     /// Extract the tuple value from the given field
-    fn remove_tuple(&mut self, field: &'static str) -> Result<Self, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Tuple(TupleData { data_map, .. })) => {
-                Ok(Self::new(data_map, self.tx_info))
-            }
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+    fn remove_tuple(&mut self, field: &'static str) -> Result<Self, EventParseError> {
+        match self.remove(field)? {
+            ClarityValue::Tuple(TupleData { data_map, .. }) => Ok(Self {
+                data_map,
+                topic: self.topic,
+                path: self.field_path(field),
+                tx_info: self.tx_info,
+            }),
+            value => Err(self.type_error(field, ClarityType::Tuple(&[]), value)),
         }
     }
 
-    /// Extract the list value from the given field
-    fn remove_list(&mut self, field: &'static str) -> Result<Vec<ClarityValue>, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Sequence(SequenceData::List(list))) => Ok(list.data),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+    /// Extract the list value from the given field, where the elements
+    /// are expected to have the given type
+    fn remove_list(
+        &mut self,
+        field: &'static str,
+        element: &'static ClarityType,
+    ) -> Result<Vec<ClarityValue>, EventParseError> {
+        match self.remove(field)? {
+            ClarityValue::Sequence(SequenceData::List(list)) => Ok(list.data),
+            value => Err(self.type_error(field, ClarityType::List(element), value)),
         }
     }
 
@@ -377,35 +664,31 @@ impl RawTupleData {
     ///
     /// The above event is emitted after the indicated amount of sBTC has
     /// been emitted to the recipient.
-    fn completed_deposit(mut self) -> Result<RegistryEvent, EventError> {
-        let amount = self.remove_u128("amount")?;
-        let vout = self.remove_u128("output-index")?;
-        let txid_bytes = <[u8; 32]>::try_from(self.remove_buff("bitcoin-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_txid = <[u8; 32]>::try_from(self.remove_buff("sweep-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_hash = <[u8; 32]>::try_from(self.remove_buff("burn-hash")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_height = self.remove_u128("burn-height")?;
+    fn completed_deposit(mut self) -> Result<RegistryEvent, EventParseError> {
+        // This shouldn't error, since this amount is set from the u64
+        // amount of sats by us.
+        let amount = self.remove_uint("amount")?;
+        // This shouldn't actually error, we cast u32s to u128s before
+        // making the contract call, and that is the value that gets
+        // emitted here.
+        let vout = self.remove_uint("output-index")?;
+        // This shouldn't error, this is set from a proper [`Txid`] in a
+        // contract call.
+        let txid_bytes = self.remove_hash("bitcoin-txid")?;
+        let sweep_txid = self.remove_hash("sweep-txid")?;
+        let sweep_block_hash = self.remove_hash("burn-hash")?;
+        let sweep_block_height = self.remove_uint("burn-height")?;
 
         Ok(RegistryEvent::CompletedDeposit(CompletedDepositEvent {
             txid: self.tx_info.txid,
             block_id: self.tx_info.block_id,
-            // This shouldn't error, since this amount is set from the u64
-            // amount of sats by us.
-            amount: u64::try_from(amount).map_err(EventError::ClarityIntConversion)?,
+            amount,
             outpoint: OutPoint {
-                // This shouldn't error, this is set from a proper [`Txid`]
-                // in a contract call.
                 txid: BitcoinTxid::from_le_bytes(txid_bytes),
-                // This shouldn't actually error, we cast u32s to u128s
-                // before making the contract call, and that is the value
-                // that gets emitted here.
-                vout: u32::try_from(vout).map_err(EventError::ClarityIntConversion)?,
+                vout,
             },
             sweep_block_hash: BitcoinBlockHash::from_le_bytes(sweep_block_hash),
-            sweep_block_height: u64::try_from(sweep_block_height)
-                .map_err(EventError::ClarityIntConversion)?,
+            sweep_block_height,
             sweep_txid: BitcoinTxid::from_le_bytes(sweep_txid),
         }))
     }
@@ -429,25 +712,25 @@ impl RawTupleData {
     ///   max-fee: uint,
     /// })
     /// ```
-    fn withdrawal_create(mut self) -> Result<RegistryEvent, EventError> {
-        let request_id = self.remove_u128("request-id")?;
-        let amount = self.remove_u128("amount")?;
-        let max_fee = self.remove_u128("max-fee")?;
-        let block_height = self.remove_u128("block-height")?;
+    fn withdrawal_create(mut self) -> Result<RegistryEvent, EventParseError> {
+        // This shouldn't error, practically speaking. Each withdrawal
+        // request increments the integer by one, so we'd have to do many
+        // orders of magnitude more requests than there are bitcoin
+        // transactions, ever.
+        let request_id = self.remove_uint("request-id")?;
+        let amount = self.remove_uint("amount")?;
+        let max_fee = self.remove_uint("max-fee")?;
+        let block_height = self.remove_uint("block-height")?;
         let sender = self.remove_principal("sender")?;
         let recipient = self.remove_tuple("recipient")?;
 
         Ok(RegistryEvent::WithdrawalCreate(WithdrawalCreateEvent {
             txid: self.tx_info.txid,
             block_id: self.tx_info.block_id,
-            // This shouldn't error, practically speaking. Each withdrawal
-            // request increments the integer by one, so we'd have to do many
-            // orders of magnitude more requests than there are bitcoin
-            // transactions, ever.
-            request_id: u64::try_from(request_id).map_err(EventError::ClarityIntConversion)?,
-            amount: u64::try_from(amount).map_err(EventError::ClarityIntConversion)?,
-            max_fee: u64::try_from(max_fee).map_err(EventError::ClarityIntConversion)?,
-            block_height: u64::try_from(block_height).map_err(EventError::ClarityIntConversion)?,
+            request_id,
+            amount,
+            max_fee,
+            block_height,
             recipient: recipient.try_into_script_pub_key()?,
             sender,
         }))
@@ -551,7 +834,7 @@ impl RawTupleData {
     /// To specify this address type in the `initiate-withdrawal-request`
     /// contract call, the `version` is 0x06 and the `hashbytes` is the
     /// "tweaked" public key.
-    fn try_into_script_pub_key(mut self) -> Result<ScriptBuf, EventError> {
+    fn try_into_script_pub_key(mut self) -> Result<ScriptBuf, EventParseError> {
         let version = self.remove_buff("version")?;
         let hash_bytes_buf = self.remove_buff("hashbytes")?;
         let hash_bytes = hash_bytes_buf.as_slice();
@@ -559,8 +842,9 @@ impl RawTupleData {
         match version.as_slice() {
             // version == 0x00 and (len hashbytes) == 20 => P2PKH
             [0x00] => {
-                let bytes =
-                    <[u8; 20]>::try_from(hash_bytes).map_err(EventError::ClaritySliceConversion)?;
+                let bytes = <[u8; 20]>::try_from(hash_bytes).map_err(|error| {
+                    self.field_error("hashbytes", EventError::ClaritySliceConversion(error))
+                })?;
                 let pubkey_hash = PubkeyHash::from_byte_array(bytes);
                 Ok(ScriptBuf::new_p2pkh(&pubkey_hash))
             }
@@ -573,33 +857,40 @@ impl RawTupleData {
             // In these cases we assume the `hashbytes` is the Hash160 of
             // the redeem script.
             [0x01] | [0x02] | [0x03] => {
-                let bytes =
-                    <[u8; 20]>::try_from(hash_bytes).map_err(EventError::ClaritySliceConversion)?;
+                let bytes = <[u8; 20]>::try_from(hash_bytes).map_err(|error| {
+                    self.field_error("hashbytes", EventError::ClaritySliceConversion(error))
+                })?;
                 let script_hash = ScriptHash::from_byte_array(bytes);
                 Ok(ScriptBuf::new_p2sh(&script_hash))
             }
             // version == 0x04 and (len hashbytes) == 20 => P2WPKH
             [0x04] if hash_bytes.len() == 20 => {
-                let program = WitnessProgram::new(WitnessVersion::V0, hash_bytes)
-                    .map_err(EventError::InvalidWitnessProgram)?;
+                let program =
+                    WitnessProgram::new(WitnessVersion::V0, hash_bytes).map_err(|error| {
+                        self.field_error("hashbytes", EventError::InvalidWitnessProgram(error))
+                    })?;
                 Ok(ScriptBuf::new_witness_program(&program))
             }
             // version == 0x05 and (len hashbytes) == 32 => P2WSH
             [0x05] if hash_bytes.len() == 32 => {
-                let program = WitnessProgram::new(WitnessVersion::V0, hash_bytes)
-                    .map_err(EventError::InvalidWitnessProgram)?;
+                let program =
+                    WitnessProgram::new(WitnessVersion::V0, hash_bytes).map_err(|error| {
+                        self.field_error("hashbytes", EventError::InvalidWitnessProgram(error))
+                    })?;
                 Ok(ScriptBuf::new_witness_program(&program))
             }
             // version == 0x06 and (len hashbytes) == 32 => P2TR
             [0x06] if hash_bytes.len() == 32 => {
-                let program = WitnessProgram::new(WitnessVersion::V1, hash_bytes)
-                    .map_err(EventError::InvalidWitnessProgram)?;
+                let program =
+                    WitnessProgram::new(WitnessVersion::V1, hash_bytes).map_err(|error| {
+                        self.field_error("hashbytes", EventError::InvalidWitnessProgram(error))
+                    })?;
                 Ok(ScriptBuf::new_witness_program(&program))
             }
             // We make sure that the version and hash byte lengths conform
             // to the above expectations in the smart contract, so this
             // should never happen.
-            _ => Err(EventError::UnhandledRecipient(version, hash_bytes_buf)),
+            _ => Err(self.tuple_error(EventError::UnhandledRecipient(version, hash_bytes_buf))),
         }
     }
 
@@ -624,44 +915,37 @@ impl RawTupleData {
     ///   sweep-txid: (buff 32),
     /// })
     /// ```
-    fn withdrawal_accept(mut self) -> Result<RegistryEvent, EventError> {
-        let request_id = self.remove_u128("request-id")?;
+    fn withdrawal_accept(mut self) -> Result<RegistryEvent, EventParseError> {
+        // This shouldn't error for the reasons noted in
+        // [`withdrawal_create`].
+        let request_id = self.remove_uint("request-id")?;
         let bitmap = self.remove_u128("signer-bitmap")?;
-        let fee = self.remove_u128("fee")?;
-        let vout = self.remove_u128("output-index")?;
-        let txid_bytes = <[u8; 32]>::try_from(self.remove_buff("bitcoin-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_txid = <[u8; 32]>::try_from(self.remove_buff("sweep-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_hash = <[u8; 32]>::try_from(self.remove_buff("burn-hash")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_height = self.remove_u128("burn-height")?;
+        // This shouldn't error, since this amount is set from the u64
+        // amount of sats by us.
+        let fee = self.remove_uint("fee")?;
+        // This shouldn't actually error, we cast u32s to u128s before
+        // making the contract call, and that is the value that gets
+        // emitted here.
+        let vout = self.remove_uint("output-index")?;
+        // This shouldn't error, this is set from a proper [`Txid`] in a
+        // contract call.
+        let txid_bytes = self.remove_hash("bitcoin-txid")?;
+        let sweep_txid = self.remove_hash("sweep-txid")?;
+        let sweep_block_hash = self.remove_hash("burn-hash")?;
+        let sweep_block_height = self.remove_uint("burn-height")?;
 
         Ok(RegistryEvent::WithdrawalAccept(WithdrawalAcceptEvent {
             txid: self.tx_info.txid,
             block_id: self.tx_info.block_id,
-            // This shouldn't error for the reasons noted in
-            // [`withdrawal_create`].
-            request_id: u64::try_from(request_id).map_err(EventError::ClarityIntConversion)?,
+            request_id,
             signer_bitmap: bitmap,
             outpoint: OutPoint {
-                // This shouldn't error, this is set from a proper [`Txid`] in
-                // a contract call.
                 txid: BitcoinTxid::from_le_bytes(txid_bytes),
-                // This shouldn't actually error, we cast u32s to u128s before
-                // making the contract call, and that is the value that gets
-                // emitted here.
-                vout: u32::try_from(vout).map_err(EventError::ClarityIntConversion)?,
+                vout,
             },
-            // This shouldn't error, since this amount is set from the u64
-            // amount of sats by us.
-            fee: u64::try_from(fee).map_err(EventError::ClarityIntConversion)?,
-
+            fee,
             sweep_block_hash: BitcoinBlockHash::from_le_bytes(sweep_block_hash),
-
-            sweep_block_height: u64::try_from(sweep_block_height)
-                .map_err(EventError::ClarityIntConversion)?,
-
+            sweep_block_height,
             sweep_txid: BitcoinTxid::from_le_bytes(sweep_txid),
         }))
     }
@@ -684,16 +968,16 @@ impl RawTupleData {
     ///
     /// The above event is emitted after the locked sBTC has been unlocked back
     /// to the account that initiated the request.
-    fn withdrawal_reject(mut self) -> Result<RegistryEvent, EventError> {
-        let request_id = self.remove_u128("request-id")?;
+    fn withdrawal_reject(mut self) -> Result<RegistryEvent, EventParseError> {
+        // This shouldn't error for the reasons noted in
+        // [`withdrawal_create`].
+        let request_id = self.remove_uint("request-id")?;
         let bitmap = self.remove_u128("signer-bitmap")?;
 
         Ok(RegistryEvent::WithdrawalReject(WithdrawalRejectEvent {
             txid: self.tx_info.txid,
             block_id: self.tx_info.block_id,
-            // This shouldn't error for the reasons noted in
-            // [`withdrawal_create`].
-            request_id: u64::try_from(request_id).map_err(EventError::ClarityIntConversion)?,
+            request_id,
             signer_bitmap: bitmap,
         }))
     }
@@ -717,35 +1001,104 @@ impl RawTupleData {
     ///
     /// The above event is emitted after the keys for the multi-sig wallet
     /// have been rotated.
-    fn key_rotation(mut self) -> Result<RegistryEvent, EventError> {
+    fn key_rotation(mut self) -> Result<RegistryEvent, EventParseError> {
         let new_keys = self
-            .remove_list("new-keys")?
+            .remove_list("new-keys", &ClarityType::Buffer)?
             .into_iter()
-            .map(|val| match val {
-                ClarityValue::Sequence(SequenceData::Buffer(buf)) => {
-                    PublicKey::from_slice(&buf.data).map_err(EventError::ClarityPublicKeyConversion)
+            .enumerate()
+            .map(|(index, val)| {
+                let field = format!("new-keys[{index}]");
+                match val {
+                    ClarityValue::Sequence(SequenceData::Buffer(buf)) => {
+                        PublicKey::from_slice(&buf.data).map_err(|error| {
+                            self.field_error(&field, EventError::ClarityPublicKeyConversion(error))
+                        })
+                    }
+                    val => Err(self.type_error(&field, ClarityType::Buffer, val)),
                 }
-                _ => Err(EventError::ClarityUnexpectedValue(val, self.tx_info)),
             })
-            .collect::<Result<Vec<PublicKey>, EventError>>()?;
+            .collect::<Result<Vec<PublicKey>, EventParseError>>()?;
 
         let new_address = self.remove_principal("new-address")?;
-        let new_aggregate_pubkey = self.remove_buff("new-aggregate-pubkey")?;
-        let new_signature_threshold = self.remove_u128("new-signature-threshold")?;
+        let new_aggregate_pubkey = self.remove_public_key("new-aggregate-pubkey")?;
+        let new_signature_threshold = self.remove_uint("new-signature-threshold")?;
 
         Ok(RegistryEvent::KeyRotation(KeyRotationEvent {
             txid: self.tx_info.txid,
             block_id: self.tx_info.block_id,
             new_keys,
             new_address,
-            new_aggregate_pubkey: PublicKey::from_slice(&new_aggregate_pubkey)
-                .map_err(EventError::ClarityPublicKeyConversion)?,
-            new_signature_threshold: u16::try_from(new_signature_threshold)
-                .map_err(EventError::ClarityIntConversion)?,
+            new_aggregate_pubkey,
+            new_signature_threshold,
         }))
     }
 }
 
+/// Join the path to a tuple within an event with the name of one of its
+/// fields.
+fn join_field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+/// Check that the given tuple fields match the schema exactly, returning
+/// the path to the first field that does not along with the reason.
+fn validate_fields(
+    data_map: &BTreeMap<ClarityName, ClarityValue>,
+    fields: &[FieldSchema],
+    path: &str,
+) -> Result<(), (String, EventError)> {
+    for field in fields {
+        let field_path = join_field_path(path, field.name);
+        match data_map.get(field.name) {
+            Some(value) => validate_value(value, field.clarity_type, field_path)?,
+            None => return Err((field_path, EventError::MissingField)),
+        }
+    }
+
+    let extra_field = data_map
+        .keys()
+        .find(|name| fields.iter().all(|field| field.name != name.as_str()));
+
+    match extra_field {
+        Some(name) => Err((join_field_path(path, name), EventError::UnexpectedField)),
+        None => Ok(()),
+    }
+}
+
+/// Check that the given value has the expected clarity type, and that
+/// any nested tuples and list elements match their schema as well.
+fn validate_value(
+    value: &ClarityValue,
+    expected: ClarityType,
+    path: String,
+) -> Result<(), (String, EventError)> {
+    match (expected, value) {
+        (ClarityType::UInt, ClarityValue::UInt(_))
+        | (ClarityType::Buffer, ClarityValue::Sequence(SequenceData::Buffer(_)))
+        | (
+            ClarityType::StringAscii,
+            ClarityValue::Sequence(SequenceData::String(CharType::ASCII(_))),
+        )
+        | (ClarityType::Principal, ClarityValue::Principal(_)) => Ok(()),
+        (ClarityType::Tuple(fields), ClarityValue::Tuple(tuple)) => {
+            validate_fields(&tuple.data_map, fields, &path)
+        }
+        (ClarityType::List(element), ClarityValue::Sequence(SequenceData::List(list))) => {
+            list.data.iter().enumerate().try_for_each(|(index, value)| {
+                validate_value(value, *element, format!("{path}[{index}]"))
+            })
+        }
+        (expected, value) => Err((
+            path,
+            EventError::ClarityUnexpectedType(expected, Box::new(value.clone())),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::LazyLock;
@@ -834,21 +1187,12 @@ mod tests {
                 ClarityName::from("request-id"),
                 ClarityValue::UInt(request_id),
             ),
-            (
-                ClarityName::from("signer-bitmap"),
-                ClarityValue::UInt(13579),
-            ),
             (ClarityName::from("max-fee"), ClarityValue::UInt(max_fee)),
-            (ClarityName::from("output-index"), ClarityValue::UInt(2)),
             (ClarityName::from("amount"), ClarityValue::UInt(amount)),
             (
                 ClarityName::from("block-height"),
                 ClarityValue::UInt(block_height),
             ),
-            (
-                ClarityName::from("bitcoin-txid"),
-                ClarityValue::buff_from(vec![1; 32]).unwrap(),
-            ),
             (
                 ClarityName::from("sender"),
                 ClarityValue::Principal(sender.clone()),
@@ -1061,7 +1405,7 @@ mod tests {
             .into_iter()
             .collect();
 
-            RawTupleData::new(recipient, TX_INFO)
+            RawTupleData::new(recipient, "withdrawal-create", TX_INFO)
         }
     }
 
@@ -1128,4 +1472,230 @@ mod tests {
         let res = map.try_into_script_pub_key();
         assert!(res.is_err());
     }
+
+    /// The fields of a well formed `completed-deposit` print event.
+    fn completed_deposit_fields() -> BTreeMap<ClarityName, ClarityValue> {
+        [
+            (
+                ClarityName::from("topic"),
+                ClarityValue::string_ascii_from_bytes("completed-deposit".as_bytes().to_vec())
+                    .unwrap(),
+            ),
+            (
+                ClarityName::from("bitcoin-txid"),
+                ClarityValue::buff_from(vec![1; 32]).unwrap(),
+            ),
+            (ClarityName::from("output-index"), ClarityValue::UInt(3)),
+            (ClarityName::from("amount"), ClarityValue::UInt(123654789)),
+            (
+                ClarityName::from("burn-hash"),
+                ClarityValue::buff_from(vec![2; 32]).unwrap(),
+            ),
+            (ClarityName::from("burn-height"), ClarityValue::UInt(139)),
+            (
+                ClarityName::from("sweep-txid"),
+                ClarityValue::buff_from(vec![3; 32]).unwrap(),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// The fields of a well formed `withdrawal-create` print event.
+    fn withdrawal_create_fields() -> BTreeMap<ClarityName, ClarityValue> {
+        let recipient = vec![
+            (
+                ClarityName::from("version"),
+                ClarityValue::buff_from_byte(0),
+            ),
+            (
+                ClarityName::from("hashbytes"),
+                ClarityValue::buff_from(vec![0; 20]).unwrap(),
+            ),
+        ];
+        let sender = PrincipalData::parse("ST1RQHF4VE5CZ6EK3MZPZVQBA0JVSMM9H5PMHMS1Y").unwrap();
+        [
+            (
+                ClarityName::from("topic"),
+                ClarityValue::string_ascii_from_bytes("withdrawal-create".as_bytes().to_vec())
+                    .unwrap(),
+            ),
+            (ClarityName::from("request-id"), ClarityValue::UInt(1)),
+            (ClarityName::from("amount"), ClarityValue::UInt(24681012)),
+            (ClarityName::from("max-fee"), ClarityValue::UInt(369)),
+            (ClarityName::from("block-height"), ClarityValue::UInt(139)),
+            (ClarityName::from("sender"), ClarityValue::Principal(sender)),
+            (
+                ClarityName::from("recipient"),
+                ClarityValue::Tuple(TupleData::from_data(recipient).unwrap()),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn parse_fields(
+        fields: BTreeMap<ClarityName, ClarityValue>,
+    ) -> Result<RegistryEvent, EventParseError> {
+        let tuple_data = TupleData::from_data(fields.into_iter().collect()).unwrap();
+        RegistryEvent::try_new(ClarityValue::Tuple(tuple_data), TX_INFO)
+    }
+
+    #[test]
+    fn well_formed_fixtures_parse() {
+        assert!(parse_fields(completed_deposit_fields()).is_ok());
+        assert!(parse_fields(withdrawal_create_fields()).is_ok());
+    }
+
+    #[test_case(
+        completed_deposit_fields,
+        |fields| { fields.remove("amount"); },
+        "amount",
+        |reason| matches!(reason, EventError::MissingField);
+        "field removed")]
+    #[test_case(
+        completed_deposit_fields,
+        |fields| { fields.insert(ClarityName::from("amount"), ClarityValue::Int(5)); },
+        "amount",
+        |reason| matches!(reason, EventError::ClarityUnexpectedType(ClarityType::UInt, _));
+        "wrong-typed field")]
+    #[test_case(
+        completed_deposit_fields,
+        |fields| { fields.insert(ClarityName::from("memo"), ClarityValue::UInt(1)); },
+        "memo",
+        |reason| matches!(reason, EventError::UnexpectedField);
+        "extra field")]
+    #[test_case(
+        completed_deposit_fields,
+        |fields| { fields.insert(ClarityName::from("burn-hash"), ClarityValue::buff_from(vec![2; 20]).unwrap()); },
+        "burn-hash",
+        |reason| matches!(reason, EventError::ClarityHashByteLength(20));
+        "short hash")]
+    #[test_case(
+        withdrawal_create_fields,
+        |fields| {
+            let recipient = vec![
+                (ClarityName::from("version"), ClarityValue::buff_from_byte(0)),
+            ];
+            fields.insert(ClarityName::from("recipient"), ClarityValue::Tuple(TupleData::from_data(recipient).unwrap()));
+        },
+        "recipient.hashbytes",
+        |reason| matches!(reason, EventError::MissingField);
+        "nested field removed")]
+    #[test_case(
+        withdrawal_create_fields,
+        |fields| {
+            let recipient = vec![
+                (ClarityName::from("version"), ClarityValue::buff_from_byte(0)),
+                (ClarityName::from("hashbytes"), ClarityValue::buff_from(vec![0; 20]).unwrap()),
+                (ClarityName::from("memo"), ClarityValue::UInt(1)),
+            ];
+            fields.insert(ClarityName::from("recipient"), ClarityValue::Tuple(TupleData::from_data(recipient).unwrap()));
+        },
+        "recipient.memo",
+        |reason| matches!(reason, EventError::UnexpectedField);
+        "nested extra field")]
+    fn malformed_events_are_rejected<F, G>(
+        fields: fn() -> BTreeMap<ClarityName, ClarityValue>,
+        modify: F,
+        expected_field: &str,
+        expected_reason: G,
+    ) where
+        F: FnOnce(&mut BTreeMap<ClarityName, ClarityValue>),
+        G: Fn(&EventError) -> bool,
+    {
+        let mut fields = fields();
+        let topic = match fields.get("topic") {
+            Some(ClarityValue::Sequence(SequenceData::String(CharType::ASCII(ascii)))) => {
+                String::from_utf8(ascii.data.clone()).unwrap()
+            }
+            _ => panic!("the fixture is missing its topic"),
+        };
+        modify(&mut fields);
+
+        let error = parse_fields(fields).unwrap_err();
+        assert_eq!(error.event_topic, topic);
+        assert_eq!(error.field.as_deref(), Some(expected_field));
+        assert!(expected_reason(&error.reason), "{error}");
+    }
+
+    #[test]
+    fn events_without_a_topic_are_rejected() {
+        let mut fields = completed_deposit_fields();
+        fields.remove("topic");
+
+        let error = parse_fields(fields).unwrap_err();
+        assert_eq!(error.event_topic, UNKNOWN_EVENT_TOPIC);
+        assert_eq!(error.field.as_deref(), Some("topic"));
+        assert!(matches!(error.reason, EventError::MissingField));
+    }
+
+    #[test]
+    fn events_with_an_unknown_topic_are_rejected() {
+        let mut fields = completed_deposit_fields();
+        let topic = ClarityValue::string_ascii_from_bytes(b"update-protocol-contract".to_vec());
+        fields.insert(ClarityName::from("topic"), topic.unwrap());
+
+        let error = parse_fields(fields).unwrap_err();
+        assert_eq!(error.event_topic, "update-protocol-contract");
+        assert_eq!(error.field, None);
+        assert!(matches!(
+            error.reason,
+            EventError::ClarityUnexpectedEventTopic(_)
+        ));
+    }
+
+    #[test]
+    fn events_that_are_not_tuples_are_rejected() {
+        let error = RegistryEvent::try_new(ClarityValue::UInt(42), TX_INFO).unwrap_err();
+        assert_eq!(error.event_topic, UNKNOWN_EVENT_TOPIC);
+        assert_eq!(error.field, None);
+        assert!(matches!(
+            error.reason,
+            EventError::ClarityUnexpectedType(ClarityType::Tuple(_), _)
+        ));
+    }
+
+    #[test]
+    fn list_elements_are_validated() {
+        let new_keys = ListData {
+            data: vec![
+                ClarityValue::buff_from(PUBLIC_KEY.to_bytes()).unwrap(),
+                ClarityValue::UInt(1),
+            ],
+            type_signature: ListTypeData::new_list(BUFF_33.clone(), 128).unwrap(),
+        };
+        let event = [
+            (
+                ClarityName::from("topic"),
+                ClarityValue::string_ascii_from_bytes("key-rotation".as_bytes().to_vec()).unwrap(),
+            ),
+            (
+                ClarityName::from("new-keys"),
+                ClarityValue::Sequence(SequenceData::List(new_keys)),
+            ),
+            (
+                ClarityName::from("new-address"),
+                ClarityValue::Principal(
+                    PrincipalData::parse("ST1RQHF4VE5CZ6EK3MZPZVQBA0JVSMM9H5PMHMS1Y").unwrap(),
+                ),
+            ),
+            (
+                ClarityName::from("new-aggregate-pubkey"),
+                ClarityValue::buff_from(PUBLIC_KEY.to_bytes()).unwrap(),
+            ),
+            (
+                ClarityName::from("new-signature-threshold"),
+                ClarityValue::UInt(2),
+            ),
+        ];
+
+        let error = parse_fields(event.into_iter().collect()).unwrap_err();
+        assert_eq!(error.event_topic, "key-rotation");
+        assert_eq!(error.field.as_deref(), Some("new-keys[1]"));
+        assert!(matches!(
+            error.reason,
+            EventError::ClarityUnexpectedType(ClarityType::Buffer, _)
+        ));
+    }
 }
@@ -1,5 +1,5 @@
 //! This module contains structs that represent the payload for new block
-//! webhooks from a stacks node.
+//! and new burn block webhooks from a stacks node.
 //!
 //! The payload of a Stacks node webhooks is really defined in the source.
 //! Here we attempt to follow the source and deserialize them using the
@@ -29,6 +29,7 @@ use serde::Deserialize;
 use stacks_common::codec::StacksMessageCodec;
 use stacks_common::types::chainstate::BlockHeaderHash;
 use stacks_common::types::chainstate::BurnchainHeaderHash;
+use stacks_common::types::chainstate::ConsensusHash;
 use stacks_common::types::chainstate::StacksBlockId;
 
 use crate::error::Error;
@@ -83,6 +84,43 @@ pub struct NewBlockEvent {
     pub parent_burn_block_timestamp: u64,
 }
 
+/// This struct represents the body of POST /new_burn_block events from a
+/// stacks node, which are sent once the node processes a bitcoin block.
+///
+/// # Note
+///
+/// This struct leaves out some of the fields that are included. For the
+/// full payload, see the source here:
+/// <https://github.com/stacks-network/stacks-core/blob/09c4b066e25104be8b066e8f7530ff0c6df4ccd5/testnet/stacks-node/src/event_dispatcher.rs#L1130-L1141>
+#[derive(Debug, Deserialize)]
+pub struct NewBurnBlockEvent {
+    /// The hash of the bitcoin block.
+    #[serde(deserialize_with = "deserialize_hex")]
+    pub burn_block_hash: BurnchainHeaderHash,
+    /// The height of the bitcoin block.
+    pub burn_block_height: u64,
+    /// The PoX reward recipients that were paid in the bitcoin block.
+    pub reward_recipients: Vec<RewardRecipient>,
+    /// The bitcoin addresses of the PoX reward slot holders.
+    pub reward_slot_holders: Vec<String>,
+    /// The amount of BTC, in sats, that was burned in the block commits of
+    /// the bitcoin block.
+    pub burn_amount: u64,
+    /// The consensus hash of the sortition for the bitcoin block.
+    #[serde(deserialize_with = "deserialize_hex")]
+    pub consensus_hash: ConsensusHash,
+}
+
+/// A PoX reward recipient in a POST /new_burn_block event.
+#[derive(Debug, Deserialize)]
+pub struct RewardRecipient {
+    /// The bitcoin address of the recipient.
+    pub recipient: String,
+    /// The amount of BTC, in sats, that the recipient was paid.
+    #[serde(rename = "amt")]
+    pub amount: u64,
+}
+
 /// This matches the json value that is defined in stacks-core[^1]. It
 /// contains the raw transaction and the result of the transaction.
 ///
@@ -358,4 +396,54 @@ mod tests {
         assert_eq!(event.block_hash, expected_block_hash);
         assert_eq!(event.transactions.first().unwrap().txid, expected_txid);
     }
+
+    /// A POST /new_burn_block payload in the format that stacks-core
+    /// sends it.
+    const BURN_BLOCK_WEBHOOK_PAYLOAD: &str = r#"{
+    "burn_amount": 20000,
+    "burn_block_hash": "0x7d4db9d88dd86c31c75a351e4974940db55f6db77c9d49881dd0028946c661ac",
+    "burn_block_height": 159,
+    "consensus_hash": "0x3a6ab0c9e7f13f3f1d1a13f7a9d6bd2e2fc3bbf4",
+    "parent_burn_block_hash": "0x1e7a5c8c1ddf7ab0ad7e4e1a2fb14a1f0ed2c3e46a6d0cd5c6b9a0c9b1b8e4d2",
+    "reward_recipients": [
+        {
+            "amt": 10000,
+            "recipient": "mqVnk6NPRdhntvfm4hh9vvjiRkFDUuSYsH"
+        }
+    ],
+    "reward_slot_holders": [
+        "mqVnk6NPRdhntvfm4hh9vvjiRkFDUuSYsH",
+        "mqVnk6NPRdhntvfm4hh9vvjiRkFDUuSYsH"
+    ]
+}"#;
+
+    #[test]
+    fn test_new_burn_block_event_deserialization() {
+        let event: NewBurnBlockEvent = serde_json::from_str(BURN_BLOCK_WEBHOOK_PAYLOAD).unwrap();
+
+        let expected_block_hash = BurnchainHeaderHash::from_hex(
+            "7d4db9d88dd86c31c75a351e4974940db55f6db77c9d49881dd0028946c661ac",
+        )
+        .unwrap();
+        let expected_consensus_hash =
+            ConsensusHash::from_hex("3a6ab0c9e7f13f3f1d1a13f7a9d6bd2e2fc3bbf4").unwrap();
+
+        assert_eq!(event.burn_block_hash, expected_block_hash);
+        assert_eq!(event.burn_block_height, 159);
+        assert_eq!(event.consensus_hash, expected_consensus_hash);
+        assert_eq!(event.burn_amount, 20000);
+        assert_eq!(event.reward_recipients.len(), 1);
+        assert_eq!(event.reward_recipients[0].amount, 10000);
+        assert_eq!(event.reward_slot_holders.len(), 2);
+    }
+
+    #[test]
+    fn new_burn_block_event_requires_a_block_hash() {
+        let mut payload: serde_json::Value =
+            serde_json::from_str(BURN_BLOCK_WEBHOOK_PAYLOAD).unwrap();
+        payload["burn_block_hash"] = serde_json::json!("0x1234");
+
+        let result = serde_json::from_value::<NewBurnBlockEvent>(payload);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,35 @@
+-- Stacks nodes retry the new_block webhook until they get a successful
+-- response, so the same sbtc-registry event can be delivered to us more
+-- than once. Remove any duplicate rows that we may have written before
+-- and add unique indexes so that writing an event is idempotent.
+
+DELETE FROM sbtc_signer.completed_deposit_events AS a
+USING sbtc_signer.completed_deposit_events AS b
+WHERE a.id > b.id
+  AND a.txid = b.txid
+  AND a.block_hash = b.block_hash
+  AND a.bitcoin_txid = b.bitcoin_txid
+  AND a.output_index = b.output_index;
+
+CREATE UNIQUE INDEX uix_completed_deposit_events_txid_block_hash_outpoint
+    ON sbtc_signer.completed_deposit_events(txid, block_hash, bitcoin_txid, output_index);
+
+DELETE FROM sbtc_signer.withdrawal_accept_events AS a
+USING sbtc_signer.withdrawal_accept_events AS b
+WHERE a.id > b.id
+  AND a.txid = b.txid
+  AND a.block_hash = b.block_hash
+  AND a.request_id = b.request_id;
+
+CREATE UNIQUE INDEX uix_withdrawal_accept_events_txid_block_hash_request_id
+    ON sbtc_signer.withdrawal_accept_events(txid, block_hash, request_id);
+
+DELETE FROM sbtc_signer.withdrawal_reject_events AS a
+USING sbtc_signer.withdrawal_reject_events AS b
+WHERE a.id > b.id
+  AND a.txid = b.txid
+  AND a.block_hash = b.block_hash
+  AND a.request_id = b.request_id;
+
+CREATE UNIQUE INDEX uix_withdrawal_reject_events_txid_block_hash_request_id
+    ON sbtc_signer.withdrawal_reject_events(txid, block_hash, request_id);
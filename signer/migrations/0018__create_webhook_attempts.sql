@@ -0,0 +1,16 @@
+-- Stacks nodes retry the new_block webhook every second until they get a
+-- successful response. This table counts how many times we failed to
+-- process the webhook for a stacks block, so that we can give up on it
+-- after a fixed number of attempts and reprocess it later.
+CREATE TABLE sbtc_signer.webhook_attempts (
+    -- The index block hash of the stacks block in the webhook.
+    index_block_hash BYTEA PRIMARY KEY,
+    -- The body of the webhook, kept so that it can be reprocessed.
+    payload TEXT NOT NULL,
+    -- The number of times that processing the webhook has failed.
+    failed_attempts INTEGER NOT NULL,
+    -- Whether we gave up on processing the webhook.
+    is_poisoned BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Bitcoin blocks that were on the canonical bitcoin blockchain when we
+-- observed them but have since been replaced by a reorg. They are kept
+-- around, since the chain may switch back to them, but they are never
+-- considered for the canonical chain tip.
+ALTER TABLE sbtc_signer.bitcoin_blocks
+    ADD COLUMN is_orphaned BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,39 @@
+-- Signers broadcast their decisions on deposit and withdrawal requests
+-- over the P2P network, but the receiving signer may not have a record of
+-- the request yet, for example when it is still catching up. These tables
+-- buffer such decisions until the request is stored, at which point they
+-- are moved into the `deposit_signers` and `withdrawal_signers` tables.
+-- There are no foreign keys here since the requests do not exist yet.
+CREATE TABLE sbtc_signer.pending_deposit_signers (
+    -- The bitcoin transaction containing the deposit UTXO.
+    txid BYTEA NOT NULL,
+    -- The output index of the deposit UTXO within the transaction.
+    output_index INTEGER NOT NULL,
+    -- The public key of the signer whose decision this is.
+    signer_pub_key BYTEA NOT NULL,
+    -- Whether the signer can sign for the deposit request.
+    can_sign BOOLEAN NOT NULL,
+    -- Whether the signer's blocklist client accepted the deposit request.
+    can_accept BOOLEAN NOT NULL,
+    -- When the decision was received, used for expiring old decisions.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+
+    PRIMARY KEY (txid, output_index, signer_pub_key)
+);
+
+CREATE TABLE sbtc_signer.pending_withdrawal_signers (
+    -- The id of the withdrawal request.
+    request_id BIGINT NOT NULL,
+    -- The stacks transaction id of the withdrawal request.
+    txid BYTEA NOT NULL,
+    -- The block hash of the stacks block which `txid` was included in.
+    block_hash BYTEA NOT NULL,
+    -- The public key of the signer whose decision this is.
+    signer_pub_key BYTEA NOT NULL,
+    -- Whether the signer has accepted the withdrawal request or not.
+    is_accepted BOOLEAN NOT NULL,
+    -- When the decision was received, used for expiring old decisions.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+
+    PRIMARY KEY (request_id, block_hash, signer_pub_key)
+);
@@ -0,0 +1,14 @@
+-- Signers attach a monotonically increasing sequence number to each
+-- message that they publish over the P2P network, and receiving signers
+-- drop messages whose sequence number is far behind the highest one that
+-- they have seen. The counter is stored here so that it keeps increasing
+-- across restarts; otherwise peers would drop the fresh messages of a
+-- signer that just restarted.
+CREATE TABLE sbtc_signer.p2p_message_sequences (
+    -- The public key of the signer that publishes the messages.
+    signer_pub_key BYTEA PRIMARY KEY,
+    -- The sequence number of the most recently published message.
+    sequence BIGINT NOT NULL,
+    -- When the sequence number was last incremented.
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
@@ -0,0 +1,17 @@
+-- The addresses at which signers successfully connected to their peers
+-- over the P2P network. On startup, signers dial the addresses of the
+-- peers that they have seen recently, in addition to the configured
+-- seeds, so that they can rejoin the network even if the seeds are
+-- unavailable.
+CREATE TABLE sbtc_signer.p2p_peers (
+    -- The libp2p peer ID of the peer.
+    peer_id TEXT NOT NULL,
+    -- The multiaddress at which we connected to the peer, without the
+    -- `/p2p/<peer_id>` suffix.
+    address TEXT NOT NULL,
+    -- When we last connected to the peer at this address.
+    last_seen TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (peer_id, address)
+);
+
+CREATE INDEX ix_p2p_peers_last_seen ON sbtc_signer.p2p_peers(last_seen);
@@ -0,0 +1,21 @@
+-- The shape of the reclaim script of a deposit request, as recognized
+-- when the deposit request was parsed. All reclaim scripts start with
+-- `<locked-time> OP_CSV`. The `csv_p2pk` scripts follow that with
+-- `OP_DROP <x-only-public-key> OP_CHECKSIG`, while `csv` scripts are
+-- followed by anything else, such as a multi-sig script.
+CREATE TYPE sbtc_signer.reclaim_script_kind AS ENUM (
+    'csv',
+    'csv_p2pk'
+);
+
+ALTER TABLE sbtc_signer.deposit_requests
+    ADD COLUMN reclaim_script_kind sbtc_signer.reclaim_script_kind NOT NULL DEFAULT 'csv';
+
+-- Backfill the single-key reclaim scripts of the existing deposit
+-- requests. These end with the 36 bytes
+-- `OP_CSV OP_DROP OP_PUSHBYTES_32 <x-only-public-key> OP_CHECKSIG`.
+UPDATE sbtc_signer.deposit_requests
+SET reclaim_script_kind = 'csv_p2pk'
+WHERE length(reclaim_script) > 36
+  AND substring(reclaim_script FROM length(reclaim_script) - 35 FOR 3) = '\xb27520'::BYTEA
+  AND get_byte(reclaim_script, length(reclaim_script) - 1) = 172;
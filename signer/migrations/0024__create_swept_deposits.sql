@@ -0,0 +1,18 @@
+-- The bitcoin miner fee assessed to each deposit request swept in by a
+-- confirmed sweep transaction. The fee is apportioned from the actual fee
+-- of the confirmed transaction, and the amount of sBTC minted for the
+-- deposit is the deposited amount less this fee.
+CREATE TABLE sbtc_signer.swept_deposits (
+    -- The ID of the sweep transaction that spent the deposit UTXO.
+    sweep_txid BYTEA NOT NULL,
+    -- The ID of the deposit request transaction.
+    txid BYTEA NOT NULL,
+    -- The index of the deposit UTXO in the deposit request transaction.
+    output_index INTEGER NOT NULL,
+    -- The portion of the fee of the sweep transaction, in sats, that is
+    -- assessed to the deposit.
+    assessed_fee BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (sweep_txid, txid, output_index),
+    FOREIGN KEY (txid, output_index) REFERENCES sbtc_signer.deposit_requests(txid, output_index) ON DELETE CASCADE
+);
@@ -0,0 +1,42 @@
+-- How far a sweep transaction proposed by the coordinator got. A sweep
+-- is `proposed` once enough signers acknowledged the pre-sign request,
+-- `signed` once the signing rounds for all of its inputs completed, and
+-- `broadcast` once the bitcoin node accepted it. It is `rejected` if any
+-- of these steps failed.
+CREATE TYPE sbtc_signer.sweep_attempt_outcome AS ENUM (
+    'proposed',
+    'signed',
+    'broadcast',
+    'rejected'
+);
+
+-- The history of the attempts at fulfilling deposit and withdrawal
+-- requests with a sweep transaction. Each row is for either a deposit
+-- request or a withdrawal request, never both.
+CREATE TABLE sbtc_signer.sweep_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    -- The ID of the deposit request transaction, for deposit requests.
+    txid BYTEA,
+    -- The index of the deposit UTXO, for deposit requests.
+    output_index INTEGER,
+    -- The ID of the withdrawal request, for withdrawal requests.
+    request_id BIGINT,
+    -- The stacks block ID of the block that includes the withdrawal
+    -- request, for withdrawal requests.
+    block_hash BYTEA,
+    -- The ID of the proposed sweep transaction.
+    sweep_txid BYTEA NOT NULL,
+    outcome sbtc_signer.sweep_attempt_outcome NOT NULL,
+    -- A description of why the attempt was rejected, if it was.
+    error_detail TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    CHECK (
+        (txid IS NOT NULL AND output_index IS NOT NULL AND request_id IS NULL AND block_hash IS NULL)
+        OR (txid IS NULL AND output_index IS NULL AND request_id IS NOT NULL AND block_hash IS NOT NULL)
+    )
+);
+
+CREATE INDEX ix_sweep_attempts_deposit
+    ON sbtc_signer.sweep_attempts(txid, output_index);
+CREATE INDEX ix_sweep_attempts_withdrawal
+    ON sbtc_signer.sweep_attempts(request_id, block_hash);
@@ -0,0 +1,22 @@
+-- Print events from the sbtc-registry smart contract that could not be
+-- parsed into one of the expected event types. The new_block handler
+-- skips these events, records them here so that they can be inspected
+-- later, and processes the rest of the events in the block.
+CREATE TABLE sbtc_signer.failed_events (
+    -- The index block hash of the stacks block with the event.
+    block_hash BYTEA NOT NULL,
+    -- The index of the event within the stacks block.
+    event_index BIGINT NOT NULL,
+    -- The ID of the stacks transaction that emitted the event.
+    txid BYTEA NOT NULL,
+    -- The topic of the event, or 'unknown' if it could not be read.
+    event_topic TEXT NOT NULL,
+    -- The path to the malformed field, if the problem is with a field.
+    field TEXT,
+    -- Why the event could not be parsed.
+    reason TEXT NOT NULL,
+    -- The clarity value of the event, as JSON.
+    payload TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (block_hash, event_index)
+);
@@ -0,0 +1,11 @@
+-- Bitcoin blocks that the stacks node told us it processed, through the
+-- POST /new_burn_block webhook. These tell us how far along the stacks
+-- node is with the bitcoin blockchain, which can lag behind the bitcoin
+-- blocks that the block observer learns about from bitcoin-core.
+CREATE TABLE sbtc_signer.stacks_burn_blocks (
+    -- The hash of the bitcoin block.
+    block_hash BYTEA PRIMARY KEY,
+    -- The height of the bitcoin block.
+    block_height BIGINT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
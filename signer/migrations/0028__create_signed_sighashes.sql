@@ -0,0 +1,21 @@
+-- Bitcoin input signature hashes that this signer emitted a signature
+-- share for, so that we can audit exactly what the signer signed. Rows
+-- are written just before the signature share is sent.
+CREATE TABLE sbtc_signer.signed_sighashes (
+    -- The signature hash that was signed.
+    sighash BYTEA PRIMARY KEY,
+    -- The ID of the bitcoin transaction with the input.
+    txid BYTEA NOT NULL,
+    -- The index of the input in the bitcoin transaction.
+    input_index INTEGER NOT NULL,
+    -- The bitcoin chain tip when the signature share was emitted.
+    chain_tip BYTEA NOT NULL,
+    -- The aggregate key that is locking the output spent by the input.
+    aggregate_key BYTEA NOT NULL,
+    -- The public key of the coordinator that requested the signature
+    -- share.
+    origin_public_key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX ix_signed_sighashes_txid ON sbtc_signer.signed_sighashes(txid);
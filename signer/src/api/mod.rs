@@ -3,8 +3,10 @@
 
 mod info;
 mod new_block;
+mod new_burn_block;
 mod router;
 mod status;
+mod validate_sweep;
 
 pub use new_block::new_block_handler;
 pub use router::get_router;
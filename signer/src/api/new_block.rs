@@ -2,13 +2,21 @@
 //! which is for processing new block webhooks from a stacks node.
 //!
 
+use std::net::SocketAddr;
+
+use axum::extract::ConnectInfo;
+use axum::extract::FromRequestParts;
 use axum::extract::State;
+use axum::http::HeaderValue;
 use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
 use clarity::vm::representations::ContractName;
 use clarity::vm::types::QualifiedContractIdentifier;
 use clarity::vm::types::StandardPrincipalData;
 use sbtc::events::RegistryEvent;
 use sbtc::events::TxInfo;
+use sha2::Sha256;
 use std::sync::OnceLock;
 
 use crate::context::Context;
@@ -16,12 +24,12 @@ use crate::error::Error;
 use crate::metrics::Metrics;
 use crate::metrics::STACKS_BLOCKCHAIN;
 use crate::storage::DbWrite;
-use crate::storage::model::CompletedDepositEvent;
-use crate::storage::model::KeyRotationEvent;
+use crate::storage::model::FailedEvent;
 use crate::storage::model::StacksBlock;
-use crate::storage::model::WithdrawalAcceptEvent;
-use crate::storage::model::WithdrawalRejectEvent;
-use crate::storage::model::WithdrawalRequest;
+use crate::storage::model::StacksBlockEvents;
+use crate::storage::model::StacksBlockHash;
+use hkdf::hmac::Hmac;
+use hkdf::hmac::Mac as _;
 use sbtc::webhooks::NewBlockEvent;
 
 use super::ApiState;
@@ -47,6 +55,82 @@ static SBTC_REGISTRY_IDENTIFIER: OnceLock<QualifiedContractIdentifier> = OnceLoc
 /// will be fine since it is twice as high as required.
 pub const EVENT_OBSERVER_BODY_LIMIT: usize = 8 * 1024 * 1024;
 
+/// An extractor that checks that a `POST /new_block` webhook comes from
+/// the configured stacks node. It runs before the body of the webhook is
+/// read, and rejects the webhook with `401 Unauthorized` otherwise.
+///
+/// When a webhook secret is configured, the `Authorization` header must
+/// match it. When allowed webhook sources are configured, the webhook
+/// must come from one of those IP addresses. When neither is configured,
+/// every webhook is accepted.
+#[derive(Debug)]
+pub struct AuthorizedWebhook;
+
+impl<C: Context> FromRequestParts<ApiState<C>> for AuthorizedWebhook {
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ApiState<C>,
+    ) -> Result<Self, Self::Rejection> {
+        let config = &state.ctx.config().signer.event_observer;
+
+        if let Some(secret) = config.webhook_secret.as_deref() {
+            let credentials = parts
+                .headers
+                .get(AUTHORIZATION)
+                .map(HeaderValue::as_bytes)
+                .unwrap_or_default();
+
+            if !secret_matches(secret, credentials) {
+                tracing::warn!("rejecting a webhook without the expected authorization header");
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+        }
+
+        if !config.allowed_webhook_sources.is_empty() {
+            // The source address is only known when the server is run
+            // with connect info, and we reject the webhook otherwise.
+            let source = parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_canonical());
+
+            if !source.is_some_and(|ip| config.allowed_webhook_sources.contains(&ip)) {
+                tracing::warn!(?source, "rejecting a webhook from an unexpected source");
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+        }
+
+        Ok(AuthorizedWebhook)
+    }
+}
+
+/// Whether the given credentials match the webhook secret. We compare
+/// HMAC tags of the two rather than the raw bytes, so that the time that
+/// the comparison takes does not depend on how much of the secret was
+/// guessed correctly.
+fn secret_matches(secret: &str, credentials: &[u8]) -> bool {
+    let tag = |bytes: &[u8]| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("BUG: HMAC keys can have any length");
+        mac.update(bytes);
+        mac
+    };
+    let expected = tag(secret.as_bytes()).finalize().into_bytes();
+    tag(credentials).verify_slice(&expected).is_ok()
+}
+
+/// A handler of `POST /new_block` webhook events that hands them to
+/// [`new_block_handler`] once they pass the [`AuthorizedWebhook`] check.
+pub async fn authorized_new_block_handler<C: Context>(
+    _: AuthorizedWebhook,
+    state: State<ApiState<C>>,
+    body: String,
+) -> StatusCode {
+    new_block_handler(state, body).await
+}
+
 /// A handler of `POST /new_block` webhook events.
 ///
 /// # Notes
@@ -60,8 +144,13 @@ pub const EVENT_OBSERVER_BODY_LIMIT: usize = 8 * 1024 * 1024;
 /// unless we encounter an error where retrying in a second might succeed,
 /// we will return a 200 OK status code.
 ///
-/// TODO: We need to be careful to only return a non success status code a
-/// fixed number of times.
+/// Even then, we only return a non success status code a fixed number of
+/// times for any one stacks block. Each failed attempt is recorded in the
+/// database, and once the number of attempts reaches the configured
+/// maximum, the webhook is marked as poisoned and we return 200 OK. The
+/// webhook body is kept so that poisoned webhooks can be found with
+/// [`crate::storage::DbRead::get_poisoned_webhooks`] and sent to this
+/// endpoint again.
 ///
 /// [^1]: <https://github.com/stacks-network/stacks-core/blob/09c4b066e25104be8b066e8f7530ff0c6df4ccd5/testnet/stacks-node/src/event_dispatcher.rs#L317-L385>
 #[tracing::instrument(skip_all, name = "new-block", fields(
@@ -122,8 +211,8 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
         .events
         .into_iter()
         .filter(|x| x.committed)
-        .filter_map(|x| x.contract_event.map(|ev| (ev, x.txid)))
-        .filter(|(ev, _)| &ev.contract_identifier == registry_address && ev.topic == "print")
+        .filter_map(|x| x.contract_event.map(|ev| (ev, x.txid, x.event_index)))
+        .filter(|(ev, _, _)| &ev.contract_identifier == registry_address && ev.topic == "print")
         .collect::<Vec<_>>();
 
     if events.is_empty() {
@@ -134,162 +223,162 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
 
     tracing::debug!(count = %events.len(), "processing events for new stacks block");
 
-    for (ev, txid) in events {
+    let mut block_events = StacksBlockEvents::default();
+    let mut failed_events = Vec::new();
+
+    for (ev, txid, event_index) in events {
         let tx_info = TxInfo {
             txid: sbtc::events::StacksTxid(txid.0),
             block_id,
         };
-        let res = match RegistryEvent::try_new(ev.value, tx_info) {
+        match RegistryEvent::try_new(ev.value.clone(), tx_info) {
             Ok(RegistryEvent::CompletedDeposit(event)) => {
-                handle_completed_deposit(&api.ctx, event.into()).await
+                block_events.completed_deposits.push(event.into())
             }
             Ok(RegistryEvent::WithdrawalAccept(event)) => {
-                handle_withdrawal_accept(&api.ctx, event.into()).await
+                block_events.withdrawal_accepts.push(event.into())
             }
             Ok(RegistryEvent::WithdrawalReject(event)) => {
-                handle_withdrawal_reject(&api.ctx, event.into()).await
+                block_events.withdrawal_rejects.push(event.into())
             }
             Ok(RegistryEvent::WithdrawalCreate(event)) => {
-                handle_withdrawal_create(&api.ctx, event.into()).await
-            }
-            Ok(RegistryEvent::KeyRotation(event)) => {
-                handle_key_rotation(&api.ctx, event.into()).await
+                block_events.withdrawal_requests.push(event.into())
             }
+            Ok(RegistryEvent::KeyRotation(event)) => block_events.key_rotations.push(event.into()),
+            // A malformed event only means that we skip that event. We
+            // record it so that it can be inspected later, and carry on
+            // with the rest of the events in the block.
             Err(error) => {
                 tracing::error!(%error, %txid, "got an error when transforming the event ClarityValue");
-                continue;
+                failed_events.push(FailedEvent {
+                    block_hash: stacks_chaintip.block_hash,
+                    event_index,
+                    txid: txid.into(),
+                    payload: serde_json::to_string(&ev.value)
+                        .unwrap_or_else(|_| format!("{:?}", ev.value)),
+                    event_topic: error.event_topic,
+                    field: error.field,
+                    reason: error.reason.to_string(),
+                });
             }
         };
-        // If we got an error writing to the database, this might be an
-        // issue that will resolve itself if we try again in a few moments.
-        // So we return a non success status code so that the node retries
-        // in a second.
-        if let Err(Error::SqlxQuery(error)) = res {
-            tracing::error!(%error, "could not write an event to the database");
-            return StatusCode::INTERNAL_SERVER_ERROR;
-        // If we got an error processing the event, we log the error and
+    }
+
+    let result = match write_failed_events(&api.ctx, &failed_events).await {
+        Ok(()) => handle_stacks_block_events(&api.ctx, &block_events).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(()) => {
+            let db = api.ctx.get_storage_mut();
+            if let Err(error) = db.clear_webhook_attempts(&stacks_chaintip.block_hash).await {
+                tracing::warn!(%error, "could not clear failed webhook attempts");
+            }
+            StatusCode::OK
+        }
+        // If we got a retryable error, such as a database connection issue,
+        // this might be an issue that will resolve itself if we try again
+        // in a few moments. So we return a non success status code so that
+        // the node retries in a second, unless we have already failed too
+        // many times. Writing the events is idempotent, so the retry will
+        // not duplicate anything.
+        Err(error) if error.is_retryable() => {
+            tracing::error!(%error, "could not write the events to the database");
+            record_failed_attempt(&api.ctx, &stacks_chaintip.block_hash, &body).await
+        }
+        // If we got an error processing the events, we log the error and
         // return a success status code so that the node does not retry the
         // webhook. We rely on the redundancy of the other sBTC signers to
         // ensure that the update is sent to Emily.
-        } else if let Err(error) = res {
-            tracing::error!(%error, "could not process an event");
+        Err(error) => {
+            tracing::error!(%error, "could not process the events");
+            StatusCode::OK
         }
     }
-
-    StatusCode::OK
 }
 
-/// Processes a completed deposit event by adding the event to the database.
+/// Record a failed attempt at processing the webhook for the given
+/// stacks block and return the status code that we should respond with.
 ///
-/// # Parameters
-/// - `ctx`: Shared application context containing configuration and database access.
-/// - `event`: The deposit event to be processed.
-///
-/// # Returns
-/// - `Result<(), Error>`: In case of a database error, returns an `Error`
-#[tracing::instrument(skip_all, fields(
-    bitcoin_outpoint = %event.outpoint,
-    stacks_txid = %event.txid
-))]
-async fn handle_completed_deposit(
+/// We respond with a non success status code, so that the node retries
+/// the webhook, until the number of failed attempts reaches the
+/// configured maximum. After that the webhook is marked as poisoned and
+/// we respond with 200 OK.
+async fn record_failed_attempt(
     ctx: &impl Context,
-    event: CompletedDepositEvent,
-) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_completed_deposit_event(&event)
-        .await?;
-
-    tracing::debug!(topic = "completed-deposit", "handled stacks event");
-    Ok(())
-}
+    block_hash: &StacksBlockHash,
+    body: &str,
+) -> StatusCode {
+    let max_attempts = ctx.config().signer.event_observer.max_webhook_attempts;
+    let db = ctx.get_storage_mut();
+
+    let failed_attempts = match db.record_failed_webhook_attempt(block_hash, body).await {
+        Ok(failed_attempts) => failed_attempts,
+        // If we cannot record the attempt then the database is probably
+        // unavailable, which should resolve itself, so the node should
+        // keep retrying.
+        Err(error) => {
+            tracing::warn!(%error, "could not record the failed webhook attempt");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    };
 
-/// Handles a withdrawal acceptance event by adding the event to the database.
-///
-/// # Parameters
-/// - `ctx`: Shared application context with configuration and database access.
-/// - `event`: The withdrawal acceptance event to be processed.
-///
-/// # Returns
-/// - `Result<(), Error>`: In case of a database error, returns an `Error`
-#[tracing::instrument(skip_all, fields(
-    stacks_txid = %event.txid,
-    request_id = %event.request_id
-))]
-async fn handle_withdrawal_accept(
-    ctx: &impl Context,
-    event: WithdrawalAcceptEvent,
-) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_accept_event(&event)
-        .await?;
+    if failed_attempts < max_attempts.get() {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
 
-    tracing::debug!(topic = "withdrawal-accept", "handled stacks event");
+    if let Err(error) = db.mark_webhook_poisoned(block_hash).await {
+        tracing::warn!(%error, "could not mark the webhook as poisoned");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
 
-    Ok(())
+    tracing::error!(
+        %failed_attempts,
+        "giving up on the webhook after too many failed attempts, marked it as poisoned"
+    );
+    StatusCode::OK
 }
 
-/// Processes a withdrawal creation event by adding the event to the database.
-///
-/// # Parameters
-/// - `ctx`: Shared application context containing configuration and database access.
-/// - `event`: The withdrawal creation event to be processed.
-///
-/// # Returns
-/// - `Result<(), Error>`: In case of a database error, returns an `Error`
-#[tracing::instrument(skip_all, fields(
-    stacks_txid = %event.txid,
-    request_id = %event.request_id
-))]
-async fn handle_withdrawal_create(
-    ctx: &impl Context,
-    event: WithdrawalRequest,
-) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_request(&event)
-        .await?;
-
-    tracing::debug!(topic = "withdrawal-create", "handled stacks event");
-
+/// Records the sbtc-registry events in a stacks block that could not be
+/// parsed, so that they can be inspected later.
+async fn write_failed_events(ctx: &impl Context, events: &[FailedEvent]) -> Result<(), Error> {
+    let db = ctx.get_storage_mut();
+    for event in events {
+        db.write_failed_event(event).await?;
+    }
     Ok(())
 }
 
-/// Processes a withdrawal rejection event by adding the event to the database.
+/// Writes all of the sbtc-registry events for a stacks block to the
+/// database in a single database transaction.
 ///
 /// # Parameters
 /// - `ctx`: Shared application context containing configuration and database access.
-/// - `event`: The withdrawal rejection event to be processed.
+/// - `events`: The events that were emitted in the stacks block.
 ///
 /// # Returns
 /// - `Result<(), Error>`: In case of a database error, returns an `Error`
 #[tracing::instrument(skip_all, fields(
-    stacks_txid = %event.txid,
-    request_id = %event.request_id
+    completed_deposits = events.completed_deposits.len(),
+    withdrawal_requests = events.withdrawal_requests.len(),
+    withdrawal_accepts = events.withdrawal_accepts.len(),
+    withdrawal_rejects = events.withdrawal_rejects.len(),
+    key_rotations = events.key_rotations.len(),
 ))]
-async fn handle_withdrawal_reject(
+async fn handle_stacks_block_events(
     ctx: &impl Context,
-    event: WithdrawalRejectEvent,
+    events: &StacksBlockEvents,
 ) -> Result<(), Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_reject_event(&event)
-        .await?;
-
-    tracing::debug!(topic = "withdrawal-reject", "handled stacks event");
-
-    Ok(())
-}
+    if events.is_empty() {
+        return Ok(());
+    }
 
-#[tracing::instrument(skip_all, fields(
-    stacks_txid = %event.txid,
-    address = %event.address,
-    aggregate_key = %event.aggregate_key
-))]
-async fn handle_key_rotation(ctx: &impl Context, event: KeyRotationEvent) -> Result<(), Error> {
     ctx.get_storage_mut()
-        .write_rotate_keys_transaction(&event)
+        .write_stacks_block_events(events)
         .await?;
 
-    tracing::debug!(topic = "key-rotation", "handled stacks event");
-
+    tracing::debug!("handled stacks events");
     Ok(())
 }
 
@@ -306,16 +395,22 @@ mod tests {
     use fake::Fake;
     use rand::rngs::OsRng;
     use sbtc::events::KeyRotationEvent;
+    use sbtc::events::UNKNOWN_EVENT_TOPIC;
     use secp256k1::SECP256K1;
     use stacks_common::types::chainstate::StacksBlockId;
     use test_case::test_case;
     use tower::ServiceExt;
 
     use crate::api::get_router;
+    use crate::storage::DbRead as _;
     use crate::storage::in_memory::Store;
+    use crate::storage::model::CompletedDepositEvent;
     use crate::storage::model::DepositRequest;
     use crate::storage::model::StacksPrincipal;
     use crate::storage::model::StacksTxId;
+    use crate::storage::model::WithdrawalAcceptEvent;
+    use crate::storage::model::WithdrawalRejectEvent;
+    use crate::storage::model::WithdrawalRequest;
     use crate::testing::context::*;
     use crate::testing::get_rng;
     use crate::testing::storage::model::TestData;
@@ -373,6 +468,70 @@ mod tests {
         assert!(!table_is_empty(db.lock().await));
     }
 
+    /// Once a webhook is processed successfully, any record of earlier
+    /// failed attempts at processing it is removed.
+    #[tokio::test]
+    async fn successful_webhook_clears_failed_attempts() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let event: NewBlockEvent = serde_json::from_str(WITHDRAWAL_REJECT_WEBHOOK).unwrap();
+        let block_hash = event.index_block_hash.into();
+
+        let db = ctx.inner_storage();
+        for _ in 0..2 {
+            db.record_failed_webhook_attempt(&block_hash, WITHDRAWAL_REJECT_WEBHOOK)
+                .await
+                .unwrap();
+        }
+        db.mark_webhook_poisoned(&block_hash).await.unwrap();
+        assert_eq!(db.get_poisoned_webhooks().await.unwrap().len(), 1);
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, WITHDRAWAL_REJECT_WEBHOOK.to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        assert!(db.get_poisoned_webhooks().await.unwrap().is_empty());
+        assert!(db.lock().await.webhook_attempts.is_empty());
+    }
+
+    /// The stacks node retries webhooks until it gets a success response,
+    /// so we check that handling the same webhook twice does not write
+    /// anything new the second time around.
+    #[test_case(COMPLETED_DEPOSIT_WEBHOOK, |db| db.completed_deposit_events.len(); "completed-deposit")]
+    #[test_case(WITHDRAWAL_CREATE_WEBHOOK, |db| db.stacks_block_to_withdrawal_requests.values().map(Vec::len).sum(); "withdrawal-create")]
+    #[test_case(WITHDRAWAL_ACCEPT_WEBHOOK, |db| db.withdrawal_accept_events.len(); "withdrawal-accept")]
+    #[test_case(WITHDRAWAL_REJECT_WEBHOOK, |db| db.withdrawal_reject_events.len(); "withdrawal-reject")]
+    #[test_case(ROTATE_KEYS_WEBHOOK, |db| db.rotate_keys_transactions.len(); "rotate-keys")]
+    #[tokio::test]
+    async fn replayed_webhooks_are_idempotent<F>(body_str: &str, row_count: F)
+    where
+        F: Fn(tokio::sync::MutexGuard<'_, Store>) -> usize,
+    {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let db = ctx.inner_storage();
+        assert_eq!(row_count(db.lock().await), 0);
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, body_str.to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let count = row_count(db.lock().await);
+        assert!(count > 0);
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, body_str.to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        assert_eq!(row_count(db.lock().await), count);
+    }
+
     #[test_case(COMPLETED_DEPOSIT_WEBHOOK, |db| db.completed_deposit_events.get(&OutPoint::null()).is_none(); "completed-deposit")]
     #[test_case(WITHDRAWAL_CREATE_WEBHOOK, |db| db.withdrawal_requests.get(&(1, StacksBlockId::from_hex("75b02b9884ec41c05f2cfa6e20823328321518dd0b027e7b609b63d4d1ea7c78").unwrap().into())).is_none(); "withdrawal-create")]
     #[test_case(WITHDRAWAL_ACCEPT_WEBHOOK, |db| db.withdrawal_accept_events.get(&1).is_none(); "withdrawal-accept")]
@@ -461,6 +620,7 @@ mod tests {
             num_withdraw_requests_per_block: 1,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
         let db = ctx.inner_storage();
         let test_data = TestData::generate(&mut rng, &[], &test_params);
@@ -488,7 +648,11 @@ mod tests {
             sweep_block_height: bitcoin_block.block_height,
             sweep_txid: txid.into(),
         };
-        let res = handle_completed_deposit(&ctx, event).await;
+        let events = StacksBlockEvents {
+            completed_deposits: vec![event],
+            ..Default::default()
+        };
+        let res = handle_stacks_block_events(&ctx, &events).await;
         assert!(res.is_ok());
         let db = db.lock().await;
         assert_eq!(db.completed_deposit_events.len(), 1);
@@ -518,6 +682,7 @@ mod tests {
             num_withdraw_requests_per_block: 2,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
 
         let db = ctx.inner_storage();
@@ -541,7 +706,11 @@ mod tests {
             sweep_txid: txid.into(),
         };
 
-        let res = handle_withdrawal_accept(&ctx, event).await;
+        let events = StacksBlockEvents {
+            withdrawal_accepts: vec![event],
+            ..Default::default()
+        };
+        let res = handle_stacks_block_events(&ctx, &events).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -568,6 +737,7 @@ mod tests {
             num_withdraw_requests_per_block: 2,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
 
         let db = ctx.inner_storage();
@@ -588,7 +758,11 @@ mod tests {
             bitcoin_block_height: test_data.bitcoin_blocks[0].block_height,
         };
 
-        let res = handle_withdrawal_create(&ctx, event).await;
+        let events = StacksBlockEvents {
+            withdrawal_requests: vec![event],
+            ..Default::default()
+        };
+        let res = handle_stacks_block_events(&ctx, &events).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -621,6 +795,7 @@ mod tests {
             num_withdraw_requests_per_block: 2,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
 
         let test_data = TestData::generate(&mut rng, &[], &test_params);
@@ -638,7 +813,11 @@ mod tests {
             signer_bitmap: BitArray::<_>::ZERO,
         };
 
-        let res = handle_withdrawal_reject(&ctx, event).await;
+        let events = StacksBlockEvents {
+            withdrawal_rejects: vec![event],
+            ..Default::default()
+        };
+        let res = handle_stacks_block_events(&ctx, &events).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -671,7 +850,11 @@ mod tests {
             new_signature_threshold: 3,
         };
 
-        let res = handle_key_rotation(&ctx, event.into()).await;
+        let events = StacksBlockEvents {
+            key_rotations: vec![event.into()],
+            ..Default::default()
+        };
+        let res = handle_stacks_block_events(&ctx, &events).await;
 
         assert!(res.is_ok());
         let db = db.lock().await;
@@ -715,6 +898,94 @@ mod tests {
         }
     }
 
+    /// Send the rotate keys webhook through the router, with the given
+    /// `Authorization` header and source address, and return the
+    /// response status.
+    async fn send_webhook<C: Context + 'static>(
+        ctx: &C,
+        authorization: Option<&str>,
+        source: Option<SocketAddr>,
+    ) -> StatusCode {
+        let app = get_router().with_state(ApiState { ctx: ctx.clone() });
+
+        let mut request = Request::builder().uri("/new_block").method(Method::POST);
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        if let Some(source) = source {
+            request = request.extension(ConnectInfo(source));
+        }
+        let request = request.body(Body::from(ROTATE_KEYS_WEBHOOK)).unwrap();
+
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[test_case(None, StatusCode::OK; "no secret configured")]
+    #[test_case(Some("hunter2"), StatusCode::OK; "valid credentials")]
+    #[test_case(Some("hunter3"), StatusCode::UNAUTHORIZED; "wrong credentials")]
+    #[test_case(Some("hunter"), StatusCode::UNAUTHORIZED; "secret prefix")]
+    #[tokio::test]
+    async fn webhook_secret_is_checked(authorization: Option<&str>, expected: StatusCode) {
+        let secret = authorization.map(|_| "hunter2".to_string());
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| settings.signer.event_observer.webhook_secret = secret)
+            .build();
+
+        let status = send_webhook(&ctx, authorization, None).await;
+        assert_eq!(status, expected);
+
+        let db = ctx.inner_storage();
+        let written = !db.lock().await.rotate_keys_transactions.is_empty();
+        assert_eq!(written, expected == StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn webhooks_without_credentials_are_rejected() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.webhook_secret = Some("hunter2".to_string())
+            })
+            .build();
+
+        let status = send_webhook(&ctx, None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(
+            ctx.inner_storage()
+                .lock()
+                .await
+                .rotate_keys_transactions
+                .is_empty()
+        );
+    }
+
+    #[test_case(Some("10.0.0.5:20443"), StatusCode::OK; "allowed source")]
+    #[test_case(Some("[::ffff:10.0.0.5]:20443"), StatusCode::OK; "ipv4-mapped allowed source")]
+    #[test_case(Some("10.0.0.6:20443"), StatusCode::UNAUTHORIZED; "unexpected source")]
+    #[test_case(None, StatusCode::UNAUTHORIZED; "unknown source")]
+    #[tokio::test]
+    async fn webhook_source_is_checked(source: Option<&str>, expected: StatusCode) {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.allowed_webhook_sources =
+                    vec!["10.0.0.5".parse().unwrap()]
+            })
+            .build();
+
+        let source = source.map(|addr| addr.parse().unwrap());
+        let status = send_webhook(&ctx, None, source).await;
+        assert_eq!(status, expected);
+
+        let db = ctx.inner_storage();
+        let written = !db.lock().await.rotate_keys_transactions.is_empty();
+        assert_eq!(written, expected == StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_invalid_event() {
         let ctx = TestContext::builder()
@@ -751,5 +1022,130 @@ mod tests {
         // But we expect the second (valid) event to be processed anyway
         assert_eq!(res, StatusCode::OK);
         assert!(!db.lock().await.rotate_keys_transactions.is_empty());
+
+        // And the invalid one to be recorded for later inspection.
+        let block_hash = new_block_event.index_block_hash.into();
+        let failed_events = db.get_failed_events(&block_hash).await.unwrap();
+        assert_eq!(failed_events.len(), 1);
+        assert_eq!(failed_events[0].event_index, failing_event.event_index);
+        assert_eq!(failed_events[0].event_topic, UNKNOWN_EVENT_TOPIC);
+        assert_eq!(failed_events[0].field, None);
+    }
+
+    /// Change the print event in the given webhook, which must have
+    /// exactly one event.
+    fn modify_print_event<F>(body: &str, modify: F) -> String
+    where
+        F: FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    {
+        let mut webhook: serde_json::Value = serde_json::from_str(body).unwrap();
+        let events = webhook["events"].as_array_mut().unwrap();
+        assert_eq!(events.len(), 1);
+
+        let data_map = events[0]["contract_event"]["value"]["Tuple"]["data_map"]
+            .as_object_mut()
+            .unwrap();
+        modify(data_map);
+        webhook.to_string()
+    }
+
+    /// A malformed event is skipped and recorded, and the webhook is not
+    /// retried. Replaying the webhook does not record the event twice.
+    #[test_case(|event| { event.remove("signer-bitmap"); }, "signer-bitmap"; "field removed")]
+    #[test_case(|event| { event["signer-bitmap"] = serde_json::json!({"Int": 1}); }, "signer-bitmap"; "wrong-typed field")]
+    #[test_case(|event| { event.insert("memo".to_string(), serde_json::json!({"UInt": 1})); }, "memo"; "extra field")]
+    #[tokio::test]
+    async fn malformed_events_are_recorded<F>(modify: F, field: &str)
+    where
+        F: FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+    {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let body = modify_print_event(WITHDRAWAL_REJECT_WEBHOOK, modify);
+        let new_block_event: NewBlockEvent = serde_json::from_str(&body).unwrap();
+        let block_hash = new_block_event.index_block_hash.into();
+
+        let db = ctx.inner_storage();
+        for _ in 0..2 {
+            let state = State(ApiState { ctx: ctx.clone() });
+            let res = new_block_handler(state, body.clone()).await;
+            assert_eq!(res, StatusCode::OK);
+        }
+
+        assert!(db.lock().await.withdrawal_reject_events.is_empty());
+
+        let failed_events = db.get_failed_events(&block_hash).await.unwrap();
+        assert_eq!(failed_events.len(), 1);
+        assert_eq!(failed_events[0].event_topic, "withdrawal-reject");
+        assert_eq!(failed_events[0].field.as_deref(), Some(field));
+        assert!(failed_events[0].payload.contains("request-id"));
+    }
+
+    /// Install a global prometheus recorder, once, and return a handle to
+    /// it. Metrics recorded before this is called are dropped.
+    fn prometheus_handle() -> metrics_exporter_prometheus::PrometheusHandle {
+        static HANDLE: std::sync::OnceLock<metrics_exporter_prometheus::PrometheusHandle> =
+            std::sync::OnceLock::new();
+        HANDLE
+            .get_or_init(|| {
+                metrics_exporter_prometheus::PrometheusBuilder::new()
+                    .install_recorder()
+                    .unwrap()
+            })
+            .clone()
+    }
+
+    /// Scrape the metrics endpoint and return the value of the stacks
+    /// blocks observed counter.
+    async fn scrape_stacks_blocks_observed(
+        handle: metrics_exporter_prometheus::PrometheusHandle,
+    ) -> u64 {
+        let request = Request::builder()
+            .uri("/metrics")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+
+        let response = crate::metrics::get_router(handle)
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        body.lines()
+            .find_map(|line| line.strip_prefix("blocks_observed_total{blockchain=\"stacks\"} "))
+            .map(|value| value.parse().unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Processing a webhook increments the blocks observed counter, and
+    /// the new value is served from the metrics endpoint.
+    #[tokio::test]
+    async fn webhooks_are_counted_in_scraped_metrics() {
+        let handle = prometheus_handle();
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let before = scrape_stacks_blocks_observed(handle.clone()).await;
+
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, WITHDRAWAL_CREATE_WEBHOOK.to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        // Other tests may be driving the handler concurrently, so we can
+        // only say that the counter went up by at least one.
+        let after = scrape_stacks_blocks_observed(handle).await;
+        assert!(after > before);
     }
 }
@@ -0,0 +1,186 @@
+//! This module contains the handler for the `POST /new_burn_block`
+//! endpoint, which is for processing new burn block webhooks from a
+//! stacks node.
+//!
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use sbtc::webhooks::NewBurnBlockEvent;
+
+use crate::context::Context;
+use crate::context::SignerEvent;
+use crate::storage::DbWrite;
+use crate::storage::model::BitcoinBlockRef;
+
+use super::ApiState;
+use super::new_block::AuthorizedWebhook;
+
+/// A handler of `POST /new_burn_block` webhook events.
+///
+/// # Notes
+///
+/// The stacks node sends this webhook once it has processed a bitcoin
+/// block. We record the block, so that we know how far along the stacks
+/// node is with the bitcoin blockchain, and signal that the block was
+/// observed so that other components can get ready for it.
+///
+/// Like with `POST /new_block` webhooks, the node retries the webhook
+/// until it gets a success response[^1]. The block observer learns of
+/// every bitcoin block from bitcoin-core directly, so nothing here is
+/// critical, and we always return a 200 OK status code once the webhook
+/// is authorized.
+///
+/// [^1]: <https://github.com/stacks-network/stacks-core/blob/09c4b066e25104be8b066e8f7530ff0c6df4ccd5/testnet/stacks-node/src/event_dispatcher.rs#L317-L385>
+#[tracing::instrument(skip_all, name = "new-burn-block", fields(
+    block_hash = tracing::field::Empty,
+    block_height = tracing::field::Empty,
+))]
+pub async fn new_burn_block_handler<C: Context>(
+    _: AuthorizedWebhook,
+    state: State<ApiState<C>>,
+    body: String,
+) -> StatusCode {
+    let api = state.0;
+
+    let event: NewBurnBlockEvent = match serde_json::from_str(&body) {
+        Ok(value) => value,
+        // Retrying will not make the body deserialize, so we log the
+        // error and return `200 OK` so that the node does not retry the
+        // webhook.
+        Err(error) => {
+            tracing::error!(%body, %error, "could not deserialize POST /new_burn_block webhook:");
+            return StatusCode::OK;
+        }
+    };
+
+    let block = BitcoinBlockRef {
+        block_hash: event.burn_block_hash.into(),
+        block_height: event.burn_block_height.into(),
+    };
+
+    let span = tracing::span::Span::current();
+    span.record("block_hash", block.block_hash.to_string());
+    span.record("block_height", *block.block_height);
+
+    tracing::debug!("received a new burn block event from stacks-core");
+
+    if let Err(error) = api
+        .ctx
+        .get_storage_mut()
+        .write_stacks_burn_block(&block)
+        .await
+    {
+        tracing::warn!(%error, "could not write the stacks burn block to the database");
+    }
+
+    if let Err(error) = api
+        .ctx
+        .signal(SignerEvent::StacksBurnBlockObserved(block).into())
+    {
+        tracing::warn!(%error, "could not signal that the stacks node observed a burn block");
+    }
+
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use axum::http::header::AUTHORIZATION;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::context::SignerSignal;
+    use crate::storage::DbRead as _;
+    use crate::testing::context::*;
+
+    use super::*;
+
+    /// A POST /new_burn_block payload in the format that stacks-core sends
+    /// it.
+    const NEW_BURN_BLOCK_WEBHOOK: &str =
+        include_str!("../../tests/fixtures/new-burn-block-event.json");
+
+    async fn send_burn_block_webhook<C: Context + 'static>(
+        ctx: &C,
+        authorization: Option<&str>,
+        body: &'static str,
+    ) -> StatusCode {
+        let app: Router = get_router().with_state(ApiState { ctx: ctx.clone() });
+
+        let mut request = Request::builder()
+            .uri("/new_burn_block")
+            .method(Method::POST);
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        let request = request.body(Body::from(body)).unwrap();
+
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn burn_blocks_are_recorded_and_signalled() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let mut signal_rx = ctx.get_signal_receiver();
+
+        let status = send_burn_block_webhook(&ctx, None, NEW_BURN_BLOCK_WEBHOOK).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let event: NewBurnBlockEvent = serde_json::from_str(NEW_BURN_BLOCK_WEBHOOK).unwrap();
+        let expected = BitcoinBlockRef {
+            block_hash: event.burn_block_hash.into(),
+            block_height: 159u64.into(),
+        };
+
+        let tip = ctx.get_storage().get_stacks_burn_chain_tip().await.unwrap();
+        assert_eq!(tip, Some(expected));
+
+        let signal = signal_rx.try_recv().unwrap();
+        assert_eq!(
+            signal,
+            SignerSignal::Event(SignerEvent::StacksBurnBlockObserved(expected))
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_burn_blocks_are_acknowledged() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let status = send_burn_block_webhook(&ctx, None, "{}").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let tip = ctx.get_storage().get_stacks_burn_chain_tip().await.unwrap();
+        assert!(tip.is_none());
+    }
+
+    #[tokio::test]
+    async fn burn_block_webhooks_are_authorized() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.webhook_secret = Some("hunter2".to_string())
+            })
+            .build();
+
+        let status = send_burn_block_webhook(&ctx, None, NEW_BURN_BLOCK_WEBHOOK).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let tip = ctx.get_storage().get_stacks_burn_chain_tip().await.unwrap();
+        assert!(tip.is_none());
+
+        let status = send_burn_block_webhook(&ctx, Some("hunter2"), NEW_BURN_BLOCK_WEBHOOK).await;
+        assert_eq!(status, StatusCode::OK);
+        let tip = ctx.get_storage().get_stacks_burn_chain_tip().await.unwrap();
+        assert!(tip.is_some());
+    }
+}
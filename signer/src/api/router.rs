@@ -11,25 +11,56 @@ use crate::context::Context;
 
 use axum::http::StatusCode;
 
-use super::{ApiState, info, new_block, status};
+use super::{ApiState, info, new_block, new_burn_block, status, validate_sweep};
 
-async fn new_attachment_handler() -> StatusCode {
+/// The stacks node event observer paths for events that the signer does
+/// not use. The node sends these events to every observer regardless of
+/// what it subscribed to, and keeps retrying them until it gets a success
+/// response, so we acknowledge them to keep the node logs clean.
+///
+/// TODO: remove `/attachments/new` once
+/// https://github.com/stacks-network/stacks-core/issues/5558 is addressed
+const IGNORED_EVENT_OBSERVER_PATHS: [&str; 9] = [
+    "/new_mempool_tx",
+    "/drop_mempool_tx",
+    "/new_microblocks",
+    "/attachments/new",
+    "/mined_block",
+    "/mined_microblock",
+    "/mined_nakamoto_block",
+    "/stackerdb_chunks",
+    "/proposal_response",
+];
+
+async fn ignored_event_handler() -> StatusCode {
     StatusCode::OK
 }
 
 /// Return the default router
 pub fn get_router<C: Context + 'static>() -> Router<ApiState<C>> {
-    Router::new()
+    let router = Router::new()
         .route("/", get(status::status_handler))
         .route("/info", get(info::info_handler))
+        .route("/status", get(status::signer_status_handler))
         .route(
             "/new_block",
-            post(new_block::new_block_handler)
+            post(new_block::authorized_new_block_handler)
                 .layer(DefaultBodyLimit::max(new_block::EVENT_OBSERVER_BODY_LIMIT)),
         )
-        // TODO: remove this once https://github.com/stacks-network/stacks-core/issues/5558
-        // is addressed
-        .route("/attachments/new", post(new_attachment_handler))
+        .route(
+            "/new_burn_block",
+            post(new_burn_block::new_burn_block_handler),
+        )
+        .route(
+            "/validate-sweep",
+            post(validate_sweep::validate_sweep_handler),
+        );
+
+    IGNORED_EVENT_OBSERVER_PATHS
+        .into_iter()
+        .fold(router, |router, path| {
+            router.route(path, post(ignored_event_handler))
+        })
 }
 
 #[cfg(test)]
@@ -39,6 +70,7 @@ mod tests {
         body::Body,
         http::{Method, Request, StatusCode},
     };
+    use test_case::test_case;
     use tower::ServiceExt;
 
     use crate::{
@@ -46,15 +78,24 @@ mod tests {
         testing::context::TestContext,
     };
 
+    #[test_case("/new_mempool_tx"; "new mempool tx")]
+    #[test_case("/drop_mempool_tx"; "drop mempool tx")]
+    #[test_case("/new_microblocks"; "new microblocks")]
+    #[test_case("/attachments/new"; "new attachment")]
+    #[test_case("/mined_block"; "mined block")]
+    #[test_case("/mined_microblock"; "mined microblock")]
+    #[test_case("/mined_nakamoto_block"; "mined nakamoto block")]
+    #[test_case("/stackerdb_chunks"; "stackerdb chunks")]
+    #[test_case("/proposal_response"; "proposal response")]
     #[tokio::test]
-    async fn test_new_attachment() {
+    async fn ignored_events_are_acknowledged(path: &str) {
         let context = TestContext::default_mocked();
 
         let state = ApiState { ctx: context.clone() };
         let app: Router = get_router().with_state(state);
 
         let request = Request::builder()
-            .uri("/attachments/new")
+            .uri(path)
             .method(Method::POST)
             .body(Body::empty())
             .unwrap();
@@ -1,8 +1,388 @@
-//! This module is for the `GET /` endpoint, which just returns the status.
+//! This module is for the `GET /` endpoint, which just returns the status,
+//! and the `GET /status` endpoint, which summarizes what the signer knows
+//! from its database and the state of its P2P connections.
 
+use axum::Json;
+use axum::extract::State;
 use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::storage::DbRead;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::SignedSigHash;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksBlockHeight;
+use crate::storage::model::SweepAttemptCounts;
+
+use super::ApiState;
+use super::info::ChainTipInfo;
+
+/// The number of the most recently signed sighashes that are included in
+/// the response of the `/status` endpoint.
+const RECENT_SIGNED_SIGHASHES: u16 = 10;
 
 /// A basic handler that responds with 200 OK
 pub async fn status_handler() -> StatusCode {
     StatusCode::OK
 }
+
+/// The response for the `/status` endpoint.
+#[derive(Debug, Serialize)]
+pub struct SignerStatusResponse {
+    pub version: &'static str,
+    pub public_key: String,
+    pub bitcoin_tip: Option<ChainTipInfo<BitcoinBlockHash, BitcoinBlockHeight>>,
+    pub stacks_tip: Option<ChainTipInfo<StacksBlockHash, StacksBlockHeight>>,
+    pub signer_utxo: Option<SignerUtxoInfo>,
+    pub aggregate_key: Option<String>,
+    pub pending_deposit_requests: Option<usize>,
+    pub pending_withdrawal_requests: Option<usize>,
+    pub sweep_attempts: Option<SweepAttemptCounts>,
+    pub recent_signed_sighashes: Option<Vec<SignedSigHashInfo>>,
+    pub p2p_connected_peers: usize,
+    pub p2p_mesh_peers: usize,
+}
+
+/// The signer UTXO as seen from the canonical bitcoin chain tip.
+#[derive(Debug, Serialize)]
+pub struct SignerUtxoInfo {
+    pub outpoint: String,
+    pub amount: u64,
+    pub public_key: String,
+}
+
+/// A bitcoin input sighash that this signer emitted a signature share for.
+#[derive(Debug, Serialize)]
+pub struct SignedSigHashInfo {
+    pub sighash: String,
+    pub txid: String,
+    pub input_index: u32,
+    pub chain_tip: String,
+    pub aggregate_key: String,
+    pub origin_public_key: String,
+}
+
+impl From<SignedSigHash> for SignedSigHashInfo {
+    fn from(signed: SignedSigHash) -> Self {
+        SignedSigHashInfo {
+            sighash: signed.sighash.to_string(),
+            txid: signed.txid.to_string(),
+            input_index: signed.input_index,
+            chain_tip: signed.chain_tip.to_string(),
+            aggregate_key: signed.aggregate_key.to_string(),
+            origin_public_key: signed.origin_public_key.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for SignerStatusResponse {
+    fn into_response(self) -> axum::response::Response {
+        Json(self).into_response()
+    }
+}
+
+/// Handler for the `/status` endpoint. The response is built from the
+/// signer's database and its in-memory P2P swarm status only, so it is
+/// cheap to compute. This method is infallible and returns `null` for any
+/// missing information.
+///
+/// The pending request counts are the number of requests in the context
+/// window that this signer has not voted on yet. The sweep attempt counts
+/// are the number of attempts with each outcome that this signer recorded
+/// while it was the coordinator. The signed sighashes are the most recent
+/// bitcoin input sighashes that this signer emitted signature shares for.
+pub async fn signer_status_handler<C: Context>(state: State<ApiState<C>>) -> SignerStatusResponse {
+    let storage = state.ctx.get_storage();
+    let config = state.ctx.config();
+
+    let p2p_status = state.ctx.state().p2p_status();
+
+    let mut response = SignerStatusResponse {
+        version: crate::VERSION,
+        public_key: config.signer.public_key().to_string(),
+        bitcoin_tip: None,
+        stacks_tip: None,
+        signer_utxo: None,
+        aggregate_key: None,
+        pending_deposit_requests: None,
+        pending_withdrawal_requests: None,
+        sweep_attempts: None,
+        recent_signed_sighashes: None,
+        p2p_connected_peers: p2p_status.num_connected_peers(),
+        p2p_mesh_peers: p2p_status.num_mesh_peers(),
+    };
+
+    let bitcoin_tip = match storage.get_bitcoin_canonical_chain_tip_ref().await {
+        Ok(Some(bitcoin_tip)) => bitcoin_tip,
+        Ok(None) => {
+            tracing::debug!("no local bitcoin tip found in the database.");
+            return response;
+        }
+        Err(error) => {
+            tracing::error!(%error, "error reading bitcoin tip from the database");
+            return response;
+        }
+    };
+    response.bitcoin_tip = Some(ChainTipInfo {
+        block_hash: bitcoin_tip.block_hash,
+        block_height: bitcoin_tip.block_height,
+    });
+    let chain_tip = &bitcoin_tip.block_hash;
+
+    match storage.get_stacks_chain_tip(chain_tip).await {
+        Ok(stacks_tip) => {
+            response.stacks_tip = stacks_tip.map(|block| ChainTipInfo {
+                block_hash: block.block_hash,
+                block_height: block.block_height,
+            });
+        }
+        Err(error) => {
+            tracing::error!(%error, "error reading local Stacks tip from the database");
+        }
+    }
+
+    match storage.get_signer_utxo(chain_tip).await {
+        Ok(utxo) => {
+            response.signer_utxo = utxo.map(|utxo| SignerUtxoInfo {
+                outpoint: utxo.outpoint.to_string(),
+                amount: utxo.amount,
+                public_key: utxo.public_key.to_string(),
+            });
+        }
+        Err(error) => {
+            tracing::error!(%error, "error reading the signer UTXO from the database");
+        }
+    }
+
+    match storage.get_last_key_rotation(chain_tip).await {
+        Ok(rotation) => {
+            response.aggregate_key = rotation.map(|rotation| rotation.aggregate_key.to_string());
+        }
+        Err(error) => {
+            tracing::error!(%error, "error reading the last key rotation from the database");
+        }
+    }
+
+    let context_window = config.signer.context_window;
+    let public_key = config.signer.public_key();
+
+    match storage
+        .get_pending_deposit_requests(chain_tip, context_window, &public_key)
+        .await
+    {
+        Ok(requests) => response.pending_deposit_requests = Some(requests.len()),
+        Err(error) => {
+            tracing::error!(%error, "error reading pending deposit requests from the database");
+        }
+    }
+
+    match storage
+        .get_pending_withdrawal_requests(chain_tip, context_window, &public_key)
+        .await
+    {
+        Ok(requests) => response.pending_withdrawal_requests = Some(requests.len()),
+        Err(error) => {
+            tracing::error!(%error, "error reading pending withdrawal requests from the database");
+        }
+    }
+
+    match storage.get_sweep_attempt_counts().await {
+        Ok(counts) => response.sweep_attempts = Some(counts),
+        Err(error) => {
+            tracing::error!(%error, "error reading sweep attempt counts from the database");
+        }
+    }
+
+    match storage
+        .get_recent_signed_sighashes(RECENT_SIGNED_SIGHASHES)
+        .await
+    {
+        Ok(signed) => {
+            response.recent_signed_sighashes = Some(signed.into_iter().map(Into::into).collect());
+        }
+        Err(error) => {
+            tracing::error!(%error, "error reading signed sighashes from the database");
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+
+    use crate::api::ApiState;
+    use crate::context::SwarmStatus;
+    use crate::storage::DbWrite as _;
+    use crate::storage::model::SweepAttempt;
+    use crate::storage::model::SweepAttemptOutcome;
+    use crate::testing::context::*;
+    use crate::testing::get_rng;
+    use crate::testing::storage::model::Params;
+    use crate::testing::storage::model::TestData;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_empty_database() {
+        // The clients are mocked without any expectations, so this also
+        // checks that the handler does not call the bitcoin or stacks
+        // nodes.
+        let context = TestContext::default_mocked();
+
+        let state = State(ApiState { ctx: context.clone() });
+        let result = signer_status_handler(state).await;
+
+        assert_eq!(result.version, crate::VERSION);
+        let public_key = context.config().signer.public_key();
+        assert_eq!(result.public_key, public_key.to_string());
+        assert!(result.bitcoin_tip.is_none());
+        assert!(result.stacks_tip.is_none());
+        assert!(result.signer_utxo.is_none());
+        assert!(result.aggregate_key.is_none());
+        assert!(result.pending_deposit_requests.is_none());
+        assert!(result.pending_withdrawal_requests.is_none());
+        assert!(result.sweep_attempts.is_none());
+        assert!(result.recent_signed_sighashes.is_none());
+        assert_eq!(result.p2p_connected_peers, 0);
+        assert_eq!(result.p2p_mesh_peers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_p2p_peer_counts() {
+        let context = TestContext::default_mocked();
+
+        let peer1 = libp2p::PeerId::random();
+        let peer2 = libp2p::PeerId::random();
+        let mut status = SwarmStatus::default();
+        status.connected_peers.insert(peer1, Vec::new());
+        status.connected_peers.insert(peer2, Vec::new());
+        status
+            .mesh_peers
+            .insert("sbtc-signer".to_string(), [peer1].into_iter().collect());
+        context.state().set_p2p_status(status);
+
+        let state = State(ApiState { ctx: context.clone() });
+        let result = signer_status_handler(state).await;
+
+        assert_eq!(result.p2p_connected_peers, 2);
+        assert_eq!(result.p2p_mesh_peers, 1);
+    }
+
+    #[tokio::test]
+    async fn test_populated_database() {
+        let mut rng = get_rng();
+        let context = TestContext::default_mocked();
+        let storage = context.get_storage_mut();
+
+        let params = Params {
+            num_bitcoin_blocks: 10,
+            num_stacks_blocks_per_bitcoin_block: 2,
+            num_deposit_requests_per_block: 2,
+            num_withdraw_requests_per_block: 2,
+            num_signers_per_request: 0,
+            consecutive_blocks: true,
+            deposit_scripts: None,
+        };
+        let signer_set = crate::testing::wsts::generate_signer_set_public_keys(&mut rng, 3);
+        let test_data = TestData::generate(&mut rng, &signer_set, &params);
+        test_data.write_to(&storage).await;
+
+        let state = State(ApiState { ctx: context.clone() });
+        let result = signer_status_handler(state).await;
+
+        let bitcoin_tip = storage
+            .get_bitcoin_canonical_chain_tip_ref()
+            .await
+            .unwrap()
+            .unwrap();
+        let stacks_tip = storage
+            .get_stacks_chain_tip(&bitcoin_tip.block_hash)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let result_bitcoin_tip = result.bitcoin_tip.unwrap();
+        assert_eq!(result_bitcoin_tip.block_hash, bitcoin_tip.block_hash);
+        assert_eq!(result_bitcoin_tip.block_height, bitcoin_tip.block_height);
+        let result_stacks_tip = result.stacks_tip.unwrap();
+        assert_eq!(result_stacks_tip.block_hash, stacks_tip.block_hash);
+        assert_eq!(result_stacks_tip.block_height, stacks_tip.block_height);
+
+        let context_window = context.config().signer.context_window;
+        let public_key = context.config().signer.public_key();
+        let deposits = storage
+            .get_pending_deposit_requests(&bitcoin_tip.block_hash, context_window, &public_key)
+            .await
+            .unwrap();
+        let withdrawals = storage
+            .get_pending_withdrawal_requests(&bitcoin_tip.block_hash, context_window, &public_key)
+            .await
+            .unwrap();
+        assert!(!deposits.is_empty());
+        assert!(!withdrawals.is_empty());
+        assert_eq!(result.pending_deposit_requests, Some(deposits.len()));
+        assert_eq!(result.pending_withdrawal_requests, Some(withdrawals.len()));
+
+        // There are no sweeps or key rotations in the test data.
+        assert!(result.signer_utxo.is_none());
+        assert!(result.aggregate_key.is_none());
+        assert_eq!(result.sweep_attempts, Some(SweepAttemptCounts::default()));
+        assert_eq!(result.recent_signed_sighashes.map(|s| s.len()), Some(0));
+
+        // Once the signers rotate keys, the new aggregate key is reported.
+        let key_rotation = crate::storage::model::KeyRotationEvent {
+            block_hash: stacks_tip.block_hash,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        storage
+            .write_rotate_keys_transaction(&key_rotation)
+            .await
+            .unwrap();
+
+        // Recorded sweep attempts are counted by their outcome.
+        let outcomes = [
+            SweepAttemptOutcome::Proposed,
+            SweepAttemptOutcome::Proposed,
+            SweepAttemptOutcome::Rejected,
+        ];
+        for outcome in outcomes {
+            let attempt = SweepAttempt {
+                outcome,
+                ..fake::Faker.fake_with_rng(&mut rng)
+            };
+            storage.write_sweep_attempt(&attempt).await.unwrap();
+        }
+
+        // Signed sighashes are reported, most recent first, up to a limit.
+        let signed_sighashes: Vec<SignedSigHash> = (0..RECENT_SIGNED_SIGHASHES + 2)
+            .map(|_| fake::Faker.fake_with_rng(&mut rng))
+            .collect();
+        for signed in signed_sighashes.iter() {
+            storage.write_signed_sighash(signed).await.unwrap();
+        }
+
+        let state = State(ApiState { ctx: context.clone() });
+        let result = signer_status_handler(state).await;
+        assert_eq!(
+            result.aggregate_key,
+            Some(key_rotation.aggregate_key.to_string())
+        );
+        let expected = SweepAttemptCounts {
+            proposed: 2,
+            rejected: 1,
+            ..Default::default()
+        };
+        assert_eq!(result.sweep_attempts, Some(expected));
+
+        let recent = result.recent_signed_sighashes.unwrap();
+        assert_eq!(recent.len(), RECENT_SIGNED_SIGHASHES as usize);
+        let most_recent = signed_sighashes.last().unwrap();
+        assert_eq!(recent[0].sighash, most_recent.sighash.to_string());
+        assert_eq!(recent[0].input_index, most_recent.input_index);
+    }
+}
@@ -0,0 +1,593 @@
+//! This module is for the `POST /validate-sweep` endpoint, which runs a
+//! proposed sweep transaction through this signer's validation without
+//! signing it, so that coordinators and operators can check whether this
+//! signer would accept it.
+//!
+//! The endpoint only reads from the database, and it is disabled unless
+//! `signer.event_observer.enable_validate_sweep` is set.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use bitcoin::OutPoint;
+use hex::FromHex as _;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::bitcoin::utxo::FeeAssessment as _;
+use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::validation::BitcoinTxContext;
+use crate::bitcoin::validation::BitcoinValidationReport;
+use crate::bitcoin::validation::InputValidationResult;
+use crate::bitcoin::validation::TxRequestIds;
+use crate::bitcoin::validation::WithdrawalValidationResult;
+use crate::context::Context;
+use crate::error::Error;
+use crate::message::BitcoinPreSignRequest;
+use crate::storage::DbRead;
+use crate::storage::model::DkgSharesStatus;
+use crate::storage::model::QualifiedRequestId;
+
+use super::ApiState;
+
+/// The body of a `POST /validate-sweep` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateSweepRequest {
+    /// The proposed sweep transaction, consensus encoded as hex.
+    #[serde(with = "bitcoin::consensus::serde::With::<bitcoin::consensus::serde::Hex>")]
+    pub tx: bitcoin::Transaction,
+    /// The deposit requests that the transaction sweeps in.
+    pub deposits: Vec<OutPoint>,
+    /// The withdrawal requests that the transaction services.
+    pub withdrawals: Vec<WithdrawalRequestId>,
+    /// The fee rate, in sats per vbyte, used to construct the transaction.
+    pub fee_rate: f64,
+    /// The total fee amount and the fee rate for the last transaction that
+    /// spent the signers' UTXO, if it has not been confirmed.
+    pub last_fees: Option<Fees>,
+}
+
+/// The identifier of a withdrawal request, with hex encoded IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalRequestId {
+    /// The ID that was generated in the clarity contract call for the
+    /// withdrawal request.
+    pub request_id: u64,
+    /// The txid that generated the request.
+    pub txid: String,
+    /// The Stacks block ID that includes the transaction that generated
+    /// the request.
+    pub block_hash: String,
+}
+
+impl TryFrom<&WithdrawalRequestId> for QualifiedRequestId {
+    type Error = hex::FromHexError;
+
+    fn try_from(id: &WithdrawalRequestId) -> Result<Self, Self::Error> {
+        Ok(QualifiedRequestId {
+            request_id: id.request_id,
+            txid: <[u8; 32]>::from_hex(&id.txid)?.into(),
+            block_hash: <[u8; 32]>::from_hex(&id.block_hash)?.into(),
+        })
+    }
+}
+
+/// The response for the `POST /validate-sweep` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidateSweepResponse {
+    /// The ID of the transaction in the request.
+    pub txid: bitcoin::Txid,
+    /// Whether the transaction in the request is the one that this signer
+    /// constructs from the given requests.
+    pub tx_matches: bool,
+    /// Whether this signer would sign the transaction in the request.
+    pub will_sign: bool,
+    /// The fee, in sats, of the transaction that this signer constructs.
+    pub tx_fee: u64,
+    /// The summary of the validation of the transaction.
+    pub report: BitcoinValidationReport,
+    /// The verdict for each of the deposit inputs.
+    pub deposits: Vec<DepositVerdict>,
+    /// The verdict for each of the withdrawal outputs.
+    pub withdrawals: Vec<WithdrawalVerdict>,
+}
+
+/// The result of validating a deposit input of a sweep transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepositVerdict {
+    /// The outpoint of the deposit request.
+    pub outpoint: OutPoint,
+    /// The result of validating the deposit.
+    pub result: InputValidationResult,
+    /// The fee, in sats, assessed to the deposit.
+    pub assessed_fee: Option<u64>,
+}
+
+/// The result of validating a withdrawal output of a sweep transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithdrawalVerdict {
+    /// The ID that was generated in the clarity contract call for the
+    /// withdrawal request.
+    pub request_id: u64,
+    /// The index of the output in the transaction.
+    pub output_index: usize,
+    /// The result of validating the withdrawal.
+    pub result: WithdrawalValidationResult,
+    /// The fee, in sats, assessed to the withdrawal.
+    pub assessed_fee: Option<u64>,
+}
+
+/// The response when the request could not be validated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidateSweepError {
+    /// A description of what went wrong.
+    pub error: String,
+}
+
+impl IntoResponse for ValidateSweepResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+/// Handler for the `POST /validate-sweep` endpoint.
+///
+/// This responds with `404 Not Found` if the endpoint is disabled, with
+/// `400 Bad Request` if the body could not be parsed, and with
+/// `422 Unprocessable Entity` if the transaction could not be validated,
+/// say because this signer does not know about one of the requests.
+/// Otherwise, it responds with the full validation report, whether or
+/// not this signer would sign the transaction.
+pub async fn validate_sweep_handler<C: Context>(
+    state: State<ApiState<C>>,
+    body: String,
+) -> Response {
+    if !state
+        .ctx
+        .config()
+        .signer
+        .event_observer
+        .enable_validate_sweep
+    {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let request: ValidateSweepRequest = match serde_json::from_str(&body) {
+        Ok(request) => request,
+        Err(error) => return error_response(StatusCode::BAD_REQUEST, error),
+    };
+
+    match validate_sweep(&state.ctx, &request).await {
+        Ok(response) => response.into_response(),
+        Err(error) => {
+            tracing::debug!(%error, "could not validate the proposed sweep transaction");
+            error_response(StatusCode::UNPROCESSABLE_ENTITY, error)
+        }
+    }
+}
+
+fn error_response(status: StatusCode, error: impl std::fmt::Display) -> Response {
+    let body = ValidateSweepError { error: error.to_string() };
+    (status, Json(body)).into_response()
+}
+
+/// Validate the proposed sweep transaction against the current state of
+/// the database, the same way that we would for a pre-sign request,
+/// without writing anything.
+async fn validate_sweep<C: Context>(
+    ctx: &C,
+    request: &ValidateSweepRequest,
+) -> Result<ValidateSweepResponse, Error> {
+    let db = ctx.get_storage();
+    let chain_tip = db
+        .get_bitcoin_canonical_chain_tip_ref()
+        .await?
+        .ok_or(Error::NoChainTip)?;
+
+    let aggregate_key = ctx
+        .state()
+        .current_aggregate_key()
+        .ok_or(Error::NoDkgShares)?;
+    let dkg_shares = db.get_encrypted_dkg_shares(aggregate_key).await?;
+    let aggregate_key = match dkg_shares.map(|shares| shares.dkg_shares_status) {
+        Some(DkgSharesStatus::Verified) => aggregate_key,
+        None | Some(DkgSharesStatus::Unverified) | Some(DkgSharesStatus::Failed) => {
+            db.get_latest_verified_dkg_shares()
+                .await?
+                .ok_or(Error::NoVerifiedDkgShares)?
+                .aggregate_key
+        }
+    };
+
+    let btc_ctx = BitcoinTxContext {
+        chain_tip: chain_tip.block_hash,
+        chain_tip_height: chain_tip.block_height,
+        signer_public_key: ctx.config().signer.public_key(),
+        aggregate_key,
+    };
+
+    let withdrawals = request
+        .withdrawals
+        .iter()
+        .map(QualifiedRequestId::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::DecodeHexBytes)?;
+
+    let pre_sign_request = BitcoinPreSignRequest {
+        request_package: vec![TxRequestIds {
+            deposits: request.deposits.clone(),
+            withdrawals,
+        }],
+        fee_rate: request.fee_rate,
+        last_fees: request.last_fees,
+    };
+
+    let mut packages = pre_sign_request
+        .construct_package_sighashes(ctx, &btc_ctx)
+        .await?;
+    // We always construct exactly one transaction for each element of
+    // the request package.
+    let data = packages.pop().ok_or(Error::PreSignContainsNoRequests)?;
+
+    let txid = request.tx.compute_txid();
+    let tx_matches = data.tx.compute_txid() == txid;
+    let report = data.validation_report();
+
+    let deposits = data
+        .reports
+        .deposits
+        .iter()
+        .map(|(_, report)| DepositVerdict {
+            outpoint: report.outpoint,
+            result: report.validate(
+                data.chain_tip_height,
+                &data.tx,
+                data.tx_fee,
+                &data.sbtc_limits,
+                data.min_deposit_amount,
+            ),
+            assessed_fee: data
+                .tx
+                .assess_input_fee(&report.outpoint, data.tx_fee)
+                .map(|fee| fee.to_sat()),
+        })
+        .collect();
+
+    let withdrawals = data
+        .reports
+        .withdrawals
+        .iter()
+        .enumerate()
+        .map(|(index, (_, report))| {
+            let output_index = index + 2;
+            WithdrawalVerdict {
+                request_id: report.id.request_id,
+                output_index,
+                result: report.validate(
+                    data.chain_tip_height,
+                    output_index,
+                    &data.tx,
+                    data.tx_fee,
+                    &data.sbtc_limits,
+                ),
+                assessed_fee: data
+                    .tx
+                    .assess_output_fee(output_index, data.tx_fee)
+                    .map(|fee| fee.to_sat()),
+            }
+        })
+        .collect();
+
+    Ok(ValidateSweepResponse {
+        txid,
+        tx_matches,
+        will_sign: tx_matches && report.is_valid_tx,
+        tx_fee: data.tx_fee.to_sat(),
+        report,
+        deposits,
+        withdrawals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::http::Request;
+    use bitcoin::hashes::Hash as _;
+    use clarity::vm::types::PrincipalData;
+    use fake::Fake as _;
+    use sbtc::deposits::DepositScriptInputs;
+    use sbtc::deposits::ReclaimScriptInputs;
+    use stacks_common::types::chainstate::StacksAddress;
+    use tower::ServiceExt as _;
+
+    use crate::api::get_router;
+    use crate::context::SbtcLimits;
+    use crate::keys::PrivateKey;
+    use crate::keys::PublicKey;
+    use crate::keys::SignerScriptPubKey as _;
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::storage::model::BitcoinBlockRef;
+    use crate::testing::context::*;
+    use crate::testing::get_rng;
+    use crate::testing::storage::model::Params;
+    use crate::testing::storage::model::TestData;
+
+    use super::*;
+
+    const DEPOSIT_AMOUNT: u64 = 1_000_000;
+    const FEE_RATE: f64 = 10.0;
+
+    async fn test_context(enabled: bool) -> impl Context + 'static {
+        let mut ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.event_observer.enable_validate_sweep = enabled;
+            })
+            .build();
+
+        ctx.with_bitcoin_client(|client| {
+            client
+                .expect_estimate_fee_rate()
+                .returning(|| Box::pin(async { Ok(FEE_RATE) }));
+        })
+        .await;
+
+        ctx
+    }
+
+    /// Write a bitcoin block to the database, along with a donation to
+    /// the signers and a deposit request that this signer has voted on,
+    /// and return the outpoint of the deposit request.
+    async fn seed_deposit<C: Context>(ctx: &C, can_accept: bool) -> OutPoint {
+        let mut rng = get_rng();
+        let db = ctx.get_storage_mut();
+        let signer_public_key = ctx.config().signer.public_key();
+
+        let aggregate_key = PublicKey::from_private_key(&PrivateKey::new(&mut rng));
+        let mut shares: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+        shares.aggregate_key = aggregate_key;
+        shares.script_pubkey = aggregate_key.signers_script_pubkey().into();
+        shares.signer_set_public_keys = vec![signer_public_key];
+        shares.dkg_shares_status = DkgSharesStatus::Verified;
+        db.write_encrypted_dkg_shares(&shares).await.unwrap();
+        ctx.state().set_current_aggregate_key(aggregate_key);
+        ctx.state()
+            .update_current_signer_set(shares.signer_set_public_keys.iter().copied().collect());
+        ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+        let params = Params {
+            num_bitcoin_blocks: 1,
+            num_stacks_blocks_per_bitcoin_block: 1,
+            num_deposit_requests_per_block: 0,
+            num_withdraw_requests_per_block: 0,
+            num_signers_per_request: 0,
+            consecutive_blocks: true,
+            deposit_scripts: None,
+        };
+        let mut test_data = TestData::generate(&mut rng, &[signer_public_key], &params);
+        let block = BitcoinBlockRef::from(&test_data.bitcoin_blocks[0]);
+
+        let deposit_inputs = DepositScriptInputs {
+            signers_public_key: aggregate_key.into(),
+            recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+            max_fee: 100_000,
+        };
+        let reclaim_inputs = ReclaimScriptInputs::try_new(1000, bitcoin::ScriptBuf::new()).unwrap();
+        let deposit_script = deposit_inputs.deposit_script();
+        let reclaim_script = reclaim_inputs.reclaim_script();
+
+        let donation_tx = bitcoin::Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: Vec::new(),
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(10_000_000),
+                script_pubkey: aggregate_key.signers_script_pubkey(),
+            }],
+        };
+        let deposit_tx = bitcoin::Transaction {
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(DEPOSIT_AMOUNT),
+                script_pubkey: sbtc::deposits::to_script_pubkey(
+                    deposit_script.clone(),
+                    reclaim_script.clone(),
+                ),
+            }],
+            ..donation_tx.clone()
+        };
+        test_data.push_bitcoin_txs(
+            &block,
+            vec![
+                (model::TransactionType::Donation, donation_tx),
+                (model::TransactionType::DepositRequest, deposit_tx.clone()),
+            ],
+        );
+        test_data.write_to(&db).await;
+
+        let outpoint = OutPoint::new(deposit_tx.compute_txid(), 0);
+        let mut deposit_request: model::DepositRequest = fake::Faker.fake_with_rng(&mut rng);
+        deposit_request.txid = outpoint.txid.into();
+        deposit_request.output_index = outpoint.vout;
+        deposit_request.spend_script = deposit_script.to_bytes();
+        deposit_request.reclaim_script = reclaim_script.to_bytes();
+        deposit_request.amount = DEPOSIT_AMOUNT;
+        deposit_request.max_fee = deposit_inputs.max_fee;
+        deposit_request.lock_time = reclaim_inputs.lock_time();
+        deposit_request.signers_public_key = aggregate_key.into();
+        db.write_deposit_request(&deposit_request).await.unwrap();
+
+        let decision = model::DepositSigner {
+            txid: deposit_request.txid,
+            output_index: deposit_request.output_index,
+            signer_pub_key: signer_public_key,
+            can_accept,
+            can_sign: true,
+        };
+        db.write_deposit_signer_decision(&decision).await.unwrap();
+
+        outpoint
+    }
+
+    /// Construct the transaction that this signer would sign for the
+    /// given deposit.
+    async fn expected_sweep<C: Context>(ctx: &C, outpoint: OutPoint) -> bitcoin::Transaction {
+        let db = ctx.get_storage();
+        let chain_tip = db
+            .get_bitcoin_canonical_chain_tip_ref()
+            .await
+            .unwrap()
+            .unwrap();
+        let btc_ctx = BitcoinTxContext {
+            chain_tip: chain_tip.block_hash,
+            chain_tip_height: chain_tip.block_height,
+            signer_public_key: ctx.config().signer.public_key(),
+            aggregate_key: ctx.state().current_aggregate_key().unwrap(),
+        };
+        let request = BitcoinPreSignRequest {
+            request_package: vec![TxRequestIds {
+                deposits: vec![outpoint],
+                withdrawals: Vec::new(),
+            }],
+            fee_rate: FEE_RATE,
+            last_fees: None,
+        };
+        let mut packages = request
+            .construct_package_sighashes(ctx, &btc_ctx)
+            .await
+            .unwrap();
+        packages.pop().unwrap().tx
+    }
+
+    async fn post_validate_sweep<C>(ctx: &C, body: String) -> (StatusCode, Vec<u8>)
+    where
+        C: Context + 'static,
+    {
+        let state = ApiState { ctx: ctx.clone() };
+        let app: Router = get_router().with_state(state);
+
+        let request = Request::builder()
+            .uri("/validate-sweep")
+            .method(Method::POST)
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    fn request_body(tx: bitcoin::Transaction, outpoint: OutPoint) -> String {
+        let request = ValidateSweepRequest {
+            tx,
+            deposits: vec![outpoint],
+            withdrawals: Vec::new(),
+            fee_rate: FEE_RATE,
+            last_fees: None,
+        };
+        serde_json::to_string(&request).unwrap()
+    }
+
+    #[tokio::test]
+    async fn accepts_the_transaction_that_the_signer_would_sign() {
+        let ctx = test_context(true).await;
+        let outpoint = seed_deposit(&ctx, true).await;
+        let tx = expected_sweep(&ctx, outpoint).await;
+        let txid = tx.compute_txid();
+
+        let (status, body) = post_validate_sweep(&ctx, request_body(tx, outpoint)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let response: ValidateSweepResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.txid, txid);
+        assert!(response.tx_matches);
+        assert!(response.will_sign);
+        assert!(response.report.is_valid_tx);
+        assert!(response.report.failures.is_empty());
+        assert!(response.withdrawals.is_empty());
+
+        let [deposit] = response.deposits.as_slice() else {
+            panic!("expected exactly one deposit verdict");
+        };
+        assert_eq!(deposit.outpoint, outpoint);
+        assert_eq!(deposit.result, InputValidationResult::Ok);
+        assert_eq!(deposit.assessed_fee, Some(response.tx_fee));
+    }
+
+    #[tokio::test]
+    async fn rejects_deposits_that_the_signer_rejected() {
+        let ctx = test_context(true).await;
+        let outpoint = seed_deposit(&ctx, false).await;
+        let tx = expected_sweep(&ctx, outpoint).await;
+
+        let (status, body) = post_validate_sweep(&ctx, request_body(tx, outpoint)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let response: ValidateSweepResponse = serde_json::from_slice(&body).unwrap();
+        assert!(response.tx_matches);
+        assert!(!response.will_sign);
+        assert!(!response.report.is_valid_tx);
+        assert_eq!(response.deposits.len(), 1);
+        assert_eq!(
+            response.deposits[0].result,
+            InputValidationResult::RejectedRequest
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_transactions_that_the_signer_would_not_construct() {
+        let ctx = test_context(true).await;
+        let outpoint = seed_deposit(&ctx, true).await;
+        let mut tx = expected_sweep(&ctx, outpoint).await;
+        // Pay a bit more to the signers, and so less in fees.
+        tx.output[0].value += bitcoin::Amount::from_sat(1);
+
+        let (status, body) = post_validate_sweep(&ctx, request_body(tx, outpoint)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let response: ValidateSweepResponse = serde_json::from_slice(&body).unwrap();
+        assert!(!response.tx_matches);
+        assert!(!response.will_sign);
+        assert!(response.report.is_valid_tx);
+    }
+
+    #[tokio::test]
+    async fn unknown_deposits_are_unprocessable() {
+        let ctx = test_context(true).await;
+        let outpoint = seed_deposit(&ctx, true).await;
+        let tx = expected_sweep(&ctx, outpoint).await;
+        let unknown = OutPoint::new(bitcoin::Txid::from_byte_array([1; 32]), 0);
+
+        let (status, body) = post_validate_sweep(&ctx, request_body(tx, unknown)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let error: ValidateSweepError = serde_json::from_slice(&body).unwrap();
+        assert!(!error.error.is_empty());
+    }
+
+    #[tokio::test]
+    async fn malformed_requests_are_bad_requests() {
+        let ctx = test_context(true).await;
+
+        let (status, _) = post_validate_sweep(&ctx, "{\"tx\": \"zz\"}".to_string()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn endpoint_is_disabled_by_default() {
+        let ctx = TestContext::default_mocked();
+        let outpoint = seed_deposit(&ctx, true).await;
+        let tx = expected_sweep(&ctx, outpoint).await;
+
+        let (status, _) = post_validate_sweep(&ctx, request_body(tx, outpoint)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -209,8 +209,7 @@ impl AsContractCall for InitiateWithdrawalRequest {
 
 impl Context {
     fn new(args: &CliArgs) -> Result<Context, Error> {
-        let settings =
-            Settings::new(Some("signer/src/config/default.toml")).map_err(Error::ConfigError)?;
+        let settings = Settings::new(None::<&str>).map_err(Error::ConfigError)?;
 
         let emily_config = get_emily_config(&settings);
         let bitcoin_client = get_bitcoin_client(&settings);
@@ -17,8 +17,10 @@
 use bitcoin::BlockHash;
 use bitcoin::Txid;
 use bitcoincore_rpc_json::GetTxOutResult;
-use url::Url;
 
+use crate::config::BitcoinConfig;
+use crate::error::ErrorContext;
+use crate::error::ResultExt as _;
 use crate::{error::Error, util::ApiFallbackClient};
 
 use super::BitcoinInteract;
@@ -28,14 +30,15 @@ use super::rpc::BitcoinCoreClient;
 use super::rpc::BitcoinTxInfo;
 use super::rpc::GetTxResponse;
 
-/// Implement the [`TryFrom`] trait for a slice of [`Url`]s to allow for a
-/// [`ApiFallbackClient`] to be implicitly created from a list of URLs.
-impl TryFrom<&[Url]> for ApiFallbackClient<BitcoinCoreClient> {
+/// Implement the [`TryFrom`] trait for the [`BitcoinConfig`] to allow for
+/// a [`ApiFallbackClient`] to be implicitly created from its RPC endpoints.
+impl TryFrom<&BitcoinConfig> for ApiFallbackClient<BitcoinCoreClient> {
     type Error = Error;
-    fn try_from(urls: &[Url]) -> Result<Self, Self::Error> {
-        let clients = urls
+    fn try_from(config: &BitcoinConfig) -> Result<Self, Self::Error> {
+        let clients = config
+            .rpc_endpoints
             .iter()
-            .map(BitcoinCoreClient::try_from)
+            .map(|endpoint| BitcoinCoreClient::new_from_config(config, endpoint))
             .collect::<Result<Vec<_>, _>>()?;
 
         Self::new(clients).map_err(Into::into)
@@ -71,17 +74,22 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
     ) -> Result<Option<BitcoinTxInfo>, Error> {
         self.exec(|client, _| BitcoinInteract::get_tx_info(client, txid, block_hash))
             .await
+            .with_context(|| ErrorContext::BitcoinTransaction("get_tx_info", *txid))
     }
 
     async fn estimate_fee_rate(&self) -> Result<f64, Error> {
         // TODO(542)
         self.exec(|client, _| BitcoinInteract::estimate_fee_rate(client))
             .await
+            .with_context(|| ErrorContext::Operation("estimate_fee_rate"))
     }
 
     async fn broadcast_transaction(&self, tx: &bitcoin::Transaction) -> Result<(), Error> {
         self.exec(|client, _| client.broadcast_transaction(tx))
             .await
+            .with_context(|| {
+                ErrorContext::BitcoinTransaction("broadcast_transaction", tx.compute_txid())
+            })
     }
 
     async fn find_mempool_transactions_spending_output(
@@ -113,6 +121,7 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
     ) -> Result<super::GetTransactionFeeResult, Error> {
         self.exec(|client, _| client.get_transaction_fee(txid, lookup_hint))
             .await
+            .with_context(|| ErrorContext::BitcoinTransaction("get_transaction_fee", *txid))
     }
 
     async fn get_mempool_entry(
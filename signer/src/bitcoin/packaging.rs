@@ -42,24 +42,29 @@ const PACKAGE_MAX_VSIZE: u64 =
 /// 3. Withdrawal IDs must fit within the OP_RETURN size limit (~77 bytes)
 /// 4. The total virtual size across all bags must not exceed
 ///    [`PACKAGE_MAX_VSIZE`]
+/// 5. The number of items cannot exceed `max_items`, if it is set.
 ///
 /// ## Parameters
 /// - `items`: Collection of items to be packaged
 /// - `max_votes_against`: Maximum allowed votes against for any bag
 /// - `max_needs_signature`: Maximum number of items requiring signatures in a
 ///   bag
+/// - `max_items`: Optional maximum number of items in a bag
 ///
 /// ## Notes
 /// - Items that exceed constraints individually are silently ignored
+/// - Items with the same weight are placed in the order that they are
+///   given, so earlier items are less likely to be left out
 ///
 /// ## Returns
-/// An iterator over vectors, where each inner vector represents a bag of
-/// compatible items.
+/// An iterator over [`Package`]s, where each package represents a bag of
+/// compatible items along with metadata describing the bag.
 pub fn compute_optimal_packages<I, T>(
     items: I,
     max_votes_against: u32,
     max_needs_signature: u16,
-) -> impl Iterator<Item = Vec<T>>
+    max_items: Option<usize>,
+) -> impl Iterator<Item = Package<T>>
 where
     I: IntoIterator<Item = T>,
     T: Weighted,
@@ -77,7 +82,8 @@ where
     // Now we just add each item into a bag, and return the
     // collection of bags afterward.
     // Create config and packager
-    let config = PackagerConfig::new(max_votes_against, max_needs_signature);
+    let config =
+        PackagerConfig::new(max_votes_against, max_needs_signature).with_max_items(max_items);
     let mut packager = BestFitPackager::new(config);
 
     for (_, item) in item_vec {
@@ -87,6 +93,32 @@ where
     packager.finalize()
 }
 
+/// A group of compatible items, as returned by [`compute_optimal_packages`].
+///
+/// Along with the items themselves, this includes metadata about the
+/// package that explains how the items were grouped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package<T> {
+    /// The items in this package.
+    pub items: Vec<T>,
+    /// The combined votes against all items in this package (using bitwise
+    /// OR). A bit that is set to 1 indicates that the signer voted against
+    /// at least one of the items in the package.
+    pub votes_against: u128,
+    /// The total virtual size of all items in this package.
+    pub vsize: u64,
+    /// The number of items in this package that require a signature.
+    pub items_needing_signatures: u16,
+}
+
+impl<T> Package<T> {
+    /// The number of distinct signers that voted against at least one of
+    /// the items in this package.
+    pub fn num_votes_against(&self) -> u32 {
+        self.votes_against.count_ones()
+    }
+}
+
 /// A trait for items that can be packaged together according to specific
 /// constraints. Used by [`compute_optimal_packages`].
 ///
@@ -174,6 +206,8 @@ struct PackagerConfig {
     /// Enforcement of this limit prevents transaction rejection due to
     /// oversized OP_RETURN outputs.
     max_op_return_size: usize,
+    /// Maximum number of items in a bag, if any.
+    max_items: Option<usize>,
 }
 
 impl PackagerConfig {
@@ -192,8 +226,15 @@ impl PackagerConfig {
             max_signatures,
             max_total_vsize: PACKAGE_MAX_VSIZE,
             max_op_return_size: OP_RETURN_AVAILABLE_SIZE,
+            max_items: None,
         }
     }
+
+    /// Set the maximum number of items allowed in a bag.
+    fn with_max_items(mut self, max_items: Option<usize>) -> Self {
+        self.max_items = max_items;
+        self
+    }
 }
 
 /// A container for compatible items that can be packaged together in a Bitcoin
@@ -285,6 +326,7 @@ where
     /// 1. Combined votes against ≤ max_votes_against
     /// 2. Combined signature requirements ≤ max_signatures
     /// 3. Withdrawal ID (if any) fits within remaining OP_RETURN space
+    /// 4. The bag has room for another item, if max_items is set
     ///
     /// ## Parameters
     /// - `item`: Item to check for compatibility
@@ -295,6 +337,17 @@ where
         self.votes_compatible(item)
             && self.signatures_compatible(item)
             && self.withdrawal_id_compatible(item)
+            && self.has_room_for_item()
+    }
+
+    /// Check if the bag can hold another item.
+    ///
+    /// ## Returns
+    /// `true` if adding an item wouldn't exceed the item limit.
+    fn has_room_for_item(&self) -> bool {
+        self.config
+            .max_items
+            .is_none_or(|max_items| self.items.len() < max_items)
     }
 
     /// Check if an item's votes are compatible with this bag.
@@ -499,10 +552,16 @@ impl<T: Weighted> BestFitPackager<T> {
     /// groups.
     ///
     /// ## Returns
-    /// An iterator that yields each bag's contents as a `Vec<T>`, preserving
-    /// the original compatibility constraints established during insertion.
-    fn finalize(self) -> impl Iterator<Item = Vec<T>> {
-        self.bags.into_iter().map(|bag| bag.items)
+    /// An iterator that yields each bag's contents as a [`Package`],
+    /// preserving the original compatibility constraints established
+    /// during insertion.
+    fn finalize(self) -> impl Iterator<Item = Package<T>> {
+        self.bags.into_iter().map(|bag| Package {
+            items: bag.items,
+            votes_against: bag.votes_bitmap,
+            vsize: bag.vsize,
+            items_needing_signatures: bag.items_needing_signatures,
+        })
     }
 }
 
@@ -511,6 +570,7 @@ mod tests {
     use super::*;
     use bitvec::array::BitArray;
     use bitvec::field::BitField;
+    use proptest::prelude::*;
     use test_case::test_case;
 
     impl<T> BestFitPackager<T>
@@ -724,8 +784,12 @@ mod tests {
         expected_bag_vsizes: [0, 0],
     } ; "votes-against-placement")]
     fn returns_optimal_placements<const N: usize>(case: VotesTestCase<N>) {
-        let ans =
-            compute_optimal_packages(case.items, case.max_votes_against, case.max_needs_signature);
+        let ans = compute_optimal_packages(
+            case.items,
+            case.max_votes_against,
+            case.max_needs_signature,
+            None,
+        );
         let collection = ans.collect::<Vec<_>>();
         let iter = collection
             .iter()
@@ -733,16 +797,39 @@ mod tests {
             .zip(case.expected_bag_vsizes);
 
         assert_eq!(collection.len(), N);
-        for ((bag, expected_size), expected_vsize) in iter {
-            assert_eq!(bag.len(), expected_size);
-            let package_vsize = bag.iter().map(|item| item.vsize()).sum::<u64>();
+        for ((package, expected_size), expected_vsize) in iter {
+            assert_eq!(package.items.len(), expected_size);
+            let package_vsize = package.items.iter().map(|item| item.vsize()).sum::<u64>();
             assert_eq!(package_vsize, expected_vsize);
+            assert_eq!(package.vsize, expected_vsize);
 
             // Now for the bitcoin requirement
             more_asserts::assert_le!(package_vsize, PACKAGE_MAX_VSIZE);
         }
     }
 
+    /// Tests that packages never hold more than the configured maximum
+    /// number of items, and that the package metadata matches the items.
+    #[test]
+    fn respects_max_items_and_reports_metadata() {
+        let items = vec![RequestItem::with_vote(1).sig_required().vsize(10); 7];
+        let packages = compute_optimal_packages(items, 1, 100, Some(3)).collect::<Vec<_>>();
+
+        let package_sizes = packages
+            .iter()
+            .map(|package| package.items.len())
+            .collect::<Vec<_>>();
+        assert_eq!(package_sizes, [3, 3, 1]);
+
+        for package in packages {
+            let num_items = package.items.len();
+            assert_eq!(package.votes_against, RequestItem::with_vote(1).votes());
+            assert_eq!(package.num_votes_against(), 1);
+            assert_eq!(package.vsize, 10 * num_items as u64);
+            assert_eq!(package.items_needing_signatures as usize, num_items);
+        }
+    }
+
     /// Tests that the OP_RETURN size estimation correctly identifies both small sets that fit
     /// and large sets that exceed the size limit.
     #[test]
@@ -1109,7 +1196,9 @@ mod tests {
         items.push(RequestItem::with_vote(1).wid(3000)); // Different vote pattern
         items.push(RequestItem::no_votes().wid(10000)); // Large ID
 
-        let bags = compute_optimal_packages(items, 1, 5).collect::<Vec<_>>();
+        let bags = compute_optimal_packages(items, 1, 5, None)
+            .map(|package| package.items)
+            .collect::<Vec<_>>();
 
         // Verify multiple bags were created due to both vote and withdrawal ID constraints
         assert!(bags.len() > 1);
@@ -1144,4 +1233,85 @@ mod tests {
             }
         }
     }
+
+    /// Generate request items with random votes against. Each item gets a
+    /// unique withdrawal ID so that it can be identified after packaging.
+    fn arb_request_items() -> impl Strategy<Value = Vec<RequestItem>> {
+        prop::collection::vec((prop::array::uniform5(any::<bool>()), any::<bool>()), 0..60)
+            .prop_map(|items| {
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (votes, needs_signature))| RequestItem {
+                        votes,
+                        needs_signature,
+                        vsize: 0,
+                        withdrawal_id: Some(index as u64),
+                    })
+                    .collect()
+            })
+    }
+
+    proptest! {
+        /// No package may have more combined votes against than the reject
+        /// capacity, and the package metadata must match its items.
+        #[test]
+        fn packages_never_exceed_reject_capacity(
+            items in arb_request_items(),
+            max_votes_against in 0..=5_u32,
+            max_needs_signature in 1..=10_u16,
+            max_items in prop::option::of(1..=10_usize),
+        ) {
+            let packages =
+                compute_optimal_packages(items, max_votes_against, max_needs_signature, max_items);
+
+            for package in packages {
+                let combined_votes = package
+                    .items
+                    .iter()
+                    .fold(0u128, |acc, item| acc | item.votes());
+                let needs_signature = package
+                    .items
+                    .iter()
+                    .filter(|item| item.needs_signature())
+                    .count();
+
+                prop_assert!(!package.items.is_empty());
+                prop_assert_eq!(package.votes_against, combined_votes);
+                prop_assert!(package.num_votes_against() <= max_votes_against);
+                prop_assert_eq!(package.items_needing_signatures as usize, needs_signature);
+                prop_assert!(package.items_needing_signatures <= max_needs_signature);
+                if let Some(max_items) = max_items {
+                    prop_assert!(package.items.len() <= max_items);
+                }
+            }
+        }
+
+        /// Every item that does not individually exceed the reject
+        /// capacity appears in exactly one package, and no other items
+        /// appear in any package.
+        #[test]
+        fn every_eligible_item_appears_in_exactly_one_package(
+            items in arb_request_items(),
+            max_votes_against in 0..=5_u32,
+            max_needs_signature in 1..=10_u16,
+            max_items in prop::option::of(1..=10_usize),
+        ) {
+            let mut expected_ids = items
+                .iter()
+                .filter(|item| item.votes().count_ones() <= max_votes_against)
+                .filter_map(|item| item.withdrawal_id)
+                .collect::<Vec<_>>();
+            expected_ids.sort_unstable();
+
+            let mut packaged_ids =
+                compute_optimal_packages(items, max_votes_against, max_needs_signature, max_items)
+                    .flat_map(|package| package.items)
+                    .filter_map(|item| item.withdrawal_id)
+                    .collect::<Vec<_>>();
+            packaged_ids.sort_unstable();
+
+            prop_assert_eq!(packaged_ids, expected_ids);
+        }
+    }
 }
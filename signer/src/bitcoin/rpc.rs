@@ -27,6 +27,7 @@ use serde::Deserialize;
 use url::Url;
 
 use crate::bitcoin::BitcoinInteract;
+use crate::config::BitcoinConfig;
 use crate::error::Error;
 use crate::storage::model::BitcoinBlockHeight;
 
@@ -276,17 +277,23 @@ impl TryFrom<&Url> for BitcoinCoreClient {
     fn try_from(url: &Url) -> Result<Self, Self::Error> {
         let username = url.username().to_string();
         let password = url.password().unwrap_or_default().to_string();
-        let host = url
-            .host_str()
-            .ok_or(Error::InvalidUrl(url::ParseError::EmptyHost))?;
-        let port = url.port().ok_or(Error::PortRequired)?;
-
-        let endpoint = format!("{}://{host}:{port}", url.scheme());
+        let endpoint = rpc_endpoint_without_credentials(url)?;
 
         Self::new(&endpoint, username, password)
     }
 }
 
+/// Return the scheme, host and port of the given RPC endpoint, leaving out
+/// any credentials.
+fn rpc_endpoint_without_credentials(url: &Url) -> Result<String, Error> {
+    let host = url
+        .host_str()
+        .ok_or(Error::InvalidUrl(url::ParseError::EmptyHost))?;
+    let port = url.port().ok_or(Error::PortRequired)?;
+
+    Ok(format!("{}://{host}:{port}", url.scheme()))
+}
+
 impl BitcoinCoreClient {
     /// Return a bitcoin-core RPC client. Will error if the URL is an invalid URL.
     ///
@@ -294,7 +301,25 @@ impl BitcoinCoreClient {
     ///
     /// This function does not attempt to establish a connection to bitcoin-core.
     pub fn new(url: &str, username: String, password: String) -> Result<Self, Error> {
-        let auth = Auth::UserPass(username, password);
+        Self::new_with_auth(url, Auth::UserPass(username, password))
+    }
+
+    /// Return a bitcoin-core RPC client for the given RPC endpoint of the
+    /// config, authenticating with the credentials that the config holds
+    /// for that endpoint.
+    ///
+    /// # Notes
+    ///
+    /// This function does not attempt to establish a connection to bitcoin-core.
+    pub fn new_from_config(config: &BitcoinConfig, endpoint: &Url) -> Result<Self, Error> {
+        let url = rpc_endpoint_without_credentials(endpoint)?;
+        Self::new_with_auth(&url, config.rpc_auth(endpoint))
+    }
+
+    /// Return a bitcoin-core RPC client that uses the given authentication.
+    /// Will error if the URL is an invalid URL, or if the authentication
+    /// uses a cookie file that cannot be read.
+    fn new_with_auth(url: &str, auth: Auth) -> Result<Self, Error> {
         let client = bitcoincore_rpc::Client::new(url, auth)
             .map(Arc::new)
             .map_err(|err| Error::BitcoinCoreRpcClient(err, url.to_string()))?;
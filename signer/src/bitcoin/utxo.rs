@@ -1,5 +1,6 @@
 //! Utxo management and transaction construction
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::LazyLock;
 
@@ -47,6 +48,7 @@ use crate::bitcoin::packaging::compute_optimal_packages;
 use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::context::SbtcLimits;
 use crate::error::Error;
+use crate::keys::PublicKey;
 use crate::keys::SignerScriptPubKey as _;
 use crate::storage::model;
 use crate::storage::model::BitcoinTxId;
@@ -55,6 +57,7 @@ use crate::storage::model::ScriptPubKey;
 use crate::storage::model::SignerVotes;
 use crate::storage::model::StacksBlockHash;
 use crate::storage::model::StacksTxId;
+use crate::storage::model::SweepRequestId;
 use crate::storage::model::TxOutput;
 use crate::storage::model::TxOutputType;
 use crate::storage::model::TxPrevout;
@@ -121,6 +124,25 @@ pub struct Fees {
 impl Fees {
     /// A zero-fee [`Fees`] instance.
     pub const ZERO: Self = Self { total: 0, rate: 0.0 };
+
+    /// The lowest fee rate, in sats per virtual byte, that a transaction
+    /// replacing the one that paid these fees may have.
+    pub fn min_replacement_fee_rate(&self) -> f64 {
+        self.rate + self.rate * SATS_PER_VBYTE_INCREMENT
+    }
+}
+
+/// The lowest fee rate, in sats per virtual byte, that a sweep
+/// transaction constructed with the given fee rate pays.
+///
+/// This is the given fee rate unless the transaction replaces one that
+/// paid the `last_fees`, in which case it must pay a higher fee rate than
+/// that transaction.
+pub fn min_sweep_fee_rate(fee_rate: f64, last_fees: Option<Fees>) -> f64 {
+    match last_fees {
+        Some(fees) => fee_rate.max(fees.min_replacement_fee_rate()),
+        None => fee_rate,
+    }
 }
 
 /// A trait for getting the fees for a given instance.
@@ -304,6 +326,10 @@ pub struct SbtcRequests {
     /// that there is enough time for the signers to sign all the inputs
     /// during the tenure of a single bitcoin block.
     pub max_deposits_per_bitcoin_tx: u16,
+    /// The maximum number of requests, deposits and withdrawals combined,
+    /// that can be included in a single bitcoin transaction. If this is
+    /// not set then only the other packaging constraints apply.
+    pub max_requests_per_bitcoin_tx: Option<u16>,
 }
 
 impl SbtcRequests {
@@ -331,9 +357,17 @@ impl SbtcRequests {
 
         let max_votes_against = self.reject_capacity();
         let max_needs_signature = self.max_deposits_per_bitcoin_tx;
-        compute_optimal_packages(items, max_votes_against, max_needs_signature)
-            .scan(self.signer_state, |state, request_refs| {
-                let requests = Requests::new(request_refs);
+        let max_items = self.max_requests_per_bitcoin_tx.map(usize::from);
+        compute_optimal_packages(items, max_votes_against, max_needs_signature, max_items)
+            .scan(self.signer_state, |state, package| {
+                tracing::debug!(
+                    num_requests = package.items.len(),
+                    num_votes_against = package.num_votes_against(),
+                    items_needing_signatures = package.items_needing_signatures,
+                    vsize = package.vsize,
+                    "packaged requests into a transaction"
+                );
+                let requests = Requests::new(package.items);
                 let tx = UnsignedTransaction::new(requests, state);
                 if let Ok(tx_ref) = tx.as_ref() {
                     state.utxo = tx_ref.new_signer_utxo();
@@ -385,12 +419,12 @@ impl SbtcRequests {
 /// BIP-125: https://github.com/bitcoin/bips/blob/master/bip-0125.mediawiki#implementation-details
 fn compute_transaction_fee(tx_vsize: f64, fee_rate: f64, last_fees: Option<Fees>) -> u64 {
     match last_fees {
-        Some(Fees { total, rate }) => {
+        Some(fees) => {
             // The requirement for an RBF transaction is that the new fee
             // amount be greater than the old fee amount.
-            let minimum_fee_rate = fee_rate.max(rate + rate * SATS_PER_VBYTE_INCREMENT);
+            let minimum_fee_rate = min_sweep_fee_rate(fee_rate, last_fees);
             let fee_increment = tx_vsize * DEFAULT_INCREMENTAL_RELAY_FEE_RATE;
-            (total as f64 + fee_increment)
+            (fees.total as f64 + fee_increment)
                 .max(tx_vsize * minimum_fee_rate)
                 .ceil() as u64
         }
@@ -510,16 +544,23 @@ impl DepositRequest {
     }
 
     /// Try convert from a model::DepositRequest with some additional info.
-    pub fn from_model(request: model::DepositRequest, votes: SignerVotes) -> Self {
-        Self {
+    ///
+    /// The votes must be keyed to a signer set with `num_signers` signers,
+    /// otherwise an error is returned.
+    pub fn from_model(
+        request: model::DepositRequest,
+        votes: SignerVotes,
+        num_signers: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
             outpoint: request.outpoint(),
             max_fee: request.max_fee,
-            signer_bitmap: votes.into(),
+            signer_bitmap: votes.to_signer_bitmap(num_signers)?,
             amount: request.amount,
             deposit_script: ScriptBuf::from_bytes(request.spend_script),
             reclaim_script: ScriptBuf::from_bytes(request.reclaim_script),
             signers_public_key: request.signers_public_key.into(),
-        }
+        })
     }
 }
 
@@ -571,16 +612,23 @@ impl WithdrawalRequest {
     }
 
     /// Try convert from a model::DepositRequest with some additional info.
-    pub fn from_model(request: model::WithdrawalRequest, votes: SignerVotes) -> Self {
-        Self {
+    ///
+    /// The votes must be keyed to a signer set with `num_signers` signers,
+    /// otherwise an error is returned.
+    pub fn from_model(
+        request: model::WithdrawalRequest,
+        votes: SignerVotes,
+        num_signers: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
             amount: request.amount,
             max_fee: request.max_fee,
             script_pubkey: request.recipient,
-            signer_bitmap: votes.into(),
+            signer_bitmap: votes.to_signer_bitmap(num_signers)?,
             request_id: request.request_id,
             txid: request.txid,
             block_hash: request.block_hash,
-        }
+        })
     }
 
     /// Return the identifier for the withdrawal request.
@@ -634,6 +682,14 @@ impl<'a> RequestRef<'a> {
         }
     }
 
+    /// Return the identifier of the underlying request.
+    pub fn sweep_request_id(&self) -> SweepRequestId {
+        match self {
+            RequestRef::Deposit(req) => SweepRequestId::from(&req.outpoint),
+            RequestRef::Withdrawal(req) => SweepRequestId::from(&req.qualified_id()),
+        }
+    }
+
     /// Extract the signer bitmap for the underlying request.
     pub fn signer_bitmap(&self) -> BitArray<[u8; 16]> {
         match self {
@@ -834,22 +890,50 @@ pub struct SignatureHash {
     /// The aggregate key that is locking the output associated with this
     /// signature hash.
     pub aggregate_key: XOnlyPublicKey,
+    /// The index of the input in the transaction that this signature hash
+    /// is for.
+    pub input_index: u32,
+}
+
+impl SignatureHash {
+    /// Create the record of this signature hash being signed at the given
+    /// chain tip, at the request of the signer with the given public key.
+    pub fn to_signed_sighash(
+        &self,
+        chain_tip: model::BitcoinBlockHash,
+        origin_public_key: PublicKey,
+    ) -> model::SignedSigHash {
+        model::SignedSigHash {
+            sighash: self.sighash.into(),
+            txid: self.txid.into(),
+            input_index: self.input_index,
+            chain_tip,
+            aggregate_key: self.aggregate_key.into(),
+            origin_public_key,
+        }
+    }
 }
 
 impl SignatureHashes<'_> {
     /// Get deposit sighashes
-    pub fn deposit_sighashes(mut self) -> Vec<SignatureHash> {
-        self.deposits.sort_by_key(|(x, _)| x.outpoint);
-        self.deposits
+    pub fn deposit_sighashes(self) -> Vec<SignatureHash> {
+        // The deposits are in the order of their inputs in the
+        // transaction, which come right after the signers' input.
+        let mut sighashes: Vec<SignatureHash> = self
+            .deposits
             .into_iter()
-            .map(|(deposit, sighash)| SignatureHash {
+            .zip(1..)
+            .map(|((deposit, sighash), input_index)| SignatureHash {
                 txid: self.txid,
                 outpoint: deposit.outpoint,
                 sighash,
                 prevout_type: TxPrevoutType::Deposit,
                 aggregate_key: deposit.signers_public_key,
+                input_index,
             })
-            .collect()
+            .collect();
+        sighashes.sort_by_key(|sighash| sighash.outpoint);
+        sighashes
     }
 
     /// Get the signers' sighash
@@ -860,6 +944,7 @@ impl SignatureHashes<'_> {
             sighash: self.signers,
             prevout_type: TxPrevoutType::SignersInput,
             aggregate_key: self.signers_aggregate_key,
+            input_index: 0,
         }
     }
 }
@@ -1380,6 +1465,126 @@ impl BitcoinTxInfo {
     }
 }
 
+/// Apportion the fee of a confirmed sweep transaction to the given
+/// deposit requests, returning the fee, in sats, assessed to each request
+/// swept in by the transaction.
+///
+/// # Notes
+///
+/// The fees are assessed using the fee and the weight of the transaction
+/// as it was confirmed. The witness data of the deposit inputs, and so
+/// their weight, can differ from the dummy witness data that was used to
+/// estimate the fee when the transaction was constructed, so the realized
+/// fees can differ from the estimated ones. Requests that were not swept
+/// in by the transaction are skipped.
+pub fn assess_confirmed_fees(
+    tx_info: &BitcoinTxInfo,
+    requests: &[model::DepositRequest],
+) -> Vec<(OutPoint, u64)> {
+    requests
+        .iter()
+        .filter_map(|request| {
+            let outpoint = request.outpoint();
+            let assessed_fee = tx_info.assess_input_fee(&outpoint)?;
+            Some((outpoint, assessed_fee.to_sat()))
+        })
+        .collect()
+}
+
+/// Return the amount of sBTC to mint for the deposit with the given
+/// `outpoint` and `deposit_amount`, swept in by the given confirmed sweep
+/// transaction.
+///
+/// The fee assessed to each deposit is taken from `recorded_fees`, the
+/// fees recorded when the sweep was confirmed, falling back to assessing
+/// it from the transaction. Before returning, this function checks that
+/// * the deposit amount matches the amount of the swept UTXO,
+/// * the amount to mint, which is the deposit amount less the assessed
+///   fee, is positive, and
+/// * the sBTC minted for all deposits swept in by the transaction does not
+///   exceed the bitcoin that the signers kept from them. That is the
+///   increase in the signers' UTXO plus what was paid out, including
+///   fees, for the withdrawals serviced by the transaction.
+///
+/// Minting more sBTC than the bitcoin received would leave sBTC
+/// unbacked, so an error here means that signing must not proceed.
+pub fn deposit_mint_amount(
+    tx_info: &BitcoinTxInfo,
+    recorded_fees: &HashMap<OutPoint, u64>,
+    outpoint: &OutPoint,
+    deposit_amount: u64,
+) -> Result<u64, Error> {
+    let assessed_fee = |outpoint: &OutPoint| {
+        recorded_fees
+            .get(outpoint)
+            .copied()
+            .or_else(|| Some(tx_info.assess_input_fee(outpoint)?.to_sat()))
+            .ok_or(Error::OutPointMissing(*outpoint))
+    };
+
+    // The first input is always the signers' UTXO, everything after it
+    // is a deposit.
+    let mut swept_amount = None;
+    let mut minted = 0u64;
+    for index in 1..tx_info.inputs().len() {
+        let prevout = tx_info
+            .prevout(index)
+            .ok_or(Error::OutPointMissing(*outpoint))?;
+        let prevout_outpoint = OutPoint::new(*prevout.txid, prevout.output_index);
+        if &prevout_outpoint == outpoint {
+            swept_amount = Some(prevout.amount.to_sat());
+        }
+        let fee = assessed_fee(&prevout_outpoint)?;
+        minted = minted.saturating_add(prevout.amount.to_sat().saturating_sub(fee));
+    }
+
+    let swept_amount = swept_amount.ok_or(Error::OutPointMissing(*outpoint))?;
+    if swept_amount != deposit_amount {
+        return Err(Error::DepositMintAmountMismatch {
+            outpoint: *outpoint,
+            deposit_amount,
+            swept_amount,
+        });
+    }
+
+    let fee = assessed_fee(outpoint)?;
+    let amount = deposit_amount
+        .checked_sub(fee)
+        .filter(|amount| *amount > 0)
+        .ok_or(Error::DepositMintNotPositive {
+            outpoint: *outpoint,
+            amount: deposit_amount,
+            assessed_fee: fee,
+        })?;
+
+    let signers_input = tx_info
+        .prevout(0)
+        .ok_or(Error::OutPointMissing(*outpoint))?
+        .amount
+        .to_sat();
+    let signers_output = tx_info
+        .outputs()
+        .first()
+        .map_or(0, |out| out.value.to_sat());
+    let withdrawn: u64 = (2..tx_info.outputs().len())
+        .map(|vout| {
+            let fee = tx_info.assess_output_fee(vout).unwrap_or_default();
+            tx_info.outputs()[vout].value.to_sat() + fee.to_sat()
+        })
+        .sum();
+    let received = (signers_output + withdrawn).saturating_sub(signers_input);
+
+    if minted > received {
+        return Err(Error::DepositMintExceedsSweep {
+            sweep_txid: tx_info.txid,
+            minted,
+            received,
+        });
+    }
+
+    Ok(amount)
+}
+
 /// An output used as an input into a transaction, a previous output.
 #[derive(Copy, Clone, Debug)]
 pub struct PrevoutRef<'a> {
@@ -1639,6 +1844,7 @@ mod tests {
     use bitcoin::key::TapTweak;
     use bitcoin::opcodes::all::OP_RETURN;
     use bitcoin::script::Instruction;
+    use bitcoincore_rpc_json::GetRawTransactionResultVin;
     use clarity::vm::types::PrincipalData;
     use fake::Fake as _;
     use model::SignerVote;
@@ -1654,6 +1860,9 @@ mod tests {
 
     use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
     use crate::MAX_MEMPOOL_PACKAGE_TX_COUNT;
+    use crate::bitcoin::rpc::BitcoinTxVin;
+    use crate::bitcoin::rpc::BitcoinTxVinPrevout;
+    use crate::bitcoin::rpc::PrevoutScriptPubKey;
     use crate::context::RollingWithdrawalLimits;
     use crate::testing;
     use crate::testing::btc::base_signer_transaction;
@@ -1889,6 +2098,7 @@ mod tests {
             accept_threshold: 2,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
         let keypair = Keypair::new_global(&mut OsRng);
 
@@ -2013,6 +2223,7 @@ mod tests {
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2109,6 +2320,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // Generate transactions
@@ -2223,6 +2435,7 @@ mod tests {
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2268,6 +2481,7 @@ mod tests {
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2319,6 +2533,7 @@ mod tests {
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2367,6 +2582,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -2427,6 +2643,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -2527,6 +2744,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2562,6 +2780,18 @@ mod tests {
         });
     }
 
+    #[test_case(5.0, None, 5.0; "no last fees")]
+    #[test_case(5.0, Some(Fees { total: 1000, rate: 2.0 }), 5.0; "fee rate above last rate")]
+    #[test_case(1.0, Some(Fees { total: 1000, rate: 2.0 }), 2.002; "fee rate below last rate")]
+    #[test_case(2.0, Some(Fees { total: 1000, rate: 2.0 }), 2.002; "fee rate equal to last rate")]
+    fn min_sweep_fee_rate_exceeds_last_fee_rate(
+        fee_rate: f64,
+        last_fees: Option<Fees>,
+        expected: f64,
+    ) {
+        assert_eq!(min_sweep_fee_rate(fee_rate, last_fees), expected);
+    }
+
     #[test]
     fn rbf_txs_have_greater_total_fee() {
         // Each deposit and withdrawal has a max fee greater than the current market fee rate
@@ -2593,6 +2823,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // In the below code, we need to make sure that we take the _first_
@@ -2674,6 +2905,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
         // If multiple_txs is specified, we add a withdrawal that will
         // cause the transaction to be split into two.
@@ -2689,7 +2921,15 @@ mod tests {
         let unsigned = transactions.first().unwrap();
         let sighashes = unsigned.construct_digests().unwrap();
 
-        assert_eq!(sighashes.deposits.len(), num_deposits)
+        assert_eq!(sighashes.deposits.len(), num_deposits);
+
+        // Each signature hash knows the transaction input that it is for.
+        let signer_sighash = sighashes.signer_sighash();
+        let input_sighashes = std::iter::once(signer_sighash).chain(sighashes.deposit_sighashes());
+        for sighash in input_sighashes {
+            let tx_in = &unsigned.tx.input[sighash.input_index as usize];
+            assert_eq!(tx_in.previous_output, sighash.outpoint);
+        }
     }
 
     /// If the signer's UTXO does not have enough to cover the requests
@@ -2719,6 +2959,7 @@ mod tests {
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let transactions = requests.construct_transactions();
@@ -2777,6 +3018,7 @@ mod tests {
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2832,7 +3074,7 @@ mod tests {
         ];
         let votes = SignerVotes::from(signer_votes.to_vec());
         let request: model::DepositRequest = fake::Faker.fake_with_rng(&mut OsRng);
-        let deposit_request = DepositRequest::from_model(request, votes.clone());
+        let deposit_request = DepositRequest::from_model(request, votes.clone(), 5).unwrap();
 
         // One explicit vote against and one implicit vote against.
         assert_eq!(deposit_request.votes().count_ones(), 2);
@@ -2843,6 +3085,44 @@ mod tests {
         })
     }
 
+    /// Requests whose votes were recorded before a rotate-keys event that
+    /// grew the signer set from five to seven signers cannot be weighed
+    /// against the new signer set, while requests that were voted on by
+    /// the new signer set are weighed using all seven votes.
+    #[test]
+    fn creating_requests_from_model_across_rotate_keys_boundary() {
+        let signer_votes = |num_votes: usize| {
+            let votes: Vec<SignerVote> = (0..num_votes)
+                .map(|index| SignerVote {
+                    signer_public_key: fake::Faker.fake_with_rng(&mut OsRng),
+                    is_accepted: Some(index % 3 != 0),
+                })
+                .collect();
+            SignerVotes::from(votes)
+        };
+        let num_signers = 7;
+        let old_votes = signer_votes(5);
+        let new_votes = signer_votes(7);
+
+        let request: model::DepositRequest = fake::Faker.fake_with_rng(&mut OsRng);
+        let result = DepositRequest::from_model(request.clone(), old_votes.clone(), num_signers);
+        assert!(matches!(
+            result,
+            Err(Error::SignerVotesLengthMismatch { num_votes: 5, num_signers: 7 })
+        ));
+        let deposit = DepositRequest::from_model(request, new_votes.clone(), num_signers).unwrap();
+        assert_eq!(deposit.votes().count_ones(), 3);
+
+        let request: model::WithdrawalRequest = fake::Faker.fake_with_rng(&mut OsRng);
+        let result = WithdrawalRequest::from_model(request.clone(), old_votes, num_signers);
+        assert!(matches!(
+            result,
+            Err(Error::SignerVotesLengthMismatch { num_votes: 5, num_signers: 7 })
+        ));
+        let withdrawal = WithdrawalRequest::from_model(request, new_votes, num_signers).unwrap();
+        assert_eq!(withdrawal.votes().count_ones(), 3);
+    }
+
     /// Check that the signer bitmap is recoded correctly when going from
     /// the model type to the required type here.
     #[test]
@@ -2875,7 +3155,7 @@ mod tests {
         ];
         let votes = SignerVotes::from(signer_votes.to_vec());
         let request: model::WithdrawalRequest = fake::Faker.fake_with_rng(&mut OsRng);
-        let withdrawal_request = WithdrawalRequest::from_model(request, votes.clone());
+        let withdrawal_request = WithdrawalRequest::from_model(request, votes.clone(), 6).unwrap();
 
         // One explicit vote against and one implicit vote against.
         assert_eq!(withdrawal_request.votes().count_ones(), 3);
@@ -2905,6 +3185,197 @@ mod tests {
         assert_eq!(assessed_fee, fee);
     }
 
+    #[test]
+    fn confirmed_fees_follow_the_realized_witness_sizes() {
+        let requests: Vec<model::DepositRequest> = (0..3)
+            .map(|_| fake::Faker.fake_with_rng(&mut OsRng))
+            .collect();
+        let unswept_request = requests[2].clone();
+
+        // The sweep transaction spends the first two deposits. When it was
+        // constructed, both deposit inputs had the same dummy witness.
+        let mut tx = base_signer_transaction();
+        for request in &requests[..2] {
+            tx.input.push(bitcoin::TxIn {
+                previous_output: request.outpoint(),
+                script_sig: ScriptBuf::new(),
+                sequence: bitcoin::Sequence::ZERO,
+                witness: Witness::from_slice(&[vec![0; 64], vec![0; 100]]),
+            });
+        }
+        let estimated_fee = Amount::from_sat(10_000);
+        let estimated_tx = BitcoinTxInfo::from_tx(tx.clone(), estimated_fee);
+        let estimated_fees = assess_confirmed_fees(&estimated_tx, &requests);
+        assert_eq!(estimated_fees[0].1, estimated_fees[1].1);
+
+        // The confirmed transaction has a much larger witness for the
+        // second deposit, say because its reclaim script is large, and
+        // so it paid a larger fee overall.
+        tx.input[2].witness = Witness::from_slice(&[vec![0; 64], vec![0; 500]]);
+        let realized_fee = Amount::from_sat(12_500);
+        let confirmed_tx = BitcoinTxInfo::from_tx(tx, realized_fee);
+        let realized_fees = assess_confirmed_fees(&confirmed_tx, &requests);
+
+        // Only the swept requests get assessed a fee.
+        assert_eq!(realized_fees.len(), 2);
+        assert_eq!(realized_fees[0].0, requests[0].outpoint());
+        assert_eq!(realized_fees[1].0, requests[1].outpoint());
+        assert!(
+            realized_fees
+                .iter()
+                .all(|(outpoint, _)| *outpoint != unswept_request.outpoint())
+        );
+
+        // The realized fees differ from the estimates, with the larger
+        // input paying more, and they add up to the realized fee.
+        assert_ne!(realized_fees, estimated_fees);
+        assert!(realized_fees[1].1 > realized_fees[0].1);
+        let total: u64 = realized_fees.iter().map(|(_, fee)| fee).sum();
+        assert!(total >= realized_fee.to_sat());
+        assert!(total <= realized_fee.to_sat() + 2);
+    }
+
+    /// Create a confirmed sweep transaction that spends the signers' UTXO
+    /// of one bitcoin and deposits with the given amounts, paying the
+    /// given fee.
+    fn confirmed_sweep(deposit_amounts: &[u64], fee: u64) -> (BitcoinTxInfo, Vec<OutPoint>) {
+        let mut tx = base_signer_transaction();
+        let outpoints: Vec<OutPoint> = deposit_amounts
+            .iter()
+            .map(|_| {
+                OutPoint::new(
+                    Txid::from_byte_array(fake::Faker.fake_with_rng(&mut OsRng)),
+                    0,
+                )
+            })
+            .collect();
+        for outpoint in &outpoints {
+            tx.input.push(bitcoin::TxIn {
+                previous_output: *outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: bitcoin::Sequence::ZERO,
+                witness: Witness::from_slice(&[vec![0; 64], vec![0; 100]]),
+            });
+        }
+        let total_deposits: u64 = deposit_amounts.iter().sum();
+        tx.output[0].value = Amount::ONE_BTC + Amount::from_sat(total_deposits - fee);
+
+        let mut tx_info = BitcoinTxInfo::from_tx(tx.clone(), Amount::from_sat(fee));
+        let prevout_values =
+            std::iter::once(Amount::ONE_BTC.to_sat()).chain(deposit_amounts.iter().copied());
+        tx_info.vin = tx
+            .input
+            .iter()
+            .zip(prevout_values)
+            .map(|(tx_in, value)| BitcoinTxVin {
+                details: GetRawTransactionResultVin {
+                    sequence: 0,
+                    coinbase: None,
+                    txid: Some(tx_in.previous_output.txid),
+                    vout: Some(tx_in.previous_output.vout),
+                    script_sig: None,
+                    txinwitness: None,
+                },
+                prevout: BitcoinTxVinPrevout {
+                    generated: false,
+                    height: 1u64.into(),
+                    value: Amount::from_sat(value),
+                    script_pub_key: PrevoutScriptPubKey { script: ScriptBuf::new() },
+                },
+            })
+            .collect();
+
+        (tx_info, outpoints)
+    }
+
+    #[test]
+    fn deposit_mint_amount_uses_the_recorded_fees() {
+        let (tx_info, outpoints) = confirmed_sweep(&[100_000, 200_000], 10_000);
+        let recorded_fees: HashMap<OutPoint, u64> = outpoints
+            .iter()
+            .map(|outpoint| {
+                (
+                    *outpoint,
+                    tx_info.assess_input_fee(outpoint).unwrap().to_sat(),
+                )
+            })
+            .collect();
+
+        let amount = deposit_mint_amount(&tx_info, &recorded_fees, &outpoints[0], 100_000).unwrap();
+        assert_eq!(amount, 100_000 - recorded_fees[&outpoints[0]]);
+
+        // Without any recorded fees, we assess them from the transaction,
+        // which gives the same answer here.
+        let amount =
+            deposit_mint_amount(&tx_info, &HashMap::new(), &outpoints[1], 200_000).unwrap();
+        assert_eq!(amount, 200_000 - recorded_fees[&outpoints[1]]);
+    }
+
+    #[test]
+    fn deposit_mint_amount_rejects_fee_records_that_over_mint() {
+        let (tx_info, outpoints) = confirmed_sweep(&[100_000, 200_000], 10_000);
+        // The fee record for the first deposit has been corrupted, so the
+        // signers would mint more sBTC than the bitcoin that they kept.
+        let recorded_fees = HashMap::from([
+            (outpoints[0], 0),
+            (
+                outpoints[1],
+                tx_info.assess_input_fee(&outpoints[1]).unwrap().to_sat(),
+            ),
+        ]);
+
+        // Both deposits were swept in by the bad sweep, so we refuse to
+        // mint for either one of them.
+        for (outpoint, amount) in outpoints.iter().zip([100_000, 200_000]) {
+            let result = deposit_mint_amount(&tx_info, &recorded_fees, outpoint, amount);
+            match result.unwrap_err() {
+                Error::DepositMintExceedsSweep { sweep_txid, minted, received } => {
+                    assert_eq!(sweep_txid, tx_info.txid);
+                    assert_eq!(received, 290_000);
+                    assert!(minted > received);
+                }
+                err => panic!("unexpected error: {err}"),
+            }
+        }
+    }
+
+    #[test]
+    fn deposit_mint_amount_rejects_non_positive_mints() {
+        let (tx_info, outpoints) = confirmed_sweep(&[100_000, 200_000], 10_000);
+        let recorded_fees = HashMap::from([(outpoints[0], 100_000)]);
+
+        let result = deposit_mint_amount(&tx_info, &recorded_fees, &outpoints[0], 100_000);
+        assert!(matches!(
+            result,
+            Err(Error::DepositMintNotPositive {
+                amount: 100_000,
+                assessed_fee: 100_000,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn deposit_mint_amount_rejects_mismatched_deposit_amounts() {
+        let (tx_info, outpoints) = confirmed_sweep(&[100_000, 200_000], 10_000);
+
+        let result = deposit_mint_amount(&tx_info, &HashMap::new(), &outpoints[0], 150_000);
+        assert!(matches!(
+            result,
+            Err(Error::DepositMintAmountMismatch {
+                deposit_amount: 150_000,
+                swept_amount: 100_000,
+                ..
+            })
+        ));
+
+        // A deposit that was not swept in by the transaction has nothing
+        // to mint.
+        let outpoint = OutPoint::new(Txid::from_byte_array([1; 32]), 0);
+        let result = deposit_mint_amount(&tx_info, &HashMap::new(), &outpoint, 100_000);
+        assert!(matches!(result, Err(Error::OutPointMissing(_))));
+    }
+
     #[test]
     fn sole_withdrawal_gets_entire_fee() {
         let mut tx = base_signer_transaction();
@@ -3080,6 +3551,7 @@ mod tests {
             accept_threshold: 6,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // Let's construct the unsigned transaction and check to see if we
@@ -3127,6 +3599,7 @@ mod tests {
             num_signers: 128,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -3135,6 +3608,52 @@ mod tests {
         more_asserts::assert_le!(total_size, MEMPOOL_MAX_PACKAGE_SIZE);
     }
 
+    #[test]
+    fn construct_transactions_respects_max_requests_per_bitcoin_tx() {
+        // None of these requests have votes against them, so without a
+        // cap on the number of requests they would all fit into a single
+        // transaction.
+        let deposits: Vec<DepositRequest> =
+            (0..5).map(|_| create_deposit(10_000, 10_000, 0)).collect();
+        let withdrawals: Vec<WithdrawalRequest> = (0..5)
+            .map(|_| create_withdrawal(10_000, 10_000, 0))
+            .collect();
+
+        let mut requests = SbtcRequests {
+            deposits,
+            withdrawals,
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: OutPoint::null(),
+                    amount: 1000000,
+                    public_key: generate_x_only_public_key(),
+                },
+                fee_rate: 1.0,
+                public_key: generate_x_only_public_key(),
+                last_fees: None,
+                magic_bytes: [0; 2],
+            },
+            accept_threshold: 127,
+            num_signers: 128,
+            sbtc_limits: SbtcLimits::unlimited(),
+            max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].requests.len(), 10);
+
+        requests.max_requests_per_bitcoin_tx = Some(3);
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 4);
+        for tx in transactions.iter() {
+            more_asserts::assert_le!(tx.requests.len(), 3);
+        }
+        let num_requests: usize = transactions.iter().map(|tx| tx.requests.len()).sum();
+        assert_eq!(num_requests, 10);
+    }
+
     #[test]
     fn construct_transactions_limits_package_vsize() {
         const NUM_DEPOSITS: usize =
@@ -3183,6 +3702,7 @@ mod tests {
             num_signers: 14,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -3642,4 +4162,73 @@ mod tests {
         ];
         assert_eq!(withdrawal_outs, expected);
     }
+
+    proptest::proptest! {
+        /// The transactions constructed for any set of requests must be
+        /// valid sweep transactions that pay at least the requested fee
+        /// rate, and that assess all of their fees to the requests that
+        /// they service.
+        #[test]
+        fn constructed_transactions_uphold_fee_invariants(
+            requests in testing::sweep::arb_sbtc_requests(),
+        ) {
+            use proptest::prelude::*;
+
+            let transactions = requests.construct_transactions().unwrap();
+            let fee_rate = requests.signer_state.fee_rate;
+
+            let mut signer_utxo = requests.signer_state.utxo;
+            let mut swept_deposits = HashSet::new();
+            let mut swept_withdrawals = HashSet::new();
+
+            for unsigned in transactions.iter() {
+                // Each transaction spends the signers' UTXO created by the
+                // transaction before it, and the first one spends the
+                // signers' current UTXO.
+                prop_assert_eq!(unsigned.signer_utxo.utxo, signer_utxo);
+                prop_assert_eq!(unsigned.tx.input[0].previous_output, signer_utxo.outpoint);
+                signer_utxo = unsigned.new_signer_utxo();
+
+                let input_amounts = unsigned.input_amounts();
+                let output_amounts = unsigned.output_amounts();
+                prop_assert!(output_amounts <= input_amounts);
+
+                // The signers' UTXO covers all fees, so the fee paid is
+                // the one that was computed for the transaction, and it
+                // is at least the requested fee rate.
+                let tx_fee = input_amounts - output_amounts;
+                prop_assert_eq!(tx_fee, unsigned.tx_fee);
+                prop_assert!(tx_fee as f64 >= unsigned.tx_vsize as f64 * fee_rate);
+
+                // Requests that would have created a dust output are
+                // filtered out before packaging.
+                for tx_out in unsigned.tx.output.iter().skip(2) {
+                    prop_assert!(tx_out.value >= tx_out.script_pubkey.minimal_non_dust());
+                }
+                let signers_output = &unsigned.tx.output[0];
+                prop_assert!(signers_output.value >= signers_output.script_pubkey.minimal_non_dust());
+
+                // Each fee is rounded up when it is apportioned, so the
+                // assessed fees add up to the transaction fee plus at most
+                // one sat for each request after the first.
+                let fee = Amount::from_sat(tx_fee);
+                let mut assessed_fees = 0;
+                for deposit in unsigned.requests.iter().filter_map(RequestRef::as_deposit) {
+                    prop_assert!(swept_deposits.insert(deposit.outpoint));
+                    assessed_fees += unsigned.assess_input_fee(&deposit.outpoint, fee).unwrap().to_sat();
+                }
+                let withdrawals = unsigned.requests.iter().filter_map(RequestRef::as_withdrawal);
+                for (vout, withdrawal) in (2..).zip(withdrawals) {
+                    prop_assert!(swept_withdrawals.insert(withdrawal.request_id));
+                    prop_assert_eq!(&unsigned.tx.output[vout].script_pubkey, &*withdrawal.script_pubkey);
+                    assessed_fees += unsigned.assess_output_fee(vout, fee).unwrap().to_sat();
+                }
+
+                let num_requests = unsigned.requests.len() as u64;
+                prop_assert!(num_requests > 0);
+                prop_assert!(assessed_fees >= tx_fee);
+                prop_assert!(assessed_fees < tx_fee + num_requests);
+            }
+        }
+    }
 }
@@ -2,19 +2,27 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::future::Future;
+use std::num::NonZeroUsize;
 
 use bitcoin::Amount;
 use bitcoin::OutPoint;
 use bitcoin::ScriptBuf;
 use bitcoin::XOnlyPublicKey;
 use bitcoin::relative::LockTime;
+use lru::LruCache;
 
 use crate::DEPOSIT_DUST_LIMIT;
 use crate::DEPOSIT_LOCKTIME_BLOCK_BUFFER;
+use crate::MAX_CACHED_REQUEST_REPORTS;
 use crate::WITHDRAWAL_BLOCKS_EXPIRY;
 use crate::WITHDRAWAL_MIN_CONFIRMATIONS;
+use crate::bitcoin::BitcoinInteract as _;
 use crate::bitcoin::utxo::FeeAssessment;
 use crate::bitcoin::utxo::SignerBtcState;
+use crate::bitcoin::utxo::SignerUtxo;
+use crate::bitcoin::utxo::min_sweep_fee_rate;
+use crate::config::ReloadableSettings;
 use crate::context::Context;
 use crate::context::SbtcLimits;
 use crate::error::Error;
@@ -30,6 +38,7 @@ use crate::storage::model::BitcoinWithdrawalOutput;
 use crate::storage::model::DkgSharesStatus;
 use crate::storage::model::QualifiedRequestId;
 use crate::storage::model::SignerVotes;
+use crate::storage::model::StacksBlockHash;
 
 use super::utxo::DepositRequest;
 use super::utxo::RequestRef;
@@ -45,6 +54,103 @@ struct ValidationCache<'a> {
     withdrawal_reports: HashMap<&'a QualifiedRequestId, (WithdrawalRequestReport, SignerVotes)>,
 }
 
+/// Deposit and withdrawal request reports fetched from the database while
+/// validating bitcoin transaction packages.
+///
+/// Coordinators can ask us to validate the same requests several times
+/// for the same chain tip, so the reports are kept around for as long as
+/// the bitcoin and stacks chain tips stay the same, and the cache is
+/// cleared as soon as either of them changes. It holds a bounded number
+/// of reports of each kind, evicting the least recently used ones. Signer
+/// votes are not cached, since they change as the decisions of the other
+/// signers arrive.
+#[derive(Debug)]
+pub struct ReportCache {
+    /// The chain tips and signer that the cached reports were fetched for.
+    key: Option<ReportCacheKey>,
+    deposit_reports: LruCache<OutPoint, DepositRequestReport>,
+    withdrawal_reports: LruCache<QualifiedRequestId, WithdrawalRequestReport>,
+}
+
+/// What the reports in a [`ReportCache`] depend on, other than the
+/// request itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ReportCacheKey {
+    bitcoin_chain_tip: BitcoinBlockHash,
+    stacks_chain_tip: StacksBlockHash,
+    signer_public_key: PublicKey,
+}
+
+impl Default for ReportCache {
+    fn default() -> Self {
+        let capacity =
+            NonZeroUsize::new(MAX_CACHED_REQUEST_REPORTS as usize).unwrap_or(NonZeroUsize::MIN);
+        Self::new(capacity)
+    }
+}
+
+impl ReportCache {
+    /// Create a new cache holding at most `capacity` deposit reports and
+    /// `capacity` withdrawal reports.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            key: None,
+            deposit_reports: LruCache::new(capacity),
+            withdrawal_reports: LruCache::new(capacity),
+        }
+    }
+
+    /// Clear the cache if its reports were fetched for a different chain
+    /// tip or signer than the given one.
+    fn invalidate_if_stale(&mut self, key: ReportCacheKey) {
+        if self.key != Some(key) {
+            self.deposit_reports.clear();
+            self.withdrawal_reports.clear();
+            self.key = Some(key);
+        }
+    }
+
+    /// Return the cached report for the deposit request with the given
+    /// outpoint, running the `fetch` future if it is not in the cache.
+    async fn deposit_report<F>(
+        &mut self,
+        outpoint: &OutPoint,
+        fetch: F,
+    ) -> Result<Option<DepositRequestReport>, Error>
+    where
+        F: Future<Output = Result<Option<DepositRequestReport>, Error>>,
+    {
+        if let Some(report) = self.deposit_reports.get(outpoint) {
+            return Ok(Some(report.clone()));
+        }
+        let report = fetch.await?;
+        if let Some(report) = report.as_ref() {
+            self.deposit_reports.put(*outpoint, report.clone());
+        }
+        Ok(report)
+    }
+
+    /// Return the cached report for the withdrawal request with the given
+    /// ID, running the `fetch` future if it is not in the cache.
+    async fn withdrawal_report<F>(
+        &mut self,
+        id: &QualifiedRequestId,
+        fetch: F,
+    ) -> Result<Option<WithdrawalRequestReport>, Error>
+    where
+        F: Future<Output = Result<Option<WithdrawalRequestReport>, Error>>,
+    {
+        if let Some(report) = self.withdrawal_reports.get(id) {
+            return Ok(Some(report.clone()));
+        }
+        let report = fetch.await?;
+        if let Some(report) = report.as_ref() {
+            self.withdrawal_reports.put(*id, report.clone());
+        }
+        Ok(report)
+    }
+}
+
 /// The necessary information for validating a bitcoin transaction.
 #[derive(Debug, Clone)]
 pub struct BitcoinTxContext {
@@ -105,6 +211,22 @@ pub fn is_unique(package: &[TxRequestIds]) -> bool {
     })
 }
 
+/// The highest fee rate, in sats per virtual byte, that the signers
+/// accept for sweep transactions.
+///
+/// This is the configured maximum fee rate, or the configured multiple of
+/// the market fee rate if that is lower. The market fee rate is `None`
+/// when it could not be estimated, and then only the configured maximum
+/// applies.
+pub fn max_acceptable_fee_rate(settings: &ReloadableSettings, market_fee_rate: Option<f64>) -> f64 {
+    let fee_rate_max = settings.bitcoin_fee_rate_max.get() as f64;
+    let market_multiple = f64::from(settings.bitcoin_fee_rate_market_multiple.get());
+    match market_fee_rate {
+        Some(fee_rate) => fee_rate_max.min(fee_rate * market_multiple),
+        None => fee_rate_max,
+    }
+}
+
 impl BitcoinPreSignRequest {
     /// Check that the request object is valid
     // TODO: Have the type system do these checks. Perhaps TxRequestIds
@@ -132,10 +254,30 @@ impl BitcoinPreSignRequest {
         Ok(())
     }
 
+    /// Check that the fee rate in the request does not exceed the given
+    /// maximum fee rate.
+    ///
+    /// Each request in the transaction is checked against its own
+    /// max-fee, but that alone does not stop a coordinator from burning
+    /// the signers' UTXO on fees, since the signers pay for their own
+    /// input and output too. So we have a global ceiling on the fee rate
+    /// as well. Transactions that replace the one that paid the
+    /// `last_fees` pay a higher fee rate than it, regardless of the fee
+    /// rate in the request, so that is the fee rate that gets checked.
+    fn validate_fee_rate(&self, max_fee_rate: f64) -> Result<(), Error> {
+        let fee_rate = min_sweep_fee_rate(self.fee_rate, self.last_fees);
+        if fee_rate > max_fee_rate {
+            return Err(Error::PreSignFeeRateTooHigh { fee_rate, max_fee_rate });
+        }
+
+        Ok(())
+    }
+
     async fn fetch_all_reports<D>(
         &self,
         db: &D,
         btc_ctx: &BitcoinTxContext,
+        reports: &mut ReportCache,
     ) -> Result<ValidationCache, Error>
     where
         D: DbRead,
@@ -148,6 +290,12 @@ impl BitcoinPreSignRequest {
             return Err(Error::NoStacksChainTip);
         };
 
+        reports.invalidate_if_stale(ReportCacheKey {
+            bitcoin_chain_tip: *bitcoin_chain_tip,
+            stacks_chain_tip,
+            signer_public_key: btc_ctx.signer_public_key,
+        });
+
         for requests in &self.request_package {
             // Fetch all deposit reports and votes
             for outpoint in &requests.deposits {
@@ -160,7 +308,8 @@ impl BitcoinPreSignRequest {
                     output_index,
                     &btc_ctx.signer_public_key,
                 );
-                let Some(report) = report_future.await? else {
+                let report = reports.deposit_report(outpoint, report_future).await?;
+                let Some(report) = report else {
                     return Err(InputValidationResult::Unknown.into_error(btc_ctx));
                 };
 
@@ -173,13 +322,16 @@ impl BitcoinPreSignRequest {
 
             // Fetch all withdrawal reports and votes
             for qualified_id in &requests.withdrawals {
-                let report = db.get_withdrawal_request_report(
+                let report_future = db.get_withdrawal_request_report(
                     bitcoin_chain_tip,
                     &stacks_chain_tip,
                     qualified_id,
                     &btc_ctx.signer_public_key,
                 );
-                let Some(report) = report.await? else {
+                let report = reports
+                    .withdrawal_report(qualified_id, report_future)
+                    .await?;
+                let Some(report) = report else {
                     return Err(WithdrawalValidationResult::Unknown.into_error(btc_ctx));
                 };
 
@@ -251,13 +403,40 @@ impl BitcoinPreSignRequest {
         ctx: &C,
         btc_ctx: &BitcoinTxContext,
     ) -> Result<Vec<BitcoinTxValidationData>, Error>
+    where
+        C: Context + Send + Sync,
+    {
+        let mut reports = ReportCache::default();
+        self.construct_package_sighashes_cached(ctx, btc_ctx, &mut reports)
+            .await
+    }
+
+    /// Construct the reports for each request that this transaction will
+    /// service, reusing the request reports in the given cache that were
+    /// fetched for the same chain tip.
+    pub async fn construct_package_sighashes_cached<C>(
+        &self,
+        ctx: &C,
+        btc_ctx: &BitcoinTxContext,
+        reports: &mut ReportCache,
+    ) -> Result<Vec<BitcoinTxValidationData>, Error>
     where
         C: Context + Send + Sync,
     {
         // Let's do basic validation of the request object itself.
         self.pre_validation()?;
+        let market_fee_rate = match ctx.get_bitcoin_client().estimate_fee_rate().await {
+            Ok(fee_rate) => Some(fee_rate),
+            Err(error) => {
+                tracing::warn!(%error, "could not estimate the market fee rate; only checking against the configured maximum");
+                None
+            }
+        };
+        let max_fee_rate = max_acceptable_fee_rate(&ctx.reloadable_config().get(), market_fee_rate);
+        self.validate_fee_rate(max_fee_rate)?;
+
         let db = ctx.get_storage();
-        let cache = self.fetch_all_reports(&db, btc_ctx).await?;
+        let cache = self.fetch_all_reports(&db, btc_ctx, reports).await?;
 
         // We now check that the withdrawal amounts adhere to the rolling
         // limits. We check the individual withdrawal caps later.
@@ -286,9 +465,55 @@ impl BitcoinPreSignRequest {
             outputs.push(output);
         }
 
+        // Only the first transaction spends the signers' UTXO from our
+        // database, the rest spend the outputs of the transactions before
+        // them. So if the first transaction does not conflict with
+        // anything in-flight then neither do the others.
+        if let Some(first) = outputs.first() {
+            let max_age = ctx.config().signer.in_flight_signer_utxo_timeout;
+            Self::assert_signer_utxo_not_in_flight(&db, btc_ctx, &signer_utxo, first, max_age)
+                .await?;
+        }
+
         Ok(outputs)
     }
 
+    /// Check that we have not agreed to sign a different transaction
+    /// spending the signers' UTXO, within the last `max_age`, while the
+    /// current chain tip was the chain tip.
+    ///
+    /// Coordinators may resend the same request package, which results
+    /// in the same transaction, so that is fine. But signing two distinct
+    /// transactions spending the same UTXO means that at least one of
+    /// them will never confirm, and we may end up having signed for a
+    /// transaction that we did not intend to have confirmed. The
+    /// `max_age` bounds how long a signing round that never completed
+    /// can block other proposals.
+    async fn assert_signer_utxo_not_in_flight<D>(
+        db: &D,
+        btc_ctx: &BitcoinTxContext,
+        signer_utxo: &SignerUtxo,
+        first: &BitcoinTxValidationData,
+        max_age: std::time::Duration,
+    ) -> Result<(), Error>
+    where
+        D: DbRead,
+    {
+        let outpoint = signer_utxo.outpoint;
+        let txid: BitcoinTxId = first.tx.compute_txid().into();
+        let in_flight_txids = db
+            .get_in_flight_signer_prevout_txids(&btc_ctx.chain_tip, &outpoint, max_age)
+            .await?;
+
+        match in_flight_txids
+            .into_iter()
+            .find(|in_flight| in_flight != &txid)
+        {
+            Some(txid) => Err(Error::SignerUtxoAlreadyInFlight { outpoint, txid }),
+            None => Ok(()),
+        }
+    }
+
     /// Construct the validation for each request that this transaction
     /// will service.
     ///
@@ -308,6 +533,9 @@ impl BitcoinPreSignRequest {
     {
         let mut deposits = Vec::with_capacity(requests.deposits.len());
         let mut withdrawals = Vec::with_capacity(requests.withdrawals.len());
+        // The votes are keyed to the current signer set, and we need to
+        // know its size in order to weigh each request correctly.
+        let num_signers = ctx.state().current_signer_public_keys().len();
 
         for outpoint in requests.deposits.iter() {
             let (report, votes) = cache
@@ -315,7 +543,8 @@ impl BitcoinPreSignRequest {
                 .get(outpoint)
                 // This should never happen because we have already validated that we have all the reports.
                 .ok_or_else(|| InputValidationResult::Unknown.into_error(btc_ctx))?;
-            deposits.push((report.to_deposit_request(votes), report.clone()));
+            let request = report.to_deposit_request(votes, num_signers)?;
+            deposits.push((request, report.clone()));
         }
 
         for id in requests.withdrawals.iter() {
@@ -324,7 +553,8 @@ impl BitcoinPreSignRequest {
                 .get(id)
                 // This should never happen because we have already validated that we have all the reports.
                 .ok_or_else(|| WithdrawalValidationResult::Unknown.into_error(btc_ctx))?;
-            withdrawals.push((report.to_withdrawal_request(votes), report.clone()));
+            let request = report.to_withdrawal_request(votes, num_signers)?;
+            withdrawals.push((request, report.clone()));
         }
 
         deposits.sort_by_key(|(request, _)| request.outpoint);
@@ -355,6 +585,7 @@ impl BitcoinPreSignRequest {
             reports,
             chain_tip_height: btc_ctx.chain_tip_height,
             sbtc_limits: ctx.state().get_current_limits(),
+            min_deposit_amount: ctx.reloadable_config().get().min_deposit_amount,
         };
 
         Ok((out, signer_state))
@@ -383,6 +614,8 @@ pub struct BitcoinTxValidationData {
     pub chain_tip_height: BitcoinBlockHeight,
     /// The current sBTC limits.
     pub sbtc_limits: SbtcLimits,
+    /// The minimum deposit amount, in sats, that this signer will accept.
+    pub min_deposit_amount: u64,
 }
 
 impl BitcoinTxValidationData {
@@ -412,6 +645,7 @@ impl BitcoinTxValidationData {
                 &self.tx,
                 self.tx_fee,
                 &self.sbtc_limits,
+                self.min_deposit_amount,
             )
         });
 
@@ -480,6 +714,21 @@ impl BitcoinTxValidationData {
             .collect()
     }
 
+    /// Validate every deposit and withdrawal in the transaction and return
+    /// a report of all of them that failed validation.
+    ///
+    /// This does not stop at the first failure, which makes it useful
+    /// when reporting why a transaction was rejected.
+    pub fn validation_report(&self) -> BitcoinValidationReport {
+        self.reports.validation_report(
+            self.chain_tip_height,
+            &self.tx,
+            self.tx_fee,
+            &self.sbtc_limits,
+            self.min_deposit_amount,
+        )
+    }
+
     /// Check whether the transaction is valid. This determines whether
     /// this signer will sign any of the sighashes for the transaction
     ///
@@ -490,41 +739,7 @@ impl BitcoinTxValidationData {
     /// verification for the signer. In such cases, it will just sign for
     /// the deposits that it can.
     pub fn is_valid_tx(&self) -> bool {
-        // A transaction is invalid if it is not servicing any deposit or
-        // withdrawal requests. Doing so costs fees and the signers do not
-        // gain anything by permitting such a transaction.
-        if self.reports.deposits.is_empty() && self.reports.withdrawals.is_empty() {
-            return false;
-        }
-
-        let chain_tip_height = self.chain_tip_height;
-        let tx = &self.tx;
-        let tx_fee = self.tx_fee;
-        let sbtc_limits = &self.sbtc_limits;
-
-        let deposit_validation_results = self.reports.deposits.iter().all(|(_, report)| {
-            matches!(
-                report.validate(chain_tip_height, tx, tx_fee, sbtc_limits),
-                InputValidationResult::Ok
-                    | InputValidationResult::CannotSignUtxo
-                    | InputValidationResult::DkgSharesUnverified
-                    | InputValidationResult::DkgSharesVerifyFailed
-            )
-        });
-
-        let withdrawal_validation_results =
-            self.reports
-                .withdrawals
-                .iter()
-                .enumerate()
-                .all(|(index, (_, report))| {
-                    let output_index = index + 2;
-                    let result =
-                        report.validate(chain_tip_height, output_index, tx, tx_fee, sbtc_limits);
-                    result == WithdrawalValidationResult::Ok
-                });
-
-        deposit_validation_results && withdrawal_validation_results
+        self.validation_report().is_valid_tx
     }
 }
 
@@ -542,6 +757,74 @@ pub struct SbtcReports {
 }
 
 impl SbtcReports {
+    /// Validate all deposits and withdrawals in the given transaction and
+    /// return a report of every one of them that failed validation.
+    ///
+    /// The deposits are expected to be the inputs after the signers'
+    /// input, and the withdrawals the outputs after the signers' outputs,
+    /// in the order of the reports.
+    pub fn validation_report<F>(
+        &self,
+        chain_tip_height: BitcoinBlockHeight,
+        tx: &F,
+        tx_fee: Amount,
+        sbtc_limits: &SbtcLimits,
+        min_deposit_amount: u64,
+    ) -> BitcoinValidationReport
+    where
+        F: FeeAssessment,
+    {
+        let deposit_failures =
+            self.deposits
+                .iter()
+                .enumerate()
+                .filter_map(|(index, (_, report))| {
+                    let result = report.validate(
+                        chain_tip_height,
+                        tx,
+                        tx_fee,
+                        sbtc_limits,
+                        min_deposit_amount,
+                    );
+                    let failure = BitcoinValidationFailure {
+                        index: index + 1,
+                        error: BitcoinSweepErrorMsg::Deposit(result),
+                    };
+                    (result != InputValidationResult::Ok).then_some(failure)
+                });
+
+        let withdrawal_failures =
+            self.withdrawals
+                .iter()
+                .enumerate()
+                .filter_map(|(index, (_, report))| {
+                    let output_index = index + 2;
+                    let result =
+                        report.validate(chain_tip_height, output_index, tx, tx_fee, sbtc_limits);
+                    let failure = BitcoinValidationFailure {
+                        index: output_index,
+                        error: BitcoinSweepErrorMsg::Withdrawal(result),
+                    };
+                    (result != WithdrawalValidationResult::Ok).then_some(failure)
+                });
+
+        let failures: Vec<_> = deposit_failures.chain(withdrawal_failures).collect();
+        let has_requests = !self.deposits.is_empty() || !self.withdrawals.is_empty();
+        let is_valid_tx = has_requests
+            && failures.iter().all(|failure| {
+                matches!(
+                    failure.error,
+                    BitcoinSweepErrorMsg::Deposit(
+                        InputValidationResult::CannotSignUtxo
+                            | InputValidationResult::DkgSharesUnverified
+                            | InputValidationResult::DkgSharesVerifyFailed
+                    )
+                )
+            });
+
+        BitcoinValidationReport { is_valid_tx, failures }
+    }
+
     /// Create the transaction with witness data using the requests.
     pub fn create_transaction(&self) -> Result<UnsignedTransaction, Error> {
         let deposits = self
@@ -561,14 +844,32 @@ impl SbtcReports {
 }
 
 /// The responses for validation of a sweep transaction on bitcoin.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::IntoStaticStr,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub enum InputValidationResult {
     /// The deposit request passed validation
     Ok,
     /// The deposit request amount is below the allowed per-deposit minimum.
     AmountTooLow,
+    /// The deposit request amount is below the minimum deposit amount
+    /// configured for this signer, so it is not worth sweeping.
+    AmountBelowMinimum,
     /// The deposit request amount, less the fees, would be rejected from
     /// the smart contract during the complete-deposit contract call.
     MintAmountBelowDustLimit,
@@ -625,8 +926,23 @@ impl InputValidationResult {
 
 /// The responses for validation of the outputs of a sweep transaction on
 /// bitcoin.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::IntoStaticStr,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub enum WithdrawalValidationResult {
     /// The withdrawal request passed validation
@@ -694,7 +1010,10 @@ impl WithdrawalValidationResult {
 }
 
 /// The responses for validation of a sweep transaction on bitcoin.
-#[derive(Debug, thiserror::Error, PartialEq, Eq, Copy, Clone)]
+#[derive(
+    Debug, thiserror::Error, PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
 pub enum BitcoinSweepErrorMsg {
     /// The error has something to do with the inputs.
     #[error("deposit error")]
@@ -729,6 +1048,28 @@ impl std::error::Error for BitcoinValidationError {
     }
 }
 
+/// A summary of every problem found while validating the inputs and
+/// outputs of a sweep transaction.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BitcoinValidationReport {
+    /// Whether the transaction is valid. Note that a transaction can be
+    /// valid even if some of the deposit inputs fail validation, for
+    /// example when this signer cannot sign for one of them.
+    pub is_valid_tx: bool,
+    /// Each of the inputs and outputs that failed validation.
+    pub failures: Vec<BitcoinValidationFailure>,
+}
+
+/// An input or output of a sweep transaction that failed validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BitcoinValidationFailure {
+    /// The index of the input, for deposits, or the output, for
+    /// withdrawals, in the transaction.
+    pub index: usize,
+    /// The reason that the input or output failed validation.
+    pub error: BitcoinSweepErrorMsg,
+}
+
 /// An enum for the confirmation status of a deposit request.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DepositConfirmationStatus {
@@ -791,12 +1132,17 @@ pub struct DepositRequestReport {
 
 impl DepositRequestReport {
     /// Validate that the deposit request is okay given the report.
-    fn validate<F>(
+    ///
+    /// The `min_deposit_amount` is the minimum deposit amount, in sats,
+    /// configured for this signer. It is applied in addition to the
+    /// per-deposit minimum in the `sbtc_limits`.
+    pub fn validate<F>(
         &self,
         chain_tip_height: BitcoinBlockHeight,
         tx: &F,
         tx_fee: Amount,
         sbtc_limits: &SbtcLimits,
+        min_deposit_amount: u64,
     ) -> InputValidationResult
     where
         F: FeeAssessment,
@@ -824,6 +1170,10 @@ impl DepositRequestReport {
             return InputValidationResult::AmountTooLow;
         }
 
+        if self.amount < min_deposit_amount {
+            return InputValidationResult::AmountBelowMinimum;
+        }
+
         if self.amount > sbtc_limits.per_deposit_cap().to_sat() {
             return InputValidationResult::AmountTooHigh;
         }
@@ -895,16 +1245,23 @@ impl DepositRequestReport {
     }
 
     /// As deposit request.
-    fn to_deposit_request(&self, votes: &SignerVotes) -> DepositRequest {
-        DepositRequest {
+    ///
+    /// The votes must be keyed to a signer set with `num_signers` signers,
+    /// otherwise an error is returned.
+    fn to_deposit_request(
+        &self,
+        votes: &SignerVotes,
+        num_signers: usize,
+    ) -> Result<DepositRequest, Error> {
+        Ok(DepositRequest {
             outpoint: self.outpoint,
             max_fee: self.max_fee,
             amount: self.amount,
             deposit_script: self.deposit_script.clone(),
             reclaim_script: self.reclaim_script.clone(),
             signers_public_key: self.signers_public_key,
-            signer_bitmap: votes.into(),
-        }
+            signer_bitmap: votes.to_signer_bitmap(num_signers)?,
+        })
     }
 }
 
@@ -1013,22 +1370,34 @@ impl WithdrawalRequestReport {
         WithdrawalValidationResult::Ok
     }
 
-    fn to_withdrawal_request(&self, votes: &SignerVotes) -> WithdrawalRequest {
-        WithdrawalRequest {
+    /// As withdrawal request.
+    ///
+    /// The votes must be keyed to a signer set with `num_signers` signers,
+    /// otherwise an error is returned.
+    fn to_withdrawal_request(
+        &self,
+        votes: &SignerVotes,
+        num_signers: usize,
+    ) -> Result<WithdrawalRequest, Error> {
+        Ok(WithdrawalRequest {
             request_id: self.id.request_id,
             txid: self.id.txid,
             block_hash: self.id.block_hash,
             amount: self.amount,
             max_fee: self.max_fee,
             script_pubkey: self.recipient.clone().into(),
-            signer_bitmap: votes.into(),
-        }
+            signer_bitmap: votes.to_signer_bitmap(num_signers)?,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU16;
+    use std::num::NonZeroU64;
     use std::sync::LazyLock;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
 
     use bitcoin::ScriptBuf;
     use bitcoin::Sequence;
@@ -1037,11 +1406,15 @@ mod tests {
     use bitcoin::Txid;
     use bitcoin::Witness;
     use bitcoin::hashes::Hash as _;
+    use rand::rngs::OsRng;
     use secp256k1::SECP256K1;
     use test_case::test_case;
 
+    use crate::bitcoin::utxo::Fees;
+    use crate::config::BlocklistFailurePolicy;
     use crate::context::RollingWithdrawalLimits;
     use crate::context::SbtcLimits;
+    use crate::keys::PrivateKey;
     use crate::storage::model::BitcoinBlockHeight;
     use crate::storage::model::StacksBlockHash;
     use crate::storage::model::StacksTxId;
@@ -1431,11 +1804,55 @@ mod tests {
         let status =
             mapping
                 .report
-                .validate(mapping.chain_tip_height, &tx, TX_FEE, &mapping.limits);
+                .validate(mapping.chain_tip_height, &tx, TX_FEE, &mapping.limits, 0);
 
         assert_eq!(status, mapping.status);
     }
 
+    #[test_case(99_999, 0, 100_000, InputValidationResult::AmountBelowMinimum ; "below-configured-minimum")]
+    #[test_case(100_000, 0, 100_000, InputValidationResult::Ok ; "at-configured-minimum")]
+    #[test_case(99_999, 100_000, 0, InputValidationResult::AmountTooLow ; "below-limits-minimum")]
+    #[test_case(100_000, 100_000, 0, InputValidationResult::Ok ; "at-limits-minimum")]
+    #[test_case(100_000, 50_000, 100_001, InputValidationResult::AmountBelowMinimum ; "below-configured-minimum-above-limits-minimum")]
+    #[test_case(100_000, 100_001, 50_000, InputValidationResult::AmountTooLow ; "below-limits-minimum-above-configured-minimum")]
+    #[test_case(99_999, 100_000, 100_000, InputValidationResult::AmountTooLow ; "below-both-minimums")]
+    fn deposit_report_validation_min_amount(
+        amount: u64,
+        per_deposit_minimum: u64,
+        min_deposit_amount: u64,
+        status: InputValidationResult,
+    ) {
+        let report = DepositRequestReport {
+            status: DepositConfirmationStatus::Confirmed(
+                0u64.into(),
+                BitcoinBlockHash::from([0; 32]),
+            ),
+            can_sign: Some(true),
+            can_accept: Some(true),
+            amount,
+            max_fee: u64::MAX,
+            lock_time: LockTime::from_height(DEPOSIT_LOCKTIME_BLOCK_BUFFER + 3),
+            outpoint: OutPoint::null(),
+            deposit_script: ScriptBuf::new(),
+            reclaim_script: ScriptBuf::new(),
+            signers_public_key: *sbtc::UNSPENDABLE_TAPROOT_KEY,
+            dkg_shares_status: Some(DkgSharesStatus::Verified),
+        };
+        let mut tx = crate::testing::btc::base_signer_transaction();
+        tx.input.push(TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        });
+
+        let limits = SbtcLimits::new_per_deposit(per_deposit_minimum, u64::MAX);
+        let chain_tip_height = 2u64.into();
+        let result = report.validate(chain_tip_height, &tx, TX_FEE, &limits, min_deposit_amount);
+
+        assert_eq!(result, status);
+    }
+
     /// A helper struct to aid in testing of deposit validation.
     #[derive(Debug)]
     struct WithdrawalReportErrorMapping {
@@ -1532,6 +1949,26 @@ mod tests {
         limits: SbtcLimits::new_per_withdrawal(Amount::ONE_BTC.to_sat()),
         status: WithdrawalValidationResult::Ok,
     } ; "amount-and-fee-divorced")]
+    #[test_case(WithdrawalReportErrorMapping {
+        report: WithdrawalRequestReport {
+            status: WithdrawalRequestStatus::Confirmed,
+            id: QualifiedRequestId {
+                request_id: 0,
+                txid: StacksTxId::from([0; 32]),
+                block_hash: StacksBlockHash::from([0; 32]),
+            },
+            is_accepted: Some(true),
+            amount: Amount::ONE_BTC.to_sat(),
+            // The withdrawal is the only request in the transaction, so
+            // it is assessed the entire transaction fee.
+            max_fee: TX_FEE.to_sat(),
+            recipient: TEST_RECIPIENT.clone(),
+            bitcoin_block_height: 0u64.into(),
+        },
+        chain_tip_height: WITHDRAWAL_MIN_CONFIRMATIONS.into(),
+        limits: SbtcLimits::new_per_withdrawal(Amount::ONE_BTC.to_sat()),
+        status: WithdrawalValidationResult::Ok,
+    } ; "fee-at-the-border")]
     #[test_case(WithdrawalReportErrorMapping {
         report: WithdrawalRequestReport {
             status: WithdrawalRequestStatus::Confirmed,
@@ -1549,7 +1986,25 @@ mod tests {
         chain_tip_height: WITHDRAWAL_MIN_CONFIRMATIONS.into(),
         limits: SbtcLimits::new_per_withdrawal(Amount::ONE_BTC.to_sat()),
         status: WithdrawalValidationResult::FeeTooHigh,
-    } ; "fee-too-high")]
+    } ; "one-sat-too-high-fee")]
+    #[test_case(WithdrawalReportErrorMapping {
+        report: WithdrawalRequestReport {
+            status: WithdrawalRequestStatus::Confirmed,
+            id: QualifiedRequestId {
+                request_id: 0,
+                txid: StacksTxId::from([0; 32]),
+                block_hash: StacksBlockHash::from([0; 32]),
+            },
+            is_accepted: Some(true),
+            amount: Amount::ONE_BTC.to_sat(),
+            max_fee: 0,
+            recipient: TEST_RECIPIENT.clone(),
+            bitcoin_block_height: 0u64.into(),
+        },
+        chain_tip_height: WITHDRAWAL_MIN_CONFIRMATIONS.into(),
+        limits: SbtcLimits::new_per_withdrawal(Amount::ONE_BTC.to_sat()),
+        status: WithdrawalValidationResult::FeeTooHigh,
+    } ; "zero-max-fee")]
     #[test_case(WithdrawalReportErrorMapping {
         report: WithdrawalRequestReport {
             status: WithdrawalRequestStatus::Confirmed,
@@ -1679,6 +2134,112 @@ mod tests {
         assert_eq!(status, mapping.status);
     }
 
+    #[test]
+    fn validation_report_lists_every_failure() {
+        let deposit_report = DepositRequestReport {
+            status: DepositConfirmationStatus::Unconfirmed,
+            can_sign: Some(true),
+            can_accept: Some(true),
+            amount: 100_000_000,
+            max_fee: u64::MAX,
+            lock_time: LockTime::from_height(u16::MAX),
+            outpoint: OutPoint::null(),
+            deposit_script: ScriptBuf::new(),
+            reclaim_script: ScriptBuf::new(),
+            signers_public_key: *sbtc::UNSPENDABLE_TAPROOT_KEY,
+            dkg_shares_status: Some(DkgSharesStatus::Verified),
+        };
+        let withdrawal_report = |request_id, is_accepted, amount| WithdrawalRequestReport {
+            status: WithdrawalRequestStatus::Confirmed,
+            id: QualifiedRequestId {
+                request_id,
+                txid: StacksTxId::from([0; 32]),
+                block_hash: StacksBlockHash::from([0; 32]),
+            },
+            is_accepted: Some(is_accepted),
+            amount,
+            max_fee: TX_FEE.to_sat(),
+            recipient: TEST_RECIPIENT.clone(),
+            bitcoin_block_height: 0u64.into(),
+        };
+        // The first withdrawal is fine, the second one was rejected by
+        // this signer and the third one is for a dust amount.
+        let withdrawal_reports = [
+            withdrawal_report(0, true, Amount::ONE_BTC.to_sat()),
+            withdrawal_report(1, false, Amount::ONE_BTC.to_sat()),
+            withdrawal_report(2, true, TEST_RECIPIENT.minimal_non_dust().to_sat() - 1),
+        ];
+
+        let mut tx = crate::testing::btc::base_signer_transaction();
+        tx.input.push(TxIn {
+            previous_output: deposit_report.outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        });
+        for report in withdrawal_reports.iter() {
+            tx.output.push(TxOut {
+                value: Amount::from_sat(report.amount),
+                script_pubkey: report.recipient.clone(),
+            });
+        }
+
+        let votes = SignerVotes::from(Vec::new());
+        let reports = SbtcReports {
+            deposits: vec![(
+                deposit_report.to_deposit_request(&votes, 0).unwrap(),
+                deposit_report,
+            )],
+            withdrawals: withdrawal_reports
+                .into_iter()
+                .map(|report| (report.to_withdrawal_request(&votes, 0).unwrap(), report))
+                .collect(),
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: OutPoint::null(),
+                    amount: Amount::ONE_BTC.to_sat(),
+                    public_key: *sbtc::UNSPENDABLE_TAPROOT_KEY,
+                },
+                fee_rate: 1.0,
+                public_key: *sbtc::UNSPENDABLE_TAPROOT_KEY,
+                last_fees: None,
+                magic_bytes: [0; 2],
+            },
+        };
+
+        let chain_tip_height = WITHDRAWAL_MIN_CONFIRMATIONS.into();
+        let limits = SbtcLimits::unlimited();
+        let report = reports.validation_report(chain_tip_height, &tx, TX_FEE, &limits, 0);
+
+        let expected = BitcoinValidationReport {
+            is_valid_tx: false,
+            failures: vec![
+                BitcoinValidationFailure {
+                    index: 1,
+                    error: BitcoinSweepErrorMsg::Deposit(InputValidationResult::TxNotOnBestChain),
+                },
+                BitcoinValidationFailure {
+                    index: 3,
+                    error: BitcoinSweepErrorMsg::Withdrawal(
+                        WithdrawalValidationResult::RequestRejected,
+                    ),
+                },
+                BitcoinValidationFailure {
+                    index: 4,
+                    error: BitcoinSweepErrorMsg::Withdrawal(
+                        WithdrawalValidationResult::AmountIsDust,
+                    ),
+                },
+            ],
+        };
+        assert_eq!(report, expected);
+
+        // The report can be serialized for logs and messages.
+        let json = serde_json::to_string(&report).unwrap();
+        let round_trip: BitcoinValidationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip, report);
+    }
+
     #[test]
     fn withdrawal_report_validation_unknown() {
         let report = WithdrawalRequestReport {
@@ -1987,6 +2548,65 @@ mod tests {
         assert_eq!(requests.pre_validation().is_ok(), result);
     }
 
+    #[test_case(1.0, 1000.0, true ; "well-below-max")]
+    #[test_case(1000.0, 1000.0, true ; "equal-to-max")]
+    #[test_case(1000.1, 1000.0, false ; "above-max")]
+    fn test_validate_fee_rate(fee_rate: f64, max_fee_rate: f64, result: bool) {
+        let requests = BitcoinPreSignRequest {
+            request_package: vec![TxRequestIds {
+                deposits: vec![OutPoint::new(Txid::from_byte_array([1; 32]), 0)],
+                withdrawals: vec![],
+            }],
+            fee_rate,
+            last_fees: None,
+        };
+        assert_eq!(requests.validate_fee_rate(max_fee_rate).is_ok(), result);
+    }
+
+    #[test_case(10.0, 11.0, 11.0, true ; "replacement-at-requested-rate")]
+    #[test_case(10.0, 1.0, 10.1, true ; "replacement-minimum-below-max")]
+    #[test_case(10.0, 1.0, 10.0, false ; "replacement-minimum-above-max")]
+    #[test_case(1.0, 20.0, 15.0, false ; "requested-rate-above-max")]
+    fn test_validate_fee_rate_with_last_fees(
+        last_fee_rate: f64,
+        fee_rate: f64,
+        max_fee_rate: f64,
+        result: bool,
+    ) {
+        // A transaction replacing one that paid the last fees must pay a
+        // higher fee rate than it, whatever the requested fee rate is.
+        let requests = BitcoinPreSignRequest {
+            request_package: vec![TxRequestIds {
+                deposits: vec![OutPoint::new(Txid::from_byte_array([1; 32]), 0)],
+                withdrawals: vec![],
+            }],
+            fee_rate,
+            last_fees: Some(Fees {
+                total: 1000,
+                rate: last_fee_rate,
+            }),
+        };
+        assert_eq!(requests.validate_fee_rate(max_fee_rate).is_ok(), result);
+    }
+
+    #[test_case(None, 100.0 ; "no-market-rate")]
+    #[test_case(Some(2.0), 20.0 ; "market-multiple-below-max")]
+    #[test_case(Some(10.0), 100.0 ; "market-multiple-equal-to-max")]
+    #[test_case(Some(50.0), 100.0 ; "market-multiple-above-max")]
+    fn test_max_acceptable_fee_rate(market_fee_rate: Option<f64>, expected: f64) {
+        let settings = ReloadableSettings {
+            stacks_fees_max_ustx: NonZeroU64::new(1_000_000).unwrap(),
+            bitcoin_fee_rate_max: NonZeroU64::new(100).unwrap(),
+            bitcoin_fee_rate_market_multiple: NonZeroU16::new(10).unwrap(),
+            min_deposit_amount: 0,
+            blocklist_failure_policy: BlocklistFailurePolicy::default(),
+        };
+        assert_eq!(
+            max_acceptable_fee_rate(&settings, market_fee_rate),
+            expected
+        );
+    }
+
     fn create_deposit_report(idx: u8, amount: u64) -> (DepositRequestReport, SignerVotes) {
         (
             DepositRequestReport {
@@ -2009,6 +2629,119 @@ mod tests {
         )
     }
 
+    /// A mock of the storage layer for request reports that counts the
+    /// number of queries made.
+    #[derive(Default)]
+    struct CountingReportStore {
+        deposit_queries: AtomicUsize,
+        withdrawal_queries: AtomicUsize,
+    }
+
+    impl CountingReportStore {
+        async fn get_deposit_request_report(
+            &self,
+            idx: u8,
+        ) -> Result<Option<DepositRequestReport>, Error> {
+            self.deposit_queries.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(create_deposit_report(idx, 10_000).0))
+        }
+
+        async fn get_withdrawal_request_report(
+            &self,
+            idx: u8,
+        ) -> Result<Option<WithdrawalRequestReport>, Error> {
+            self.withdrawal_queries.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(create_withdrawal_report(idx, 10_000).0))
+        }
+
+        fn queries(&self) -> (usize, usize) {
+            (
+                self.deposit_queries.load(Ordering::SeqCst),
+                self.withdrawal_queries.load(Ordering::SeqCst),
+            )
+        }
+    }
+
+    /// Fetch the reports for a transaction with the given number of
+    /// deposits and withdrawals, the way that a validation pass does.
+    async fn fetch_reports(
+        store: &CountingReportStore,
+        reports: &mut ReportCache,
+        key: ReportCacheKey,
+        num_deposits: u8,
+        num_withdrawals: u8,
+    ) {
+        reports.invalidate_if_stale(key);
+        for idx in 0..num_deposits {
+            let (expected, _) = create_deposit_report(idx, 10_000);
+            let fetch = store.get_deposit_request_report(idx);
+            let report = reports.deposit_report(&expected.outpoint, fetch).await;
+            assert_eq!(report.unwrap(), Some(expected));
+        }
+        for idx in 0..num_withdrawals {
+            let (expected, _) = create_withdrawal_report(idx, 10_000);
+            let fetch = store.get_withdrawal_request_report(idx);
+            let report = reports.withdrawal_report(&expected.id, fetch).await;
+            assert_eq!(report.unwrap(), Some(expected));
+        }
+    }
+
+    fn report_cache_key(bitcoin_chain_tip: u8, stacks_chain_tip: u8) -> ReportCacheKey {
+        ReportCacheKey {
+            bitcoin_chain_tip: BitcoinBlockHash::from([bitcoin_chain_tip; 32]),
+            stacks_chain_tip: StacksBlockHash::from([stacks_chain_tip; 32]),
+            signer_public_key: PublicKey::from_private_key(&PrivateKey::new(&mut OsRng)),
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_are_fetched_once_per_chain_tip() {
+        let store = CountingReportStore::default();
+        let mut reports = ReportCache::default();
+        let key = report_cache_key(1, 1);
+
+        // Validating the same transaction twice only queries the
+        // database for the reports the first time around.
+        fetch_reports(&store, &mut reports, key, 20, 5).await;
+        assert_eq!(store.queries(), (20, 5));
+        fetch_reports(&store, &mut reports, key, 20, 5).await;
+        assert_eq!(store.queries(), (20, 5));
+
+        // The reports depend on the chain tips, so we fetch them again
+        // whenever either of them changes.
+        let new_bitcoin_tip = ReportCacheKey {
+            bitcoin_chain_tip: BitcoinBlockHash::from([2; 32]),
+            ..key
+        };
+        fetch_reports(&store, &mut reports, new_bitcoin_tip, 20, 5).await;
+        assert_eq!(store.queries(), (40, 10));
+
+        let new_stacks_tip = ReportCacheKey {
+            stacks_chain_tip: StacksBlockHash::from([2; 32]),
+            ..new_bitcoin_tip
+        };
+        fetch_reports(&store, &mut reports, new_stacks_tip, 20, 5).await;
+        fetch_reports(&store, &mut reports, new_stacks_tip, 20, 5).await;
+        assert_eq!(store.queries(), (60, 15));
+    }
+
+    #[tokio::test]
+    async fn report_cache_is_bounded() {
+        let store = CountingReportStore::default();
+        let mut reports = ReportCache::new(NonZeroUsize::new(10).unwrap());
+        let key = report_cache_key(1, 1);
+
+        fetch_reports(&store, &mut reports, key, 20, 20).await;
+        assert_eq!(reports.deposit_reports.len(), 10);
+        assert_eq!(reports.withdrawal_reports.len(), 10);
+
+        // Only the most recently used reports are kept around.
+        fetch_reports(&store, &mut reports, key, 20, 20).await;
+        assert_eq!(store.queries(), (40, 40));
+        fetch_reports(&store, &mut reports, key, 10, 10).await;
+        assert_eq!(store.queries(), (50, 50));
+    }
+
     fn create_withdrawal_report(idx: u8, amount: u64) -> (WithdrawalRequestReport, SignerVotes) {
         let report = WithdrawalRequestReport {
             id: QualifiedRequestId {
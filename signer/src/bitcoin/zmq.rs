@@ -40,7 +40,9 @@ use bitcoincore_zmq::SocketMessage;
 use bitcoincore_zmq::subscribe_async_monitor_stream::MessageStream;
 use futures::stream::Stream;
 use futures::stream::StreamExt as _;
+use url::Url;
 
+use crate::config::BitcoinConfig;
 use crate::error::Error;
 
 /// A struct for messages over bitcoin-core's ZeroMQ interface.
@@ -50,6 +52,34 @@ pub struct BitcoinCoreMessageStream {
 }
 
 impl BitcoinCoreMessageStream {
+    /// Create a new stream of block hashes, connecting to the first of the
+    /// configured block hash stream endpoints that accepts the connection.
+    pub async fn new_block_hash_stream(config: &BitcoinConfig) -> Result<Self, Error> {
+        Self::new_from_endpoints(&config.block_hash_stream_endpoints).await
+    }
+
+    /// Create a new stream of raw blocks, connecting to the first of the
+    /// configured block stream endpoints that accepts the connection.
+    pub async fn new_block_stream(config: &BitcoinConfig) -> Result<Self, Error> {
+        Self::new_from_endpoints(&config.block_stream_endpoints).await
+    }
+
+    /// Create a new one using the first of the given endpoints that
+    /// accepts the connection.
+    async fn new_from_endpoints(endpoints: &[Url]) -> Result<Self, Error> {
+        let mut result = Err(Error::BitcoinCoreZmqNoEndpoints);
+        for endpoint in endpoints {
+            result = Self::new_from_endpoint(endpoint.as_str()).await;
+            match &result {
+                Ok(_) => break,
+                Err(error) => {
+                    tracing::warn!(%error, %endpoint, "could not connect to ZeroMQ endpoint");
+                }
+            }
+        }
+        result
+    }
+
     /// Create a new one using the given endpoint.
     pub async fn new_from_endpoint(endpoint: &str) -> Result<Self, Error> {
         let inner_stream = tokio::time::timeout(Duration::from_secs(10), async {
@@ -89,11 +119,19 @@ impl BitcoinCoreMessageStream {
         }
     }
 
-    /// Convert this stream into one that returns only blocks
+    /// Convert this stream into one that returns only blocks.
+    ///
+    /// This requires that bitcoin-core publishes to the `rawblock` topic
+    /// on the endpoint of this stream. Blocks that could not be
+    /// deserialized are returned as [`Error::DecodeBitcoinBlock`] errors,
+    /// so that consumers can fall back to fetching the block over RPC.
     pub fn to_block_stream(self) -> impl Stream<Item = Result<Block, Error>> {
         self.inspect(Self::inspect_message)
             .filter_map(|msg| match msg {
                 Ok(SocketMessage::Message(Message::Block(block, _))) => ready(Some(Ok(block))),
+                Err(Error::BitcoinCoreZmq(bitcoincore_zmq::Error::BitcoinDeserialization(err))) => {
+                    ready(Some(Err(Error::DecodeBitcoinBlock(err))))
+                }
                 Err(err) => ready(Some(Err(err))),
                 Ok(_) => ready(None),
             })
@@ -20,17 +20,23 @@
 use std::collections::BTreeSet;
 use std::future::Future;
 use std::time::Duration;
+use std::time::Instant;
 
+use crate::PENDING_SIGNER_DECISIONS_MAX_AGE;
 use crate::bitcoin::BitcoinInteract;
 use crate::bitcoin::rpc::BitcoinBlockHeader;
 use crate::bitcoin::rpc::BitcoinTxInfo;
 use crate::bitcoin::utxo::TxDeconstructor as _;
+use crate::bitcoin::utxo::assess_confirmed_fees;
+use crate::bitcoin::validation::TxRequestIds;
 use crate::context::Context;
 use crate::context::SbtcLimits;
+use crate::context::SignerCommand;
 use crate::context::SignerEvent;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
 use crate::metrics::BITCOIN_BLOCKCHAIN;
 use crate::metrics::Metrics;
 use crate::stacks::api::GetNakamotoStartHeight as _;
@@ -42,23 +48,71 @@ use crate::storage::DbWrite;
 use crate::storage::model;
 use crate::storage::model::EncryptedDkgShares;
 use bitcoin::Amount;
+use bitcoin::Block;
 use bitcoin::BlockHash;
 use bitcoin::ScriptBuf;
 use bitcoin::Transaction;
 use bitcoin::hashes::Hash as _;
+use emily_client::models::Status;
+use emily_client::models::Withdrawal;
 use futures::stream::Stream;
 use futures::stream::StreamExt;
 use sbtc::deposits::CreateDepositRequest;
 use sbtc::deposits::DepositInfo;
 use std::collections::HashSet;
 
+/// The maximum number of aggregate keys, taken from the most recent
+/// rotate-keys transactions, whose scriptPubKeys are considered to belong
+/// to the signers when extracting sBTC transactions.
+const SIGNER_SET_AGGREGATE_KEY_LOOKBACK: u16 = 8;
+
+/// The number of bitcoin blocks processed between progress logs when the
+/// block observer is catching up to bitcoin-core's chain tip.
+const CATCH_UP_PROGRESS_INTERVAL: usize = 100;
+
 /// Block observer
 #[derive(Debug)]
-pub struct BlockObserver<Context, BlockHashStream> {
+pub struct BlockObserver<Context, BlockStream> {
     /// Signer context
     pub context: Context,
-    /// Stream of blocks from the block notifier
-    pub bitcoin_blocks: BlockHashStream,
+    /// Stream of blocks or block hashes from the block notifier
+    pub bitcoin_blocks: BlockStream,
+}
+
+/// A notification of a new bitcoin block from the block notifier.
+///
+/// Depending on the ZeroMQ topic that we subscribe to, bitcoin-core
+/// either sends us the hash of the new block or the full block.
+#[derive(Debug, Clone)]
+pub enum BlockNotification {
+    /// Only the block hash was received, so the block itself needs to be
+    /// fetched from bitcoin-core over RPC.
+    Hash(BlockHash),
+    /// The full block was received, so we do not need to make a follow-up
+    /// RPC call to fetch it.
+    Block(Box<Block>),
+}
+
+impl BlockNotification {
+    /// The hash of the block in this notification.
+    pub fn block_hash(&self) -> BlockHash {
+        match self {
+            BlockNotification::Hash(block_hash) => *block_hash,
+            BlockNotification::Block(block) => block.block_hash(),
+        }
+    }
+}
+
+impl From<BlockHash> for BlockNotification {
+    fn from(block_hash: BlockHash) -> Self {
+        BlockNotification::Hash(block_hash)
+    }
+}
+
+impl From<Block> for BlockNotification {
+    fn from(block: Block) -> Self {
+        BlockNotification::Block(Box::new(block))
+    }
 }
 
 /// A full "deposit", containing the bitcoin transaction and a fully
@@ -128,67 +182,145 @@ pub trait DepositRequestValidator {
         C: BitcoinInteract;
 }
 
-impl<C, S> BlockObserver<C, S>
+impl<C, S, N> BlockObserver<C, S>
 where
     C: Context,
-    S: Stream<Item = Result<bitcoin::BlockHash, Error>> + Unpin,
+    S: Stream<Item = Result<N, Error>> + Unpin,
+    N: Into<BlockNotification>,
 {
     /// Run the block observer
     #[tracing::instrument(skip_all, name = "block-observer")]
     pub async fn run(mut self) -> Result<(), Error> {
         let term = self.context.get_termination_handle();
+        // We only check for the shutdown signal between notifications, so
+        // the notification being processed when it is signalled is always
+        // completed before we acknowledge.
+        let _participant = term.register_participant("block-observer");
+
+        // We may have missed blocks while we were offline, so we catch up
+        // to bitcoin-core's chain tip before handling any notifications.
+        if let Err(error) = self.catch_up_bitcoin_blocks().await {
+            tracing::warn!(%error, "could not catch up on bitcoin blocks");
+        }
+
+        // We have also missed the decisions that the other signers made
+        // on requests while we were offline, so we ask them for those.
+        if let Err(error) = self.request_missed_decisions().await {
+            tracing::warn!(%error, "could not request missed signer decisions");
+        }
+
+        // If we don't hear from the block stream for a while, we poll
+        // bitcoin-core for new blocks in case the stream has stopped
+        // delivering notifications.
+        let poll_fallback_interval = self.context.config().bitcoin.poll_fallback_interval;
+        let mut last_notification = Instant::now();
 
         loop {
             if term.shutdown_signalled() {
                 break;
             }
 
+            if last_notification.elapsed() >= poll_fallback_interval {
+                tracing::debug!("no recent block notifications; polling bitcoin-core for blocks");
+                if let Err(error) = self.catch_up_bitcoin_blocks().await {
+                    tracing::warn!(%error, "could not catch up on bitcoin blocks");
+                }
+                last_notification = Instant::now();
+            }
+
             // Bitcoin blocks will generally arrive in ~10 minute intervals, so
             // we don't need to be so aggressive in our timeout here.
             let poll = tokio::time::timeout(Duration::from_millis(100), self.bitcoin_blocks.next());
 
-            match poll.await {
-                Ok(Some(Ok(block_hash))) => {
-                    tracing::info!("observed new bitcoin block from stream");
-                    metrics::counter!(
-                        Metrics::BlocksObservedTotal,
-                        "blockchain" => BITCOIN_BLOCKCHAIN,
-                    )
-                    .increment(1);
-
-                    if let Err(error) = self.process_bitcoin_blocks_until(block_hash).await {
-                        tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
-                    }
-
-                    if let Err(error) = self.process_stacks_blocks().await {
-                        tracing::warn!(%error, "could not process stacks blocks");
-                    }
-
-                    if let Err(error) = self.check_pending_dkg_shares(block_hash).await {
-                        tracing::warn!(%error, "could not check pending dkg shares");
-                        continue;
-                    }
-
-                    tracing::debug!("updating the signer state");
-                    if let Err(error) = self.update_signer_state(block_hash).await {
-                        tracing::warn!(%error, "could not update the signer state");
-                        continue;
+            let notification: BlockNotification = match poll.await {
+                Ok(Some(Ok(notification))) => notification.into(),
+                // We received a full block over the stream but could not
+                // parse it. Let's fall back to fetching the block using
+                // the hash of bitcoin-core's current chain tip.
+                Ok(Some(Err(error @ Error::DecodeBitcoinBlock(_)))) => {
+                    tracing::warn!(%error, "could not parse bitcoin block from stream");
+                    match self.fetch_best_block_hash().await {
+                        Ok(block_hash) => BlockNotification::Hash(block_hash),
+                        Err(error) => {
+                            tracing::warn!(%error, "could not fetch the best block hash");
+                            continue;
+                        }
                     }
-
-                    tracing::info!("loading latest deposit requests from Emily");
-                    if let Err(error) = self.load_latest_deposit_requests().await {
-                        tracing::warn!(%error, "could not load latest deposit requests from Emily");
-                    }
-
-                    self.context
-                        .signal(SignerEvent::BitcoinBlockObserved.into())?;
                 }
                 Ok(Some(Err(error))) => {
                     tracing::warn!(%error, "error decoding new bitcoin block hash from stream");
+                    // We may have missed notifications, so let's catch up
+                    // using bitcoin-core's current chain tip.
+                    if let Err(error) = self.catch_up_bitcoin_blocks().await {
+                        tracing::warn!(%error, "could not catch up on bitcoin blocks");
+                    }
                     continue;
                 }
                 _ => continue,
             };
+            last_notification = Instant::now();
+
+            let block_hash = notification.block_hash();
+            tracing::info!("observed new bitcoin block from stream");
+            metrics::counter!(
+                Metrics::BlocksObservedTotal,
+                "blockchain" => BITCOIN_BLOCKCHAIN,
+            )
+            .increment(1);
+
+            if let Err(error) = self.process_block_notification(&notification).await {
+                tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
+            }
+
+            if let Err(error) = self.process_stacks_blocks().await {
+                tracing::warn!(%error, "could not process stacks blocks");
+            }
+
+            if let Err(error) = self.check_pending_dkg_shares(block_hash).await {
+                tracing::warn!(%error, "could not check pending dkg shares");
+                continue;
+            }
+
+            tracing::debug!("updating the signer state");
+            if let Err(error) = self.update_signer_state(block_hash).await {
+                tracing::warn!(%error, "could not update the signer state");
+                continue;
+            }
+
+            tracing::info!("loading latest deposit requests from Emily");
+            if let Err(error) = self.load_latest_deposit_requests().await {
+                tracing::warn!(%error, "could not load latest deposit requests from Emily");
+            }
+
+            tracing::info!("loading latest withdrawal requests from Emily");
+            if let Err(error) = self.load_latest_withdrawal_requests().await {
+                tracing::warn!(%error, "could not load latest withdrawal requests from Emily");
+            }
+
+            if let Err(error) = self.replay_pending_signer_decisions().await {
+                tracing::warn!(%error, "could not replay buffered signer decisions");
+            }
+
+            if let Err(error) = self.prune_storage().await {
+                tracing::warn!(%error, "could not prune old data from the database");
+            }
+
+            // The chain tip and signer set in the signer state are up to
+            // date by now, so everyone that reacts to the signal below
+            // agrees on the coordinator.
+            let activation_height = self
+                .context
+                .config()
+                .signer
+                .coordinator_election_activation_height;
+            let coordinator = self.context.state().current_coordinator(activation_height);
+            tracing::debug!(
+                ?coordinator,
+                "elected the coordinator for the new chain tip"
+            );
+
+            self.context
+                .signal(SignerEvent::BitcoinBlockObserved.into())?;
         }
 
         tracing::info!("block observer has stopped");
@@ -197,13 +329,132 @@ where
     }
 }
 
+/// Decode a hex-encoded stacks block ID or transaction ID.
+fn parse_stacks_hash(hex_str: &str) -> Result<[u8; 32], Error> {
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(hex_str, &mut bytes).map_err(Error::DecodeHexBytes)?;
+    Ok(bytes)
+}
+
+/// Convert a withdrawal request returned by Emily into the withdrawal
+/// request that we store in the database.
+fn parse_withdrawal_request(
+    withdrawal: &Withdrawal,
+    bitcoin_block_height: model::BitcoinBlockHeight,
+) -> Result<model::WithdrawalRequest, Error> {
+    let recipient = ScriptBuf::from_hex(&withdrawal.recipient).map_err(Error::DecodeHexScript)?;
+    Ok(model::WithdrawalRequest {
+        request_id: withdrawal.request_id,
+        txid: parse_stacks_hash(&withdrawal.txid)?.into(),
+        block_hash: parse_stacks_hash(&withdrawal.stacks_block_hash)?.into(),
+        recipient: recipient.into(),
+        amount: withdrawal.amount,
+        max_fee: withdrawal.parameters.max_fee,
+        sender_address: withdrawal.sender.parse()?,
+        bitcoin_block_height,
+    })
+}
+
 impl<C: Context, B> BlockObserver<C, B> {
     /// Fetch deposit requests from Emily and store the ones that pass
     /// validation into the database.
     #[tracing::instrument(skip_all)]
     async fn load_latest_deposit_requests(&self) -> Result<(), Error> {
-        let requests = self.context.get_emily_client().get_deposits().await?;
-        self.load_requests(&requests).await
+        let fetched = self.context.get_emily_client().get_deposits().await?;
+        tracing::info!(
+            deposits = fetched.deposits.len(),
+            truncated = fetched.truncated,
+            "fetched deposit requests from Emily"
+        );
+        if fetched.truncated {
+            tracing::warn!("did not fetch all deposit requests from Emily");
+        }
+        self.load_requests(&fetched.deposits).await
+    }
+
+    /// Fetch pending withdrawal requests from Emily and store the ones
+    /// that were created in a stacks block that we know about into the
+    /// database.
+    ///
+    /// Withdrawal requests are normally stored when we observe the stacks
+    /// event that created them, so this only fills in the requests whose
+    /// events we have missed. Only requests anchored to a bitcoin block
+    /// within the signer's `context_window` are stored, and storing a
+    /// request that we already have is a no-op.
+    #[tracing::instrument(skip_all)]
+    async fn load_latest_withdrawal_requests(&self) -> Result<(), Error> {
+        let emily_client = self.context.get_emily_client();
+        let db = self.context.get_storage_mut();
+
+        let Some(chain_tip) = db.get_bitcoin_canonical_chain_tip_ref().await? else {
+            tracing::debug!("no bitcoin chain tip, skipping withdrawal requests from Emily");
+            return Ok(());
+        };
+        // Withdrawal requests that were created in a stacks block anchored
+        // to a bitcoin block outside our context window are not going to
+        // be considered by the other components, so we ignore them here.
+        let context_window = self.context.config().signer.context_window;
+        let min_block_height = chain_tip.block_height.saturating_sub(context_window);
+
+        let withdrawals = emily_client.get_withdrawals(Status::Pending).await?;
+
+        for info in withdrawals {
+            let block_hash = match parse_stacks_hash(&info.stacks_block_hash) {
+                Ok(block_hash) => model::StacksBlockHash::from(block_hash),
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        request_id = info.request_id,
+                        "skipping corrupted withdrawal request"
+                    );
+                    continue;
+                }
+            };
+            // We only store withdrawal requests that were created in a
+            // stacks block that we have observed, since we need the
+            // height of its bitcoin anchor.
+            let Some(stacks_block) = db.get_stacks_block(&block_hash).await? else {
+                tracing::debug!(
+                    request_id = info.request_id,
+                    %block_hash,
+                    "skipping withdrawal request from an unknown stacks block"
+                );
+                continue;
+            };
+            let Some(bitcoin_block) = db.get_bitcoin_block(&stacks_block.bitcoin_anchor).await?
+            else {
+                continue;
+            };
+            if bitcoin_block.block_height < min_block_height {
+                tracing::debug!(
+                    request_id = info.request_id,
+                    %block_hash,
+                    "skipping withdrawal request from outside the context window"
+                );
+                continue;
+            }
+            // The summaries returned when listing withdrawals do not
+            // include the request parameters, so we need to fetch the
+            // whole request.
+            let Some(withdrawal) = emily_client.get_withdrawal(info.request_id).await? else {
+                continue;
+            };
+
+            let request = match parse_withdrawal_request(&withdrawal, bitcoin_block.block_height) {
+                Ok(request) => request,
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        request_id = info.request_id,
+                        "skipping corrupted withdrawal request"
+                    );
+                    continue;
+                }
+            };
+            db.write_withdrawal_request(&request).await?;
+        }
+
+        Ok(())
     }
 
     /// Validate the given deposit requests and store the ones that pass
@@ -223,19 +474,57 @@ impl<C: Context, B> BlockObserver<C, B> {
         let mut deposit_requests = Vec::new();
         let mut deposit_request_txs = Vec::new();
         let bitcoin_client = self.context.get_bitcoin_client();
-        let is_mainnet = self.context.config().signer.network.is_mainnet();
-
-        for request in requests {
-            let deposit = request
-                .validate(&bitcoin_client, is_mainnet)
-                .await
-                .inspect_err(|error| tracing::warn!(%error, "could not validate deposit request"));
+        let config = &self.context.config().signer;
+        let is_mainnet = config.network.is_mainnet();
+        let min_deposit_amount = self.context.reloadable_config().get().min_deposit_amount;
+        let concurrency = usize::from(config.deposit_validation_concurrency.get());
+
+        // Validation mostly waits on bitcoin-core, so we validate the
+        // requests concurrently. A failure for one request does not stop
+        // the validation of the others.
+        let client = &bitcoin_client;
+        let validated: Vec<_> = futures::stream::iter(requests)
+            .map(|request| async move {
+                request
+                    .validate(client, is_mainnet)
+                    .await
+                    .inspect_err(|error| {
+                        tracing::warn!(
+                            %error,
+                            outpoint = %request.outpoint,
+                            "could not validate deposit request"
+                        )
+                    })
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+        let failed = validated.iter().filter(|result| result.is_err()).count();
+        tracing::debug!(
+            requests = requests.len(),
+            failed,
+            "finished validating deposit requests"
+        );
 
+        for deposit in validated {
             // We log the error above, so we just need to extract the
             // deposit now.
             Metrics::increment_deposit_total(&deposit);
             let Ok(Some(deposit)) = deposit else { continue };
 
+            // There is no point in storing deposits that we will never
+            // sweep, so we skip the ones that are too small.
+            if deposit.info.amount < min_deposit_amount {
+                tracing::info!(
+                    outpoint = %deposit.info.outpoint,
+                    amount = deposit.info.amount,
+                    min_deposit_amount,
+                    "skipping deposit request below the minimum deposit amount"
+                );
+                continue;
+            }
+
             self.process_bitcoin_blocks_until(deposit.tx_info.block_hash)
                 .await?;
 
@@ -294,8 +583,24 @@ impl<C: Context, B> BlockObserver<C, B> {
     /// of writing) into memory.
     #[tracing::instrument(skip_all, fields(%block_hash))]
     pub async fn next_headers_to_process(
+        &self,
+        block_hash: BlockHash,
+    ) -> Result<Vec<BitcoinBlockHeader>, Error> {
+        self.next_headers_to_process_with_limit(block_hash, usize::MAX)
+            .await
+    }
+
+    /// Find the parent blocks from the given block that are also missing
+    /// from our database, fetching at most `max_headers` block headers.
+    ///
+    /// This does the same thing as [`Self::next_headers_to_process`],
+    /// except that we stop fetching block headers once we have fetched
+    /// `max_headers` of them, in which case the returned headers do not
+    /// link up with the blocks in our database.
+    async fn next_headers_to_process_with_limit(
         &self,
         mut block_hash: BlockHash,
+        max_headers: usize,
     ) -> Result<Vec<BitcoinBlockHeader>, Error> {
         self.set_sbtc_bitcoin_start_height().await?;
 
@@ -305,6 +610,15 @@ impl<C: Context, B> BlockObserver<C, B> {
         let bitcoin_client = self.context.get_bitcoin_client();
 
         while !db.is_known_bitcoin_block_hash(&block_hash.into()).await? {
+            if headers.len() >= max_headers {
+                tracing::warn!(
+                    %block_hash,
+                    max_headers,
+                    "reached the maximum number of block headers to fetch"
+                );
+                break;
+            }
+
             let Some(header) = bitcoin_client.get_block_header(&block_hash).await? else {
                 tracing::error!(%block_hash, "bitcoin-core does not know about block header");
                 return Err(Error::BitcoinCoreUnknownBlockHeader(block_hash));
@@ -346,17 +660,236 @@ impl<C: Context, B> BlockObserver<C, B> {
     /// subsequent calls to this function will properly pick up from where
     /// we left off and update the database.
     async fn process_bitcoin_blocks_until(&self, block_hash: BlockHash) -> Result<(), Error> {
-        let block_headers = self.next_headers_to_process(block_hash).await?;
+        self.process_block_notification(&BlockNotification::Hash(block_hash))
+            .await
+    }
+
+    /// Process bitcoin blocks until we get caught up to the block in the
+    /// given notification.
+    ///
+    /// This does the same thing as [`Self::process_bitcoin_blocks_until`],
+    /// except that if the notification contains the full block then we
+    /// use it instead of fetching it from bitcoin-core. Any missing
+    /// ancestors of the block are still fetched over RPC. Once the blocks
+    /// are written, we check whether the block in the notification
+    /// replaced our previous chain tip through a reorg.
+    async fn process_block_notification(
+        &self,
+        notification: &BlockNotification,
+    ) -> Result<(), Error> {
+        let chain_tip = self
+            .context
+            .get_storage()
+            .get_bitcoin_canonical_chain_tip()
+            .await?;
+        let block_headers = self
+            .next_headers_to_process(notification.block_hash())
+            .await?;
 
         for block_header in block_headers {
+            match notification {
+                BlockNotification::Block(block) if block.block_hash() == block_header.hash => {
+                    self.write_bitcoin_block(block).await?
+                }
+                _ => self.process_bitcoin_block(block_header).await?,
+            }
+        }
+
+        let new_chain_tip = notification.block_hash().into();
+        match chain_tip {
+            Some(chain_tip) if chain_tip != new_chain_tip => {
+                self.handle_bitcoin_reorg(chain_tip, new_chain_tip).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fetch and store the bitcoin blocks between the canonical chain tip
+    /// in our database and bitcoin-core's current chain tip.
+    ///
+    /// This is done when the block observer starts, and whenever the
+    /// block stream returns an error, so that we do not have to wait for
+    /// the next block notification to learn about the blocks that were
+    /// mined while we were not listening. We fetch at most
+    /// `bitcoin_catch_up_max_depth` blocks, starting from bitcoin-core's
+    /// chain tip.
+    #[tracing::instrument(skip_all)]
+    pub async fn catch_up_bitcoin_blocks(&self) -> Result<(), Error> {
+        let chain_tip = self
+            .context
+            .get_storage()
+            .get_bitcoin_canonical_chain_tip()
+            .await?;
+        let best_block_hash = self.fetch_best_block_hash().await?;
+        let max_depth = self.context.config().signer.bitcoin_catch_up_max_depth;
+
+        let block_headers = self
+            .next_headers_to_process_with_limit(best_block_hash, max_depth.get() as usize)
+            .await?;
+
+        if block_headers.is_empty() {
+            tracing::debug!(%best_block_hash, "bitcoin blocks are up to date");
+            return Ok(());
+        }
+
+        let total_blocks = block_headers.len();
+        tracing::info!(%best_block_hash, total_blocks, "catching up on bitcoin blocks");
+
+        for (index, block_header) in block_headers.into_iter().enumerate() {
             self.process_bitcoin_block(block_header).await?;
+
+            let processed_blocks = index + 1;
+            if processed_blocks % CATCH_UP_PROGRESS_INTERVAL == 0 {
+                tracing::info!(
+                    processed_blocks,
+                    total_blocks,
+                    "catching up on bitcoin blocks"
+                );
+            }
+        }
+
+        tracing::info!(%best_block_hash, total_blocks, "caught up on bitcoin blocks");
+
+        match chain_tip {
+            Some(chain_tip) if chain_tip != best_block_hash.into() => {
+                self.handle_bitcoin_reorg(chain_tip, best_block_hash.into())
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Ask the other signers for their decisions on the requests in the
+    /// context window that we have not made a decision on ourselves yet.
+    ///
+    /// This is done after catching up on bitcoin blocks when the block
+    /// observer starts, at which point these are the requests that we
+    /// learned about while we were offline, and whose decisions were
+    /// gossiped without us.
+    #[tracing::instrument(skip_all)]
+    pub async fn request_missed_decisions(&self) -> Result<(), Error> {
+        let db = self.context.get_storage();
+        let Some(chain_tip) = db.get_bitcoin_canonical_chain_tip().await? else {
+            return Ok(());
+        };
+        let config = self.context.config();
+        let context_window = config.signer.context_window;
+        let public_key = config.signer.public_key();
+
+        let deposits = db
+            .get_pending_deposit_requests(&chain_tip, context_window, &public_key)
+            .await?;
+        let withdrawals = db
+            .get_pending_withdrawal_requests(&chain_tip, context_window, &public_key)
+            .await?;
+
+        if deposits.is_empty() && withdrawals.is_empty() {
+            return Ok(());
+        }
+
+        let request_ids = TxRequestIds {
+            deposits: deposits
+                .iter()
+                .map(model::DepositRequest::outpoint)
+                .collect(),
+            withdrawals: withdrawals
+                .iter()
+                .map(model::WithdrawalRequest::qualified_id)
+                .collect(),
+        };
+        tracing::info!(
+            deposits = request_ids.deposits.len(),
+            withdrawals = request_ids.withdrawals.len(),
+            "requesting missed signer decisions from our peers"
+        );
+
+        self.context
+            .signal(SignerCommand::P2PRequestDecisions(request_ids).into())
+    }
+
+    /// Check whether the new bitcoin chain tip builds on top of the old
+    /// one, and if it doesn't, mark the blocks that are no longer on the
+    /// canonical bitcoin blockchain as orphaned and signal the reorg.
+    ///
+    /// We walk back from both chain tips using the blocks in the database
+    /// until we get to their most recent common ancestor, which is the
+    /// fork point. Blocks on the new chain above the fork point may have
+    /// been orphaned by an earlier reorg, so they are marked as canonical
+    /// again.
+    #[tracing::instrument(skip_all, fields(%old_chain_tip, %new_chain_tip))]
+    async fn handle_bitcoin_reorg(
+        &self,
+        old_chain_tip: model::BitcoinBlockHash,
+        new_chain_tip: model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+
+        // The new chain tip is not in the database if it is below the
+        // sBTC start height.
+        let Some(mut new_block) = db.get_bitcoin_block(&new_chain_tip).await? else {
+            return Ok(());
+        };
+        let Some(mut old_block) = db.get_bitcoin_block(&old_chain_tip).await? else {
+            return Ok(());
+        };
+
+        let mut orphaned = Vec::new();
+        let mut reconnected = Vec::new();
+
+        while old_block.block_hash != new_block.block_hash {
+            // Step back on whichever chain is taller. When both are at the
+            // same height we step back on the old one first.
+            let (block, blocks) = if old_block.block_height >= new_block.block_height {
+                (&mut old_block, &mut orphaned)
+            } else {
+                (&mut new_block, &mut reconnected)
+            };
+            blocks.push(block.block_hash);
+
+            let Some(parent) = db.get_bitcoin_block(&block.parent_hash).await? else {
+                tracing::warn!(
+                    parent_hash = %block.parent_hash,
+                    "could not find the common ancestor of the old and new chain tips"
+                );
+                return Ok(());
+            };
+            *block = parent;
+        }
+
+        if orphaned.is_empty() {
+            return Ok(());
         }
 
+        let fork_point = model::BitcoinBlockRef::from(old_block);
+        tracing::warn!(
+            fork_point = %fork_point.block_hash,
+            fork_point_height = %fork_point.block_height,
+            orphaned_blocks = orphaned.len(),
+            "observed a bitcoin reorg"
+        );
+
+        db.set_bitcoin_blocks_orphaned(&orphaned, true).await?;
+        db.set_bitcoin_blocks_orphaned(&reconnected, false).await?;
+
+        self.context
+            .signal(SignerEvent::BitcoinReorgObserved { fork_point, orphaned }.into())?;
+
         Ok(())
     }
 
-    /// Write the bitcoin block and any transactions that spend to any of
-    /// the signers `scriptPubKey`s to the database.
+    /// Fetch the hash of the current chain tip from bitcoin-core.
+    async fn fetch_best_block_hash(&self) -> Result<BlockHash, Error> {
+        let info = self
+            .context
+            .get_bitcoin_client()
+            .get_blockchain_info()
+            .await?;
+        Ok(info.best_block_hash)
+    }
+
+    /// Fetch the bitcoin block from bitcoin-core and write it, and any
+    /// transactions that spend to any of the signers `scriptPubKey`s, to
+    /// the database.
     #[tracing::instrument(skip_all, fields(block_hash = %block_header.hash))]
     async fn process_bitcoin_block(&self, block_header: BitcoinBlockHeader) -> Result<(), Error> {
         let block = self
@@ -365,13 +898,22 @@ impl<C: Context, B> BlockObserver<C, B> {
             .get_block(&block_header.hash)
             .await?
             .ok_or(Error::BitcoinCoreMissingBlock(block_header.hash))?;
-        let db_block = model::BitcoinBlock::from(&block);
+
+        self.write_bitcoin_block(&block).await
+    }
+
+    /// Write the bitcoin block and any transactions that spend to any of
+    /// the signers `scriptPubKey`s to the database.
+    #[tracing::instrument(skip_all, fields(block_hash = %block.block_hash()))]
+    async fn write_bitcoin_block(&self, block: &Block) -> Result<(), Error> {
+        let block_hash = block.block_hash();
+        let db_block = model::BitcoinBlock::from(block);
 
         self.context
             .get_storage_mut()
             .write_bitcoin_block(&db_block)
             .await?;
-        self.extract_sbtc_transactions(block_header.hash, &block.txdata)
+        self.extract_sbtc_transactions(block_hash, &block.txdata)
             .await?;
 
         tracing::debug!("finished processing bitcoin block");
@@ -415,12 +957,22 @@ impl<C: Context, B> BlockObserver<C, B> {
         let db = self.context.get_storage_mut();
         // We store all the scriptPubKeys associated with the signers'
         // aggregate public key. Let's get the last years worth of them.
-        let signer_script_pubkeys: HashSet<ScriptBuf> = db
+        let mut signer_script_pubkeys: HashSet<ScriptBuf> = db
             .get_signers_script_pubkeys()
             .await?
             .into_iter()
             .map(ScriptBuf::from_bytes)
             .collect();
+        // A signer that joined after a key rotation does not have DKG
+        // shares for the earlier aggregate keys, but the signers' UTXO may
+        // still be locked by one of them until it is swept into the new
+        // key. So we also include the keys from recent rotate-keys
+        // transactions.
+        let chain_tip = model::BitcoinBlockHash::from(block_hash);
+        let aggregate_keys = db
+            .get_signer_set_aggregate_keys(&chain_tip, SIGNER_SET_AGGREGATE_KEY_LOOKBACK)
+            .await?;
+        signer_script_pubkeys.extend(aggregate_keys.iter().map(|key| key.signers_script_pubkey()));
 
         let btc_rpc = self.context.get_bitcoin_client();
         // Look through all the UTXOs in the given transaction slice and
@@ -469,6 +1021,7 @@ impl<C: Context, B> BlockObserver<C, B> {
                 block_hash: block_hash.to_byte_array(),
             });
 
+            let mut swept_requests = Vec::new();
             for prevout in tx_info.to_inputs(&signer_script_pubkeys) {
                 db.write_tx_prevout(&prevout).await?;
                 if prevout.prevout_type == model::TxPrevoutType::Deposit {
@@ -477,9 +1030,27 @@ impl<C: Context, B> BlockObserver<C, B> {
                         "blockchain" => BITCOIN_BLOCKCHAIN,
                     )
                     .increment(1);
+
+                    let request = db
+                        .get_deposit_request(&prevout.prevout_txid, prevout.prevout_output_index)
+                        .await?;
+                    swept_requests.extend(request);
                 }
             }
 
+            // Now that the sweep is confirmed we know its actual fee, so
+            // we record the portion of it that each deposit pays. This is
+            // what gets deducted from the minted amount.
+            for (outpoint, assessed_fee) in assess_confirmed_fees(&tx_info, &swept_requests) {
+                let swept_deposit = model::SweptDeposit {
+                    sweep_txid: txid.into(),
+                    txid: outpoint.txid.into(),
+                    output_index: outpoint.vout,
+                    assessed_fee,
+                };
+                db.write_swept_deposit(&swept_deposit).await?;
+            }
+
             let (tx_outputs, withdrawal_outputs) = tx_info.to_outputs(&signer_script_pubkeys)?;
             for output in tx_outputs {
                 db.write_tx_output(&output).await?;
@@ -630,6 +1201,60 @@ impl<C: Context, B> BlockObserver<C, B> {
         self.update_bitcoin_chain_tip().await
     }
 
+    /// Remove settled requests and stale forks that are older than the
+    /// configured retention window from the database.
+    ///
+    /// The retention window is never smaller than the context window, so
+    /// nothing within the context window of the chain tip is pruned.
+    async fn prune_storage(&self) -> Result<(), Error> {
+        let config = &self.context.config().signer;
+        let Some(retention_blocks) = config.storage.retention_blocks else {
+            return Ok(());
+        };
+        let retention_blocks = retention_blocks.max(u32::from(config.context_window));
+
+        let db = self.context.get_storage_mut();
+        let chain_tip = db
+            .get_bitcoin_canonical_chain_tip_ref()
+            .await?
+            .ok_or(Error::NoChainTip)?;
+
+        let min_height = chain_tip
+            .block_height
+            .saturating_sub(retention_blocks as u64);
+        if *min_height == 0 {
+            return Ok(());
+        }
+
+        tracing::debug!(%min_height, "pruning settled data from the database");
+        db.prune_below_height(min_height).await
+    }
+
+    /// Replay the signer decisions that we received over the P2P network
+    /// before we had a record of their deposit or withdrawal request.
+    ///
+    /// This runs after we load the latest requests, so any buffered
+    /// decision whose request we have now stored is moved into the
+    /// deposit and withdrawal signers tables. Buffered decisions older
+    /// than [`PENDING_SIGNER_DECISIONS_MAX_AGE`] are dropped.
+    pub async fn replay_pending_signer_decisions(&self) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+
+        let replayed = db.replay_pending_signer_decisions().await?;
+        let expired = db
+            .delete_expired_pending_signer_decisions(PENDING_SIGNER_DECISIONS_MAX_AGE)
+            .await?;
+        let buffered = db.get_pending_signer_decisions_count().await?;
+
+        tracing::debug!(
+            replayed,
+            expired,
+            buffered,
+            "replayed buffered signer decisions"
+        );
+        Ok(())
+    }
+
     /// Checks if the latest dkg share is pending and is no longer valid
     async fn check_pending_dkg_shares(&self, chain_tip: BlockHash) -> Result<(), Error> {
         let db = self.context.get_storage_mut();
@@ -725,6 +1350,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU16;
+
     use bitcoin::Amount;
     use bitcoin::BlockHash;
     use bitcoin::TxOut;
@@ -926,6 +1553,239 @@ mod tests {
         assert_eq!(deposit.outpoint(), req0.outpoint);
     }
 
+    /// Test that `BlockObserver::load_requests` validates deposit requests
+    /// concurrently, and that it stores the same deposit requests as when
+    /// they are validated one at a time.
+    #[tokio::test]
+    async fn deposit_requests_are_validated_concurrently() {
+        let mut rng = get_rng();
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+
+        let latency = Duration::from_millis(100);
+        test_harness.set_get_tx_latency(latency);
+
+        let num_requests = 10;
+        let requests: Vec<CreateDepositRequest> = (0..num_requests)
+            .map(|index| {
+                let tx_setup = sbtc::testing::deposits::tx_setup(150 + index, 32_000, &[500_000]);
+                let response = GetTxResponse {
+                    tx: tx_setup.tx.clone(),
+                    block_hash,
+                    confirmations: None,
+                    block_time: None,
+                };
+                test_harness.add_deposit(tx_setup.tx.compute_txid(), response);
+                CreateDepositRequest {
+                    outpoint: bitcoin::OutPoint {
+                        txid: tx_setup.tx.compute_txid(),
+                        vout: 0,
+                    },
+                    deposit_script: tx_setup.deposits.first().unwrap().deposit_script(),
+                    reclaim_script: tx_setup.reclaims.first().unwrap().reclaim_script(),
+                }
+            })
+            .collect();
+        let min_height = test_harness.min_block_height();
+
+        let mut stored_requests = Vec::new();
+        let mut elapsed = Vec::new();
+
+        for concurrency in [1, num_requests as u16] {
+            let storage = storage::in_memory::Store::new_shared();
+            let ctx = TestContext::builder()
+                .with_storage(storage.clone())
+                .with_stacks_client(test_harness.clone())
+                .with_emily_client(test_harness.clone())
+                .with_bitcoin_client(test_harness.clone())
+                .modify_settings(|settings| {
+                    settings.signer.sbtc_bitcoin_start_height = min_height.map(Into::into);
+                    settings.signer.deposit_validation_concurrency =
+                        NonZeroU16::new(concurrency).unwrap();
+                })
+                .build();
+
+            let block_observer = BlockObserver {
+                context: ctx,
+                bitcoin_blocks: (),
+            };
+
+            let start = std::time::Instant::now();
+            block_observer.load_requests(&requests).await.unwrap();
+            elapsed.push(start.elapsed());
+
+            stored_requests.push(storage.lock().await.deposit_requests.clone());
+        }
+
+        assert_eq!(stored_requests[0].len(), num_requests as usize);
+        assert_eq!(stored_requests[0], stored_requests[1]);
+
+        // Validating the requests one at a time takes at least as long as
+        // all of the `get_tx` calls combined, while validating them all at
+        // once should take roughly as long as one of them.
+        assert!(elapsed[0] >= latency * num_requests);
+        assert!(elapsed[1] < latency * num_requests / 2);
+    }
+
+    /// Test that `BlockObserver::load_latest_withdrawal_requests` stores
+    /// the pending withdrawal requests from Emily that were created in a
+    /// stacks block that we know about, and skips the other ones.
+    #[tokio::test]
+    async fn withdrawals_from_known_stacks_blocks_get_added_to_state() {
+        let mut rng = get_rng();
+        let mut ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let storage = ctx.inner_storage();
+
+        let bitcoin_block: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        let stacks_block = model::StacksBlock::new_genesis().anchored_to(&bitcoin_block);
+        storage.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        storage.write_stacks_block(&stacks_block).await.unwrap();
+        let unknown_block_hash: model::StacksBlockHash = fake::Faker.fake_with_rng(&mut rng);
+
+        let recipient = "0014abababababababababababababababababababab".to_string();
+        let sender = "ST000000000000000000002AMW42H".to_string();
+        let withdrawal = |request_id: u64, block_hash: model::StacksBlockHash| Withdrawal {
+            amount: 10_000,
+            fulfillment: None,
+            last_update_block_hash: block_hash.to_string(),
+            last_update_height: 1,
+            parameters: Box::new(emily_client::models::WithdrawalParameters { max_fee: 500 }),
+            recipient: recipient.clone(),
+            request_id,
+            sender: sender.clone(),
+            stacks_block_hash: block_hash.to_string(),
+            stacks_block_height: 1,
+            status: Status::Pending,
+            status_message: String::new(),
+            txid: "11".repeat(32),
+        };
+        let known = withdrawal(1, stacks_block.block_hash);
+        let unknown = withdrawal(2, unknown_block_hash);
+        let infos: Vec<_> = [&known, &unknown]
+            .into_iter()
+            .map(|w| emily_client::models::WithdrawalInfo {
+                amount: w.amount,
+                last_update_block_hash: w.last_update_block_hash.clone(),
+                last_update_height: w.last_update_height,
+                recipient: w.recipient.clone(),
+                request_id: w.request_id,
+                sender: w.sender.clone(),
+                stacks_block_hash: w.stacks_block_hash.clone(),
+                stacks_block_height: w.stacks_block_height,
+                status: w.status,
+                txid: w.txid.clone(),
+            })
+            .collect();
+
+        ctx.with_emily_client(|client| {
+            client
+                .expect_get_withdrawals()
+                .times(1)
+                .returning(move |_| Box::pin(std::future::ready(Ok(infos.clone()))));
+            // We should only look up the request from the known block.
+            client
+                .expect_get_withdrawal()
+                .withf(|request_id| *request_id == 1)
+                .times(1)
+                .returning(move |_| Box::pin(std::future::ready(Ok(Some(known.clone())))));
+        })
+        .await;
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+        block_observer
+            .load_latest_withdrawal_requests()
+            .await
+            .unwrap();
+
+        let db = storage.lock().await;
+        assert_eq!(db.withdrawal_requests.len(), 1);
+        let stored = db.withdrawal_requests.values().next().unwrap();
+        assert_eq!(stored.request_id, 1);
+        assert_eq!(stored.block_hash, stacks_block.block_hash);
+        assert_eq!(stored.max_fee, 500);
+        assert_eq!(stored.bitcoin_block_height, bitcoin_block.block_height);
+        assert_eq!(stored.recipient.to_hex_string(), recipient);
+        assert_eq!(stored.sender_address.to_string(), sender);
+    }
+
+    /// Test that `BlockObserver::load_latest_deposit_requests` skips
+    /// deposits whose amount is below the configured minimum deposit
+    /// amount, while keeping the ones at exactly the minimum.
+    #[tokio::test]
+    async fn deposits_below_min_deposit_amount_are_skipped() {
+        let mut rng = get_rng();
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+
+        let min_deposit_amount = 100_000;
+        let mut deposit_requests = Vec::new();
+        let mut get_tx_responses = Vec::new();
+
+        for amount in [min_deposit_amount - 1, min_deposit_amount] {
+            let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, &[amount]);
+            deposit_requests.push(CreateDepositRequest {
+                outpoint: bitcoin::OutPoint {
+                    txid: tx_setup.tx.compute_txid(),
+                    vout: 0,
+                },
+                deposit_script: tx_setup.deposits.first().unwrap().deposit_script(),
+                reclaim_script: tx_setup.reclaims.first().unwrap().reclaim_script(),
+            });
+            let get_tx_resp = GetTxResponse {
+                tx: tx_setup.tx.clone(),
+                block_hash,
+                confirmations: None,
+                block_time: None,
+            };
+            get_tx_responses.push((tx_setup.tx.compute_txid(), get_tx_resp));
+        }
+
+        test_harness.add_deposits(&get_tx_responses);
+        test_harness.add_pending_deposits(&deposit_requests);
+        let min_height = test_harness.min_block_height();
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| {
+                settings.signer.sbtc_bitcoin_start_height = min_height.map(Into::into);
+                settings.signer.min_deposit_amount = min_deposit_amount;
+            })
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        // Only the deposit at exactly the minimum amount should be kept.
+        let deposit = {
+            let db = storage.lock().await;
+            assert_eq!(db.deposit_requests.len(), 1);
+            db.deposit_requests.values().next().cloned().unwrap()
+        };
+
+        assert_eq!(deposit.outpoint(), deposit_requests[1].outpoint);
+        assert_eq!(deposit.amount, min_deposit_amount);
+    }
+
     /// Test that `BlockObserver::extract_deposit_requests` after
     /// `BlockObserver::load_latest_deposit_requests` stores validated
     /// deposit requests into "storage".
@@ -1138,4 +1998,78 @@ mod tests {
         assert_eq!(tx_ids.len(), 1);
         assert_eq!(tx_ids[0], expected_tx_id);
     }
+
+    /// Test that `BlockObserver::extract_sbtc_transactions` recognizes
+    /// outputs locked by an aggregate key from a rotate-keys transaction
+    /// even when this signer does not have DKG shares for that key. This
+    /// happens to signers that join the signer set after a key rotation,
+    /// while the signers' UTXO is still locked by the previous key.
+    #[tokio::test]
+    async fn sbtc_transactions_locked_by_rotated_keys_get_stored() {
+        let mut rng = get_rng();
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+
+        let storage = storage::in_memory::Store::new_shared();
+
+        let bitcoin_block = model::BitcoinBlock {
+            block_hash: BlockHash::from_byte_array([2u8; 32]).into(),
+            block_height: 1u64.into(),
+            parent_hash: BlockHash::from_byte_array([1u8; 32]).into(),
+        };
+        let block_hash = bitcoin::BlockHash::from(bitcoin_block.block_hash);
+        let stacks_block = model::StacksBlock::new_genesis().anchored_to(&bitcoin_block);
+        storage.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        storage.write_stacks_block(&stacks_block).await.unwrap();
+
+        // The previous signer set rotated into a new aggregate key, but
+        // the signers' UTXO is still locked by the old one.
+        let old_aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let key_rotation = model::KeyRotationEvent {
+            block_hash: stacks_block.block_hash,
+            aggregate_key: old_aggregate_key,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        storage
+            .write_rotate_keys_transaction(&key_rotation)
+            .await
+            .unwrap();
+
+        let mut tx_setup = sbtc::testing::deposits::tx_setup(0, 0, &[100]);
+        tx_setup.tx.output.push(TxOut {
+            value: Amount::ONE_BTC,
+            script_pubkey: old_aggregate_key.signers_script_pubkey(),
+        });
+        let response = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(tx_setup.tx.compute_txid(), response);
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+        };
+
+        let txs = [tx_setup.tx.clone()];
+        block_observer
+            .extract_sbtc_transactions(block_hash, &txs)
+            .await
+            .unwrap();
+
+        let store = storage.lock().await;
+        let tx_ids = store
+            .bitcoin_block_to_transactions
+            .get(&block_hash.into())
+            .unwrap();
+        assert_eq!(tx_ids, &vec![tx_setup.tx.compute_txid().into()]);
+    }
 }
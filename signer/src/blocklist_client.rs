@@ -23,6 +23,7 @@ pub enum BlocklistClientError {
 }
 
 /// A trait for checking if an address is blocklisted.
+#[cfg_attr(any(test, feature = "testing"), mockall::automock())]
 pub trait BlocklistChecker {
     /// Checks if the given address is blocklisted.
     /// Returns `true` if the address is blocklisted, otherwise `false`.
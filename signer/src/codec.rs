@@ -178,6 +178,8 @@ mod tests {
     use crate::keys::PublicKey;
     use crate::message::BitcoinPreSignAck;
     use crate::message::BitcoinPreSignRequest;
+    use crate::message::BitcoinTxRejection;
+    use crate::message::RoundAbort;
     use crate::message::SignerDepositDecision;
     use crate::message::SignerMessage;
     use crate::message::SignerWithdrawalDecision;
@@ -243,6 +245,9 @@ mod tests {
     #[test_case(PhantomData::<(Fees, proto::Fees)>; "Fees")]
     #[test_case(PhantomData::<(BitcoinPreSignRequest, proto::BitcoinPreSignRequest)>; "BitcoinPreSignRequest")]
     #[test_case(PhantomData::<(BitcoinPreSignAck, proto::BitcoinPreSignAck)>; "BitcoinPreSignAck")]
+    #[test_case(PhantomData::<(BitcoinTxRejection, proto::BitcoinTxRejection)>; "BitcoinTxRejection")]
+    #[test_case(PhantomData::<(BitcoinValidationFailure, proto::BitcoinValidationFailure)>; "BitcoinValidationFailure")]
+    #[test_case(PhantomData::<(RoundAbort, proto::RoundAbort)>; "RoundAbort")]
     fn sbtc_protobuf_message_codec_tag_order<T, U, E>(_: PhantomData<(T, U)>)
     where
         // `.unwrap()` requires that `E` implement `std::fmt::Debug` and
@@ -368,6 +373,9 @@ mod tests {
     #[test_case(PhantomData::<proto::Fees>; "Fees")]
     #[test_case(PhantomData::<proto::BitcoinPreSignRequest>; "BitcoinPreSignRequest")]
     #[test_case(PhantomData::<proto::BitcoinPreSignAck>; "BitcoinPreSignAck")]
+    #[test_case(PhantomData::<proto::BitcoinTxRejection>; "BitcoinTxRejection")]
+    #[test_case(PhantomData::<proto::BitcoinValidationFailure>; "BitcoinValidationFailure")]
+    #[test_case(PhantomData::<proto::RoundAbort>; "RoundAbort")]
     #[test_case(PhantomData::<proto::OutPoint>; "OutPoint")]
     #[test_case(PhantomData::<proto::RecoverableSignature>; "RecoverableSignature")]
     #[test_case(PhantomData::<proto::EcdsaSignature>; "EcdsaSignature")]
@@ -3,6 +3,10 @@ use libp2p::Multiaddr;
 /// Configuration error variants.
 #[derive(Debug, thiserror::Error)]
 pub enum SignerConfigError {
+    /// The configuration file does not exist
+    #[error("The configuration file {0} does not exist")]
+    ConfigFileNotFound(std::path::PathBuf),
+
     /// Invalid Stacks private key length
     #[error(
         "The Stacks private key provided is invalid, it must be either 64 or 66 hex characters long, got {0}"
@@ -81,4 +85,13 @@ pub enum SignerConfigError {
     /// An error returned for duration parameters that must be positive.
     #[error("Duration for {0} must be nonzero")]
     ZeroDurationForbidden(&'static str),
+
+    /// An error for a storage retention window that is smaller than the
+    /// context window.
+    #[error("The storage retention blocks must be at least the context window of {0}, got {1}")]
+    RetentionBlocksBelowContextWindow(u16, u32),
+
+    /// An error for a webhook secret that is set but empty.
+    #[error("The event observer webhook secret must not be empty when it is set")]
+    EmptyWebhookSecret,
 }
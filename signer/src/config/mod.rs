@@ -3,6 +3,7 @@ use config::Config;
 use config::ConfigError;
 use config::Environment;
 use config::File;
+use config::FileFormat;
 use libp2p::Multiaddr;
 use libp2p::multiaddr::Protocol;
 use serde::Deserialize;
@@ -11,10 +12,13 @@ use std::collections::BTreeSet;
 use std::num::NonZeroU16;
 use std::num::NonZeroU32;
 use std::num::NonZeroU64;
+use std::num::NonZeroUsize;
 use std::path::Path;
+use std::path::PathBuf;
 use url::Url;
 
 use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
+use crate::DEFAULT_MIN_DEPOSIT_AMOUNT;
 use crate::config::error::SignerConfigError;
 use crate::config::serialization::duration_milliseconds_deserializer;
 use crate::config::serialization::duration_seconds_deserializer;
@@ -30,8 +34,20 @@ use crate::stacks::wallet::SignerWallet;
 use crate::storage::model::BitcoinBlockHeight;
 
 mod error;
+mod reload;
 mod serialization;
 
+pub use reload::*;
+
+/// The environment variable with the path to the configuration file, used
+/// when no path is given on the command line.
+pub const CONFIG_PATH_ENV_VAR: &str = "SIGNER_CONFIG_PATH";
+
+/// The default configuration file, embedded in the binary. It is used when
+/// no configuration file is given, so that the signer doesn't depend on
+/// the directory that it is run from.
+pub const DEFAULT_CONFIG_TOML: &str = include_str!("default.toml");
+
 /// Maximum configurable delay (in seconds) before processing new Bitcoin blocks.
 pub const MAX_BITCOIN_PROCESSING_DELAY_SECONDS: u64 = 300;
 
@@ -119,16 +135,145 @@ pub struct Settings {
     pub emily: EmilyClientConfig,
 }
 
-/// Configuration used for the [`BitcoinCoreClient`](sbtc::rpc::BitcoinCoreClient).
+/// Configuration for connecting to bitcoin-core, used for the
+/// [`BitcoinCoreClient`](crate::bitcoin::rpc::BitcoinCoreClient) and the
+/// [`BitcoinCoreMessageStream`](crate::bitcoin::zmq::BitcoinCoreMessageStream).
 #[derive(Deserialize, Clone, Debug)]
 pub struct BitcoinConfig {
     /// Bitcoin RPC endpoints.
     #[serde(deserialize_with = "url_deserializer_vec")]
     pub rpc_endpoints: Vec<Url>,
 
+    /// The username used to authenticate with the RPC endpoints that do
+    /// not include credentials in their URL. Must be set together with
+    /// `rpc_password`.
+    #[serde(default)]
+    pub rpc_username: Option<String>,
+
+    /// The password used to authenticate with the RPC endpoints that do
+    /// not include credentials in their URL. Must be set together with
+    /// `rpc_username`.
+    #[serde(default)]
+    pub rpc_password: Option<String>,
+
+    /// The path to the cookie file of bitcoin-core, used to authenticate
+    /// with the RPC endpoints that do not include credentials in their
+    /// URL. Cannot be set together with `rpc_username` and `rpc_password`.
+    #[serde(default)]
+    pub rpc_cookie_file: Option<PathBuf>,
+
     /// Bitcoin ZeroMQ block-hash stream endpoint.
     #[serde(deserialize_with = "url_deserializer_vec")]
     pub block_hash_stream_endpoints: Vec<Url>,
+
+    /// Bitcoin ZeroMQ raw block stream endpoints. When set, the block
+    /// observer receives full blocks over ZeroMQ instead of fetching them
+    /// over RPC after receiving their block hash.
+    #[serde(default, deserialize_with = "url_deserializer_vec")]
+    pub block_stream_endpoints: Vec<Url>,
+
+    /// How long the block observer waits for a notification over ZeroMQ
+    /// before polling bitcoin-core over RPC for blocks that it may have
+    /// missed.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub poll_fallback_interval: std::time::Duration,
+}
+
+impl BitcoinConfig {
+    /// The credentials used to authenticate with the given RPC endpoint.
+    /// Credentials in the URL of the endpoint take precedence over the
+    /// `rpc_username`/`rpc_password` and `rpc_cookie_file` settings.
+    pub fn rpc_auth(&self, endpoint: &Url) -> bitcoincore_rpc::Auth {
+        if !endpoint.username().is_empty() {
+            let username = endpoint.username().to_string();
+            let password = endpoint.password().unwrap_or_default().to_string();
+            return bitcoincore_rpc::Auth::UserPass(username, password);
+        }
+
+        match (
+            &self.rpc_username,
+            &self.rpc_password,
+            &self.rpc_cookie_file,
+        ) {
+            (Some(username), Some(password), _) => {
+                bitcoincore_rpc::Auth::UserPass(username.clone(), password.clone())
+            }
+            (_, _, Some(cookie_file)) => bitcoincore_rpc::Auth::CookieFile(cookie_file.clone()),
+            _ => bitcoincore_rpc::Auth::None,
+        }
+    }
+}
+
+impl Validatable for BitcoinConfig {
+    fn validate(&self, _: &Settings) -> Result<(), ConfigError> {
+        let has_user_pass = match (&self.rpc_username, &self.rpc_password) {
+            (Some(_), Some(_)) => true,
+            (None, None) => false,
+            _ => {
+                return Err(ConfigError::Message(
+                    "[bitcoin] rpc_username and rpc_password must be set together".to_string(),
+                ));
+            }
+        };
+        if has_user_pass && self.rpc_cookie_file.is_some() {
+            return Err(ConfigError::Message(
+                "[bitcoin] rpc_cookie_file cannot be set together with rpc_username and rpc_password"
+                    .to_string(),
+            ));
+        }
+        let has_credentials = has_user_pass || self.rpc_cookie_file.is_some();
+
+        if self.rpc_endpoints.is_empty() {
+            return Err(ConfigError::Message(
+                "[bitcoin] At least one RPC endpoint must be provided".to_string(),
+            ));
+        }
+        for endpoint in &self.rpc_endpoints {
+            if !["http", "https"].contains(&endpoint.scheme()) {
+                return Err(ConfigError::Message(
+                    "[bitcoin.rpc_endpoints] Invalid URL scheme: must be HTTP or HTTPS".to_string(),
+                ));
+            }
+            if endpoint.host_str().is_none() || endpoint.port().is_none() {
+                return Err(ConfigError::Message(
+                    "[bitcoin.rpc_endpoints] Invalid URL: host and port are required".to_string(),
+                ));
+            }
+            if endpoint.username().is_empty() && !has_credentials {
+                return Err(ConfigError::Message(format!(
+                    "[bitcoin.rpc_endpoints] No credentials for {}:{}; set them in the URL, \
+                    with rpc_username and rpc_password, or with rpc_cookie_file",
+                    endpoint.host_str().unwrap_or_default(),
+                    endpoint.port().unwrap_or_default(),
+                )));
+            }
+        }
+
+        if self.block_hash_stream_endpoints.is_empty() {
+            return Err(ConfigError::Message(
+                "[bitcoin] At least one block hash stream endpoint must be provided".to_string(),
+            ));
+        }
+        let zmq_endpoints = self
+            .block_hash_stream_endpoints
+            .iter()
+            .chain(&self.block_stream_endpoints);
+        for endpoint in zmq_endpoints {
+            if !["tcp", "ipc"].contains(&endpoint.scheme()) {
+                return Err(ConfigError::Message(
+                    "[bitcoin] Invalid ZeroMQ URL scheme: must be TCP or IPC".to_string(),
+                ));
+            }
+        }
+
+        if self.poll_fallback_interval.is_zero() {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("poll_fallback_interval").to_string(),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 /// Signer network configuration
@@ -148,25 +293,54 @@ pub struct P2PNetworkConfig {
     /// public endpoint(s).
     #[serde(deserialize_with = "p2p_multiaddr_deserializer_vec")]
     pub public_endpoints: Vec<Multiaddr>,
+    /// Relay servers that the signer reserves a slot on, so that peers
+    /// that cannot dial the signer directly (e.g. because it is behind a
+    /// NAT) can still reach it through the relay.
+    #[serde(default, deserialize_with = "p2p_multiaddr_deserializer_vec")]
+    pub relay_servers: Vec<Multiaddr>,
     /// Enable mDNS discovery for the P2P network. This is useful for local
     /// testing and development.
     #[serde(default)]
     pub enable_mdns: bool,
+    /// The amount of time that a peer is banned for after misbehaving on
+    /// the P2P network. Once the ban expires the peer may reconnect.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub peer_ban_duration: std::time::Duration,
+    /// The amount of time that the address of a peer that we connected to
+    /// is remembered for. On startup the signer dials the addresses of
+    /// the peers that it has connected to within this period, in addition
+    /// to the seeds.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub peer_address_max_age: std::time::Duration,
 }
 
 impl P2PNetworkConfig {
     /// Returns whether QUIC is used in the P2P network configuration, i.e. if
-    /// any of the seeds or listen_on addresses use the QUIC protocol.
+    /// any of the seeds, listen_on addresses or relay servers use the QUIC
+    /// protocol.
     pub fn is_quic_used(&self) -> bool {
         self.listen_on
             .iter()
             .chain(self.seeds.iter())
+            .chain(self.relay_servers.iter())
             .any(|addr| addr.is_quic())
     }
 }
 
 impl Validatable for P2PNetworkConfig {
     fn validate(&self, cfg: &Settings) -> Result<(), ConfigError> {
+        if self.peer_ban_duration.is_zero() {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("p2p.peer_ban_duration").to_string(),
+            ));
+        }
+
+        if self.peer_address_max_age.is_zero() {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("p2p.peer_address_max_age").to_string(),
+            ));
+        }
+
         if [NetworkKind::Mainnet, NetworkKind::Testnet].contains(&cfg.signer.network)
             && self.seeds.is_empty()
         {
@@ -222,6 +396,26 @@ pub struct BlocklistClientConfig {
         deserialize_with = "duration_milliseconds_deserializer"
     )]
     pub retry_delay: std::time::Duration,
+
+    /// What to do with a request when the blocklist client cannot be
+    /// reached.
+    #[serde(default)]
+    pub failure_policy: BlocklistFailurePolicy,
+}
+
+/// How the signer decides on a request when screening it with the
+/// blocklist client fails.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlocklistFailurePolicy {
+    /// Do not make a decision on the request, and try screening it again
+    /// when the next bitcoin block is observed.
+    #[default]
+    Retry,
+    /// Accept the request as if it had passed screening.
+    FailOpen,
+    /// Reject the request as if it had been flagged during screening.
+    FailClosed,
 }
 
 impl BlocklistClientConfig {
@@ -238,6 +432,12 @@ pub struct EmilyClientConfig {
     /// Pagination timeout in seconds.
     #[serde(deserialize_with = "duration_seconds_deserializer")]
     pub pagination_timeout: std::time::Duration,
+    /// The maximum number of updates sent to Emily in a single update
+    /// deposits or update withdrawals request.
+    pub max_updates_per_request: NonZeroUsize,
+    /// The maximum size, in bytes, of the body of a single update deposits
+    /// or update withdrawals request.
+    pub max_update_request_bytes: NonZeroUsize,
 }
 
 impl Validatable for EmilyClientConfig {
@@ -325,6 +525,19 @@ pub struct SignerConfig {
     /// coordinator will time out and return an error.
     #[serde(deserialize_with = "duration_seconds_deserializer")]
     pub dkg_max_duration: std::time::Duration,
+    /// The maximum amount of time to wait, once a shutdown has been
+    /// signalled, for the long-running components of the signer to finish
+    /// their current unit of work. Components that are still running after
+    /// this deadline are abandoned.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub shutdown_max_duration: std::time::Duration,
+    /// How long the signer treats a sweep transaction that it agreed to
+    /// sign as in-flight. While a transaction spending the signers' UTXO
+    /// is in-flight, the signer rejects proposals for different
+    /// transactions spending that UTXO, until either the bitcoin chain tip
+    /// changes or this much time has passed.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub in_flight_signer_utxo_timeout: std::time::Duration,
     /// The amount of time, in seconds, the signer should pause for after
     /// receiving a DKG begin message before relaying to give the other
     /// signers time to catch up.
@@ -332,6 +545,9 @@ pub struct SignerConfig {
     /// The minimum bitcoin block height for which the sbtc signers will
     /// backfill bitcoin blocks to.
     pub sbtc_bitcoin_start_height: Option<BitcoinBlockHeight>,
+    /// The maximum number of bitcoin blocks that the block observer will
+    /// fetch when catching up to bitcoin-core's chain tip on startup.
+    pub bitcoin_catch_up_max_depth: NonZeroU32,
     /// The maximum number of deposit inputs that will be included in a
     /// single bitcoin transaction. Transactions must be constructed within
     /// a tenure of a bitcoin block, and higher values here imply lower
@@ -339,11 +555,22 @@ pub struct SignerConfig {
     /// arrives. The default here is controlled by the
     /// [`MAX_DEPOSITS_PER_BITCOIN_TX`] constant
     pub max_deposits_per_bitcoin_tx: NonZeroU16,
+    /// The maximum number of requests, deposits and withdrawals combined,
+    /// that will be included in a single bitcoin transaction. If this is
+    /// not set then there is no limit beyond the other packaging
+    /// constraints.
+    pub max_requests_per_bitcoin_tx: Option<NonZeroU16>,
     /// Configures a DKG re-run Bitcoin block height. If this is set and DKG has
     /// already been run, the coordinator will attempt to re-run DKG after this
     /// block height is met if `dkg_target_rounds` has not been reached. If DKG
     /// has never been run, this configuration has no effect.
     pub dkg_min_bitcoin_block_height: Option<BitcoinBlockHeight>,
+    /// The bitcoin block height from which the coordinator is elected
+    /// using [`crate::coordinator::elect`]. All signers must use the same
+    /// value, and if this is not set then the legacy election rule is used
+    /// for every block. See the [`crate::coordinator`] module for how to
+    /// roll out the new rule.
+    pub coordinator_election_activation_height: Option<BitcoinBlockHeight>,
     /// Configures a target number of DKG rounds to run/accept. If this is set
     /// and the number of DKG shares is less than this number, the coordinator
     /// will continue to run DKG rounds until this number of rounds is reached,
@@ -355,6 +582,30 @@ pub struct SignerConfig {
     pub dkg_verification_window: u16,
     /// The maximum stacks fee in microSTX that the signer will accept for any stacks transaction.
     pub stacks_fees_max_ustx: NonZeroU64,
+    /// The maximum fee rate, in sats per virtual byte, that the signer
+    /// will accept for any bitcoin sweep transaction.
+    pub bitcoin_fee_rate_max: NonZeroU64,
+    /// The maximum fee rate that the signer will accept for any bitcoin
+    /// sweep transaction, as a multiple of the market fee rate that the
+    /// signer estimates. The lower of this and `bitcoin_fee_rate_max`
+    /// applies.
+    pub bitcoin_fee_rate_market_multiple: NonZeroU16,
+    /// The minimum amount, in sats, that a deposit must have for the
+    /// signer to accept it. This is applied on top of the per-deposit
+    /// minimum set in Emily.
+    pub min_deposit_amount: u64,
+    /// The number of signers, including this one, that must have voted
+    /// against a deposit request before this signer marks it as failed in
+    /// Emily. The signer only does so for deposit requests that can never
+    /// be accepted, like ones with an unsupported lock-time. If this is
+    /// not set then the signer never marks deposit requests as failed.
+    pub deposit_decline_quorum: Option<NonZeroU16>,
+    /// The maximum number of deposit requests fetched from Emily that the
+    /// block observer validates concurrently.
+    pub deposit_validation_concurrency: NonZeroU16,
+    /// Storage configuration
+    #[serde(default)]
+    pub storage: StorageConfig,
 }
 
 impl Validatable for SignerConfig {
@@ -421,6 +672,43 @@ impl Validatable for SignerConfig {
                 SignerConfigError::ZeroDurationForbidden("signer_round_max_duration").to_string(),
             ));
         }
+        if cfg.signer.shutdown_max_duration == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("shutdown_max_duration").to_string(),
+            ));
+        }
+        if cfg.signer.in_flight_signer_utxo_timeout == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("in_flight_signer_utxo_timeout")
+                    .to_string(),
+            ));
+        }
+        let context_window = u32::from(cfg.signer.context_window);
+        if let Some(retention_blocks) = cfg
+            .signer
+            .storage
+            .retention_blocks
+            .filter(|blocks| *blocks < context_window)
+        {
+            return Err(ConfigError::Message(
+                SignerConfigError::RetentionBlocksBelowContextWindow(
+                    cfg.signer.context_window,
+                    retention_blocks,
+                )
+                .to_string(),
+            ));
+        }
+        if cfg
+            .signer
+            .event_observer
+            .webhook_secret
+            .as_ref()
+            .is_some_and(String::is_empty)
+        {
+            return Err(ConfigError::Message(
+                SignerConfigError::EmptyWebhookSecret.to_string(),
+            ));
+        }
         // db_endpoint note: we don't validate the host because we will never
         // get here; the URL deserializer will fail if the host is empty.
         Ok(())
@@ -447,11 +735,36 @@ impl SignerConfig {
     }
 }
 
+/// Configuration for the signer's database.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StorageConfig {
+    /// The number of bitcoin blocks, counting back from the chain tip,
+    /// for which settled requests and stale forks are kept in the
+    /// database. Anything older is pruned after each new bitcoin block.
+    /// Pruning is disabled when this is not set.
+    pub retention_blocks: Option<u32>,
+}
+
 /// Configuration for the Stacks event observer server (hosted within the signer).
 #[derive(Debug, Clone, Deserialize)]
 pub struct EventObserverConfig {
     /// The address and port to bind the server to.
     pub bind: std::net::SocketAddr,
+    /// The number of times we fail to process a `new_block` webhook
+    /// before giving up on it, so that the stacks node stops retrying it.
+    pub max_webhook_attempts: NonZeroU32,
+    /// Whether to serve the dry-run endpoint for validating proposed
+    /// sweep transactions.
+    pub enable_validate_sweep: bool,
+    /// The shared secret that the stacks node sends in the
+    /// `Authorization` header of its webhooks. When set, `new_block`
+    /// webhooks without it are rejected.
+    pub webhook_secret: Option<String>,
+    /// The IP addresses that `new_block` webhooks are accepted from. This
+    /// is for stacks nodes that cannot send the webhook secret, and
+    /// webhooks are accepted from any address when it is empty.
+    #[serde(default)]
+    pub allowed_webhook_sources: Vec<std::net::IpAddr>,
 }
 
 impl Settings {
@@ -476,7 +789,54 @@ impl Settings {
     ///    │  └ prefix_separator("_")
     ///    └ with_prefix("SIGNER")
     /// ```
+    ///
+    /// The configuration file is read from `config_path` if it is given,
+    /// otherwise from the path in the [`CONFIG_PATH_ENV_VAR`] environment
+    /// variable if it is set, and otherwise the embedded
+    /// [`DEFAULT_CONFIG_TOML`] is used.
     pub fn new(config_path: Option<impl AsRef<Path>>) -> Result<Self, ConfigError> {
+        match Self::resolve_config_path(config_path) {
+            Some(path) => Self::new_from_path(path),
+            None => Self::new_from_embedded_default(),
+        }
+    }
+
+    /// The path of the configuration file that [`Settings::new`] reads,
+    /// or `None` if the embedded [`DEFAULT_CONFIG_TOML`] is used.
+    pub fn resolve_config_path(config_path: Option<impl AsRef<Path>>) -> Option<PathBuf> {
+        config_path
+            .map(|path| path.as_ref().to_path_buf())
+            .or_else(|| {
+                std::env::var_os(CONFIG_PATH_ENV_VAR)
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from)
+            })
+    }
+
+    /// Load the settings from the configuration file at the given path,
+    /// with any environment variables taking precedence.
+    pub fn new_from_path(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            let error = SignerConfigError::ConfigFileNotFound(path.to_path_buf());
+            return Err(ConfigError::Message(error.to_string()));
+        }
+
+        Self::build(File::from(path))
+    }
+
+    /// Load the settings from the embedded [`DEFAULT_CONFIG_TOML`], with
+    /// any environment variables taking precedence.
+    pub fn new_from_embedded_default() -> Result<Self, ConfigError> {
+        Self::build(File::from_str(DEFAULT_CONFIG_TOML, FileFormat::Toml))
+    }
+
+    /// Build the settings from the defaults, the given configuration file
+    /// and the environment, in increasing order of precedence.
+    fn build<S>(config_file: S) -> Result<Self, ConfigError>
+    where
+        S: config::Source + Send + Sync + 'static,
+    {
         // To properly parse lists from both environment and config files while
         // using a custom deserializer, we need to specify the list separator,
         // enable try_parsing and specify the keys which should be parsed as lists.
@@ -490,9 +850,12 @@ impl Settings {
             .with_list_parse_key("signer.p2p.seeds")
             .with_list_parse_key("signer.p2p.listen_on")
             .with_list_parse_key("signer.p2p.public_endpoints")
+            .with_list_parse_key("signer.p2p.relay_servers")
             .with_list_parse_key("bitcoin.rpc_endpoints")
             .with_list_parse_key("bitcoin.block_hash_stream_endpoints")
+            .with_list_parse_key("bitcoin.block_stream_endpoints")
             .with_list_parse_key("stacks.endpoints")
+            .with_list_parse_key("signer.event_observer.allowed_webhook_sources")
             .with_list_parse_key("emily.endpoints")
             .prefix_separator("_");
 
@@ -507,18 +870,38 @@ impl Settings {
         cfg_builder = cfg_builder.set_default("signer.dkg_max_duration", 120)?;
         cfg_builder = cfg_builder.set_default("signer.bitcoin_presign_request_max_duration", 30)?;
         cfg_builder = cfg_builder.set_default("signer.signer_round_max_duration", 30)?;
+        cfg_builder = cfg_builder.set_default("signer.shutdown_max_duration", 30)?;
+        cfg_builder = cfg_builder.set_default("signer.in_flight_signer_utxo_timeout", 180)?;
+        cfg_builder = cfg_builder.set_default("signer.p2p.peer_ban_duration", 300)?;
+        cfg_builder = cfg_builder.set_default("signer.p2p.peer_address_max_age", 604800)?;
         cfg_builder = cfg_builder.set_default(
             "signer.max_deposits_per_bitcoin_tx",
             DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
         )?;
         cfg_builder = cfg_builder.set_default("signer.dkg_target_rounds", 1)?;
+        cfg_builder = cfg_builder.set_default("signer.bitcoin_catch_up_max_depth", 2016)?;
+        cfg_builder = cfg_builder.set_default("signer.event_observer.max_webhook_attempts", 10)?;
+        cfg_builder =
+            cfg_builder.set_default("signer.event_observer.enable_validate_sweep", false)?;
         cfg_builder = cfg_builder.set_default("emily.pagination_timeout", 10)?;
+        cfg_builder = cfg_builder.set_default(
+            "emily.max_updates_per_request",
+            crate::emily_client::MAX_UPDATES_PER_REQUEST as u64,
+        )?;
+        cfg_builder = cfg_builder.set_default(
+            "emily.max_update_request_bytes",
+            crate::emily_client::MAX_UPDATE_REQUEST_BYTES as u64,
+        )?;
+        cfg_builder = cfg_builder.set_default("bitcoin.poll_fallback_interval", 60)?;
         cfg_builder = cfg_builder.set_default("signer.dkg_verification_window", 10)?;
         cfg_builder = cfg_builder.set_default("signer.stacks_fees_max_ustx", 1_500_000)?;
+        cfg_builder = cfg_builder.set_default("signer.bitcoin_fee_rate_max", 1_000)?;
+        cfg_builder = cfg_builder.set_default("signer.bitcoin_fee_rate_market_multiple", 10)?;
+        cfg_builder =
+            cfg_builder.set_default("signer.min_deposit_amount", DEFAULT_MIN_DEPOSIT_AMOUNT)?;
+        cfg_builder = cfg_builder.set_default("signer.deposit_validation_concurrency", 10)?;
 
-        if let Some(path) = config_path {
-            cfg_builder = cfg_builder.add_source(File::from(path.as_ref()));
-        }
+        cfg_builder = cfg_builder.add_source(config_file);
         cfg_builder = cfg_builder.add_source(env);
 
         let cfg = cfg_builder.build()?;
@@ -533,6 +916,7 @@ impl Settings {
     /// Perform validation on the configuration.
     fn validate(&self) -> Result<(), ConfigError> {
         self.signer.validate(self)?;
+        self.bitcoin.validate(self)?;
         self.stacks.validate(self)?;
         self.emily.validate(self)?;
 
@@ -610,6 +994,7 @@ mod tests {
         assert_eq!(settings.signer.network, NetworkKind::Regtest);
 
         assert_eq!(settings.signer.p2p.seeds, vec![]);
+        assert_eq!(settings.signer.p2p.relay_servers, vec![]);
         assert_eq!(
             settings.signer.p2p.listen_on,
             vec![
@@ -617,6 +1002,14 @@ mod tests {
                 multiaddr("quic-v1://0.0.0.0:4122")
             ]
         );
+        assert_eq!(
+            settings.signer.p2p.peer_ban_duration,
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            settings.signer.p2p.peer_address_max_age,
+            Duration::from_secs(604800)
+        );
 
         assert_eq!(
             settings.bitcoin.rpc_endpoints,
@@ -624,6 +1017,13 @@ mod tests {
         );
         assert_eq!(settings.bitcoin.rpc_endpoints[0].username(), "devnet");
         assert_eq!(settings.bitcoin.rpc_endpoints[0].password(), Some("devnet"));
+        assert!(settings.bitcoin.rpc_username.is_none());
+        assert!(settings.bitcoin.rpc_password.is_none());
+        assert!(settings.bitcoin.rpc_cookie_file.is_none());
+        assert_eq!(
+            settings.bitcoin.poll_fallback_interval,
+            Duration::from_secs(60)
+        );
         assert_eq!(
             settings.signer.event_observer.bind,
             "0.0.0.0:8801".parse::<SocketAddr>().unwrap()
@@ -659,6 +1059,14 @@ mod tests {
         assert_eq!(settings.signer.dkg_verification_window, 10);
         assert_eq!(settings.signer.dkg_min_bitcoin_block_height, None);
         assert_eq!(settings.emily.pagination_timeout, Duration::from_secs(10));
+        assert_eq!(
+            settings.emily.max_updates_per_request,
+            NonZeroUsize::new(100).unwrap()
+        );
+        assert_eq!(
+            settings.emily.max_update_request_bytes,
+            NonZeroUsize::new(5_000_000).unwrap()
+        );
     }
 
     #[test]
@@ -669,6 +1077,7 @@ mod tests {
         set_var("SIGNER_SIGNER__BITCOIN_PRESIGN_REQUEST_MAX_DURATION", "60");
         set_var("SIGNER_SIGNER__SIGNER_ROUND_MAX_DURATION", "70");
         set_var("SIGNER_SIGNER__DKG_MAX_DURATION", "80");
+        set_var("SIGNER_SIGNER__SHUTDOWN_MAX_DURATION", "90");
 
         let settings = Settings::new_from_default_config().unwrap();
 
@@ -682,6 +1091,10 @@ mod tests {
             Duration::from_secs(70)
         );
         assert_eq!(settings.signer.dkg_max_duration, Duration::from_secs(80));
+        assert_eq!(
+            settings.signer.shutdown_max_duration,
+            Duration::from_secs(90)
+        );
     }
 
     #[test]
@@ -693,6 +1106,11 @@ mod tests {
             "tcp://seed-1:4122,tcp://seed-2:4122",
         );
         set_var("SIGNER_SIGNER__P2P__LISTEN_ON", "tcp://1.2.3.4:1234");
+        set_var("SIGNER_SIGNER__P2P__PEER_BAN_DURATION", "45");
+        set_var(
+            "SIGNER_SIGNER__P2P__RELAY_SERVERS",
+            "tcp://relay-1:4122,quic-v1://relay-2:4122",
+        );
 
         let settings = Settings::new_from_default_config().unwrap();
 
@@ -707,6 +1125,17 @@ mod tests {
             settings.signer.p2p.listen_on,
             vec![multiaddr("tcp://1.2.3.4:1234")]
         );
+        assert_eq!(
+            settings.signer.p2p.peer_ban_duration,
+            Duration::from_secs(45)
+        );
+        assert_eq!(
+            settings.signer.p2p.relay_servers,
+            vec![
+                multiaddr("tcp://relay-1:4122"),
+                multiaddr("quic-v1://relay-2:4122")
+            ]
+        );
     }
 
     #[test]
@@ -807,6 +1236,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn default_config_toml_loads_coordinator_election_activation_height() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.coordinator_election_activation_height, None);
+
+        set_var(
+            "SIGNER_SIGNER__COORDINATOR_ELECTION_ACTIVATION_HEIGHT",
+            "42",
+        );
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.coordinator_election_activation_height,
+            Some(42u64.into())
+        );
+    }
+
     #[test]
     fn default_config_toml_loads_dkg_target_rounds() {
         clear_env();
@@ -825,6 +1272,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn default_config_toml_loads_max_webhook_attempts() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.event_observer.max_webhook_attempts,
+            NonZeroU32::new(10).unwrap()
+        );
+
+        set_var("SIGNER_SIGNER__EVENT_OBSERVER__MAX_WEBHOOK_ATTEMPTS", "3");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.signer.event_observer.max_webhook_attempts,
+            NonZeroU32::new(3).unwrap()
+        );
+    }
+
+    #[test]
+    fn webhook_authentication_is_disabled_by_default() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.event_observer.webhook_secret.is_none());
+        assert!(
+            settings
+                .signer
+                .event_observer
+                .allowed_webhook_sources
+                .is_empty()
+        );
+
+        set_var("SIGNER_SIGNER__EVENT_OBSERVER__WEBHOOK_SECRET", "hunter2");
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__ALLOWED_WEBHOOK_SOURCES",
+            "10.0.0.5,::1",
+        );
+        let settings = Settings::new_from_default_config().unwrap();
+        let event_observer = &settings.signer.event_observer;
+        assert_eq!(event_observer.webhook_secret.as_deref(), Some("hunter2"));
+        assert_eq!(
+            event_observer.allowed_webhook_sources,
+            vec![
+                "10.0.0.5".parse::<std::net::IpAddr>().unwrap(),
+                "::1".parse().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_sweep_endpoint_is_disabled_by_default() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.event_observer.enable_validate_sweep);
+
+        set_var(
+            "SIGNER_SIGNER__EVENT_OBSERVER__ENABLE_VALIDATE_SWEEP",
+            "true",
+        );
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.event_observer.enable_validate_sweep);
+    }
+
     #[test]
     fn default_config_toml_loads_dkg_verification_window() {
         clear_env();
@@ -970,9 +1481,7 @@ mod tests {
         // this test will actually test nothing, so we need to reset them.
         clear_env();
 
-        let config_file = format!("{}.toml", crate::testing::DEFAULT_CONFIG_PATH.unwrap());
-        let config_str = std::fs::read_to_string(config_file).unwrap();
-        let mut config_toml = config_str.parse::<DocumentMut>().unwrap();
+        let mut config_toml = DEFAULT_CONFIG_TOML.parse::<DocumentMut>().unwrap();
 
         let mut remove_parameter = |config_name: &str, parameter: &str| {
             config_toml
@@ -988,6 +1497,8 @@ mod tests {
         remove_parameter("signer", "signer_round_max_duration");
         remove_parameter("signer", "bitcoin_presign_request_max_duration");
         remove_parameter("signer", "dkg_max_duration");
+        remove_parameter("signer", "shutdown_max_duration");
+        remove_parameter("signer", "in_flight_signer_utxo_timeout");
         remove_parameter("signer", "max_deposits_per_bitcoin_tx");
 
         remove_parameter("emily", "pagination_timeout");
@@ -1010,6 +1521,14 @@ mod tests {
             Duration::from_secs(30)
         );
         assert_eq!(settings.signer.dkg_max_duration, Duration::from_secs(120));
+        assert_eq!(
+            settings.signer.shutdown_max_duration,
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            settings.signer.in_flight_signer_utxo_timeout,
+            Duration::from_secs(180)
+        );
 
         assert_eq!(settings.emily.pagination_timeout, Duration::from_secs(10));
     }
@@ -1031,10 +1550,210 @@ mod tests {
         );
     }
 
+    #[test]
+    fn emily_update_limits_can_be_loaded_from_environment() {
+        clear_env();
+        set_var("SIGNER_EMILY__MAX_UPDATES_PER_REQUEST", "25");
+        set_var("SIGNER_EMILY__MAX_UPDATE_REQUEST_BYTES", "1000000");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            settings.emily.max_updates_per_request,
+            NonZeroUsize::new(25).unwrap()
+        );
+        assert_eq!(
+            settings.emily.max_update_request_bytes,
+            NonZeroUsize::new(1_000_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn bitcoin_fee_rate_max_can_be_loaded_from_environment() {
+        clear_env();
+        let expected_bitcoin_fee_rate_max = NonZeroU64::new(250).unwrap();
+        set_var(
+            "SIGNER_SIGNER__BITCOIN_FEE_RATE_MAX",
+            format!("{expected_bitcoin_fee_rate_max}"),
+        );
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .bitcoin_fee_rate_max,
+            expected_bitcoin_fee_rate_max,
+        );
+    }
+
+    #[test]
+    fn bitcoin_fee_rate_market_multiple_can_be_loaded_from_environment() {
+        clear_env();
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .bitcoin_fee_rate_market_multiple
+                .get(),
+            10,
+        );
+
+        set_var("SIGNER_SIGNER__BITCOIN_FEE_RATE_MARKET_MULTIPLE", "3");
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .bitcoin_fee_rate_market_multiple
+                .get(),
+            3,
+        );
+    }
+
+    #[test]
+    fn deposit_decline_quorum_can_be_loaded_from_environment() {
+        clear_env();
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .deposit_decline_quorum,
+            None,
+        );
+
+        set_var("SIGNER_SIGNER__DEPOSIT_DECLINE_QUORUM", "4");
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .deposit_decline_quorum,
+            NonZeroU16::new(4),
+        );
+    }
+
+    #[test]
+    fn max_requests_per_bitcoin_tx_can_be_loaded_from_environment() {
+        clear_env();
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .max_requests_per_bitcoin_tx,
+            None,
+        );
+
+        set_var("SIGNER_SIGNER__MAX_REQUESTS_PER_BITCOIN_TX", "12");
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .max_requests_per_bitcoin_tx,
+            NonZeroU16::new(12),
+        );
+
+        set_var("SIGNER_SIGNER__MAX_REQUESTS_PER_BITCOIN_TX", "0");
+        assert!(Settings::new_from_default_config().is_err());
+    }
+
+    #[test]
+    fn bitcoin_catch_up_max_depth_can_be_loaded_from_environment() {
+        clear_env();
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .bitcoin_catch_up_max_depth
+                .get(),
+            2016,
+        );
+
+        set_var("SIGNER_SIGNER__BITCOIN_CATCH_UP_MAX_DEPTH", "100");
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .bitcoin_catch_up_max_depth
+                .get(),
+            100,
+        );
+    }
+
+    #[test]
+    fn deposit_validation_concurrency_can_be_loaded_from_environment() {
+        clear_env();
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .deposit_validation_concurrency
+                .get(),
+            10,
+        );
+
+        set_var("SIGNER_SIGNER__DEPOSIT_VALIDATION_CONCURRENCY", "4");
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .deposit_validation_concurrency
+                .get(),
+            4,
+        );
+    }
+
+    #[test]
+    fn min_deposit_amount_can_be_loaded_from_environment() {
+        clear_env();
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .min_deposit_amount,
+            DEFAULT_MIN_DEPOSIT_AMOUNT,
+        );
+
+        set_var("SIGNER_SIGNER__MIN_DEPOSIT_AMOUNT", "10000");
+        assert_eq!(
+            Settings::new_from_default_config()
+                .unwrap()
+                .signer
+                .min_deposit_amount,
+            10_000,
+        );
+    }
+
+    #[test]
+    fn storage_retention_blocks_can_be_loaded_from_environment() {
+        clear_env();
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.storage.retention_blocks, None);
+
+        set_var("SIGNER_SIGNER__STORAGE__RETENTION_BLOCKS", "5000");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.signer.storage.retention_blocks, Some(5000));
+    }
+
+    #[test]
+    fn storage_retention_blocks_below_context_window_returns_correct_error() {
+        clear_env();
+
+        set_var("SIGNER_SIGNER__CONTEXT_WINDOW", "1000");
+        set_var("SIGNER_SIGNER__STORAGE__RETENTION_BLOCKS", "999");
+
+        let settings = Settings::new_from_default_config();
+        assert!(matches!(
+            settings.unwrap_err(),
+            ConfigError::Message(msg) if msg == SignerConfigError::RetentionBlocksBelowContextWindow(1000, 999).to_string()
+        ));
+    }
+
     #[test_case("dkg_max_duration" ; "dkg_max_duration")]
     #[test_case("bitcoin_presign_request_max_duration" ; "bitcoin_presign_request_max_duration")]
     #[test_case("signer_round_max_duration" ; "signer_round_max_duration")]
+    #[test_case("shutdown_max_duration" ; "shutdown_max_duration")]
+    #[test_case("in_flight_signer_utxo_timeout" ; "in_flight_signer_utxo_timeout")]
+    #[test_case("p2p__peer_ban_duration" ; "p2p_peer_ban_duration")]
+    #[test_case("p2p__peer_address_max_age" ; "p2p_peer_address_max_age")]
     #[test_case("stacks_fees_max_ustx" ; "stacks_fees_max_ustx")]
+    #[test_case("bitcoin_fee_rate_max" ; "bitcoin_fee_rate_max")]
+    #[test_case("bitcoin_fee_rate_market_multiple" ; "bitcoin_fee_rate_market_multiple")]
     fn zero_values_for_nonzero_fields_fail_in_signer_config(field: &str) {
         clear_env();
 
@@ -1043,6 +1762,93 @@ mod tests {
         Settings::new_from_default_config().expect_err("value for must be non zero");
     }
 
+    #[test]
+    fn bitcoin_rpc_credentials_can_be_loaded_from_environment() {
+        clear_env();
+
+        set_var(
+            "SIGNER_BITCOIN__RPC_ENDPOINTS",
+            "http://localhost:1234,http://a:b@localhost:5678",
+        );
+        set_var("SIGNER_BITCOIN__RPC_USERNAME", "user");
+        set_var("SIGNER_BITCOIN__RPC_PASSWORD", "pass");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let bitcoin = &settings.bitcoin;
+
+        let auth = bitcoincore_rpc::Auth::UserPass("user".to_string(), "pass".to_string());
+        assert_eq!(bitcoin.rpc_auth(&url("http://localhost:1234")), auth);
+        // Credentials in the URL take precedence.
+        let auth = bitcoincore_rpc::Auth::UserPass("a".to_string(), "b".to_string());
+        assert_eq!(bitcoin.rpc_auth(&url("http://a:b@localhost:5678")), auth);
+    }
+
+    #[test]
+    fn bitcoin_rpc_cookie_file_can_be_loaded_from_config_file() {
+        clear_env();
+
+        let cookie_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(cookie_file.path(), "__cookie__:secret").unwrap();
+
+        let mut config_toml = DEFAULT_CONFIG_TOML.parse::<DocumentMut>().unwrap();
+        let mut endpoints = toml_edit::Array::new();
+        endpoints.push("http://127.0.0.1:18443");
+        config_toml["bitcoin"]["rpc_endpoints"] = toml_edit::value(endpoints);
+        config_toml["bitcoin"]["rpc_cookie_file"] =
+            toml_edit::value(cookie_file.path().to_str().unwrap());
+
+        let config_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(config_file.path(), config_toml.to_string()).unwrap();
+
+        let settings = Settings::new_from_path(config_file.path()).unwrap();
+        let bitcoin = &settings.bitcoin;
+        let endpoint = &bitcoin.rpc_endpoints[0];
+
+        let auth = bitcoincore_rpc::Auth::CookieFile(cookie_file.path().to_path_buf());
+        assert_eq!(bitcoin.rpc_auth(endpoint), auth);
+        // The client reads the credentials from the cookie file when it
+        // is created.
+        crate::bitcoin::rpc::BitcoinCoreClient::new_from_config(bitcoin, endpoint).unwrap();
+    }
+
+    #[test_case(&[("RPC_USERNAME", "user")]; "username without password")]
+    #[test_case(&[("RPC_PASSWORD", "pass")]; "password without username")]
+    #[test_case(&[
+        ("RPC_USERNAME", "user"),
+        ("RPC_PASSWORD", "pass"),
+        ("RPC_COOKIE_FILE", "/tmp/.cookie"),
+    ]; "username and password with cookie file")]
+    #[test_case(&[("RPC_ENDPOINTS", "http://localhost:18443")]; "endpoint without credentials")]
+    #[test_case(&[("RPC_ENDPOINTS", "tcp://a:b@localhost:18443")]; "rpc endpoint with tcp scheme")]
+    #[test_case(&[("RPC_ENDPOINTS", "http://a:b@localhost")]; "rpc endpoint without port")]
+    #[test_case(&[("BLOCK_HASH_STREAM_ENDPOINTS", "http://localhost:28332")]; "zmq endpoint with http scheme")]
+    #[test_case(&[("BLOCK_STREAM_ENDPOINTS", "http://localhost:28332")]; "block stream endpoint with http scheme")]
+    #[test_case(&[("POLL_FALLBACK_INTERVAL", "0")]; "zero poll fallback interval")]
+    fn invalid_bitcoin_config_fails_validation(vars: &[(&str, &str)]) {
+        clear_env();
+
+        for (name, value) in vars {
+            set_var(format!("SIGNER_BITCOIN__{name}"), value);
+        }
+
+        Settings::new_from_default_config().expect_err("bitcoin config must be invalid");
+    }
+
+    #[test]
+    fn bitcoin_config_requires_block_hash_stream_endpoint() {
+        clear_env();
+
+        let mut config_toml = DEFAULT_CONFIG_TOML.parse::<DocumentMut>().unwrap();
+        config_toml["bitcoin"]["block_hash_stream_endpoints"] =
+            toml_edit::value(toml_edit::Array::new());
+
+        let config_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(config_file.path(), config_toml.to_string()).unwrap();
+
+        Settings::new_from_path(config_file.path())
+            .expect_err("at least one block hash stream endpoint is required");
+    }
+
     #[test]
     fn blocklist_client_endpoint() {
         clear_env();
@@ -1055,6 +1861,29 @@ mod tests {
         assert_eq!(actual_endpoint, url::Url::parse(endpoint).unwrap());
     }
 
+    #[test_case(None, BlocklistFailurePolicy::Retry; "default")]
+    #[test_case(Some("retry"), BlocklistFailurePolicy::Retry; "retry")]
+    #[test_case(Some("fail_open"), BlocklistFailurePolicy::FailOpen; "fail-open")]
+    #[test_case(Some("fail_closed"), BlocklistFailurePolicy::FailClosed; "fail-closed")]
+    fn blocklist_client_failure_policy_can_be_loaded_from_environment(
+        value: Option<&str>,
+        expected: BlocklistFailurePolicy,
+    ) {
+        clear_env();
+
+        set_var(
+            "SIGNER_BLOCKLIST_CLIENT__ENDPOINT",
+            "http://127.0.0.1:12345",
+        );
+        if let Some(value) = value {
+            set_var("SIGNER_BLOCKLIST_CLIENT__FAILURE_POLICY", value);
+        }
+        let settings = Settings::new_from_default_config().unwrap();
+
+        let policy = settings.blocklist_client.unwrap().failure_policy;
+        assert_eq!(policy, expected);
+    }
+
     #[test]
     fn invalid_private_key_length_returns_correct_error() {
         clear_env();
@@ -1316,6 +2145,21 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test_case("SIGNER_SIGNER__P2P__LISTEN_ON", "quic-v1://0.0.0.0:4122"; "listen_on")]
+    #[test_case("SIGNER_SIGNER__P2P__SEEDS", "quic-v1://seed-1:4122"; "seeds")]
+    #[test_case("SIGNER_SIGNER__P2P__RELAY_SERVERS", "quic-v1://relay-1:4122"; "relay_servers")]
+    fn p2p_quic_is_used_when_any_address_uses_quic(var: &str, value: &str) {
+        clear_env();
+
+        set_var("SIGNER_SIGNER__P2P__LISTEN_ON", "tcp://0.0.0.0:4122");
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(!settings.signer.p2p.is_quic_used());
+
+        set_var(var, value);
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.signer.p2p.is_quic_used());
+    }
+
     #[test]
     fn p2p_memory_transport_cannot_be_used() {
         clear_env();
@@ -1376,6 +2220,40 @@ mod tests {
         assert!(Settings::new_from_default_config().is_ok());
     }
 
+    #[test_case(NetworkKind::Mainnet, "bc1q"; "mainnet")]
+    #[test_case(NetworkKind::Testnet, "tb1q"; "testnet")]
+    #[test_case(NetworkKind::Regtest, "bcrt1q"; "regtest")]
+    fn p2wpkh_withdrawal_recipients_roundtrip_under_each_network_kind(
+        network: NetworkKind,
+        address_prefix: &str,
+    ) {
+        // The recipient of a withdrawal is a scriptPubKey, here one for a
+        // P2WPKH address that was created on regtest.
+        let public_key = PublicKey::from_private_key(&PrivateKey::new(&mut rand::rngs::OsRng));
+        let public_key = bitcoin::CompressedPublicKey(public_key.into());
+        let regtest_address = bitcoin::Address::p2wpkh(&public_key, bitcoin::KnownHrp::Regtest);
+        let recipient = regtest_address.script_pubkey();
+
+        // The signer turns it into an address for the configured network,
+        // like it does when checking the recipient with the blocklist
+        // client, and that address parses back into the same script.
+        let bitcoin_network = bitcoin::Network::from(network);
+        let address = bitcoin::Address::from_script(&recipient, bitcoin_network.params()).unwrap();
+        assert!(address.to_string().starts_with(address_prefix));
+        assert_eq!(
+            address,
+            bitcoin::Address::p2wpkh(&public_key, bitcoin::KnownHrp::from(network))
+        );
+
+        let parsed = address
+            .to_string()
+            .parse::<bitcoin::Address<bitcoin::address::NetworkUnchecked>>()
+            .unwrap()
+            .require_network(bitcoin_network)
+            .unwrap();
+        assert_eq!(parsed.script_pubkey(), recipient);
+    }
+
     #[test]
     fn bootstrap_wallet_signatures_required() {
         clear_env();
@@ -1450,4 +2328,74 @@ mod tests {
             ConfigError::Message(msg) if msg == SignerConfigError::UnsupportedDatabaseDriver(driver.to_string()).to_string()
         ));
     }
+
+    /// Write a copy of the default configuration, with the given context
+    /// window, to a temporary file.
+    fn config_file_with_context_window(context_window: u16) -> tempfile::NamedTempFile {
+        let mut config_toml = DEFAULT_CONFIG_TOML.parse::<DocumentMut>().unwrap();
+        config_toml["signer"]["context_window"] = toml_edit::value(i64::from(context_window));
+
+        let config_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(config_file.path(), config_toml.to_string()).unwrap();
+        config_file
+    }
+
+    #[test]
+    fn config_path_argument_is_used() {
+        clear_env();
+
+        let config_file = config_file_with_context_window(123);
+        let settings = Settings::new(Some(config_file.path())).unwrap();
+        assert_eq!(settings.signer.context_window, 123);
+    }
+
+    #[test]
+    fn config_path_environment_variable_is_used_without_argument() {
+        clear_env();
+
+        let env_file = config_file_with_context_window(234);
+        set_var(CONFIG_PATH_ENV_VAR, env_file.path());
+
+        let settings = Settings::new(None::<&Path>).unwrap();
+        assert_eq!(settings.signer.context_window, 234);
+
+        // The argument takes precedence over the environment variable.
+        let arg_file = config_file_with_context_window(345);
+        let settings = Settings::new(Some(arg_file.path())).unwrap();
+        assert_eq!(settings.signer.context_window, 345);
+    }
+
+    #[test]
+    fn missing_config_file_returns_correct_error() {
+        clear_env();
+
+        let path = std::env::temp_dir().join("sbtc-signer-missing-config.toml");
+        let expected = SignerConfigError::ConfigFileNotFound(path.clone()).to_string();
+
+        let settings = Settings::new(Some(&path));
+        assert!(matches!(settings, Err(ConfigError::Message(msg)) if msg == expected));
+
+        set_var(CONFIG_PATH_ENV_VAR, &path);
+        let settings = Settings::new(None::<&Path>);
+        assert!(matches!(settings, Err(ConfigError::Message(msg)) if msg == expected));
+    }
+
+    #[test]
+    fn embedded_default_config_is_used_without_a_path() {
+        clear_env();
+
+        let settings = Settings::new(None::<&Path>).unwrap();
+        let expected = Settings::new_from_path("./src/config/default.toml").unwrap();
+        assert_eq!(settings.signer.context_window, 1000);
+        assert_eq!(settings.signer.private_key, expected.signer.private_key);
+        assert_eq!(
+            settings.signer.bootstrap_signing_set,
+            expected.signer.bootstrap_signing_set
+        );
+
+        // An empty environment variable is treated as unset.
+        set_var(CONFIG_PATH_ENV_VAR, "");
+        let settings = Settings::new(None::<&Path>).unwrap();
+        assert_eq!(settings.signer.context_window, 1000);
+    }
 }
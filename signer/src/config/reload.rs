@@ -0,0 +1,361 @@
+//! Reloading of the non-critical parts of the signer configuration while
+//! the signer is running.
+//!
+//! Only the fields in [`ReloadableSettings`] take effect when the
+//! configuration file changes. Components read them through
+//! [`Context::reloadable_config`] each time they use them, instead of
+//! caching them. All other fields keep the value that they had when the
+//! signer started, and a warning is logged when they change in the file.
+//! Changes to the fields that identify the signer, such as its private
+//! key, network or database, are rejected outright.
+//!
+//! Some settings that operators may want to tune at runtime are not
+//! reloadable on purpose:
+//! * Log filters are not part of the configuration file. They come from
+//!   the `RUST_LOG` environment variable, which is read once when the
+//!   tracing subscriber is installed at startup.
+//! * Fee estimation has no parameters of its own; the signer asks
+//!   bitcoin-core for the market fee rate. The limits that are applied to
+//!   the estimate, `signer.bitcoin_fee_rate_max` and
+//!   `signer.bitcoin_fee_rate_market_multiple`, are reloadable.
+//! * The Emily, bitcoin-core and stacks-core endpoints are baked into the
+//!   API clients that are created when the signer starts and shared by
+//!   all of its event loops, so changing them means rebuilding those
+//!   clients, which amounts to a restart.
+
+use std::collections::BTreeMap;
+use std::num::NonZeroU16;
+use std::num::NonZeroU64;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use config::ConfigError;
+use config::ValueKind;
+
+use crate::config::BlocklistFailurePolicy;
+use crate::config::Settings;
+use crate::context::Context;
+
+/// How often the configuration file is checked for changes.
+pub const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The names of the fields in the configuration file that make up the
+/// [`ReloadableSettings`].
+const RELOADABLE_FIELDS: [&str; 5] = [
+    "signer.stacks_fees_max_ustx",
+    "signer.bitcoin_fee_rate_max",
+    "signer.bitcoin_fee_rate_market_multiple",
+    "signer.min_deposit_amount",
+    "blocklist_client.failure_policy",
+];
+
+/// The settings that can be changed without restarting the signer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReloadableSettings {
+    /// See [`SignerConfig::stacks_fees_max_ustx`](super::SignerConfig::stacks_fees_max_ustx).
+    pub stacks_fees_max_ustx: NonZeroU64,
+    /// See [`SignerConfig::bitcoin_fee_rate_max`](super::SignerConfig::bitcoin_fee_rate_max).
+    pub bitcoin_fee_rate_max: NonZeroU64,
+    /// See [`SignerConfig::bitcoin_fee_rate_market_multiple`](super::SignerConfig::bitcoin_fee_rate_market_multiple).
+    pub bitcoin_fee_rate_market_multiple: NonZeroU16,
+    /// See [`SignerConfig::min_deposit_amount`](super::SignerConfig::min_deposit_amount).
+    pub min_deposit_amount: u64,
+    /// See [`BlocklistClientConfig::failure_policy`](super::BlocklistClientConfig::failure_policy).
+    /// This is the default policy if no blocklist client is configured.
+    pub blocklist_failure_policy: BlocklistFailurePolicy,
+}
+
+impl From<&Settings> for ReloadableSettings {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            stacks_fees_max_ustx: settings.signer.stacks_fees_max_ustx,
+            bitcoin_fee_rate_max: settings.signer.bitcoin_fee_rate_max,
+            bitcoin_fee_rate_market_multiple: settings.signer.bitcoin_fee_rate_market_multiple,
+            min_deposit_amount: settings.signer.min_deposit_amount,
+            blocklist_failure_policy: settings
+                .blocklist_client
+                .as_ref()
+                .map(|config| config.failure_policy)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// A shared handle to the current [`ReloadableSettings`].
+#[derive(Debug, Clone)]
+pub struct ReloadableConfig(Arc<RwLock<Arc<ReloadableSettings>>>);
+
+impl ReloadableConfig {
+    /// Create a new handle holding the reloadable part of the given
+    /// settings.
+    pub fn new(settings: &Settings) -> Self {
+        let reloadable = Arc::new(ReloadableSettings::from(settings));
+        Self(Arc::new(RwLock::new(reloadable)))
+    }
+
+    /// Get the current reloadable settings.
+    pub fn get(&self) -> Arc<ReloadableSettings> {
+        // The lock only guards an `Arc` swap, so the settings behind a
+        // poisoned lock are still consistent.
+        let settings = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(&settings)
+    }
+
+    /// Replace the current reloadable settings with those from the
+    /// `new` settings, unless any field that cannot be reloaded differs
+    /// between the `current` and the `new` settings. Returns whether the
+    /// reloadable settings changed.
+    pub fn apply(&self, current: &Settings, new: &Settings) -> Result<bool, ConfigError> {
+        let immutable_changes = immutable_changes(current, new);
+        if !immutable_changes.is_empty() {
+            tracing::warn!(
+                fields = ?immutable_changes,
+                "rejecting reloaded configuration; these fields require a restart to change"
+            );
+            let msg = format!("fields that require a restart changed: {immutable_changes:?}");
+            return Err(ConfigError::Message(msg));
+        }
+
+        let reloadable = ReloadableSettings::from(new);
+        let mut settings = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        if **settings == reloadable {
+            return Ok(false);
+        }
+
+        tracing::info!(old = ?**settings, new = ?reloadable, "applying reloaded configuration");
+        *settings = Arc::new(reloadable);
+        Ok(true)
+    }
+}
+
+/// The names of the fields that cannot change while the signer is running
+/// but differ between the two settings.
+fn immutable_changes(current: &Settings, new: &Settings) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if current.signer.private_key != new.signer.private_key {
+        changes.push("signer.private_key");
+    }
+    if current.signer.network != new.signer.network {
+        changes.push("signer.network");
+    }
+    if current.signer.db_endpoint != new.signer.db_endpoint {
+        changes.push("signer.db_endpoint");
+    }
+    if current.signer.deployer != new.signer.deployer {
+        changes.push("signer.deployer");
+    }
+    changes
+}
+
+impl Settings {
+    /// Watch the configuration file at the given path, applying changes to
+    /// the [`ReloadableSettings`] of the context, until the signer shuts
+    /// down.
+    ///
+    /// The file is re-read every [`CONFIG_WATCH_INTERVAL`]. Configurations
+    /// that fail to load or validate, or that change fields that require a
+    /// restart, are logged and otherwise ignored.
+    pub async fn watch(path: impl AsRef<Path>, ctx: impl Context) {
+        watch_with_interval(path.as_ref().to_path_buf(), ctx, CONFIG_WATCH_INTERVAL).await
+    }
+}
+
+/// Watch the configuration file, checking it for changes every `interval`.
+async fn watch_with_interval(path: PathBuf, ctx: impl Context, interval: Duration) {
+    let mut term = ctx.get_termination_handle();
+    let mut last_contents = tokio::fs::read_to_string(&path).await.ok();
+
+    loop {
+        tokio::select! {
+            _ = term.wait_for_shutdown() => return,
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        let contents = tokio::fs::read_to_string(&path).await.ok();
+        if contents == last_contents {
+            continue;
+        }
+        let previous_contents = std::mem::replace(&mut last_contents, contents);
+
+        tracing::info!(path = %path.display(), "configuration file changed; reloading");
+        let result = Settings::new_from_path(&path)
+            .and_then(|new| ctx.reloadable_config().apply(ctx.config(), &new));
+
+        if let Err(error) = result {
+            tracing::warn!(%error, "could not reload the configuration file");
+            continue;
+        }
+
+        let previous_contents = previous_contents.unwrap_or_default();
+        let contents = last_contents.as_deref().unwrap_or_default();
+        let restart_required = restart_required_changes(&previous_contents, contents);
+        if !restart_required.is_empty() {
+            tracing::warn!(
+                fields = ?restart_required,
+                "ignoring changes to fields that require a restart to take effect"
+            );
+        }
+    }
+}
+
+/// The names of the fields that differ between the two contents of the
+/// configuration file, but that are not reloadable.
+fn restart_required_changes(old: &str, new: &str) -> Vec<String> {
+    let old = file_fields(old);
+    let new = file_fields(new);
+
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| old.get(*name) != new.get(*name))
+        .filter(|name| !RELOADABLE_FIELDS.contains(&name.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// The fields set in the given configuration file contents, keyed by their
+/// dotted names. Contents that cannot be parsed have no fields.
+fn file_fields(contents: &str) -> BTreeMap<String, ValueKind> {
+    let table = config::Config::builder()
+        .add_source(config::File::from_str(contents, config::FileFormat::Toml))
+        .build()
+        .and_then(|config| config.collect())
+        .unwrap_or_default();
+
+    let mut fields = BTreeMap::new();
+    let mut pending: Vec<(String, config::Value)> = table.into_iter().collect();
+    while let Some((name, value)) = pending.pop() {
+        match value.kind {
+            ValueKind::Table(table) => {
+                let children = table
+                    .into_iter()
+                    .map(|(key, value)| (format!("{name}.{key}"), value));
+                pending.extend(children);
+            }
+            kind => {
+                fields.insert(name, kind);
+            }
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use toml_edit::DocumentMut;
+
+    use crate::config::DEFAULT_CONFIG_TOML;
+    use crate::testing::clear_env;
+    use crate::testing::context::*;
+
+    use super::*;
+
+    fn write_config(path: &Path, f: impl FnOnce(&mut DocumentMut)) {
+        let mut config_toml = DEFAULT_CONFIG_TOML.parse::<DocumentMut>().unwrap();
+        f(&mut config_toml);
+        std::fs::write(path, config_toml.to_string()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn changes_to_the_config_file_take_effect_without_a_restart() {
+        clear_env();
+
+        let config_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        write_config(config_file.path(), |_| {});
+
+        let ctx = TestContext::builder()
+            .with_settings(Settings::new_from_path(config_file.path()).unwrap())
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let original = ctx.reloadable_config().get();
+
+        let watcher = tokio::spawn(watch_with_interval(
+            config_file.path().to_path_buf(),
+            ctx.clone(),
+            Duration::from_millis(10),
+        ));
+
+        // A reloadable field changes.
+        let new_fee_rate_max = original.bitcoin_fee_rate_max.get() + 1;
+        write_config(config_file.path(), |config| {
+            config["signer"]["bitcoin_fee_rate_max"] = toml_edit::value(new_fee_rate_max as i64);
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while ctx.reloadable_config().get().bitcoin_fee_rate_max.get() != new_fee_rate_max {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the reloaded fee rate never took effect");
+
+        let reloaded = ctx.reloadable_config().get();
+        assert_eq!(reloaded.stacks_fees_max_ustx, original.stacks_fees_max_ustx);
+
+        // A field that requires a restart changes along with a reloadable
+        // one, and the whole configuration is rejected.
+        write_config(config_file.path(), |config| {
+            config["signer"]["bitcoin_fee_rate_max"] = toml_edit::value(1);
+            config["signer"]["db_endpoint"] = toml_edit::value("postgres://other:5432/signer");
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(ctx.reloadable_config().get(), reloaded);
+
+        ctx.get_termination_handle().signal_shutdown();
+        watcher.await.unwrap();
+    }
+
+    #[test]
+    fn changes_that_require_a_restart_are_reported() {
+        let old = DEFAULT_CONFIG_TOML.parse::<DocumentMut>().unwrap();
+
+        let mut new = old.clone();
+        new["signer"]["bitcoin_fee_rate_max"] = toml_edit::value(1);
+        new["signer"]["min_deposit_amount"] = toml_edit::value(1);
+        let changes = restart_required_changes(&old.to_string(), &new.to_string());
+        assert!(changes.is_empty(), "{changes:?}");
+
+        new["emily"]["pagination_timeout"] = toml_edit::value(1);
+        new["signer"]["p2p"]["enable_mdns"] = toml_edit::value(false);
+        new["signer"]["event_observer"]
+            .as_table_mut()
+            .unwrap()
+            .remove("bind");
+        let changes = restart_required_changes(&old.to_string(), &new.to_string());
+        assert_eq!(
+            changes,
+            [
+                "emily.pagination_timeout",
+                "signer.event_observer.bind",
+                "signer.p2p.enable_mdns",
+            ]
+        );
+    }
+
+    #[test]
+    fn immutable_fields_are_rejected() {
+        clear_env();
+
+        let current = Settings::new_from_default_config().unwrap();
+        let reloadable = ReloadableConfig::new(&current);
+
+        let mut new = current.clone();
+        new.signer.min_deposit_amount += 1;
+        new.signer.network = crate::config::NetworkKind::Mainnet;
+        assert!(reloadable.apply(&current, &new).is_err());
+        assert_eq!(*reloadable.get(), ReloadableSettings::from(&current));
+
+        new.signer.network = current.signer.network;
+        assert!(reloadable.apply(&current, &new).unwrap());
+        assert!(!reloadable.apply(&current, &new).unwrap());
+        assert_eq!(*reloadable.get(), ReloadableSettings::from(&new));
+    }
+}
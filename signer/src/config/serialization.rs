@@ -231,4 +231,25 @@ mod tests {
             .expect("failed to parse valid DNS address");
         assert_eq!(addr.to_string(), "/dns/example.com/tcp/4122");
     }
+
+    #[test]
+    fn try_parse_p2p_multiaddr_quic_ipv4() {
+        let addr = try_parse_p2p_multiaddr("quic-v1://0.0.0.0:4122/")
+            .expect("failed to parse valid QUIC IPv4 address");
+        assert_eq!(addr.to_string(), "/ip4/0.0.0.0/udp/4122/quic-v1");
+    }
+
+    #[test]
+    fn try_parse_p2p_multiaddr_quic_ipv6() {
+        let addr = try_parse_p2p_multiaddr("quic-v1://[::1]:4122/")
+            .expect("failed to parse valid QUIC IPv6 address");
+        assert_eq!(addr.to_string(), "/ip6/::1/udp/4122/quic-v1");
+    }
+
+    #[test]
+    fn try_parse_p2p_multiaddr_quic_dns() {
+        let addr = try_parse_p2p_multiaddr("quic-v1://example.com:4122")
+            .expect("failed to parse valid QUIC DNS address");
+        assert_eq!(addr.to_string(), "/dns/example.com/udp/4122/quic-v1");
+    }
 }
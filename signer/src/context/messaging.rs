@@ -26,6 +26,10 @@ impl SignerSignal {
 pub enum SignerCommand {
     /// Signals to the application to publish a message to the P2P network.
     P2PPublish(crate::network::Msg),
+    /// Signals to the application to ask the other signers on the P2P
+    /// network for their decisions on the given deposit and withdrawal
+    /// requests.
+    P2PRequestDecisions(crate::bitcoin::validation::TxRequestIds),
     /// Signal to shut down the application
     Shutdown,
 }
@@ -37,6 +41,20 @@ pub enum SignerEvent {
     P2P(P2PEvent),
     /// Signals that a block observer event has occurred.
     BitcoinBlockObserved,
+    /// Signals that the block observer has detected a bitcoin reorg.
+    BitcoinReorgObserved {
+        /// The most recent block shared by the old and the new canonical
+        /// bitcoin blockchains.
+        fork_point: crate::storage::model::BitcoinBlockRef,
+        /// The blocks that were on the old canonical bitcoin blockchain
+        /// but are not on the new one, ordered from highest to lowest.
+        orphaned: Vec<crate::storage::model::BitcoinBlockHash>,
+    },
+    /// Signals that the stacks node has processed a new bitcoin block,
+    /// which it tells us through the `POST /new_burn_block` webhook. This
+    /// can arrive before the block observer sees the block, so it can be
+    /// used to get ready for it, like pre-warming fee estimates.
+    StacksBurnBlockObserved(crate::storage::model::BitcoinBlockRef),
     /// A Request decider event has occurred.
     RequestDecider(RequestDeciderEvent),
     /// Transaction signer events
@@ -59,6 +77,15 @@ pub enum P2PEvent {
     PeerConnected(libp2p::PeerId),
     /// Event which occurs when the P2P network has started its event loop.
     EventLoopStarted,
+    /// Signals that a consumer of the messages received from the P2P
+    /// network is not keeping up, so message delivery is waiting for it.
+    LaggedMessages {
+        /// The id of the lagging consumer.
+        consumer_id: u64,
+        /// The number of messages that have found the queue of the
+        /// consumer full so far.
+        lagged: u64,
+    },
 }
 
 /// Events that can be triggered from the request decider.
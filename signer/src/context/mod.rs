@@ -10,9 +10,11 @@ use tokio_stream::wrappers::ReceiverStream;
 
 use crate::SIGNER_CHANNEL_CAPACITY;
 use crate::bitcoin::BitcoinInteract;
+use crate::config::ReloadableConfig;
 use crate::config::Settings;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
+use crate::network::libp2p::MessageDispatcher;
 use crate::stacks::api::StacksInteract;
 use crate::storage::DbRead;
 use crate::storage::DbWrite;
@@ -26,6 +28,9 @@ pub use termination::*;
 pub trait Context: Clone + Sync + Send {
     /// Get the current configuration for the signer.
     fn config(&self) -> &Settings;
+    /// Get the part of the configuration that can be reloaded while the
+    /// signer is running. Read it each time that it is used.
+    fn reloadable_config(&self) -> &ReloadableConfig;
     /// Get the current state for the signer.
     fn state(&self) -> &SignerState;
     /// Subscribe to the application signalling channel, returning a receiver
@@ -37,6 +42,9 @@ pub trait Context: Clone + Sync + Send {
     fn signal(&self, signal: SignerSignal) -> Result<(), Error>;
     /// Returns a handle to the application's termination signal.
     fn get_termination_handle(&self) -> TerminationHandle;
+    /// Get a handle to the dispatcher which delivers the messages received
+    /// from the P2P network to their consumers.
+    fn get_message_dispatcher(&self) -> MessageDispatcher;
     /// Get a read-only handle to the signer storage.
     fn get_storage(&self) -> impl DbRead + Clone + Sync + Send + 'static;
     /// Get a read-write handle to the signer storage.
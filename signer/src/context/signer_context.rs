@@ -1,19 +1,19 @@
 use std::sync::Arc;
 
 use tokio::sync::broadcast::Sender;
-use url::Url;
 
 use crate::{
     SIGNER_CHANNEL_CAPACITY,
     bitcoin::BitcoinInteract,
-    config::{EmilyClientConfig, Settings},
+    config::{BitcoinConfig, ReloadableConfig, Settings},
     emily_client::EmilyInteract,
     error::Error,
+    network::libp2p::MessageDispatcher,
     stacks::api::StacksInteract,
     storage::{DbRead, DbWrite},
 };
 
-use super::{Context, SignerSignal, SignerState, TerminationHandle};
+use super::{Context, ShutdownBarrier, SignerSignal, SignerState, TerminationHandle};
 
 /// Signer context which is passed to different components within the
 /// signer binary.
@@ -24,12 +24,20 @@ pub struct SignerContext<S, BC, ST, EM> {
     // for the duration of the program and is used both to send messages
     // and to hand out new receivers.
     signal_tx: Sender<SignerSignal>,
+    /// Delivers the messages received from the P2P network to each of
+    /// their consumers through a bounded queue.
+    message_dispatcher: MessageDispatcher,
+    /// The configuration that can be reloaded while the signer is running.
+    reloadable_config: ReloadableConfig,
     /// The internal state of the signer.
     state: Arc<SignerState>,
     /// Handle to the app termination channel. This keeps the channel alive
     /// for the duration of the program and is used to provide new senders
     /// and receivers for a [`TerminationHandle`].
     term_tx: tokio::sync::watch::Sender<bool>,
+    /// The barrier that long-running components register with so that
+    /// shutdown can wait for their in-flight work to complete.
+    shutdown_barrier: ShutdownBarrier,
     /// Handle to the signer storage.
     storage: S,
     /// Handle to a Bitcoin-RPC fallback-client.
@@ -48,19 +56,19 @@ pub struct SignerContext<S, BC, ST, EM> {
 impl<S, BC, ST, EM> SignerContext<S, BC, ST, EM>
 where
     S: DbRead + DbWrite + Clone + Sync + Send + 'static,
-    BC: for<'a> TryFrom<&'a [Url]> + BitcoinInteract + Clone + 'static,
+    BC: for<'a> TryFrom<&'a BitcoinConfig> + BitcoinInteract + Clone + 'static,
     ST: for<'a> TryFrom<&'a Settings> + StacksInteract + Clone + Sync + Send + 'static,
-    EM: for<'a> TryFrom<&'a EmilyClientConfig> + EmilyInteract + Clone + Sync + Send + 'static,
-    Error: for<'a> From<<BC as TryFrom<&'a [Url]>>::Error>,
+    EM: for<'a> TryFrom<&'a Settings> + EmilyInteract + Clone + Sync + Send + 'static,
+    Error: for<'a> From<<BC as TryFrom<&'a BitcoinConfig>>::Error>,
     Error: for<'a> From<<ST as TryFrom<&'a Settings>>::Error>,
-    Error: for<'a> From<<EM as TryFrom<&'a EmilyClientConfig>>::Error>,
+    Error: for<'a> From<<EM as TryFrom<&'a Settings>>::Error>,
 {
     /// Initializes a new [`SignerContext`], automatically creating clients
     /// based on the provided types.
     pub fn init(config: Settings, db: S) -> Result<Self, Error> {
-        let bc = BC::try_from(&config.bitcoin.rpc_endpoints)?;
+        let bc = BC::try_from(&config.bitcoin)?;
         let st = ST::try_from(&config)?;
-        let em = EM::try_from(&config.emily)?;
+        let em = EM::try_from(&config)?;
 
         Ok(Self::new(config, db, bc, st, em))
     }
@@ -92,10 +100,13 @@ where
         }
 
         Self {
+            reloadable_config: ReloadableConfig::new(&config),
             config,
             state: Arc::new(state),
             signal_tx,
+            message_dispatcher: MessageDispatcher::default(),
             term_tx,
+            shutdown_barrier: ShutdownBarrier::default(),
             storage: db,
             bitcoin_client,
             stacks_client,
@@ -115,6 +126,10 @@ where
         &self.config
     }
 
+    fn reloadable_config(&self) -> &ReloadableConfig {
+        &self.reloadable_config
+    }
+
     fn state(&self) -> &SignerState {
         &self.state
     }
@@ -142,7 +157,15 @@ where
     }
 
     fn get_termination_handle(&self) -> TerminationHandle {
-        TerminationHandle::new(self.term_tx.clone(), self.term_tx.subscribe())
+        TerminationHandle::new(
+            self.term_tx.clone(),
+            self.term_tx.subscribe(),
+            self.shutdown_barrier.clone(),
+        )
+    }
+
+    fn get_message_dispatcher(&self) -> MessageDispatcher {
+        self.message_dispatcher.clone()
     }
 
     fn get_storage(&self) -> impl DbRead + Clone + Sync + Send + 'static {
@@ -1,14 +1,15 @@
 //! Module for signer state
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::{
     RwLock,
     atomic::{AtomicBool, AtomicU64, Ordering},
 };
+use std::time::Instant;
 
 use bitcoin::Amount;
 use hashbrown::HashSet;
-use libp2p::PeerId;
+use libp2p::{Multiaddr, PeerId};
 
 use crate::keys::PublicKey;
 use crate::storage::model::BitcoinBlockHash;
@@ -29,9 +30,29 @@ pub struct SignerState {
     // The current bitcoin chain tip. This gets updated at the end of the
     // block observer's duties when it observes a new bitcoin block.
     bitcoin_chain_tip: RwLock<BitcoinBlockRef>,
+    // A snapshot of the state of the P2P swarm. This gets updated by the
+    // libp2p event loop.
+    p2p_status: RwLock<SwarmStatus>,
 }
 
 impl SignerState {
+    /// Return a snapshot of the state of the P2P swarm.
+    pub fn p2p_status(&self) -> SwarmStatus {
+        self.p2p_status
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .clone()
+    }
+
+    /// Replace the snapshot of the state of the P2P swarm.
+    pub fn set_p2p_status(&self, status: SwarmStatus) {
+        let mut p2p_status = self
+            .p2p_status
+            .write()
+            .expect("BUG: Failed to acquire write lock");
+        *p2p_status = status;
+    }
+
     /// Get the current signer set.
     pub fn current_signer_set(&self) -> &SignerSet {
         &self.current_signer_set
@@ -51,6 +72,33 @@ impl SignerState {
         self.current_signer_set.replace_signers(public_keys);
     }
 
+    /// Return the coordinator for the current bitcoin chain tip and
+    /// signer set, or `None` if the signer set is empty. The election rule
+    /// in effect at the chain tip depends on the configured
+    /// `activation_height`, see [`crate::coordinator::elect_at`].
+    ///
+    /// The coordinator is elected from the current state every time that
+    /// this is called, so it reflects the latest chain tip and signer set
+    /// recorded by the block observer before it signals
+    /// `BitcoinBlockObserved`, including any key rotations.
+    pub fn current_coordinator(
+        &self,
+        activation_height: Option<BitcoinBlockHeight>,
+    ) -> Option<PublicKey> {
+        let signer_set = self.current_signer_public_keys();
+        crate::coordinator::elect_at(&self.bitcoin_chain_tip(), &signer_set, activation_height)
+    }
+
+    /// Return whether the given public key belongs to the coordinator for
+    /// the current bitcoin chain tip and signer set.
+    pub fn is_coordinator(
+        &self,
+        public_key: &PublicKey,
+        activation_height: Option<BitcoinBlockHeight>,
+    ) -> bool {
+        self.current_coordinator(activation_height).as_ref() == Some(public_key)
+    }
+
     /// Return the current aggregate key from the cache.
     #[allow(clippy::unwrap_in_result)]
     pub fn current_aggregate_key(&self) -> Option<PublicKey> {
@@ -150,10 +198,53 @@ impl Default for SignerState {
                 block_height: 0u64.into(),
                 block_hash: BitcoinBlockHash::from([0; 32]),
             }),
+            p2p_status: RwLock::new(SwarmStatus::default()),
         }
     }
 }
 
+/// A snapshot of the connections and gossipsub mesh of the P2P swarm.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SwarmStatus {
+    /// The peers that we are connected to, along with the remote addresses
+    /// of each connection to the peer.
+    pub connected_peers: BTreeMap<PeerId, Vec<Multiaddr>>,
+    /// The peers in our gossipsub mesh for each topic that we are
+    /// subscribed to.
+    pub mesh_peers: BTreeMap<String, BTreeSet<PeerId>>,
+    /// The peers that we are dialing but have not connected to yet.
+    pub pending_dials: BTreeSet<PeerId>,
+    /// The most recent connection error for each peer that we failed to
+    /// connect to.
+    pub last_errors: BTreeMap<PeerId, String>,
+    /// The peers that we are only connected to through a relay server,
+    /// because we could not establish a direct connection to them.
+    pub relayed_peers: BTreeSet<PeerId>,
+    /// The behavior score of each peer that has sent us messages and is
+    /// not banned. Peers lose points for misbehaving.
+    pub peer_scores: BTreeMap<PeerId, i64>,
+    /// The peers that are banned for misbehaving, along with when their
+    /// ban expires.
+    pub banned_peers: BTreeMap<PeerId, Instant>,
+}
+
+impl SwarmStatus {
+    /// The number of peers that we are connected to.
+    pub fn num_connected_peers(&self) -> usize {
+        self.connected_peers.len()
+    }
+
+    /// The number of distinct peers in our gossipsub mesh across all
+    /// topics.
+    pub fn num_mesh_peers(&self) -> usize {
+        self.mesh_peers
+            .values()
+            .flatten()
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+}
+
 /// Represents the current sBTC limits.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SbtcLimits {
@@ -572,4 +663,50 @@ mod tests {
         signer_set.remove_signer(&public_key);
         assert!(!signer_set.is_allowed_peer(&public_key.into()));
     }
+
+    #[test]
+    fn coordinator_follows_chain_tip_and_signer_set() {
+        use super::*;
+
+        let state = SignerState::default();
+        assert_eq!(state.current_coordinator(None), None);
+
+        let public_keys: BTreeSet<PublicKey> =
+            std::iter::repeat_with(|| PublicKey::from_private_key(&PrivateKey::new(&mut OsRng)))
+                .take(3)
+                .collect();
+        state.update_current_signer_set(public_keys.clone());
+
+        let chain_tip = BitcoinBlockRef {
+            block_height: 1u64.into(),
+            block_hash: BitcoinBlockHash::from([1; 32]),
+        };
+        state.set_bitcoin_chain_tip(chain_tip);
+
+        // The election rule depends on the activation height.
+        let activation_height = Some(chain_tip.block_height);
+        let coordinator =
+            crate::coordinator::elect_at(&chain_tip, &public_keys, activation_height).unwrap();
+        assert_eq!(
+            state.current_coordinator(activation_height),
+            Some(coordinator)
+        );
+        assert!(state.is_coordinator(&coordinator, activation_height));
+
+        let legacy_coordinator =
+            crate::coordinator::elect_legacy(&chain_tip.block_hash, &public_keys);
+        assert_eq!(state.current_coordinator(None), legacy_coordinator);
+
+        // Once the coordinator rotates out of the signer set, another
+        // signer takes over right away.
+        let remaining: BTreeSet<PublicKey> = public_keys
+            .into_iter()
+            .filter(|key| key != &coordinator)
+            .collect();
+        state.update_current_signer_set(remaining.clone());
+
+        assert!(!state.is_coordinator(&coordinator, activation_height));
+        let new_coordinator = state.current_coordinator(activation_height).unwrap();
+        assert!(remaining.contains(&new_coordinator));
+    }
 }
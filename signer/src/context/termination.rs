@@ -1,10 +1,17 @@
 //! Module that contains termination-related code for the [`Context`].
 
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
 /// Handle to the termination signal. This can be used to signal the application
 /// to shutdown or to wait for a shutdown signal.
 pub struct TerminationHandle(
     tokio::sync::watch::Sender<bool>,
     tokio::sync::watch::Receiver<bool>,
+    ShutdownBarrier,
 );
 
 impl Clone for TerminationHandle {
@@ -12,6 +19,7 @@ impl Clone for TerminationHandle {
         Self(
             self.0.clone(),     // Sender
             self.0.subscribe(), // Receiver
+            self.2.clone(),     // Barrier
         )
     }
 }
@@ -21,8 +29,26 @@ impl TerminationHandle {
     pub fn new(
         tx: tokio::sync::watch::Sender<bool>,
         rx: tokio::sync::watch::Receiver<bool>,
+        barrier: ShutdownBarrier,
     ) -> Self {
-        Self(tx, rx)
+        Self(tx, rx, barrier)
+    }
+
+    /// Register a long-running component as a participant in shutdown.
+    ///
+    /// The component acknowledges the shutdown by dropping the returned
+    /// participant, which it should do once it has finished its current
+    /// unit of work after a shutdown has been signalled.
+    pub fn register_participant(&self, name: &'static str) -> ShutdownParticipant {
+        self.2.register(name)
+    }
+
+    /// Wait for all registered participants to acknowledge the shutdown,
+    /// for at most the given deadline. Returns the names of the
+    /// participants that had not acknowledged the shutdown by the
+    /// deadline.
+    pub async fn wait_for_participants(&self, deadline: Duration) -> Vec<&'static str> {
+        self.2.wait(deadline).await
     }
 
     /// Check if a shutdown signal has been signalled.
@@ -57,3 +83,157 @@ impl TerminationHandle {
         }
     }
 }
+
+/// A barrier that long-running components register with, so that the
+/// application can wait for them to finish their current unit of work
+/// before exiting.
+#[derive(Debug, Clone)]
+pub struct ShutdownBarrier(Arc<ShutdownBarrierInner>);
+
+#[derive(Debug)]
+struct ShutdownBarrierInner {
+    /// The names of the participants that have not yet acknowledged the
+    /// shutdown, keyed by their registration ID.
+    participants: tokio::sync::watch::Sender<BTreeMap<u64, &'static str>>,
+    /// The registration ID to give to the next participant.
+    next_id: AtomicU64,
+}
+
+impl Default for ShutdownBarrier {
+    fn default() -> Self {
+        Self(Arc::new(ShutdownBarrierInner {
+            participants: tokio::sync::watch::Sender::new(BTreeMap::new()),
+            next_id: AtomicU64::new(0),
+        }))
+    }
+}
+
+impl ShutdownBarrier {
+    /// Register a new participant with the given name.
+    fn register(&self, name: &'static str) -> ShutdownParticipant {
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        self.0.participants.send_modify(|participants| {
+            participants.insert(id, name);
+        });
+
+        ShutdownParticipant {
+            id,
+            name,
+            barrier: self.clone(),
+        }
+    }
+
+    /// Wait for all participants to acknowledge the shutdown, returning
+    /// the names of the participants that did not do so before the
+    /// deadline.
+    async fn wait(&self, deadline: Duration) -> Vec<&'static str> {
+        let mut receiver = self.0.participants.subscribe();
+        let all_acknowledged = receiver.wait_for(BTreeMap::is_empty);
+
+        if tokio::time::timeout(deadline, all_acknowledged)
+            .await
+            .is_ok()
+        {
+            return Vec::new();
+        }
+
+        self.0.participants.borrow().values().copied().collect()
+    }
+}
+
+/// A registration of a long-running component with the [`ShutdownBarrier`].
+/// Dropping this acknowledges the shutdown on behalf of the component.
+#[derive(Debug)]
+pub struct ShutdownParticipant {
+    id: u64,
+    name: &'static str,
+    barrier: ShutdownBarrier,
+}
+
+impl ShutdownParticipant {
+    /// The name that this participant was registered with.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Acknowledge the shutdown, signalling that the component has
+    /// finished its current unit of work.
+    pub fn acknowledge(self) {
+        tracing::debug!(participant = self.name, "shutdown acknowledged");
+    }
+}
+
+impl Drop for ShutdownParticipant {
+    fn drop(&mut self) {
+        self.barrier.0.participants.send_modify(|participants| {
+            participants.remove(&self.id);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use fake::Fake as _;
+
+    use crate::context::Context as _;
+    use crate::storage::DbRead as _;
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::testing::context::*;
+
+    /// A database write that is in progress when the shutdown is
+    /// signalled completes before the participant acknowledges the
+    /// shutdown, and waiting on the participants waits for it.
+    #[tokio::test]
+    async fn in_flight_write_completes_before_shutdown() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let term = ctx.get_termination_handle();
+        let participant = term.register_participant("slow-writer");
+        let block: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rand::rngs::OsRng);
+
+        let db = ctx.get_storage_mut();
+        let write_block = block.clone();
+        let handle = tokio::spawn(async move {
+            // A slow database write that is in progress when the shutdown
+            // is signalled.
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            db.write_bitcoin_block(&write_block).await.unwrap();
+            participant.acknowledge();
+        });
+
+        term.signal_shutdown();
+        let abandoned = term.wait_for_participants(Duration::from_secs(5)).await;
+        assert!(abandoned.is_empty());
+
+        let db = ctx.get_storage();
+        let stored = db.get_bitcoin_block(&block.block_hash).await.unwrap();
+        assert_eq!(stored, Some(block));
+        handle.await.unwrap();
+    }
+
+    /// Participants that do not acknowledge the shutdown before the
+    /// deadline are returned as abandoned.
+    #[tokio::test]
+    async fn participants_exceeding_deadline_are_abandoned() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let term = ctx.get_termination_handle();
+
+        let stuck = term.register_participant("stuck");
+        let done = term.register_participant("done");
+        done.acknowledge();
+
+        term.signal_shutdown();
+        let abandoned = term.wait_for_participants(Duration::from_millis(50)).await;
+        assert_eq!(abandoned, vec![stuck.name()]);
+    }
+}
@@ -0,0 +1,239 @@
+//! This module contains the rule that the signers use to agree on which
+//! of them coordinates the signing rounds while a given bitcoin block is
+//! the chain tip.
+//!
+//! Every signer computes the coordinator from data that they all share,
+//! the bitcoin chain tip and the public keys of the current signer set, so
+//! electing a coordinator does not require any messages. Since all signers
+//! must elect the same coordinator, the rule implemented here is part of
+//! the protocol and must not change between versions of the signer.
+//!
+//! Signers used to elect the coordinator with [`elect_legacy`], which
+//! always favors the same signers for chain tips whose hashes share a
+//! prefix. The rule in [`elect`] replaces it, but only for chain tips at or
+//! above the `signer.coordinator_election_activation_height` configured on
+//! the signer, see [`elect_at`]. The rollout goes as follows:
+//!
+//! 1. All signers upgrade to a version that includes [`elect`], leaving
+//!    the activation height unset, so that they keep using the legacy
+//!    rule.
+//! 2. The signers agree on a bitcoin block height that is comfortably in
+//!    the future, and all of them set the activation height to it.
+//! 3. From that block onward every signer uses the new rule.
+//!
+//! A signer whose activation height differs from the others will disagree
+//! with them on the coordinator, ignoring the coordinator's requests and
+//! trying to coordinate when it shouldn't, until the setting is fixed.
+
+use std::collections::BTreeSet;
+
+use sha2::Digest as _;
+
+use crate::keys::PublicKey;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinBlockHeight;
+use crate::storage::model::BitcoinBlockRef;
+
+/// The domain separation tag that is hashed in with the chain tip and a
+/// public key when electing the coordinator.
+const COORDINATOR_ELECTION_TAG: &[u8] = b"SBTC_COORDINATOR_ELECTION";
+
+/// Elect the coordinator for the given bitcoin chain tip from the given
+/// signer set, using the rule that is in effect at the height of the chain
+/// tip given the configured activation height of [`elect`].
+pub fn elect_at(
+    chain_tip: &BitcoinBlockRef,
+    signer_set: &BTreeSet<PublicKey>,
+    activation_height: Option<BitcoinBlockHeight>,
+) -> Option<PublicKey> {
+    let is_active = activation_height.is_some_and(|height| chain_tip.block_height >= height);
+    if !is_active {
+        return elect_legacy(&chain_tip.block_hash, signer_set);
+    }
+
+    let signer_set: Vec<PublicKey> = signer_set.iter().copied().collect();
+    elect(&chain_tip.block_hash, &signer_set)
+}
+
+/// Elect the coordinator for the given bitcoin chain tip from the given
+/// signer set, using the rule from before [`elect`] was activated.
+///
+/// The first 4 bytes of the SHA256 digest of the chain tip are read as a
+/// big-endian `u32`, and the coordinator is the key at that index, modulo
+/// the size of the signer set, in the sorted signer set. We don't use the
+/// chain tip directly because it typically starts with a lot of leading
+/// zeros.
+///
+/// Returns `None` if the signer set is empty.
+pub fn elect_legacy(
+    chain_tip: &BitcoinBlockHash,
+    signer_set: &BTreeSet<PublicKey>,
+) -> Option<PublicKey> {
+    let digest: [u8; 32] = sha2::Sha256::digest(chain_tip.into_bytes()).into();
+    let index = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+
+    let num_signers = signer_set.len().max(1);
+    signer_set
+        .iter()
+        .nth((index as usize) % num_signers)
+        .copied()
+}
+
+/// Elect the coordinator for the given bitcoin chain tip from the given
+/// signer set.
+///
+/// For each public key we compute the SHA256 digest of
+/// `COORDINATOR_ELECTION_TAG || chain_tip || public_key`, where the public
+/// key is in its 33 byte compressed form, and the coordinator is the key
+/// with the smallest digest, compared as big-endian byte strings. The
+/// signer set is sorted first, so the order in which the keys are given
+/// does not matter, and in the practically impossible case of two equal
+/// digests the smaller key wins.
+///
+/// Returns `None` if the signer set is empty.
+pub fn elect(chain_tip: &BitcoinBlockHash, signer_set: &[PublicKey]) -> Option<PublicKey> {
+    let mut signer_set = signer_set.to_vec();
+    signer_set.sort();
+
+    signer_set
+        .into_iter()
+        .min_by_key(|public_key| election_digest(chain_tip, public_key))
+}
+
+/// The digest of the given public key for the election at the given
+/// chain tip.
+fn election_digest(chain_tip: &BitcoinBlockHash, public_key: &PublicKey) -> [u8; 32] {
+    sha2::Sha256::new_with_prefix(COORDINATOR_ELECTION_TAG)
+        .chain_update(chain_tip.into_bytes())
+        .chain_update(public_key.serialize())
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+    use rand::SeedableRng as _;
+
+    use crate::keys::PrivateKey;
+
+    use super::*;
+
+    fn signer_set(seed: u64, num_signers: usize) -> Vec<PublicKey> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        std::iter::repeat_with(|| PublicKey::from_private_key(&PrivateKey::new(&mut rng)))
+            .take(num_signers)
+            .collect()
+    }
+
+    #[test]
+    fn empty_signer_set_has_no_coordinator() {
+        let chain_tip = BitcoinBlockHash::from([1; 32]);
+        assert_eq!(elect(&chain_tip, &[]), None);
+        assert_eq!(elect_legacy(&chain_tip, &BTreeSet::new()), None);
+    }
+
+    #[test]
+    fn election_is_stable() {
+        // The election is part of the protocol, so this pins down the
+        // result for fixed inputs. If this test fails then the rule has
+        // changed and signers running different versions will disagree
+        // on the coordinator.
+        let signer_set: Vec<PublicKey> = (1..=5)
+            .map(|scalar| {
+                let private_key: PrivateKey = format!("{scalar:064x}").parse().unwrap();
+                PublicKey::from_private_key(&private_key)
+            })
+            .collect();
+        let chain_tip = BitcoinBlockHash::from([0xab; 32]);
+
+        // The digest for the public key of the private key 4 starts with
+        // 0x31, while the others start with 0x47 or more.
+        assert_eq!(elect(&chain_tip, &signer_set), Some(signer_set[3]));
+    }
+
+    #[test]
+    fn election_is_roughly_uniform() {
+        let num_signers = 5;
+        let num_tips = 10_000;
+        let signer_set = signer_set(42, num_signers);
+
+        let mut wins: HashMap<PublicKey, usize> = HashMap::new();
+        for i in 0..num_tips {
+            let tip: [u8; 32] = sha2::Sha256::digest((i as u64).to_be_bytes()).into();
+            let coordinator = elect(&BitcoinBlockHash::from(tip), &signer_set).unwrap();
+            *wins.entry(coordinator).or_default() += 1;
+        }
+
+        // Each signer should be elected about 2000 times. The standard
+        // deviation is 40, so a 20% tolerance is more than 10 of them.
+        let expected = num_tips / num_signers;
+        assert_eq!(wins.len(), num_signers);
+        for count in wins.values() {
+            assert!(count.abs_diff(expected) < expected / 5, "{wins:?}");
+        }
+    }
+
+    #[test]
+    fn election_rule_switches_at_the_activation_height() {
+        let signer_set: BTreeSet<PublicKey> = signer_set(7, 5).into_iter().collect();
+        let keys: Vec<PublicKey> = signer_set.iter().copied().collect();
+
+        // Find a chain tip where the two rules disagree, so that we can
+        // tell which one was used.
+        let block_hash = (0..u64::MAX)
+            .map(|i| {
+                let tip: [u8; 32] = sha2::Sha256::digest(i.to_be_bytes()).into();
+                BitcoinBlockHash::from(tip)
+            })
+            .find(|tip| elect(tip, &keys) != elect_legacy(tip, &signer_set))
+            .unwrap();
+        let legacy = elect_legacy(&block_hash, &signer_set);
+        let new = elect(&block_hash, &keys);
+
+        let chain_tip = BitcoinBlockRef {
+            block_hash,
+            block_height: 100u64.into(),
+        };
+
+        // The legacy rule stays in effect until an activation height is
+        // configured and reached.
+        assert_eq!(elect_at(&chain_tip, &signer_set, None), legacy);
+        let activation_height = Some(101u64.into());
+        assert_eq!(elect_at(&chain_tip, &signer_set, activation_height), legacy);
+
+        let activation_height = Some(100u64.into());
+        assert_eq!(elect_at(&chain_tip, &signer_set, activation_height), new);
+        let activation_height = Some(99u64.into());
+        assert_eq!(elect_at(&chain_tip, &signer_set, activation_height), new);
+    }
+
+    proptest! {
+        /// Signers given the same chain tip and signer set elect the same
+        /// coordinator, regardless of the order of the signer set.
+        #[test]
+        fn all_signers_elect_the_same_coordinator(
+            tip in any::<[u8; 32]>(),
+            seed in any::<u64>(),
+            num_signers in 1..=15_usize,
+            shuffle_seed in any::<u64>(),
+        ) {
+            use rand::seq::SliceRandom as _;
+
+            let chain_tip = BitcoinBlockHash::from(tip);
+            let signer_set = signer_set(seed, num_signers);
+            let coordinator = elect(&chain_tip, &signer_set);
+
+            let mut rng = rand::rngs::StdRng::seed_from_u64(shuffle_seed);
+            for _ in 0..num_signers {
+                let mut view = signer_set.clone();
+                view.shuffle(&mut rng);
+                prop_assert_eq!(elect(&chain_tip, &view), coordinator);
+            }
+
+            prop_assert!(coordinator.is_some_and(|key| signer_set.contains(&key)));
+        }
+    }
+}
@@ -70,6 +70,11 @@ use crate::keys::PublicKey;
 use crate::message::SignerMessage;
 use crate::proto;
 
+/// The version of the signed message wire format that this signer
+/// produces. Messages carrying a newer version are rejected, so that a
+/// future change to the format cannot be misinterpreted by older signers.
+pub const SIGNED_MESSAGE_VERSION: u32 = 0;
+
 /// Wraps an inner type with a public key and a signature,
 /// allowing easy verification of the integrity of the inner data.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -80,12 +85,17 @@ pub struct Signed<T> {
     pub signature: secp256k1::ecdsa::Signature,
     /// The public key of the signer that generated the message.
     pub signer_public_key: PublicKey,
+    /// A number that increases with each message published by the signer,
+    /// used by receivers to detect replayed messages. It is zero if the
+    /// message was not assigned a sequence number, and receivers drop such
+    /// messages.
+    pub sequence: u64,
 }
 
 impl Signed<SignerMessage> {
     /// Unique identifier for the signed message
     pub fn id(&self) -> [u8; 32] {
-        self.inner.to_digest(self.signer_public_key)
+        self.inner.to_digest(self.signer_public_key, self.sequence)
     }
 
     /// Verify that the signature was created over the given digest with
@@ -187,13 +197,15 @@ impl Signed<SignerMessage> {
 }
 
 impl SignerMessage {
-    fn to_digest(&self, public_key: PublicKey) -> [u8; 32] {
+    fn to_digest(&self, public_key: PublicKey, sequence: u64) -> [u8; 32] {
         let mut hasher = sha2::Sha256::new_with_prefix(self.type_tag());
 
         let ans = proto::Signed {
             signature: None,
             signer_public_key: Some(public_key.into()),
             signer_message: Some(self.clone().into()),
+            version: SIGNED_MESSAGE_VERSION,
+            sequence,
         };
 
         hasher.update(ans.encode_to_vec());
@@ -223,13 +235,22 @@ pub trait SignEcdsa: Sized {
 
 impl SignEcdsa for SignerMessage {
     fn sign_ecdsa(self, private_key: &PrivateKey) -> Signed<Self> {
+        self.sign_ecdsa_with_sequence(private_key, 0)
+    }
+}
+
+impl SignerMessage {
+    /// Wrap this message into a [`Signed<Self>`] with the given sequence
+    /// number, which is covered by the signature.
+    pub fn sign_ecdsa_with_sequence(self, private_key: &PrivateKey, sequence: u64) -> Signed<Self> {
         let public_key = PublicKey::from_private_key(private_key);
-        let msg = secp256k1::Message::from_digest(self.to_digest(public_key));
+        let msg = secp256k1::Message::from_digest(self.to_digest(public_key, sequence));
 
         Signed {
             signature: private_key.sign_ecdsa(&msg),
             inner: self,
             signer_public_key: public_key,
+            sequence,
         }
     }
 }
@@ -242,18 +263,23 @@ impl Signed<SignerMessage> {
         Self::random_with_private_key(rng, &private_key)
     }
 
-    /// Generate a random signed message with the given private key
+    /// Generate a random signed message with the given private key. The
+    /// messages are given increasing sequence numbers, so that receivers
+    /// do not drop them as replays.
     pub fn random_with_private_key<R: rand::CryptoRng + rand::Rng>(
         rng: &mut R,
         private_key: &PrivateKey,
     ) -> Self {
+        static NEXT_SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
         let inner = SignerMessage::random(rng);
-        inner.sign_ecdsa(private_key)
+        let sequence = NEXT_SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        inner.sign_ecdsa_with_sequence(private_key, sequence)
     }
 
     /// Verify the signature over the inner data.
     pub fn verify(&self) -> bool {
-        let digest = self.inner.to_digest(self.signer_public_key);
+        let digest = self.inner.to_digest(self.signer_public_key, self.sequence);
         self.verify_digest(digest).is_ok()
     }
 }
@@ -305,7 +331,7 @@ mod tests {
         // reproduce the original digest that was signed after deserializing
         // the protobuf. This is why we use the
         // Signed::<SignerMessage>::decode_with_digest function.
-        let original_digest = original_message.to_digest(public_key);
+        let original_digest = original_message.to_digest(public_key, 0);
 
         let signed_message: Signed<SignerMessage> = original_message.sign_ecdsa(&private_key);
         let buf = signed_message.clone().encode_to_vec();
@@ -363,7 +389,7 @@ mod tests {
         // reproduce the original digest that was signed after deserializing
         // the protobuf. This is why we use the
         // Signed::<SignerMessage>::decode_with_digest function.
-        let original_digest = original_message.to_digest(public_key);
+        let original_digest = original_message.to_digest(public_key, 0);
 
         let mut signed_message: Signed<SignerMessage> = original_message.sign_ecdsa(&private_key);
         // Let's change one byte of the signed payload
@@ -415,7 +441,7 @@ mod tests {
         /// An additional field for timestamps. Maybe we use this to
         /// prevent replay attacks. This field is a backwards compatible
         /// upgrade.
-        #[prost(message, optional, tag = "4")]
+        #[prost(message, optional, tag = "6")]
         pub timestamp: Option<Timestamp>,
     }
 
@@ -498,7 +524,7 @@ mod tests {
         /// An additional field for timestamps. Maybe we use this to
         /// prevent replay attacks. This field is a backwards compatible
         /// upgrade.
-        #[prost(message, optional, tag = "4")]
+        #[prost(message, optional, tag = "6")]
         pub timestamp: Option<Timestamp>,
     }
 
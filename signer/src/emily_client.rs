@@ -1,5 +1,8 @@
 //! Emily API client module
 
+use std::collections::HashMap;
+use std::future::Future;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
@@ -10,32 +13,45 @@ use bitcoin::ScriptBuf;
 use bitcoin::Txid;
 use emily_client::apis::Error as EmilyError;
 use emily_client::apis::ResponseContent;
+use emily_client::apis::chainstate_api;
 use emily_client::apis::configuration::ApiKey;
 use emily_client::apis::configuration::Configuration as EmilyApiConfig;
 use emily_client::apis::deposit_api;
 use emily_client::apis::limits_api;
 use emily_client::apis::withdrawal_api;
+use emily_client::models::Chainstate;
 use emily_client::models::DepositInfo;
 use emily_client::models::DepositUpdate;
+use emily_client::models::SetChainstatesBulkRequestBody;
 use emily_client::models::Status;
 use emily_client::models::UpdateDepositsRequestBody;
 use emily_client::models::UpdateDepositsResponse;
 use emily_client::models::UpdateWithdrawalsRequestBody;
 use emily_client::models::UpdateWithdrawalsResponse;
+use emily_client::models::Withdrawal;
+use emily_client::models::WithdrawalInfo;
 use emily_client::models::WithdrawalUpdate;
 use sbtc::deposits::CreateDepositRequest;
+use tracing::Instrument as _;
 use url::Url;
 
 use crate::bitcoin::utxo::RequestRef;
 use crate::bitcoin::utxo::UnsignedTransaction;
-use crate::config::EmilyClientConfig;
+use crate::config::Settings;
 use crate::context::SbtcLimits;
 use crate::error::Error;
+use crate::error::ErrorContext;
+use crate::error::ResultExt as _;
+use crate::keys::PublicKey;
+use crate::metrics::Metrics;
+use crate::storage::DbRead;
+use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinTxId;
 use crate::util::ApiFallbackClient;
 
 /// Emily client error variants.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum EmilyClientError {
     /// Scheme must be HTTP or HTTPS
     #[error("invalid URL scheme: {0}")]
@@ -45,25 +61,224 @@ pub enum EmilyClientError {
     #[error("invalid URL: host is required: {0}")]
     InvalidUrlHostRequired(String),
 
-    /// An error occurred while getting a deposit request
-    #[error("error getting a deposit: {0}")]
-    GetDeposit(EmilyError<deposit_api::GetDepositError>),
+    /// Emily rejected the request because it conflicts with the state of
+    /// the resource in Emily (409).
+    #[error("request conflicts with the state in Emily: {message}")]
+    Conflict {
+        /// The message that Emily responded with.
+        message: String,
+    },
+
+    /// Emily, or the API gateway in front of it, rejected the request
+    /// because its body is too large (413).
+    #[error("request body too large for Emily")]
+    PayloadTooLarge,
+
+    /// The requested resource does not exist in Emily (404).
+    #[error("resource not found in Emily")]
+    NotFound,
+
+    /// Emily rejected the request as invalid (any other 4xx).
+    #[error("request rejected by Emily with status {status}: {message}")]
+    Validation {
+        /// The HTTP status code of the response.
+        status: u16,
+        /// The message that Emily responded with.
+        message: String,
+    },
+
+    /// Emily is throttling our requests (429).
+    #[error("rate limited by Emily")]
+    RateLimited {
+        /// How long Emily asked us to wait before trying again. The
+        /// generated client does not expose response headers, so this is
+        /// only known if the response body includes a `retryAfter` field
+        /// with a number of seconds.
+        retry_after: Option<Duration>,
+    },
+
+    /// Emily failed to handle the request (5xx).
+    #[error("Emily failed with status {status}: {message}")]
+    Server {
+        /// The HTTP status code of the response.
+        status: u16,
+        /// The message that Emily responded with.
+        message: String,
+    },
+
+    /// The request could not be sent or the response could not be read.
+    #[error("could not reach Emily: {0}")]
+    Transport(String),
+
+    /// Emily responded with a body that we could not deserialize.
+    #[error("could not deserialize the response from Emily: {0}")]
+    InvalidResponse(#[source] serde_json::Error),
+}
+
+impl EmilyClientError {
+    /// Whether the request that failed with this error may succeed if it
+    /// is sent again.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. } | Self::Server { .. } | Self::Transport(_)
+        )
+    }
+}
+
+impl<T> From<EmilyError<T>> for EmilyClientError {
+    fn from(error: EmilyError<T>) -> Self {
+        let ResponseContent { status, content, .. } = match error {
+            EmilyError::ResponseError(response) => response,
+            EmilyError::Reqwest(error) => return Self::Transport(error.to_string()),
+            EmilyError::Io(error) => return Self::Transport(error.to_string()),
+            EmilyError::Serde(error) => return Self::InvalidResponse(error),
+        };
+
+        // Emily's error bodies are JSON objects that usually carry a
+        // `message` field. If there is none then we keep the whole body.
+        let body: Option<serde_json::Value> = serde_json::from_str(&content).ok();
+        let message = body
+            .as_ref()
+            .and_then(|body| body.get("message"))
+            .and_then(serde_json::Value::as_str)
+            .map_or(content, str::to_string);
+
+        match status.as_u16() {
+            404 => Self::NotFound,
+            409 => Self::Conflict { message },
+            413 => Self::PayloadTooLarge,
+            429 => Self::RateLimited {
+                retry_after: body
+                    .as_ref()
+                    .and_then(|body| body.get("retryAfter"))
+                    .and_then(serde_json::Value::as_u64)
+                    .map(Duration::from_secs),
+            },
+            code if status.is_server_error() => Self::Server { status: code, message },
+            code => Self::Validation { status: code, message },
+        }
+    }
+}
+
+/// The maximum number of updates sent to Emily in a single update deposits
+/// or update withdrawals request. Larger batches are split into multiple
+/// requests.
+pub const MAX_UPDATES_PER_REQUEST: usize = 100;
+
+/// The maximum size, in bytes, of the body of a single update deposits or
+/// update withdrawals request. This stays well below the 6 MB payload
+/// limit of AWS Lambda, which is lower than the 10 MB limit of API
+/// Gateway.
+pub const MAX_UPDATE_REQUEST_BYTES: usize = 5_000_000;
+
+/// The number of bytes that we budget for the JSON object that wraps the
+/// updates in an update request, like `{"deposits":[...]}`.
+const UPDATE_REQUEST_ENVELOPE_BYTES: usize = 32;
+
+/// The maximum number of pages fetched from Emily when listing deposits
+/// with a given status. This bounds the time spent polling Emily if it
+/// keeps handing out pagination tokens.
+pub const MAX_DEPOSIT_PAGES: usize = 100;
+
+/// The maximum number of chainstates sent to Emily in a single bulk
+/// chainstate request. This is the most that Emily accepts.
+pub const MAX_CHAINSTATES_PER_BULK_REQUEST: usize = 1000;
+
+/// Deposit requests fetched from Emily.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchedDeposits {
+    /// The deposit requests, in the order that Emily returned them.
+    pub deposits: Vec<CreateDepositRequest>,
+    /// Whether we stopped before fetching the last page of deposits,
+    /// either because we hit [`MAX_DEPOSIT_PAGES`], the pagination
+    /// timeout, or an error when fetching a page.
+    pub truncated: bool,
+}
+
+/// The policy for retrying failed requests to Emily.
+///
+/// Requests are retried when Emily responds with a 429 or a 5xx status
+/// code, or when the request could not be sent at all. Any other error,
+/// like a 4xx validation error, is returned right away. All the requests
+/// that the signer makes to Emily are idempotent, so a request that
+/// reached Emily before failing can safely be sent again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts for a request, including the first
+    /// one.
+    pub max_attempts: u32,
+    /// The delay before the first retry. The delay doubles after each
+    /// retry.
+    pub initial_delay: Duration,
+    /// The upper bound for the delay between two attempts.
+    pub max_delay: Duration,
+}
 
-    /// An error occurred while getting deposits
-    #[error("error getting deposits: {0}")]
-    GetDeposits(EmilyError<deposit_api::GetDepositsError>),
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
 
-    /// An error occurred while updating deposits
-    #[error("error updating deposits: {0}")]
-    UpdateDeposits(EmilyError<deposit_api::UpdateDepositsError>),
+impl RetryPolicy {
+    /// Returns the delay before the retry that follows the given attempt,
+    /// where the first attempt is attempt 1.
+    fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
 
-    /// An error occurred while updating withdrawals
-    #[error("error updating withdrawals: {0}")]
-    UpdateWithdrawals(EmilyError<withdrawal_api::UpdateWithdrawalsError>),
+/// Limits on the size of a single update request to Emily.
+///
+/// Batches of updates are split into requests that respect both limits.
+/// If Emily still rejects a request as too large, the client halves the
+/// number of updates per request for the rest of the batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateRequestLimits {
+    /// The maximum number of updates in a single request.
+    pub max_updates: NonZeroUsize,
+    /// The maximum size, in bytes, of the JSON body of a single request.
+    /// A single update that is larger than this is still sent on its own.
+    pub max_body_bytes: NonZeroUsize,
+}
 
-    /// An error occurred while getting limits
-    #[error("error getting limits: {0}")]
-    GetLimits(EmilyError<limits_api::GetLimitsError>),
+impl Default for UpdateRequestLimits {
+    fn default() -> Self {
+        Self {
+            max_updates: NonZeroUsize::new(MAX_UPDATES_PER_REQUEST).unwrap_or(NonZeroUsize::MIN),
+            max_body_bytes: NonZeroUsize::new(MAX_UPDATE_REQUEST_BYTES)
+                .unwrap_or(NonZeroUsize::MIN),
+        }
+    }
+}
+
+impl UpdateRequestLimits {
+    /// Returns the number of leading `updates` that fit in one request,
+    /// given that a request may have at most `max_updates` updates. This
+    /// is at least one if there are any updates.
+    fn chunk_len<T: serde::Serialize>(&self, updates: &[T], max_updates: usize) -> usize {
+        let max_updates = max_updates.min(self.max_updates.get());
+        let mut body_bytes = UPDATE_REQUEST_ENVELOPE_BYTES;
+        let mut len = 0;
+        for update in updates.iter().take(max_updates) {
+            // Each update after the first one is preceded by a comma.
+            let update_bytes = serde_json::to_string(update).map_or(0, |json| json.len()) + 1;
+            body_bytes += update_bytes;
+            if len > 0 && body_bytes > self.max_body_bytes.get() {
+                break;
+            }
+            len += 1;
+        }
+        len
+    }
 }
 
 /// Trait describing the interactions with Emily API.
@@ -76,16 +291,30 @@ pub trait EmilyInteract: Sync + Send {
         output_index: u32,
     ) -> impl std::future::Future<Output = Result<Option<CreateDepositRequest>, Error>> + Send;
 
-    /// Get pending and accepted deposits to process from Emily.
+    /// Get pending and accepted deposits to process from Emily, following
+    /// the pagination tokens until all pages have been fetched.
     fn get_deposits(
         &self,
-    ) -> impl std::future::Future<Output = Result<Vec<CreateDepositRequest>, Error>> + Send;
+    ) -> impl std::future::Future<Output = Result<FetchedDeposits, Error>> + Send;
 
-    /// Get pending deposits with a specific status from Emily.
+    /// Get deposits with a specific status from Emily, following the
+    /// pagination tokens until all pages have been fetched.
     fn get_deposits_with_status(
         &self,
         status: Status,
-    ) -> impl std::future::Future<Output = Result<Vec<CreateDepositRequest>, Error>> + Send;
+    ) -> impl std::future::Future<Output = Result<FetchedDeposits, Error>> + Send;
+
+    /// Get a withdrawal request from Emily.
+    fn get_withdrawal(
+        &self,
+        request_id: u64,
+    ) -> impl std::future::Future<Output = Result<Option<Withdrawal>, Error>> + Send;
+
+    /// Get withdrawal requests with a specific status from Emily.
+    fn get_withdrawals(
+        &self,
+        status: Status,
+    ) -> impl std::future::Future<Output = Result<Vec<WithdrawalInfo>, Error>> + Send;
 
     /// Update accepted deposits after their sweep bitcoin transaction has been
     /// confirmed (but before being finalized -- the stacks transaction minting
@@ -128,6 +357,13 @@ pub struct EmilyClient {
     /// Regardless of the page_size setting, responses are always capped at 1 MB total size.
     /// If None, only the 1 MB cap applies.
     page_size: Option<u32>,
+    /// The policy for retrying failed requests.
+    retry_policy: RetryPolicy,
+    /// The limits on the size of update requests.
+    update_limits: UpdateRequestLimits,
+    /// The public key of this signer, attached to the status updates sent
+    /// to Emily that aren't attributed to a signer already.
+    updated_by: Option<String>,
 }
 
 impl EmilyClient {
@@ -180,9 +416,230 @@ impl EmilyClient {
             // This limitation exists because Emily needs to pass the parameter
             // to DynamoDB's as a i32.
             page_size: page_size.map(|size| size as u32),
+            retry_policy: RetryPolicy::default(),
+            update_limits: UpdateRequestLimits::default(),
+            updated_by: None,
         })
     }
 
+    /// Set the policy for retrying failed requests.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the limits on the size of update requests.
+    pub fn with_update_limits(mut self, update_limits: UpdateRequestLimits) -> Self {
+        self.update_limits = update_limits;
+        self
+    }
+
+    /// Attribute the status updates sent by this client to the signer
+    /// with the given public key.
+    pub fn with_updated_by(mut self, public_key: PublicKey) -> Self {
+        self.updated_by = Some(public_key.to_string());
+        self
+    }
+
+    /// Backfill the chainstates in Emily with the canonical stacks blocks
+    /// in the database, so that a fresh Emily catches up with the chain
+    /// without replaying every block one at a time.
+    ///
+    /// The chainstates start one height above the chain tip in Emily, or
+    /// at the earliest stored block if Emily has no chain tip, and end at
+    /// the stacks chain tip. They are sent in bulk requests of at most
+    /// [`MAX_CHAINSTATES_PER_BULK_REQUEST`] chainstates each, and the
+    /// number of chainstates that Emily applied is returned. The requests
+    /// are not retried, since Emily rejects a replayed bulk request.
+    pub async fn backfill_chainstates<D: DbRead>(&self, db: &D) -> Result<u64, Error> {
+        let Some(bitcoin_chain_tip) = db.get_bitcoin_canonical_chain_tip().await? else {
+            return Ok(0);
+        };
+        let Some(stacks_chain_tip) = db.get_stacks_chain_tip(&bitcoin_chain_tip).await? else {
+            return Ok(0);
+        };
+        let emily_chain_tip = self
+            .with_retry("get_chain_tip", || {
+                chainstate_api::get_chain_tip(&self.config)
+            })
+            .await?;
+        // Emily reports a chain tip at height zero when it has none.
+        let start_height = match emily_chain_tip.stacks_block_height {
+            0 => 0,
+            height => height + 1,
+        };
+
+        // Walk the stacks blockchain back from its tip, stopping at the
+        // chain tip in Emily or at the first block that isn't stored.
+        let mut blocks = Vec::new();
+        let mut next_block = Some(stacks_chain_tip);
+        while let Some(block) = next_block {
+            if *block.block_height < start_height {
+                break;
+            }
+            next_block = db.get_stacks_block(&block.parent_hash).await?;
+            blocks.push(block);
+        }
+
+        let mut bitcoin_block_heights: HashMap<BitcoinBlockHash, Option<u64>> = HashMap::new();
+        let mut chainstates = Vec::with_capacity(blocks.len());
+        for block in blocks.into_iter().rev() {
+            let bitcoin_block_height = match bitcoin_block_heights.get(&block.bitcoin_anchor) {
+                Some(height) => *height,
+                None => {
+                    let height = db
+                        .get_bitcoin_block(&block.bitcoin_anchor)
+                        .await?
+                        .map(|bitcoin_block| *bitcoin_block.block_height);
+                    bitcoin_block_heights.insert(block.bitcoin_anchor, height);
+                    height
+                }
+            };
+            chainstates.push(Chainstate {
+                stacks_block_hash: block.block_hash.to_string(),
+                stacks_block_height: *block.block_height,
+                bitcoin_block_height: Some(bitcoin_block_height),
+            });
+        }
+
+        let mut applied = 0;
+        for chunk in chainstates.chunks(MAX_CHAINSTATES_PER_BULK_REQUEST) {
+            let request = SetChainstatesBulkRequestBody::new(chunk.to_vec());
+            let instant = Instant::now();
+            let result = chainstate_api::set_chainstates_bulk(&self.config, request)
+                .await
+                .map_err(EmilyClientError::from);
+            Metrics::record_emily_request("set_chainstates_bulk", &result, instant.elapsed());
+            applied += result?.applied;
+        }
+
+        tracing::info!(applied, start_height, "backfilled chainstates in Emily");
+        Ok(applied)
+    }
+
+    /// Fill in the signer of the given updates, leaving the ones that are
+    /// already attributed untouched.
+    fn attribute_updates<T>(
+        &self,
+        updates: &mut [T],
+        updated_by: impl Fn(&mut T) -> &mut Option<Option<String>>,
+    ) {
+        let Some(public_key) = self.updated_by.as_ref() else {
+            return;
+        };
+        for update in updates {
+            let updated_by = updated_by(update);
+            if updated_by.is_none() {
+                *updated_by = Some(Some(public_key.clone()));
+            }
+        }
+    }
+
+    /// Send the given updates to Emily in as many requests as it takes to
+    /// stay within the update limits of this client, returning the
+    /// responses of all requests in order.
+    ///
+    /// Each request is retried according to the retry policy. If Emily
+    /// rejects a request as too large then we halve the number of updates
+    /// per request and carry on from the rejected request. If a request
+    /// fails otherwise then the requests before it were still applied.
+    /// Updates are idempotent, so the whole batch can be retried.
+    async fn send_in_chunks<T, R, E, F, Fut>(
+        &self,
+        operation: &'static str,
+        updates: &[T],
+        mut send: F,
+    ) -> Result<Vec<R>, Error>
+    where
+        T: serde::Serialize,
+        F: FnMut(&[T]) -> Fut,
+        Fut: Future<Output = Result<Vec<R>, EmilyError<E>>>,
+    {
+        let mut responses = Vec::with_capacity(updates.len());
+        let mut max_updates = self.update_limits.max_updates.get();
+        let mut remaining = updates;
+        let mut requests = 0;
+
+        while !remaining.is_empty() {
+            let (chunk, rest) =
+                remaining.split_at(self.update_limits.chunk_len(remaining, max_updates));
+            requests += 1;
+            match self.with_retry(operation, || send(chunk)).await {
+                Ok(response) => {
+                    responses.extend(response);
+                    remaining = rest;
+                }
+                Err(EmilyClientError::PayloadTooLarge) if chunk.len() > 1 => {
+                    max_updates = chunk.len() / 2;
+                    tracing::warn!(
+                        operation,
+                        updates = chunk.len(),
+                        max_updates,
+                        "Emily rejected the request as too large, splitting it"
+                    );
+                }
+                Err(error) => return Err(Error::EmilyApi(error)),
+            }
+        }
+
+        tracing::debug!(
+            operation,
+            updates = updates.len(),
+            requests,
+            "sent updates to Emily"
+        );
+        Ok(responses)
+    }
+
+    /// Run the given request, retrying it according to the retry policy
+    /// of this client.
+    ///
+    /// Only transient errors are retried. If Emily tells us how long to
+    /// wait when rate limiting us, then we wait at least that long, up to
+    /// the maximum delay of the retry policy.
+    async fn with_retry<T, E, F, Fut>(
+        &self,
+        operation: &'static str,
+        mut request: F,
+    ) -> Result<T, EmilyClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, EmilyError<E>>>,
+    {
+        let span =
+            tracing::debug_span!("emily_request", operation, attempts = tracing::field::Empty);
+        let retry_policy = self.retry_policy;
+        async move {
+            let mut attempt = 1;
+            loop {
+                tracing::Span::current().record("attempts", attempt);
+                let instant = Instant::now();
+                let result = request().await.map_err(EmilyClientError::from);
+                Metrics::record_emily_request(operation, &result, instant.elapsed());
+
+                let error = match result {
+                    Ok(value) => return Ok(value),
+                    Err(error) => error,
+                };
+                if attempt >= retry_policy.max_attempts || !error.is_transient() {
+                    return Err(error);
+                }
+
+                let delay = match error {
+                    EmilyClientError::RateLimited { retry_after: Some(retry_after) } => retry_after
+                        .max(retry_policy.delay_after(attempt))
+                        .min(retry_policy.max_delay),
+                    _ => retry_policy.delay_after(attempt),
+                };
+                tracing::warn!(%error, attempt, ?delay, "request to Emily failed, retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
     fn parse_deposit(deposit: &DepositInfo) -> Result<CreateDepositRequest, Error> {
         Ok(CreateDepositRequest {
             outpoint: OutPoint {
@@ -206,16 +663,16 @@ impl EmilyInteract for EmilyClient {
         let txid_str = txid.to_string();
         let index = output_index.to_string();
 
-        let resp = deposit_api::get_deposit(&self.config, &txid_str, &index).await;
+        let resp = self
+            .with_retry("get_deposit", || {
+                deposit_api::get_deposit(&self.config, &txid_str, &index)
+            })
+            .await;
 
         let deposit = match resp {
             Ok(deposit) => deposit,
-            Err(EmilyError::ResponseError(ResponseContent { status, .. }))
-                if status.as_u16() == 404 =>
-            {
-                return Ok(None);
-            }
-            error => error.map_err(EmilyClientError::GetDeposit)?,
+            Err(EmilyClientError::NotFound) => return Ok(None),
+            Err(error) => return Err(error.into()),
         };
 
         Ok(Some(CreateDepositRequest {
@@ -230,7 +687,7 @@ impl EmilyInteract for EmilyClient {
         }))
     }
 
-    async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
+    async fn get_deposits(&self) -> Result<FetchedDeposits, Error> {
         let pending_deposits = self.get_deposits_with_status(Status::Pending).await;
         let accepted_deposits = self.get_deposits_with_status(Status::Accepted).await;
 
@@ -239,49 +696,56 @@ impl EmilyInteract for EmilyClient {
                 // If both calls fail, return the error from the first call
                 Err(pending_err)
             }
-            (Ok(pending), Err(accepted_err)) => {
+            (Ok(mut pending), Err(accepted_err)) => {
                 // If the pending call succeeds, return the pending deposits
                 tracing::warn!("failed to fetch accepted deposits: {:?}", accepted_err);
+                pending.truncated = true;
                 Ok(pending)
             }
-            (Err(pending_err), Ok(accepted)) => {
+            (Err(pending_err), Ok(mut accepted)) => {
                 // If the pending call fails, return the accepted deposits
                 tracing::warn!("failed to fetch pending deposits: {:?}", pending_err);
+                accepted.truncated = true;
                 Ok(accepted)
             }
             (Ok(mut pending), Ok(mut accepted)) => {
                 // Combine the results
-                pending.append(&mut accepted);
+                pending.deposits.append(&mut accepted.deposits);
+                pending.truncated |= accepted.truncated;
                 Ok(pending)
             }
         }
     }
 
-    async fn get_deposits_with_status(
-        &self,
-        status: Status,
-    ) -> Result<Vec<CreateDepositRequest>, Error> {
+    async fn get_deposits_with_status(&self, status: Status) -> Result<FetchedDeposits, Error> {
         let mut all_deposits = Vec::new();
         let mut next_token: Option<String> = None;
+        let mut pages = 0;
         let start_time = Instant::now();
-        loop {
-            let resp = match deposit_api::get_deposits(
-                &self.config,
-                status,
-                next_token.as_deref(),
-                self.page_size,
-            )
-            .await
+        let truncated = loop {
+            let resp = match self
+                .with_retry("get_deposits", || {
+                    deposit_api::get_deposits(
+                        &self.config,
+                        status,
+                        next_token.as_deref(),
+                        self.page_size,
+                        None,
+                        None,
+                    )
+                })
+                .await
             {
                 Ok(resp) => resp,
                 Err(e) => {
                     if all_deposits.is_empty() {
-                        return Err(Error::EmilyApi(EmilyClientError::GetDeposits(e)));
+                        return Err(Error::EmilyApi(e));
                     }
                     tracing::warn!("failed to fetch page of deposits: {:?}", e);
-                    break;
+                    break true;
                 }
             };
+            pages += 1;
             // Convert each DepositInfo to our CreateDepositRequest
             for deposit in resp.deposits.iter() {
                 match Self::parse_deposit(deposit) {
@@ -297,7 +761,16 @@ impl EmilyInteract for EmilyClient {
             // If more pages exist, loop again; otherwise stop
             match resp.next_token.flatten() {
                 Some(token) => next_token = Some(token),
-                None => break,
+                None => break false,
+            }
+
+            if pages >= MAX_DEPOSIT_PAGES {
+                tracing::warn!(
+                    "page limit fetching deposits, breaking at page {:?}, fetched {} deposits",
+                    next_token,
+                    all_deposits.len()
+                );
+                break true;
             }
 
             if start_time.elapsed() > self.pagination_timeout {
@@ -306,26 +779,99 @@ impl EmilyInteract for EmilyClient {
                     next_token,
                     all_deposits.len()
                 );
+                break true;
+            }
+        };
+
+        Ok(FetchedDeposits {
+            deposits: all_deposits,
+            truncated,
+        })
+    }
+
+    async fn get_withdrawal(&self, request_id: u64) -> Result<Option<Withdrawal>, Error> {
+        let resp = self
+            .with_retry("get_withdrawal", || {
+                withdrawal_api::get_withdrawal(&self.config, request_id)
+            })
+            .await;
+
+        match resp {
+            Ok(withdrawal) => Ok(Some(withdrawal)),
+            Err(EmilyClientError::NotFound) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn get_withdrawals(&self, status: Status) -> Result<Vec<WithdrawalInfo>, Error> {
+        let mut all_withdrawals = Vec::new();
+        let mut next_token: Option<String> = None;
+        let start_time = Instant::now();
+        loop {
+            let resp = match self
+                .with_retry("get_withdrawals", || {
+                    withdrawal_api::get_withdrawals(
+                        &self.config,
+                        status,
+                        next_token.as_deref(),
+                        self.page_size,
+                    )
+                })
+                .await
+            {
+                Ok(resp) => resp,
+                Err(e) => {
+                    if all_withdrawals.is_empty() {
+                        return Err(Error::EmilyApi(e));
+                    }
+                    tracing::warn!("failed to fetch page of withdrawals: {:?}", e);
+                    break;
+                }
+            };
+            all_withdrawals.extend(resp.withdrawals);
+
+            // If more pages exist, loop again; otherwise stop
+            match resp.next_token.flatten() {
+                Some(token) => next_token = Some(token),
+                None => break,
+            }
+
+            if start_time.elapsed() > self.pagination_timeout {
+                tracing::warn!(
+                    "timeout fetching withdrawals, breaking at page {:?}, fetched {} withdrawals",
+                    next_token,
+                    all_withdrawals.len()
+                );
                 break;
             }
         }
 
-        Ok(all_deposits)
+        Ok(all_withdrawals)
     }
 
     async fn update_deposits(
         &self,
-        update_deposits: Vec<DepositUpdate>,
+        mut update_deposits: Vec<DepositUpdate>,
     ) -> Result<UpdateDepositsResponse, Error> {
         if update_deposits.is_empty() {
             return Ok(UpdateDepositsResponse { deposits: vec![] });
         }
+        self.attribute_updates(&mut update_deposits, |update| &mut update.updated_by);
+
+        let span = tracing::debug_span!("emily_update_deposits", updates = update_deposits.len());
+        let deposits = self
+            .send_in_chunks("update_deposits", &update_deposits, |chunk| {
+                let update_request = UpdateDepositsRequestBody { deposits: chunk.to_vec() };
+                async move {
+                    deposit_api::update_deposits(&self.config, update_request)
+                        .await
+                        .map(|response| response.deposits)
+                }
+            })
+            .instrument(span)
+            .await?;
 
-        let update_request = UpdateDepositsRequestBody { deposits: update_deposits };
-        deposit_api::update_deposits(&self.config, update_request)
-            .await
-            .map_err(EmilyClientError::UpdateDeposits)
-            .map_err(Error::EmilyApi)
+        Ok(UpdateDepositsResponse { deposits })
     }
 
     async fn accept_withdrawals<'a>(
@@ -343,6 +889,7 @@ impl EmilyInteract for EmilyClient {
                 fulfillment: None,
                 status: Status::Accepted,
                 status_message: "".to_string(),
+                updated_by: None,
             })
             .collect();
 
@@ -365,6 +912,7 @@ impl EmilyInteract for EmilyClient {
                 status: Status::Accepted,
                 fulfillment: None,
                 status_message: "".to_string(),
+                updated_by: None,
             })
             .collect();
 
@@ -373,25 +921,36 @@ impl EmilyInteract for EmilyClient {
 
     async fn update_withdrawals(
         &self,
-        update_withdrawals: Vec<WithdrawalUpdate>,
+        mut update_withdrawals: Vec<WithdrawalUpdate>,
     ) -> Result<UpdateWithdrawalsResponse, Error> {
         if update_withdrawals.is_empty() {
             return Ok(UpdateWithdrawalsResponse { withdrawals: vec![] });
         }
+        self.attribute_updates(&mut update_withdrawals, |update| &mut update.updated_by);
+
+        let span = tracing::debug_span!(
+            "emily_update_withdrawals",
+            updates = update_withdrawals.len()
+        );
+        let withdrawals = self
+            .send_in_chunks("update_withdrawals", &update_withdrawals, |chunk| {
+                let update_request = UpdateWithdrawalsRequestBody { withdrawals: chunk.to_vec() };
+                async move {
+                    withdrawal_api::update_withdrawals(&self.config, update_request)
+                        .await
+                        .map(|response| response.withdrawals)
+                }
+            })
+            .instrument(span)
+            .await?;
 
-        let update_request = UpdateWithdrawalsRequestBody {
-            withdrawals: update_withdrawals,
-        };
-        withdrawal_api::update_withdrawals(&self.config, update_request)
-            .await
-            .map_err(EmilyClientError::UpdateWithdrawals)
-            .map_err(Error::EmilyApi)
+        Ok(UpdateWithdrawalsResponse { withdrawals })
     }
 
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
-        let limits = limits_api::get_limits(&self.config)
+        let limits = self
+            .with_retry("get_limits", || limits_api::get_limits(&self.config))
             .await
-            .map_err(EmilyClientError::GetLimits)
             .map_err(Error::EmilyApi)?;
 
         let total_cap = limits.peg_cap.flatten().map(Amount::from_sat);
@@ -425,26 +984,38 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
     ) -> Result<Option<CreateDepositRequest>, Error> {
         self.exec(|client, _| client.get_deposit(txid, output_index))
             .await
+            .with_context(|| {
+                let outpoint = OutPoint::new((*txid).into(), output_index);
+                ErrorContext::DepositRequest("get_deposit", outpoint)
+            })
     }
 
-    async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
+    async fn get_deposits(&self) -> Result<FetchedDeposits, Error> {
         self.exec(|client, _| client.get_deposits()).await
     }
 
-    async fn get_deposits_with_status(
-        &self,
-        status: Status,
-    ) -> Result<Vec<CreateDepositRequest>, Error> {
+    async fn get_deposits_with_status(&self, status: Status) -> Result<FetchedDeposits, Error> {
         self.exec(|client, _| client.get_deposits_with_status(status))
             .await
     }
 
+    async fn get_withdrawal(&self, request_id: u64) -> Result<Option<Withdrawal>, Error> {
+        self.exec(|client, _| client.get_withdrawal(request_id))
+            .await
+            .with_context(|| ErrorContext::WithdrawalRequest("get_withdrawal", request_id))
+    }
+
+    async fn get_withdrawals(&self, status: Status) -> Result<Vec<WithdrawalInfo>, Error> {
+        self.exec(|client, _| client.get_withdrawals(status)).await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<DepositUpdate>,
     ) -> Result<UpdateDepositsResponse, Error> {
         self.exec(|client, _| client.update_deposits(update_deposits.clone()))
             .await
+            .with_context(|| ErrorContext::Operation("update_deposits"))
     }
 
     async fn accept_deposits<'a>(
@@ -469,21 +1040,34 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
     ) -> Result<UpdateWithdrawalsResponse, Error> {
         self.exec(|client, _| client.update_withdrawals(update_withdrawals.clone()))
             .await
+            .with_context(|| ErrorContext::Operation("update_withdrawals"))
     }
 
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
-        self.exec(|client, _| client.get_limits()).await
+        self.exec(|client, _| client.get_limits())
+            .await
+            .with_context(|| ErrorContext::Operation("get_limits"))
     }
 }
 
-impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
+impl TryFrom<&Settings> for ApiFallbackClient<EmilyClient> {
     type Error = Error;
 
-    fn try_from(config: &EmilyClientConfig) -> Result<Self, Self::Error> {
+    fn try_from(settings: &Settings) -> Result<Self, Self::Error> {
+        let config = &settings.emily;
+        let public_key = settings.signer.public_key();
         let clients = config
             .endpoints
             .iter()
-            .map(|url| EmilyClient::try_new(url, config.pagination_timeout, None))
+            .map(|url| {
+                let client = EmilyClient::try_new(url, config.pagination_timeout, None)?;
+                Ok(client
+                    .with_update_limits(UpdateRequestLimits {
+                        max_updates: config.max_updates_per_request,
+                        max_body_bytes: config.max_update_request_bytes,
+                    })
+                    .with_updated_by(public_key))
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         Self::new(clients).map_err(Into::into)
@@ -493,6 +1077,628 @@ impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keys::PrivateKey;
+    use crate::storage::DbWrite as _;
+    use crate::storage::in_memory::SharedStore;
+    use crate::storage::in_memory::Store;
+    use crate::storage::model::BitcoinBlock;
+    use crate::storage::model::StacksBlock;
+    use mockito::Server;
+    use serde_json::json;
+    use test_case::test_case;
+
+    const NO_DELAY_RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    fn test_client(server: &Server) -> EmilyClient {
+        let url = Url::parse(&server.url()).unwrap();
+        EmilyClient::try_new(&url, Duration::from_secs(1), None)
+            .unwrap()
+            .with_retry_policy(NO_DELAY_RETRY_POLICY)
+    }
+
+    fn deposit_update(vout: u32) -> DepositUpdate {
+        DepositUpdate {
+            bitcoin_tx_output_index: vout,
+            bitcoin_txid: "test_txid".to_string(),
+            fulfillment: None,
+            status: Status::Accepted,
+            status_message: String::new(),
+            updated_by: None,
+        }
+    }
+
+    fn withdrawal_update(request_id: u64) -> WithdrawalUpdate {
+        WithdrawalUpdate {
+            request_id,
+            fulfillment: None,
+            status: Status::Accepted,
+            status_message: String::new(),
+            updated_by: None,
+        }
+    }
+
+    fn deposits_page(vouts: &[u32], next_token: Option<&str>) -> String {
+        let deposits: Vec<_> = vouts
+            .iter()
+            .map(|vout| {
+                json!({
+                    "amount": 10_000,
+                    "bitcoinTxOutputIndex": vout,
+                    "bitcoinTxid": "11".repeat(32),
+                    "depositScript": "51",
+                    "lastUpdateBlockHash": "00".repeat(32),
+                    "lastUpdateHeight": 1,
+                    "recipient": "",
+                    "reclaimScript": "51",
+                    "status": "pending",
+                })
+            })
+            .collect();
+        json!({ "deposits": deposits, "nextToken": next_token }).to_string()
+    }
+
+    fn withdrawal_info(request_id: u64) -> serde_json::Value {
+        json!({
+            "amount": 10_000,
+            "lastUpdateBlockHash": "00".repeat(32),
+            "lastUpdateHeight": 1,
+            "recipient": "0014abababababababababababababababababababab",
+            "requestId": request_id,
+            "sender": "ST000000000000000000002AMW42H",
+            "stacksBlockHash": "00".repeat(32),
+            "stacksBlockHeight": 1,
+            "status": "pending",
+            "txid": "00".repeat(32),
+        })
+    }
+
+    fn response_error(status: u16, content: &str) -> EmilyError<()> {
+        EmilyError::ResponseError(ResponseContent {
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            content: content.to_string(),
+            entity: None,
+        })
+    }
+
+    #[test]
+    fn response_errors_map_to_typed_errors() {
+        let error = EmilyClientError::from(response_error(
+            404,
+            r#"{"error":"not_found","resource":"deposit","key":"abc:0"}"#,
+        ));
+        assert!(matches!(error, EmilyClientError::NotFound));
+
+        let error = EmilyClientError::from(response_error(409, r#"{"message":"DepositConflict"}"#));
+        assert!(
+            matches!(error, EmilyClientError::Conflict { message } if message == "DepositConflict")
+        );
+
+        let error = EmilyClientError::from(response_error(400, r#"{"message":"invalid body"}"#));
+        assert!(matches!(
+            error,
+            EmilyClientError::Validation { status: 400, message } if message == "invalid body"
+        ));
+
+        let error = EmilyClientError::from(response_error(403, "Forbidden"));
+        assert!(matches!(
+            error,
+            EmilyClientError::Validation { status: 403, message } if message == "Forbidden"
+        ));
+
+        let error = EmilyClientError::from(response_error(413, "Request Entity Too Large"));
+        assert!(matches!(error, EmilyClientError::PayloadTooLarge));
+
+        let error =
+            EmilyClientError::from(response_error(429, r#"{"message":"Too Many Requests"}"#));
+        assert!(matches!(
+            error,
+            EmilyClientError::RateLimited { retry_after: None }
+        ));
+
+        let error = EmilyClientError::from(response_error(429, r#"{"retryAfter":3}"#));
+        assert!(matches!(
+            error,
+            EmilyClientError::RateLimited { retry_after: Some(delay) } if delay == Duration::from_secs(3)
+        ));
+
+        let error = EmilyClientError::from(response_error(500, r#"{"message":"InternalServer"}"#));
+        assert!(matches!(
+            error,
+            EmilyClientError::Server { status: 500, message } if message == "InternalServer"
+        ));
+
+        let error = EmilyClientError::from(response_error(503, ""));
+        assert!(matches!(
+            error,
+            EmilyClientError::Server { status: 503, .. }
+        ));
+    }
+
+    #[test]
+    fn non_response_errors_map_to_typed_errors() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        let error = EmilyClientError::from(EmilyError::<()>::Io(io_error));
+        assert!(matches!(error, EmilyClientError::Transport(_)));
+
+        let serde_error = serde_json::from_str::<u64>("not json").unwrap_err();
+        let error = EmilyClientError::from(EmilyError::<()>::Serde(serde_error));
+        assert!(matches!(error, EmilyClientError::InvalidResponse(_)));
+    }
+
+    #[test_case(EmilyClientError::NotFound, false; "not found")]
+    #[test_case(EmilyClientError::Conflict { message: String::new() }, false; "conflict")]
+    #[test_case(EmilyClientError::Validation { status: 400, message: String::new() }, false; "validation")]
+    #[test_case(EmilyClientError::PayloadTooLarge, false; "payload too large")]
+    #[test_case(EmilyClientError::RateLimited { retry_after: None }, true; "rate limited")]
+    #[test_case(EmilyClientError::Server { status: 502, message: String::new() }, true; "server")]
+    #[test_case(EmilyClientError::Transport(String::new()), true; "transport")]
+    fn only_transient_errors_are_retried(error: EmilyClientError, expected: bool) {
+        assert_eq!(error.is_transient(), expected);
+    }
+
+    #[test_case(1, Duration::from_millis(100); "first retry")]
+    #[test_case(2, Duration::from_millis(200); "second retry")]
+    #[test_case(4, Duration::from_millis(800); "fourth retry")]
+    #[test_case(10, Duration::from_secs(5); "capped")]
+    #[test_case(u32::MAX, Duration::from_secs(5); "capped without overflow")]
+    fn retry_delay_backs_off_exponentially(attempt: u32, expected: Duration) {
+        assert_eq!(RetryPolicy::default().delay_after(attempt), expected);
+    }
+
+    #[tokio::test]
+    async fn update_deposits_retries_on_service_unavailable() {
+        let mut server = Server::new_async().await;
+        let unavailable = server
+            .mock("PUT", "/deposit")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("PUT", "/deposit")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "deposits": [] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = test_client(&server)
+            .update_deposits(vec![deposit_update(0)])
+            .await;
+
+        assert!(result.is_ok());
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_deposits_does_not_retry_client_errors() {
+        let mut server = Server::new_async().await;
+        let bad_request = server
+            .mock("PUT", "/deposit")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "message": "invalid request body" }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = test_client(&server)
+            .update_deposits(vec![deposit_update(0)])
+            .await;
+
+        assert!(result.is_err());
+        bad_request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_withdrawals_chunks_large_batches() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/withdrawal")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "withdrawals": [] }).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let updates = (0..150).map(withdrawal_update).collect();
+        let result = test_client(&server).update_withdrawals(updates).await;
+
+        assert!(result.is_ok());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn updates_are_attributed_to_the_signer() {
+        let public_key = PublicKey::from_private_key(&PrivateKey::new(&mut rand::rngs::OsRng));
+        let mut server = Server::new_async().await;
+        let deposits = server
+            .mock("PUT", "/deposit")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "deposits": [
+                    { "updatedBy": public_key.to_string() },
+                    { "updatedBy": "another-signer" },
+                ]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "deposits": [] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let withdrawals = server
+            .mock("PUT", "/withdrawal")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "withdrawals": [{ "updatedBy": public_key.to_string() }]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "withdrawals": [] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = test_client(&server).with_updated_by(public_key);
+        // Updates that already name their signer are sent as they are.
+        let attributed = DepositUpdate {
+            updated_by: Some(Some("another-signer".to_string())),
+            ..deposit_update(1)
+        };
+        let deposit_result = client
+            .update_deposits(vec![deposit_update(0), attributed])
+            .await;
+        let withdrawal_result = client.update_withdrawals(vec![withdrawal_update(0)]).await;
+
+        assert!(deposit_result.is_ok());
+        assert!(withdrawal_result.is_ok());
+        deposits.assert_async().await;
+        withdrawals.assert_async().await;
+    }
+
+    /// Store a bitcoin block at height 100 and a stacks chain from height
+    /// 1 to 5 anchored to it, returning the stacks blocks.
+    async fn store_stacks_chain(db: &SharedStore) -> Vec<StacksBlock> {
+        let bitcoin_block = BitcoinBlock {
+            block_hash: [1; 32].into(),
+            block_height: 100u64.into(),
+            parent_hash: [0; 32].into(),
+        };
+        db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+
+        let blocks: Vec<StacksBlock> = (1..=5u8)
+            .map(|height| StacksBlock {
+                block_hash: [height; 32].into(),
+                block_height: (height as u64).into(),
+                parent_hash: [height - 1; 32].into(),
+                bitcoin_anchor: bitcoin_block.block_hash,
+            })
+            .collect();
+        for block in blocks.iter() {
+            db.write_stacks_block(block).await.unwrap();
+        }
+        blocks
+    }
+
+    fn chainstate_json(block: &StacksBlock) -> serde_json::Value {
+        json!({
+            "stacksBlockHeight": *block.block_height,
+            "stacksBlockHash": block.block_hash.to_string(),
+            "bitcoinBlockHeight": 100,
+        })
+    }
+
+    #[test_case(0, 1; "emily without a chain tip")]
+    #[test_case(2, 3; "emily behind the stacks chain tip")]
+    #[test_case(5, 6; "emily at the stacks chain tip")]
+    #[tokio::test]
+    async fn backfill_chainstates_starts_above_emily_chain_tip(
+        emily_tip_height: u64,
+        first_height: u64,
+    ) {
+        let db = Store::new_shared();
+        let blocks = store_stacks_chain(&db).await;
+        let expected: Vec<serde_json::Value> = blocks
+            .iter()
+            .filter(|block| *block.block_height >= first_height)
+            .map(chainstate_json)
+            .collect();
+
+        let mut server = Server::new_async().await;
+        let chain_tip = server
+            .mock("GET", "/chainstate")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "stacksBlockHeight": emily_tip_height,
+                    "stacksBlockHash": "emily-tip",
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let bulk = server
+            .mock("POST", "/chainstates/bulk")
+            .match_body(mockito::Matcher::Json(json!({ "chainstates": expected })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "applied": expected.len(),
+                    "chaintip": chainstate_json(&blocks[4]),
+                })
+                .to_string(),
+            )
+            .expect(usize::from(!expected.is_empty()))
+            .create_async()
+            .await;
+
+        let applied = test_client(&server)
+            .backfill_chainstates(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(applied, expected.len() as u64);
+        chain_tip.assert_async().await;
+        bulk.assert_async().await;
+    }
+
+    /// The size of the body of an update deposits request with the given
+    /// number of updates from [`deposit_update`].
+    fn update_deposits_body_len(num_updates: u32) -> usize {
+        let deposits = (0..num_updates).map(deposit_update).collect();
+        serde_json::to_vec(&UpdateDepositsRequestBody { deposits })
+            .unwrap()
+            .len()
+    }
+
+    /// Mock `PUT /deposit` on the server such that it rejects bodies larger
+    /// than `max_body_len` with a 413, like API Gateway does, expecting the
+    /// given number of rejected and accepted requests.
+    async fn mock_update_deposits_with_body_limit(
+        server: &mut Server,
+        max_body_len: usize,
+        expected_rejected: usize,
+        expected_accepted: usize,
+    ) -> (mockito::Mock, mockito::Mock) {
+        let body_len = |request: &mockito::Request| request.body().map_or(0, Vec::len);
+        let rejected = server
+            .mock("PUT", "/deposit")
+            .match_request(move |request| body_len(request) > max_body_len)
+            .with_status(413)
+            .with_body(r#"{"message":"Request Entity Too Large"}"#)
+            .expect(expected_rejected)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("PUT", "/deposit")
+            .match_request(move |request| body_len(request) <= max_body_len)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "deposits": [] }).to_string())
+            .expect(expected_accepted)
+            .create_async()
+            .await;
+        (rejected, accepted)
+    }
+
+    #[test]
+    fn update_chunks_respect_count_and_size_limits() {
+        let updates: Vec<_> = (0..10).map(deposit_update).collect();
+        let update_len = serde_json::to_string(&updates[0]).unwrap().len() + 1;
+        let limits = |max_updates: usize, max_body_bytes: usize| UpdateRequestLimits {
+            max_updates: NonZeroUsize::new(max_updates).unwrap(),
+            max_body_bytes: NonZeroUsize::new(max_body_bytes).unwrap(),
+        };
+
+        let unlimited = limits(100, MAX_UPDATE_REQUEST_BYTES);
+        assert_eq!(unlimited.chunk_len(&updates, 100), 10);
+        assert_eq!(unlimited.chunk_len(&updates, 4), 4);
+        assert_eq!(
+            limits(3, MAX_UPDATE_REQUEST_BYTES).chunk_len(&updates, 100),
+            3
+        );
+
+        let three_updates = UPDATE_REQUEST_ENVELOPE_BYTES + 3 * update_len;
+        assert_eq!(limits(100, three_updates).chunk_len(&updates, 100), 3);
+        assert_eq!(limits(100, three_updates - 1).chunk_len(&updates, 100), 2);
+
+        // An update that is too large on its own is still sent.
+        assert_eq!(limits(100, 1).chunk_len(&updates, 100), 1);
+        assert_eq!(limits(100, 1).chunk_len::<DepositUpdate>(&[], 100), 0);
+    }
+
+    #[tokio::test]
+    async fn update_deposits_halves_requests_rejected_as_too_large() {
+        let mut server = Server::new_async().await;
+        // Two updates fit in a request but three do not.
+        let max_body_len = update_deposits_body_len(2);
+        let (rejected, accepted) =
+            mock_update_deposits_with_body_limit(&mut server, max_body_len, 2, 4).await;
+
+        // The batch of 8 is rejected, then the first half of 4 is rejected,
+        // then the updates go through in 4 requests of 2.
+        let updates = (0..8).map(deposit_update).collect();
+        let result = test_client(&server).update_deposits(updates).await;
+
+        assert!(result.is_ok());
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_deposits_splits_requests_by_size() {
+        let mut server = Server::new_async().await;
+        let max_body_len = update_deposits_body_len(2);
+        let (rejected, accepted) =
+            mock_update_deposits_with_body_limit(&mut server, max_body_len, 0, 3).await;
+
+        // The client's estimate of the body size includes some slack for
+        // the JSON object around the updates.
+        let max_body_bytes = max_body_len + UPDATE_REQUEST_ENVELOPE_BYTES;
+        let client = test_client(&server).with_update_limits(UpdateRequestLimits {
+            max_updates: NonZeroUsize::new(MAX_UPDATES_PER_REQUEST).unwrap(),
+            max_body_bytes: NonZeroUsize::new(max_body_bytes).unwrap(),
+        });
+        let updates = (0..5).map(deposit_update).collect();
+        let result = client.update_deposits(updates).await;
+
+        assert!(result.is_ok());
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn update_deposits_fails_if_a_single_update_is_too_large() {
+        let mut server = Server::new_async().await;
+        let (rejected, accepted) = mock_update_deposits_with_body_limit(&mut server, 1, 2, 0).await;
+
+        let updates = (0..2).map(deposit_update).collect();
+        let result = test_client(&server).update_deposits(updates).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::EmilyApi(EmilyClientError::PayloadTooLarge))
+        ));
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_deposits_with_status_fetches_all_pages() {
+        let mut server = Server::new_async().await;
+        let pages = [
+            (None, &[0, 1][..], Some("page-2")),
+            (Some("page-2"), &[2, 3][..], Some("page-3")),
+            (Some("page-3"), &[4][..], None),
+        ];
+        let mut mocks = Vec::new();
+        for (token, vouts, next_token) in pages {
+            let query = match token {
+                None => mockito::Matcher::Exact("status=pending".to_string()),
+                Some(token) => mockito::Matcher::AllOf(vec![
+                    mockito::Matcher::UrlEncoded("status".into(), "pending".into()),
+                    mockito::Matcher::UrlEncoded("nextToken".into(), token.into()),
+                ]),
+            };
+            let mock = server
+                .mock("GET", "/deposit")
+                .match_query(query)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(deposits_page(vouts, next_token))
+                .expect(1)
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        let fetched = test_client(&server)
+            .get_deposits_with_status(Status::Pending)
+            .await
+            .unwrap();
+
+        let vouts: Vec<u32> = fetched.deposits.iter().map(|d| d.outpoint.vout).collect();
+        assert_eq!(vouts, vec![0, 1, 2, 3, 4]);
+        assert!(!fetched.truncated);
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn get_deposits_with_status_stops_at_page_limit() {
+        let mut server = Server::new_async().await;
+        // Emily always says that there is another page.
+        let mock = server
+            .mock("GET", "/deposit")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(deposits_page(&[0], Some("next")))
+            .expect(MAX_DEPOSIT_PAGES)
+            .create_async()
+            .await;
+
+        let fetched = test_client(&server)
+            .get_deposits_with_status(Status::Pending)
+            .await
+            .unwrap();
+
+        assert_eq!(fetched.deposits.len(), MAX_DEPOSIT_PAGES);
+        assert!(fetched.truncated);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_withdrawals_fetches_all_pages() {
+        let mut server = Server::new_async().await;
+        let first_page = server
+            .mock("GET", "/withdrawal")
+            .match_query(mockito::Matcher::Exact("status=pending".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "withdrawals": [withdrawal_info(1), withdrawal_info(2)],
+                    "nextToken": "page-2",
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/withdrawal")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("status".into(), "pending".into()),
+                mockito::Matcher::UrlEncoded("nextToken".into(), "page-2".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "withdrawals": [withdrawal_info(3)] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let withdrawals = test_client(&server)
+            .get_withdrawals(Status::Pending)
+            .await
+            .unwrap();
+
+        let request_ids: Vec<u64> = withdrawals.iter().map(|w| w.request_id).collect();
+        assert_eq!(request_ids, vec![1, 2, 3]);
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_withdrawal_returns_none_when_not_found() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/withdrawal/42")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({ "error": "not_found", "resource": "withdrawal", "key": "42" }).to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let withdrawal = test_client(&server).get_withdrawal(42).await.unwrap();
+
+        assert!(withdrawal.is_none());
+        mock.assert_async().await;
+    }
 
     #[test]
     fn try_from_url_with_key() {
@@ -15,12 +15,24 @@ use crate::stacks::contracts::DepositValidationError;
 use crate::stacks::contracts::RotateKeysValidationError;
 use crate::stacks::contracts::WithdrawalAcceptValidationError;
 use crate::stacks::contracts::WithdrawalRejectValidationError;
+use crate::storage::model::BitcoinTxId;
 use crate::storage::model::SigHash;
 use crate::wsts_state_machine::StateMachineId;
 
 /// Top-level signer error
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// An error that happened during the operation described by the
+    /// context. The error is classified like its source.
+    #[error("{context}: {source}")]
+    WithContext {
+        /// The operation that failed, and what it was operating on.
+        context: ErrorContext,
+        /// The error that the operation failed with.
+        #[source]
+        source: Box<Error>,
+    },
+
     /// The length of bytes to write to an OP_RETURN output exceeds the maximum allowed size.
     #[error("OP_RETURN output size limit exceeded: {size} bytes, max allowed: {max_size} bytes")]
     OpReturnSizeLimitExceeded {
@@ -95,6 +107,10 @@ pub enum Error {
     #[error("timed-out trying to connect to bitcoin-core ZMQ endpoint: {0}")]
     BitcoinCoreZmqConnectTimeout(String),
 
+    /// There are no Bitcoin Core ZMQ endpoints to connect to.
+    #[error("no bitcoin-core ZMQ endpoints are configured")]
+    BitcoinCoreZmqNoEndpoints,
+
     /// An error was received from the Bitcoin Core ZMQ subscriber.
     #[error("error from bitcoin-core ZMQ: {0}")]
     BitcoinCoreZmq(#[source] bitcoincore_zmq::Error),
@@ -108,6 +124,25 @@ pub enum Error {
     #[error("protobuf field not encoded in field tag order")]
     ProtobufTagCodec,
 
+    /// This happens when a signed P2P message uses a version of the wire
+    /// format that is newer than the one supported by this signer.
+    #[error("unsupported signed message version: {0}")]
+    UnsupportedMessageVersion(u32),
+
+    /// The number of signer votes for a request does not match the number
+    /// of signers in the signer set.
+    #[error("got {num_votes} signer votes for a signer set with {num_signers} signers")]
+    SignerVotesLengthMismatch {
+        /// The number of votes that we have for the request.
+        num_votes: usize,
+        /// The number of signers in the signer set.
+        num_signers: usize,
+    },
+
+    /// The signer set has more signers than a signer bitmap can represent.
+    #[error("got a signer set with {0} signers, but at most {max} are supported", max = crate::MAX_KEYS)]
+    SignerSetTooLarge(usize),
+
     /// Attempted division by zero
     #[error("attempted division by zero")]
     DivideByZero,
@@ -287,6 +322,16 @@ pub enum Error {
     #[error("could not convert integer type to the signed version for storing in postgres {0}")]
     ConversionDatabaseInt(#[source] std::num::TryFromIntError),
 
+    /// This happens when a P2P peer ID that was read from the database
+    /// could not be parsed.
+    #[error("could not parse the P2P peer ID stored in the database: {0}")]
+    ParseDatabasePeerId(#[source] libp2p::identity::ParseError),
+
+    /// This happens when a P2P address that was read from the database
+    /// could not be parsed.
+    #[error("could not parse the P2P address stored in the database: {0}")]
+    ParseDatabaseMultiaddr(#[source] libp2p::multiaddr::Error),
+
     /// Parsing the Hex Error
     #[error("could not decode the bitcoin block: {0}")]
     DecodeBitcoinBlock(#[source] bitcoin::consensus::encode::Error),
@@ -320,6 +365,16 @@ pub enum Error {
     #[error("invalid aggregate key: {0}")]
     InvalidAggregateKey(#[source] secp256k1::Error),
 
+    /// This happens when we try to derive the aggregate key of a signer
+    /// set that has no public keys.
+    #[error("cannot derive the aggregate key of an empty signer set")]
+    EmptySignerSet,
+
+    /// This happens when we try to derive the aggregate key of a signer
+    /// set where the same public key is included more than once.
+    #[error("the public key {0} is included more than once in the signer set")]
+    DuplicateSignerSetKey(crate::keys::PublicKey),
+
     /// This happens when we realize that the lock-time in the reclaim
     /// script disables the OP_CSV check.
     #[error("invalid lock-time: {0}")]
@@ -391,6 +446,51 @@ pub enum Error {
     #[error("outpoint missing from transaction when assessing fee {0}")]
     OutPointMissing(bitcoin::OutPoint),
 
+    /// The amount of the deposit request does not match the amount of
+    /// the deposit UTXO that was swept in, so we do not know how much
+    /// sBTC to mint.
+    #[error(
+        "deposit {outpoint} is for {deposit_amount} sats, but {swept_amount} sats were swept in"
+    )]
+    DepositMintAmountMismatch {
+        /// The outpoint of the deposit request.
+        outpoint: bitcoin::OutPoint,
+        /// The amount of the deposit request.
+        deposit_amount: u64,
+        /// The amount of the deposit UTXO spent by the sweep transaction.
+        swept_amount: u64,
+    },
+
+    /// The fee assessed to the deposit request is at least as large as
+    /// the deposit amount, so there would be no sBTC to mint.
+    #[error(
+        "no sBTC to mint for deposit {outpoint}: amount {amount} sats, assessed fee {assessed_fee} sats"
+    )]
+    DepositMintNotPositive {
+        /// The outpoint of the deposit request.
+        outpoint: bitcoin::OutPoint,
+        /// The amount of the deposit request.
+        amount: u64,
+        /// The fee assessed to the deposit request.
+        assessed_fee: u64,
+    },
+
+    /// The sBTC minted for the deposits swept in by a sweep transaction
+    /// would exceed the bitcoin that the signers received from them.
+    #[error(
+        "sBTC minted for sweep {sweep_txid} would be {minted} sats, more than the {received} sats received"
+    )]
+    DepositMintExceedsSweep {
+        /// The ID of the sweep transaction.
+        sweep_txid: bitcoin::Txid,
+        /// The total amount of sBTC that would be minted for the deposits
+        /// swept in by the transaction.
+        minted: u64,
+        /// The amount of bitcoin that the signers kept from the deposits
+        /// swept in by the transaction.
+        received: u64,
+    },
+
     /// This should never happen.
     #[error("output_index missing from block when assessing fee, txid: {0}, vout: {1}")]
     VoutMissing(bitcoin::Txid, u32),
@@ -566,6 +666,10 @@ pub enum Error {
     #[error("invalid ECDSA signature")]
     InvalidEcdsaSignature(#[source] secp256k1::Error),
 
+    /// Invalid Schnorr signature
+    #[error("invalid Schnorr signature")]
+    InvalidSchnorrSignature(#[source] secp256k1::Error),
+
     /// Codec error
     #[error("codec error: {0}")]
     Codec(#[from] codec::CodecError),
@@ -578,6 +682,11 @@ pub enum Error {
     #[error("encryption error")]
     Encryption,
 
+    /// The DKG shares for the given aggregate key could not be decrypted
+    /// with our private key.
+    #[error("could not decrypt the DKG shares for aggregate key {0}")]
+    DkgSharesDecryption(crate::keys::PublicKey),
+
     /// Invalid configuration
     #[error("invalid configuration")]
     InvalidConfiguration,
@@ -706,6 +815,43 @@ pub enum Error {
     #[error("the fee rate in the BitcoinPreSignRequest object is not greater than zero: {0}")]
     PreSignInvalidFeeRate(f64),
 
+    /// Indicates that this signer has already agreed to sign a different
+    /// transaction spending the signers' UTXO while the current bitcoin
+    /// block was the chain tip.
+    #[error("the signers' UTXO {outpoint} is already being spent by in-flight transaction {txid}")]
+    SignerUtxoAlreadyInFlight {
+        /// The outpoint of the signers' UTXO.
+        outpoint: bitcoin::OutPoint,
+        /// The ID of the in-flight transaction spending the signers' UTXO.
+        txid: BitcoinTxId,
+    },
+
+    /// Indicates that the BitcoinPreSignRequest object contains a fee rate
+    /// that exceeds the maximum fee rate that this signer will accept.
+    #[error(
+        "the fee rate in the BitcoinPreSignRequest object, {fee_rate}, exceeds the maximum of {max_fee_rate}"
+    )]
+    PreSignFeeRateTooHigh {
+        /// The fee rate in the request, in sats per virtual byte.
+        fee_rate: f64,
+        /// The maximum acceptable fee rate, in sats per virtual byte.
+        max_fee_rate: f64,
+    },
+
+    /// Indicates that the sweep transaction in the mempool cannot be
+    /// replaced, because a replacement would need a fee rate above the
+    /// maximum fee rate that the signers accept.
+    #[error(
+        "replacing the sweep transaction in the mempool needs a fee rate of at least {fee_rate}, which exceeds the maximum of {max_fee_rate}"
+    )]
+    ReplacementFeeRateTooHigh {
+        /// The minimum fee rate of a replacement transaction, in sats per
+        /// virtual byte.
+        fee_rate: f64,
+        /// The maximum acceptable fee rate, in sats per virtual byte.
+        max_fee_rate: f64,
+    },
+
     /// Error when deposit requests would exceed sBTC supply cap
     #[error(
         "total deposit amount ({total_amount} sats) would exceed sBTC supply cap (current max mintable is {max_mintable} sats)"
@@ -739,6 +885,88 @@ pub enum Error {
     Dummy,
 }
 
+/// The operation that failed with an [`Error`], along with the
+/// identifiers of what it was operating on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorContext {
+    /// An operation that is not about any particular item.
+    Operation(&'static str),
+    /// An operation on a bitcoin transaction.
+    BitcoinTransaction(&'static str, bitcoin::Txid),
+    /// An operation on a bitcoin block.
+    BitcoinBlock(&'static str, bitcoin::BlockHash),
+    /// An operation on a stacks transaction.
+    StacksTransaction(&'static str, blockstack_lib::burnchains::Txid),
+    /// An operation on a stacks block.
+    StacksBlock(&'static str, StacksBlockId),
+    /// An operation on a deposit request, identified by its outpoint.
+    DepositRequest(&'static str, bitcoin::OutPoint),
+    /// An operation on a withdrawal request, identified by its request ID.
+    WithdrawalRequest(&'static str, u64),
+}
+
+impl ErrorContext {
+    /// The name of the operation that failed.
+    pub fn operation(&self) -> &'static str {
+        match self {
+            Self::Operation(operation)
+            | Self::BitcoinTransaction(operation, _)
+            | Self::BitcoinBlock(operation, _)
+            | Self::StacksTransaction(operation, _)
+            | Self::StacksBlock(operation, _)
+            | Self::DepositRequest(operation, _)
+            | Self::WithdrawalRequest(operation, _) => operation,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Operation(operation) => write!(f, "{operation} failed"),
+            Self::BitcoinTransaction(operation, txid) => {
+                write!(f, "{operation} failed for bitcoin transaction {txid}")
+            }
+            Self::BitcoinBlock(operation, block_hash) => {
+                write!(f, "{operation} failed for bitcoin block {block_hash}")
+            }
+            Self::StacksTransaction(operation, txid) => {
+                write!(f, "{operation} failed for stacks transaction {txid}")
+            }
+            Self::StacksBlock(operation, block_id) => {
+                write!(f, "{operation} failed for stacks block {block_id}")
+            }
+            Self::DepositRequest(operation, outpoint) => {
+                write!(f, "{operation} failed for deposit request {outpoint}")
+            }
+            Self::WithdrawalRequest(operation, request_id) => {
+                write!(f, "{operation} failed for withdrawal request {request_id}")
+            }
+        }
+    }
+}
+
+/// Attach an [`ErrorContext`] to the error of a result.
+pub trait ResultExt<T> {
+    /// Wrap the error, if any, in an [`Error::WithContext`] with the
+    /// context returned by the given function.
+    fn with_context<F>(self, context: F) -> Result<T, Error>
+    where
+        F: FnOnce() -> ErrorContext;
+}
+
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: Into<Error>,
+{
+    fn with_context<F>(self, context: F) -> Result<T, Error>
+    where
+        F: FnOnce() -> ErrorContext,
+    {
+        self.map_err(|error| error.into().context(context()))
+    }
+}
+
 impl From<std::convert::Infallible> for Error {
     fn from(value: std::convert::Infallible) -> Self {
         match value {}
@@ -750,4 +978,195 @@ impl Error {
     pub fn wsts_coordinator(err: wsts::state_machine::coordinator::Error) -> Self {
         Error::WstsCoordinator(Box::new(err))
     }
+
+    /// Wrap this error in an [`Error::WithContext`] with the given
+    /// context.
+    pub fn context(self, context: ErrorContext) -> Self {
+        Error::WithContext {
+            context,
+            source: Box::new(self),
+        }
+    }
+
+    /// The error without any [`Error::WithContext`] wrappers.
+    pub fn without_context(&self) -> &Error {
+        match self {
+            Error::WithContext { source, .. } => source.without_context(),
+            error => error,
+        }
+    }
+
+    /// Whether the operation that failed with this error may succeed if
+    /// it is attempted again, because the error is due to a transient
+    /// problem like a network failure, an unavailable dependency or a
+    /// conflict with a concurrent database transaction.
+    ///
+    /// Errors that will happen again when the operation is repeated with
+    /// the same inputs, like validation or decoding errors, are terminal.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::WithContext { source, .. } => source.is_retryable(),
+            Error::SqlxQuery(error)
+            | Error::SqlxConnect(error)
+            | Error::SqlxBeginTransaction(error)
+            | Error::SqlxCommitTransaction(error)
+            | Error::SqlxRollbackTransaction(error) => sqlx_error_is_retryable(error),
+            Error::Reqwest(error)
+            | Error::StacksNodeRequest(error)
+            | Error::StacksNodeResponse(error)
+            | Error::UnexpectedStacksResponse(error) => reqwest_error_is_retryable(error),
+            Error::BitcoinCoreRpc(error)
+            | Error::BitcoinCoreGetBlock(error, _)
+            | Error::BitcoinCoreGetBlockHeader(error, _)
+            | Error::BitcoinCoreGetTransaction(error, _)
+            | Error::BitcoinCoreGetTxOut(error, _, _)
+            | Error::BitcoinCoreGetTxSpendingPrevout(error, _)
+            | Error::BitcoinCoreGetMempoolDescendants(error, _)
+            | Error::EstimateSmartFee(error, _) => bitcoin_rpc_error_is_retryable(error),
+            Error::BitcoinCoreZmq(error) => {
+                !matches!(error, bitcoincore_zmq::Error::BitcoinDeserialization(_))
+            }
+            Error::EmilyApi(error) => error.is_transient(),
+            Error::FallbackClient(error) => {
+                matches!(error, crate::util::FallbackClientError::AllClientsFailed)
+            }
+            Error::BitcoinCoreZmqConnectTimeout(_) | Error::TokioIo(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether the database operation that failed with the given error may
+/// succeed if it is attempted again.
+fn sqlx_error_is_retryable(error: &sqlx::Error) -> bool {
+    match error {
+        // Data exceptions (class 22) and integrity constraint violations
+        // (class 23) happen again for the same data. Other database
+        // errors, like serialization failures, deadlocks or lost
+        // connections, may not.
+        sqlx::Error::Database(error) => !error
+            .code()
+            .is_some_and(|code| code.starts_with("22") || code.starts_with("23")),
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::Protocol(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::WorkerCrashed => true,
+        _ => false,
+    }
+}
+
+/// Whether the HTTP request that failed with the given error may succeed
+/// if it is sent again.
+fn reqwest_error_is_retryable(error: &reqwest::Error) -> bool {
+    let status_is_retryable = error.status().is_some_and(|status| {
+        status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    });
+
+    status_is_retryable || error.is_timeout() || error.is_connect() || error.is_body()
+}
+
+/// Whether the bitcoin-core RPC call that failed with the given error may
+/// succeed if it is made again.
+fn bitcoin_rpc_error_is_retryable(error: &bitcoincore_rpc::Error) -> bool {
+    use bitcoincore_rpc::jsonrpc::error::Error as JsonRpcError;
+
+    /// The error code that bitcoin-core responds with while it is still
+    /// starting up.
+    const RPC_IN_WARMUP: i32 = -28;
+
+    match error {
+        bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Transport(_)) => true,
+        bitcoincore_rpc::Error::JsonRpc(JsonRpcError::Rpc(error)) => error.code == RPC_IN_WARMUP,
+        bitcoincore_rpc::Error::Io(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(sqlx::Error::PoolTimedOut, true; "pool timed out")]
+    #[test_case(sqlx::Error::WorkerCrashed, true; "worker crashed")]
+    #[test_case(
+        sqlx::Error::Io(std::io::ErrorKind::ConnectionReset.into()), true; "io")]
+    #[test_case(sqlx::Error::RowNotFound, false; "row not found")]
+    #[test_case(sqlx::Error::ColumnNotFound("txid".into()), false; "column not found")]
+    fn sqlx_errors_are_classified(error: sqlx::Error, retryable: bool) {
+        assert_eq!(Error::SqlxQuery(error).is_retryable(), retryable);
+    }
+
+    #[test_case(500, true; "internal server error")]
+    #[test_case(503, true; "service unavailable")]
+    #[test_case(429, true; "too many requests")]
+    #[test_case(400, false; "bad request")]
+    #[test_case(404, false; "not found")]
+    #[tokio::test]
+    async fn reqwest_status_errors_are_classified(status: usize, retryable: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .with_status(status)
+            .create_async()
+            .await;
+
+        let error = reqwest::get(server.url())
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap_err();
+        mock.assert_async().await;
+
+        assert_eq!(Error::Reqwest(error).is_retryable(), retryable);
+    }
+
+    #[tokio::test]
+    async fn reqwest_connection_errors_are_retryable() {
+        // Nothing listens on port 1, so the connection is refused.
+        let error = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+        assert!(error.is_connect());
+        assert!(Error::StacksNodeRequest(error).is_retryable());
+    }
+
+    #[test_case(Error::InvalidStacksResponse("expected a sequence"); "invalid stacks response")]
+    #[test_case(Error::EmptySignerSet; "empty signer set")]
+    #[test_case(Error::TypeConversion; "type conversion")]
+    fn validation_errors_are_terminal(error: Error) {
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn context_preserves_the_source_error() {
+        let txid = bitcoin::Txid::all_zeros();
+        let error = Error::SqlxQuery(sqlx::Error::PoolTimedOut).context(
+            ErrorContext::BitcoinTransaction("write_bitcoin_transaction", txid),
+        );
+
+        assert!(error.is_retryable());
+        assert!(matches!(
+            error.without_context(),
+            Error::SqlxQuery(sqlx::Error::PoolTimedOut)
+        ));
+        let message = error.to_string();
+        assert!(message.starts_with("write_bitcoin_transaction failed for bitcoin transaction"));
+        assert!(message.contains(&txid.to_string()));
+
+        let result: Result<(), Error> = Err(Error::EmptySignerSet);
+        let error = result
+            .with_context(|| ErrorContext::WithdrawalRequest("get_withdrawal", 42))
+            .unwrap_err();
+        assert!(!error.is_retryable());
+        assert!(matches!(error.without_context(), Error::EmptySignerSet));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "get_withdrawal failed for withdrawal request 42: {}",
+                Error::EmptySignerSet
+            )
+        );
+    }
 }
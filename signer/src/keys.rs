@@ -28,11 +28,16 @@
 //! [^3]: https://github.com/Trust-Machines/p256k1/blob/3ecb941c1af13741d52335ef911693b6d6fda94b/p256k1/src/scalar.rs#L245-L257
 //! [^4]: https://github.com/bitcoin-core/secp256k1/blob/3fdf146bad042a17f6b2f490ef8bd9d8e774cdbd/src/scalar.h#L31-L36
 
+pub mod share_encryption;
+
 use std::ops::Deref;
 use std::str::FromStr;
 
 use bitcoin::ScriptBuf;
 use bitcoin::TapTweakHash;
+use bitcoin::hashes::Hash as _;
+use bitcoin::hashes::HashEngine as _;
+use bitcoin::hashes::sha256;
 use secp256k1::SECP256K1;
 use serde::Deserialize;
 use serde::Serialize;
@@ -239,6 +244,63 @@ impl PublicKey {
             .map(Self)
             .map_err(Error::InvalidAggregateKey)
     }
+
+    /// Derive the aggregate key of a signer set.
+    ///
+    /// The keys are sorted before they are combined, which is the same
+    /// order that the public keys of the signers' multi-sig wallet are
+    /// kept in, so every signer derives the same key no matter the order
+    /// in which it learned about the signer set. Adding the points is
+    /// order independent, the sorting is there so that duplicates are
+    /// detected and so that the set matches the one used to derive the
+    /// smart-contract multi-sig address in [`SignerWallet`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the slice is empty, if it contains the
+    /// same public key more than once, or if the combined key would be
+    /// the point at infinity.
+    ///
+    /// [`SignerWallet`]: crate::stacks::wallet::SignerWallet
+    pub fn aggregate_ordered(keys: &[PublicKey]) -> Result<Self, Error> {
+        let mut keys = keys.to_vec();
+        keys.sort();
+
+        if keys.is_empty() {
+            return Err(Error::EmptySignerSet);
+        }
+        if let Some(pair) = keys.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(Error::DuplicateSignerSetKey(pair[0]));
+        }
+
+        Self::combine_keys(&keys)
+    }
+
+    /// Verify a Schnorr signature made with [`PrivateKey::sign_schnorr`]
+    /// over the given message in the given domain.
+    pub fn verify_schnorr(
+        &self,
+        msg: &[u8],
+        domain: &str,
+        sig: &secp256k1::schnorr::Signature,
+    ) -> Result<(), Error> {
+        self.verify_schnorr_digest(&MessageDigest::tagged(domain, msg), sig)
+    }
+
+    /// Verify a BIP-340 Schnorr signature over the given digest using the
+    /// x-only form of this public key and the global [`SECP256K1`]
+    /// context.
+    pub fn verify_schnorr_digest(
+        &self,
+        digest: &MessageDigest,
+        sig: &secp256k1::schnorr::Signature,
+    ) -> Result<(), Error> {
+        let msg = secp256k1::Message::from_digest(digest.to_byte_array());
+        let (x_only, _) = self.0.x_only_public_key();
+        SECP256K1
+            .verify_schnorr(sig, &msg, &x_only)
+            .map_err(Error::InvalidSchnorrSignature)
+    }
 }
 
 impl std::fmt::Display for PublicKey {
@@ -412,13 +474,60 @@ impl PrivateKey {
     ) -> secp256k1::ecdsa::RecoverableSignature {
         SECP256K1.sign_ecdsa_recoverable(msg, &self.0)
     }
+
+    /// Constructs a BIP-340 Schnorr signature over the tagged hash of
+    /// `msg` in the given domain, see [`MessageDigest::tagged`].
+    pub fn sign_schnorr(&self, msg: &[u8], domain: &str) -> secp256k1::schnorr::Signature {
+        self.sign_schnorr_digest(&MessageDigest::tagged(domain, msg))
+    }
+
+    /// Constructs a BIP-340 Schnorr signature over the given digest using
+    /// the global [`SECP256K1`] context.
+    pub fn sign_schnorr_digest(&self, digest: &MessageDigest) -> secp256k1::schnorr::Signature {
+        let msg = secp256k1::Message::from_digest(digest.to_byte_array());
+        let keypair = secp256k1::Keypair::from_secret_key(SECP256K1, &self.0);
+        SECP256K1.sign_schnorr(&msg, &keypair)
+    }
+}
+
+/// A 32-byte digest of a message that is signed with a Schnorr signature.
+///
+/// Digests can only be created with [`MessageDigest::tagged`], so a digest
+/// is always bound to the domain that it was created for, and a signature
+/// made for one domain is never valid in another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MessageDigest([u8; 32]);
+
+impl MessageDigest {
+    /// Compute the BIP-340 tagged hash of the message, using the domain
+    /// as the tag. That is,
+    /// `SHA256(SHA256(domain) || SHA256(domain) || msg)`.
+    pub fn tagged(domain: &str, msg: &[u8]) -> Self {
+        let tag = sha256::Hash::hash(domain.as_bytes());
+        let mut engine = sha256::Hash::engine();
+        engine.input(tag.as_byte_array());
+        engine.input(tag.as_byte_array());
+        engine.input(msg);
+        Self(sha256::Hash::from_engine(engine).to_byte_array())
+    }
+
+    /// Returns the digest as a byte array.
+    pub fn to_byte_array(&self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl std::fmt::Display for MessageDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
 }
 
 /// This trait is used to provide a unifying interface for converting
 /// different public key types to the `scriptPubKey` and the tweaked public
 /// key associated with the signers. We represent the `scriptPubkey` using
 /// rust-bitcoin's ScriptBuf type.
-pub trait SignerScriptPubKey: Sized {
+pub trait SignerScriptPubKey {
     /// Convert this type to the `scriptPubkey` used by the signers to lock
     /// their UTXO.
     fn signers_script_pubkey(&self) -> ScriptBuf;
@@ -445,6 +554,28 @@ impl SignerScriptPubKey for PublicKey {
     }
 }
 
+impl SignerScriptPubKey for [PublicKey] {
+    /// The `scriptPubkey` of the aggregate key of the signer set, see
+    /// [`PublicKey::aggregate_ordered`].
+    ///
+    /// # Panics
+    ///
+    /// This panics if the signer set is empty, contains duplicate keys,
+    /// or if its aggregate key is the point at infinity. Use
+    /// [`SignerScriptPubKey::signers_tweaked_pubkey`] or
+    /// [`PublicKey::aggregate_ordered`] when the signer set hasn't been
+    /// validated.
+    fn signers_script_pubkey(&self) -> ScriptBuf {
+        PublicKey::aggregate_ordered(self)
+            .expect("BUG: the signer set should have a valid aggregate key")
+            .signers_script_pubkey()
+    }
+    /// Construct the tweaked aggregate key of the signer set.
+    fn signers_tweaked_pubkey(&self) -> Result<PublicKey, Error> {
+        PublicKey::aggregate_ordered(self)?.signers_tweaked_pubkey()
+    }
+}
+
 impl SignerScriptPubKey for secp256k1::XOnlyPublicKey {
     fn signers_script_pubkey(&self) -> ScriptBuf {
         ScriptBuf::new_p2tr(SECP256K1, *self, None)
@@ -471,7 +602,9 @@ impl SignerScriptPubKey for secp256k1::XOnlyPublicKey {
 mod tests {
     use super::*;
 
+    use rand::SeedableRng as _;
     use rand::rngs::OsRng;
+    use rand::seq::SliceRandom as _;
     use secp256k1::Parity;
     use secp256k1::SecretKey;
     use stacks_common::util::secp256k1::Secp256k1PrivateKey;
@@ -680,4 +813,142 @@ mod tests {
             tweaked_aggregate_key2.0.x_only_public_key().0.serialize();
         assert_eq!(tweaked_aggregate_key1_bytes, tweaked_aggregate_key2_bytes);
     }
+
+    #[test]
+    fn schnorr_signatures_verify_only_in_their_domain() {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let msg = b"some signer message";
+
+        let sig = private_key.sign_schnorr(msg, "sbtc/test/1");
+        public_key.verify_schnorr(msg, "sbtc/test/1", &sig).unwrap();
+
+        let result = public_key.verify_schnorr(msg, "sbtc/test/2", &sig);
+        assert!(matches!(result, Err(Error::InvalidSchnorrSignature(_))));
+
+        let result = public_key.verify_schnorr(b"another message", "sbtc/test/1", &sig);
+        assert!(matches!(result, Err(Error::InvalidSchnorrSignature(_))));
+
+        let other_key = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let result = other_key.verify_schnorr(msg, "sbtc/test/1", &sig);
+        assert!(matches!(result, Err(Error::InvalidSchnorrSignature(_))));
+    }
+
+    #[test]
+    fn schnorr_signatures_cross_verify_with_rust_secp256k1() {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let digest = MessageDigest::tagged("sbtc/test", b"some signer message");
+
+        let msg = secp256k1::Message::from_digest(digest.to_byte_array());
+        let keypair = secp256k1::Keypair::from_secret_key(SECP256K1, &private_key.0);
+        let x_only = keypair.x_only_public_key().0;
+
+        // Our signatures verify with rust-secp256k1 directly.
+        let sig = private_key.sign_schnorr_digest(&digest);
+        SECP256K1.verify_schnorr(&sig, &msg, &x_only).unwrap();
+
+        // And signatures made with rust-secp256k1 directly verify with
+        // our helpers.
+        let sig = SECP256K1.sign_schnorr_no_aux_rand(&msg, &keypair);
+        public_key.verify_schnorr_digest(&digest, &sig).unwrap();
+    }
+
+    // These are the first test vectors from BIP-340, see
+    // https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    #[test_case(
+        "0000000000000000000000000000000000000000000000000000000000000003",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0";
+        "vector 0")]
+    #[test_case(
+        "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+        "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+        "0000000000000000000000000000000000000000000000000000000000000001",
+        "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A";
+        "vector 1")]
+    fn schnorr_signatures_match_bip340_vectors(
+        secret_key: &str,
+        digest: &str,
+        aux_rand: &str,
+        signature: &str,
+    ) {
+        let private_key = PrivateKey::from_slice(&hex::decode(secret_key).unwrap()).unwrap();
+        let public_key = PublicKey::from_private_key(&private_key);
+        let digest = MessageDigest(hex::decode(digest).unwrap().try_into().unwrap());
+        let expected =
+            secp256k1::schnorr::Signature::from_slice(&hex::decode(signature).unwrap()).unwrap();
+
+        // With the same auxiliary randomness, rust-secp256k1 reproduces
+        // the vectors exactly.
+        let aux_rand: [u8; 32] = hex::decode(aux_rand).unwrap().try_into().unwrap();
+        let msg = secp256k1::Message::from_digest(digest.to_byte_array());
+        let keypair = secp256k1::Keypair::from_secret_key(SECP256K1, &private_key.0);
+        let sig = SECP256K1.sign_schnorr_with_aux_rand(&msg, &keypair, &aux_rand);
+        assert_eq!(sig, expected);
+
+        public_key
+            .verify_schnorr_digest(&digest, &expected)
+            .unwrap();
+
+        // Our own signatures use fresh auxiliary randomness, so they
+        // differ from the vectors, but they still verify.
+        let sig = private_key.sign_schnorr_digest(&digest);
+        public_key.verify_schnorr_digest(&digest, &sig).unwrap();
+    }
+
+    #[test]
+    fn message_digests_are_bip340_tagged_hashes() {
+        let x_only = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let x_only = secp256k1::XOnlyPublicKey::from(x_only);
+
+        let digest = MessageDigest::tagged("TapTweak", &x_only.serialize());
+        let tap_tweak = TapTweakHash::from_key_and_tweak(x_only, None);
+        assert_eq!(digest.to_byte_array(), tap_tweak.to_byte_array());
+    }
+
+    #[test]
+    fn ordered_aggregate_keys_are_permutation_invariant() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+        let mut keys: Vec<PublicKey> = std::iter::repeat_with(|| PrivateKey::new(&mut rng))
+            .map(|private_key| PublicKey::from_private_key(&private_key))
+            .take(7)
+            .collect();
+
+        let aggregate_key = PublicKey::aggregate_ordered(&keys).unwrap();
+        assert_eq!(aggregate_key, PublicKey::combine_keys(&keys).unwrap());
+
+        for _ in 0..20 {
+            keys.shuffle(&mut rng);
+            assert_eq!(PublicKey::aggregate_ordered(&keys).unwrap(), aggregate_key);
+            assert_eq!(
+                keys.signers_script_pubkey(),
+                aggregate_key.signers_script_pubkey()
+            );
+            assert_eq!(
+                keys.signers_tweaked_pubkey().unwrap(),
+                aggregate_key.signers_tweaked_pubkey().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn ordered_aggregate_keys_reject_empty_and_duplicate_signer_sets() {
+        let result = PublicKey::aggregate_ordered(&[]);
+        assert!(matches!(result, Err(Error::EmptySignerSet)));
+
+        let mut keys: Vec<PublicKey> = std::iter::repeat_with(|| PrivateKey::new(&mut OsRng))
+            .map(|private_key| PublicKey::from_private_key(&private_key))
+            .take(5)
+            .collect();
+        let duplicate = keys[3];
+        keys.insert(1, duplicate);
+
+        let result = PublicKey::aggregate_ordered(&keys);
+        assert!(matches!(result, Err(Error::DuplicateSignerSetKey(key)) if key == duplicate));
+
+        let result = keys.signers_tweaked_pubkey();
+        assert!(matches!(result, Err(Error::DuplicateSignerSetKey(key)) if key == duplicate));
+    }
 }
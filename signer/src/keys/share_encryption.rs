@@ -0,0 +1,286 @@
+//! Encryption of the private DKG shares of a signer at rest.
+//!
+//! The shares are encrypted with ChaCha20-Poly1305 under a key that is
+//! derived with HKDF-SHA256 from the signer's private key, using the
+//! aggregate key of the shares as the salt. So each set of shares gets
+//! its own encryption key, and only the signer that took part in DKG can
+//! decrypt them.
+//!
+//! Encrypted shares are laid out as a format version byte, followed by
+//! the nonce, followed by the ciphertext and its authentication tag.
+//! Shares that were stored before this module existed were encrypted with
+//! [`wsts::util::encrypt`] using the raw private key. Those can still be
+//! decrypted, and [`reencrypt_all_shares`] rewrites them in the current
+//! format.
+//!
+//! When the operator rotates the signer's private key, the stored shares
+//! must be re-encrypted under the new key with [`reencrypt_all_shares`]
+//! before the signer is restarted with the new key.
+
+use chacha20poly1305::ChaCha20Poly1305;
+use chacha20poly1305::KeyInit as _;
+use chacha20poly1305::Nonce;
+use chacha20poly1305::aead::Aead as _;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::error::Error;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
+use crate::storage::DbRead;
+use crate::storage::DbWrite;
+
+/// The version of the encryption format, which is the first byte of the
+/// encrypted shares.
+const FORMAT_VERSION: u8 = 1;
+
+/// The size, in bytes, of a ChaCha20-Poly1305 nonce.
+const NONCE_SIZE: usize = 12;
+
+/// The HKDF info string, which binds the derived key to its purpose.
+const HKDF_INFO: &[u8] = b"sbtc-signer/dkg-shares/v1";
+
+/// Derive the cipher used to encrypt the DKG shares for the given
+/// aggregate key.
+fn derive_cipher(private_key: &PrivateKey, aggregate_key: &PublicKey) -> ChaCha20Poly1305 {
+    let hkdf = Hkdf::<Sha256>::new(Some(&aggregate_key.serialize()), &private_key.to_bytes());
+    let mut key = [0; 32];
+    hkdf.expand(HKDF_INFO, &mut key)
+        .expect("BUG: 32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(&key.into())
+}
+
+/// Encrypt the encoded DKG shares for the given aggregate key.
+pub fn encrypt_shares<R>(
+    private_key: &PrivateKey,
+    aggregate_key: &PublicKey,
+    shares: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, Error>
+where
+    R: rand::RngCore + rand::CryptoRng,
+{
+    let mut nonce = [0; NONCE_SIZE];
+    rng.fill_bytes(&mut nonce);
+
+    let ciphertext = derive_cipher(private_key, aggregate_key)
+        .encrypt(Nonce::from_slice(&nonce), shares)
+        .map_err(|_| Error::Encryption)?;
+
+    let mut encrypted = Vec::with_capacity(1 + NONCE_SIZE + ciphertext.len());
+    encrypted.push(FORMAT_VERSION);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypt the DKG shares for the given aggregate key, falling back to
+/// the format used before this module existed.
+///
+/// # Errors
+///
+/// An [`Error::DkgSharesDecryption`] is returned if the shares weren't
+/// encrypted with the given private key for the given aggregate key, or
+/// if they have been tampered with.
+pub fn decrypt_shares(
+    private_key: &PrivateKey,
+    aggregate_key: &PublicKey,
+    encrypted: &[u8],
+) -> Result<Vec<u8>, Error> {
+    decrypt_current(private_key, aggregate_key, encrypted)
+        .or_else(|| wsts::util::decrypt(&private_key.to_bytes(), encrypted).ok())
+        .ok_or(Error::DkgSharesDecryption(*aggregate_key))
+}
+
+/// Decrypt shares that were encrypted with [`encrypt_shares`].
+fn decrypt_current(
+    private_key: &PrivateKey,
+    aggregate_key: &PublicKey,
+    encrypted: &[u8],
+) -> Option<Vec<u8>> {
+    let (&version, rest) = encrypted.split_first()?;
+    if version != FORMAT_VERSION || rest.len() < NONCE_SIZE {
+        return None;
+    }
+    let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+
+    derive_cipher(private_key, aggregate_key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()
+}
+
+/// Re-encrypt all stored DKG shares, which are encrypted with the old
+/// private key, under the new private key. Returns the number of shares
+/// that were re-encrypted.
+///
+/// All shares are decrypted before any of them are written, and they are
+/// written in a single database transaction, so either all of the shares
+/// are re-encrypted or none of them are.
+pub async fn reencrypt_all_shares<S, R>(
+    storage: &S,
+    old_key: &PrivateKey,
+    new_key: &PrivateKey,
+    rng: &mut R,
+) -> Result<usize, Error>
+where
+    S: DbRead + DbWrite,
+    R: rand::RngCore + rand::CryptoRng + Send,
+{
+    let all_shares = storage.get_all_encrypted_dkg_shares().await?;
+
+    let mut reencrypted = Vec::with_capacity(all_shares.len());
+    for shares in all_shares {
+        let aggregate_key = shares.aggregate_key;
+        let decrypted = decrypt_shares(old_key, &aggregate_key, &shares.encrypted_private_shares)?;
+        let encrypted = encrypt_shares(new_key, &aggregate_key, &decrypted, rng)?;
+        reencrypted.push((aggregate_key, encrypted));
+    }
+
+    storage
+        .update_encrypted_private_shares(&reencrypted)
+        .await?;
+
+    tracing::info!(
+        num_shares = reencrypted.len(),
+        "re-encrypted the stored DKG shares under the new signer key"
+    );
+    Ok(reencrypted.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+    use rand::rngs::OsRng;
+
+    use crate::storage::in_memory::Store;
+    use crate::storage::model;
+
+    use super::*;
+
+    #[test]
+    fn shares_roundtrip() {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let aggregate_key = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let shares = b"some encoded DKG shares";
+
+        let encrypted = encrypt_shares(&private_key, &aggregate_key, shares, &mut OsRng).unwrap();
+        assert_eq!(encrypted[0], FORMAT_VERSION);
+        assert_eq!(encrypted.len(), 1 + NONCE_SIZE + shares.len() + 16);
+
+        let decrypted = decrypt_shares(&private_key, &aggregate_key, &encrypted).unwrap();
+        assert_eq!(decrypted, shares);
+    }
+
+    #[test]
+    fn shares_only_decrypt_with_the_same_keys() {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let aggregate_key = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let shares = b"some encoded DKG shares";
+        let encrypted = encrypt_shares(&private_key, &aggregate_key, shares, &mut OsRng).unwrap();
+
+        let other_private_key = PrivateKey::new(&mut OsRng);
+        let result = decrypt_shares(&other_private_key, &aggregate_key, &encrypted);
+        assert!(matches!(result, Err(Error::DkgSharesDecryption(key)) if key == aggregate_key));
+
+        let other_aggregate_key = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let result = decrypt_shares(&private_key, &other_aggregate_key, &encrypted);
+        assert!(matches!(result, Err(Error::DkgSharesDecryption(_))));
+
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        let result = decrypt_shares(&private_key, &aggregate_key, &tampered);
+        assert!(matches!(result, Err(Error::DkgSharesDecryption(_))));
+
+        for truncated in [&[][..], &encrypted[..1], &encrypted[..NONCE_SIZE]] {
+            let result = decrypt_shares(&private_key, &aggregate_key, truncated);
+            assert!(matches!(result, Err(Error::DkgSharesDecryption(_))));
+        }
+    }
+
+    #[test]
+    fn legacy_shares_still_decrypt() {
+        let private_key = PrivateKey::new(&mut OsRng);
+        let aggregate_key = PublicKey::from_private_key(&PrivateKey::new(&mut OsRng));
+        let shares = b"some encoded DKG shares";
+        let encrypted = wsts::util::encrypt(&private_key.to_bytes(), shares, &mut OsRng).unwrap();
+
+        let decrypted = decrypt_shares(&private_key, &aggregate_key, &encrypted).unwrap();
+        assert_eq!(decrypted, shares);
+    }
+
+    #[tokio::test]
+    async fn rotated_keys_reencrypt_all_shares() {
+        let storage = Store::new_shared();
+        let old_key = PrivateKey::new(&mut OsRng);
+        let new_key = PrivateKey::new(&mut OsRng);
+
+        // One set of shares in the current format and one in the legacy
+        // format.
+        let mut expected = Vec::new();
+        for legacy in [false, true] {
+            let mut shares: model::EncryptedDkgShares = Faker.fake_with_rng(&mut OsRng);
+            let decrypted: [u8; 32] = Faker.fake_with_rng(&mut OsRng);
+            shares.encrypted_private_shares = if legacy {
+                wsts::util::encrypt(&old_key.to_bytes(), &decrypted, &mut OsRng).unwrap()
+            } else {
+                encrypt_shares(&old_key, &shares.aggregate_key, &decrypted, &mut OsRng).unwrap()
+            };
+            storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+            expected.push((shares.aggregate_key, decrypted));
+        }
+
+        let count = reencrypt_all_shares(&storage, &old_key, &new_key, &mut OsRng)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        for (aggregate_key, decrypted) in expected {
+            let shares = storage
+                .get_encrypted_dkg_shares(aggregate_key)
+                .await
+                .unwrap()
+                .unwrap();
+            let encrypted = &shares.encrypted_private_shares;
+
+            let result = decrypt_shares(&new_key, &aggregate_key, encrypted).unwrap();
+            assert_eq!(result, decrypted);
+
+            let result = decrypt_shares(&old_key, &aggregate_key, encrypted);
+            assert!(matches!(result, Err(Error::DkgSharesDecryption(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_reencryption_leaves_all_shares_untouched() {
+        let storage = Store::new_shared();
+        let old_key = PrivateKey::new(&mut OsRng);
+        let new_key = PrivateKey::new(&mut OsRng);
+
+        let mut shares: model::EncryptedDkgShares = Faker.fake_with_rng(&mut OsRng);
+        shares.encrypted_private_shares =
+            encrypt_shares(&old_key, &shares.aggregate_key, b"shares", &mut OsRng).unwrap();
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        // These shares were not encrypted with the old key.
+        let mut foreign: model::EncryptedDkgShares = Faker.fake_with_rng(&mut OsRng);
+        foreign.encrypted_private_shares =
+            encrypt_shares(&new_key, &foreign.aggregate_key, b"shares", &mut OsRng).unwrap();
+        storage.write_encrypted_dkg_shares(&foreign).await.unwrap();
+
+        let result = reencrypt_all_shares(&storage, &old_key, &new_key, &mut OsRng).await;
+        assert!(
+            matches!(result, Err(Error::DkgSharesDecryption(key)) if key == foreign.aggregate_key)
+        );
+
+        let stored = storage
+            .get_encrypted_dkg_shares(shares.aggregate_key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.encrypted_private_shares,
+            shares.encrypted_private_shares
+        );
+    }
+}
@@ -13,6 +13,7 @@ pub mod blocklist_client;
 pub mod codec;
 pub mod config;
 pub mod context;
+pub mod coordinator;
 pub mod dkg;
 pub mod ecdsa;
 pub mod emily_client;
@@ -66,6 +67,23 @@ pub const MAX_KEYS: u16 = 128;
 /// the deposit.
 pub const DEPOSIT_LOCKTIME_BLOCK_BUFFER: u16 = 3;
 
+/// The maximum number of signer decisions that we buffer in the database
+/// when they are received over the P2P network before we have a record of
+/// the associated deposit or withdrawal request. Decisions received when
+/// the buffer is full are dropped.
+pub const MAX_PENDING_SIGNER_DECISIONS: u64 = 10_000;
+
+/// The maximum amount of time that we keep a buffered signer decision
+/// around while waiting for a record of the associated request.
+pub const PENDING_SIGNER_DECISIONS_MAX_AGE: std::time::Duration =
+    std::time::Duration::from_secs(24 * 60 * 60);
+
+/// How far behind the highest sequence number seen from a signer the
+/// sequence number of a P2P message from that signer can be before we drop
+/// it as a replay. Messages can arrive out of order, so we accept messages
+/// within this window that we have not seen before.
+pub const P2P_MESSAGE_SEQUENCE_WINDOW: u64 = 1_000;
+
 /// This is the capacity of the channel used for messages sent within the
 /// signer.
 pub const SIGNER_CHANNEL_CAPACITY: usize = 1024;
@@ -99,6 +117,13 @@ pub const DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX: u16 = 25;
 /// smart contract.
 pub const DEPOSIT_DUST_LIMIT: u64 = 546;
 
+/// The default minimum amount, in sats, that a deposit must have for the
+/// signers to consider sweeping it in.
+///
+/// Deposits with amounts below the smart contract dust limit can never be
+/// minted, so there is no point in spending fees on them.
+pub const DEFAULT_MIN_DEPOSIT_AMOUNT: u64 = DEPOSIT_DUST_LIMIT;
+
 /// This is the max dust amount for a standard transaction using the
 /// default `dustrelayfee` config setting from bitcoin core. The smart
 /// contract has this dust limit as well, but we have our own to make sure
@@ -156,6 +181,14 @@ pub const MAX_SIGNER_STATE_MACHINES: u64 = MAX_MEMPOOL_PACKAGE_SIZE
     .saturating_add(1)
     .next_power_of_two();
 
+/// The maximum number of deposit request reports, and separately of
+/// withdrawal request reports, that the transaction signer keeps cached
+/// while validating transaction packages for the same chain tip.
+///
+/// This matches the maximum number of inputs that a transaction package
+/// could have, so the reports for any one package fit in the cache.
+pub const MAX_CACHED_REQUEST_REPORTS: u64 = MAX_SIGNER_STATE_MACHINES;
+
 /// This is the vsize of a signed key-spend taproot input on bitcoin, which
 /// should be the smallest vsize that a signed taproot input could have on
 /// bitcoin.
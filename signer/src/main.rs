@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
@@ -24,6 +25,8 @@ use signer::network::P2PNetwork;
 use signer::network::libp2p::SignerSwarmBuilder;
 use signer::request_decider::RequestDeciderEventLoop;
 use signer::stacks::api::StacksClient;
+use signer::storage::DbRead as _;
+use signer::storage::DbWrite as _;
 use signer::storage::postgres::PgStore;
 use signer::transaction_coordinator;
 use signer::transaction_signer;
@@ -49,8 +52,11 @@ enum LogOutputFormat {
 #[derive(Debug, Parser)]
 #[clap(name = "sBTC Signer")]
 struct SignerArgs {
-    /// Optional path to the configuration file. If not provided, it is expected
-    /// that all parameters are provided via environment variables.
+    /// Optional path to the configuration file. If not provided, the path
+    /// in the SIGNER_CONFIG_PATH environment variable is used, and if that
+    /// isn't set either then the default configuration embedded in the
+    /// binary is used. Environment variables take precedence over the
+    /// values in the configuration file.
     #[clap(short = 'c', long, required = false)]
     config: Option<PathBuf>,
 
@@ -82,7 +88,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Load the configuration file and/or environment variables.
-    let settings = Settings::new(args.config).inspect_err(|error| {
+    let config_path = Settings::resolve_config_path(args.config);
+    let settings = Settings::new(config_path.as_ref()).inspect_err(|error| {
         tracing::error!(%error, "failed to construct the configuration");
     })?;
 
@@ -134,23 +141,62 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // is the reason we also use the `run_checked` helper method, which will
     // intercept errors and send a shutdown signal to the other components if an error
     // does occur, otherwise the `join` will continue running indefinitely.
-    let _ = tokio::join!(
-        // Our global termination signal watcher. This does not run using `run_checked`
-        // as it sends its own shutdown signal.
-        run_shutdown_signal_watcher(context.clone()),
-        // The rest of our services which run concurrently, and must all be
-        // running for the signer to be operational.
-        run_checked(run_api, &context),
-        run_checked(run_libp2p_swarm, &context),
-        run_checked(run_block_observer, &context),
-        run_checked(run_request_decider, &context),
-        run_checked(run_transaction_coordinator, &context),
-        run_checked(run_transaction_signer, &context),
-    );
+    let components = async {
+        tokio::join!(
+            // Our global termination signal watcher. This does not run using `run_checked`
+            // as it sends its own shutdown signal.
+            run_shutdown_signal_watcher(context.clone()),
+            // Reloads the parts of the configuration that can change while
+            // the signer is running. This is not required for the signer
+            // to be operational, so it also does not use `run_checked`.
+            run_config_watcher(context.clone(), config_path),
+            // The rest of our services which run concurrently, and must all be
+            // running for the signer to be operational.
+            run_checked(run_api, &context),
+            run_checked(run_libp2p_swarm, &context),
+            run_checked(run_block_observer, &context),
+            run_checked(run_request_decider, &context),
+            run_checked(run_transaction_coordinator, &context),
+            run_checked(run_transaction_signer, &context),
+        )
+    };
+
+    // Once the shutdown signal is sent, components that registered as
+    // shutdown participants get until the configured deadline to finish
+    // their in-flight work. Those that don't are abandoned when we return.
+    tokio::select! {
+        _ = components => {},
+        _ = run_shutdown_deadline(context.clone()) => {},
+    }
 
     Ok(())
 }
 
+/// Waits for the shutdown signal and then for the registered shutdown
+/// participants to acknowledge it. This only completes if some of the
+/// participants have not acknowledged the shutdown by the configured
+/// deadline, in which case they are logged and abandoned.
+async fn run_shutdown_deadline(ctx: impl Context) {
+    let mut term = ctx.get_termination_handle();
+    term.wait_for_shutdown().await;
+
+    let deadline = ctx.config().signer.shutdown_max_duration;
+    let abandoned = term.wait_for_participants(deadline).await;
+    if abandoned.is_empty() {
+        tracing::info!("all shutdown participants finished their in-flight work");
+        // The components will all return shortly, so we let them.
+        std::future::pending::<()>().await;
+    }
+
+    for participant in abandoned {
+        tracing::warn!(
+            participant,
+            ?deadline,
+            "component did not finish its in-flight work before the shutdown deadline; abandoning"
+        );
+    }
+}
+
 /// A helper method that captures errors from the provided future and sends a
 /// shutdown signal to the application if an error is encountered. This is needed
 /// as otherwise the application would continue running indefinitely (since no
@@ -228,6 +274,18 @@ async fn run_shutdown_signal_watcher(ctx: impl Context) -> Result<(), Error> {
     Ok(())
 }
 
+/// Watches the configuration file, if there is one, for changes to the
+/// settings that can be reloaded while the signer is running.
+#[tracing::instrument(skip(ctx), name = "config-watcher")]
+async fn run_config_watcher(ctx: impl Context, config_path: Option<PathBuf>) {
+    let Some(config_path) = config_path else {
+        tracing::debug!("no configuration file to watch");
+        return;
+    };
+
+    Settings::watch(config_path, ctx).await;
+}
+
 /// Runs the libp2p swarm.
 #[tracing::instrument(skip_all, name = "p2p")]
 async fn run_libp2p_swarm(ctx: impl Context) -> Result<(), Error> {
@@ -248,13 +306,28 @@ async fn run_libp2p_swarm(ctx: impl Context) -> Result<(), Error> {
         .try_into()
         .unwrap_or(signer::MAX_KEYS);
 
+    // Forget the peer addresses that have gone stale, and dial the ones
+    // that we have seen recently in addition to the seeds.
+    let storage = ctx.get_storage_mut();
+    let peer_address_max_age = config.signer.p2p.peer_address_max_age;
+    let pruned = storage.prune_p2p_peers(peer_address_max_age).await?;
+    let known_peers = storage.get_recent_p2p_peers(peer_address_max_age).await?;
+    tracing::debug!(
+        pruned,
+        known_peers = known_peers.len(),
+        "loaded known peer addresses"
+    );
+
     // Build the swarm.
     let mut swarm = SignerSwarmBuilder::new(&config.signer.private_key)
         .add_listen_endpoints(&ctx.config().signer.p2p.listen_on)
         .add_seed_addrs(&ctx.config().signer.p2p.seeds)
+        .add_known_peers(&known_peers)
         .add_external_addresses(&ctx.config().signer.p2p.public_endpoints)
         .enable_mdns(config.signer.p2p.enable_mdns)
         .enable_quic_transport(enable_quic)
+        .enable_relay(true)
+        .add_relay_servers(&ctx.config().signer.p2p.relay_servers)
         .with_initial_bootstrap_delay(Duration::from_secs(INITIAL_BOOTSTRAP_DELAY_SECS))
         .with_num_signers(num_signers)
         .build()?;
@@ -275,6 +348,15 @@ async fn run_api(ctx: impl Context + 'static) -> Result<(), Error> {
     let socket_addr = ctx.config().signer.event_observer.bind;
     tracing::info!(%socket_addr, "initializing the signer API server");
 
+    let event_observer = &ctx.config().signer.event_observer;
+    if event_observer.webhook_secret.is_none() && event_observer.allowed_webhook_sources.is_empty()
+    {
+        tracing::warn!(
+            "neither a webhook secret nor allowed webhook sources are configured, \
+            so new_block webhooks are accepted from anyone who can reach the API"
+        );
+    }
+
     let state = ApiState { ctx: ctx.clone() };
 
     let request_id = Arc::new(AtomicU64::new(0));
@@ -305,11 +387,19 @@ async fn run_api(ctx: impl Context + 'static) -> Result<(), Error> {
         .await
         .expect("failed to bind the signer API to configured address");
 
-    // Get the termination signal handle.
+    // Get the termination signal handle. The server stops accepting new
+    // connections on shutdown but waits for in-flight requests, such as
+    // webhook writes, to complete before we acknowledge.
     let mut term = ctx.get_termination_handle();
+    let _participant = term.register_participant("api");
+
+    // The source address of each request is made available to the
+    // handlers, so that webhooks can be checked against the allowed
+    // webhook sources.
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
 
     // Run our app with hyper
-    axum::serve(listener, app)
+    axum::serve(listener, service)
         .with_graceful_shutdown(async move {
             // Listen for an application shutdown signal. We need to loop here
             // because we may receive other signals (which we will ignore here).
@@ -328,10 +418,26 @@ async fn run_api(ctx: impl Context + 'static) -> Result<(), Error> {
 async fn run_block_observer(ctx: impl Context) -> Result<(), Error> {
     let config = ctx.config().clone();
 
-    // TODO: Need to handle multiple endpoints, so some sort of
-    // failover-stream-wrapper.
-    let endpoint = config.bitcoin.block_hash_stream_endpoints[0].as_str();
-    let stream = BitcoinCoreMessageStream::new_from_endpoint(endpoint)
+    // We prefer receiving full blocks over ZMQ, since that saves us an
+    // RPC round trip for each new block.
+    //
+    // TODO: We only fail over to the next endpoint when connecting, so we
+    // need some sort of failover-stream-wrapper for when the connection is
+    // lost.
+    if !config.bitcoin.block_stream_endpoints.is_empty() {
+        let stream = BitcoinCoreMessageStream::new_block_stream(&config.bitcoin)
+            .await
+            .unwrap();
+
+        let block_observer = block_observer::BlockObserver {
+            context: ctx,
+            bitcoin_blocks: stream.to_block_stream(),
+        };
+
+        return block_observer.run().await;
+    }
+
+    let stream = BitcoinCoreMessageStream::new_block_hash_stream(&config.bitcoin)
         .await
         .unwrap();
 
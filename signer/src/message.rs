@@ -1,14 +1,17 @@
 //! Signer message definition for network communication
 
+use bitcoin::hashes::Hash as _;
 use secp256k1::ecdsa::RecoverableSignature;
 
 use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::validation::BitcoinValidationFailure;
 use crate::bitcoin::validation::TxRequestIds;
 use crate::keys::PublicKey;
 use crate::stacks::contracts::ContractCall;
 use crate::stacks::contracts::StacksTx;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::SigHash;
 use crate::storage::model::StacksBlockHash;
 use crate::storage::model::StacksTxId;
 
@@ -38,6 +41,8 @@ pub enum Payload {
     BitcoinPreSignRequest(BitcoinPreSignRequest),
     /// An acknowledgment of a BitconPreSignRequest
     BitcoinPreSignAck(BitcoinPreSignAck),
+    /// A notice from the coordinator that it abandoned a WSTS round
+    RoundAbort(RoundAbort),
 }
 
 impl std::fmt::Display for Payload {
@@ -69,6 +74,9 @@ impl std::fmt::Display for Payload {
             }
             Self::BitcoinPreSignRequest(_) => write!(f, "BitcoinPreSignRequest(..)"),
             Self::BitcoinPreSignAck(_) => write!(f, "BitcoinPreSignAck(..)"),
+            Self::RoundAbort(abort) => {
+                write!(f, "RoundAbort({}, {})", abort.round_id, abort.reason)
+            }
         }
     }
 }
@@ -125,6 +133,12 @@ impl From<BitcoinPreSignAck> for Payload {
     }
 }
 
+impl From<RoundAbort> for Payload {
+    fn from(value: RoundAbort) -> Self {
+        Self::RoundAbort(value)
+    }
+}
+
 /// Represents a decision related to signer deposit
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignerDepositDecision {
@@ -234,10 +248,23 @@ pub struct BitcoinPreSignRequest {
 
 /// An acknowledgment of a [`BitcoinPreSignRequest`].
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct BitcoinPreSignAck;
+pub struct BitcoinPreSignAck {
+    /// The transactions in the request package that failed validation,
+    /// and so will not be signed by the acknowledging signer.
+    pub rejected_txs: Vec<BitcoinTxRejection>,
+}
+
+/// A transaction in a [`BitcoinPreSignRequest`] that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BitcoinTxRejection {
+    /// The ID of the transaction that failed validation.
+    pub txid: bitcoin::Txid,
+    /// Every input and output of the transaction that failed validation.
+    pub failures: Vec<BitcoinValidationFailure>,
+}
 
 /// The identifier for a WSTS message.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum WstsMessageId {
     /// The WSTS message is related to a Bitcoin transaction signing round.
     Sweep(bitcoin::Txid),
@@ -273,16 +300,65 @@ impl std::fmt::Display for WstsMessageId {
     }
 }
 
+/// A notice from the coordinator that it abandoned a DKG or signing
+/// round. Signers drop any state machines that they have for the round
+/// and ignore late messages for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundAbort {
+    /// The id of the abandoned round, which is the id of the WSTS messages
+    /// sent during the round.
+    pub round_id: WstsMessageId,
+    /// Why the coordinator abandoned the round.
+    pub reason: RoundAbortReason,
+}
+
+/// The reasons that a coordinator abandons a WSTS round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum RoundAbortReason {
+    /// The round did not complete before the coordinator's timer fired,
+    /// usually because at least one signer went silent.
+    Timeout,
+}
+
 /// A wsts message.
 #[derive(Debug, Clone, PartialEq)]
 pub struct WstsMessage {
     /// The id of the wsts message.
     pub id: WstsMessageId,
+    /// A random identifier that the coordinator picks for each DKG or
+    /// signing round. Signers echo it back in their replies, so that
+    /// messages can be tied to the round that they belong to even when
+    /// the WSTS `dkg_id` and `sign_id` values of two rounds collide.
+    ///
+    /// Signers that predate this field cannot verify messages that carry
+    /// it, and messages without it are rejected, so rolling it out
+    /// requires all signers to upgrade at the same time. See the
+    /// `WstsMessage.session` field in the protobuf definitions.
+    pub session_id: [u8; 16],
+    /// The sighash being signed, if the message is part of a signing
+    /// round.
+    pub sighash: Option<SigHash>,
     /// The wsts message
     pub inner: wsts::net::Message,
 }
 
 impl WstsMessage {
+    /// Whether the sighash of this message is the data that the inner
+    /// WSTS message is about. Inner messages that do not carry the data
+    /// being signed always match.
+    pub fn sighash_matches_inner(&self) -> bool {
+        let data = match &self.inner {
+            wsts::net::Message::NonceRequest(request) => &request.message,
+            wsts::net::Message::NonceResponse(response) => &response.message,
+            wsts::net::Message::SignatureShareRequest(request) => &request.message,
+            _ => return true,
+        };
+
+        self.sighash
+            .is_some_and(|sighash| sighash.as_byte_array().as_slice() == data.as_slice())
+    }
+
     /// Returns the type of the message as a &str.
     pub fn type_id(&self) -> &'static str {
         match self.inner {
@@ -318,6 +394,7 @@ mod tests {
     #[test_case(PhantomData::<StacksTransactionSignature> ; "StacksTransactionSignature")]
     #[test_case(PhantomData::<WstsMessage> ; "WstsMessage")]
     #[test_case(PhantomData::<BitcoinPreSignRequest> ; "BitcoinPreSignRequest")]
+    #[test_case(PhantomData::<RoundAbort> ; "RoundAbort")]
     fn signer_messages_should_be_signable_with_type<P>(_: PhantomData<P>)
     where
         P: fake::Dummy<fake::Faker> + Into<Payload>,
@@ -337,6 +414,7 @@ mod tests {
     #[test_case(PhantomData::<StacksTransactionSignature> ; "StacksTransactionSignature")]
     #[test_case(PhantomData::<WstsMessage> ; "WstsMessage")]
     #[test_case(PhantomData::<BitcoinPreSignRequest> ; "BitcoinPreSignRequest")]
+    #[test_case(PhantomData::<RoundAbort> ; "RoundAbort")]
     fn signer_messages_should_be_encodable_with_type<P>(_: PhantomData<P>)
     where
         P: fake::Dummy<fake::Faker> + Into<Payload>,
@@ -354,4 +432,28 @@ mod tests {
 
         assert_eq!(decoded, signed_message);
     }
+
+    #[test_case(Some([1; 32]), [1; 32], true ; "same-sighash")]
+    #[test_case(Some([1; 32]), [2; 32], false ; "different-sighash")]
+    #[test_case(None, [1; 32], false ; "missing-sighash")]
+    fn nonce_request_sighash_must_match_its_message(
+        sighash: Option<[u8; 32]>,
+        message: [u8; 32],
+        matches: bool,
+    ) {
+        let msg = WstsMessage {
+            id: WstsMessageId::Dkg([0; 32]),
+            session_id: [0; 16],
+            sighash: sighash.map(|bytes| bitcoin::TapSighash::from_byte_array(bytes).into()),
+            inner: wsts::net::Message::NonceRequest(wsts::net::NonceRequest {
+                dkg_id: 1,
+                sign_id: 1,
+                sign_iter_id: 1,
+                message: message.to_vec(),
+                signature_type: wsts::net::SignatureType::Schnorr,
+            }),
+        };
+
+        assert_eq!(msg.sighash_matches_inner(), matches);
+    }
 }
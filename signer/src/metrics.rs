@@ -2,10 +2,16 @@
 //!
 
 use std::net::SocketAddr;
+use std::time::Duration;
 
+use axum::Router;
+use axum::extract::State;
+use axum::routing::get;
 use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_exporter_prometheus::PrometheusHandle;
 
 use crate::block_observer::Deposit;
+use crate::emily_client::EmilyClientError;
 use crate::error::Error;
 
 /// The buckets used for metric histograms
@@ -47,6 +53,39 @@ pub enum Metrics {
     /// The amount of time, in seconds for running bitcoin or stacks
     /// validation.
     ValidationDurationSeconds,
+    /// The total number of sign requests that were rejected during
+    /// validation. We use labels to distinguish between the blockchains
+    /// and the reasons for the rejection.
+    ValidationRejectionsTotal,
+    /// The number of pending deposit requests that the request decider
+    /// found on the canonical bitcoin blockchain.
+    DepositRequestsPending,
+    /// The number of pending withdrawal requests that the request decider
+    /// found on the canonical stacks blockchain.
+    WithdrawalRequestsPending,
+    /// The total number of requests sent to Emily, including retries. We
+    /// use labels to distinguish between the operations and outcomes.
+    EmilyRequestsTotal,
+    /// The amount of time, in seconds, that a request to Emily took.
+    EmilyRequestDurationSeconds,
+    /// The number of peers that this signer is connected to over the P2P
+    /// network.
+    P2PPeersConnected,
+    /// The number of distinct peers in this signer's gossipsub mesh.
+    P2PMeshPeers,
+    /// The total number of P2P messages that were dropped, labeled by
+    /// the reason that they were rejected.
+    P2PMessagesRejectedTotal,
+    /// The total number of DKG and signing rounds that the coordinator
+    /// abandoned, labeled by the reason for aborting them.
+    WstsRoundsAbortedTotal,
+    /// The total number of WSTS messages that arrived after the round
+    /// that they were for had been aborted.
+    WstsLateMessagesTotal,
+    /// The total number of WSTS messages that were dropped because they
+    /// did not match the session or sighash of their round. We use a label
+    /// to distinguish between the coordinator and the signer.
+    WstsSessionMismatchesTotal,
 }
 
 impl From<Metrics> for metrics::KeyName {
@@ -71,6 +110,64 @@ impl Metrics {
         )
         .increment(1);
     }
+
+    /// Increment the validation rejection counter if the given error came
+    /// from failing validation.
+    pub fn increment_validation_rejections(blockchain: &'static str, error: &Error) {
+        let Some(reason) = validation_rejection_reason(error) else {
+            return;
+        };
+
+        metrics::counter!(
+            Metrics::ValidationRejectionsTotal,
+            "blockchain" => blockchain,
+            "reason" => reason,
+        )
+        .increment(1);
+    }
+
+    /// Record the outcome and duration of a single request to Emily.
+    pub fn record_emily_request<T>(
+        operation: &'static str,
+        result: &Result<T, EmilyClientError>,
+        duration: Duration,
+    ) {
+        let status = match result {
+            Ok(_) => "success",
+            Err(error) => error.into(),
+        };
+
+        metrics::counter!(
+            Metrics::EmilyRequestsTotal,
+            "operation" => operation,
+            "status" => status,
+        )
+        .increment(1);
+        metrics::histogram!(
+            Metrics::EmilyRequestDurationSeconds,
+            "operation" => operation,
+        )
+        .record(duration);
+    }
+}
+
+/// The reason that a sign request failed validation, or `None` if the
+/// error did not come from validation.
+fn validation_rejection_reason(error: &Error) -> Option<&'static str> {
+    use crate::bitcoin::validation::BitcoinSweepErrorMsg;
+
+    let reason = match error {
+        Error::BitcoinValidation(error) => match error.error {
+            BitcoinSweepErrorMsg::Deposit(result) => result.into(),
+            BitcoinSweepErrorMsg::Withdrawal(result) => result.into(),
+        },
+        Error::DepositValidation(error) => (&error.error).into(),
+        Error::WithdrawalAcceptValidation(error) => (&error.error).into(),
+        Error::WithdrawalRejectValidation(error) => (&error.error).into(),
+        Error::RotateKeysValidation(error) => (&error.error).into(),
+        _ => return None,
+    };
+    Some(reason)
 }
 
 /// Label for bitcoin blockchain based metrics
@@ -80,17 +177,29 @@ pub const BITCOIN_BLOCKCHAIN: &str = "bitcoin";
 pub const STACKS_BLOCKCHAIN: &str = "stacks";
 
 /// Set up a prometheus exporter for metrics.
+///
+/// If an endpoint is given, the metrics are served from `GET /metrics`
+/// on that endpoint.
 pub fn setup_metrics(prometheus_exporter_endpoint: Option<SocketAddr>) {
     if let Some(addr) = prometheus_exporter_endpoint {
-        PrometheusBuilder::new()
-            .with_http_listener(addr)
+        let handle = PrometheusBuilder::new()
             .add_global_label("app", crate::PACKAGE_NAME)
             .set_buckets(&METRIC_BUCKETS)
             .expect("received an empty slice of metric buckets")
             .set_quantiles(&METRIC_QUANTILES)
             .expect("received an empty slice of metric quantiles")
-            .install()
-            .expect("could not install the prometheus server");
+            .install_recorder()
+            .expect("could not install the prometheus recorder");
+
+        tokio::spawn(async move {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .expect("failed to bind the prometheus exporter to configured address");
+            tracing::info!(%addr, "serving metrics");
+            axum::serve(listener, get_router(handle))
+                .await
+                .expect("could not run the prometheus server");
+        });
     }
 
     metrics::gauge!(
@@ -102,3 +211,16 @@ pub fn setup_metrics(prometheus_exporter_endpoint: Option<SocketAddr>) {
     )
     .set(1.0);
 }
+
+/// Get the router for serving the metrics recorded by the given handle.
+pub fn get_router(handle: PrometheusHandle) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(handle)
+}
+
+/// A handler that renders all recorded metrics in the Prometheus text
+/// exposition format.
+pub async fn metrics_handler(State(handle): State<PrometheusHandle>) -> String {
+    handle.render()
+}
@@ -0,0 +1,337 @@
+//! A request/response protocol that signers use to fetch the decisions on
+//! deposit and withdrawal requests that they have missed, for example
+//! because they were offline while the decisions were gossiped.
+//!
+//! A signer asks its peers for their decisions on a set of requests,
+//! identified by a [`TxRequestIds`], and each peer responds with its own
+//! decisions, freshly signed, in a [`proto::SignerDecisions`] message.
+//! The messages in the response are verified exactly like the messages
+//! received over gossipsub before they are handed to the application.
+
+use std::io;
+use std::time::Duration;
+
+use futures::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+use libp2p::StreamProtocol;
+use libp2p::request_response;
+use prost::Message as _;
+
+use crate::bitcoin::validation::TxRequestIds;
+use crate::codec::Encode as _;
+use crate::context::Context;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::message::Payload;
+use crate::message::SignerDepositDecision;
+use crate::message::SignerWithdrawalDecision;
+use crate::network;
+use crate::proto;
+use crate::storage::DbRead as _;
+
+/// The name of the decisions sync protocol.
+pub const PROTOCOL: StreamProtocol = StreamProtocol::new("/sbtc/decisions/1.0.0");
+
+/// The maximum size, in bytes, of an encoded request.
+pub const MAX_REQUEST_SIZE: usize = 256 * 1024;
+
+/// The maximum size, in bytes, of an encoded response.
+pub const MAX_RESPONSE_SIZE: usize = 4 * 1024 * 1024;
+
+/// The maximum number of deposit requests, and separately of withdrawal
+/// requests, whose decisions are asked for in a single request. Larger
+/// sets of requests are split up with [`split_request_ids`].
+pub const MAX_REQUEST_IDS: usize = 1000;
+
+/// How long we wait for a peer to respond to a request.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The protobuf encoding of each message in a response adds a tag and a
+/// length prefix to the encoded message, which take at most this many
+/// bytes.
+const RESPONSE_MESSAGE_OVERHEAD: usize = 11;
+
+/// Encodes the requests and responses of the decisions sync protocol
+/// using the signer's protobuf definitions.
+#[derive(Debug, Clone, Default)]
+pub struct DecisionsCodec;
+
+#[async_trait::async_trait]
+impl request_response::Codec for DecisionsCodec {
+    type Protocol = StreamProtocol;
+    type Request = TxRequestIds;
+    type Response = proto::SignerDecisions;
+
+    async fn read_request<T>(&mut self, _: &StreamProtocol, io: &mut T) -> io::Result<TxRequestIds>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let bytes = read_bounded(io, MAX_REQUEST_SIZE).await?;
+        let request = proto::TxRequestIds::decode(bytes.as_slice()).map_err(invalid_data)?;
+        TxRequestIds::try_from(request).map_err(invalid_data)
+    }
+
+    async fn read_response<T>(
+        &mut self,
+        _: &StreamProtocol,
+        io: &mut T,
+    ) -> io::Result<proto::SignerDecisions>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let bytes = read_bounded(io, MAX_RESPONSE_SIZE).await?;
+        proto::SignerDecisions::decode(bytes.as_slice()).map_err(invalid_data)
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        _: &StreamProtocol,
+        io: &mut T,
+        request: TxRequestIds,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        let bytes = proto::TxRequestIds::from(request).encode_to_vec();
+        io.write_all(&bytes).await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        _: &StreamProtocol,
+        io: &mut T,
+        response: proto::SignerDecisions,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        io.write_all(&response.encode_to_vec()).await
+    }
+}
+
+/// Read the rest of the stream, failing if it holds more than `limit`
+/// bytes.
+async fn read_bounded<T>(io: &mut T, limit: usize) -> io::Result<Vec<u8>>
+where
+    T: AsyncRead + Unpin + Send,
+{
+    let mut bytes = Vec::new();
+    io.take(limit as u64 + 1).read_to_end(&mut bytes).await?;
+    if bytes.len() > limit {
+        return Err(invalid_data("the message exceeds the maximum size"));
+    }
+    Ok(bytes)
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Split the request ids into chunks that can each be sent in a single
+/// request.
+pub fn split_request_ids(request_ids: &TxRequestIds) -> Vec<TxRequestIds> {
+    let mut deposits = request_ids.deposits.chunks(MAX_REQUEST_IDS);
+    let mut withdrawals = request_ids.withdrawals.chunks(MAX_REQUEST_IDS);
+    let mut chunks = Vec::new();
+
+    loop {
+        let (deposits, withdrawals) = match (deposits.next(), withdrawals.next()) {
+            (None, None) => break,
+            (deposits, withdrawals) => (deposits, withdrawals),
+        };
+        chunks.push(TxRequestIds {
+            deposits: deposits.unwrap_or_default().to_vec(),
+            withdrawals: withdrawals.unwrap_or_default().to_vec(),
+        });
+    }
+
+    chunks
+}
+
+/// Build the response to a request for our decisions on the given
+/// deposit and withdrawal requests.
+///
+/// Our decisions are signed again, with our next P2P message sequence
+/// number, since the signatures of the messages that we originally
+/// gossiped are not kept. Requests that we have not made a decision on
+/// are skipped, as are any decisions that would not fit in the response.
+pub async fn respond_with_decisions(
+    ctx: &impl Context,
+    request_ids: &TxRequestIds,
+) -> Result<proto::SignerDecisions, Error> {
+    let storage = ctx.get_storage_mut();
+    let private_key = ctx.config().signer.private_key;
+    let public_key = PublicKey::from_private_key(&private_key);
+
+    let mut response = proto::SignerDecisions::default();
+    let Some(chain_tip) = storage.get_bitcoin_canonical_chain_tip().await? else {
+        return Ok(response);
+    };
+
+    let mut payloads: Vec<Payload> = Vec::new();
+    for outpoint in request_ids.deposits.iter().take(MAX_REQUEST_IDS) {
+        let decision = storage
+            .get_deposit_signers(&outpoint.txid.into(), outpoint.vout)
+            .await?
+            .into_iter()
+            .find(|decision| decision.signer_pub_key == public_key);
+
+        payloads.extend(decision.map(|decision| SignerDepositDecision::from(decision).into()));
+    }
+    for id in request_ids.withdrawals.iter().take(MAX_REQUEST_IDS) {
+        let decision = storage
+            .get_withdrawal_signers(id.request_id, &id.block_hash)
+            .await?
+            .into_iter()
+            .find(|decision| decision.signer_pub_key == public_key);
+
+        payloads.extend(decision.map(|decision| SignerWithdrawalDecision::from(decision).into()));
+    }
+
+    let mut response_size = 0;
+    for payload in payloads {
+        let msg = payload.to_message(chain_tip);
+        let msg = network::sign_with_next_sequence(&storage, msg, &private_key).await?;
+        let encoded = msg.encode_to_vec();
+
+        response_size += encoded.len() + RESPONSE_MESSAGE_OVERHEAD;
+        if response_size > MAX_RESPONSE_SIZE {
+            tracing::debug!("not all of our decisions fit in the response");
+            break;
+        }
+        response.messages.push(encoded);
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+    use futures::io::Cursor;
+    use libp2p::request_response::Codec as _;
+
+    use crate::storage::DbWrite as _;
+    use crate::storage::model;
+    use crate::testing::context::*;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    fn request_ids(num_deposits: usize, num_withdrawals: usize) -> TxRequestIds {
+        let mut rng = get_rng();
+        TxRequestIds {
+            deposits: (0..num_deposits)
+                .map(|_| Faker.fake_with_rng::<model::DepositRequest, _>(&mut rng))
+                .map(|request| request.outpoint())
+                .collect(),
+            withdrawals: (0..num_withdrawals)
+                .map(|_| Faker.fake_with_rng::<model::WithdrawalRequest, _>(&mut rng))
+                .map(|request| request.qualified_id())
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_roundtrip_through_the_codec() {
+        let request = request_ids(3, 2);
+
+        let mut bytes = Vec::new();
+        DecisionsCodec
+            .write_request(&PROTOCOL, &mut bytes, request.clone())
+            .await
+            .unwrap();
+        let decoded = DecisionsCodec
+            .read_request(&PROTOCOL, &mut Cursor::new(bytes))
+            .await
+            .unwrap();
+
+        assert_eq!(decoded, request);
+    }
+
+    #[tokio::test]
+    async fn oversized_responses_are_rejected() {
+        let response = proto::SignerDecisions {
+            messages: vec![vec![0; MAX_RESPONSE_SIZE]],
+        };
+
+        let mut bytes = Vec::new();
+        DecisionsCodec
+            .write_response(&PROTOCOL, &mut bytes, response)
+            .await
+            .unwrap();
+        let error = DecisionsCodec
+            .read_response(&PROTOCOL, &mut Cursor::new(bytes))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn large_requests_are_split_into_bounded_chunks() {
+        let request = request_ids(MAX_REQUEST_IDS + 1, 2);
+
+        let chunks = split_request_ids(&request);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].deposits, request.deposits[..MAX_REQUEST_IDS]);
+        assert_eq!(chunks[0].withdrawals, request.withdrawals);
+        assert_eq!(chunks[1].deposits, request.deposits[MAX_REQUEST_IDS..]);
+        assert!(chunks[1].withdrawals.is_empty());
+    }
+
+    #[tokio::test]
+    async fn responses_only_hold_our_own_decisions() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let storage = ctx.get_storage_mut();
+        let public_key = ctx.config().signer.public_key();
+
+        let mut rng = get_rng();
+        let chain_tip: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+        storage.write_bitcoin_block(&chain_tip).await.unwrap();
+
+        let request: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        let our_decision = model::DepositSigner {
+            txid: request.txid,
+            output_index: request.output_index,
+            signer_pub_key: public_key,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        let their_decision = model::DepositSigner {
+            txid: request.txid,
+            output_index: request.output_index,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        storage.write_deposit_request(&request).await.unwrap();
+        for decision in [&our_decision, &their_decision] {
+            storage
+                .write_deposit_signer_decision(decision)
+                .await
+                .unwrap();
+        }
+
+        let request_ids = TxRequestIds {
+            deposits: vec![request.outpoint()],
+            withdrawals: Vec::new(),
+        };
+        let response = respond_with_decisions(&ctx, &request_ids).await.unwrap();
+
+        assert_eq!(response.messages.len(), 1);
+        let msg = network::Msg::decode_with_digest(&response.messages[0])
+            .unwrap()
+            .0;
+        assert_eq!(msg.signer_public_key, public_key);
+        assert_eq!(msg.inner.bitcoin_chain_tip, chain_tip.block_hash);
+        assert_eq!(
+            msg.inner.payload,
+            Payload::SignerDepositDecision(our_decision.into())
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Delivery of the messages received from the P2P network to the local
+//! consumers, i.e. the [`P2PNetwork`](super::P2PNetwork) instances that
+//! receive messages.
+//!
+//! Each consumer gets its own bounded queue. When the queue of a consumer
+//! that is receiving messages is full, the dispatcher waits for the
+//! consumer to catch up instead of dropping messages, and signals
+//! [`P2PEvent::LaggedMessages`] to the application so that the slow
+//! consumer can be identified.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::SIGNER_CHANNEL_CAPACITY;
+use crate::context::Context;
+use crate::context::P2PEvent;
+use crate::network::Msg;
+
+/// The statistics of a consumer, shared between the dispatcher and the
+/// consumer's [`MessageReceiver`].
+#[derive(Debug, Default)]
+struct ConsumerStats {
+    /// Whether the consumer has started receiving messages. Consumers that
+    /// have never asked for a message, such as networks that are only
+    /// used for broadcasting, do not hold the dispatcher back.
+    active: AtomicBool,
+    /// The number of messages that found the queue of the consumer full.
+    lagged: AtomicU64,
+    /// The number of messages that were not delivered to the consumer
+    /// because its queue was full before it started receiving messages.
+    dropped: AtomicU64,
+}
+
+/// A consumer registered with the [`MessageDispatcher`].
+#[derive(Debug)]
+struct Consumer {
+    id: u64,
+    sender: mpsc::Sender<Msg>,
+    stats: Arc<ConsumerStats>,
+}
+
+/// Fans out the messages received from the P2P network to the registered
+/// consumers.
+#[derive(Debug, Clone)]
+pub struct MessageDispatcher {
+    consumers: Arc<Mutex<Vec<Consumer>>>,
+    next_consumer_id: Arc<AtomicU64>,
+    capacity: usize,
+}
+
+impl Default for MessageDispatcher {
+    fn default() -> Self {
+        Self::with_capacity(SIGNER_CHANNEL_CAPACITY)
+    }
+}
+
+impl MessageDispatcher {
+    /// Create a new dispatcher where the queue of each consumer holds up
+    /// to `capacity` messages.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            consumers: Arc::new(Mutex::new(Vec::new())),
+            next_consumer_id: Arc::new(AtomicU64::new(0)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Register a new consumer, which receives all messages dispatched
+    /// from now on.
+    pub fn subscribe(&self) -> MessageReceiver {
+        let id = self.next_consumer_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        let stats = Arc::new(ConsumerStats::default());
+
+        let consumer = Consumer {
+            id,
+            sender,
+            stats: Arc::clone(&stats),
+        };
+        self.consumers
+            .lock()
+            .expect("message dispatcher lock poisoned")
+            .push(consumer);
+
+        MessageReceiver { id, receiver, stats }
+    }
+
+    /// The number of registered consumers.
+    pub fn num_consumers(&self) -> usize {
+        self.consumers
+            .lock()
+            .expect("message dispatcher lock poisoned")
+            .len()
+    }
+
+    /// Deliver the message to every consumer, waiting for the consumers
+    /// whose queue is full to catch up.
+    pub async fn dispatch(&self, ctx: &impl Context, msg: Msg) {
+        // Clone the senders so that the lock isn't held across awaits.
+        let consumers: Vec<(u64, mpsc::Sender<Msg>, Arc<ConsumerStats>)> = self
+            .consumers
+            .lock()
+            .expect("message dispatcher lock poisoned")
+            .iter()
+            .map(|consumer| {
+                let stats = Arc::clone(&consumer.stats);
+                (consumer.id, consumer.sender.clone(), stats)
+            })
+            .collect();
+
+        for (consumer_id, sender, stats) in consumers {
+            let msg = match sender.try_send(msg.clone()) {
+                Ok(()) => continue,
+                Err(TrySendError::Closed(_)) => {
+                    self.unsubscribe(consumer_id);
+                    continue;
+                }
+                Err(TrySendError::Full(msg)) => msg,
+            };
+
+            if !stats.active.load(Ordering::Acquire) {
+                let dropped = stats.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                tracing::debug!(
+                    consumer_id,
+                    dropped,
+                    "dropping P2P message for a consumer that is not receiving messages"
+                );
+                continue;
+            }
+
+            let lagged = stats.lagged.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::warn!(
+                consumer_id,
+                lagged,
+                "P2P message consumer is lagging; waiting for it to catch up"
+            );
+            let _ = ctx
+                .get_signal_sender()
+                .send(P2PEvent::LaggedMessages { consumer_id, lagged }.into());
+
+            if sender.send(msg).await.is_err() {
+                self.unsubscribe(consumer_id);
+            }
+        }
+    }
+
+    /// Remove the consumer with the given id.
+    fn unsubscribe(&self, consumer_id: u64) {
+        self.consumers
+            .lock()
+            .expect("message dispatcher lock poisoned")
+            .retain(|consumer| consumer.id != consumer_id);
+    }
+}
+
+/// The receiving end of a consumer registered with the
+/// [`MessageDispatcher`].
+#[derive(Debug)]
+pub struct MessageReceiver {
+    id: u64,
+    receiver: mpsc::Receiver<Msg>,
+    stats: Arc<ConsumerStats>,
+}
+
+impl MessageReceiver {
+    /// The id of the consumer, as reported in
+    /// [`P2PEvent::LaggedMessages`].
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Receive the next message. Returns `None` if the dispatcher has been
+    /// dropped.
+    pub async fn recv(&mut self) -> Option<Msg> {
+        self.stats.active.store(true, Ordering::Release);
+        self.receiver.recv().await
+    }
+
+    /// The number of messages that found the queue of this consumer full,
+    /// making the dispatcher wait for it.
+    pub fn lagged(&self) -> u64 {
+        self.stats.lagged.load(Ordering::Relaxed)
+    }
+
+    /// The number of messages that were not delivered to this consumer
+    /// because its queue was full before it started receiving messages.
+    pub fn dropped(&self) -> u64 {
+        self.stats.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    use crate::context::SignerEvent;
+    use crate::context::SignerSignal;
+    use crate::testing::context::*;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn every_consumer_receives_every_message() {
+        let ctx = TestContext::default_mocked();
+        let dispatcher = MessageDispatcher::with_capacity(4);
+        let mut receiver1 = dispatcher.subscribe();
+        let mut receiver2 = dispatcher.subscribe();
+        assert_eq!(dispatcher.num_consumers(), 2);
+
+        let msg = Msg::random(&mut get_rng());
+        dispatcher.dispatch(&ctx, msg.clone()).await;
+
+        assert_eq!(receiver1.recv().await, Some(msg.clone()));
+        assert_eq!(receiver2.recv().await, Some(msg));
+    }
+
+    #[tokio::test]
+    async fn dropped_consumers_are_unsubscribed() {
+        let ctx = TestContext::default_mocked();
+        let dispatcher = MessageDispatcher::with_capacity(4);
+        let mut receiver = dispatcher.subscribe();
+        drop(dispatcher.subscribe());
+
+        let msg = Msg::random(&mut get_rng());
+        dispatcher.dispatch(&ctx, msg.clone()).await;
+
+        assert_eq!(dispatcher.num_consumers(), 1);
+        assert_eq!(receiver.recv().await, Some(msg));
+    }
+
+    #[tokio::test]
+    async fn idle_consumers_do_not_hold_back_the_dispatcher() {
+        let ctx = TestContext::default_mocked();
+        let dispatcher = MessageDispatcher::with_capacity(4);
+        let receiver = dispatcher.subscribe();
+
+        let mut rng = get_rng();
+        for _ in 0..10 {
+            dispatcher.dispatch(&ctx, Msg::random(&mut rng)).await;
+        }
+
+        assert_eq!(receiver.dropped(), 6);
+        assert_eq!(receiver.lagged(), 0);
+    }
+
+    #[tokio::test]
+    async fn slow_consumers_apply_backpressure_without_losing_messages() {
+        const NUM_MESSAGES: u64 = 10_000;
+
+        let ctx = TestContext::default_mocked();
+        let mut signals = ctx.get_signal_receiver();
+        let dispatcher = MessageDispatcher::with_capacity(16);
+        let mut receiver = dispatcher.subscribe();
+        let consumer_id = receiver.id();
+
+        // The receiver deliberately takes its time every so often.
+        let slow_receiver = tokio::spawn(async move {
+            let mut sequences = Vec::new();
+            while let Some(msg) = receiver.recv().await {
+                sequences.push(msg.sequence);
+                if msg.sequence % 500 == 0 {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                if sequences.len() as u64 == NUM_MESSAGES {
+                    break;
+                }
+            }
+            (sequences, receiver)
+        });
+        tokio::task::yield_now().await;
+
+        let template = Msg::random(&mut get_rng());
+        for sequence in 0..NUM_MESSAGES {
+            let mut msg = template.clone();
+            msg.sequence = sequence;
+            dispatcher.dispatch(&ctx, msg).await;
+        }
+
+        let (sequences, receiver) = slow_receiver.await.unwrap();
+
+        // Every message was delivered, in order.
+        assert_eq!(sequences, (0..NUM_MESSAGES).collect::<Vec<_>>());
+        assert_eq!(receiver.dropped(), 0);
+
+        // The dispatcher had to wait for the receiver, and said so.
+        assert!(receiver.lagged() > 0);
+        let lagged_consumer_id = loop {
+            match signals.try_recv() {
+                Ok(SignerSignal::Event(SignerEvent::P2P(P2PEvent::LaggedMessages {
+                    consumer_id,
+                    ..
+                }))) => break consumer_id,
+                Ok(_) | Err(TryRecvError::Lagged(_)) => continue,
+                Err(error) => panic!("no lagged messages signal: {error}"),
+            }
+        };
+        assert_eq!(lagged_consumer_id, consumer_id);
+    }
+}
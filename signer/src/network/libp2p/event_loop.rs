@@ -1,22 +1,43 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use futures::StreamExt;
+use libp2p::core::ConnectedPoint;
 use libp2p::kad::RoutingUpdate;
-use libp2p::swarm::SwarmEvent;
-use libp2p::{Swarm, gossipsub, identify, kad, mdns};
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::{ConnectionId, SwarmEvent};
+use libp2p::{Multiaddr, PeerId, Swarm, gossipsub, identify, kad, mdns, request_response};
 use tokio::sync::Mutex;
 
+use crate::bitcoin::validation::TxRequestIds;
 use crate::codec::Encode;
-use crate::context::{Context, P2PEvent, SignerCommand, SignerSignal};
+use crate::context::{Context, P2PEvent, SignerCommand, SignerSet, SignerSignal, SwarmStatus};
 use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::message::Payload;
+use crate::metrics::Metrics;
 use crate::network::Msg;
+use crate::network::MsgId;
+use crate::proto;
+use crate::storage::DbWrite as _;
+use crate::{P2P_MESSAGE_SEQUENCE_WINDOW, SIGNER_CHANNEL_CAPACITY};
 
+use super::MultiaddrExt as _;
 use super::TOPIC;
+use super::decisions;
+use super::scoring::{PeerOffense, PeerScores};
 use super::swarm::{SignerBehavior, SignerBehaviorEvent};
 
 #[tracing::instrument(skip_all, name = "swarm")]
-pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
+pub async fn run(
+    ctx: &impl Context,
+    swarm: Arc<Mutex<Swarm<SignerBehavior>>>,
+    relay_servers: &[Multiaddr],
+) {
     // Subscribe to the gossipsub topic.
     let topic = TOPIC.clone();
     swarm
@@ -35,18 +56,33 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
     // Here we create a future that listens for `P2PPublish` commands from the
     // app signalling channel and pushes them into the outbound message queue.
     // This queue is then polled by the `poll_swarm` event loop to publish the
-    // messages to the network.
+    // messages to the network. The same goes for `P2PRequestDecisions`
+    // commands, which are queued up until the event loop asks our peers for
+    // the decisions.
     let outbox = Mutex::new(Vec::<Msg>::new());
+    let wanted_decisions = Mutex::new(Vec::<TxRequestIds>::new());
     let poll_outbound = async {
         tracing::debug!("p2p outbound message polling started");
         loop {
-            let Ok(SignerSignal::Command(SignerCommand::P2PPublish(payload))) =
-                signal_rx.recv().await
-            else {
-                continue;
-            };
+            match signal_rx.recv().await {
+                Ok(SignerSignal::Command(SignerCommand::P2PPublish(payload))) => {
+                    outbox.lock().await.push(payload);
+                }
+                Ok(SignerSignal::Command(SignerCommand::P2PRequestDecisions(request_ids))) => {
+                    wanted_decisions.lock().await.push(request_ids);
+                }
+                _ => continue,
+            }
+        }
+    };
 
-            outbox.lock().await.push(payload);
+    // Here we create a future that delivers the messages received from the
+    // network to each of their consumers, see the `MessageDispatcher`.
+    let (inbox_tx, mut inbox_rx) = tokio::sync::mpsc::channel::<Msg>(SIGNER_CHANNEL_CAPACITY);
+    let dispatcher = ctx.get_message_dispatcher();
+    let dispatch_inbound = async {
+        while let Some(msg) = inbox_rx.recv().await {
+            dispatcher.dispatch(ctx, msg).await;
         }
     };
 
@@ -58,6 +94,16 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
             .inspect_err(|error| tracing::error!(%error, "error signalling event loop start"));
         tracing::debug!("p2p network polling started");
 
+        let mut seen_messages = SeenMessages::new();
+        let mut swarm_status = SwarmStatusTracker::new();
+        let mut peer_scores = PeerScores::new(ctx.config().signer.p2p.peer_ban_duration);
+        let mut relays = RelayTracker::new(relay_servers);
+        let mut received = Vec::new();
+        let mut reached_peers = Vec::new();
+        let mut decision_sync = DecisionSync::new();
+        let mut decision_requests = Vec::new();
+        relays.dial_disconnected(&mut *swarm.lock().await);
+
         loop {
             // Poll the libp2p swarm for events, waiting for a maximum of 5ms
             // so that we don't starve the outbox.
@@ -69,6 +115,8 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
             if let Some(event) = event {
                 let mut swarm = swarm.lock().await;
 
+                let status_changed = swarm_status.observe(&event);
+
                 match event {
                     // mDNS autodiscovery events. These are used by the local
                     // peer to discover other peers on the local network.
@@ -82,7 +130,27 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                     }
                     // Gossipsub protocol events.
                     SwarmEvent::Behaviour(SignerBehaviorEvent::Gossipsub(event)) => {
-                        handle_gossipsub_event(&mut swarm, ctx, event)
+                        handle_gossipsub_event(
+                            &mut swarm,
+                            ctx,
+                            &mut seen_messages,
+                            &mut peer_scores,
+                            &mut received,
+                            event,
+                        )
+                    }
+                    // Requests for, and responses with, missed signer
+                    // decisions.
+                    SwarmEvent::Behaviour(SignerBehaviorEvent::Decisions(event)) => {
+                        handle_decisions_event(
+                            &mut swarm,
+                            ctx,
+                            &mut seen_messages,
+                            &mut peer_scores,
+                            &mut received,
+                            &mut decision_requests,
+                            event,
+                        )
                     }
                     SwarmEvent::NewListenAddr { address, .. } => {
                         tracing::info!(%address, "listener started");
@@ -105,11 +173,25 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                         peer_id,
                         ..
                     } => {
-                        if !ctx.state().current_signer_set().is_allowed_peer(&peer_id) {
+                        let relayed = endpoint.is_relayed();
+                        if relays.relay_connected(&mut swarm, peer_id, &endpoint) {
+                            tracing::debug!(%peer_id, ?endpoint, "connected to relay server");
+                        } else if !ctx.state().current_signer_set().is_allowed_peer(&peer_id) {
                             tracing::warn!(%connection_id, %peer_id, ?endpoint, "connected to peer, however it is not a known signer; disconnecting");
                             let _ = swarm.disconnect_peer_id(peer_id);
+                        } else if peer_scores.is_banned(&peer_id, Instant::now()) {
+                            tracing::warn!(%connection_id, %peer_id, ?endpoint, "connected to peer, however it is banned; disconnecting");
+                            let _ = swarm.disconnect_peer_id(peer_id);
                         } else {
-                            tracing::debug!(%peer_id, ?endpoint, "connected to peer");
+                            tracing::debug!(%peer_id, ?endpoint, relayed, "connected to peer");
+                            relays.peer_connected(&mut swarm, peer_id, connection_id, relayed);
+                            decision_sync.peer_connected(&mut swarm, peer_id);
+                            // We know that the peer can be reached at the
+                            // address that we dialed, so we remember it.
+                            if endpoint.is_dialer() && !relayed {
+                                let address = endpoint.get_remote_address().without_p2p_protocol();
+                                reached_peers.push((peer_id, address));
+                            }
                             if endpoint.is_dialer() && swarm.behaviour().kademlia.is_enabled() {
                                 let kad_addr = endpoint.get_remote_address();
                                 tracing::debug!(%peer_id, %kad_addr, "adding address to kademlia");
@@ -123,8 +205,16 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                             }
                         }
                     }
-                    SwarmEvent::ConnectionClosed { peer_id, cause, endpoint, .. } => {
+                    SwarmEvent::ConnectionClosed {
+                        peer_id,
+                        connection_id,
+                        cause,
+                        endpoint,
+                        num_established,
+                        ..
+                    } => {
                         tracing::trace!(%peer_id, ?cause, ?endpoint, "connection closed");
+                        relays.connection_closed(peer_id, connection_id, num_established);
                     }
                     SwarmEvent::IncomingConnection { local_addr, send_back_addr, .. } => {
                         tracing::trace!(%local_addr, %send_back_addr, "incoming connection");
@@ -199,11 +289,98 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
                             "autonat server event"
                         );
                     }
+                    SwarmEvent::Behaviour(SignerBehaviorEvent::RelayClient(event)) => {
+                        tracing::debug!(?event, "relay client event");
+                    }
+                    SwarmEvent::Behaviour(SignerBehaviorEvent::Dcutr(event)) => {
+                        match event.result {
+                            Ok(connection_id) => tracing::info!(
+                                remote_peer_id = %event.remote_peer_id,
+                                %connection_id,
+                                "upgraded relayed connection to a direct connection"
+                            ),
+                            Err(error) => tracing::debug!(
+                                remote_peer_id = %event.remote_peer_id,
+                                %error,
+                                "failed to upgrade relayed connection to a direct connection"
+                            ),
+                        }
+                    }
                     SwarmEvent::Behaviour(SignerBehaviorEvent::Bootstrap(_)) => {}
                     // The derived `SwarmEvent` is marked as #[non_exhaustive], so we must have a
                     // catch-all.
                     event => tracing::trace!(?event, "unhandled swarm event"),
                 }
+
+                let scores_changed = peer_scores.take_changed();
+                if status_changed || scores_changed {
+                    swarm_status.publish(ctx, &swarm, &peer_scores);
+                }
+            }
+
+            // Hand the messages that we received over to the dispatcher. If
+            // it is backed up then we wait for it, which in turn holds back
+            // the swarm, rather than dropping messages.
+            for msg in received.drain(..) {
+                if inbox_tx.send(msg).await.is_err() {
+                    tracing::warn!("P2P message dispatcher has stopped");
+                }
+            }
+
+            // Remember the addresses at which we reached our peers, so that
+            // we can dial them again after a restart.
+            for (peer_id, address) in reached_peers.drain(..) {
+                let _ = ctx
+                    .get_storage_mut()
+                    .upsert_p2p_peer(&peer_id, &address)
+                    .await
+                    .inspect_err(|error| {
+                        tracing::warn!(%peer_id, %address, %error, "failed to record the address of a peer")
+                    });
+            }
+
+            // Respond to the peers that asked for our decisions. This reads
+            // from the database, so it is done without holding the swarm
+            // lock.
+            for (peer_id, request_ids, channel) in decision_requests.drain(..) {
+                let response = match decisions::respond_with_decisions(ctx, &request_ids).await {
+                    Ok(response) => response,
+                    Err(error) => {
+                        tracing::warn!(%peer_id, %error, "could not look up our decisions for peer");
+                        continue;
+                    }
+                };
+                let num_decisions = response.messages.len();
+                let sent = swarm
+                    .lock()
+                    .await
+                    .behaviour_mut()
+                    .decisions
+                    .send_response(channel, response);
+                match sent {
+                    Ok(()) => {
+                        tracing::debug!(%peer_id, num_decisions, "sent our decisions to peer")
+                    }
+                    Err(_) => tracing::debug!(%peer_id, "peer stopped waiting for our decisions"),
+                }
+            }
+
+            // Ask our peers for the decisions that the application is
+            // missing.
+            for request_ids in wanted_decisions.lock().await.drain(..) {
+                decision_sync.request(&mut *swarm.lock().await, ctx, request_ids);
+            }
+
+            // The gossipsub mesh changes and bans expire without emitting
+            // swarm events, so we refresh the status periodically too.
+            if swarm_status.refresh_due() {
+                let mut swarm = swarm.lock().await;
+                let now = Instant::now();
+                peer_scores.lift_expired_bans(now);
+                peer_scores.recover_scores(now);
+                relays.dial_disconnected(&mut swarm);
+                swarm_status.publish(ctx, &swarm, &peer_scores);
+                swarm_status.log_changes();
             }
 
             // Drain the outbox and publish the messages to the network.
@@ -258,6 +435,7 @@ pub async fn run(ctx: &impl Context, swarm: Arc<Mutex<Swarm<SignerBehavior>>>) {
         },
         _ = poll_outbound => {},
         _ = poll_swarm => {},
+        _ = dispatch_inbound => {},
         _ = log => {},
     }
 
@@ -355,6 +533,9 @@ fn handle_identify_event(
 fn handle_gossipsub_event(
     swarm: &mut Swarm<SignerBehavior>,
     ctx: &impl Context,
+    seen_messages: &mut SeenMessages,
+    peer_scores: &mut PeerScores,
+    received: &mut Vec<Msg>,
     event: gossipsub::Event,
 ) {
     use gossipsub::Event;
@@ -373,53 +554,79 @@ fn handle_gossipsub_event(
             // we should re-evaluate whether we should remove this check.
             if !current_signer_set.is_allowed_peer(&peer_id) {
                 tracing::warn!(%peer_id, "ignoring message from unknown peer");
+                MessageRejection::UnknownPeer.record();
                 return;
             }
 
             // The message may have originated from someone else, let's
             // check that peer ID too. If we haven't been told the source
             // then we distrust the message and ignore it.
+            let now = Instant::now();
             let Some(origin_peer_id) = message.source else {
                 tracing::warn!(%peer_id, "origin peer id unknown, ignoring message");
+                MessageRejection::MissingOrigin.record();
+                penalize_peer(
+                    swarm,
+                    peer_scores,
+                    peer_id,
+                    PeerOffense::MalformedMessage,
+                    now,
+                );
                 return;
             };
 
             if !current_signer_set.is_allowed_peer(&origin_peer_id) {
                 tracing::warn!(%origin_peer_id, "ignoring message from unknown origin peer");
+                MessageRejection::UnknownOrigin.record();
                 return;
             }
 
-            Msg::decode_with_digest(&message.data)
-                .and_then(|(msg, digest)| {
-                    tracing::trace!(
-                        local_peer_id = %swarm.local_peer_id(),
-                        %peer_id,
-                        message_id = hex::encode(msg.id()),
-                        %msg,
-                        "received message",
-                    );
-
-                    if origin_peer_id != msg.signer_public_key.into() {
-                        tracing::error!(%origin_peer_id, "connected peer sent an invalid message");
-                        return Err(Error::InvalidSignature)
-                    }
+            // Gossipsub messages are signed by their origin, so we hold the
+            // origin peer accountable for the messages below.
+            if peer_scores.record_message(origin_peer_id, now) {
+                tracing::debug!(%origin_peer_id, "ignoring message from banned peer");
+                MessageRejection::BannedPeer.record();
+                let _ = swarm.disconnect_peer_id(origin_peer_id);
+                return;
+            }
 
-                    if let Err(error) = msg.verify_digest(digest) {
-                        tracing::error!(%origin_peer_id, "connected peer sent an invalid signature");
-                        return Err(error)
+            let msg = match verify_gossip_message(
+                current_signer_set,
+                &origin_peer_id,
+                &message.data,
+            ) {
+                Ok(msg) => msg,
+                Err(rejection) => {
+                    tracing::warn!(%peer_id, %origin_peer_id, %rejection, "dropping invalid message");
+                    rejection.record();
+                    if let Some(offense) = rejection.offense() {
+                        penalize_peer(swarm, peer_scores, origin_peer_id, offense, now);
                     }
+                    return;
+                }
+            };
 
-                    let _ = ctx.get_signal_sender()
-                        .send(P2PEvent::MessageReceived(msg).into())
-                        .inspect_err(|error| {
-                            tracing::debug!(%error, "Failed to send message to application; we are likely shutting down.");
-                        });
+            // Anyone can re-send a message that was validly signed by its
+            // origin, so we hold the peer that relayed a replayed message to
+            // us accountable for it instead.
+            if let Err(rejection) = seen_messages.insert(&msg) {
+                tracing::debug!(%peer_id, %origin_peer_id, %rejection, "dropping replayed message");
+                rejection.record();
+                if let Some(offense) = rejection.offense() {
+                    penalize_peer(swarm, peer_scores, peer_id, offense, now);
+                }
+                return;
+            }
 
-                    Ok(())
-                })
-                .unwrap_or_else(|error| {
-                    tracing::warn!(%peer_id, %error, "Failed to decode message");
-                });
+            tracing::trace!(
+                local_peer_id = %swarm.local_peer_id(),
+                %peer_id,
+                message_id = hex::encode(msg.id()),
+                %msg,
+                "received message",
+            );
+
+            deliver_message(ctx, received, msg);
         }
         Event::Subscribed { peer_id, topic } => {
             tracing::debug!(%peer_id, %topic, "subscribed to topic");
@@ -443,3 +650,924 @@ fn handle_gossipsub_event(
         }
     }
 }
+
+/// A request from a peer for our decisions, along with the channel to
+/// respond on.
+type DecisionRequest = (
+    PeerId,
+    TxRequestIds,
+    request_response::ResponseChannel<proto::SignerDecisions>,
+);
+
+#[tracing::instrument(skip_all, name = "decisions")]
+fn handle_decisions_event(
+    swarm: &mut Swarm<SignerBehavior>,
+    ctx: &impl Context,
+    seen_messages: &mut SeenMessages,
+    peer_scores: &mut PeerScores,
+    received: &mut Vec<Msg>,
+    decision_requests: &mut Vec<DecisionRequest>,
+    event: request_response::Event<TxRequestIds, proto::SignerDecisions>,
+) {
+    use request_response::{Event, Message};
+
+    match event {
+        Event::Message {
+            peer: peer_id,
+            message: Message::Request { request, channel, .. },
+            ..
+        } => {
+            // Only signers can connect to us, so this should never happen.
+            if !ctx.state().current_signer_set().is_allowed_peer(&peer_id) {
+                tracing::warn!(%peer_id, "ignoring decisions request from unknown peer");
+                return;
+            }
+
+            tracing::debug!(
+                %peer_id,
+                deposits = request.deposits.len(),
+                withdrawals = request.withdrawals.len(),
+                "peer asked for our decisions"
+            );
+            decision_requests.push((peer_id, request, channel));
+        }
+        Event::Message {
+            peer: peer_id,
+            message: Message::Response { response, .. },
+            ..
+        } => {
+            let current_signer_set = ctx.state().current_signer_set();
+            let now = Instant::now();
+            tracing::debug!(%peer_id, num_decisions = response.messages.len(), "received decisions from peer");
+
+            // Each peer responds with its own decisions, so we hold the
+            // responding peer accountable for the messages below, just
+            // like the origin of a gossipsub message.
+            for data in response.messages {
+                let msg = match verify_synced_decision(current_signer_set, &peer_id, &data) {
+                    Ok(msg) => msg,
+                    Err(rejection) => {
+                        tracing::warn!(%peer_id, %rejection, "dropping invalid decision");
+                        rejection.record();
+                        if let Some(offense) = rejection.offense() {
+                            penalize_peer(swarm, peer_scores, peer_id, offense, now);
+                        }
+                        continue;
+                    }
+                };
+
+                if let Err(rejection) = seen_messages.insert(&msg) {
+                    tracing::debug!(%peer_id, %rejection, "dropping replayed decision");
+                    rejection.record();
+                    continue;
+                }
+
+                deliver_message(ctx, received, msg);
+            }
+        }
+        Event::OutboundFailure {
+            peer: peer_id,
+            request_id,
+            error,
+            ..
+        } => {
+            tracing::warn!(%peer_id, %request_id, %error, "could not fetch decisions from peer");
+        }
+        Event::InboundFailure {
+            peer: peer_id,
+            request_id,
+            error,
+            ..
+        } => {
+            tracing::debug!(%peer_id, %request_id, %error, "could not send our decisions to peer");
+        }
+        Event::ResponseSent { peer: peer_id, request_id, .. } => {
+            tracing::trace!(%peer_id, %request_id, "finished sending our decisions to peer");
+        }
+    }
+}
+
+/// Hand a verified message over to the application.
+fn deliver_message(ctx: &impl Context, received: &mut Vec<Msg>, msg: Msg) {
+    received.push(msg.clone());
+    let _ = ctx
+        .get_signal_sender()
+        .send(P2PEvent::MessageReceived(msg).into())
+        .inspect_err(|error| {
+            tracing::debug!(%error, "Failed to send message to application; we are likely shutting down.");
+        });
+}
+
+/// The reasons for dropping a message received over gossipsub. The
+/// variant name is used as the `reason` label of the
+/// [`Metrics::P2PMessagesRejectedTotal`] counter.
+#[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+enum MessageRejection {
+    /// The message was relayed by a peer that is not in the current
+    /// signer set.
+    #[error("the relaying peer is not in the signer set")]
+    UnknownPeer,
+    /// The message was published by a peer that is banned for
+    /// misbehaving.
+    #[error("the origin peer is banned")]
+    BannedPeer,
+    /// The message did not say which peer published it.
+    #[error("the origin peer is unknown")]
+    MissingOrigin,
+    /// The message was published by a peer that is not in the current
+    /// signer set.
+    #[error("the origin peer is not in the signer set")]
+    UnknownOrigin,
+    /// The message could not be decoded.
+    #[error("could not decode the message: {0}")]
+    InvalidEncoding(#[source] Error),
+    /// The message uses a newer version of the wire format than we
+    /// support.
+    #[error("unsupported message version: {0}")]
+    UnsupportedVersion(u32),
+    /// The public key in the message does not belong to the peer that
+    /// published it.
+    #[error("the message was not signed by the origin peer")]
+    SignerMismatch,
+    /// The message was signed by a key that is not in the current signer
+    /// set.
+    #[error("the message was signed by a key outside of the signer set")]
+    NonMemberSigner,
+    /// The signature does not match the contents of the message.
+    #[error("invalid message signature: {0}")]
+    InvalidSignature(#[source] Error),
+    /// We have already received this message.
+    #[error("the message has already been received")]
+    Duplicate,
+    /// The message does not have a sequence number, so we cannot tell
+    /// whether it is a replay.
+    #[error("the message does not have a sequence number")]
+    MissingSequence,
+    /// The sequence number of the message is too far behind the highest
+    /// sequence number that we have seen from the signer.
+    #[error("the message sequence number {0} is outside of the accepted window")]
+    StaleSequence(u64),
+    /// A message that we received in response to a request for signer
+    /// decisions does not carry a decision.
+    #[error("the message is not a signer decision")]
+    UnexpectedPayload,
+}
+
+impl MessageRejection {
+    /// The offense that the peer that published the rejected message
+    /// committed, if any.
+    fn offense(&self) -> Option<PeerOffense> {
+        match self {
+            MessageRejection::InvalidEncoding(_) | MessageRejection::UnexpectedPayload => {
+                Some(PeerOffense::MalformedMessage)
+            }
+            MessageRejection::SignerMismatch
+            | MessageRejection::NonMemberSigner
+            | MessageRejection::InvalidSignature(_) => Some(PeerOffense::InvalidSignature),
+            MessageRejection::Duplicate | MessageRejection::StaleSequence(_) => {
+                Some(PeerOffense::DuplicateMessage)
+            }
+            // Peers that support a newer version of the wire format, or
+            // that have not yet upgraded to sequenced messages, are not
+            // misbehaving, and the remaining rejections are about the
+            // peers that relay the message to us rather than about its
+            // origin.
+            MessageRejection::UnsupportedVersion(_)
+            | MessageRejection::MissingSequence
+            | MessageRejection::UnknownPeer
+            | MessageRejection::BannedPeer
+            | MessageRejection::MissingOrigin
+            | MessageRejection::UnknownOrigin => None,
+        }
+    }
+
+    /// Increment the rejected P2P messages counter for this rejection.
+    fn record(&self) {
+        let reason: &'static str = self.into();
+        metrics::counter!(Metrics::P2PMessagesRejectedTotal, "reason" => reason).increment(1);
+    }
+}
+
+/// Decode a message received over gossipsub and verify that it was signed
+/// by a member of the current signer set, and that this member is the
+/// peer that published the message.
+fn verify_gossip_message(
+    signer_set: &SignerSet,
+    origin_peer_id: &PeerId,
+    data: &[u8],
+) -> Result<Msg, MessageRejection> {
+    let (msg, digest) = Msg::decode_with_digest(data).map_err(|error| match error {
+        Error::UnsupportedMessageVersion(version) => MessageRejection::UnsupportedVersion(version),
+        error => MessageRejection::InvalidEncoding(error),
+    })?;
+
+    if *origin_peer_id != msg.signer_public_key.into() {
+        return Err(MessageRejection::SignerMismatch);
+    }
+
+    if !signer_set.is_signer(&msg.signer_public_key) {
+        return Err(MessageRejection::NonMemberSigner);
+    }
+
+    msg.verify_digest(digest)
+        .map_err(MessageRejection::InvalidSignature)?;
+
+    Ok(msg)
+}
+
+/// Verify a message that a peer sent us in response to a request for
+/// signer decisions. Peers only respond with their own decisions, so on
+/// top of the checks made for gossipsub messages, the message must have
+/// been signed by the responding peer and it must carry a decision.
+fn verify_synced_decision(
+    signer_set: &SignerSet,
+    peer_id: &PeerId,
+    data: &[u8],
+) -> Result<Msg, MessageRejection> {
+    let msg = verify_gossip_message(signer_set, peer_id, data)?;
+
+    match msg.inner.payload {
+        Payload::SignerDepositDecision(_) | Payload::SignerWithdrawalDecision(_) => Ok(msg),
+        _ => Err(MessageRejection::UnexpectedPayload),
+    }
+}
+
+/// Penalize the peer for the given offense, disconnecting from it if this
+/// gets it banned.
+fn penalize_peer(
+    swarm: &mut Swarm<SignerBehavior>,
+    peer_scores: &mut PeerScores,
+    peer_id: PeerId,
+    offense: PeerOffense,
+    now: Instant,
+) {
+    if peer_scores.penalize(peer_id, offense, now) {
+        let _ = swarm.disconnect_peer_id(peer_id);
+    }
+}
+
+/// Keeps track of our connections to relay servers, and of the connections
+/// to other signers that go through a relay.
+struct RelayTracker {
+    /// The configured addresses of the relay servers.
+    servers: Vec<Multiaddr>,
+    /// The relay servers that we are connected to, along with the
+    /// configured address that we reached each of them on.
+    connected: HashMap<PeerId, Multiaddr>,
+    /// The relayed connections to each signer.
+    relayed_connections: HashMap<PeerId, HashSet<ConnectionId>>,
+}
+
+impl RelayTracker {
+    fn new(servers: &[Multiaddr]) -> Self {
+        Self {
+            servers: servers.to_vec(),
+            connected: HashMap::new(),
+            relayed_connections: HashMap::new(),
+        }
+    }
+
+    /// Dial the relay servers that we are not connected to.
+    fn dial_disconnected(&self, swarm: &mut Swarm<SignerBehavior>) {
+        for addr in self.servers.iter() {
+            if self.connected.values().any(|connected| connected == addr) {
+                continue;
+            }
+            tracing::debug!(%addr, "dialing relay server");
+            if let Err(error) = swarm.dial(addr.clone()) {
+                tracing::warn!(%addr, %error, "failed to dial relay server");
+            }
+        }
+    }
+
+    /// Handle a newly established connection, returning whether it is a
+    /// connection to one of our relay servers. The first time that we
+    /// connect to a relay server we reserve a slot on it, so that peers
+    /// can reach us through the relay.
+    fn relay_connected(
+        &mut self,
+        swarm: &mut Swarm<SignerBehavior>,
+        peer_id: PeerId,
+        endpoint: &ConnectedPoint,
+    ) -> bool {
+        if self.connected.contains_key(&peer_id) {
+            return true;
+        }
+
+        // We only know the relay servers by their address, so we can only
+        // recognize them on connections that we dialed.
+        let ConnectedPoint::Dialer { address, .. } = endpoint else {
+            return false;
+        };
+        // Connections that go through a relay server are not connections to
+        // the relay server itself.
+        if address.is_relayed() {
+            return false;
+        }
+        let address = address.without_p2p_protocol();
+        let Some(server) = self
+            .servers
+            .iter()
+            .find(|server| server.without_p2p_protocol() == address)
+        else {
+            return false;
+        };
+
+        self.connected.insert(peer_id, server.clone());
+
+        let circuit_addr = address
+            .with(Protocol::P2p(peer_id))
+            .with(Protocol::P2pCircuit);
+        tracing::info!(%circuit_addr, "reserving a slot on relay server");
+        if let Err(error) = swarm.listen_on(circuit_addr.clone()) {
+            tracing::warn!(%circuit_addr, %error, "failed to listen on relay server");
+        }
+        true
+    }
+
+    /// Handle a newly established connection to a signer. We prefer direct
+    /// connections, so once we have one to the signer we close the
+    /// relayed connections to it.
+    fn peer_connected(
+        &mut self,
+        swarm: &mut Swarm<SignerBehavior>,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        relayed: bool,
+    ) {
+        if relayed {
+            self.relayed_connections
+                .entry(peer_id)
+                .or_default()
+                .insert(connection_id);
+            return;
+        }
+
+        for relayed_connection_id in self
+            .relayed_connections
+            .remove(&peer_id)
+            .unwrap_or_default()
+        {
+            tracing::debug!(
+                %peer_id,
+                %relayed_connection_id,
+                "closing relayed connection now that we are directly connected"
+            );
+            swarm.close_connection(relayed_connection_id);
+        }
+    }
+
+    /// Handle a closed connection.
+    fn connection_closed(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        num_established: u32,
+    ) {
+        if num_established == 0 && self.connected.remove(&peer_id).is_some() {
+            tracing::info!(%peer_id, "disconnected from relay server");
+        }
+        if let Some(connections) = self.relayed_connections.get_mut(&peer_id) {
+            connections.remove(&connection_id);
+            if connections.is_empty() {
+                self.relayed_connections.remove(&peer_id);
+            }
+        }
+    }
+}
+
+/// How long we keep asking the signers that we connect to for the
+/// decisions that the application is missing.
+const DECISION_SYNC_PERIOD: Duration = Duration::from_secs(300);
+
+/// Keeps track of the decisions that we have asked our peers for, so that
+/// the signers that we connect to shortly after asking are asked too. This
+/// matters after a restart, when we ask for the decisions that we missed
+/// before we have reconnected to all of our peers.
+struct DecisionSync {
+    /// The requests whose decisions we are missing, along with when the
+    /// application asked for them.
+    wanted: Option<(TxRequestIds, Instant)>,
+    /// The peers that we have asked for the decisions.
+    asked: HashSet<PeerId>,
+}
+
+impl DecisionSync {
+    fn new() -> Self {
+        Self {
+            wanted: None,
+            asked: HashSet::new(),
+        }
+    }
+
+    /// Ask the signers that we are connected to for their decisions on the
+    /// given requests. This replaces any earlier request.
+    fn request(
+        &mut self,
+        swarm: &mut Swarm<SignerBehavior>,
+        ctx: &impl Context,
+        request_ids: TxRequestIds,
+    ) {
+        self.wanted = Some((request_ids, Instant::now()));
+        self.asked.clear();
+
+        let signer_set = ctx.state().current_signer_set();
+        let peers: Vec<PeerId> = swarm
+            .connected_peers()
+            .filter(|peer_id| signer_set.is_allowed_peer(peer_id))
+            .copied()
+            .collect();
+        for peer_id in peers {
+            self.peer_connected(swarm, peer_id);
+        }
+    }
+
+    /// Ask the signer that we have just connected to for the decisions
+    /// that we are missing, unless we have asked it already.
+    fn peer_connected(&mut self, swarm: &mut Swarm<SignerBehavior>, peer_id: PeerId) {
+        let Some((request_ids, requested_at)) = &self.wanted else {
+            return;
+        };
+        if requested_at.elapsed() >= DECISION_SYNC_PERIOD {
+            self.wanted = None;
+            self.asked.clear();
+            return;
+        }
+        if !self.asked.insert(peer_id) {
+            return;
+        }
+
+        for request_ids in decisions::split_request_ids(request_ids) {
+            let request_id = swarm
+                .behaviour_mut()
+                .decisions
+                .send_request(&peer_id, request_ids);
+            tracing::debug!(%peer_id, %request_id, "asking peer for missed decisions");
+        }
+    }
+}
+
+/// How often the swarm status is refreshed and changes to it are logged.
+const SWARM_STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Maintains the [`SwarmStatus`] snapshot that is served by the signer
+/// state.
+struct SwarmStatusTracker {
+    /// The current status of the swarm.
+    status: SwarmStatus,
+    /// The status when we last logged it.
+    last_logged: SwarmStatus,
+    /// When the status was last refreshed.
+    last_refreshed: Instant,
+    /// The established connections to each peer, along with whether each
+    /// of them goes through a relay.
+    connections: HashMap<PeerId, HashMap<ConnectionId, bool>>,
+}
+
+impl SwarmStatusTracker {
+    fn new() -> Self {
+        Self {
+            status: SwarmStatus::default(),
+            last_logged: SwarmStatus::default(),
+            last_refreshed: Instant::now(),
+            connections: HashMap::new(),
+        }
+    }
+
+    /// Update the connection state using the given swarm event. Returns
+    /// whether the event changed the status.
+    fn observe(&mut self, event: &SwarmEvent<SignerBehaviorEvent>) -> bool {
+        let status = &mut self.status;
+        match event {
+            SwarmEvent::Dialing { peer_id: Some(peer_id), .. } => {
+                status.pending_dials.insert(*peer_id);
+            }
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                connection_id,
+                endpoint,
+                ..
+            } => {
+                status.pending_dials.remove(peer_id);
+                status
+                    .connected_peers
+                    .entry(*peer_id)
+                    .or_default()
+                    .push(endpoint.get_remote_address().clone());
+                self.connections
+                    .entry(*peer_id)
+                    .or_default()
+                    .insert(*connection_id, endpoint.is_relayed());
+                self.update_relayed(*peer_id);
+            }
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                connection_id,
+                endpoint,
+                num_established,
+                ..
+            } => {
+                if *num_established == 0 {
+                    status.connected_peers.remove(peer_id);
+                    self.connections.remove(peer_id);
+                } else {
+                    if let Some(addresses) = status.connected_peers.get_mut(peer_id) {
+                        let address = endpoint.get_remote_address();
+                        if let Some(index) = addresses.iter().position(|addr| addr == address) {
+                            addresses.remove(index);
+                        }
+                    }
+                    if let Some(connections) = self.connections.get_mut(peer_id) {
+                        connections.remove(connection_id);
+                    }
+                }
+                self.update_relayed(*peer_id);
+            }
+            SwarmEvent::OutgoingConnectionError {
+                peer_id: Some(peer_id), error, ..
+            } => {
+                status.pending_dials.remove(peer_id);
+                status.last_errors.insert(*peer_id, error.to_string());
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Record whether we are only connected to the peer through a relay.
+    fn update_relayed(&mut self, peer_id: PeerId) {
+        let relayed = self.connections.get(&peer_id).is_some_and(|connections| {
+            !connections.is_empty() && connections.values().all(|relayed| *relayed)
+        });
+        if relayed {
+            self.status.relayed_peers.insert(peer_id);
+        } else {
+            self.status.relayed_peers.remove(&peer_id);
+        }
+    }
+
+    /// Whether it is time to refresh the status.
+    fn refresh_due(&self) -> bool {
+        self.last_refreshed.elapsed() >= SWARM_STATUS_REFRESH_INTERVAL
+    }
+
+    /// Refresh the gossipsub mesh peers and peer scores, and publish the
+    /// status to the signer state and metrics.
+    fn publish(
+        &mut self,
+        ctx: &impl Context,
+        swarm: &Swarm<SignerBehavior>,
+        peer_scores: &PeerScores,
+    ) {
+        let gossipsub = &swarm.behaviour().gossipsub;
+        self.status.mesh_peers = gossipsub
+            .topics()
+            .map(|topic| {
+                (
+                    topic.to_string(),
+                    gossipsub.mesh_peers(topic).copied().collect(),
+                )
+            })
+            .collect();
+        self.status.peer_scores = peer_scores.scores();
+        self.status.banned_peers = peer_scores.banned_peers();
+        self.last_refreshed = Instant::now();
+
+        metrics::gauge!(Metrics::P2PPeersConnected).set(self.status.num_connected_peers() as f64);
+        metrics::gauge!(Metrics::P2PMeshPeers).set(self.status.num_mesh_peers() as f64);
+        ctx.state().set_p2p_status(self.status.clone());
+    }
+
+    /// Log the status if it changed since the last time it was logged.
+    fn log_changes(&mut self) {
+        if self.status == self.last_logged {
+            return;
+        }
+
+        tracing::info!(
+            connected_peers = self.status.num_connected_peers(),
+            mesh_peers = self.status.num_mesh_peers(),
+            pending_dials = self.status.pending_dials.len(),
+            failed_peers = self.status.last_errors.len(),
+            relayed_peers = self.status.relayed_peers.len(),
+            banned_peers = self.status.banned_peers.len(),
+            "p2p swarm status changed"
+        );
+        self.last_logged = self.status.clone();
+    }
+}
+
+/// Tracks the messages that we have recently received, so that messages
+/// re-sent by a peer are not handed to the application more than once.
+///
+/// Signers give every message that they sign a fresh sequence number, so a
+/// message is identified by its signer and sequence number. For each signer
+/// we remember every sequence number within [`P2P_MESSAGE_SEQUENCE_WINDOW`]
+/// of the highest one that we have seen, and reject anything older, so a
+/// replay is caught no matter how many messages other signers send in the
+/// meantime.
+#[derive(Default)]
+struct SeenMessages {
+    /// The sequence numbers received from each signer.
+    windows: HashMap<PublicKey, SequenceWindow>,
+}
+
+/// The sequence numbers that we have received from a single signer.
+#[derive(Default)]
+struct SequenceWindow {
+    /// The highest sequence number received from the signer.
+    highest: u64,
+    /// The sequence numbers received from the signer that are within the
+    /// window.
+    seen: BTreeSet<u64>,
+}
+
+impl SeenMessages {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the given message as received, returning an error if it is
+    /// a duplicate, if its sequence number is too old, or if it does not
+    /// have a sequence number at all.
+    fn insert(&mut self, msg: &Msg) -> Result<(), MessageRejection> {
+        if msg.sequence == 0 {
+            return Err(MessageRejection::MissingSequence);
+        }
+
+        let window = self.windows.entry(msg.signer_public_key).or_default();
+        if msg.sequence.saturating_add(P2P_MESSAGE_SEQUENCE_WINDOW) <= window.highest {
+            return Err(MessageRejection::StaleSequence(msg.sequence));
+        }
+        if !window.seen.insert(msg.sequence) {
+            return Err(MessageRejection::Duplicate);
+        }
+
+        if msg.sequence > window.highest {
+            window.highest = msg.sequence;
+            // Anything below the window is rejected as stale above, so
+            // there is no need to remember it.
+            let oldest = window.highest.saturating_sub(P2P_MESSAGE_SEQUENCE_WINDOW);
+            window.seen = window.seen.split_off(&oldest.saturating_add(1));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use fake::Faker;
+    use prost::Message as _;
+
+    use crate::ecdsa::SignEcdsa as _;
+    use crate::keys::PrivateKey;
+    use crate::message::SignerMessage;
+    use crate::proto;
+    use crate::storage::DbWrite as _;
+    use crate::storage::in_memory::Store;
+    use crate::testing::get_rng;
+
+    use super::*;
+
+    fn signed_message(private_key: &PrivateKey) -> Msg {
+        let mut rng = get_rng();
+        let payload: crate::message::SignerDepositDecision = Faker.fake_with_rng(&mut rng);
+        let message = SignerMessage {
+            bitcoin_chain_tip: Faker.fake_with_rng(&mut rng),
+            payload: payload.into(),
+        };
+        message.sign_ecdsa(private_key)
+    }
+
+    #[test]
+    fn messages_from_signer_set_members_are_accepted() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let signer_set = SignerSet::default();
+        signer_set.add_signer(public_key);
+
+        let msg = signed_message(&private_key);
+        let data = msg.clone().encode_to_vec();
+
+        let received = verify_gossip_message(&signer_set, &public_key.into(), &data).unwrap();
+        assert_eq!(received, msg);
+    }
+
+    #[test]
+    fn messages_with_a_tampered_payload_are_rejected() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let signer_set = SignerSet::default();
+        signer_set.add_signer(public_key);
+
+        // Change the chain tip after the message has been signed, keeping
+        // the original signature.
+        let mut msg = signed_message(&private_key);
+        msg.inner.bitcoin_chain_tip = Faker.fake_with_rng(&mut get_rng());
+        let data = msg.encode_to_vec();
+
+        let rejection = verify_gossip_message(&signer_set, &public_key.into(), &data).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::InvalidSignature(_)));
+    }
+
+    #[test]
+    fn messages_signed_by_non_members_are_rejected() {
+        let member_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let signer_set = SignerSet::default();
+        signer_set.add_signer(PublicKey::from_private_key(&member_key));
+
+        // The message is validly signed, but by a key that is not part of
+        // the current signer set.
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let data = signed_message(&private_key).encode_to_vec();
+
+        let rejection = verify_gossip_message(&signer_set, &public_key.into(), &data).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::NonMemberSigner));
+    }
+
+    #[test]
+    fn messages_claiming_another_signers_key_are_rejected() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let other_key = PublicKey::from_private_key(&PrivateKey::new(&mut rand::rngs::OsRng));
+        let signer_set = SignerSet::default();
+        signer_set.add_signer(public_key);
+        signer_set.add_signer(other_key);
+
+        let data = signed_message(&private_key).encode_to_vec();
+
+        let rejection = verify_gossip_message(&signer_set, &other_key.into(), &data).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::SignerMismatch));
+    }
+
+    #[test]
+    fn messages_with_a_newer_version_are_rejected() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let signer_set = SignerSet::default();
+        signer_set.add_signer(public_key);
+
+        let mut signed = proto::Signed::from(signed_message(&private_key));
+        signed.version = crate::ecdsa::SIGNED_MESSAGE_VERSION + 1;
+        let data = signed.encode_to_vec();
+
+        let rejection = verify_gossip_message(&signer_set, &public_key.into(), &data).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::UnsupportedVersion(_)));
+    }
+
+    #[test]
+    fn rebroadcast_messages_are_delivered_once() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let message = signed_message(&private_key).inner;
+        let first = message.clone().sign_ecdsa_with_sequence(&private_key, 1);
+        let second = message.sign_ecdsa_with_sequence(&private_key, 2);
+
+        let mut seen_messages = SeenMessages::new();
+        seen_messages.insert(&first).unwrap();
+        seen_messages.insert(&second).unwrap();
+
+        let rejection = seen_messages.insert(&first).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::Duplicate));
+        let rejection = seen_messages.insert(&second).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::Duplicate));
+    }
+
+    #[test]
+    fn messages_without_a_sequence_number_are_rejected() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let msg = signed_message(&private_key);
+
+        let mut seen_messages = SeenMessages::new();
+        let rejection = seen_messages.insert(&msg).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::MissingSequence));
+    }
+
+    #[test]
+    fn replays_are_rejected_regardless_of_traffic_from_other_signers() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let message = signed_message(&private_key).inner;
+        let msg = message.clone().sign_ecdsa_with_sequence(&private_key, 1);
+
+        let mut seen_messages = SeenMessages::new();
+        seen_messages.insert(&msg).unwrap();
+
+        // Lots of messages from other signers do not push the first one
+        // out of what we remember.
+        let other_keys: Vec<PrivateKey> = (0..10)
+            .map(|_| PrivateKey::new(&mut rand::rngs::OsRng))
+            .collect();
+        for sequence in 1..=P2P_MESSAGE_SEQUENCE_WINDOW {
+            for other_key in other_keys.iter() {
+                let other = message
+                    .clone()
+                    .sign_ecdsa_with_sequence(other_key, sequence);
+                seen_messages.insert(&other).unwrap();
+            }
+        }
+
+        let rejection = seen_messages.insert(&msg).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::Duplicate));
+    }
+
+    #[test]
+    fn seen_sequence_numbers_are_remembered_for_the_whole_window() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let message = signed_message(&private_key).inner;
+
+        let mut seen_messages = SeenMessages::new();
+        for sequence in 1..=P2P_MESSAGE_SEQUENCE_WINDOW {
+            let msg = message
+                .clone()
+                .sign_ecdsa_with_sequence(&private_key, sequence);
+            seen_messages.insert(&msg).unwrap();
+        }
+
+        // Every sequence number that is still within the window is
+        // rejected as a duplicate.
+        let highest = P2P_MESSAGE_SEQUENCE_WINDOW + 1;
+        let msg = message
+            .clone()
+            .sign_ecdsa_with_sequence(&private_key, highest);
+        seen_messages.insert(&msg).unwrap();
+
+        for sequence in 2..=P2P_MESSAGE_SEQUENCE_WINDOW {
+            let msg = message
+                .clone()
+                .sign_ecdsa_with_sequence(&private_key, sequence);
+            let rejection = seen_messages.insert(&msg).unwrap_err();
+            assert!(matches!(rejection, MessageRejection::Duplicate));
+        }
+
+        let msg = message.sign_ecdsa_with_sequence(&private_key, 1);
+        let rejection = seen_messages.insert(&msg).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::StaleSequence(1)));
+
+        let window = &seen_messages.windows[&PublicKey::from_private_key(&private_key)];
+        assert_eq!(window.seen.len() as u64, P2P_MESSAGE_SEQUENCE_WINDOW);
+    }
+
+    #[test]
+    fn messages_outside_of_the_sequence_window_are_rejected() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let message = signed_message(&private_key).inner;
+        let highest = P2P_MESSAGE_SEQUENCE_WINDOW + 10;
+
+        let mut seen_messages = SeenMessages::new();
+        let msg = message
+            .clone()
+            .sign_ecdsa_with_sequence(&private_key, highest);
+        seen_messages.insert(&msg).unwrap();
+
+        // Messages can arrive out of order, so anything within the window
+        // is fine.
+        let msg = message.clone().sign_ecdsa_with_sequence(&private_key, 11);
+        seen_messages.insert(&msg).unwrap();
+
+        let msg = message.clone().sign_ecdsa_with_sequence(&private_key, 10);
+        let rejection = seen_messages.insert(&msg).unwrap_err();
+        assert!(matches!(rejection, MessageRejection::StaleSequence(10)));
+
+        // The window is tracked per signer.
+        let other_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let msg = message.sign_ecdsa_with_sequence(&other_key, 10);
+        seen_messages.insert(&msg).unwrap();
+    }
+
+    #[tokio::test]
+    async fn sequence_numbers_continue_after_sender_restart() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let public_key = PublicKey::from_private_key(&private_key);
+        let message = signed_message(&private_key).inner;
+        let store = Store::new_shared();
+
+        let mut seen_messages = SeenMessages::new();
+        for _ in 0..3 {
+            let sequence = store.next_p2p_message_sequence(&public_key).await.unwrap();
+            let msg = message
+                .clone()
+                .sign_ecdsa_with_sequence(&private_key, sequence);
+            seen_messages.insert(&msg).unwrap();
+        }
+
+        // The sender restarts, keeping only what it has in its database.
+        // Re-sending the same message gets a fresh sequence number, so the
+        // receiver does not mistake it for a replay.
+        let restarted_store = Store::new_shared();
+        restarted_store.lock().await.p2p_message_sequences =
+            store.lock().await.p2p_message_sequences.clone();
+        drop(store);
+
+        let sequence = restarted_store
+            .next_p2p_message_sequence(&public_key)
+            .await
+            .unwrap();
+        assert_eq!(sequence, 4);
+
+        let msg = message.sign_ecdsa_with_sequence(&private_key, sequence);
+        seen_messages.insert(&msg).unwrap();
+    }
+}
@@ -5,12 +5,16 @@ use std::sync::LazyLock;
 use libp2p::gossipsub::IdentTopic;
 
 mod bootstrap;
+mod decisions;
+mod dispatcher;
 mod errors;
 mod event_loop;
 mod multiaddr;
 mod network;
+mod scoring;
 mod swarm;
 
+pub use self::dispatcher::{MessageDispatcher, MessageReceiver};
 pub use self::errors::SignerSwarmError;
 pub use self::multiaddr::MultiaddrExt;
 pub use self::network::P2PNetwork;
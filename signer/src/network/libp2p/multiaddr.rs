@@ -11,6 +11,9 @@ pub trait MultiaddrExt {
     fn is_quic(&self) -> bool;
     /// Returns `true` if the multiaddress uses the in-memory transport protocol.
     fn is_memory(&self) -> bool;
+    /// Returns `true` if the multiaddress is reached through a relay, i.e.
+    /// if it contains the P2P circuit protocol.
+    fn is_relayed(&self) -> bool;
     /// Returns the transport protocol used by the multiaddress, or `None` if no
     /// supported transport protocol was found.
     fn get_transport_protocol(&self) -> Option<Protocol>;
@@ -60,6 +63,10 @@ impl MultiaddrExt for Multiaddr {
         matches!(parts.next(), Some(Protocol::Memory(_)))
     }
 
+    fn is_relayed(&self) -> bool {
+        self.iter().any(|part| matches!(part, Protocol::P2pCircuit))
+    }
+
     fn get_transport_protocol(&self) -> Option<Protocol> {
         let mut parts = self.iter();
         parts.find(|part| {
@@ -176,6 +183,21 @@ mod tests {
         assert!(!multiaddr.is_memory(), "dns + tcp");
     }
 
+    #[test]
+    fn test_is_relayed() {
+        let relay = Multiaddr::empty()
+            .with(Protocol::Ip4(IP4_LOOPBACK))
+            .with(Protocol::Tcp(8080))
+            .with(Protocol::P2p(PeerId::random()));
+        assert!(!relay.is_relayed(), "ip4 + tcp + p2p");
+
+        let multiaddr = relay.clone().with(Protocol::P2pCircuit);
+        assert!(multiaddr.is_relayed(), "relay + p2p-circuit");
+
+        let multiaddr = multiaddr.with(Protocol::P2p(PeerId::random()));
+        assert!(multiaddr.is_relayed(), "relay + p2p-circuit + p2p");
+    }
+
     #[test]
     fn test_get_transport_protocol() {
         let multiaddr = Multiaddr::empty()
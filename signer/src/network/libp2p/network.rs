@@ -1,23 +1,24 @@
 //! MessageTransfer implementation for the application signalling channel
 //! together with LibP2P.
 
-use tokio::sync::broadcast::Receiver;
 use tokio::sync::broadcast::Sender;
 
 use crate::context::Context;
-use crate::context::P2PEvent;
 use crate::context::SignerCommand;
-use crate::context::SignerEvent;
 use crate::context::SignerSignal;
 use crate::context::TerminationHandle;
 use crate::error::Error;
 use crate::network::MessageTransfer;
 use crate::network::Msg;
 
+use super::MessageDispatcher;
+use super::MessageReceiver;
+
 /// MessageTransfer interface for the application signalling channel.
 pub struct P2PNetwork {
     signal_tx: Sender<SignerSignal>,
-    signal_rx: Receiver<SignerSignal>,
+    dispatcher: MessageDispatcher,
+    message_rx: MessageReceiver,
     term: TerminationHandle,
 }
 
@@ -25,7 +26,8 @@ impl Clone for P2PNetwork {
     fn clone(&self) -> Self {
         Self {
             signal_tx: self.signal_tx.clone(),
-            signal_rx: self.signal_tx.subscribe(),
+            dispatcher: self.dispatcher.clone(),
+            message_rx: self.dispatcher.subscribe(),
             term: self.term.clone(),
         }
     }
@@ -33,11 +35,14 @@ impl Clone for P2PNetwork {
 
 impl P2PNetwork {
     /// Create a new broadcast channel network instance. This requires an active
-    /// [`Context`] and will retrieve its own signalling sender and receiver.
+    /// [`Context`] and will retrieve its own signalling sender and register
+    /// itself as a consumer of the messages received from the P2P network.
     pub fn new(ctx: &impl Context) -> Self {
+        let dispatcher = ctx.get_message_dispatcher();
         Self {
             signal_tx: ctx.get_signal_sender(),
-            signal_rx: ctx.get_signal_receiver(),
+            message_rx: dispatcher.subscribe(),
+            dispatcher,
             term: ctx.get_termination_handle(),
         }
     }
@@ -53,10 +58,11 @@ impl MessageTransfer for P2PNetwork {
     /// as soon as the message has been sent to the signalling channel.
     ///
     /// If you need to wait for a receipt (success/fail), you can use your own
-    /// [`Receiver<SignerSignal>`] to listen for the
-    /// [`SignerEvent::P2PPublishFailure`] and [`SignerEvent::P2PPublishSuccess`]
-    /// events, which will provide you with the [`MsgId`] to match against your
-    /// in-flight requests.
+    /// [`Receiver<SignerSignal>`](tokio::sync::broadcast::Receiver) to listen
+    /// for the [`P2PEvent::PublishFailure`](crate::context::P2PEvent::PublishFailure)
+    /// and [`P2PEvent::PublishSuccess`](crate::context::P2PEvent::PublishSuccess)
+    /// events, which will provide you with the [`MsgId`](crate::network::MsgId)
+    /// to match against your in-flight requests.
     async fn broadcast(&mut self, msg: Msg) -> Result<(), Error> {
         self.signal_tx
             .send(SignerSignal::Command(SignerCommand::P2PPublish(msg)))
@@ -64,40 +70,23 @@ impl MessageTransfer for P2PNetwork {
             .map(|_| ())
     }
 
-    /// This will listen for incoming messages on the application signalling
-    /// channel, and return the message once it has been received.
+    /// This will wait for the next message received from the P2P network,
+    /// and return it once it has been received.
     ///
     /// This is a blocking operation, and will wait until a message has been
     /// received before returning.
     ///
-    /// ### Important Note
-    /// To avoid ending up in a slow-receiver situation, you should queue
-    /// messages in a local buffer (i.e. [`VecDeque`](std::collections::VecDeque) and
-    /// process them in your own time. Otherwise, if there are a large number
-    /// of messages being sent, you risk lagging and eventually having the tail
-    /// of the receiver being dropped, thus missing messages.
-    ///
-    /// In other words, you should be calling this method as rapidly as possible.
+    /// Messages are queued for each [`P2PNetwork`] instance in a bounded
+    /// queue. If this instance falls behind then the delivery of messages
+    /// waits for it to catch up, and a
+    /// [`P2PEvent::LaggedMessages`](crate::context::P2PEvent::LaggedMessages)
+    /// signal is sent, so messages are never silently lost. Instances that
+    /// have never called this method are not waited for, so instances that
+    /// are only used for broadcasting do not hold the network back.
     async fn receive(&mut self) -> Result<Msg, Error> {
-        loop {
-            tokio::select! {
-                _ = self.term.wait_for_shutdown() => {
-                    return Err(Error::SignerShutdown);
-                },
-                recv = self.signal_rx.recv() => {
-                    match recv {
-                        Ok(SignerSignal::Event(SignerEvent::P2P(P2PEvent::MessageReceived(msg)))) => {
-                            return Ok(msg);
-                        },
-                        Err(_) => {
-                            return Err(Error::SignerShutdown);
-                        },
-                        // We're only interested in the above messages, so we ignore
-                        // the rest.
-                        _ => continue,
-                    }
-                }
-            }
+        tokio::select! {
+            _ = self.term.wait_for_shutdown() => Err(Error::SignerShutdown),
+            msg = self.message_rx.recv() => msg.ok_or(Error::SignerShutdown),
         }
     }
 }
@@ -109,6 +98,7 @@ mod tests {
 
     use futures::StreamExt;
     use libp2p::Multiaddr;
+    use libp2p::multiaddr::Protocol;
     use test_log::test;
     use tokio_stream::wrappers::BroadcastStream;
 
@@ -116,7 +106,11 @@ mod tests {
 
     use crate::{
         keys::{PrivateKey, PublicKey},
-        network::libp2p::SignerSwarmBuilder,
+        network::libp2p::{MultiaddrExt as _, SignerSwarmBuilder, swarm::SignerSwarm},
+        storage::{
+            DbRead as _,
+            in_memory::{SharedStore, Store},
+        },
         testing::{self, clear_env, context::*, get_rng, network::RandomMemoryMultiaddr},
     };
 
@@ -715,4 +709,820 @@ mod tests {
         handle2.abort();
         handle3.abort();
     }
+
+    /// Wait until the P2P status of the given context satisfies the
+    /// predicate, panicking if it takes too long.
+    async fn wait_for_p2p_status<F>(ctx: &TestContext, predicate: F)
+    where
+        F: Fn(&crate::context::SwarmStatus) -> bool,
+    {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !predicate(&ctx.state().p2p_status()) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for the swarm status to update");
+    }
+
+    #[test(tokio::test)]
+    async fn swarm_status_tracks_peer_connections() {
+        clear_env();
+
+        let mut rng = get_rng();
+        let key1 = PrivateKey::new(&mut rng);
+        let key2 = PrivateKey::new(&mut rng);
+        let peer_id2: libp2p::PeerId = PublicKey::from_private_key(&key2).into();
+
+        let context1 = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.private_key = key1;
+                settings.signer.p2p.enable_mdns = false;
+            })
+            .build();
+        let context2 = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.private_key = key2;
+                settings.signer.p2p.enable_mdns = false;
+            })
+            .build();
+
+        for key in [key1, key2] {
+            let public_key = PublicKey::from_private_key(&key);
+            context1.state().current_signer_set().add_signer(public_key);
+            context2.state().current_signer_set().add_signer(public_key);
+        }
+
+        let swarm1_addr = Multiaddr::random_memory();
+        let swarm2_addr = Multiaddr::random_memory();
+
+        let mut swarm1 = SignerSwarmBuilder::new(&key1)
+            .enable_memory_transport(true)
+            .add_listen_endpoint(swarm1_addr)
+            .add_seed_addr(swarm2_addr.clone())
+            .build()
+            .expect("Failed to build swarm 1");
+
+        let mut swarm2 = SignerSwarmBuilder::new(&key2)
+            .enable_memory_transport(true)
+            .add_listen_endpoint(swarm2_addr)
+            .build()
+            .expect("Failed to build swarm 2");
+
+        // Nothing is known about the swarm before it starts.
+        assert_eq!(context1.state().p2p_status(), Default::default());
+
+        let term1 = context1.get_termination_handle();
+        let term2 = context2.get_termination_handle();
+
+        let ctx1 = context1.clone();
+        let handle1 = tokio::spawn(async move {
+            swarm1.start(&ctx1).await.unwrap();
+        });
+        let handle2 = tokio::spawn(async move {
+            swarm2.start(&context2).await.unwrap();
+        });
+
+        wait_for_p2p_status(&context1, |status| {
+            status.connected_peers.contains_key(&peer_id2)
+        })
+        .await;
+
+        let status = context1.state().p2p_status();
+        assert_eq!(status.num_connected_peers(), 1);
+        assert!(!status.connected_peers[&peer_id2].is_empty());
+        assert!(!status.pending_dials.contains(&peer_id2));
+
+        // Once the second swarm shuts down, the first one should notice
+        // that the connection is gone.
+        term2.signal_shutdown();
+        tokio::time::timeout(Duration::from_secs(10), handle2)
+            .await
+            .expect("swarm 2 did not shut down")
+            .unwrap();
+
+        wait_for_p2p_status(&context1, |status| status.connected_peers.is_empty()).await;
+        assert_eq!(context1.state().p2p_status().num_mesh_peers(), 0);
+
+        term1.signal_shutdown();
+        handle1.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn misbehaving_peers_are_banned_until_the_cooldown_expires() {
+        clear_env();
+
+        let mut rng = get_rng();
+        let key1 = PrivateKey::new(&mut rng);
+        let key2 = PrivateKey::new(&mut rng);
+        let peer_id2: libp2p::PeerId = PublicKey::from_private_key(&key2).into();
+        let ban_duration = Duration::from_secs(2);
+
+        let context1 = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.private_key = key1;
+                settings.signer.p2p.enable_mdns = false;
+                settings.signer.p2p.peer_ban_duration = ban_duration;
+            })
+            .build();
+        let context2 = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.private_key = key2;
+                settings.signer.p2p.enable_mdns = false;
+            })
+            .build();
+
+        // Signer 2 is a known signer, so it is trusted enough to connect.
+        for key in [key1, key2] {
+            let public_key = PublicKey::from_private_key(&key);
+            context1.state().current_signer_set().add_signer(public_key);
+            context2.state().current_signer_set().add_signer(public_key);
+        }
+
+        let swarm1_addr = Multiaddr::random_memory();
+        let swarm2_addr = Multiaddr::random_memory();
+
+        let mut swarm1 = SignerSwarmBuilder::new(&key1)
+            .enable_memory_transport(true)
+            .add_listen_endpoint(swarm1_addr.clone())
+            .build()
+            .expect("Failed to build swarm 1");
+        let mut swarm2 = SignerSwarmBuilder::new(&key2)
+            .enable_memory_transport(true)
+            .add_listen_endpoint(swarm2_addr)
+            .add_seed_addr(swarm1_addr.clone())
+            .build()
+            .expect("Failed to build swarm 2");
+        let dialer2 = swarm2.clone();
+
+        let mut network2 = P2PNetwork::new(&context2);
+        let term1 = context1.get_termination_handle();
+        let term2 = context2.get_termination_handle();
+
+        let ctx1 = context1.clone();
+        let handle1 = tokio::spawn(async move {
+            swarm1.start(&ctx1).await.unwrap();
+        });
+        let handle2 = tokio::spawn(async move {
+            swarm2.start(&context2).await.unwrap();
+        });
+
+        wait_for_p2p_status(&context1, |status| {
+            status.connected_peers.contains_key(&peer_id2)
+        })
+        .await;
+
+        // Signer 2 sends garbage: messages whose signature does not match
+        // their contents because the signed sequence number was changed.
+        let garbage = tokio::spawn(async move {
+            let mut rng = get_rng();
+            loop {
+                let mut msg = Msg::random_with_private_key(&mut rng, &key2);
+                msg.sequence += 1;
+                let _ = network2.broadcast(msg).await;
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        });
+
+        wait_for_p2p_status(&context1, |status| {
+            status.banned_peers.contains_key(&peer_id2)
+                && !status.connected_peers.contains_key(&peer_id2)
+        })
+        .await;
+        garbage.abort();
+
+        // While the ban is in effect, signer 1 drops any new connection
+        // from signer 2.
+        dialer2.dial(swarm1_addr.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let status = context1.state().p2p_status();
+        assert!(!status.connected_peers.contains_key(&peer_id2));
+
+        // Once the cooldown has elapsed, signer 2 may rejoin on probation.
+        tokio::time::sleep(ban_duration).await;
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !context1
+                .state()
+                .p2p_status()
+                .connected_peers
+                .contains_key(&peer_id2)
+            {
+                let _ = dialer2.dial(swarm1_addr.clone()).await;
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+        })
+        .await
+        .expect("banned peer did not rejoin after the cooldown");
+
+        let status = context1.state().p2p_status();
+        assert!(!status.banned_peers.contains_key(&peer_id2));
+        assert_eq!(
+            status.peer_scores.get(&peer_id2),
+            Some(&crate::network::libp2p::scoring::PROBATION_PEER_SCORE)
+        );
+
+        term1.signal_shutdown();
+        term2.signal_shutdown();
+        handle1.abort();
+        handle2.abort();
+    }
+
+    /// Start a relay server listening on a random memory address, returning
+    /// its peer id and address.
+    fn start_relay_server() -> (libp2p::PeerId, Multiaddr) {
+        use libp2p::Transport as _;
+        use libp2p::core::upgrade::Version;
+        use libp2p::{noise, relay, yamux};
+
+        let keypair = libp2p::identity::Keypair::generate_ed25519();
+        let peer_id = keypair.public().to_peer_id();
+        let transport = libp2p::core::transport::MemoryTransport::default()
+            .upgrade(Version::V1)
+            .authenticate(noise::Config::new(&keypair).unwrap())
+            .multiplex(yamux::Config::default())
+            .boxed();
+        let behavior = relay::Behaviour::new(peer_id, relay::Config::default());
+        let mut swarm = libp2p::Swarm::new(
+            transport,
+            behavior,
+            peer_id,
+            libp2p::swarm::Config::with_tokio_executor(),
+        );
+
+        // The relay hands out its external addresses in reservations, so it
+        // needs at least one of them.
+        let addr = Multiaddr::random_memory();
+        swarm.listen_on(addr.clone()).unwrap();
+        swarm.add_external_address(addr.clone());
+
+        tokio::spawn(async move {
+            loop {
+                swarm.select_next_some().await;
+            }
+        });
+
+        (peer_id, addr)
+    }
+
+    #[test(tokio::test)]
+    async fn unreachable_peers_exchange_messages_through_a_relay() {
+        clear_env();
+
+        let mut rng = get_rng();
+        let key1 = PrivateKey::new(&mut rng);
+        let key2 = PrivateKey::new(&mut rng);
+        let peer_id1: libp2p::PeerId = PublicKey::from_private_key(&key1).into();
+        let peer_id2: libp2p::PeerId = PublicKey::from_private_key(&key2).into();
+
+        let context1 = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.private_key = key1;
+                settings.signer.p2p.enable_mdns = false;
+            })
+            .build();
+        let context2 = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.signer.private_key = key2;
+                settings.signer.p2p.enable_mdns = false;
+            })
+            .build();
+
+        for key in [key1, key2] {
+            let public_key = PublicKey::from_private_key(&key);
+            context1.state().current_signer_set().add_signer(public_key);
+            context2.state().current_signer_set().add_signer(public_key);
+        }
+
+        let (relay_peer_id, relay_addr) = start_relay_server();
+
+        // Neither swarm listens on an address that the other can dial, so
+        // the only way for them to connect is through the relay.
+        let mut swarm1 = SignerSwarmBuilder::new(&key1)
+            .enable_memory_transport(true)
+            .enable_relay(true)
+            .add_relay_server(relay_addr.clone())
+            .build()
+            .expect("Failed to build swarm 1");
+        let mut swarm2 = SignerSwarmBuilder::new(&key2)
+            .enable_memory_transport(true)
+            .enable_relay(true)
+            .add_relay_server(relay_addr.clone())
+            .build()
+            .expect("Failed to build swarm 2");
+        let dialer2 = swarm2.clone();
+
+        let mut network1 = P2PNetwork::new(&context1);
+        let mut network2 = P2PNetwork::new(&context2);
+
+        let ctx1 = context1.clone();
+        let handle1 = tokio::spawn(async move {
+            swarm1.start(&ctx1).await.unwrap();
+        });
+        let ctx2 = context2.clone();
+        let handle2 = tokio::spawn(async move {
+            swarm2.start(&ctx2).await.unwrap();
+        });
+
+        // Signer 2 dials signer 1 through the relay. The dial fails until
+        // signer 1 has reserved a slot on the relay, so we keep trying.
+        let circuit_addr = relay_addr
+            .with(Protocol::P2p(relay_peer_id))
+            .with(Protocol::P2pCircuit)
+            .with(Protocol::P2p(peer_id1));
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !context2
+                .state()
+                .p2p_status()
+                .connected_peers
+                .contains_key(&peer_id1)
+            {
+                let _ = dialer2.dial(circuit_addr.clone()).await;
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+        })
+        .await
+        .expect("signers did not connect through the relay");
+
+        wait_for_p2p_status(&context1, |status| status.relayed_peers.contains(&peer_id2)).await;
+        assert!(
+            context2
+                .state()
+                .p2p_status()
+                .relayed_peers
+                .contains(&peer_id1)
+        );
+
+        // Signer 1 keeps broadcasting messages until signer 2 receives one,
+        // since gossipsub needs a moment to learn about the topic
+        // subscriptions of the new peer.
+        let broadcast = tokio::spawn(async move {
+            let mut rng = get_rng();
+            loop {
+                let msg = Msg::random_with_private_key(&mut rng, &key1);
+                let _ = network1.broadcast(msg).await;
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+        });
+
+        let received = tokio::time::timeout(Duration::from_secs(10), network2.receive())
+            .await
+            .expect("timed out waiting for the relayed message")
+            .expect("failed to receive the relayed message");
+        assert_eq!(
+            received.signer_public_key,
+            PublicKey::from_private_key(&key1)
+        );
+
+        broadcast.abort();
+        context1.get_termination_handle().signal_shutdown();
+        context2.get_termination_handle().signal_shutdown();
+        handle1.abort();
+        handle2.abort();
+    }
+
+    /// Wait until the swarm listens on an address that satisfies the
+    /// predicate, returning that address.
+    async fn wait_for_listen_addr<F>(swarm: &SignerSwarm, predicate: F) -> Multiaddr
+    where
+        F: Fn(&Multiaddr) -> bool,
+    {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let addrs = swarm.listen_addrs().await;
+                if let Some(addr) = addrs.into_iter().find(|addr| predicate(addr)) {
+                    return addr;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for the swarm to listen")
+    }
+
+    /// Note: This test will create actual listening sockets on the system on
+    /// OS-provided ports.
+    #[test(tokio::test)]
+    async fn tcp_and_quic_signers_exchange_messages_through_a_dual_stack_signer() {
+        clear_env();
+
+        let mut rng = get_rng();
+        let keys: Vec<PrivateKey> = (0..3).map(|_| PrivateKey::new(&mut rng)).collect();
+        let (key1, key2, key3) = (keys[0], keys[1], keys[2]);
+        let peer_id1: libp2p::PeerId = PublicKey::from_private_key(&key1).into();
+        let peer_id2: libp2p::PeerId = PublicKey::from_private_key(&key2).into();
+        let peer_id3: libp2p::PeerId = PublicKey::from_private_key(&key3).into();
+
+        let contexts: Vec<_> = keys
+            .iter()
+            .map(|key| {
+                TestContext::builder()
+                    .with_in_memory_storage()
+                    .with_mocked_clients()
+                    .modify_settings(|settings| {
+                        settings.signer.private_key = *key;
+                        settings.signer.p2p.enable_mdns = false;
+                    })
+                    .build()
+            })
+            .collect();
+        for context in contexts.iter() {
+            for key in keys.iter() {
+                let public_key = PublicKey::from_private_key(key);
+                context.state().current_signer_set().add_signer(public_key);
+            }
+        }
+        let (context1, context2, context3) = (
+            contexts[0].clone(),
+            contexts[1].clone(),
+            contexts[2].clone(),
+        );
+
+        // Signer 2 listens on both TCP and QUIC.
+        let mut swarm2 = SignerSwarmBuilder::new(&key2)
+            .enable_quic_transport(true)
+            .add_listen_endpoint("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .add_listen_endpoint("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+            .build()
+            .expect("Failed to build swarm 2");
+        let listener2 = swarm2.clone();
+        let ctx2 = context2.clone();
+        let handle2 = tokio::spawn(async move {
+            swarm2.start(&ctx2).await.unwrap();
+        });
+        let swarm2_tcp_addr = wait_for_listen_addr(&listener2, |addr| addr.is_tcp()).await;
+        let swarm2_quic_addr = wait_for_listen_addr(&listener2, |addr| addr.is_quic()).await;
+
+        // Signer 1 only uses TCP, while signer 3 only uses QUIC.
+        let mut swarm1 = SignerSwarmBuilder::new(&key1)
+            .add_listen_endpoint("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .add_seed_addr(swarm2_tcp_addr)
+            .build()
+            .expect("Failed to build swarm 1");
+        let mut swarm3 = SignerSwarmBuilder::new(&key3)
+            .enable_quic_transport(true)
+            .add_listen_endpoint("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+            .add_seed_addr(swarm2_quic_addr)
+            .build()
+            .expect("Failed to build swarm 3");
+
+        let mut network1 = P2PNetwork::new(&context1);
+        let mut network3 = P2PNetwork::new(&context3);
+
+        let ctx1 = context1.clone();
+        let handle1 = tokio::spawn(async move {
+            swarm1.start(&ctx1).await.unwrap();
+        });
+        let ctx3 = context3.clone();
+        let handle3 = tokio::spawn(async move {
+            swarm3.start(&ctx3).await.unwrap();
+        });
+
+        wait_for_p2p_status(&context2, |status| {
+            status.connected_peers.contains_key(&peer_id1)
+                && status.connected_peers.contains_key(&peer_id3)
+        })
+        .await;
+
+        // Each signer reached signer 2 over its own transport.
+        wait_for_p2p_status(&context1, |status| {
+            status
+                .connected_peers
+                .get(&peer_id2)
+                .is_some_and(|addrs| addrs.iter().all(|addr| addr.is_tcp()))
+        })
+        .await;
+        wait_for_p2p_status(&context3, |status| {
+            status
+                .connected_peers
+                .get(&peer_id2)
+                .is_some_and(|addrs| addrs.iter().all(|addr| addr.is_quic()))
+        })
+        .await;
+
+        // Signer 1 keeps broadcasting messages until signer 3 receives one,
+        // since gossipsub needs a moment to build its mesh.
+        let broadcast = tokio::spawn(async move {
+            let mut rng = get_rng();
+            loop {
+                let msg = Msg::random_with_private_key(&mut rng, &key1);
+                let _ = network1.broadcast(msg).await;
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+        });
+
+        let received = tokio::time::timeout(Duration::from_secs(10), network3.receive())
+            .await
+            .expect("timed out waiting for the message from the TCP signer")
+            .expect("failed to receive the message from the TCP signer");
+        assert_eq!(
+            received.signer_public_key,
+            PublicKey::from_private_key(&key1)
+        );
+
+        broadcast.abort();
+        for context in contexts {
+            context.get_termination_handle().signal_shutdown();
+        }
+        handle1.abort();
+        handle2.abort();
+        handle3.abort();
+    }
+
+    /// Note: This test will create actual listening sockets on the system on
+    /// OS-provided ports.
+    #[test(tokio::test)]
+    async fn quic_peer_id_is_stable_across_restarts() {
+        clear_env();
+
+        let mut rng = get_rng();
+        let key1 = PrivateKey::new(&mut rng);
+        let key2 = PrivateKey::new(&mut rng);
+        let peer_id2: libp2p::PeerId = PublicKey::from_private_key(&key2).into();
+
+        let new_context = |key: PrivateKey| {
+            let context = TestContext::builder()
+                .with_in_memory_storage()
+                .with_mocked_clients()
+                .modify_settings(|settings| {
+                    settings.signer.private_key = key;
+                    settings.signer.p2p.enable_mdns = false;
+                })
+                .build();
+            for key in [key1, key2] {
+                let public_key = PublicKey::from_private_key(&key);
+                context.state().current_signer_set().add_signer(public_key);
+            }
+            context
+        };
+        let start_quic_swarm = |key: PrivateKey, context: TestContext<_, _, _, _>| {
+            let mut swarm = SignerSwarmBuilder::new(&key)
+                .enable_quic_transport(true)
+                .add_listen_endpoint("/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap())
+                .build()
+                .expect("Failed to build swarm");
+            let handle = swarm.clone();
+            let task = tokio::spawn(async move {
+                swarm.start(&context).await.unwrap();
+            });
+            (handle, task)
+        };
+
+        let context1 = new_context(key1);
+        let (swarm1, task1) = start_quic_swarm(key1, context1.clone());
+
+        // Signer 1 connects to signer 2 over QUIC, then signer 2 restarts
+        // and signer 1 connects to it again.
+        for _ in 0..2 {
+            let context2 = new_context(key2);
+            let (swarm2, task2) = start_quic_swarm(key2, context2.clone());
+            let swarm2_addr = wait_for_listen_addr(&swarm2, |addr| addr.is_quic()).await;
+
+            swarm1.dial(swarm2_addr.clone()).await.unwrap();
+
+            // The peer ID that signer 1 sees on the new QUIC connection is
+            // the one derived from the private key of signer 2.
+            wait_for_p2p_status(&context1, |status| {
+                status.connected_peers.get(&peer_id2).is_some_and(|addrs| {
+                    addrs
+                        .iter()
+                        .any(|addr| addr.without_p2p_protocol() == swarm2_addr)
+                })
+            })
+            .await;
+
+            context2.get_termination_handle().signal_shutdown();
+            task2.abort();
+        }
+
+        context1.get_termination_handle().signal_shutdown();
+        task1.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn restarted_signer_reconnects_using_only_cached_peer_addresses() {
+        clear_env();
+
+        let mut rng = get_rng();
+        let key1 = PrivateKey::new(&mut rng);
+        let key2 = PrivateKey::new(&mut rng);
+        let peer_id2: libp2p::PeerId = PublicKey::from_private_key(&key2).into();
+
+        // Signer 1 keeps its database across restarts.
+        let storage1 = Store::new_shared();
+        let new_context = |key: PrivateKey, storage: SharedStore| {
+            let context = TestContext::builder()
+                .with_storage(storage)
+                .with_mocked_clients()
+                .modify_settings(|settings| {
+                    settings.signer.private_key = key;
+                    settings.signer.p2p.enable_mdns = false;
+                })
+                .build();
+            for key in [key1, key2] {
+                let public_key = PublicKey::from_private_key(&key);
+                context.state().current_signer_set().add_signer(public_key);
+            }
+            context
+        };
+        let start_swarm = |builder: SignerSwarmBuilder, context: TestContext<_, _, _, _>| {
+            let mut swarm = builder
+                .enable_memory_transport(true)
+                .add_listen_endpoint(Multiaddr::random_memory())
+                .build()
+                .expect("Failed to build swarm");
+            let handle = swarm.clone();
+            let task = tokio::spawn(async move {
+                swarm.start(&context).await.unwrap();
+            });
+            (handle, task)
+        };
+
+        let context2 = new_context(key2, Store::new_shared());
+        let (swarm2, task2) = start_swarm(SignerSwarmBuilder::new(&key2), context2.clone());
+        let swarm2_addr = wait_for_listen_addr(&swarm2, |addr| addr.is_memory()).await;
+
+        // Signer 1 first finds signer 2 through its seeds, and remembers
+        // the address at which it reached it.
+        let context1 = new_context(key1, storage1.clone());
+        let builder = SignerSwarmBuilder::new(&key1).add_seed_addr(swarm2_addr.clone());
+        let (_, task1) = start_swarm(builder, context1.clone());
+        wait_for_p2p_status(&context1, |status| {
+            status.connected_peers.contains_key(&peer_id2)
+        })
+        .await;
+
+        let max_age = context1.config().signer.p2p.peer_address_max_age;
+        let known_peers = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let peers = storage1.get_recent_p2p_peers(max_age).await.unwrap();
+                if !peers.is_empty() {
+                    return peers;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for the peer address to be recorded");
+        assert_eq!(known_peers, vec![(peer_id2, swarm2_addr)]);
+
+        context1.get_termination_handle().signal_shutdown();
+        task1.abort();
+
+        // After a restart without any seeds, signer 1 reconnects to signer
+        // 2 using the cached address alone.
+        let context1 = new_context(key1, storage1.clone());
+        let known_peers = storage1.get_recent_p2p_peers(max_age).await.unwrap();
+        let builder = SignerSwarmBuilder::new(&key1).add_known_peers(&known_peers);
+        let (_, task1) = start_swarm(builder, context1.clone());
+        wait_for_p2p_status(&context1, |status| {
+            status.connected_peers.contains_key(&peer_id2)
+        })
+        .await;
+
+        context1.get_termination_handle().signal_shutdown();
+        context2.get_termination_handle().signal_shutdown();
+        task1.abort();
+        task2.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn late_joining_signer_syncs_missed_decisions_from_peers() {
+        use fake::{Fake as _, Faker};
+
+        use crate::bitcoin::validation::TxRequestIds;
+        use crate::context::{P2PEvent, SignerEvent};
+        use crate::message::Payload;
+        use crate::storage::{DbWrite as _, model};
+
+        clear_env();
+
+        let mut rng = get_rng();
+        let key1 = PrivateKey::new(&mut rng);
+        let key2 = PrivateKey::new(&mut rng);
+        let public_key1 = PublicKey::from_private_key(&key1);
+
+        let new_context = |key: PrivateKey| {
+            let context = TestContext::builder()
+                .with_in_memory_storage()
+                .with_mocked_clients()
+                .modify_settings(|settings| {
+                    settings.signer.private_key = key;
+                    settings.signer.p2p.enable_mdns = false;
+                })
+                .build();
+            for key in [key1, key2] {
+                let public_key = PublicKey::from_private_key(&key);
+                context.state().current_signer_set().add_signer(public_key);
+            }
+            context
+        };
+        let start_swarm = |key: PrivateKey, context: TestContext<_, _, _, _>| {
+            let mut swarm = SignerSwarmBuilder::new(&key)
+                .enable_memory_transport(true)
+                .add_listen_endpoint(Multiaddr::random_memory())
+                .build()
+                .expect("Failed to build swarm");
+            let handle = swarm.clone();
+            let task = tokio::spawn(async move {
+                swarm.start(&context).await.unwrap();
+            });
+            (handle, task)
+        };
+
+        // Signer 1 has voted on a deposit and a withdrawal request while
+        // signer 2 was offline.
+        let context1 = new_context(key1);
+        let storage1 = context1.get_storage_mut();
+        let chain_tip: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+        storage1.write_bitcoin_block(&chain_tip).await.unwrap();
+
+        let deposit: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+        let deposit_decision = model::DepositSigner {
+            txid: deposit.txid,
+            output_index: deposit.output_index,
+            signer_pub_key: public_key1,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        storage1.write_deposit_request(&deposit).await.unwrap();
+        storage1
+            .write_deposit_signer_decision(&deposit_decision)
+            .await
+            .unwrap();
+
+        let withdrawal: model::WithdrawalRequest = Faker.fake_with_rng(&mut rng);
+        let withdrawal_decision = model::WithdrawalSigner {
+            request_id: withdrawal.request_id,
+            block_hash: withdrawal.block_hash,
+            txid: withdrawal.txid,
+            signer_pub_key: public_key1,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        storage1
+            .write_withdrawal_request(&withdrawal)
+            .await
+            .unwrap();
+        storage1
+            .write_withdrawal_signer_decision(&withdrawal_decision)
+            .await
+            .unwrap();
+
+        let (swarm1, task1) = start_swarm(key1, context1.clone());
+        let swarm1_addr = wait_for_listen_addr(&swarm1, |addr| addr.is_memory()).await;
+
+        // Signer 2 comes online, asks for the decisions that it missed
+        // before it is connected to anyone, and then connects to signer 1.
+        let context2 = new_context(key2);
+        let mut signals2 = context2.get_signal_receiver();
+        let (swarm2, task2) = start_swarm(key2, context2.clone());
+        wait_for_listen_addr(&swarm2, |addr| addr.is_memory()).await;
+
+        let request_ids = TxRequestIds {
+            deposits: vec![deposit.outpoint()],
+            withdrawals: vec![withdrawal.qualified_id()],
+        };
+        context2
+            .get_signal_sender()
+            .send(SignerCommand::P2PRequestDecisions(request_ids).into())
+            .unwrap();
+        swarm2.dial(swarm1_addr).await.unwrap();
+
+        // Signer 2 receives the decisions of signer 1, verified and signed
+        // by signer 1, just as if they had been gossiped.
+        let mut synced = Vec::new();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while synced.len() < 2 {
+                let Ok(SignerSignal::Event(SignerEvent::P2P(P2PEvent::MessageReceived(msg)))) =
+                    signals2.recv().await
+                else {
+                    continue;
+                };
+                assert_eq!(msg.signer_public_key, public_key1);
+                synced.push(msg.inner.payload);
+            }
+        })
+        .await
+        .expect("timed out waiting for the missed decisions");
+
+        assert!(synced.contains(&Payload::SignerDepositDecision(deposit_decision.into())));
+        assert!(synced.contains(&Payload::SignerWithdrawalDecision(
+            withdrawal_decision.into()
+        )));
+
+        context1.get_termination_handle().signal_shutdown();
+        context2.get_termination_handle().signal_shutdown();
+        task1.abort();
+        task2.abort();
+    }
 }
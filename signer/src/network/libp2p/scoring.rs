@@ -0,0 +1,375 @@
+//! Scoring of the behavior of peers on the P2P network.
+//!
+//! Each peer starts with [`INITIAL_PEER_SCORE`] points, and loses points
+//! whenever it sends us something that it shouldn't. Lost points are
+//! slowly regained over time, at a rate of [`SCORE_RECOVERY_POINTS`] every
+//! [`SCORE_RECOVERY_INTERVAL`], so that occasional offenses do not add up
+//! to a ban over the lifetime of a long-running peer. Once a peer's score
+//! drops to [`BAN_THRESHOLD`] or below, it is banned for a configurable
+//! amount of time. When the ban expires the peer may reconnect, but it
+//! starts over with a lower, probationary, score.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use libp2p::PeerId;
+
+/// The score of a peer that we have not penalized yet.
+pub const INITIAL_PEER_SCORE: i64 = 100;
+
+/// The score of a peer whose ban has just expired.
+pub const PROBATION_PEER_SCORE: i64 = 50;
+
+/// Peers whose score drops to this value or below are banned.
+pub const BAN_THRESHOLD: i64 = 0;
+
+/// The number of points that a peer regains every
+/// [`SCORE_RECOVERY_INTERVAL`], until it is back at [`INITIAL_PEER_SCORE`].
+pub const SCORE_RECOVERY_POINTS: i64 = 5;
+
+/// How often a peer regains [`SCORE_RECOVERY_POINTS`].
+pub const SCORE_RECOVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The maximum number of messages that a peer may send us within
+/// [`MESSAGE_RATE_WINDOW`] before it is penalized.
+pub const MAX_MESSAGES_PER_WINDOW: u32 = 200;
+
+/// The window over which the message rate of a peer is measured.
+pub const MESSAGE_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// The ways that a peer can misbehave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum PeerOffense {
+    /// The peer sent a message that could not be decoded.
+    MalformedMessage,
+    /// The peer sent a message with a signature that does not verify.
+    InvalidSignature,
+    /// The peer sent a message that we have already received.
+    DuplicateMessage,
+    /// The peer sent more than [`MAX_MESSAGES_PER_WINDOW`] messages within
+    /// [`MESSAGE_RATE_WINDOW`].
+    ExcessiveMessageRate,
+}
+
+impl PeerOffense {
+    /// The number of points that a peer loses for this offense.
+    pub fn penalty(&self) -> i64 {
+        match self {
+            PeerOffense::MalformedMessage => 25,
+            PeerOffense::InvalidSignature => 25,
+            PeerOffense::DuplicateMessage => 2,
+            PeerOffense::ExcessiveMessageRate => 20,
+        }
+    }
+}
+
+/// The score and recent message rate of a peer.
+#[derive(Debug)]
+struct PeerRecord {
+    score: i64,
+    window_start: Instant,
+    messages_in_window: u32,
+    /// When the peer last regained points, or when its score was last
+    /// found to be full.
+    last_recovery: Instant,
+}
+
+impl PeerRecord {
+    fn new(score: i64, now: Instant) -> Self {
+        Self {
+            score,
+            window_start: now,
+            messages_in_window: 0,
+            last_recovery: now,
+        }
+    }
+
+    /// Give the peer back the points that it has regained since the last
+    /// recovery. Returns whether the score has changed.
+    fn recover(&mut self, now: Instant) -> bool {
+        if self.score >= INITIAL_PEER_SCORE {
+            self.last_recovery = now;
+            return false;
+        }
+
+        let elapsed = now.saturating_duration_since(self.last_recovery);
+        let intervals = (elapsed.as_secs() / SCORE_RECOVERY_INTERVAL.as_secs()) as u32;
+        if intervals == 0 {
+            return false;
+        }
+
+        let points = SCORE_RECOVERY_POINTS.saturating_mul(intervals as i64);
+        self.score = self.score.saturating_add(points).min(INITIAL_PEER_SCORE);
+        self.last_recovery += SCORE_RECOVERY_INTERVAL * intervals;
+        true
+    }
+}
+
+/// Tracks the scores of peers and which of them are banned.
+#[derive(Debug)]
+pub struct PeerScores {
+    /// The records of the peers that are not banned.
+    peers: HashMap<PeerId, PeerRecord>,
+    /// The peers that are banned, along with when the ban expires.
+    banned: HashMap<PeerId, Instant>,
+    /// How long a peer is banned for once its score drops below the
+    /// threshold.
+    ban_duration: Duration,
+    /// Whether any score or ban has changed since the last call to
+    /// [`PeerScores::take_changed`].
+    changed: bool,
+}
+
+impl PeerScores {
+    /// Create a new instance that bans peers for the given duration.
+    pub fn new(ban_duration: Duration) -> Self {
+        Self {
+            peers: HashMap::new(),
+            banned: HashMap::new(),
+            ban_duration,
+            changed: false,
+        }
+    }
+
+    /// Record that the peer sent us a message, penalizing it if it has sent
+    /// too many messages recently. Returns whether the peer is now banned.
+    pub fn record_message(&mut self, peer_id: PeerId, now: Instant) -> bool {
+        if self.is_banned(&peer_id, now) {
+            return true;
+        }
+
+        let record = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerRecord::new(INITIAL_PEER_SCORE, now));
+
+        if now.duration_since(record.window_start) >= MESSAGE_RATE_WINDOW {
+            record.window_start = now;
+            record.messages_in_window = 0;
+        }
+        record.messages_in_window = record.messages_in_window.saturating_add(1);
+
+        // We only penalize the peer once per window.
+        if record.messages_in_window == MAX_MESSAGES_PER_WINDOW + 1 {
+            return self.penalize(peer_id, PeerOffense::ExcessiveMessageRate, now);
+        }
+        false
+    }
+
+    /// Decrease the score of the peer for the given offense, banning it if
+    /// its score drops to the threshold. Returns whether the peer is now
+    /// banned.
+    pub fn penalize(&mut self, peer_id: PeerId, offense: PeerOffense, now: Instant) -> bool {
+        if self.is_banned(&peer_id, now) {
+            return true;
+        }
+
+        let record = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerRecord::new(INITIAL_PEER_SCORE, now));
+        record.recover(now);
+        record.score -= offense.penalty();
+        self.changed = true;
+
+        if record.score > BAN_THRESHOLD {
+            return false;
+        }
+
+        tracing::warn!(
+            %peer_id,
+            offense = <&'static str>::from(offense),
+            ban_duration = ?self.ban_duration,
+            "banning misbehaving peer"
+        );
+        self.peers.remove(&peer_id);
+        self.banned.insert(peer_id, now + self.ban_duration);
+        true
+    }
+
+    /// Whether the peer is currently banned. If the ban on the peer has
+    /// expired then it is lifted, and the peer is given a probationary
+    /// score.
+    pub fn is_banned(&mut self, peer_id: &PeerId, now: Instant) -> bool {
+        let Some(banned_until) = self.banned.get(peer_id) else {
+            return false;
+        };
+        if now < *banned_until {
+            return true;
+        }
+
+        tracing::info!(%peer_id, "ban on peer has expired");
+        self.banned.remove(peer_id);
+        self.peers
+            .insert(*peer_id, PeerRecord::new(PROBATION_PEER_SCORE, now));
+        self.changed = true;
+        false
+    }
+
+    /// Lift the bans that have expired. Returns the peers whose ban was
+    /// lifted.
+    pub fn lift_expired_bans(&mut self, now: Instant) -> Vec<PeerId> {
+        let expired: Vec<PeerId> = self
+            .banned
+            .iter()
+            .filter(|(_, banned_until)| now >= **banned_until)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+
+        for peer_id in expired.iter() {
+            self.is_banned(peer_id, now);
+        }
+        expired
+    }
+
+    /// Give peers back the points that they have regained over time.
+    pub fn recover_scores(&mut self, now: Instant) {
+        for record in self.peers.values_mut() {
+            if record.recover(now) {
+                self.changed = true;
+            }
+        }
+    }
+
+    /// The scores of the peers that are not banned.
+    pub fn scores(&self) -> BTreeMap<PeerId, i64> {
+        self.peers
+            .iter()
+            .map(|(peer_id, record)| (*peer_id, record.score))
+            .collect()
+    }
+
+    /// The peers that are banned, along with when their ban expires.
+    pub fn banned_peers(&self) -> BTreeMap<PeerId, Instant> {
+        self.banned.iter().map(|(k, v)| (*k, *v)).collect()
+    }
+
+    /// Return whether any score or ban has changed since the last call to
+    /// this function.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BAN_DURATION: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn offenses_reduce_the_score_until_the_peer_is_banned() {
+        let mut scores = PeerScores::new(BAN_DURATION);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        let offense = PeerOffense::InvalidSignature;
+        let offenses_until_ban = INITIAL_PEER_SCORE / offense.penalty();
+        for i in 1..offenses_until_ban {
+            assert!(!scores.penalize(peer_id, offense, now));
+            let expected = INITIAL_PEER_SCORE - i * offense.penalty();
+            assert_eq!(scores.scores()[&peer_id], expected);
+        }
+        assert!(scores.take_changed());
+
+        assert!(scores.penalize(peer_id, offense, now));
+        assert!(scores.is_banned(&peer_id, now));
+        assert!(scores.scores().get(&peer_id).is_none());
+        assert_eq!(scores.banned_peers()[&peer_id], now + BAN_DURATION);
+
+        // Other peers are unaffected.
+        assert!(!scores.is_banned(&PeerId::random(), now));
+    }
+
+    #[test]
+    fn banned_peers_return_on_probation_after_the_cooldown() {
+        let mut scores = PeerScores::new(BAN_DURATION);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        while !scores.penalize(peer_id, PeerOffense::MalformedMessage, now) {}
+
+        let almost = now + BAN_DURATION - Duration::from_millis(1);
+        assert!(scores.is_banned(&peer_id, almost));
+        assert!(scores.lift_expired_bans(almost).is_empty());
+
+        let later = now + BAN_DURATION;
+        assert_eq!(scores.lift_expired_bans(later), vec![peer_id]);
+        assert!(!scores.is_banned(&peer_id, later));
+        assert_eq!(scores.scores()[&peer_id], PROBATION_PEER_SCORE);
+        assert!(scores.banned_peers().is_empty());
+
+        // Peers on probation get banned sooner.
+        let offense = PeerOffense::MalformedMessage;
+        let offenses_until_ban = (PROBATION_PEER_SCORE / offense.penalty()) as usize;
+        let results: Vec<bool> =
+            std::iter::repeat_with(|| scores.penalize(peer_id, offense, later))
+                .take(offenses_until_ban)
+                .collect();
+        assert_eq!(results.last(), Some(&true));
+    }
+
+    #[test]
+    fn flooding_peers_are_penalized_once_per_window() {
+        let mut scores = PeerScores::new(BAN_DURATION);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 0..MAX_MESSAGES_PER_WINDOW {
+            assert!(!scores.record_message(peer_id, now));
+        }
+        assert_eq!(scores.scores()[&peer_id], INITIAL_PEER_SCORE);
+
+        for _ in 0..MAX_MESSAGES_PER_WINDOW {
+            scores.record_message(peer_id, now);
+        }
+        let penalty = PeerOffense::ExcessiveMessageRate.penalty();
+        assert_eq!(scores.scores()[&peer_id], INITIAL_PEER_SCORE - penalty);
+
+        // The count starts over in the next window.
+        let next_window = now + MESSAGE_RATE_WINDOW;
+        for _ in 0..MAX_MESSAGES_PER_WINDOW {
+            assert!(!scores.record_message(peer_id, next_window));
+        }
+        assert_eq!(scores.scores()[&peer_id], INITIAL_PEER_SCORE - penalty);
+    }
+
+    #[test]
+    fn scores_recover_over_time() {
+        let mut scores = PeerScores::new(BAN_DURATION);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        let offense = PeerOffense::MalformedMessage;
+        scores.penalize(peer_id, offense, now);
+        scores.penalize(peer_id, offense, now);
+        let penalized = INITIAL_PEER_SCORE - 2 * offense.penalty();
+        assert!(scores.take_changed());
+
+        // Nothing is regained before a full interval has passed.
+        scores.recover_scores(now + SCORE_RECOVERY_INTERVAL - Duration::from_millis(1));
+        assert_eq!(scores.scores()[&peer_id], penalized);
+        assert!(!scores.take_changed());
+
+        let later = now + SCORE_RECOVERY_INTERVAL * 2;
+        scores.recover_scores(later);
+        assert_eq!(
+            scores.scores()[&peer_id],
+            penalized + 2 * SCORE_RECOVERY_POINTS
+        );
+        assert!(scores.take_changed());
+
+        // Points regained are taken into account before a new penalty.
+        let even_later = later + SCORE_RECOVERY_INTERVAL;
+        scores.penalize(peer_id, offense, even_later);
+        let expected = penalized + 3 * SCORE_RECOVERY_POINTS - offense.penalty();
+        assert_eq!(scores.scores()[&peer_id], expected);
+
+        // Scores never recover past the initial score.
+        scores.recover_scores(even_later + SCORE_RECOVERY_INTERVAL * 1_000);
+        assert_eq!(scores.scores()[&peer_id], INITIAL_PEER_SCORE);
+    }
+}
@@ -13,15 +13,15 @@ use libp2p::swarm::NetworkBehaviour;
 use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::dial_opts::DialOpts;
 use libp2p::{
-    Multiaddr, PeerId, Swarm, Transport, autonat, connection_limits, gossipsub, identify, kad,
-    mdns, noise, ping, quic, tcp, yamux,
+    Multiaddr, PeerId, Swarm, Transport, autonat, connection_limits, dcutr, gossipsub, identify,
+    kad, mdns, noise, ping, quic, relay, request_response, tcp, yamux,
 };
 use rand::SeedableRng as _;
 use rand::rngs::StdRng;
 use tokio::sync::Mutex;
 
 use super::errors::SignerSwarmError;
-use super::{bootstrap, event_loop};
+use super::{bootstrap, decisions, event_loop};
 
 /// The maximum number of substreams _per connection_. This is used to limit
 /// the number of concurrent substreams that can be opened on a single
@@ -31,6 +31,9 @@ use super::{bootstrap, event_loop};
 /// * AutoNAT: 2 streams (one for client, one for server operations)
 /// * Identify: 1 stream for peer identification
 /// * Ping: 1 stream for keepalive pings
+/// * Relay: 1 stream per reservation or circuit
+/// * DCUtR: 1 stream while hole punching a relayed connection
+/// * Decisions sync: 1 stream per request for missed signer decisions
 const MAX_SUBSTREAMS_PER_CONNECTION: usize = 20;
 
 /// The maximum time to wait for a connection negotiation to complete. This is
@@ -54,6 +57,9 @@ pub struct SignerBehavior {
     pub autonat_server: Toggle<autonat::v2::server::Behaviour<StdRng>>,
     pub bootstrap: bootstrap::Behavior,
     pub connection_limits: connection_limits::Behaviour,
+    pub relay_client: Toggle<relay::client::Behaviour>,
+    dcutr: Toggle<dcutr::Behaviour>,
+    pub decisions: request_response::Behaviour<decisions::DecisionsCodec>,
 }
 
 pub struct SignerSwarmConfig {
@@ -63,6 +69,10 @@ pub struct SignerSwarmConfig {
     pub initial_bootstrap_delay: Duration,
     pub seed_addresses: Vec<Multiaddr>,
     pub num_signers: u16,
+    /// The relay client behavior, if relaying is enabled. It is created
+    /// together with the relay transport, so it is passed in rather than
+    /// created by [`SignerBehavior::new`].
+    pub relay_client: Option<relay::client::Behaviour>,
 }
 
 impl SignerBehavior {
@@ -96,6 +106,13 @@ impl SignerBehavior {
             (None.into(), None.into())
         };
 
+        // Hole punching is only useful for upgrading relayed connections, so
+        // we only enable it alongside the relay client.
+        let dcutr = config
+            .relay_client
+            .as_ref()
+            .map(|_| dcutr::Behaviour::new(local_peer_id));
+
         let identify = identify::Behaviour::new(identify::Config::new(
             identify::PUSH_PROTOCOL_NAME.to_string(),
             keypair.public(),
@@ -106,6 +123,11 @@ impl SignerBehavior {
             .add_seed_addresses(&config.seed_addresses);
         let bootstrap = bootstrap::Behavior::new(bootstrap_config);
 
+        let decisions = request_response::Behaviour::new(
+            [(decisions::PROTOCOL, request_response::ProtocolSupport::Full)],
+            request_response::Config::default().with_request_timeout(decisions::REQUEST_TIMEOUT),
+        );
+
         Ok(Self {
             gossipsub: Self::gossipsub(&keypair)?,
             mdns,
@@ -116,6 +138,9 @@ impl SignerBehavior {
             autonat_server,
             bootstrap,
             connection_limits: Self::connection_limits(&config),
+            relay_client: config.relay_client.into(),
+            dcutr: dcutr.into(),
+            decisions,
         })
     }
 
@@ -208,6 +233,8 @@ pub struct SignerSwarmBuilder<'a> {
     enable_autonat: bool,
     enable_quic_transport: bool,
     enable_memory_transport: bool,
+    enable_relay: bool,
+    relay_servers: Vec<Multiaddr>,
     initial_bootstrap_delay: Duration,
     num_signers: u16,
 }
@@ -225,6 +252,8 @@ impl<'a> SignerSwarmBuilder<'a> {
             enable_autonat: true,
             enable_quic_transport: false,
             enable_memory_transport: false,
+            enable_relay: false,
+            relay_servers: Vec::new(),
             initial_bootstrap_delay: Duration::ZERO,
             num_signers: crate::MAX_KEYS,
         }
@@ -269,6 +298,34 @@ impl<'a> SignerSwarmBuilder<'a> {
         self
     }
 
+    /// Sets whether or not this swarm should be able to connect to peers
+    /// through relay servers, upgrading such connections to direct ones
+    /// using hole punching when possible.
+    pub fn enable_relay(mut self, enable: bool) -> Self {
+        self.enable_relay = enable;
+        self
+    }
+
+    /// Add a relay server to the builder. The swarm reserves a slot on the
+    /// relay so that peers can reach it through the relay. Relay servers
+    /// are only used if relaying is enabled.
+    pub fn add_relay_server(mut self, addr: Multiaddr) -> Self {
+        if !self.relay_servers.contains(&addr) {
+            self.relay_servers.push(addr);
+        }
+        self
+    }
+
+    /// Add multiple relay servers to the builder.
+    pub fn add_relay_servers(mut self, addrs: &[Multiaddr]) -> Self {
+        for addr in addrs {
+            if !self.relay_servers.contains(addr) {
+                self.relay_servers.push(addr.clone());
+            }
+        }
+        self
+    }
+
     /// Add a listen endpoint to the builder.
     pub fn add_listen_endpoint(mut self, addr: Multiaddr) -> Self {
         if !self.listen_on.contains(&addr) {
@@ -305,6 +362,23 @@ impl<'a> SignerSwarmBuilder<'a> {
         self
     }
 
+    /// Add the addresses of peers that we have previously connected to,
+    /// such as those returned by
+    /// [`DbRead::get_recent_p2p_peers`](crate::storage::DbRead::get_recent_p2p_peers),
+    /// to the seed addresses of the builder.
+    pub fn add_known_peers(mut self, peers: &[(PeerId, Multiaddr)]) -> Self {
+        for (peer_id, addr) in peers {
+            // This only fails if the address is for a different peer.
+            let Ok(addr) = addr.clone().with_p2p(*peer_id) else {
+                continue;
+            };
+            if !self.seed_addrs.contains(&addr) {
+                self.seed_addrs.push(addr);
+            }
+        }
+        self
+    }
+
     /// Add an external address to the builder.
     pub fn add_external_address(mut self, addr: Multiaddr) -> Self {
         if !self.external_addresses.contains(&addr) {
@@ -332,6 +406,16 @@ impl<'a> SignerSwarmBuilder<'a> {
     /// Build the [`SignerSwarm`], consuming the builder.
     pub fn build(self) -> Result<SignerSwarm, SignerSwarmError> {
         let keypair: Keypair = (*self.private_key).into();
+
+        // The relay client consists of a transport, which dials and listens
+        // on relayed addresses, and a behavior which drives it.
+        let (relay_transport, relay_client) = if self.enable_relay {
+            let (transport, behavior) = relay::client::new(keypair.public().to_peer_id());
+            (Some(transport), Some(behavior))
+        } else {
+            (None, None)
+        };
+
         let behavior_config = SignerSwarmConfig {
             enable_mdns: self.enable_mdns,
             enable_kademlia: self.enable_kademlia,
@@ -339,6 +423,7 @@ impl<'a> SignerSwarmBuilder<'a> {
             initial_bootstrap_delay: self.initial_bootstrap_delay,
             seed_addresses: self.seed_addrs,
             num_signers: self.num_signers,
+            relay_client,
         };
         let behavior = SignerBehavior::new(keypair.clone(), behavior_config)?;
 
@@ -368,7 +453,12 @@ impl<'a> SignerSwarmBuilder<'a> {
             .outbound_timeout(Duration::from_secs(NEGOTIATION_TIMEOUT_SECS))
             .boxed();
 
-        // If QUIC transport is enabled, add it to the transport.
+        // If QUIC transport is enabled, add it to the transport. QUIC uses
+        // TLS, where the certificate is generated on startup but embeds a
+        // signature from the same keypair that we use everywhere else. So
+        // the peer ID of the signer is the same over QUIC as over TCP, and
+        // it stays the same across restarts, without having to persist the
+        // certificate.
         if self.enable_quic_transport {
             let config = quic::Config::new(&keypair);
             let quic_transport = quic::tokio::Transport::new(config)
@@ -393,6 +483,21 @@ impl<'a> SignerSwarmBuilder<'a> {
                 .boxed();
         }
 
+        // If relaying is enabled, add the relay transport to the transport.
+        // Relayed connections are secured and multiplexed end-to-end, just
+        // like direct connections.
+        if let Some(relay_transport) = relay_transport {
+            let relay_transport = relay_transport
+                .upgrade(Version::V1)
+                .authenticate(noise.clone())
+                .multiplex(yamux.clone())
+                .boxed();
+            transport = transport
+                .or_transport(relay_transport)
+                .map(|either, _| either.into_inner())
+                .boxed();
+        }
+
         // Add the DNS transport to the transport.
         transport = libp2p::dns::tokio::Transport::system(transport)
             .map_err(|e| SignerSwarmError::LibP2P(Box::new(e)))?
@@ -411,6 +516,10 @@ impl<'a> SignerSwarmBuilder<'a> {
             swarm: Arc::new(Mutex::new(swarm)),
             listen_addrs: self.listen_on,
             external_addresses: self.external_addresses,
+            relay_servers: match self.enable_relay {
+                true => self.relay_servers,
+                false => Vec::new(),
+            },
         })
     }
 }
@@ -421,6 +530,7 @@ pub struct SignerSwarm {
     swarm: Arc<Mutex<Swarm<SignerBehavior>>>,
     listen_addrs: Vec<Multiaddr>,
     external_addresses: Vec<Multiaddr>,
+    relay_servers: Vec<Multiaddr>,
 }
 
 impl SignerSwarm {
@@ -446,6 +556,10 @@ impl SignerSwarm {
     /// Start the [`SignerSwarm`] and run the event loop. This function will block until the
     /// swarm is stopped (either by receiving a shutdown signal or an unrecoverable error).
     pub async fn start(&mut self, ctx: &impl Context) -> Result<(), SignerSwarmError> {
+        let _participant = ctx
+            .get_termination_handle()
+            .register_participant("p2p-swarm");
+
         // Separate scope to ensure that the lock is released before the event loop is run.
         {
             let mut swarm = self.swarm.lock().await;
@@ -464,7 +578,7 @@ impl SignerSwarm {
         }
 
         // Run the event loop, blocking until its completion.
-        event_loop::run(ctx, Arc::clone(&self.swarm)).await;
+        event_loop::run(ctx, Arc::clone(&self.swarm), &self.relay_servers).await;
 
         Ok(())
     }
@@ -16,7 +16,10 @@ use std::future::Future;
 
 use crate::ecdsa;
 use crate::error::Error;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
 use crate::message;
+use crate::storage::DbWrite;
 
 #[cfg(any(test, feature = "testing"))]
 pub use in_memory::InMemoryNetwork;
@@ -36,6 +39,23 @@ pub trait MessageTransfer: Clone {
     fn receive(&mut self) -> impl Future<Output = Result<Msg, Error>> + Send;
 }
 
+/// Sign the given message using the next P2P message sequence number of
+/// the signer, so that receiving signers can detect replayed messages. The
+/// sequence number is kept in the database so that it keeps increasing
+/// across restarts.
+pub async fn sign_with_next_sequence<S>(
+    storage: &S,
+    msg: message::SignerMessage,
+    private_key: &PrivateKey,
+) -> Result<Msg, Error>
+where
+    S: DbWrite,
+{
+    let public_key = PublicKey::from_private_key(private_key);
+    let sequence = storage.next_p2p_message_sequence(&public_key).await?;
+    Ok(msg.sign_ecdsa_with_sequence(private_key, sequence))
+}
+
 impl std::fmt::Display for Msg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Msg({})", self.payload)
@@ -9,6 +9,8 @@ use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 
 use bitcoin::OutPoint;
+use bitcoin::TapSighash;
+use bitcoin::hashes::Hash as _;
 use clarity::codec::StacksMessageCodec as _;
 use clarity::vm::types::PrincipalData;
 use p256k1::point::Point;
@@ -39,14 +41,22 @@ use wsts::traits::PartyState;
 use wsts::traits::SignerState;
 
 use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::validation::BitcoinSweepErrorMsg;
+use crate::bitcoin::validation::BitcoinValidationFailure;
+use crate::bitcoin::validation::InputValidationResult;
 use crate::bitcoin::validation::TxRequestIds;
+use crate::bitcoin::validation::WithdrawalValidationResult;
 use crate::codec;
+use crate::ecdsa::SIGNED_MESSAGE_VERSION;
 use crate::ecdsa::Signed;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::message::BitcoinPreSignAck;
 use crate::message::BitcoinPreSignRequest;
+use crate::message::BitcoinTxRejection;
 use crate::message::Payload;
+use crate::message::RoundAbort;
+use crate::message::RoundAbortReason;
 use crate::message::SignerDepositDecision;
 use crate::message::SignerMessage;
 use crate::message::SignerWithdrawalDecision;
@@ -65,6 +75,7 @@ use crate::stacks::contracts::StacksTx;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinTxId;
 use crate::storage::model::QualifiedRequestId;
+use crate::storage::model::SigHash;
 use crate::storage::model::StacksBlockHash;
 use crate::storage::model::StacksPrincipal;
 use crate::storage::model::StacksTxId;
@@ -374,7 +385,7 @@ impl TryFrom<proto::SignerWithdrawalDecision> for SignerWithdrawalDecision {
             request_id: value.request_id,
             block_hash: StacksBlockHash::try_from(value.block_id.required()?)?,
             accepted: value.accepted,
-            txid: value.txid.required()?.try_into()?,
+            txid: BitcoinTxId::try_from(value.txid.required()?)?.into(),
         })
     }
 }
@@ -1077,6 +1088,12 @@ impl From<WstsMessage> for proto::WstsMessage {
                 WstsMessageId::Dkg(id) => wsts_message::Id::Dkg(id.into()),
             }),
             inner: Some(inner),
+            session: Some(wsts_message::Session::WstsSession(proto::WstsSession {
+                session_id: value.session_id.to_vec(),
+                sighash: value
+                    .sighash
+                    .map(|sighash| proto::Uint256::from(sighash.to_byte_array())),
+            })),
         }
     }
 }
@@ -1085,6 +1102,7 @@ impl TryFrom<proto::WstsMessage> for WstsMessage {
     type Error = Error;
 
     fn try_from(value: proto::WstsMessage) -> Result<Self, Self::Error> {
+        let wsts_message::Session::WstsSession(session) = value.session.required()?;
         let inner = match value.inner.required()? {
             proto::wsts_message::Inner::DkgBegin(inner) => {
                 wsts::net::Message::DkgBegin(inner.into())
@@ -1128,6 +1146,13 @@ impl TryFrom<proto::WstsMessage> for WstsMessage {
                 }
                 wsts_message::Id::Dkg(id) => WstsMessageId::Dkg(id.into()),
             },
+            session_id: session
+                .session_id
+                .try_into()
+                .map_err(|_| Error::TypeConversion)?,
+            sighash: session
+                .sighash
+                .map(|sighash| SigHash::from(TapSighash::from_byte_array(sighash.into()))),
             inner,
         })
     }
@@ -1251,15 +1276,289 @@ impl TryFrom<proto::BitcoinPreSignRequest> for BitcoinPreSignRequest {
     }
 }
 
+impl From<InputValidationResult> for proto::InputValidationResult {
+    fn from(value: InputValidationResult) -> Self {
+        match value {
+            InputValidationResult::Ok => proto::InputValidationResult::Ok,
+            InputValidationResult::AmountTooLow => proto::InputValidationResult::AmountTooLow,
+            InputValidationResult::AmountBelowMinimum => {
+                proto::InputValidationResult::AmountBelowMinimum
+            }
+            InputValidationResult::MintAmountBelowDustLimit => {
+                proto::InputValidationResult::MintAmountBelowDustLimit
+            }
+            InputValidationResult::AmountTooHigh => proto::InputValidationResult::AmountTooHigh,
+            InputValidationResult::FeeTooHigh => proto::InputValidationResult::FeeTooHigh,
+            InputValidationResult::CannotSignUtxo => proto::InputValidationResult::CannotSignUtxo,
+            InputValidationResult::TxNotOnBestChain => {
+                proto::InputValidationResult::TxNotOnBestChain
+            }
+            InputValidationResult::DepositUtxoSpent => {
+                proto::InputValidationResult::DepositUtxoSpent
+            }
+            InputValidationResult::DkgSharesVerifyFailed => {
+                proto::InputValidationResult::DkgSharesVerifyFailed
+            }
+            InputValidationResult::DkgSharesUnverified => {
+                proto::InputValidationResult::DkgSharesUnverified
+            }
+            InputValidationResult::LockTimeExpiry => proto::InputValidationResult::LockTimeExpiry,
+            InputValidationResult::NoVote => proto::InputValidationResult::NoVote,
+            InputValidationResult::RejectedRequest => proto::InputValidationResult::RejectedRequest,
+            InputValidationResult::Unknown => proto::InputValidationResult::Unknown,
+            InputValidationResult::UnsupportedLockTime => {
+                proto::InputValidationResult::UnsupportedLockTime
+            }
+        }
+    }
+}
+
+impl TryFrom<proto::InputValidationResult> for InputValidationResult {
+    type Error = Error;
+    fn try_from(value: proto::InputValidationResult) -> Result<Self, Self::Error> {
+        Ok(match value {
+            proto::InputValidationResult::Ok => InputValidationResult::Ok,
+            proto::InputValidationResult::AmountTooLow => InputValidationResult::AmountTooLow,
+            proto::InputValidationResult::AmountBelowMinimum => {
+                InputValidationResult::AmountBelowMinimum
+            }
+            proto::InputValidationResult::MintAmountBelowDustLimit => {
+                InputValidationResult::MintAmountBelowDustLimit
+            }
+            proto::InputValidationResult::AmountTooHigh => InputValidationResult::AmountTooHigh,
+            proto::InputValidationResult::FeeTooHigh => InputValidationResult::FeeTooHigh,
+            proto::InputValidationResult::CannotSignUtxo => InputValidationResult::CannotSignUtxo,
+            proto::InputValidationResult::TxNotOnBestChain => {
+                InputValidationResult::TxNotOnBestChain
+            }
+            proto::InputValidationResult::DepositUtxoSpent => {
+                InputValidationResult::DepositUtxoSpent
+            }
+            proto::InputValidationResult::DkgSharesVerifyFailed => {
+                InputValidationResult::DkgSharesVerifyFailed
+            }
+            proto::InputValidationResult::DkgSharesUnverified => {
+                InputValidationResult::DkgSharesUnverified
+            }
+            proto::InputValidationResult::LockTimeExpiry => InputValidationResult::LockTimeExpiry,
+            proto::InputValidationResult::NoVote => InputValidationResult::NoVote,
+            proto::InputValidationResult::RejectedRequest => InputValidationResult::RejectedRequest,
+            proto::InputValidationResult::Unknown => InputValidationResult::Unknown,
+            proto::InputValidationResult::UnsupportedLockTime => {
+                InputValidationResult::UnsupportedLockTime
+            }
+            proto::InputValidationResult::Unspecified => return Err(Error::TypeConversion),
+        })
+    }
+}
+
+impl From<WithdrawalValidationResult> for proto::WithdrawalValidationResult {
+    fn from(value: WithdrawalValidationResult) -> Self {
+        match value {
+            WithdrawalValidationResult::Ok => proto::WithdrawalValidationResult::Ok,
+            WithdrawalValidationResult::AmountTooHigh => {
+                proto::WithdrawalValidationResult::AmountTooHigh
+            }
+            WithdrawalValidationResult::AmountIsDust => {
+                proto::WithdrawalValidationResult::AmountIsDust
+            }
+            WithdrawalValidationResult::FeeTooHigh => proto::WithdrawalValidationResult::FeeTooHigh,
+            WithdrawalValidationResult::NoVote => proto::WithdrawalValidationResult::NoVote,
+            WithdrawalValidationResult::RequestExpired => {
+                proto::WithdrawalValidationResult::RequestExpired
+            }
+            WithdrawalValidationResult::RequestFulfilled => {
+                proto::WithdrawalValidationResult::RequestFulfilled
+            }
+            WithdrawalValidationResult::RequestNotFinal => {
+                proto::WithdrawalValidationResult::RequestNotFinal
+            }
+            WithdrawalValidationResult::RequestRejected => {
+                proto::WithdrawalValidationResult::RequestRejected
+            }
+            WithdrawalValidationResult::TxNotOnBestChain => {
+                proto::WithdrawalValidationResult::TxNotOnBestChain
+            }
+            WithdrawalValidationResult::Unknown => proto::WithdrawalValidationResult::Unknown,
+        }
+    }
+}
+
+impl TryFrom<proto::WithdrawalValidationResult> for WithdrawalValidationResult {
+    type Error = Error;
+    fn try_from(value: proto::WithdrawalValidationResult) -> Result<Self, Self::Error> {
+        Ok(match value {
+            proto::WithdrawalValidationResult::Ok => WithdrawalValidationResult::Ok,
+            proto::WithdrawalValidationResult::AmountTooHigh => {
+                WithdrawalValidationResult::AmountTooHigh
+            }
+            proto::WithdrawalValidationResult::AmountIsDust => {
+                WithdrawalValidationResult::AmountIsDust
+            }
+            proto::WithdrawalValidationResult::FeeTooHigh => WithdrawalValidationResult::FeeTooHigh,
+            proto::WithdrawalValidationResult::NoVote => WithdrawalValidationResult::NoVote,
+            proto::WithdrawalValidationResult::RequestExpired => {
+                WithdrawalValidationResult::RequestExpired
+            }
+            proto::WithdrawalValidationResult::RequestFulfilled => {
+                WithdrawalValidationResult::RequestFulfilled
+            }
+            proto::WithdrawalValidationResult::RequestNotFinal => {
+                WithdrawalValidationResult::RequestNotFinal
+            }
+            proto::WithdrawalValidationResult::RequestRejected => {
+                WithdrawalValidationResult::RequestRejected
+            }
+            proto::WithdrawalValidationResult::TxNotOnBestChain => {
+                WithdrawalValidationResult::TxNotOnBestChain
+            }
+            proto::WithdrawalValidationResult::Unknown => WithdrawalValidationResult::Unknown,
+            proto::WithdrawalValidationResult::Unspecified => return Err(Error::TypeConversion),
+        })
+    }
+}
+
+impl From<BitcoinValidationFailure> for proto::BitcoinValidationFailure {
+    fn from(value: BitcoinValidationFailure) -> Self {
+        let error = match value.error {
+            BitcoinSweepErrorMsg::Deposit(result) => {
+                proto::bitcoin_validation_failure::Error::Deposit(
+                    proto::InputValidationResult::from(result).into(),
+                )
+            }
+            BitcoinSweepErrorMsg::Withdrawal(result) => {
+                proto::bitcoin_validation_failure::Error::Withdrawal(
+                    proto::WithdrawalValidationResult::from(result).into(),
+                )
+            }
+        };
+        proto::BitcoinValidationFailure {
+            // Transactions cannot have anywhere near u32::MAX inputs or
+            // outputs, so this never saturates in practice.
+            index: u32::try_from(value.index).unwrap_or(u32::MAX),
+            error: Some(error),
+        }
+    }
+}
+
+impl TryFrom<proto::BitcoinValidationFailure> for BitcoinValidationFailure {
+    type Error = Error;
+    fn try_from(value: proto::BitcoinValidationFailure) -> Result<Self, Self::Error> {
+        let error = match value.error.required()? {
+            proto::bitcoin_validation_failure::Error::Deposit(result) => {
+                let result = proto::InputValidationResult::try_from(result)
+                    .map_err(|_| Error::TypeConversion)?;
+                BitcoinSweepErrorMsg::Deposit(result.try_into()?)
+            }
+            proto::bitcoin_validation_failure::Error::Withdrawal(result) => {
+                let result = proto::WithdrawalValidationResult::try_from(result)
+                    .map_err(|_| Error::TypeConversion)?;
+                BitcoinSweepErrorMsg::Withdrawal(result.try_into()?)
+            }
+        };
+        Ok(BitcoinValidationFailure {
+            index: usize::try_from(value.index).map_err(|_| Error::TypeConversion)?,
+            error,
+        })
+    }
+}
+
+impl From<BitcoinTxRejection> for proto::BitcoinTxRejection {
+    fn from(value: BitcoinTxRejection) -> Self {
+        proto::BitcoinTxRejection {
+            txid: Some(BitcoinTxId::from(value.txid).into()),
+            failures: value.failures.into_iter().map(|x| x.into()).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::BitcoinTxRejection> for BitcoinTxRejection {
+    type Error = Error;
+    fn try_from(value: proto::BitcoinTxRejection) -> Result<Self, Self::Error> {
+        Ok(BitcoinTxRejection {
+            txid: value.txid.required()?.try_into()?,
+            failures: value
+                .failures
+                .into_iter()
+                .map(|x| x.try_into())
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+}
+
 impl From<BitcoinPreSignAck> for proto::BitcoinPreSignAck {
-    fn from(_: BitcoinPreSignAck) -> Self {
-        proto::BitcoinPreSignAck {}
+    fn from(value: BitcoinPreSignAck) -> Self {
+        proto::BitcoinPreSignAck {
+            rejected_txs: value.rejected_txs.into_iter().map(|x| x.into()).collect(),
+        }
     }
 }
 
-impl From<proto::BitcoinPreSignAck> for BitcoinPreSignAck {
-    fn from(_: proto::BitcoinPreSignAck) -> Self {
-        BitcoinPreSignAck {}
+impl TryFrom<proto::BitcoinPreSignAck> for BitcoinPreSignAck {
+    type Error = Error;
+    fn try_from(value: proto::BitcoinPreSignAck) -> Result<Self, Self::Error> {
+        Ok(BitcoinPreSignAck {
+            rejected_txs: value
+                .rejected_txs
+                .into_iter()
+                .map(|x| x.try_into())
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+}
+
+impl From<RoundAbortReason> for proto::RoundAbortReason {
+    fn from(value: RoundAbortReason) -> Self {
+        match value {
+            RoundAbortReason::Timeout => proto::RoundAbortReason::Timeout,
+        }
+    }
+}
+
+impl TryFrom<proto::RoundAbortReason> for RoundAbortReason {
+    type Error = Error;
+    fn try_from(value: proto::RoundAbortReason) -> Result<Self, Self::Error> {
+        Ok(match value {
+            proto::RoundAbortReason::Timeout => RoundAbortReason::Timeout,
+            proto::RoundAbortReason::Unspecified => return Err(Error::TypeConversion),
+        })
+    }
+}
+
+impl From<RoundAbort> for proto::RoundAbort {
+    fn from(value: RoundAbort) -> Self {
+        let round_id = match value.round_id {
+            WstsMessageId::Sweep(txid) => proto::round_abort::RoundId::Sweep(proto::BitcoinTxid {
+                txid: Some(proto::Uint256::from(BitcoinTxId::from(txid).into_bytes())),
+            }),
+            WstsMessageId::DkgVerification(pubkey) => {
+                proto::round_abort::RoundId::DkgVerification(pubkey.into())
+            }
+            WstsMessageId::Dkg(id) => proto::round_abort::RoundId::Dkg(id.into()),
+        };
+
+        proto::RoundAbort {
+            reason: proto::RoundAbortReason::from(value.reason).into(),
+            round_id: Some(round_id),
+        }
+    }
+}
+
+impl TryFrom<proto::RoundAbort> for RoundAbort {
+    type Error = Error;
+    fn try_from(value: proto::RoundAbort) -> Result<Self, Self::Error> {
+        let reason = value.reason().try_into()?;
+        let round_id = match value.round_id.required()? {
+            proto::round_abort::RoundId::Sweep(txid) => {
+                WstsMessageId::Sweep(BitcoinTxId::try_from(txid)?.into())
+            }
+            proto::round_abort::RoundId::DkgVerification(pubkey) => {
+                WstsMessageId::DkgVerification(PublicKey::try_from(pubkey)?)
+            }
+            proto::round_abort::RoundId::Dkg(id) => WstsMessageId::Dkg(id.into()),
+        };
+
+        Ok(RoundAbort { round_id, reason })
     }
 }
 
@@ -1306,6 +1605,7 @@ impl From<Payload> for proto::Payload {
             Payload::BitcoinPreSignAck(inner) => {
                 proto::signer_message::Payload::BitcoinPreSignAck(inner.into())
             }
+            Payload::RoundAbort(inner) => proto::signer_message::Payload::RoundAbort(inner.into()),
         }
     }
 }
@@ -1333,7 +1633,10 @@ impl TryFrom<proto::Payload> for Payload {
                 Payload::BitcoinPreSignRequest(inner.try_into()?)
             }
             proto::signer_message::Payload::BitcoinPreSignAck(inner) => {
-                Payload::BitcoinPreSignAck(inner.into())
+                Payload::BitcoinPreSignAck(inner.try_into()?)
+            }
+            proto::signer_message::Payload::RoundAbort(inner) => {
+                Payload::RoundAbort(inner.try_into()?)
             }
         };
         Ok(payload)
@@ -1346,6 +1649,8 @@ impl From<Signed<SignerMessage>> for proto::Signed {
             signature: Some(value.signature.into()),
             signer_public_key: Some(value.signer_public_key.into()),
             signer_message: Some(value.inner.into()),
+            version: SIGNED_MESSAGE_VERSION,
+            sequence: value.sequence,
         }
     }
 }
@@ -1353,11 +1658,15 @@ impl From<Signed<SignerMessage>> for proto::Signed {
 impl TryFrom<proto::Signed> for Signed<SignerMessage> {
     type Error = Error;
     fn try_from(value: proto::Signed) -> Result<Self, Self::Error> {
+        if value.version > SIGNED_MESSAGE_VERSION {
+            return Err(Error::UnsupportedMessageVersion(value.version));
+        }
         let inner: SignerMessage = value.signer_message.required()?.try_into()?;
         Ok(Signed {
             inner,
             signature: value.signature.required()?.try_into()?,
             signer_public_key: value.signer_public_key.required()?.try_into()?,
+            sequence: value.sequence,
         })
     }
 }
@@ -1643,6 +1952,7 @@ impl codec::ProtoSerializable for SignerMessage {
             Payload::WstsMessage(_) => "SBTC_WSTS_MESSAGE",
             Payload::BitcoinPreSignRequest(_) => "SBTC_BITCOIN_PRE_SIGN_REQUEST",
             Payload::BitcoinPreSignAck(_) => "SBTC_BITCOIN_PRE_SIGN_ACK",
+            Payload::RoundAbort(_) => "SBTC_ROUND_ABORT",
         }
     }
 }
@@ -1724,6 +2034,11 @@ mod tests {
     #[test_case(PhantomData::<(Fees, proto::Fees)>; "Fees")]
     #[test_case(PhantomData::<(BitcoinPreSignRequest, proto::BitcoinPreSignRequest)>; "BitcoinPreSignRequest")]
     #[test_case(PhantomData::<(BitcoinPreSignAck, proto::BitcoinPreSignAck)>; "BitcoinPreSignAck")]
+    #[test_case(PhantomData::<(BitcoinTxRejection, proto::BitcoinTxRejection)>; "BitcoinTxRejection")]
+    #[test_case(PhantomData::<(BitcoinValidationFailure, proto::BitcoinValidationFailure)>; "BitcoinValidationFailure")]
+    #[test_case(PhantomData::<(InputValidationResult, proto::InputValidationResult)>; "InputValidationResult")]
+    #[test_case(PhantomData::<(WithdrawalValidationResult, proto::WithdrawalValidationResult)>; "WithdrawalValidationResult")]
+    #[test_case(PhantomData::<(RoundAbort, proto::RoundAbort)>; "RoundAbort")]
     fn convert_protobuf_type<T, U, E>(_: PhantomData<(T, U)>)
     where
         // `.unwrap()` requires that `E` implement `std::fmt::Debug` and
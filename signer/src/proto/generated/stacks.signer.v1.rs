@@ -284,7 +284,7 @@ pub struct SignerMessage {
         super::super::super::bitcoin::BitcoinBlockHash,
     >,
     /// The message payload
-    #[prost(oneof = "signer_message::Payload", tags = "2, 3, 4, 5, 8, 10, 11")]
+    #[prost(oneof = "signer_message::Payload", tags = "2, 3, 4, 5, 8, 10, 11, 12")]
     pub payload: ::core::option::Option<signer_message::Payload>,
 }
 /// Nested message and enum types in `SignerMessage`.
@@ -313,6 +313,9 @@ pub mod signer_message {
         /// Represents an acknowledgment of a BitcoinPreSignRequest
         #[prost(message, tag = "11")]
         BitcoinPreSignAck(super::BitcoinPreSignAck),
+        /// Tells the signers that the coordinator abandoned a WSTS round
+        #[prost(message, tag = "12")]
+        RoundAbort(super::RoundAbort),
     }
 }
 /// A wsts message.
@@ -323,6 +326,19 @@ pub struct WstsMessage {
     pub inner: ::core::option::Option<wsts_message::Inner>,
     #[prost(oneof = "wsts_message::Id", tags = "12, 13, 14")]
     pub id: ::core::option::Option<wsts_message::Id>,
+    /// The round that this message belongs to. This is a oneof with a single
+    /// field so that prost serializes it after the oneofs above, which the
+    /// canonical encoding of signer messages requires.
+    ///
+    /// This field is required, which makes its introduction a breaking change
+    /// to the wire format. Signers that predate it drop the field when they
+    /// decode a message, so the signature that they verify over the decoded
+    /// message no longer matches, and signers that have it reject messages
+    /// without it. Accepting messages without a session during a transition
+    /// would therefore not let old and new signers complete rounds together,
+    /// so all signers must upgrade together, between rounds.
+    #[prost(oneof = "wsts_message::Session", tags = "15")]
+    pub session: ::core::option::Option<wsts_message::Session>,
 }
 /// Nested message and enum types in `WstsMessage`.
 pub mod wsts_message {
@@ -382,6 +398,26 @@ pub mod wsts_message {
         #[prost(message, tag = "14")]
         Dkg(super::super::super::super::crypto::Uint256),
     }
+    /// The round that this message belongs to. This is a oneof with a single
+    /// field so that prost serializes it after the oneofs above, which the
+    /// canonical encoding of signer messages requires.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Session {
+        /// The session of the DKG or signing round.
+        #[prost(message, tag = "15")]
+        WstsSession(super::WstsSession),
+    }
+}
+/// Identifies the DKG or signing round that a WSTS message belongs to.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WstsSession {
+    /// A random 16-byte id that the coordinator picks for each round, and
+    /// that the signers echo back in their replies.
+    #[prost(bytes = "vec", tag = "1")]
+    pub session_id: ::prost::alloc::vec::Vec<u8>,
+    /// The sighash being signed, if the message is part of a signing round.
+    #[prost(message, optional, tag = "2")]
+    pub sighash: ::core::option::Option<super::super::super::crypto::Uint256>,
 }
 /// Wraps an inner type with a public key and a signature,
 /// allowing easy verification of the integrity of the inner data.
@@ -398,6 +434,17 @@ pub struct Signed {
     /// The signed structure.
     #[prost(message, optional, tag = "3")]
     pub signer_message: ::core::option::Option<SignerMessage>,
+    /// The version of the signed message format. Signers reject messages
+    /// with a version that is newer than the one that they support. The
+    /// current version is 0, which is omitted on the wire.
+    #[prost(uint32, tag = "4")]
+    pub version: u32,
+    /// A number that increases with each message published by the signer,
+    /// used by receivers to detect replayed messages. It is zero if the
+    /// message was not assigned a sequence number, and receivers drop such
+    /// messages.
+    #[prost(uint64, tag = "5")]
+    pub sequence: u64,
 }
 /// Information about a new Bitcoin block sign request
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -417,8 +464,74 @@ pub struct BitcoinPreSignRequest {
     pub last_fees: ::core::option::Option<Fees>,
 }
 /// Represents an acknowledgment of a BitcoinPreSignRequest.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BitcoinPreSignAck {
+    /// The transactions in the request package that failed validation, and
+    /// so will not be signed by the acknowledging signer.
+    #[prost(message, repeated, tag = "1")]
+    pub rejected_txs: ::prost::alloc::vec::Vec<BitcoinTxRejection>,
+}
+/// A transaction in a BitcoinPreSignRequest that failed validation.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BitcoinTxRejection {
+    /// The ID of the transaction that failed validation.
+    #[prost(message, optional, tag = "1")]
+    pub txid: ::core::option::Option<super::super::super::bitcoin::BitcoinTxid>,
+    /// Every input and output of the transaction that failed validation.
+    #[prost(message, repeated, tag = "2")]
+    pub failures: ::prost::alloc::vec::Vec<BitcoinValidationFailure>,
+}
+/// An input or output of a sweep transaction that failed validation.
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
-pub struct BitcoinPreSignAck {}
+pub struct BitcoinValidationFailure {
+    /// The index of the input, for deposits, or the output, for
+    /// withdrawals, in the transaction.
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// The reason that the input or output failed validation.
+    #[prost(oneof = "bitcoin_validation_failure::Error", tags = "2, 3")]
+    pub error: ::core::option::Option<bitcoin_validation_failure::Error>,
+}
+/// Nested message and enum types in `BitcoinValidationFailure`.
+pub mod bitcoin_validation_failure {
+    /// The reason that the input or output failed validation.
+    #[derive(Clone, Copy, PartialEq, ::prost::Oneof)]
+    pub enum Error {
+        /// The input for a deposit request failed validation.
+        #[prost(enumeration = "super::InputValidationResult", tag = "2")]
+        Deposit(i32),
+        /// The output for a withdrawal request failed validation.
+        #[prost(enumeration = "super::WithdrawalValidationResult", tag = "3")]
+        Withdrawal(i32),
+    }
+}
+/// Tells the signers that the coordinator abandoned a DKG or signing
+/// round, so that they can drop any state that they have for it.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RoundAbort {
+    /// Why the coordinator abandoned the round.
+    #[prost(enumeration = "RoundAbortReason", tag = "1")]
+    pub reason: i32,
+    /// The id of the round, which is the id of the WSTS messages in it.
+    #[prost(oneof = "round_abort::RoundId", tags = "2, 3, 4")]
+    pub round_id: ::core::option::Option<round_abort::RoundId>,
+}
+/// Nested message and enum types in `RoundAbort`.
+pub mod round_abort {
+    /// The id of the round, which is the id of the WSTS messages in it.
+    #[derive(Clone, Copy, PartialEq, ::prost::Oneof)]
+    pub enum RoundId {
+        /// The round was for signing the Bitcoin transaction with this ID.
+        #[prost(message, tag = "2")]
+        Sweep(super::super::super::super::bitcoin::BitcoinTxid),
+        /// The round was for verifying this new aggregate public key.
+        #[prost(message, tag = "3")]
+        DkgVerification(super::super::super::super::crypto::PublicKey),
+        /// The round was a DKG round with this 32-byte id.
+        #[prost(message, tag = "4")]
+        Dkg(super::super::super::super::crypto::Uint256),
+    }
+}
 /// This type is a container for all deposits and withdrawals that are part
 /// of a transaction package.
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -431,3 +544,202 @@ pub struct TxRequestIds {
     #[prost(message, repeated, tag = "2")]
     pub withdrawals: ::prost::alloc::vec::Vec<QualifiedRequestId>,
 }
+/// The response to a request, made over the decisions sync protocol, for
+/// the decisions that a signer made on a set of deposit and withdrawal
+/// requests. The request itself is a `TxRequestIds` message.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignerDecisions {
+    /// The encoded `Signed` messages carrying the `SignerDepositDecision`s
+    /// and `SignerWithdrawalDecision`s of the responding signer. They are
+    /// kept encoded so that they can be verified exactly like messages
+    /// received over gossipsub.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub messages: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// The reasons that a coordinator abandons a WSTS round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RoundAbortReason {
+    Unspecified = 0,
+    /// The round did not complete before the coordinator's timer fired.
+    Timeout = 1,
+}
+impl RoundAbortReason {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "ROUND_ABORT_REASON_UNSPECIFIED",
+            Self::Timeout => "ROUND_ABORT_REASON_TIMEOUT",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ROUND_ABORT_REASON_UNSPECIFIED" => Some(Self::Unspecified),
+            "ROUND_ABORT_REASON_TIMEOUT" => Some(Self::Timeout),
+            _ => None,
+        }
+    }
+}
+/// The result of validating the input for a deposit request in a sweep
+/// transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum InputValidationResult {
+    Unspecified = 0,
+    /// The deposit request passed validation.
+    Ok = 1,
+    /// The deposit request amount is below the allowed per-deposit minimum.
+    AmountTooLow = 2,
+    /// The deposit request amount is below the minimum deposit amount
+    /// configured for the signer.
+    AmountBelowMinimum = 3,
+    /// The deposit request amount, less the fees, is below the dust limit.
+    MintAmountBelowDustLimit = 4,
+    /// The deposit request amount exceeds the allowed per-deposit cap.
+    AmountTooHigh = 5,
+    /// The assessed fee exceeds the max-fee in the deposit request.
+    FeeTooHigh = 6,
+    /// The signer cannot sign for the deposit UTXO.
+    CannotSignUtxo = 7,
+    /// The deposit transaction is not on the canonical bitcoin blockchain.
+    TxNotOnBestChain = 8,
+    /// The deposit UTXO has already been spent.
+    DepositUtxoSpent = 9,
+    /// The DKG shares locking the deposit UTXO failed verification.
+    DkgSharesVerifyFailed = 10,
+    /// The DKG shares locking the deposit UTXO have not been verified.
+    DkgSharesUnverified = 11,
+    /// The lock-time of the deposit is too close to expiring.
+    LockTimeExpiry = 12,
+    /// The signer does not have a record of its vote on the deposit request.
+    NoVote = 13,
+    /// The signer has rejected the deposit request.
+    RejectedRequest = 14,
+    /// The signer does not have a record of the deposit request.
+    Unknown = 15,
+    /// The lock-time of the deposit is in time units, which is not supported.
+    UnsupportedLockTime = 16,
+}
+impl InputValidationResult {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "INPUT_VALIDATION_RESULT_UNSPECIFIED",
+            Self::Ok => "INPUT_VALIDATION_RESULT_OK",
+            Self::AmountTooLow => "INPUT_VALIDATION_RESULT_AMOUNT_TOO_LOW",
+            Self::AmountBelowMinimum => "INPUT_VALIDATION_RESULT_AMOUNT_BELOW_MINIMUM",
+            Self::MintAmountBelowDustLimit => "INPUT_VALIDATION_RESULT_MINT_AMOUNT_BELOW_DUST_LIMIT",
+            Self::AmountTooHigh => "INPUT_VALIDATION_RESULT_AMOUNT_TOO_HIGH",
+            Self::FeeTooHigh => "INPUT_VALIDATION_RESULT_FEE_TOO_HIGH",
+            Self::CannotSignUtxo => "INPUT_VALIDATION_RESULT_CANNOT_SIGN_UTXO",
+            Self::TxNotOnBestChain => "INPUT_VALIDATION_RESULT_TX_NOT_ON_BEST_CHAIN",
+            Self::DepositUtxoSpent => "INPUT_VALIDATION_RESULT_DEPOSIT_UTXO_SPENT",
+            Self::DkgSharesVerifyFailed => "INPUT_VALIDATION_RESULT_DKG_SHARES_VERIFY_FAILED",
+            Self::DkgSharesUnverified => "INPUT_VALIDATION_RESULT_DKG_SHARES_UNVERIFIED",
+            Self::LockTimeExpiry => "INPUT_VALIDATION_RESULT_LOCK_TIME_EXPIRY",
+            Self::NoVote => "INPUT_VALIDATION_RESULT_NO_VOTE",
+            Self::RejectedRequest => "INPUT_VALIDATION_RESULT_REJECTED_REQUEST",
+            Self::Unknown => "INPUT_VALIDATION_RESULT_UNKNOWN",
+            Self::UnsupportedLockTime => "INPUT_VALIDATION_RESULT_UNSUPPORTED_LOCK_TIME",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "INPUT_VALIDATION_RESULT_UNSPECIFIED" => Some(Self::Unspecified),
+            "INPUT_VALIDATION_RESULT_OK" => Some(Self::Ok),
+            "INPUT_VALIDATION_RESULT_AMOUNT_TOO_LOW" => Some(Self::AmountTooLow),
+            "INPUT_VALIDATION_RESULT_AMOUNT_BELOW_MINIMUM" => Some(Self::AmountBelowMinimum),
+            "INPUT_VALIDATION_RESULT_MINT_AMOUNT_BELOW_DUST_LIMIT" => Some(Self::MintAmountBelowDustLimit),
+            "INPUT_VALIDATION_RESULT_AMOUNT_TOO_HIGH" => Some(Self::AmountTooHigh),
+            "INPUT_VALIDATION_RESULT_FEE_TOO_HIGH" => Some(Self::FeeTooHigh),
+            "INPUT_VALIDATION_RESULT_CANNOT_SIGN_UTXO" => Some(Self::CannotSignUtxo),
+            "INPUT_VALIDATION_RESULT_TX_NOT_ON_BEST_CHAIN" => Some(Self::TxNotOnBestChain),
+            "INPUT_VALIDATION_RESULT_DEPOSIT_UTXO_SPENT" => Some(Self::DepositUtxoSpent),
+            "INPUT_VALIDATION_RESULT_DKG_SHARES_VERIFY_FAILED" => Some(Self::DkgSharesVerifyFailed),
+            "INPUT_VALIDATION_RESULT_DKG_SHARES_UNVERIFIED" => Some(Self::DkgSharesUnverified),
+            "INPUT_VALIDATION_RESULT_LOCK_TIME_EXPIRY" => Some(Self::LockTimeExpiry),
+            "INPUT_VALIDATION_RESULT_NO_VOTE" => Some(Self::NoVote),
+            "INPUT_VALIDATION_RESULT_REJECTED_REQUEST" => Some(Self::RejectedRequest),
+            "INPUT_VALIDATION_RESULT_UNKNOWN" => Some(Self::Unknown),
+            "INPUT_VALIDATION_RESULT_UNSUPPORTED_LOCK_TIME" => Some(Self::UnsupportedLockTime),
+            _ => None,
+        }
+    }
+}
+/// The result of validating the output for a withdrawal request in a sweep
+/// transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WithdrawalValidationResult {
+    Unspecified = 0,
+    /// The withdrawal request passed validation.
+    Ok = 1,
+    /// The withdrawal request amount exceeds the allowed per-withdrawal cap.
+    AmountTooHigh = 2,
+    /// The withdrawal request amount is below the bitcoin dust amount.
+    AmountIsDust = 3,
+    /// The assessed fee exceeds the max-fee in the withdrawal request.
+    FeeTooHigh = 4,
+    /// The signer does not have a record of its vote on the withdrawal request.
+    NoVote = 5,
+    /// The withdrawal request has expired.
+    RequestExpired = 6,
+    /// The withdrawal request has already been fulfilled.
+    RequestFulfilled = 7,
+    /// The withdrawal request is not yet final.
+    RequestNotFinal = 8,
+    /// The signer has rejected the withdrawal request.
+    RequestRejected = 9,
+    /// The withdrawal request is not on the canonical Stacks blockchain.
+    TxNotOnBestChain = 10,
+    /// The signer does not have a record of the withdrawal request.
+    Unknown = 11,
+}
+impl WithdrawalValidationResult {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "WITHDRAWAL_VALIDATION_RESULT_UNSPECIFIED",
+            Self::Ok => "WITHDRAWAL_VALIDATION_RESULT_OK",
+            Self::AmountTooHigh => "WITHDRAWAL_VALIDATION_RESULT_AMOUNT_TOO_HIGH",
+            Self::AmountIsDust => "WITHDRAWAL_VALIDATION_RESULT_AMOUNT_IS_DUST",
+            Self::FeeTooHigh => "WITHDRAWAL_VALIDATION_RESULT_FEE_TOO_HIGH",
+            Self::NoVote => "WITHDRAWAL_VALIDATION_RESULT_NO_VOTE",
+            Self::RequestExpired => "WITHDRAWAL_VALIDATION_RESULT_REQUEST_EXPIRED",
+            Self::RequestFulfilled => "WITHDRAWAL_VALIDATION_RESULT_REQUEST_FULFILLED",
+            Self::RequestNotFinal => "WITHDRAWAL_VALIDATION_RESULT_REQUEST_NOT_FINAL",
+            Self::RequestRejected => "WITHDRAWAL_VALIDATION_RESULT_REQUEST_REJECTED",
+            Self::TxNotOnBestChain => "WITHDRAWAL_VALIDATION_RESULT_TX_NOT_ON_BEST_CHAIN",
+            Self::Unknown => "WITHDRAWAL_VALIDATION_RESULT_UNKNOWN",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WITHDRAWAL_VALIDATION_RESULT_UNSPECIFIED" => Some(Self::Unspecified),
+            "WITHDRAWAL_VALIDATION_RESULT_OK" => Some(Self::Ok),
+            "WITHDRAWAL_VALIDATION_RESULT_AMOUNT_TOO_HIGH" => Some(Self::AmountTooHigh),
+            "WITHDRAWAL_VALIDATION_RESULT_AMOUNT_IS_DUST" => Some(Self::AmountIsDust),
+            "WITHDRAWAL_VALIDATION_RESULT_FEE_TOO_HIGH" => Some(Self::FeeTooHigh),
+            "WITHDRAWAL_VALIDATION_RESULT_NO_VOTE" => Some(Self::NoVote),
+            "WITHDRAWAL_VALIDATION_RESULT_REQUEST_EXPIRED" => Some(Self::RequestExpired),
+            "WITHDRAWAL_VALIDATION_RESULT_REQUEST_FULFILLED" => Some(Self::RequestFulfilled),
+            "WITHDRAWAL_VALIDATION_RESULT_REQUEST_NOT_FINAL" => Some(Self::RequestNotFinal),
+            "WITHDRAWAL_VALIDATION_RESULT_REQUEST_REJECTED" => Some(Self::RequestRejected),
+            "WITHDRAWAL_VALIDATION_RESULT_TX_NOT_ON_BEST_CHAIN" => Some(Self::TxNotOnBestChain),
+            "WITHDRAWAL_VALIDATION_RESULT_UNKNOWN" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+}
@@ -7,15 +7,22 @@
 
 use std::time::Duration;
 
+use bitcoin::relative::LockTime;
+use emily_client::models::DepositUpdate;
+use emily_client::models::Status;
+
+use crate::DEPOSIT_LOCKTIME_BLOCK_BUFFER;
+use crate::MAX_PENDING_SIGNER_DECISIONS;
+use crate::bitcoin::validation::DepositConfirmationStatus;
 use crate::block_observer::BlockObserver;
 use crate::blocklist_client::BlocklistChecker;
+use crate::config::BlocklistFailurePolicy;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::RequestDeciderEvent;
 use crate::context::SignerCommand;
 use crate::context::SignerEvent;
 use crate::context::SignerSignal;
-use crate::ecdsa::SignEcdsa as _;
 use crate::ecdsa::Signed;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
@@ -25,6 +32,8 @@ use crate::message::Payload;
 use crate::message::SignerDepositDecision;
 use crate::message::SignerMessage;
 use crate::message::SignerWithdrawalDecision;
+use crate::metrics::Metrics;
+use crate::network;
 use crate::network::MessageTransfer;
 use crate::storage::DbRead as _;
 use crate::storage::DbWrite as _;
@@ -93,7 +102,8 @@ where
         while let Some(message) = signal_stream.next().await {
             match message {
                 SignerSignal::Command(SignerCommand::Shutdown) => break,
-                SignerSignal::Command(SignerCommand::P2PPublish(_)) => {}
+                SignerSignal::Command(SignerCommand::P2PPublish(_))
+                | SignerSignal::Command(SignerCommand::P2PRequestDecisions(_)) => {}
                 SignerSignal::Event(event) => match event {
                     SignerEvent::P2P(P2PEvent::MessageReceived(msg)) => {
                         if let Err(error) = self.handle_signer_message(&msg).await {
@@ -165,6 +175,8 @@ where
             .get_pending_deposit_requests(&chain_tip, self.context_window, &signer_public_key)
             .await?;
 
+        metrics::gauge!(Metrics::DepositRequestsPending).set(deposit_requests.len() as f64);
+
         for deposit_request in deposit_requests {
             let outpoint = deposit_request.outpoint();
             let _ = self
@@ -198,6 +210,8 @@ where
             .get_pending_withdrawal_requests(&chain_tip, self.context_window, &signer_public_key)
             .await?;
 
+        metrics::gauge!(Metrics::WithdrawalRequestsPending).set(withdraw_requests.len() as f64);
+
         for withdraw_request in withdraw_requests {
             let request_id = withdraw_request.request_id;
             let _ = self
@@ -230,6 +244,7 @@ where
             Payload::StacksTransactionSignRequest(_)
             | Payload::BitcoinPreSignRequest(_)
             | Payload::BitcoinPreSignAck(_)
+            | Payload::RoundAbort(_)
             | Payload::WstsMessage(_)
             | Payload::StacksTransactionSignature(_) => (),
         };
@@ -289,12 +304,83 @@ where
 
         self.send_message(msg, chain_tip).await?;
 
+        if !can_accept {
+            let _ = self
+                .decline_deposit_in_emily(&request, chain_tip)
+                .await
+                .inspect_err(|error| tracing::warn!(%error, "could not decline deposit in Emily"));
+        }
+
         self.context
             .signal(RequestDeciderEvent::PendingDepositRequestRegistered.into())?;
 
         Ok(())
     }
 
+    /// Mark the deposit request as failed in Emily if it can never be
+    /// accepted and enough signers have voted against it.
+    ///
+    /// Emily would otherwise keep showing the deposit request as pending,
+    /// so we let the depositor know why it will not be processed. We
+    /// never do this for deposits that have already been swept, so that
+    /// we do not overwrite a later status in Emily.
+    #[tracing::instrument(skip_all, fields(outpoint = %request.outpoint()))]
+    async fn decline_deposit_in_emily(
+        &self,
+        request: &model::DepositRequest,
+        chain_tip: &BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let Some(quorum) = self.context.config().signer.deposit_decline_quorum else {
+            return Ok(());
+        };
+        let Some(reason) = permanent_deposit_decline_reason(request) else {
+            return Ok(());
+        };
+
+        let db = self.context.get_storage();
+        let rejections = db
+            .get_deposit_signers(&request.txid, request.output_index)
+            .await?
+            .iter()
+            .filter(|decision| !decision.can_accept)
+            .count();
+        if rejections < usize::from(quorum.get()) {
+            tracing::debug!(rejections, %quorum, "not enough rejections to decline the deposit");
+            return Ok(());
+        }
+
+        let report = db
+            .get_deposit_request_report(
+                chain_tip,
+                &request.txid,
+                request.output_index,
+                &self.signer_public_key(),
+            )
+            .await?;
+        let is_swept = report
+            .is_some_and(|report| matches!(report.status, DepositConfirmationStatus::Spent(_)));
+        if is_swept {
+            tracing::debug!("the deposit has been swept, not declining it");
+            return Ok(());
+        }
+
+        let update = DepositUpdate {
+            bitcoin_tx_output_index: request.output_index,
+            bitcoin_txid: request.txid.to_string(),
+            fulfillment: None,
+            status: Status::Failed,
+            status_message: reason.to_string(),
+            updated_by: None,
+        };
+        self.context
+            .get_emily_client()
+            .update_deposits(vec![update])
+            .await?;
+
+        tracing::info!(reason, "declined the deposit request in Emily");
+        Ok(())
+    }
+
     /// Send the given withdrawal decisions to the other signers for redundancy.
     #[tracing::instrument(skip_all)]
     pub async fn handle_withdrawal_decisions_to_retry(
@@ -391,15 +477,22 @@ where
                 )
             })?;
 
-        let can_accept = client
+        let result = client
             .can_accept(&receiver_address.to_string())
             .await
-            .inspect_err(|error| tracing::error!(%error, "blocklist client issue"))?;
+            .inspect_err(|error| tracing::error!(%error, "blocklist client issue"));
 
-        Ok(can_accept)
+        self.apply_blocklist_failure_policy(result)
     }
 
     async fn can_accept_deposit_request(&self, req: &model::DepositRequest) -> Result<bool, Error> {
+        // There is no point in asking the blocklist client about deposit
+        // requests that we will never be able to sweep.
+        if let Some(reason) = permanent_deposit_decline_reason(req) {
+            tracing::debug!(reason, outpoint = %req.outpoint(), "rejecting deposit request");
+            return Ok(false);
+        }
+
         // If we have not configured a blocklist checker, then we can
         // return early.
         let Some(client) = self.blocklist_checker.as_ref() else {
@@ -423,7 +516,20 @@ where
             .collect::<Vec<_>>()
             .await
             .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, _>>();
+
+        let responses = match responses {
+            Ok(responses) => responses,
+            Err(error) => return self.apply_blocklist_failure_policy(Err(error)),
+        };
+
+        for (address, _) in addresses.iter().zip(&responses).filter(|(_, res)| !**res) {
+            tracing::info!(
+                %address,
+                outpoint = %req.outpoint(),
+                "sender address flagged by the blocklist client; rejecting deposit request"
+            );
+        }
 
         // If all of the inputs addresses are fine then we pass the deposit
         // request.
@@ -431,6 +537,32 @@ where
         Ok(can_accept)
     }
 
+    /// Decide on a request using the configured [`BlocklistFailurePolicy`]
+    /// if screening it with the blocklist client failed.
+    fn apply_blocklist_failure_policy(&self, result: Result<bool, Error>) -> Result<bool, Error> {
+        let Err(error) = result else {
+            return result;
+        };
+
+        let policy = self
+            .context
+            .reloadable_config()
+            .get()
+            .blocklist_failure_policy;
+
+        match policy {
+            BlocklistFailurePolicy::Retry => Err(error),
+            BlocklistFailurePolicy::FailOpen => {
+                tracing::warn!(%error, "could not screen request; accepting it per the fail-open policy");
+                Ok(true)
+            }
+            BlocklistFailurePolicy::FailClosed => {
+                tracing::warn!(%error, "could not screen request; rejecting it per the fail-closed policy");
+                Ok(false)
+            }
+        }
+    }
+
     /// Save the given decision into the database
     ///
     /// If we do not have a record of the associated deposit request in our
@@ -474,19 +606,39 @@ where
             }
         }
         // We still might not have a record of the deposit request (perhaps
-        // it failed validation). In this case we do not persist the
-        // decision and move on.
+        // we are still catching up, or it failed validation). In this case
+        // we buffer the decision so that the block observer can replay it
+        // once the request is stored.
         if !db.deposit_request_exists(&txid, output_index).await? {
             tracing::debug!(
                 %txid,
                 %output_index,
                 sender = %signer_pub_key,
-                "we still do not have a record of the deposit request"
+                "we still do not have a record of the deposit request; buffering the decision"
             );
+            if self.can_buffer_signer_decision().await? {
+                db.write_pending_deposit_signer_decision(&signer_decision)
+                    .await?;
+            }
             return Ok(());
         }
         db.write_deposit_signer_decision(&signer_decision).await?;
 
+        // Another rejection may be what gets the deposit request to the
+        // quorum needed for declining it in Emily.
+        if !decision.can_accept {
+            let chain_tip = db.get_bitcoin_canonical_chain_tip().await?;
+            let request = db.get_deposit_request(&txid, output_index).await?;
+            if let (Some(chain_tip), Some(request)) = (chain_tip, request) {
+                let _ = self
+                    .decline_deposit_in_emily(&request, &chain_tip)
+                    .await
+                    .inspect_err(
+                        |error| tracing::warn!(%error, "could not decline deposit in Emily"),
+                    );
+            }
+        }
+
         self.context
             .signal(RequestDeciderEvent::ReceivedDepositDecision.into())?;
 
@@ -507,12 +659,27 @@ where
             txid: decision.txid,
         };
 
-        // TODO: we need to check to see if we have the withdrawal request
-        // first.
+        // If we do not have a record of the withdrawal request yet then we
+        // buffer the decision so that the block observer can replay it
+        // once the request is stored.
+        let db = self.context.get_storage_mut();
+        if !db
+            .withdrawal_request_exists(decision.request_id, &decision.block_hash)
+            .await?
+        {
+            tracing::debug!(
+                request_id = %decision.request_id,
+                block_hash = %decision.block_hash,
+                "we do not have a record of the withdrawal request; buffering the decision"
+            );
+            if self.can_buffer_signer_decision().await? {
+                db.write_pending_withdrawal_signer_decision(&signer_decision)
+                    .await?;
+            }
+            return Ok(());
+        }
 
-        self.context
-            .get_storage_mut()
-            .write_withdrawal_signer_decision(&signer_decision)
+        db.write_withdrawal_signer_decision(&signer_decision)
             .await?;
 
         self.context
@@ -521,6 +688,25 @@ where
         Ok(())
     }
 
+    /// Check whether there is room to buffer another signer decision for a
+    /// request that we do not have a record of.
+    async fn can_buffer_signer_decision(&self) -> Result<bool, Error> {
+        let buffered = self
+            .context
+            .get_storage()
+            .get_pending_signer_decisions_count()
+            .await?;
+
+        if buffered >= MAX_PENDING_SIGNER_DECISIONS {
+            tracing::warn!(
+                buffered,
+                "signer decision buffer is full; dropping the decision"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn send_message(
         &mut self,
@@ -528,9 +714,12 @@ where
         chain_tip: &BitcoinBlockHash,
     ) -> Result<(), Error> {
         let payload: Payload = msg.into();
-        let msg = payload
-            .to_message(*chain_tip)
-            .sign_ecdsa(&self.signer_private_key);
+        let msg = network::sign_with_next_sequence(
+            &self.context.get_storage_mut(),
+            payload.to_message(*chain_tip),
+            &self.signer_private_key,
+        )
+        .await?;
 
         self.network.broadcast(msg).await?;
 
@@ -542,15 +731,39 @@ where
     }
 }
 
+/// Returns the reason why the given deposit request can never be
+/// accepted, regardless of the signer set or the sBTC limits, if there is
+/// one. The reason is machine readable and is reported to Emily.
+fn permanent_deposit_decline_reason(request: &model::DepositRequest) -> Option<&'static str> {
+    // We only sweep deposits whose lock-time is in blocks, and we do not
+    // sweep deposits that the depositor can reclaim within the next
+    // DEPOSIT_LOCKTIME_BLOCK_BUFFER blocks. So if the lock-time is not
+    // larger than that buffer then we can never sweep the deposit.
+    match LockTime::from_consensus(request.lock_time) {
+        Ok(LockTime::Blocks(height)) if height.value() > DEPOSIT_LOCKTIME_BLOCK_BUFFER => None,
+        _ => Some("unsupported_lock_time"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use fake::Fake as _;
+    use test_case::test_case;
+
     use crate::bitcoin::MockBitcoinInteract;
+    use crate::blocklist_client::MockBlocklistChecker;
+    use crate::config::BlocklistClientConfig;
     use crate::emily_client::MockEmilyInteract;
+    use crate::network::InMemoryNetwork;
     use crate::stacks::api::MockStacksInteract;
+    use crate::storage::DbRead as _;
+    use crate::storage::DbWrite as _;
     use crate::storage::in_memory::SharedStore;
     use crate::testing;
     use crate::testing::context::*;
 
+    use super::*;
+
     fn test_environment() -> testing::request_decider::TestEnvironment<
         TestContext<
             SharedStore,
@@ -566,6 +779,7 @@ mod tests {
             num_withdraw_requests_per_block: 5,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
 
         let context = TestContext::builder()
@@ -605,4 +819,129 @@ mod tests {
             .assert_should_store_decisions_received_from_other_signers()
             .await;
     }
+
+    /// The blocklist client's verdict on a deposit request's sender
+    /// addresses is used as-is, and the failure policy only decides the
+    /// outcome when the blocklist client could not be reached.
+    #[test_case(Some(false), BlocklistFailurePolicy::FailOpen, false; "flagged, fail-open")]
+    #[test_case(Some(false), BlocklistFailurePolicy::FailClosed, false; "flagged, fail-closed")]
+    #[test_case(Some(true), BlocklistFailurePolicy::FailOpen, true; "clean, fail-open")]
+    #[test_case(Some(true), BlocklistFailurePolicy::FailClosed, true; "clean, fail-closed")]
+    #[test_case(None, BlocklistFailurePolicy::FailOpen, true; "service down, fail-open")]
+    #[test_case(None, BlocklistFailurePolicy::FailClosed, false; "service down, fail-closed")]
+    #[tokio::test]
+    async fn deposit_screening_follows_blocklist_failure_policy(
+        response: Option<bool>,
+        failure_policy: BlocklistFailurePolicy,
+        expected: bool,
+    ) {
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| {
+                settings.blocklist_client = Some(BlocklistClientConfig {
+                    endpoint: "http://127.0.0.1:8080".parse().unwrap(),
+                    retry_delay: Duration::ZERO,
+                    failure_policy,
+                });
+            })
+            .build();
+
+        let mut blocklist_checker = MockBlocklistChecker::new();
+        blocklist_checker.expect_can_accept().returning(move |_| {
+            let result = response.ok_or(Error::Dummy);
+            Box::pin(std::future::ready(result))
+        });
+
+        let network = InMemoryNetwork::new();
+        let request_decider = RequestDeciderEventLoop {
+            context,
+            network: network.connect(),
+            blocklist_checker: Some(blocklist_checker),
+            signer_private_key: PrivateKey::new(&mut rand::rngs::OsRng),
+            context_window: 6,
+            deposit_decisions_retry_window: 1,
+            withdrawal_decisions_retry_window: 1,
+        };
+
+        let mut request: model::DepositRequest = fake::Faker.fake_with_rng(&mut rand::rngs::OsRng);
+        request.lock_time = u32::from(DEPOSIT_LOCKTIME_BLOCK_BUFFER) + 10;
+        request.sender_script_pub_keys = fake::vec![model::ScriptPubKey; 2];
+
+        let can_accept = request_decider
+            .can_accept_deposit_request(&request)
+            .await
+            .unwrap();
+        assert_eq!(can_accept, expected);
+    }
+
+    /// A deposit decision received over the P2P network before we have a
+    /// record of the deposit request is buffered, and the vote shows up
+    /// once the block observer has written the request.
+    #[tokio::test]
+    async fn deposit_decisions_received_before_the_request_are_replayed() {
+        let mut context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        // Emily does not know about the deposit request either.
+        context
+            .with_emily_client(|client| {
+                client
+                    .expect_get_deposit()
+                    .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
+            })
+            .await;
+
+        let network = InMemoryNetwork::new();
+        let mut request_decider = RequestDeciderEventLoop {
+            context: context.clone(),
+            network: network.connect(),
+            blocklist_checker: Some(()),
+            signer_private_key: PrivateKey::new(&mut rand::rngs::OsRng),
+            context_window: 6,
+            deposit_decisions_retry_window: 1,
+            withdrawal_decisions_retry_window: 1,
+        };
+
+        let request: model::DepositRequest = fake::Faker.fake_with_rng(&mut rand::rngs::OsRng);
+        let decision = SignerDepositDecision {
+            txid: request.txid.into(),
+            output_index: request.output_index,
+            can_accept: true,
+            can_sign: true,
+        };
+        let sender = PublicKey::from_private_key(&PrivateKey::new(&mut rand::rngs::OsRng));
+
+        request_decider
+            .persist_received_deposit_decision(&decision, sender)
+            .await
+            .unwrap();
+
+        let db = context.get_storage_mut();
+        let votes = db
+            .get_deposit_signers(&request.txid, request.output_index)
+            .await
+            .unwrap();
+        assert!(votes.is_empty());
+        assert_eq!(db.get_pending_signer_decisions_count().await.unwrap(), 1);
+
+        // Now the block observer catches up and writes the request.
+        db.write_deposit_request(&request).await.unwrap();
+        let block_observer = BlockObserver { context, bitcoin_blocks: () };
+        block_observer
+            .replay_pending_signer_decisions()
+            .await
+            .unwrap();
+
+        let votes = db
+            .get_deposit_signers(&request.txid, request.output_index)
+            .await
+            .unwrap();
+        assert_eq!(votes.len(), 1);
+        assert_eq!(votes[0].signer_pub_key, sender);
+        assert!(votes[0].can_accept);
+        assert_eq!(db.get_pending_signer_decisions_count().await.unwrap(), 0);
+    }
 }
@@ -39,6 +39,8 @@ use url::Url;
 
 use crate::config::Settings;
 use crate::error::Error;
+use crate::error::ErrorContext;
+use crate::error::ResultExt as _;
 use crate::keys::PublicKey;
 use crate::storage::DbRead;
 use crate::storage::model::BitcoinBlockHash;
@@ -1481,26 +1483,18 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         &self,
         contract_principal: &StacksAddress,
     ) -> Result<Vec<PublicKey>, Error> {
-        self.exec(|client, retry| async move {
-            let result = client.get_current_signer_set(contract_principal).await;
-            retry.abort_if(|| matches!(result, Err(Error::InvalidStacksResponse(_))));
-            result
-        })
-        .await
+        self.exec(|client, _| client.get_current_signer_set(contract_principal))
+            .await
+            .with_context(|| ErrorContext::Operation("get_current_signer_set"))
     }
 
     async fn get_current_signers_aggregate_key(
         &self,
         contract_principal: &StacksAddress,
     ) -> Result<Option<PublicKey>, Error> {
-        self.exec(|client, retry| async move {
-            let result = client
-                .get_current_signers_aggregate_key(contract_principal)
-                .await;
-            retry.abort_if(|| matches!(result, Err(Error::InvalidStacksResponse(_))));
-            result
-        })
-        .await
+        self.exec(|client, _| client.get_current_signers_aggregate_key(contract_principal))
+            .await
+            .with_context(|| ErrorContext::Operation("get_current_signers_aggregate_key"))
     }
 
     async fn is_deposit_completed(
@@ -1508,14 +1502,9 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         contract_principal: &StacksAddress,
         outpoint: &OutPoint,
     ) -> Result<bool, Error> {
-        self.exec(|client, retry| async move {
-            let result = client
-                .is_deposit_completed(contract_principal, outpoint)
-                .await;
-            retry.abort_if(|| matches!(result, Err(Error::InvalidStacksResponse(_))));
-            result
-        })
-        .await
+        self.exec(|client, _| client.is_deposit_completed(contract_principal, outpoint))
+            .await
+            .with_context(|| ErrorContext::DepositRequest("is_deposit_completed", *outpoint))
     }
 
     async fn is_withdrawal_completed(
@@ -1523,14 +1512,9 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         contract_principal: &StacksAddress,
         request_id: u64,
     ) -> Result<bool, Error> {
-        self.exec(|client, retry| async move {
-            let result = client
-                .is_withdrawal_completed(contract_principal, request_id)
-                .await;
-            retry.abort_if(|| matches!(result, Err(Error::InvalidStacksResponse(_))));
-            result
-        })
-        .await
+        self.exec(|client, _| client.is_withdrawal_completed(contract_principal, request_id))
+            .await
+            .with_context(|| ErrorContext::WithdrawalRequest("is_withdrawal_completed", request_id))
     }
 
     async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
@@ -1538,15 +1522,21 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
     }
 
     async fn submit_tx(&self, tx: &StacksTransaction) -> Result<SubmitTxResponse, Error> {
-        self.exec(|client, _| client.submit_tx(tx)).await
+        self.exec(|client, _| client.submit_tx(tx))
+            .await
+            .with_context(|| ErrorContext::StacksTransaction("submit_tx", tx.txid()))
     }
 
     async fn get_block(&self, block_id: StacksBlockId) -> Result<NakamotoBlock, Error> {
-        self.exec(|client, _| client.get_block(block_id)).await
+        self.exec(|client, _| client.get_block(block_id))
+            .await
+            .with_context(|| ErrorContext::StacksBlock("get_block", block_id))
     }
 
     async fn get_tenure(&self, block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
-        self.exec(|client, _| client.get_tenure(block_id)).await
+        self.exec(|client, _| client.get_tenure(block_id))
+            .await
+            .with_context(|| ErrorContext::StacksBlock("get_tenure", block_id))
     }
 
     async fn get_tenure_info(&self) -> Result<RPCGetTenureInfo, Error> {
@@ -1917,11 +1907,13 @@ mod tests {
             )
             .await;
 
+        // The fallback client attaches the operation to the error.
         let err = resp.unwrap_err();
         assert!(matches!(
-            err,
-            Error::InvalidStacksResponse(s) if s == "expected a sequence but got something else"
+            err.without_context(),
+            Error::InvalidStacksResponse(s) if *s == "expected a sequence but got something else"
         ));
+        assert!(!err.is_retryable());
         mock.assert();
     }
 
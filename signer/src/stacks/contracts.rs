@@ -17,11 +17,11 @@
 //!   of most sBTC related functions to a new multi-sig wallet.
 
 use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::future::Future;
 use std::ops::Deref;
 use std::sync::OnceLock;
 
-use bitcoin::Amount;
 use bitcoin::OutPoint;
 use bitcoin::TxOut;
 use blockstack_lib::chainstate::stacks::TransactionContractCall;
@@ -46,6 +46,9 @@ use crate::DEPOSIT_DUST_LIMIT;
 use crate::WITHDRAWAL_BLOCKS_EXPIRY;
 use crate::WITHDRAWAL_MIN_CONFIRMATIONS;
 use crate::bitcoin::BitcoinInteract;
+use crate::bitcoin::rpc::BitcoinTxInfo;
+use crate::bitcoin::utxo;
+use crate::bitcoin::utxo::TxDeconstructor as _;
 use crate::bitcoin::validation::WithdrawalRequestStatus;
 use crate::context::Context;
 use crate::error::Error;
@@ -364,6 +367,9 @@ impl AsContractCall for CompleteDepositV1 {
     ///     UTXO. This checks that the sweep transaction was generated by
     ///     the signers.
     /// 10. That sBTC has not been minted for the deposit already.
+    /// 11. That the sBTC minted for all deposits swept in by the sweep
+    ///     transaction does not exceed the bitcoin that the signers kept
+    ///     from them, see [`utxo::deposit_mint_amount`].
     ///
     /// # Notes
     ///
@@ -389,10 +395,10 @@ impl AsContractCall for CompleteDepositV1 {
         }
 
         // Covers points 3-4 & 9
-        let fee = self.validate_sweep_tx(ctx, req_ctx).await?;
+        let sweep_tx = self.validate_sweep_tx(ctx, req_ctx).await?;
         let db = ctx.get_storage();
-        // Covers points 1-2 & 5-8
-        self.validate_vars(&db, req_ctx, fee).await
+        // Covers points 1-2, 5-8 & 11
+        self.validate_vars(&db, req_ctx, &sweep_tx).await
     }
 }
 
@@ -411,10 +417,18 @@ impl CompleteDepositV1 {
     /// 6. That the amount to mint is above the dust amount.
     /// 7. That the fee matches the expected assessed fee for the outpoint.
     /// 8. That the fee is less than the specified max-fee.
+    /// 11. That the sBTC minted for all deposits swept in by the sweep
+    ///     transaction does not exceed the bitcoin that the signers kept
+    ///     from them.
     ///
-    /// The `fee` input variable is our calculation of the assessed fee for
-    /// the deposit.
-    async fn validate_vars<S>(&self, db: &S, req_ctx: &ReqContext, fee: Amount) -> Result<(), Error>
+    /// The `sweep_tx` input variable is the validated sweep transaction
+    /// that swept in the deposit.
+    async fn validate_vars<S>(
+        &self,
+        db: &S,
+        req_ctx: &ReqContext,
+        sweep_tx: &BitcoinTxInfo,
+    ) -> Result<(), Error>
     where
         S: DbRead + Send + Sync,
     {
@@ -442,13 +456,41 @@ impl CompleteDepositV1 {
             return Err(DepositErrorMsg::AmountBelowDustLimit.into_error(req_ctx, self));
         }
         // 7. That the fee matches the expected assessed fee for the outpoint.
-        if fee.to_sat() + self.amount != deposit_request.amount {
+        // 11. That the sBTC minted for all deposits swept in by the sweep
+        //     transaction does not exceed the bitcoin that the signers
+        //     kept from them.
+        //
+        // We use the same fees as the coordinator does when it constructs
+        // the transaction, the ones recorded when the sweep transaction
+        // was confirmed.
+        let recorded_fees = recorded_deposit_fees(db, sweep_tx).await?;
+        let mint_amount = utxo::deposit_mint_amount(
+            sweep_tx,
+            &recorded_fees,
+            &self.outpoint,
+            deposit_request.amount,
+        );
+        let mint_amount = match mint_amount {
+            Ok(amount) => amount,
+            Err(Error::DepositMintExceedsSweep { .. }) => {
+                return Err(DepositErrorMsg::MintExceedsSweep.into_error(req_ctx, self));
+            }
+            Err(Error::DepositMintAmountMismatch { .. } | Error::DepositMintNotPositive { .. }) => {
+                return Err(DepositErrorMsg::IncorrectFee.into_error(req_ctx, self));
+            }
+            Err(Error::OutPointMissing(_)) => {
+                return Err(DepositErrorMsg::MissingFromSweep.into_error(req_ctx, self));
+            }
+            Err(error) => return Err(error),
+        };
+        if mint_amount != self.amount {
             return Err(DepositErrorMsg::IncorrectFee.into_error(req_ctx, self));
         }
         // 8. Check that the fee is less than the specified max-fee.
         //
         // The smart contract cannot check if we exceed the max fee.
-        if fee.to_sat() > deposit_request.max_fee {
+        let fee = deposit_request.amount - mint_amount;
+        if fee > deposit_request.max_fee {
             return Err(DepositErrorMsg::FeeTooHigh.into_error(req_ctx, self));
         }
 
@@ -465,7 +507,13 @@ impl CompleteDepositV1 {
     ///    outpoint as an input.
     /// 9. That the first input into the sweep transaction is the signers'
     ///    UTXO.
-    async fn validate_sweep_tx<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<Amount, Error>
+    ///
+    /// The sweep transaction is returned if it passes these checks.
+    async fn validate_sweep_tx<C>(
+        &self,
+        ctx: &C,
+        req_ctx: &ReqContext,
+    ) -> Result<BitcoinTxInfo, Error>
     where
         C: Context + Send + Sync,
     {
@@ -522,16 +570,37 @@ impl CompleteDepositV1 {
             return Err(DepositErrorMsg::InvalidSweep.into_error(req_ctx, self));
         }
 
-        // None is only returned from BitcoinTxInfo::assess_output_fee when:
-        // a) The indicated output index is 0 or 1, since or those cannot
-        //    be valid output indices for sweep transactions, or
-        // b) When the output index points to an output that is not in
-        //    the transaction.
-        // Both cases indicate that the UTXO is missing from the transaction.
-        sweep_tx
-            .assess_input_fee(&self.outpoint)
-            .ok_or_else(|| DepositErrorMsg::MissingFromSweep.into_error(req_ctx, self))
+        Ok(sweep_tx)
+    }
+}
+
+/// Return the fees that were recorded, when the given sweep transaction
+/// was confirmed, for the deposits that it swept in, keyed by the deposit
+/// outpoints. Deposits without a recorded fee are left out.
+pub async fn recorded_deposit_fees<S>(
+    db: &S,
+    sweep_tx: &BitcoinTxInfo,
+) -> Result<HashMap<OutPoint, u64>, Error>
+where
+    S: DbRead + Send + Sync,
+{
+    let sweep_txid = sweep_tx.txid.into();
+    let mut recorded_fees = HashMap::new();
+    // The first input is the signers' UTXO, the rest are deposits.
+    for index in 1..sweep_tx.vin.len() {
+        let Some(prevout) = sweep_tx.prevout(index) else {
+            continue;
+        };
+        let txid = (*prevout.txid).into();
+        let swept_deposit = db
+            .get_swept_deposit(&sweep_txid, &txid, prevout.output_index)
+            .await?;
+        if let Some(swept_deposit) = swept_deposit {
+            let outpoint = OutPoint::new(*prevout.txid, prevout.output_index);
+            recorded_fees.insert(outpoint, swept_deposit.assessed_fee);
+        }
     }
+    Ok(recorded_fees)
 }
 
 /// A struct for a validation error containing all the necessary context.
@@ -563,7 +632,8 @@ impl std::error::Error for DepositValidationError {
 
 /// The responses for validation of a complete-deposit smart contract call
 /// transactions.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum DepositErrorMsg {
     /// The smart contract has a dust limit which is used to rejects
     /// contract calls if the mint amount is below that limit. We check for
@@ -582,6 +652,10 @@ pub enum DepositErrorMsg {
     /// The supplied fee does not match what is expected.
     #[error("the supplied fee does not match what is expected")]
     IncorrectFee,
+    /// The sBTC minted for the deposits swept in by the sweep transaction
+    /// would exceed the bitcoin that the signers kept from them.
+    #[error("the sBTC minted for the sweep would exceed the bitcoin that it swept in")]
+    MintExceedsSweep,
     /// The deposit outpoint is missing from the indicated sweep
     /// transaction.
     #[error("deposit outpoint is missing from the indicated sweep transaction")]
@@ -953,7 +1027,8 @@ impl std::error::Error for WithdrawalRejectValidationError {
 
 /// The responses for validation of an accept-withdrawal-request smart
 /// contract call transaction.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum WithdrawalErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("the deployer in the transaction does not match the expected deployer")]
@@ -1015,7 +1090,8 @@ impl WithdrawalErrorMsg {
 
 /// The responses for validation of a reject-withdrawal-request smart
 /// contract call transaction.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum WithdrawalRejectErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("the deployer in the transaction does not match the expected deployer")]
@@ -1402,7 +1478,8 @@ impl std::error::Error for RotateKeysValidationError {
 
 /// The responses for validation of a rotate-keys smart contract call
 /// transactions.
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum RotateKeysErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("The deployer in the transaction does not match the expected deployer")]
@@ -88,22 +88,23 @@ impl SignerWallet {
     /// 4. The number of public keys exceeds the MAX_KEYS constant.
     /// 5. The combined public key would be the point at infinity.
     ///
-    /// Error condition (5) occurs when [`PublicKey::combine_keys`] errors.
-    /// There are two other conditions where that function errors, which
-    /// are:
+    /// Error condition (5) occurs when [`PublicKey::aggregate_ordered`]
+    /// errors. There are other conditions where that function errors,
+    /// which are:
     ///
     /// * The provided slice of public keys is empty.
+    /// * The provided slice contains duplicate public keys.
     /// * The number of elements in the provided slice is greater than
     ///   [`i32::MAX`].
     ///
-    /// But we enforce that the number of public keys is less than
-    /// [`MAX_KEYS`] and [`MAX_KEYS`] <= [`u16::MAX`] < [`i32::MAX`] and we
-    /// explicitly check for an empty slice already so these cases are
-    /// covered.
+    /// But the public keys are collected into a set, we enforce that the
+    /// number of public keys is less than [`MAX_KEYS`] and [`MAX_KEYS`]
+    /// <= [`u16::MAX`] < [`i32::MAX`], and we explicitly check for an
+    /// empty slice already so these cases are covered.
     ///
     /// # Notes
     ///
-    /// Now there is always a small risk that [`PublicKey::combine_keys`]
+    /// Now there is always a small risk that [`PublicKey::aggregate_ordered`]
     /// will return a `Result::Err`, even with perfectly fine inputs. This
     /// is highly unlikely by chance, but a Byzantine actor could trigger
     /// it purposefully if we don't require a signer to prove that they
@@ -134,6 +135,7 @@ impl SignerWallet {
         // Used for creating the combined stacks address
         let pubkeys: Vec<Secp256k1PublicKey> =
             public_keys.iter().map(Secp256k1PublicKey::from).collect();
+        let signer_set: Vec<PublicKey> = public_keys.iter().copied().collect();
 
         let num_sigs = signatures_required as usize;
         let hash_mode = Self::hash_mode().to_address_hash_mode();
@@ -150,7 +152,7 @@ impl SignerWallet {
         // the threshold is greater than the number of public keys. We
         // enforce the threshold invariant above in this function.
         Ok(Self {
-            aggregate_key: PublicKey::combine_keys(public_keys.iter())?,
+            aggregate_key: PublicKey::aggregate_ordered(&signer_set)?,
             public_keys,
             signatures_required,
             network_kind,
@@ -683,6 +685,7 @@ mod tests {
             num_withdraw_requests_per_block: 0,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
         let test_data = TestData::generate(&mut rng, &[], &test_params);
         test_data.write_to(&db).await;
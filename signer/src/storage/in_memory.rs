@@ -2,6 +2,8 @@
 
 use bitcoin::OutPoint;
 use blockstack_lib::types::chainstate::StacksBlockId;
+use libp2p::Multiaddr;
+use libp2p::PeerId;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
@@ -12,12 +14,14 @@ use tokio::sync::Mutex;
 
 use crate::DEPOSIT_LOCKTIME_BLOCK_BUFFER;
 use crate::bitcoin::utxo::SignerUtxo;
+use crate::bitcoin::validation::DepositConfirmationStatus;
 use crate::bitcoin::validation::DepositRequestReport;
 use crate::bitcoin::validation::WithdrawalRequestReport;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::keys::SignerScriptPubKey as _;
+use crate::storage::DbRead as _;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::CompletedDepositEvent;
@@ -39,6 +43,9 @@ pub struct Store {
     /// Bitcoin blocks
     pub bitcoin_blocks: HashMap<model::BitcoinBlockHash, model::BitcoinBlock>,
 
+    /// Bitcoin blocks that have been orphaned by a reorg
+    pub orphaned_bitcoin_blocks: HashSet<model::BitcoinBlockHash>,
+
     /// Stacks blocks
     pub stacks_blocks: HashMap<model::StacksBlockHash, model::StacksBlock>,
 
@@ -112,9 +119,52 @@ pub struct Store {
     /// Bitcoin signhashes
     pub bitcoin_sighashes: HashMap<model::SigHash, model::BitcoinTxSigHash>,
 
+    /// When each of the bitcoin sighashes was first written. The postgres
+    /// implementation keeps this in the `created_at` column.
+    pub bitcoin_sighashes_created_at: HashMap<model::SigHash, OffsetDateTime>,
+
     /// Bitcoin withdrawal outputs
     pub bitcoin_withdrawal_outputs:
         HashMap<(u64, model::StacksBlockHash), model::BitcoinWithdrawalOutput>,
+
+    /// The fees assessed to swept deposit requests, keyed by the sweep
+    /// transaction ID and the deposit request.
+    pub swept_deposits: HashMap<(model::BitcoinTxId, DepositRequestPk), model::SweptDeposit>,
+
+    /// Attempts at fulfilling requests with a sweep transaction, in the
+    /// order that they were recorded.
+    pub sweep_attempts: Vec<model::SweepAttempt>,
+
+    /// Failed attempts at processing `new_block` webhooks
+    pub webhook_attempts: HashMap<model::StacksBlockHash, model::WebhookAttempt>,
+
+    /// sbtc-registry events that could not be parsed, keyed by the stacks
+    /// block and the index of the event within it.
+    pub failed_events: BTreeMap<(model::StacksBlockHash, u64), model::FailedEvent>,
+
+    /// Bitcoin blocks that the stacks node told us it processed.
+    pub stacks_burn_blocks: BTreeSet<model::BitcoinBlockRef>,
+
+    /// Signature hashes that this signer emitted signature shares for, in
+    /// the order that they were recorded.
+    pub signed_sighashes: Vec<model::SignedSigHash>,
+
+    /// Signer decisions on deposit requests that we did not have a record
+    /// of when they were received, along with when they were received.
+    pub pending_deposit_signers: Vec<(OffsetDateTime, model::DepositSigner)>,
+
+    /// Signer decisions on withdrawal requests that we did not have a
+    /// record of when they were received, along with when they were
+    /// received.
+    pub pending_withdrawal_signers: Vec<(OffsetDateTime, model::WithdrawalSigner)>,
+
+    /// The sequence number of the most recently published P2P message for
+    /// each signer.
+    pub p2p_message_sequences: HashMap<PublicKey, u64>,
+
+    /// The addresses at which we connected to P2P peers, along with when
+    /// we last connected to them there.
+    pub p2p_peers: HashMap<(PeerId, Multiaddr), OffsetDateTime>,
 }
 
 impl Store {
@@ -274,43 +324,44 @@ impl Store {
         chain_tip: &model::BitcoinBlockHash,
         context_window: u16,
     ) -> Vec<model::WithdrawalRequest> {
-        let first_block = self.bitcoin_blocks.get(chain_tip);
-
-        let context_window_end_block = std::iter::successors(first_block, |block| {
-            Some(self.bitcoin_blocks.get(&block.parent_hash).unwrap_or(block))
-        })
-        .nth(context_window as usize);
-
-        let Some(context_window_end_block) = context_window_end_block else {
-            return Vec::new();
-        };
+        // The window is extended by one block, and the stacks chain tip
+        // is always included, mirroring the postgres implementation.
+        let bitcoin_blocks_in_window: HashSet<&model::BitcoinBlockHash> =
+            std::iter::successors(Some(chain_tip), |block_hash| {
+                self.bitcoin_blocks
+                    .get(block_hash)
+                    .map(|block| &block.parent_hash)
+            })
+            .take(context_window as usize + 1)
+            .filter(|block_hash| self.bitcoin_blocks.contains_key(*block_hash))
+            .collect();
 
         let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip) else {
             return Vec::new();
         };
 
-        std::iter::successors(Some(&stacks_chain_tip), |stacks_block| {
-            self.stacks_blocks.get(&stacks_block.parent_hash)
-        })
-        .take_while(|stacks_block| {
-            self.bitcoin_blocks
-                .get(&stacks_block.bitcoin_anchor)
-                .is_some_and(|anchor| anchor.block_height >= context_window_end_block.block_height)
-        })
-        .flat_map(|stacks_block| {
-            self.stacks_block_to_withdrawal_requests
-                .get(&stacks_block.block_hash)
-                .cloned()
-                .unwrap_or_default()
-                .into_iter()
-                .map(|pk| {
-                    self.withdrawal_requests
-                        .get(&pk)
-                        .expect("missing withdraw request")
-                        .clone()
-                })
-        })
-        .collect()
+        let stacks_blocks_in_window =
+            std::iter::successors(Some(&stacks_chain_tip), |stacks_block| {
+                self.stacks_blocks
+                    .get(&stacks_block.parent_hash)
+                    .filter(|parent| bitcoin_blocks_in_window.contains(&parent.bitcoin_anchor))
+            });
+
+        stacks_blocks_in_window
+            .flat_map(|stacks_block| {
+                self.stacks_block_to_withdrawal_requests
+                    .get(&stacks_block.block_hash)
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|pk| {
+                        self.withdrawal_requests
+                            .get(&pk)
+                            .expect("missing withdraw request")
+                            .clone()
+                    })
+            })
+            .collect()
     }
 }
 
@@ -332,24 +383,24 @@ impl super::DbRead for SharedStore {
     async fn get_bitcoin_canonical_chain_tip(
         &self,
     ) -> Result<Option<model::BitcoinBlockHash>, Error> {
-        Ok(self
-            .lock()
-            .await
+        let store = self.lock().await;
+        Ok(store
             .bitcoin_blocks
             .values()
-            .max_by_key(|block| (block.block_height, block.block_hash))
+            .filter(|block| !store.orphaned_bitcoin_blocks.contains(&block.block_hash))
+            .max_by_key(|block| (block.block_height, *block.block_hash.as_ref()))
             .map(|block| block.block_hash))
     }
 
     async fn get_bitcoin_canonical_chain_tip_ref(
         &self,
     ) -> Result<Option<model::BitcoinBlockRef>, Error> {
-        Ok(self
-            .lock()
-            .await
+        let store = self.lock().await;
+        Ok(store
             .bitcoin_blocks
             .values()
-            .max_by_key(|block| (block.block_height, block.block_hash))
+            .filter(|block| !store.orphaned_bitcoin_blocks.contains(&block.block_hash))
+            .max_by_key(|block| (block.block_height, *block.block_hash.as_ref()))
             .map(model::BitcoinBlockRef::from))
     }
 
@@ -450,15 +501,94 @@ impl super::DbRead for SharedStore {
 
     async fn get_deposit_request_report(
         &self,
-        _chain_tip: &model::BitcoinBlockHash,
-        _txid: &model::BitcoinTxId,
-        _output_index: u32,
-        _signer_public_key: &PublicKey,
+        chain_tip: &model::BitcoinBlockHash,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+        signer_public_key: &PublicKey,
     ) -> Result<Option<DepositRequestReport>, Error> {
-        // You can find an implementation in git commit
-        // 717381ebcae4f399c80b9fd8f4506836ff4974ec that handles most of
-        // the logic but doesn't handle swept deposits.
-        unimplemented!()
+        let store = self.lock().await;
+        let Some(deposit_request) = store.deposit_requests.get(&(*txid, output_index)) else {
+            return Ok(None);
+        };
+
+        let bitcoin_blocks = &store.bitcoin_blocks;
+        let first = bitcoin_blocks.get(chain_tip);
+        let canonical_blocks: Vec<&model::BitcoinBlock> =
+            std::iter::successors(first, |block| bitcoin_blocks.get(&block.parent_hash)).collect();
+
+        let confirmed_block = store
+            .bitcoin_transactions_to_blocks
+            .get(txid)
+            .into_iter()
+            .flatten()
+            .find_map(|block_hash| {
+                canonical_blocks
+                    .iter()
+                    .find(|block| &block.block_hash == block_hash)
+            });
+
+        let status = match confirmed_block {
+            Some(block) => {
+                // Look for a sweep transaction spending the deposit that
+                // has been confirmed on the canonical bitcoin blockchain
+                // since the deposit was confirmed.
+                let sweep_txid = canonical_blocks
+                    .iter()
+                    .take_while(|canonical| canonical.block_height >= block.block_height)
+                    .filter_map(|canonical| {
+                        store
+                            .bitcoin_block_to_transactions
+                            .get(&canonical.block_hash)
+                    })
+                    .flatten()
+                    .find(|sweep_txid| {
+                        store
+                            .bitcoin_prevouts
+                            .get(sweep_txid)
+                            .into_iter()
+                            .flatten()
+                            .any(|prevout| {
+                                &prevout.prevout_txid == txid
+                                    && prevout.prevout_output_index == output_index
+                            })
+                    });
+
+                match sweep_txid {
+                    Some(sweep_txid) => DepositConfirmationStatus::Spent(*sweep_txid),
+                    None => {
+                        DepositConfirmationStatus::Confirmed(block.block_height, block.block_hash)
+                    }
+                }
+            }
+            None => DepositConfirmationStatus::Unconfirmed,
+        };
+
+        let decision = store
+            .deposit_request_to_signers
+            .get(&(*txid, output_index))
+            .into_iter()
+            .flatten()
+            .find(|decision| &decision.signer_pub_key == signer_public_key);
+
+        let dkg_shares_status = store
+            .encrypted_dkg_shares
+            .get(&deposit_request.signers_public_key)
+            .map(|(_, shares)| shares.dkg_shares_status);
+
+        Ok(Some(DepositRequestReport {
+            status,
+            can_sign: decision.map(|decision| decision.can_sign),
+            can_accept: decision.map(|decision| decision.can_accept),
+            amount: deposit_request.amount,
+            max_fee: deposit_request.max_fee,
+            lock_time: bitcoin::relative::LockTime::from_consensus(deposit_request.lock_time)
+                .map_err(Error::DisabledLockTime)?,
+            outpoint: OutPoint::new((*txid).into(), output_index),
+            deposit_script: deposit_request.spend_script.clone().into(),
+            reclaim_script: deposit_request.reclaim_script.clone().into(),
+            signers_public_key: deposit_request.signers_public_key.into(),
+            dkg_shares_status,
+        }))
     }
 
     async fn get_deposit_signers(
@@ -506,6 +636,17 @@ impl super::DbRead for SharedStore {
         Ok(store.deposit_requests.contains_key(&(*txid, output_index)))
     }
 
+    async fn withdrawal_request_exists(
+        &self,
+        request_id: u64,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<bool, Error> {
+        let store = self.lock().await;
+        Ok(store
+            .withdrawal_requests
+            .contains_key(&(request_id, *block_hash)))
+    }
+
     async fn get_withdrawal_signers(
         &self,
         request_id: u64,
@@ -551,12 +692,72 @@ impl super::DbRead for SharedStore {
 
     async fn get_pending_accepted_withdrawal_requests(
         &self,
-        _bitcoin_chain_tip: &model::BitcoinBlockHash,
-        _stacks_chain_tip: &model::StacksBlockHash,
-        _min_bitcoin_height: BitcoinBlockHeight,
-        _threshold: u16,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        stacks_chain_tip: &model::StacksBlockHash,
+        min_bitcoin_height: BitcoinBlockHeight,
+        threshold: u16,
     ) -> Result<Vec<model::WithdrawalRequest>, Error> {
-        unimplemented!();
+        let store = self.lock().await;
+
+        // The canonical bitcoin blockchain from the chain tip back to the
+        // block just below the minimum height.
+        let bitcoin_blockchain: HashSet<model::BitcoinBlockHash> =
+            std::iter::successors(store.bitcoin_blocks.get(bitcoin_chain_tip), |block| {
+                store.bitcoin_blocks.get(&block.parent_hash)
+            })
+            .take_while(|block| block.block_height >= min_bitcoin_height.saturating_sub(1u64))
+            .map(|block| block.block_hash)
+            .collect();
+
+        // The canonical stacks blockchain from the chain tip back to the
+        // first block that is not anchored to the bitcoin blockchain above.
+        let stacks_blockchain: HashSet<model::StacksBlockHash> =
+            std::iter::successors(store.stacks_blocks.get(stacks_chain_tip), |block| {
+                store
+                    .stacks_blocks
+                    .get(&block.parent_hash)
+                    .filter(|parent| bitcoin_blockchain.contains(&parent.bitcoin_anchor))
+            })
+            .map(|block| block.block_hash)
+            .collect();
+
+        let is_swept = |request: &model::WithdrawalRequest| {
+            store
+                .bitcoin_withdrawal_outputs
+                .get(&(request.request_id, request.block_hash))
+                .and_then(|output| {
+                    store
+                        .bitcoin_transactions_to_blocks
+                        .get(&output.bitcoin_txid)
+                })
+                .is_some_and(|blocks| blocks.iter().any(|b| bitcoin_blockchain.contains(b)))
+        };
+        let is_rejected = |request: &model::WithdrawalRequest| {
+            store
+                .withdrawal_reject_events
+                .get(&request.request_id)
+                .is_some_and(|event| stacks_blockchain.contains(&event.block_id))
+        };
+        let accept_votes = |request: &model::WithdrawalRequest| {
+            store
+                .withdrawal_request_to_signers
+                .get(&(request.request_id, request.block_hash))
+                .map(|votes| votes.iter().filter(|vote| vote.is_accepted).count())
+                .unwrap_or_default()
+        };
+
+        let mut requests: Vec<model::WithdrawalRequest> = store
+            .withdrawal_requests
+            .values()
+            .filter(|req| req.bitcoin_block_height >= min_bitcoin_height)
+            .filter(|req| stacks_blockchain.contains(&req.block_hash))
+            .filter(|&req| accept_votes(req) >= threshold as usize)
+            .filter(|&req| !is_swept(req) && !is_rejected(req))
+            .cloned()
+            .collect();
+
+        requests.sort_by_key(|req| req.request_id);
+        Ok(requests)
     }
 
     async fn get_pending_rejected_withdrawal_requests(
@@ -613,6 +814,17 @@ impl super::DbRead for SharedStore {
             .map(|(_, shares)| shares.clone()))
     }
 
+    async fn get_all_encrypted_dkg_shares(&self) -> Result<Vec<model::EncryptedDkgShares>, Error> {
+        let store = self.lock().await;
+        let mut all_shares: Vec<_> = store.encrypted_dkg_shares.values().collect();
+        all_shares.sort_by_key(|(time, _)| *time);
+
+        Ok(all_shares
+            .into_iter()
+            .map(|(_, shares)| shares.clone())
+            .collect())
+    }
+
     async fn get_latest_encrypted_dkg_shares(
         &self,
     ) -> Result<Option<model::EncryptedDkgShares>, Error> {
@@ -674,6 +886,40 @@ impl super::DbRead for SharedStore {
         )
     }
 
+    async fn get_signer_set_aggregate_keys(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<PublicKey>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(Vec::new());
+        };
+
+        let store = self.lock().await;
+
+        let mut aggregate_keys = Vec::new();
+        let rotations = std::iter::successors(Some(&stacks_chain_tip), |stacks_block| {
+            store.stacks_blocks.get(&stacks_block.parent_hash)
+        })
+        .flat_map(|block| {
+            store
+                .rotate_keys_transactions
+                .values()
+                .filter(move |rotation| rotation.block_hash == block.block_hash)
+        });
+
+        for rotation in rotations {
+            if aggregate_keys.len() >= limit as usize {
+                break;
+            }
+            if !aggregate_keys.contains(&rotation.aggregate_key) {
+                aggregate_keys.push(rotation.aggregate_key);
+            }
+        }
+
+        Ok(aggregate_keys)
+    }
+
     async fn key_rotation_exists(
         &self,
         _chain_tip: &model::BitcoinBlockHash,
@@ -945,6 +1191,20 @@ impl super::DbRead for SharedStore {
             .cloned())
     }
 
+    async fn get_swept_deposit(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<model::SweptDeposit>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .swept_deposits
+            .get(&(*sweep_txid, (*txid, output_index)))
+            .cloned())
+    }
+
     async fn will_sign_bitcoin_tx_sighash(
         &self,
         sighash: &model::SigHash,
@@ -957,6 +1217,149 @@ impl super::DbRead for SharedStore {
             .map(|s| (s.will_sign, s.aggregate_key)))
     }
 
+    async fn get_in_flight_signer_prevout_txids(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        prevout: &bitcoin::OutPoint,
+        max_age: std::time::Duration,
+    ) -> Result<Vec<model::BitcoinTxId>, Error> {
+        let store = self.lock().await;
+        let cutoff = OffsetDateTime::now_utc() - max_age;
+        let txids = store
+            .bitcoin_sighashes
+            .values()
+            .filter(|s| &s.chain_tip == chain_tip)
+            .filter(|s| {
+                store
+                    .bitcoin_sighashes_created_at
+                    .get(&s.sighash)
+                    .is_some_and(|created_at| *created_at >= cutoff)
+            })
+            .filter(|s| s.prevout_txid == prevout.txid.into())
+            .filter(|s| s.prevout_output_index == prevout.vout)
+            .filter(|s| s.prevout_type == model::TxPrevoutType::SignersInput)
+            .filter(|s| s.will_sign)
+            .map(|s| s.txid)
+            .collect::<HashSet<_>>();
+
+        Ok(txids.into_iter().collect())
+    }
+
+    async fn get_poisoned_webhooks(&self) -> Result<Vec<model::WebhookAttempt>, Error> {
+        let mut webhooks = self
+            .lock()
+            .await
+            .webhook_attempts
+            .values()
+            .filter(|attempt| attempt.is_poisoned)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        webhooks.sort_by_key(|attempt| attempt.index_block_hash);
+        Ok(webhooks)
+    }
+
+    async fn get_failed_events(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Vec<model::FailedEvent>, Error> {
+        let events = self
+            .lock()
+            .await
+            .failed_events
+            .range((*block_hash, 0)..=(*block_hash, u64::MAX))
+            .map(|(_, event)| event.clone())
+            .collect();
+
+        Ok(events)
+    }
+
+    async fn get_stacks_burn_chain_tip(&self) -> Result<Option<model::BitcoinBlockRef>, Error> {
+        Ok(self.lock().await.stacks_burn_blocks.last().copied())
+    }
+
+    async fn get_signed_sighashes(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::SignedSigHash>, Error> {
+        let mut sighashes: Vec<model::SignedSigHash> = self
+            .lock()
+            .await
+            .signed_sighashes
+            .iter()
+            .filter(|signed| &signed.txid == txid)
+            .cloned()
+            .collect();
+
+        sighashes.sort_by_key(|signed| signed.input_index);
+        Ok(sighashes)
+    }
+
+    async fn get_recent_signed_sighashes(
+        &self,
+        limit: u16,
+    ) -> Result<Vec<model::SignedSigHash>, Error> {
+        let sighashes = self
+            .lock()
+            .await
+            .signed_sighashes
+            .iter()
+            .rev()
+            .take(limit as usize)
+            .cloned()
+            .collect();
+
+        Ok(sighashes)
+    }
+
+    async fn get_request_attempt_history(
+        &self,
+        request: &model::SweepRequestId,
+    ) -> Result<Vec<model::SweepAttempt>, Error> {
+        let store = self.lock().await;
+        let attempts = store
+            .sweep_attempts
+            .iter()
+            .filter(|attempt| &attempt.request == request)
+            .cloned()
+            .collect();
+
+        Ok(attempts)
+    }
+
+    async fn get_sweep_attempt_counts(&self) -> Result<model::SweepAttemptCounts, Error> {
+        let store = self.lock().await;
+        let mut counts = model::SweepAttemptCounts::default();
+        for attempt in store.sweep_attempts.iter() {
+            counts.add(attempt.outcome, 1);
+        }
+
+        Ok(counts)
+    }
+
+    async fn get_pending_signer_decisions_count(&self) -> Result<u64, Error> {
+        let store = self.lock().await;
+        let count = store.pending_deposit_signers.len() + store.pending_withdrawal_signers.len();
+        Ok(count as u64)
+    }
+
+    async fn get_recent_p2p_peers(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<Vec<(PeerId, Multiaddr)>, Error> {
+        let store = self.lock().await;
+        let cutoff = OffsetDateTime::now_utc() - max_age;
+
+        let mut peers = store
+            .p2p_peers
+            .iter()
+            .filter(|(_, last_seen)| **last_seen >= cutoff)
+            .collect::<Vec<_>>();
+        peers.sort_by_key(|(_, last_seen)| std::cmp::Reverse(**last_seen));
+
+        Ok(peers.into_iter().map(|(peer, _)| peer.clone()).collect())
+    }
+
     // The postgres implementation uses a timestamp to figure out when a
     // decision was inserted into the database. The in memory database
     // does not have such a timestamp, so we use the Stacks block's
@@ -1068,6 +1471,23 @@ impl super::DbWrite for SharedStore {
         Ok(())
     }
 
+    async fn set_bitcoin_blocks_orphaned(
+        &self,
+        block_hashes: &[model::BitcoinBlockHash],
+        is_orphaned: bool,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        for block_hash in block_hashes {
+            if is_orphaned {
+                store.orphaned_bitcoin_blocks.insert(*block_hash);
+            } else {
+                store.orphaned_bitcoin_blocks.remove(block_hash);
+            }
+        }
+
+        Ok(())
+    }
+
     async fn write_bitcoin_transactions(&self, txs: Vec<model::Transaction>) -> Result<(), Error> {
         for tx in txs {
             self.write_transaction(&tx).await?;
@@ -1125,11 +1545,13 @@ impl super::DbWrite for SharedStore {
 
         let pk = (withdraw_request.request_id, withdraw_request.block_hash);
 
-        store
+        let requests = store
             .stacks_block_to_withdrawal_requests
             .entry(pk.1)
-            .or_default()
-            .push(pk);
+            .or_default();
+        if !requests.contains(&pk) {
+            requests.push(pk);
+        }
 
         store
             .withdrawal_requests
@@ -1190,11 +1612,14 @@ impl super::DbWrite for SharedStore {
     ) -> Result<(), Error> {
         let mut store = self.lock().await;
 
-        store
+        let txids = store
             .bitcoin_block_to_transactions
             .entry(bitcoin_transaction.block_hash)
-            .or_default()
-            .push(bitcoin_transaction.txid);
+            .or_default();
+        if txids.contains(&bitcoin_transaction.txid) {
+            return Ok(());
+        }
+        txids.push(bitcoin_transaction.txid);
 
         store
             .bitcoin_transactions_to_blocks
@@ -1319,6 +1744,29 @@ impl super::DbWrite for SharedStore {
         Ok(())
     }
 
+    async fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error> {
+        for event in events.completed_deposits.iter() {
+            self.write_completed_deposit_event(event).await?;
+        }
+        for request in events.withdrawal_requests.iter() {
+            self.write_withdrawal_request(request).await?;
+        }
+        for event in events.withdrawal_accepts.iter() {
+            self.write_withdrawal_accept_event(event).await?;
+        }
+        for event in events.withdrawal_rejects.iter() {
+            self.write_withdrawal_reject_event(event).await?;
+        }
+        for event in events.key_rotations.iter() {
+            self.write_rotate_keys_transaction(event).await?;
+        }
+
+        Ok(())
+    }
+
     async fn write_tx_output(&self, output: &model::TxOutput) -> Result<(), Error> {
         self.lock()
             .await
@@ -1348,6 +1796,25 @@ impl super::DbWrite for SharedStore {
         Ok(())
     }
 
+    async fn write_swept_deposit(&self, swept_deposit: &model::SweptDeposit) -> Result<(), Error> {
+        let key = (
+            swept_deposit.sweep_txid,
+            (swept_deposit.txid, swept_deposit.output_index),
+        );
+        self.lock()
+            .await
+            .swept_deposits
+            .entry(key)
+            .or_insert_with(|| swept_deposit.clone());
+
+        Ok(())
+    }
+
+    async fn write_sweep_attempt(&self, attempt: &model::SweepAttempt) -> Result<(), Error> {
+        self.lock().await.sweep_attempts.push(attempt.clone());
+        Ok(())
+    }
+
     async fn write_bitcoin_withdrawals_outputs(
         &self,
         withdrawal_outputs: &[model::BitcoinWithdrawalOutput],
@@ -1367,14 +1834,392 @@ impl super::DbWrite for SharedStore {
         sighashes: &[model::BitcoinTxSigHash],
     ) -> Result<(), Error> {
         let mut store = self.lock().await;
+        let now = OffsetDateTime::now_utc();
         sighashes.iter().for_each(|sighash| {
             store
                 .bitcoin_sighashes
                 .insert(sighash.sighash, sighash.clone());
+            store
+                .bitcoin_sighashes_created_at
+                .entry(sighash.sighash)
+                .or_insert(now);
         });
         Ok(())
     }
 
+    async fn prune_below_height(&self, bitcoin_height: BitcoinBlockHeight) -> Result<(), Error> {
+        let Some(chain_tip) = self.get_bitcoin_canonical_chain_tip().await? else {
+            return Ok(());
+        };
+        let stacks_chain_tip = self.get_stacks_chain_tip(&chain_tip).await?;
+        let mut store = self.lock().await;
+
+        let canonical_stacks_blocks: HashSet<model::StacksBlockHash> = std::iter::successors(
+            stacks_chain_tip.and_then(|tip| store.stacks_blocks.get(&tip.block_hash)),
+            |block| store.stacks_blocks.get(&block.parent_hash),
+        )
+        .map(|block| block.block_hash)
+        .collect();
+
+        let canonical_blocks: HashSet<model::BitcoinBlockHash> =
+            std::iter::successors(store.bitcoin_blocks.get(&chain_tip), |block| {
+                store.bitcoin_blocks.get(&block.parent_hash)
+            })
+            .map(|block| block.block_hash)
+            .collect();
+
+        let confirmed_below = |store: &Store, txid: &model::BitcoinTxId| {
+            store
+                .bitcoin_transactions_to_blocks
+                .get(txid)
+                .into_iter()
+                .flatten()
+                .filter(|block_hash| canonical_blocks.contains(block_hash))
+                .filter_map(|block_hash| store.bitcoin_blocks.get(block_hash))
+                .any(|block| block.block_height < bitcoin_height)
+        };
+        let anchored_below = |store: &Store, block_hash: &model::StacksBlockHash| {
+            store
+                .stacks_blocks
+                .get(block_hash)
+                .and_then(|block| store.bitcoin_blocks.get(&block.bitcoin_anchor))
+                .is_some_and(|block| block.block_height < bitcoin_height)
+        };
+
+        // Deposit requests that have been swept and completed on the
+        // canonical bitcoin and stacks blockchains.
+        let swept_deposits: Vec<DepositRequestPk> = store
+            .deposit_requests
+            .keys()
+            .filter(|(txid, output_index)| {
+                let outpoint = OutPoint::new((*txid).into(), *output_index);
+                let completed = store
+                    .completed_deposit_events
+                    .get(&outpoint)
+                    .filter(|event| canonical_stacks_blocks.contains(&event.block_id))
+                    .is_some_and(|event| event.sweep_block_height < bitcoin_height);
+                let swept = store.bitcoin_prevouts.iter().any(|(sweep_txid, prevouts)| {
+                    prevouts.iter().any(|prevout| {
+                        prevout.prevout_txid == *txid
+                            && prevout.prevout_output_index == *output_index
+                    }) && confirmed_below(&store, sweep_txid)
+                });
+                completed && swept
+            })
+            .copied()
+            .collect();
+
+        for pk in swept_deposits {
+            store.deposit_requests.remove(&pk);
+            store.deposit_request_to_signers.remove(&pk);
+            for requests in store.signer_to_deposit_request.values_mut() {
+                requests.retain(|req| req != &pk);
+            }
+            let outpoint = OutPoint::new(pk.0.into(), pk.1);
+            store.completed_deposit_events.remove(&outpoint);
+        }
+
+        // Withdrawal requests that have been accepted or rejected on the
+        // canonical stacks blockchain.
+        let settled_request_ids: HashSet<u64> = store
+            .withdrawal_accept_events
+            .values()
+            .map(|event| (event.request_id, event.block_id))
+            .chain(
+                store
+                    .withdrawal_reject_events
+                    .values()
+                    .map(|event| (event.request_id, event.block_id)),
+            )
+            .filter(|(_, block_id)| canonical_stacks_blocks.contains(block_id))
+            .filter(|(_, block_id)| anchored_below(&store, block_id))
+            .map(|(request_id, _)| request_id)
+            .collect();
+
+        let settled_withdrawals: Vec<WithdrawalRequestPk> = store
+            .withdrawal_requests
+            .values()
+            .filter(|req| req.bitcoin_block_height < bitcoin_height)
+            .filter(|req| settled_request_ids.contains(&req.request_id))
+            .map(|req| (req.request_id, req.block_hash))
+            .collect();
+
+        for pk in settled_withdrawals {
+            store.withdrawal_requests.remove(&pk);
+            store.withdrawal_request_to_signers.remove(&pk);
+            if let Some(requests) = store.stacks_block_to_withdrawal_requests.get_mut(&pk.1) {
+                requests.retain(|req| req != &pk);
+            }
+            let still_known = store
+                .withdrawal_requests
+                .keys()
+                .any(|(request_id, _)| *request_id == pk.0);
+            if !still_known {
+                store.withdrawal_accept_events.remove(&pk.0);
+                store.withdrawal_reject_events.remove(&pk.0);
+            }
+        }
+
+        // Bitcoin blocks from old forks, and the stacks blocks anchored
+        // to them.
+        let pruned_blocks: Vec<model::BitcoinBlockHash> = store
+            .bitcoin_blocks
+            .values()
+            .filter(|block| block.block_height < bitcoin_height)
+            .filter(|block| !canonical_blocks.contains(&block.block_hash))
+            .map(|block| block.block_hash)
+            .collect();
+
+        for block_hash in pruned_blocks {
+            store.bitcoin_blocks.remove(&block_hash);
+            for txid in store
+                .bitcoin_block_to_transactions
+                .remove(&block_hash)
+                .unwrap_or_default()
+            {
+                if let Some(blocks) = store.bitcoin_transactions_to_blocks.get_mut(&txid) {
+                    blocks.retain(|hash| hash != &block_hash);
+                }
+            }
+            for stacks_block_hash in store
+                .bitcoin_anchor_to_stacks_blocks
+                .remove(&block_hash)
+                .unwrap_or_default()
+            {
+                store.stacks_blocks.remove(&stacks_block_hash);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_failed_webhook_attempt(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+        payload: &str,
+    ) -> Result<u32, Error> {
+        let mut store = self.lock().await;
+        let attempt = store
+            .webhook_attempts
+            .entry(*index_block_hash)
+            .or_insert_with(|| model::WebhookAttempt {
+                index_block_hash: *index_block_hash,
+                payload: String::new(),
+                failed_attempts: 0,
+                is_poisoned: false,
+            });
+
+        attempt.payload = payload.to_string();
+        attempt.failed_attempts += 1;
+        Ok(attempt.failed_attempts)
+    }
+
+    async fn mark_webhook_poisoned(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        if let Some(attempt) = self.lock().await.webhook_attempts.get_mut(index_block_hash) {
+            attempt.is_poisoned = true;
+        }
+        Ok(())
+    }
+
+    async fn clear_webhook_attempts(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        self.lock().await.webhook_attempts.remove(index_block_hash);
+        Ok(())
+    }
+
+    async fn write_failed_event(&self, event: &model::FailedEvent) -> Result<(), Error> {
+        self.lock()
+            .await
+            .failed_events
+            .entry((event.block_hash, event.event_index))
+            .or_insert_with(|| event.clone());
+        Ok(())
+    }
+
+    async fn write_stacks_burn_block(&self, block: &model::BitcoinBlockRef) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        let is_duplicate = store
+            .stacks_burn_blocks
+            .iter()
+            .any(|known| known.block_hash == block.block_hash);
+        if !is_duplicate {
+            store.stacks_burn_blocks.insert(*block);
+        }
+        Ok(())
+    }
+
+    async fn write_signed_sighash(&self, sighash: &model::SignedSigHash) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        let is_duplicate = store
+            .signed_sighashes
+            .iter()
+            .any(|signed| signed.sighash == sighash.sighash);
+        if !is_duplicate {
+            store.signed_sighashes.push(sighash.clone());
+        }
+        Ok(())
+    }
+
+    async fn write_pending_deposit_signer_decision(
+        &self,
+        decision: &model::DepositSigner,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        let is_duplicate = store.pending_deposit_signers.iter().any(|(_, pending)| {
+            pending.txid == decision.txid
+                && pending.output_index == decision.output_index
+                && pending.signer_pub_key == decision.signer_pub_key
+        });
+        if !is_duplicate {
+            store
+                .pending_deposit_signers
+                .push((OffsetDateTime::now_utc(), decision.clone()));
+        }
+
+        Ok(())
+    }
+
+    async fn write_pending_withdrawal_signer_decision(
+        &self,
+        decision: &model::WithdrawalSigner,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        let is_duplicate = store.pending_withdrawal_signers.iter().any(|(_, pending)| {
+            pending.request_id == decision.request_id
+                && pending.block_hash == decision.block_hash
+                && pending.signer_pub_key == decision.signer_pub_key
+        });
+        if !is_duplicate {
+            store
+                .pending_withdrawal_signers
+                .push((OffsetDateTime::now_utc(), decision.clone()));
+        }
+
+        Ok(())
+    }
+
+    async fn replay_pending_signer_decisions(&self) -> Result<u64, Error> {
+        let mut store = self.lock().await;
+        let mut replayed = 0;
+
+        let (ready, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut store.pending_deposit_signers)
+            .into_iter()
+            .partition(|(_, decision)| {
+                let pk = (decision.txid, decision.output_index);
+                store.deposit_requests.contains_key(&pk)
+            });
+        store.pending_deposit_signers = pending;
+
+        for (_, decision) in ready {
+            let pk = (decision.txid, decision.output_index);
+            let signers = store.deposit_request_to_signers.entry(pk).or_default();
+            if signers
+                .iter()
+                .any(|signer| signer.signer_pub_key == decision.signer_pub_key)
+            {
+                continue;
+            }
+            signers.push(decision.clone());
+            store
+                .signer_to_deposit_request
+                .entry(decision.signer_pub_key)
+                .or_default()
+                .push(pk);
+            replayed += 1;
+        }
+
+        let (ready, pending): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut store.pending_withdrawal_signers)
+                .into_iter()
+                .partition(|(_, decision)| {
+                    let pk = (decision.request_id, decision.block_hash);
+                    store.withdrawal_requests.contains_key(&pk)
+                });
+        store.pending_withdrawal_signers = pending;
+
+        for (_, decision) in ready {
+            let pk = (decision.request_id, decision.block_hash);
+            let signers = store.withdrawal_request_to_signers.entry(pk).or_default();
+            if signers
+                .iter()
+                .any(|signer| signer.signer_pub_key == decision.signer_pub_key)
+            {
+                continue;
+            }
+            signers.push(decision);
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+
+    async fn delete_expired_pending_signer_decisions(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<u64, Error> {
+        let mut store = self.lock().await;
+        let cutoff = OffsetDateTime::now_utc() - max_age;
+        let count = store.pending_deposit_signers.len() + store.pending_withdrawal_signers.len();
+
+        store
+            .pending_deposit_signers
+            .retain(|(received_at, _)| *received_at >= cutoff);
+        store
+            .pending_withdrawal_signers
+            .retain(|(received_at, _)| *received_at >= cutoff);
+
+        let remaining =
+            store.pending_deposit_signers.len() + store.pending_withdrawal_signers.len();
+        Ok((count - remaining) as u64)
+    }
+
+    async fn next_p2p_message_sequence(&self, public_key: &PublicKey) -> Result<u64, Error> {
+        let mut store = self.lock().await;
+        let sequence = store.p2p_message_sequences.entry(*public_key).or_default();
+        *sequence += 1;
+        Ok(*sequence)
+    }
+
+    async fn upsert_p2p_peer(&self, peer_id: &PeerId, address: &Multiaddr) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store
+            .p2p_peers
+            .insert((*peer_id, address.clone()), OffsetDateTime::now_utc());
+        Ok(())
+    }
+
+    async fn prune_p2p_peers(&self, max_age: std::time::Duration) -> Result<u64, Error> {
+        let mut store = self.lock().await;
+        let cutoff = OffsetDateTime::now_utc() - max_age;
+        let count = store.p2p_peers.len();
+
+        store.p2p_peers.retain(|_, last_seen| *last_seen >= cutoff);
+
+        Ok((count - store.p2p_peers.len()) as u64)
+    }
+
+    async fn update_encrypted_private_shares(
+        &self,
+        shares: &[(PublicKey, Vec<u8>)],
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        for (aggregate_key, encrypted_private_shares) in shares {
+            let key = PublicKeyXOnly::from(aggregate_key);
+            if let Some((_, stored)) = store.encrypted_dkg_shares.get_mut(&key) {
+                if stored.aggregate_key == *aggregate_key {
+                    stored.encrypted_private_shares = encrypted_private_shares.clone();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     async fn revoke_dkg_shares<X>(&self, aggregate_key: X) -> Result<bool, Error>
     where
         X: Into<PublicKeyXOnly> + Send,
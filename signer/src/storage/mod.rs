@@ -17,6 +17,8 @@ use std::collections::BTreeSet;
 use std::future::Future;
 
 use blockstack_lib::types::chainstate::StacksBlockId;
+use libp2p::Multiaddr;
+use libp2p::PeerId;
 
 use crate::bitcoin::utxo::SignerUtxo;
 use crate::bitcoin::validation::DepositRequestReport;
@@ -93,6 +95,14 @@ pub trait DbRead {
         output_index: u32,
     ) -> impl Future<Output = Result<bool, Error>> + Send;
 
+    /// Check whether we have a record of the withdrawal request in our
+    /// database.
+    fn withdrawal_request_exists(
+        &self,
+        request_id: u64,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
     /// This function returns a deposit request report that does the
     /// following:
     ///
@@ -293,6 +303,15 @@ pub trait DbRead {
         chain_tip: &model::BitcoinBlockHash,
     ) -> impl Future<Output = Result<Option<model::KeyRotationEvent>, Error>> + Send;
 
+    /// Return the distinct aggregate keys of the rotate-keys transactions
+    /// confirmed by the given `chain_tip`, most recently rotated first,
+    /// returning at most `limit` keys.
+    fn get_signer_set_aggregate_keys(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> impl Future<Output = Result<Vec<PublicKey>, Error>> + Send;
+
     /// Checks if a key rotation exists on the canonical chain
     fn key_rotation_exists(
         &self,
@@ -423,11 +442,101 @@ pub trait DbRead {
         output_index: u32,
     ) -> impl Future<Output = Result<Option<model::DepositRequest>, Error>> + Send;
 
+    /// Get the fee that was assessed to the deposit request with the given
+    /// transaction id and output index by the given sweep transaction.
+    fn get_swept_deposit(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<Option<model::SweptDeposit>, Error>> + Send;
+
+    /// Get the recorded attempts at fulfilling the given request with a
+    /// sweep transaction, oldest first.
+    fn get_request_attempt_history(
+        &self,
+        request: &model::SweepRequestId,
+    ) -> impl Future<Output = Result<Vec<model::SweepAttempt>, Error>> + Send;
+
+    /// Get the number of recorded sweep attempts with each outcome,
+    /// across all requests.
+    fn get_sweep_attempt_counts(
+        &self,
+    ) -> impl Future<Output = Result<model::SweepAttemptCounts, Error>> + Send;
+
     /// Get the bitcoin sighash output.
     fn will_sign_bitcoin_tx_sighash(
         &self,
         sighash: &model::SigHash,
     ) -> impl Future<Output = Result<Option<(bool, PublicKeyXOnly)>, Error>> + Send;
+
+    /// Get the IDs of the transactions that spend the given signers'
+    /// outpoint and that this signer has agreed to sign, within the last
+    /// `max_age`, while the given block was the bitcoin chain tip.
+    ///
+    /// These are the sweep transactions that are "in-flight" for the
+    /// signers' UTXO. Once a new bitcoin block arrives, the chain tip
+    /// changes and the outpoint is no longer considered in-flight. The
+    /// same happens once `max_age` has passed, so that a signing round
+    /// that failed does not hold on to the outpoint until the next block.
+    fn get_in_flight_signer_prevout_txids(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        prevout: &bitcoin::OutPoint,
+        max_age: std::time::Duration,
+    ) -> impl Future<Output = Result<Vec<model::BitcoinTxId>, Error>> + Send;
+
+    /// Get the `new_block` webhooks that we gave up on processing after
+    /// too many failed attempts, so that they can be reprocessed.
+    fn get_poisoned_webhooks(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::WebhookAttempt>, Error>> + Send;
+
+    /// Get the sbtc-registry events in the given stacks block that could
+    /// not be parsed, ordered by their index in the block.
+    fn get_failed_events(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<Vec<model::FailedEvent>, Error>> + Send;
+
+    /// Get the highest bitcoin block that the stacks node has told us it
+    /// processed, through the `POST /new_burn_block` webhook.
+    fn get_stacks_burn_chain_tip(
+        &self,
+    ) -> impl Future<Output = Result<Option<model::BitcoinBlockRef>, Error>> + Send;
+
+    /// Get the signature hashes of the inputs in the given bitcoin
+    /// transaction that this signer emitted signature shares for, ordered
+    /// by their input index.
+    fn get_signed_sighashes(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<Vec<model::SignedSigHash>, Error>> + Send;
+
+    /// Get the most recent `limit` signature hashes that this signer
+    /// emitted signature shares for, most recent first.
+    fn get_recent_signed_sighashes(
+        &self,
+        limit: u16,
+    ) -> impl Future<Output = Result<Vec<model::SignedSigHash>, Error>> + Send;
+
+    /// Get the number of signer decisions that are buffered until we have
+    /// a record of their associated deposit or withdrawal request.
+    fn get_pending_signer_decisions_count(&self)
+    -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Get the peers, along with the addresses at which we connected to
+    /// them, that we have connected to within the last `max_age`. The
+    /// most recently seen addresses come first.
+    fn get_recent_p2p_peers(
+        &self,
+        max_age: std::time::Duration,
+    ) -> impl Future<Output = Result<Vec<(PeerId, Multiaddr)>, Error>> + Send;
+
+    /// Return all stored DKG shares, regardless of their status.
+    fn get_all_encrypted_dkg_shares(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::EncryptedDkgShares>, Error>> + Send;
 }
 
 /// Represents the ability to write data to the signer storage.
@@ -438,6 +547,15 @@ pub trait DbWrite {
         block: &model::BitcoinBlock,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Set whether the given bitcoin blocks have been orphaned by a
+    /// bitcoin reorg. Orphaned blocks are never returned as the bitcoin
+    /// canonical chain tip.
+    fn set_bitcoin_blocks_orphaned(
+        &self,
+        block_hashes: &[model::BitcoinBlockHash],
+        is_orphaned: bool,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Write a stacks block.
     fn write_stacks_block(
         &self,
@@ -540,6 +658,16 @@ pub trait DbWrite {
         event: &CompletedDepositEvent,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Write all of the sbtc-registry events emitted in a stacks block.
+    ///
+    /// Either all of the events are written or none of them are. Events
+    /// that have already been written are skipped, so writing the same
+    /// events more than once is harmless.
+    fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Write the bitcoin transaction output to the database.
     fn write_tx_output(
         &self,
@@ -558,6 +686,19 @@ pub trait DbWrite {
         prevout: &model::TxPrevout,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Write the fee that was assessed to a deposit request by the
+    /// confirmed sweep transaction that swept it in.
+    fn write_swept_deposit(
+        &self,
+        swept_deposit: &model::SweptDeposit,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record an attempt at fulfilling a request with a sweep transaction.
+    fn write_sweep_attempt(
+        &self,
+        attempt: &model::SweepAttempt,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Write the bitcoin transactions sighashes to the database.
     fn write_bitcoin_txs_sighashes(
         &self,
@@ -570,6 +711,128 @@ pub trait DbWrite {
         withdrawals_outputs: &[model::BitcoinWithdrawalOutput],
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Delete settled data that is older than the given bitcoin block
+    /// height.
+    ///
+    /// This removes deposit requests that have been swept and completed,
+    /// withdrawal requests that have been accepted or rejected, along
+    /// with the events and signer decisions associated with them, where
+    /// everything happened in blocks below the given height on the
+    /// canonical bitcoin and stacks blockchains. Bitcoin
+    /// blocks below the given height that are not on the canonical
+    /// bitcoin blockchain, and the stacks blocks anchored to them, are
+    /// removed as well. Requests that have not been swept are never
+    /// removed, regardless of their age.
+    fn prune_below_height(
+        &self,
+        bitcoin_height: model::BitcoinBlockHeight,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a failed attempt at processing the `new_block` webhook for
+    /// the given stacks block, returning the number of failed attempts
+    /// so far.
+    fn record_failed_webhook_attempt(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+        payload: &str,
+    ) -> impl Future<Output = Result<u32, Error>> + Send;
+
+    /// Mark the `new_block` webhook for the given stacks block as
+    /// poisoned, meaning that we gave up on processing it.
+    fn mark_webhook_poisoned(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Remove any record of failed attempts at processing the `new_block`
+    /// webhook for the given stacks block. This is done once the webhook
+    /// has been processed successfully.
+    fn clear_webhook_attempts(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record an sbtc-registry event that could not be parsed. Recording
+    /// the same event again is a no-op.
+    fn write_failed_event(
+        &self,
+        event: &model::FailedEvent,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record a bitcoin block that the stacks node has told us it
+    /// processed. Recording the same block again is a no-op.
+    fn write_stacks_burn_block(
+        &self,
+        block: &model::BitcoinBlockRef,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that this signer is emitting a signature share for the
+    /// given signature hash. Recording the same signature hash again is a
+    /// no-op.
+    fn write_signed_sighash(
+        &self,
+        sighash: &model::SignedSigHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Buffer a signer decision for a deposit request that we do not have
+    /// a record of yet.
+    fn write_pending_deposit_signer_decision(
+        &self,
+        decision: &model::DepositSigner,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Buffer a signer decision for a withdrawal request that we do not
+    /// have a record of yet.
+    fn write_pending_withdrawal_signer_decision(
+        &self,
+        decision: &model::WithdrawalSigner,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Move the buffered signer decisions whose associated deposit or
+    /// withdrawal request we now have a record of into the deposit and
+    /// withdrawal signers tables. Returns the number of decisions that
+    /// were replayed.
+    fn replay_pending_signer_decisions(&self) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Delete the buffered signer decisions that were received more than
+    /// `max_age` ago. Returns the number of decisions that were deleted.
+    fn delete_expired_pending_signer_decisions(
+        &self,
+        max_age: std::time::Duration,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Increment the sequence number for messages published over the P2P
+    /// network by the signer with the given public key, and return the
+    /// new sequence number. The first sequence number is 1.
+    fn next_p2p_message_sequence(
+        &self,
+        public_key: &PublicKey,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Record that we have just connected to the given peer at the given
+    /// address.
+    fn upsert_p2p_peer(
+        &self,
+        peer_id: &PeerId,
+        address: &Multiaddr,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the peer addresses that we have not connected to within the
+    /// last `max_age`. Returns the number of addresses that were deleted.
+    fn prune_p2p_peers(
+        &self,
+        max_age: std::time::Duration,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Replace the encrypted private shares of the DKG shares for each of
+    /// the given aggregate keys. All of the shares are updated in a single
+    /// transaction, so if any of the updates fail then none of them are
+    /// applied.
+    fn update_encrypted_private_shares(
+        &self,
+        shares: &[(PublicKey, Vec<u8>)],
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
     /// Marks the stored DKG shares for the provided aggregate key as revoked
     /// and thus should no longer be used.
     ///
@@ -196,8 +196,10 @@ pub struct DepositRequest {
     pub max_fee: u64,
     /// The relative lock time in the reclaim script.
     #[sqlx(try_from = "i64")]
-    #[cfg_attr(feature = "testing", dummy(faker = "3..u16::MAX as u32"))]
+    #[cfg_attr(feature = "testing", dummy(faker = "4..u16::MAX as u32"))]
     pub lock_time: u32,
+    /// The shape of the reclaim script.
+    pub reclaim_script_kind: ReclaimScriptKind,
     /// The public key used in the deposit script. The signers public key
     /// is for Schnorr signatures.
     pub signers_public_key: PublicKeyXOnly,
@@ -227,6 +229,7 @@ impl From<Deposit> for DepositRequest {
             amount: deposit.info.amount,
             max_fee: deposit.info.max_fee,
             lock_time: deposit.info.lock_time.to_consensus_u32(),
+            reclaim_script_kind: deposit.info.reclaim_script_kind.into(),
             signers_public_key: deposit.info.signers_public_key.into(),
             sender_script_pub_keys: sender_script_pub_keys.into_iter().collect(),
         }
@@ -436,6 +439,137 @@ impl SweptDepositRequest {
     }
 }
 
+/// The bitcoin miner fee assessed to a deposit request by the confirmed
+/// sweep transaction that swept it in.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SweptDeposit {
+    /// The transaction ID of the bitcoin transaction that swept in the
+    /// funds into the signers' UTXO.
+    pub sweep_txid: BitcoinTxId,
+    /// Transaction ID of the deposit request transaction.
+    pub txid: BitcoinTxId,
+    /// Index of the deposit request UTXO.
+    #[cfg_attr(feature = "testing", dummy(faker = "0..100"))]
+    #[sqlx(try_from = "i32")]
+    pub output_index: u32,
+    /// The portion of the fee of the sweep transaction, in sats, that is
+    /// assessed to the deposit.
+    #[sqlx(try_from = "i64")]
+    #[cfg_attr(feature = "testing", dummy(faker = "100..100_000"))]
+    pub assessed_fee: u64,
+}
+
+/// The identifier of a request that can be fulfilled by a sweep
+/// transaction.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum SweepRequestId {
+    /// A deposit request, identified by its outpoint.
+    Deposit {
+        /// Transaction ID of the deposit request transaction.
+        txid: BitcoinTxId,
+        /// Index of the deposit request UTXO.
+        #[cfg_attr(feature = "testing", dummy(faker = "0..100"))]
+        output_index: u32,
+    },
+    /// A withdrawal request, identified by its request ID and the stacks
+    /// block that includes it.
+    Withdrawal {
+        /// The ID that was generated in the clarity contract call for the
+        /// withdrawal request.
+        #[cfg_attr(feature = "testing", dummy(faker = "0..i64::MAX as u64"))]
+        request_id: u64,
+        /// The stacks block ID that includes the transaction that
+        /// generated the request.
+        block_hash: StacksBlockHash,
+    },
+}
+
+impl std::fmt::Display for SweepRequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SweepRequestId::Deposit { txid, output_index } => write!(f, "{txid}:{output_index}"),
+            SweepRequestId::Withdrawal { request_id, block_hash } => {
+                write!(f, "{request_id}:{block_hash}")
+            }
+        }
+    }
+}
+
+impl From<&QualifiedRequestId> for SweepRequestId {
+    fn from(id: &QualifiedRequestId) -> Self {
+        SweepRequestId::Withdrawal {
+            request_id: id.request_id,
+            block_hash: id.block_hash,
+        }
+    }
+}
+
+impl From<&bitcoin::OutPoint> for SweepRequestId {
+    fn from(outpoint: &bitcoin::OutPoint) -> Self {
+        SweepRequestId::Deposit {
+            txid: outpoint.txid.into(),
+            output_index: outpoint.vout,
+        }
+    }
+}
+
+/// How far a sweep transaction proposed by the coordinator got.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::Display)]
+#[sqlx(type_name = "sweep_attempt_outcome", rename_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+#[strum(serialize_all = "snake_case")]
+pub enum SweepAttemptOutcome {
+    /// Enough signers acknowledged the pre-sign request for the sweep.
+    Proposed,
+    /// The signing rounds for all inputs of the sweep completed.
+    Signed,
+    /// The bitcoin node accepted the signed sweep.
+    Broadcast,
+    /// The sweep was not proposed, signed or broadcast.
+    Rejected,
+}
+
+/// An attempt at fulfilling a request with a sweep transaction.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SweepAttempt {
+    /// The request that the sweep transaction would fulfill.
+    pub request: SweepRequestId,
+    /// The ID of the proposed sweep transaction.
+    pub sweep_txid: BitcoinTxId,
+    /// How far the sweep transaction got.
+    pub outcome: SweepAttemptOutcome,
+    /// A description of why the attempt was rejected, if it was.
+    pub error_detail: Option<String>,
+}
+
+/// The number of recorded sweep attempts with each outcome.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SweepAttemptCounts {
+    /// The number of attempts that were proposed.
+    pub proposed: u64,
+    /// The number of attempts that were signed.
+    pub signed: u64,
+    /// The number of attempts that were broadcast.
+    pub broadcast: u64,
+    /// The number of attempts that were rejected.
+    pub rejected: u64,
+}
+
+impl SweepAttemptCounts {
+    /// Add an attempt with the given outcome to the counts.
+    pub fn add(&mut self, outcome: SweepAttemptOutcome, count: u64) {
+        match outcome {
+            SweepAttemptOutcome::Proposed => self.proposed += count,
+            SweepAttemptOutcome::Signed => self.signed += count,
+            SweepAttemptOutcome::Broadcast => self.broadcast += count,
+            SweepAttemptOutcome::Rejected => self.rejected += count,
+        }
+    }
+}
+
 /// Withdrawal request.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
@@ -579,6 +713,31 @@ impl Deref for SignerVotes {
     }
 }
 
+impl SignerVotes {
+    /// Convert these votes into a signer bitmap for a signer set with
+    /// `num_signers` signers.
+    ///
+    /// The votes are expected to be keyed to the given signer set. Rather
+    /// than treating the bits of missing votes as votes in favor, which is
+    /// what happens when signer set sizes change across a rotate-keys
+    /// event, this function returns an error if the number of votes does
+    /// not match the number of signers. It also returns an error if there
+    /// are more signers than can be represented in a signer bitmap.
+    pub fn to_signer_bitmap(&self, num_signers: usize) -> Result<BitArray<[u8; 16]>, Error> {
+        if num_signers > crate::MAX_KEYS as usize {
+            return Err(Error::SignerSetTooLarge(num_signers));
+        }
+        if self.len() != num_signers {
+            return Err(Error::SignerVotesLengthMismatch {
+                num_votes: self.len(),
+                num_signers,
+            });
+        }
+
+        Ok(BitArray::from(self))
+    }
+}
+
 impl From<Vec<SignerVote>> for SignerVotes {
     fn from(mut votes: Vec<SignerVote>) -> Self {
         votes.sort_by_key(|vote| vote.signer_public_key);
@@ -628,6 +787,29 @@ pub enum DkgSharesStatus {
     Failed,
 }
 
+/// The shape of the reclaim script of a deposit request. See
+/// [`sbtc::deposits::ReclaimScriptKind`] for the scripts of each kind.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::Display)]
+#[sqlx(type_name = "reclaim_script_kind", rename_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+#[strum(serialize_all = "snake_case")]
+pub enum ReclaimScriptKind {
+    /// `<locked-time> OP_CSV` followed by a script that we do not
+    /// recognize, or by nothing.
+    Csv,
+    /// `<locked-time> OP_CSV` followed by a single key check.
+    CsvP2pk,
+}
+
+impl From<sbtc::deposits::ReclaimScriptKind> for ReclaimScriptKind {
+    fn from(kind: sbtc::deposits::ReclaimScriptKind) -> Self {
+        match kind {
+            sbtc::deposits::ReclaimScriptKind::Csv => Self::Csv,
+            sbtc::deposits::ReclaimScriptKind::CsvP2pk(_) => Self::CsvP2pk,
+        }
+    }
+}
+
 /// The types of transactions the signer is interested in.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::Display)]
 #[sqlx(type_name = "transaction_type", rename_all = "snake_case")]
@@ -1315,6 +1497,94 @@ pub struct WithdrawalRejectEvent {
     pub signer_bitmap: BitArray<[u8; 16]>,
 }
 
+/// All of the sbtc-registry events that were emitted in a single stacks
+/// block.
+#[derive(Debug, Clone, Default)]
+pub struct StacksBlockEvents {
+    /// Events emitted from the `complete-deposit` public function.
+    pub completed_deposits: Vec<CompletedDepositEvent>,
+    /// Events emitted from the `create-withdrawal-request` public
+    /// function.
+    pub withdrawal_requests: Vec<WithdrawalRequest>,
+    /// Events emitted from the `complete-withdrawal-accept` public
+    /// function.
+    pub withdrawal_accepts: Vec<WithdrawalAcceptEvent>,
+    /// Events emitted from the `complete-withdrawal-reject` public
+    /// function.
+    pub withdrawal_rejects: Vec<WithdrawalRejectEvent>,
+    /// Events emitted from the `rotate-keys` public function.
+    pub key_rotations: Vec<KeyRotationEvent>,
+}
+
+impl StacksBlockEvents {
+    /// Whether there are any events at all.
+    pub fn is_empty(&self) -> bool {
+        self.completed_deposits.is_empty()
+            && self.withdrawal_requests.is_empty()
+            && self.withdrawal_accepts.is_empty()
+            && self.withdrawal_rejects.is_empty()
+            && self.key_rotations.is_empty()
+    }
+}
+
+/// A record of the failed attempts at processing the `new_block` webhook
+/// for a stacks block.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct WebhookAttempt {
+    /// The index block hash of the stacks block in the webhook.
+    pub index_block_hash: StacksBlockHash,
+    /// The body of the webhook.
+    pub payload: String,
+    /// The number of times that processing the webhook has failed.
+    #[sqlx(try_from = "i32")]
+    pub failed_attempts: u32,
+    /// Whether we gave up on processing the webhook.
+    pub is_poisoned: bool,
+}
+
+/// A print event from the sbtc-registry smart contract that could not be
+/// parsed into one of the expected event types.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct FailedEvent {
+    /// The index block hash of the stacks block with the event.
+    pub block_hash: StacksBlockHash,
+    /// The index of the event within the stacks block.
+    #[sqlx(try_from = "i64")]
+    pub event_index: u64,
+    /// The ID of the stacks transaction that emitted the event.
+    pub txid: StacksTxId,
+    /// The topic of the event, or `unknown` if it could not be read.
+    pub event_topic: String,
+    /// The path to the malformed field, if the problem is with a field.
+    pub field: Option<String>,
+    /// Why the event could not be parsed.
+    pub reason: String,
+    /// The clarity value of the event, as JSON.
+    pub payload: String,
+}
+
+/// A record of a bitcoin input signature hash that this signer emitted a
+/// signature share for.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SignedSigHash {
+    /// The signature hash that was signed.
+    pub sighash: SigHash,
+    /// The ID of the bitcoin transaction with the input.
+    pub txid: BitcoinTxId,
+    /// The index of the input in the bitcoin transaction.
+    #[sqlx(try_from = "i32")]
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i32::MAX as u32"))]
+    pub input_index: u32,
+    /// The bitcoin chain tip when the signature share was emitted.
+    pub chain_tip: BitcoinBlockHash,
+    /// The aggregate key that is locking the output spent by the input.
+    pub aggregate_key: PublicKeyXOnly,
+    /// The public key of the coordinator that requested the signature
+    /// share.
+    pub origin_public_key: PublicKey,
+}
+
 impl From<u8> for BitcoinBlockHeight {
     fn from(value: u8) -> Self {
         Self(value as u64)
@@ -1584,4 +1854,161 @@ mod tests {
 
         assert_eq!(block_hash, round_trip);
     }
+
+    fn signer_votes<R: rand::Rng>(rng: &mut R, num_votes: usize) -> SignerVotes {
+        let votes: Vec<SignerVote> = (0..num_votes)
+            .map(|index| SignerVote {
+                signer_public_key: fake::Faker.fake_with_rng(rng),
+                is_accepted: Some(index % 2 == 0),
+            })
+            .collect();
+        SignerVotes::from(votes)
+    }
+
+    #[test]
+    fn signer_bitmap_requires_votes_for_the_whole_signer_set() {
+        let mut rng = get_rng();
+
+        // Votes recorded against a 5 signer set must not be used with a 7
+        // signer set after a rotate-keys event, since the two missing
+        // bits would be counted as votes in favor.
+        let old_votes = signer_votes(&mut rng, 5);
+        match old_votes.to_signer_bitmap(7) {
+            Err(Error::SignerVotesLengthMismatch { num_votes: 5, num_signers: 7 }) => {}
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        let new_votes = signer_votes(&mut rng, 7);
+        let bitmap = new_votes.to_signer_bitmap(7).unwrap();
+        assert_eq!(bitmap, BitArray::<[u8; 16]>::from(&new_votes));
+        assert_eq!(bitmap.count_ones(), 3);
+
+        // Shrinking signer sets are rejected too.
+        assert!(new_votes.to_signer_bitmap(5).is_err());
+    }
+
+    #[test]
+    fn signer_bitmap_rejects_signer_sets_larger_than_max_keys() {
+        let mut rng = get_rng();
+
+        let num_signers = crate::MAX_KEYS as usize + 1;
+        let votes = signer_votes(&mut rng, num_signers);
+        match votes.to_signer_bitmap(num_signers) {
+            Err(Error::SignerSetTooLarge(n)) => assert_eq!(n, num_signers),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        let num_signers = crate::MAX_KEYS as usize;
+        let votes = signer_votes(&mut rng, num_signers);
+        let bitmap = votes.to_signer_bitmap(num_signers).unwrap();
+        assert_eq!(bitmap.count_ones(), num_signers / 2);
+    }
+
+    /// Deposit requests record the scriptPubKeys of the outputs that the
+    /// deposit transaction spends, which bitcoin-core returns for every
+    /// kind of input, so the blocklist screening has an address to check
+    /// for legacy, nested segwit, native segwit, and taproot inputs.
+    #[test]
+    fn deposit_senders_come_from_prevout_script_pub_keys() {
+        use bitcoin::Address;
+        use bitcoin::AddressType;
+        use bitcoin::Amount;
+        use bitcoin::CompressedPublicKey;
+        use bitcoin::hashes::Hash as _;
+        use bitcoincore_rpc_json::GetRawTransactionResultVin;
+        use sbtc::deposits::CreateDepositRequest;
+
+        use crate::bitcoin::rpc::BitcoinTxInfo;
+        use crate::bitcoin::rpc::BitcoinTxVin;
+        use crate::bitcoin::rpc::BitcoinTxVinPrevout;
+        use crate::bitcoin::rpc::PrevoutScriptPubKey;
+
+        let network = bitcoin::Network::Regtest;
+        let secret_key = secp256k1::SecretKey::new(&mut rand::rngs::OsRng);
+        let public_key = CompressedPublicKey(secret_key.public_key(secp256k1::SECP256K1));
+        let x_only_key = public_key.0.x_only_public_key().0;
+
+        let legacy = Address::p2pkh(public_key, network);
+        let nested_segwit = Address::p2shwpkh(&public_key, network);
+        let native_segwit = Address::p2wpkh(&public_key, network);
+        let taproot = Address::p2tr(secp256k1::SECP256K1, x_only_key, None, network);
+
+        let setup = sbtc::testing::deposits::tx_setup(15_000, 500_000, &[150_000]);
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            reclaim_script: setup.reclaims[0].reclaim_script(),
+            deposit_script: setup.deposits[0].deposit_script(),
+        };
+        let info = request.validate_tx(&setup.tx, false).unwrap();
+
+        // The native segwit address funds two of the inputs.
+        let prevout_scripts = [
+            &legacy,
+            &nested_segwit,
+            &native_segwit,
+            &taproot,
+            &native_segwit,
+        ]
+        .map(|address| address.script_pubkey());
+        let vin = prevout_scripts
+            .iter()
+            .map(|script| BitcoinTxVin {
+                details: GetRawTransactionResultVin {
+                    sequence: 0,
+                    coinbase: None,
+                    txid: Some(bitcoin::Txid::all_zeros()),
+                    vout: Some(0),
+                    script_sig: None,
+                    txinwitness: None,
+                },
+                prevout: BitcoinTxVinPrevout {
+                    generated: false,
+                    height: 1u64.into(),
+                    value: Amount::from_sat(100_000),
+                    script_pub_key: PrevoutScriptPubKey { script: script.clone() },
+                },
+            })
+            .collect();
+
+        let tx_info = BitcoinTxInfo {
+            in_active_chain: true,
+            fee: Amount::from_sat(1_000),
+            txid: setup.tx.compute_txid(),
+            hash: setup.tx.compute_wtxid(),
+            size: setup.tx.total_size() as u64,
+            vsize: setup.tx.vsize() as u64,
+            tx: setup.tx,
+            vin,
+            vout: Vec::new(),
+            block_hash: bitcoin::BlockHash::all_zeros(),
+            confirmations: 1,
+            block_time: 0,
+        };
+
+        let deposit_request = DepositRequest::from(Deposit { tx_info, info });
+
+        // Each sender is recorded once, in a form that converts into an
+        // address of the expected type on the configured network.
+        assert_eq!(deposit_request.sender_script_pub_keys.len(), 4);
+        let mut address_types: Vec<AddressType> = deposit_request
+            .sender_script_pub_keys
+            .iter()
+            .map(|script| {
+                Address::from_script(script, network.params())
+                    .unwrap()
+                    .address_type()
+                    .unwrap()
+            })
+            .collect();
+        address_types.sort_by_key(|address_type| address_type.to_string());
+
+        let mut expected = vec![
+            AddressType::P2pkh,
+            AddressType::P2sh,
+            AddressType::P2wpkh,
+            AddressType::P2tr,
+        ];
+        expected.sort_by_key(|address_type| address_type.to_string());
+        assert_eq!(address_types, expected);
+    }
 }
@@ -9,6 +9,8 @@ use bitcoin::hashes::Hash as _;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::chainstate::stacks::TransactionPayload;
 use blockstack_lib::types::chainstate::StacksBlockId;
+use libp2p::Multiaddr;
+use libp2p::PeerId;
 use sqlx::Executor as _;
 use sqlx::PgExecutor;
 use sqlx::postgres::PgPoolOptions;
@@ -20,8 +22,11 @@ use crate::bitcoin::validation::DepositRequestReport;
 use crate::bitcoin::validation::WithdrawalRequestReport;
 use crate::bitcoin::validation::WithdrawalRequestStatus;
 use crate::error::Error;
+use crate::error::ErrorContext;
+use crate::error::ResultExt as _;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
+use crate::storage::DbRead as _;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHeight;
 use crate::storage::model::CompletedDepositEvent;
@@ -181,6 +186,38 @@ impl From<PgSignerUtxo> for SignerUtxo {
     }
 }
 
+// A convenience struct for binding a request in the sweep_attempts table,
+// where exactly one of the deposit or withdrawal columns are set.
+struct PgSweepRequestId<'a> {
+    txid: Option<&'a model::BitcoinTxId>,
+    output_index: Option<i32>,
+    request_id: Option<i64>,
+    block_hash: Option<&'a model::StacksBlockHash>,
+}
+
+impl<'a> TryFrom<&'a model::SweepRequestId> for PgSweepRequestId<'a> {
+    type Error = Error;
+
+    fn try_from(request: &'a model::SweepRequestId) -> Result<Self, Self::Error> {
+        Ok(match request {
+            model::SweepRequestId::Deposit { txid, output_index } => PgSweepRequestId {
+                txid: Some(txid),
+                output_index: Some(
+                    i32::try_from(*output_index).map_err(Error::ConversionDatabaseInt)?,
+                ),
+                request_id: None,
+                block_hash: None,
+            },
+            model::SweepRequestId::Withdrawal { request_id, block_hash } => PgSweepRequestId {
+                txid: None,
+                output_index: None,
+                request_id: Some(i64::try_from(*request_id).map_err(Error::ConversionDatabaseInt)?),
+                block_hash: Some(block_hash),
+            },
+        })
+    }
+}
+
 /// A wrapper around a [`sqlx::PgPool`] which implements
 /// [`crate::storage::DbRead`] and [`crate::storage::DbWrite`].
 #[derive(Debug, Clone)]
@@ -336,6 +373,172 @@ impl PgStore {
         &self.0
     }
 
+    /// Insert the withdrawal request using the given executor.
+    async fn insert_withdrawal_request(
+        executor: impl PgExecutor<'_>,
+        request: &model::WithdrawalRequest,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO sbtc_signer.withdrawal_requests
+              ( request_id
+              , txid
+              , block_hash
+              , recipient
+              , amount
+              , max_fee
+              , sender_address
+              , bitcoin_block_height
+              )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(i64::try_from(request.request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(request.txid)
+        .bind(request.block_hash)
+        .bind(&request.recipient)
+        .bind(i64::try_from(request.amount).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(request.max_fee).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&request.sender_address)
+        .bind(i64::try_from(request.bitcoin_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    /// Insert the rotate-keys event using the given executor.
+    async fn insert_rotate_keys_transaction(
+        executor: impl PgExecutor<'_>,
+        key_rotation: &model::KeyRotationEvent,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.rotate_keys_transactions (
+                  txid
+                , block_hash
+                , address
+                , aggregate_key
+                , signer_set
+                , signatures_required)
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(key_rotation.txid)
+        .bind(key_rotation.block_hash)
+        .bind(&key_rotation.address)
+        .bind(key_rotation.aggregate_key)
+        .bind(&key_rotation.signer_set)
+        .bind(i32::from(key_rotation.signatures_required))
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    /// Insert the completed-deposit event using the given executor.
+    async fn insert_completed_deposit_event(
+        executor: impl PgExecutor<'_>,
+        event: &CompletedDepositEvent,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "
+        INSERT INTO sbtc_signer.completed_deposit_events (
+            txid
+          , block_hash
+          , amount
+          , bitcoin_txid
+          , output_index
+          , sweep_block_hash
+          , sweep_block_height
+          , sweep_txid
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT DO NOTHING",
+        )
+        .bind(event.txid)
+        .bind(event.block_id)
+        .bind(i64::try_from(event.amount).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.outpoint.txid.to_byte_array())
+        .bind(i64::from(event.outpoint.vout))
+        .bind(event.sweep_block_hash.to_byte_array())
+        .bind(i64::try_from(event.sweep_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.sweep_txid.to_byte_array())
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    /// Insert the withdrawal-accept event using the given executor.
+    async fn insert_withdrawal_accept_event(
+        executor: impl PgExecutor<'_>,
+        event: &WithdrawalAcceptEvent,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "
+        INSERT INTO sbtc_signer.withdrawal_accept_events (
+            txid
+          , block_hash
+          , request_id
+          , signer_bitmap
+          , bitcoin_txid
+          , output_index
+          , fee
+          , sweep_block_hash
+          , sweep_block_height
+          , sweep_txid
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT DO NOTHING",
+        )
+        .bind(event.txid)
+        .bind(event.block_id)
+        .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.signer_bitmap.into_inner())
+        .bind(event.outpoint.txid.to_byte_array())
+        .bind(i64::from(event.outpoint.vout))
+        .bind(i64::try_from(event.fee).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.sweep_block_hash.to_byte_array())
+        .bind(i64::try_from(event.sweep_block_height).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.sweep_txid.to_byte_array())
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    /// Insert the withdrawal-reject event using the given executor.
+    async fn insert_withdrawal_reject_event(
+        executor: impl PgExecutor<'_>,
+        event: &WithdrawalRejectEvent,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "
+        INSERT INTO sbtc_signer.withdrawal_reject_events (
+            txid
+          , block_hash
+          , request_id
+          , signer_bitmap
+        )
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT DO NOTHING",
+        )
+        .bind(event.txid)
+        .bind(event.block_id)
+        .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.signer_bitmap.into_inner())
+        .execute(executor)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
     async fn write_transactions(
         &self,
         txs: Vec<model::Transaction>,
@@ -965,6 +1168,7 @@ impl super::DbRead for PgStore {
               , block_height
               , parent_hash
              FROM sbtc_signer.bitcoin_blocks
+             WHERE NOT is_orphaned
              ORDER BY block_height DESC, block_hash DESC
              LIMIT 1",
         )
@@ -982,6 +1186,7 @@ impl super::DbRead for PgStore {
                 block_hash
               , block_height
              FROM sbtc_signer.bitcoin_blocks
+             WHERE NOT is_orphaned
              ORDER BY block_height DESC, block_hash DESC
              LIMIT 1",
         )
@@ -1073,6 +1278,7 @@ impl super::DbRead for PgStore {
               , deposit_requests.amount
               , deposit_requests.max_fee
               , deposit_requests.lock_time
+              , deposit_requests.reclaim_script_kind
               , deposit_requests.signers_public_key
               , deposit_requests.sender_script_pub_keys
             FROM transactions_in_window transactions
@@ -1130,6 +1336,7 @@ impl super::DbRead for PgStore {
                   , deposit_requests.amount
                   , deposit_requests.max_fee
                   , deposit_requests.lock_time
+                  , deposit_requests.reclaim_script_kind
                   , deposit_requests.signers_public_key
                   , deposit_requests.sender_script_pub_keys
                 FROM transactions_in_window transactions
@@ -1159,6 +1366,7 @@ impl super::DbRead for PgStore {
               , accepted_deposits.amount
               , accepted_deposits.max_fee
               , accepted_deposits.lock_time
+              , accepted_deposits.reclaim_script_kind
               , accepted_deposits.signers_public_key
               , accepted_deposits.sender_script_pub_keys
             HAVING
@@ -1391,6 +1599,28 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn withdrawal_request_exists(
+        &self,
+        request_id: u64,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<bool, Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.withdrawal_requests AS wr
+                WHERE wr.request_id = $1
+                  AND wr.block_hash = $2
+            )
+            "#,
+        )
+        .bind(i64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(block_hash)
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn get_withdrawal_signers(
         &self,
         request_id: u64,
@@ -1988,6 +2218,50 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_signer_set_aggregate_keys(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        limit: u16,
+    ) -> Result<Vec<PublicKey>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(Vec::new());
+        };
+
+        sqlx::query_scalar::<_, PublicKey>(
+            r#"
+            WITH RECURSIVE stacks_blocks AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                  , 1 AS depth
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , last.depth + 1
+                FROM sbtc_signer.stacks_blocks parent
+                JOIN stacks_blocks last ON parent.block_hash = last.parent_hash
+            )
+            SELECT rkt.aggregate_key
+            FROM sbtc_signer.rotate_keys_transactions rkt
+            JOIN stacks_blocks AS sb
+              ON rkt.block_hash = sb.block_hash
+            GROUP BY rkt.aggregate_key
+            ORDER BY MIN(sb.depth) ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(stacks_chain_tip.block_hash)
+        .bind(i32::from(limit))
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn key_rotation_exists(
         &self,
         chain_tip: &model::BitcoinBlockHash,
@@ -2485,6 +2759,7 @@ impl super::DbRead for PgStore {
                  , amount
                  , max_fee
                  , lock_time
+                 , reclaim_script_kind
                  , signers_public_key
                  , sender_script_pub_keys
             FROM sbtc_signer.deposit_requests
@@ -2499,6 +2774,32 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_swept_deposit(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<model::SweptDeposit>, Error> {
+        sqlx::query_as::<_, model::SweptDeposit>(
+            r#"
+            SELECT sweep_txid
+                 , txid
+                 , output_index
+                 , assessed_fee
+            FROM sbtc_signer.swept_deposits
+            WHERE sweep_txid = $1
+              AND txid = $2
+              AND output_index = $3
+            "#,
+        )
+        .bind(sweep_txid)
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn will_sign_bitcoin_tx_sighash(
         &self,
         sighash: &model::SigHash,
@@ -2518,52 +2819,318 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
-    async fn get_withdrawal_signer_decisions(
+    async fn get_in_flight_signer_prevout_txids(
         &self,
         chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-        signer_public_key: &PublicKey,
-    ) -> Result<Vec<model::WithdrawalSigner>, Error> {
-        sqlx::query_as::<_, model::WithdrawalSigner>(
+        prevout: &bitcoin::OutPoint,
+        max_age: std::time::Duration,
+    ) -> Result<Vec<model::BitcoinTxId>, Error> {
+        sqlx::query_scalar::<_, model::BitcoinTxId>(
             r#"
-            WITH target_block AS (
-                SELECT blocks.block_hash, blocks.created_at
-                FROM sbtc_signer.bitcoin_blockchain_of($1, $2) chain
-                JOIN sbtc_signer.bitcoin_blocks blocks USING (block_hash)
-                ORDER BY chain.block_height ASC
-                LIMIT 1
-            )
-            SELECT
-                ws.request_id
-              , ws.txid
-              , ws.block_hash
-              , ws.signer_pub_key
-              , ws.is_accepted
-
-            FROM sbtc_signer.withdrawal_signers ws
-            WHERE ws.signer_pub_key = $3
-              AND ws.created_at >= (SELECT created_at FROM target_block)
+            SELECT DISTINCT txid
+            FROM sbtc_signer.bitcoin_tx_sighashes
+            WHERE chain_tip = $1
+              AND prevout_txid = $2
+              AND prevout_output_index = $3
+              AND prevout_type = 'signers_input'
+              AND will_sign
+              AND created_at >= CURRENT_TIMESTAMP - make_interval(secs => $4)
             "#,
         )
         .bind(chain_tip)
-        .bind(i32::from(context_window))
-        .bind(signer_public_key)
+        .bind(model::BitcoinTxId::from(prevout.txid))
+        .bind(i32::try_from(prevout.vout).map_err(Error::ConversionDatabaseInt)?)
+        .bind(max_age.as_secs_f64())
         .fetch_all(&self.0)
         .await
         .map_err(Error::SqlxQuery)
     }
 
-    async fn get_deposit_signer_decisions(
-        &self,
-        chain_tip: &model::BitcoinBlockHash,
-        context_window: u16,
-        signer_public_key: &PublicKey,
-    ) -> Result<Vec<model::DepositSigner>, Error> {
-        sqlx::query_as::<_, model::DepositSigner>(
+    async fn get_poisoned_webhooks(&self) -> Result<Vec<model::WebhookAttempt>, Error> {
+        sqlx::query_as::<_, model::WebhookAttempt>(
             r#"
-            WITH target_block AS (
-                SELECT blocks.block_hash, blocks.created_at
-                FROM sbtc_signer.bitcoin_blockchain_of($1, $2) chain
+            SELECT
+                index_block_hash
+              , payload
+              , failed_attempts
+              , is_poisoned
+            FROM sbtc_signer.webhook_attempts
+            WHERE is_poisoned
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_failed_events(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<Vec<model::FailedEvent>, Error> {
+        sqlx::query_as::<_, model::FailedEvent>(
+            r#"
+            SELECT
+                block_hash
+              , event_index
+              , txid
+              , event_topic
+              , field
+              , reason
+              , payload
+            FROM sbtc_signer.failed_events
+            WHERE block_hash = $1
+            ORDER BY event_index ASC
+            "#,
+        )
+        .bind(block_hash)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_burn_chain_tip(&self) -> Result<Option<model::BitcoinBlockRef>, Error> {
+        sqlx::query_as::<_, model::BitcoinBlockRef>(
+            "SELECT
+                block_hash
+              , block_height
+             FROM sbtc_signer.stacks_burn_blocks
+             ORDER BY block_height DESC, block_hash DESC
+             LIMIT 1",
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_signed_sighashes(
+        &self,
+        txid: &model::BitcoinTxId,
+    ) -> Result<Vec<model::SignedSigHash>, Error> {
+        sqlx::query_as::<_, model::SignedSigHash>(
+            r#"
+            SELECT
+                sighash
+              , txid
+              , input_index
+              , chain_tip
+              , aggregate_key
+              , origin_public_key
+            FROM sbtc_signer.signed_sighashes
+            WHERE txid = $1
+            ORDER BY input_index ASC
+            "#,
+        )
+        .bind(txid)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_recent_signed_sighashes(
+        &self,
+        limit: u16,
+    ) -> Result<Vec<model::SignedSigHash>, Error> {
+        sqlx::query_as::<_, model::SignedSigHash>(
+            r#"
+            SELECT
+                sighash
+              , txid
+              , input_index
+              , chain_tip
+              , aggregate_key
+              , origin_public_key
+            FROM sbtc_signer.signed_sighashes
+            ORDER BY created_at DESC, sighash DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(i64::from(limit))
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_request_attempt_history(
+        &self,
+        request: &model::SweepRequestId,
+    ) -> Result<Vec<model::SweepAttempt>, Error> {
+        let columns = PgSweepRequestId::try_from(request)?;
+
+        let rows = sqlx::query_as::<
+            _,
+            (
+                model::BitcoinTxId,
+                model::SweepAttemptOutcome,
+                Option<String>,
+            ),
+        >(
+            r#"
+            SELECT sweep_txid
+                 , outcome
+                 , error_detail
+            FROM sbtc_signer.sweep_attempts
+            WHERE (txid = $1 AND output_index = $2)
+               OR (request_id = $3 AND block_hash = $4)
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(columns.txid)
+        .bind(columns.output_index)
+        .bind(columns.request_id)
+        .bind(columns.block_hash)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        let attempts = rows
+            .into_iter()
+            .map(|(sweep_txid, outcome, error_detail)| model::SweepAttempt {
+                request: *request,
+                sweep_txid,
+                outcome,
+                error_detail,
+            })
+            .collect();
+
+        Ok(attempts)
+    }
+
+    async fn get_sweep_attempt_counts(&self) -> Result<model::SweepAttemptCounts, Error> {
+        let rows = sqlx::query_as::<_, (model::SweepAttemptOutcome, i64)>(
+            r#"
+            SELECT outcome
+                 , COUNT(*)
+            FROM sbtc_signer.sweep_attempts
+            GROUP BY outcome
+            "#,
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        let mut counts = model::SweepAttemptCounts::default();
+        for (outcome, count) in rows {
+            counts.add(
+                outcome,
+                u64::try_from(count).map_err(Error::ConversionDatabaseInt)?,
+            );
+        }
+
+        Ok(counts)
+    }
+
+    async fn get_pending_signer_decisions_count(&self) -> Result<u64, Error> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM sbtc_signer.pending_deposit_signers)
+              + (SELECT COUNT(*) FROM sbtc_signer.pending_withdrawal_signers)
+            "#,
+        )
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u64::try_from(count).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn get_recent_p2p_peers(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<Vec<(PeerId, Multiaddr)>, Error> {
+        let peers = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT peer_id, address
+            FROM sbtc_signer.p2p_peers
+            WHERE last_seen >= CURRENT_TIMESTAMP - make_interval(secs => $1)
+            ORDER BY last_seen DESC
+            "#,
+        )
+        .bind(max_age.as_secs_f64())
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        peers
+            .into_iter()
+            .map(|(peer_id, address)| {
+                let peer_id = peer_id.parse().map_err(Error::ParseDatabasePeerId)?;
+                let address = address.parse().map_err(Error::ParseDatabaseMultiaddr)?;
+                Ok((peer_id, address))
+            })
+            .collect()
+    }
+
+    async fn get_all_encrypted_dkg_shares(&self) -> Result<Vec<model::EncryptedDkgShares>, Error> {
+        sqlx::query_as::<_, model::EncryptedDkgShares>(
+            r#"
+            SELECT
+                aggregate_key
+              , tweaked_aggregate_key
+              , script_pubkey
+              , encrypted_private_shares
+              , public_shares
+              , signer_set_public_keys
+              , signature_share_threshold
+              , dkg_shares_status
+              , started_at_bitcoin_block_hash
+              , started_at_bitcoin_block_height
+            FROM sbtc_signer.dkg_shares
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_withdrawal_signer_decisions(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signer_public_key: &PublicKey,
+    ) -> Result<Vec<model::WithdrawalSigner>, Error> {
+        sqlx::query_as::<_, model::WithdrawalSigner>(
+            r#"
+            WITH target_block AS (
+                SELECT blocks.block_hash, blocks.created_at
+                FROM sbtc_signer.bitcoin_blockchain_of($1, $2) chain
+                JOIN sbtc_signer.bitcoin_blocks blocks USING (block_hash)
+                ORDER BY chain.block_height ASC
+                LIMIT 1
+            )
+            SELECT
+                ws.request_id
+              , ws.txid
+              , ws.block_hash
+              , ws.signer_pub_key
+              , ws.is_accepted
+
+            FROM sbtc_signer.withdrawal_signers ws
+            WHERE ws.signer_pub_key = $3
+              AND ws.created_at >= (SELECT created_at FROM target_block)
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(i32::from(context_window))
+        .bind(signer_public_key)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_deposit_signer_decisions(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        signer_public_key: &PublicKey,
+    ) -> Result<Vec<model::DepositSigner>, Error> {
+        sqlx::query_as::<_, model::DepositSigner>(
+            r#"
+            WITH target_block AS (
+                SELECT blocks.block_hash, blocks.created_at
+                FROM sbtc_signer.bitcoin_blockchain_of($1, $2) chain
                 JOIN sbtc_signer.bitcoin_blocks blocks USING (block_hash)
                 ORDER BY chain.block_height ASC
                 LIMIT 1
@@ -2604,6 +3171,30 @@ impl super::DbWrite for PgStore {
         .bind(block.parent_hash)
         .execute(&self.0)
         .await
+        .map_err(Error::SqlxQuery)
+        .with_context(|| {
+            ErrorContext::BitcoinBlock("write_bitcoin_block", block.block_hash.into())
+        })?;
+
+        Ok(())
+    }
+
+    async fn set_bitcoin_blocks_orphaned(
+        &self,
+        block_hashes: &[model::BitcoinBlockHash],
+        is_orphaned: bool,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            UPDATE sbtc_signer.bitcoin_blocks
+            SET is_orphaned = $2
+            WHERE block_hash = ANY($1)
+            "#,
+        )
+        .bind(block_hashes)
+        .bind(is_orphaned)
+        .execute(&self.0)
+        .await
         .map_err(Error::SqlxQuery)?;
 
         Ok(())
@@ -2647,8 +3238,9 @@ impl super::DbWrite for PgStore {
               , lock_time
               , signers_public_key
               , sender_script_pub_keys
+              , reclaim_script_kind
               )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT DO NOTHING",
         )
         .bind(deposit_request.txid)
@@ -2661,9 +3253,13 @@ impl super::DbWrite for PgStore {
         .bind(i64::from(deposit_request.lock_time))
         .bind(deposit_request.signers_public_key)
         .bind(&deposit_request.sender_script_pub_keys)
+        .bind(deposit_request.reclaim_script_kind)
         .execute(&self.0)
         .await
-        .map_err(Error::SqlxQuery)?;
+        .map_err(Error::SqlxQuery)
+        .with_context(|| {
+            ErrorContext::DepositRequest("write_deposit_request", deposit_request.outpoint())
+        })?;
 
         Ok(())
     }
@@ -2686,6 +3282,7 @@ impl super::DbWrite for PgStore {
         let mut lock_time = Vec::with_capacity(deposit_requests.len());
         let mut signers_public_key = Vec::with_capacity(deposit_requests.len());
         let mut sender_script_pubkeys = Vec::with_capacity(deposit_requests.len());
+        let mut reclaim_script_kinds = Vec::with_capacity(deposit_requests.len());
 
         for req in deposit_requests {
             let vout = i32::try_from(req.output_index).map_err(Error::ConversionDatabaseInt)?;
@@ -2698,6 +3295,7 @@ impl super::DbWrite for PgStore {
             max_fee.push(i64::try_from(req.max_fee).map_err(Error::ConversionDatabaseInt)?);
             lock_time.push(i64::from(req.lock_time));
             signers_public_key.push(req.signers_public_key);
+            reclaim_script_kinds.push(req.reclaim_script_kind.to_string());
             // We need to join the addresses like this (and later split
             // them), because handling of multidimensional arrays in
             // postgres is tough. The naive approach of doing
@@ -2723,6 +3321,7 @@ impl super::DbWrite for PgStore {
             , lock_time       AS (SELECT ROW_NUMBER() OVER (), lock_time FROM UNNEST($8::BIGINT[]) AS lock_time)
             , signer_pub_keys AS (SELECT ROW_NUMBER() OVER (), signers_public_key FROM UNNEST($9::BYTEA[]) AS signers_public_key)
             , script_pub_keys AS (SELECT ROW_NUMBER() OVER (), senders FROM UNNEST($10::VARCHAR[]) AS senders)
+            , reclaim_kinds   AS (SELECT ROW_NUMBER() OVER (), kind::sbtc_signer.reclaim_script_kind FROM UNNEST($11::VARCHAR[]) AS kind)
             INSERT INTO sbtc_signer.deposit_requests (
                   txid
                 , output_index
@@ -2733,7 +3332,8 @@ impl super::DbWrite for PgStore {
                 , max_fee
                 , lock_time
                 , signers_public_key
-                , sender_script_pub_keys)
+                , sender_script_pub_keys
+                , reclaim_script_kind)
             SELECT
                 txid
               , output_index
@@ -2745,6 +3345,7 @@ impl super::DbWrite for PgStore {
               , lock_time
               , signers_public_key
               , ARRAY(SELECT decode(UNNEST(regexp_split_to_array(senders, ',')), 'hex'))
+              , kind
             FROM tx_ids
             JOIN output_index USING (row_number)
             JOIN spend_script USING (row_number)
@@ -2755,6 +3356,7 @@ impl super::DbWrite for PgStore {
             JOIN lock_time USING (row_number)
             JOIN signer_pub_keys USING (row_number)
             JOIN script_pub_keys USING (row_number)
+            JOIN reclaim_kinds USING (row_number)
             ON CONFLICT DO NOTHING"#,
         )
         .bind(txid)
@@ -2767,6 +3369,7 @@ impl super::DbWrite for PgStore {
         .bind(lock_time)
         .bind(signers_public_key)
         .bind(sender_script_pubkeys)
+        .bind(reclaim_script_kinds)
         .execute(&self.0)
         .await
         .map_err(Error::SqlxQuery)?;
@@ -2778,33 +3381,11 @@ impl super::DbWrite for PgStore {
         &self,
         request: &model::WithdrawalRequest,
     ) -> Result<(), Error> {
-        sqlx::query(
-            "INSERT INTO sbtc_signer.withdrawal_requests
-              ( request_id
-              , txid
-              , block_hash
-              , recipient
-              , amount
-              , max_fee
-              , sender_address
-              , bitcoin_block_height
-              )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            ON CONFLICT DO NOTHING",
-        )
-        .bind(i64::try_from(request.request_id).map_err(Error::ConversionDatabaseInt)?)
-        .bind(request.txid)
-        .bind(request.block_hash)
-        .bind(&request.recipient)
-        .bind(i64::try_from(request.amount).map_err(Error::ConversionDatabaseInt)?)
-        .bind(i64::try_from(request.max_fee).map_err(Error::ConversionDatabaseInt)?)
-        .bind(&request.sender_address)
-        .bind(i64::try_from(request.bitcoin_block_height).map_err(Error::ConversionDatabaseInt)?)
-        .execute(&self.0)
-        .await
-        .map_err(Error::SqlxQuery)?;
-
-        Ok(())
+        Self::insert_withdrawal_request(&self.0, request)
+            .await
+            .with_context(|| {
+                ErrorContext::WithdrawalRequest("write_withdrawal_request", request.request_id)
+            })
     }
 
     #[tracing::instrument(skip(self))]
@@ -2890,7 +3471,10 @@ impl super::DbWrite for PgStore {
         .bind(tx_ref.block_hash)
         .execute(&self.0)
         .await
-        .map_err(Error::SqlxQuery)?;
+        .map_err(Error::SqlxQuery)
+        .with_context(|| {
+            ErrorContext::BitcoinTransaction("write_bitcoin_transaction", tx_ref.txid.into())
+        })?;
 
         Ok(())
     }
@@ -3086,125 +3670,57 @@ impl super::DbWrite for PgStore {
         &self,
         key_rotation: &model::KeyRotationEvent,
     ) -> Result<(), Error> {
-        sqlx::query(
-            r#"
-            INSERT INTO sbtc_signer.rotate_keys_transactions (
-                  txid
-                , block_hash
-                , address
-                , aggregate_key
-                , signer_set
-                , signatures_required)
-            VALUES
-                ($1, $2, $3, $4, $5, $6)
-            ON CONFLICT DO NOTHING"#,
-        )
-        .bind(key_rotation.txid)
-        .bind(key_rotation.block_hash)
-        .bind(&key_rotation.address)
-        .bind(key_rotation.aggregate_key)
-        .bind(&key_rotation.signer_set)
-        .bind(i32::from(key_rotation.signatures_required))
-        .execute(&self.0)
-        .await
-        .map_err(Error::SqlxQuery)?;
-
-        Ok(())
+        Self::insert_rotate_keys_transaction(&self.0, key_rotation).await
     }
 
     async fn write_completed_deposit_event(
         &self,
         event: &CompletedDepositEvent,
     ) -> Result<(), Error> {
-        sqlx::query(
-            "
-        INSERT INTO sbtc_signer.completed_deposit_events (
-            txid
-          , block_hash
-          , amount
-          , bitcoin_txid
-          , output_index
-          , sweep_block_hash
-          , sweep_block_height
-          , sweep_txid
-        )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
-        )
-        .bind(event.txid)
-        .bind(event.block_id)
-        .bind(i64::try_from(event.amount).map_err(Error::ConversionDatabaseInt)?)
-        .bind(event.outpoint.txid.to_byte_array())
-        .bind(i64::from(event.outpoint.vout))
-        .bind(event.sweep_block_hash.to_byte_array())
-        .bind(i64::try_from(event.sweep_block_height).map_err(Error::ConversionDatabaseInt)?)
-        .bind(event.sweep_txid.to_byte_array())
-        .execute(&self.0)
-        .await
-        .map_err(Error::SqlxQuery)?;
-
-        Ok(())
+        Self::insert_completed_deposit_event(&self.0, event).await
     }
 
     async fn write_withdrawal_accept_event(
         &self,
         event: &WithdrawalAcceptEvent,
     ) -> Result<(), Error> {
-        sqlx::query(
-            "
-        INSERT INTO sbtc_signer.withdrawal_accept_events (
-            txid
-          , block_hash
-          , request_id
-          , signer_bitmap
-          , bitcoin_txid
-          , output_index
-          , fee
-          , sweep_block_hash
-          , sweep_block_height
-          , sweep_txid
-        )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
-        )
-        .bind(event.txid)
-        .bind(event.block_id)
-        .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?)
-        .bind(event.signer_bitmap.into_inner())
-        .bind(event.outpoint.txid.to_byte_array())
-        .bind(i64::from(event.outpoint.vout))
-        .bind(i64::try_from(event.fee).map_err(Error::ConversionDatabaseInt)?)
-        .bind(event.sweep_block_hash.to_byte_array())
-        .bind(i64::try_from(event.sweep_block_height).map_err(Error::ConversionDatabaseInt)?)
-        .bind(event.sweep_txid.to_byte_array())
-        .execute(&self.0)
-        .await
-        .map_err(Error::SqlxQuery)?;
-
-        Ok(())
+        Self::insert_withdrawal_accept_event(&self.0, event).await
     }
 
     async fn write_withdrawal_reject_event(
         &self,
         event: &WithdrawalRejectEvent,
     ) -> Result<(), Error> {
-        sqlx::query(
-            "
-        INSERT INTO sbtc_signer.withdrawal_reject_events (
-            txid
-          , block_hash
-          , request_id
-          , signer_bitmap
-        )
-        VALUES ($1, $2, $3, $4)",
-        )
-        .bind(event.txid)
-        .bind(event.block_id)
-        .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?)
-        .bind(event.signer_bitmap.into_inner())
-        .execute(&self.0)
-        .await
-        .map_err(Error::SqlxQuery)?;
+        Self::insert_withdrawal_reject_event(&self.0, event).await
+    }
 
-        Ok(())
+    async fn write_stacks_block_events(
+        &self,
+        events: &model::StacksBlockEvents,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .pool()
+            .begin()
+            .await
+            .map_err(Error::SqlxBeginTransaction)?;
+
+        for event in events.completed_deposits.iter() {
+            Self::insert_completed_deposit_event(&mut *tx, event).await?;
+        }
+        for request in events.withdrawal_requests.iter() {
+            Self::insert_withdrawal_request(&mut *tx, request).await?;
+        }
+        for event in events.withdrawal_accepts.iter() {
+            Self::insert_withdrawal_accept_event(&mut *tx, event).await?;
+        }
+        for event in events.withdrawal_rejects.iter() {
+            Self::insert_withdrawal_reject_event(&mut *tx, event).await?;
+        }
+        for event in events.key_rotations.iter() {
+            Self::insert_rotate_keys_transaction(&mut *tx, event).await?;
+        }
+
+        tx.commit().await.map_err(Error::SqlxCommitTransaction)
     }
 
     async fn write_tx_output(&self, output: &model::TxOutput) -> Result<(), Error> {
@@ -3258,6 +3774,61 @@ impl super::DbWrite for PgStore {
         Ok(())
     }
 
+    async fn write_swept_deposit(&self, swept_deposit: &model::SweptDeposit) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.swept_deposits (
+                sweep_txid
+              , txid
+              , output_index
+              , assessed_fee
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(swept_deposit.sweep_txid)
+        .bind(swept_deposit.txid)
+        .bind(i32::try_from(swept_deposit.output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(swept_deposit.assessed_fee).map_err(Error::ConversionDatabaseInt)?)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_sweep_attempt(&self, attempt: &model::SweepAttempt) -> Result<(), Error> {
+        let columns = PgSweepRequestId::try_from(&attempt.request)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.sweep_attempts (
+                txid
+              , output_index
+              , request_id
+              , block_hash
+              , sweep_txid
+              , outcome
+              , error_detail
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(columns.txid)
+        .bind(columns.output_index)
+        .bind(columns.request_id)
+        .bind(columns.block_hash)
+        .bind(attempt.sweep_txid)
+        .bind(attempt.outcome)
+        .bind(attempt.error_detail.as_deref())
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
     async fn write_tx_prevout(&self, prevout: &model::TxPrevout) -> Result<(), Error> {
         sqlx::query(
             r#"
@@ -3472,6 +4043,590 @@ impl super::DbWrite for PgStore {
         Ok(())
     }
 
+    async fn prune_below_height(&self, bitcoin_height: BitcoinBlockHeight) -> Result<(), Error> {
+        let Some(chain_tip) = self.get_bitcoin_canonical_chain_tip().await? else {
+            return Ok(());
+        };
+        let stacks_chain_tip = self
+            .get_stacks_chain_tip(&chain_tip)
+            .await?
+            .map(|block| block.block_hash);
+        let min_height = i64::try_from(bitcoin_height).map_err(Error::ConversionDatabaseInt)?;
+
+        let mut tx = self
+            .pool()
+            .begin()
+            .await
+            .map_err(Error::SqlxBeginTransaction)?;
+
+        // Deposit requests are settled once they have been swept in a
+        // bitcoin block on the canonical bitcoin blockchain below the
+        // minimum height and the complete-deposit contract call has been
+        // confirmed on the canonical stacks blockchain. Sweeps and events
+        // from forks do not count, since the deposit may still need to be
+        // processed on the canonical chain. The deposit signer decisions
+        // are removed along with them.
+        if let Some(stacks_chain_tip) = stacks_chain_tip {
+            sqlx::query(
+                r#"
+                WITH RECURSIVE stacks_blockchain AS (
+                    SELECT
+                        sb.block_hash
+                      , sb.parent_hash
+                    FROM sbtc_signer.stacks_blocks AS sb
+                    WHERE sb.block_hash = $3
+
+                    UNION ALL
+
+                    SELECT
+                        parent.block_hash
+                      , parent.parent_hash
+                    FROM sbtc_signer.stacks_blocks AS parent
+                    JOIN stacks_blockchain AS child
+                      ON parent.block_hash = child.parent_hash
+                ),
+                canonical_blocks AS (
+                    SELECT block_hash, block_height
+                    FROM sbtc_signer.bitcoin_blockchain_until($2, 0)
+                )
+                DELETE FROM sbtc_signer.deposit_requests AS dr
+                USING sbtc_signer.completed_deposit_events AS cde
+                WHERE cde.bitcoin_txid = dr.txid
+                  AND cde.output_index = dr.output_index
+                  AND cde.sweep_block_height < $1
+                  AND EXISTS (
+                      SELECT TRUE
+                      FROM stacks_blockchain AS sb
+                      WHERE sb.block_hash = cde.block_hash
+                  )
+                  AND EXISTS (
+                      SELECT TRUE
+                      FROM sbtc_signer.bitcoin_tx_inputs AS bti
+                      JOIN sbtc_signer.bitcoin_transactions AS bt
+                        ON bt.txid = bti.txid
+                      JOIN canonical_blocks AS cb
+                        ON cb.block_hash = bt.block_hash
+                      WHERE bti.prevout_txid = dr.txid
+                        AND bti.prevout_output_index = dr.output_index
+                        AND cb.block_height < $1
+                  )
+                "#,
+            )
+            .bind(min_height)
+            .bind(chain_tip)
+            .bind(stacks_chain_tip)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::SqlxQuery)?;
+        }
+
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.completed_deposit_events AS cde
+            WHERE cde.sweep_block_height < $1
+              AND NOT EXISTS (
+                  SELECT TRUE
+                  FROM sbtc_signer.deposit_requests AS dr
+                  WHERE dr.txid = cde.bitcoin_txid
+                    AND dr.output_index = cde.output_index
+              )
+            "#,
+        )
+        .bind(min_height)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        // Withdrawal requests are settled once an accept or reject event
+        // for them has been confirmed in a stacks block on the canonical
+        // stacks blockchain that is anchored below the minimum height.
+        // Events in blocks from stacks forks do not count, since the
+        // request may still need to be fulfilled on the canonical chain.
+        // The withdrawal signer decisions are removed along with them.
+        if let Some(stacks_chain_tip) = stacks_chain_tip {
+            sqlx::query(
+                r#"
+                WITH RECURSIVE stacks_blockchain AS (
+                    SELECT
+                        sb.block_hash
+                      , sb.parent_hash
+                      , sb.bitcoin_anchor
+                    FROM sbtc_signer.stacks_blocks AS sb
+                    WHERE sb.block_hash = $2
+
+                    UNION ALL
+
+                    SELECT
+                        parent.block_hash
+                      , parent.parent_hash
+                      , parent.bitcoin_anchor
+                    FROM sbtc_signer.stacks_blocks AS parent
+                    JOIN stacks_blockchain AS child
+                      ON parent.block_hash = child.parent_hash
+                )
+                DELETE FROM sbtc_signer.withdrawal_requests AS wr
+                WHERE wr.bitcoin_block_height < $1
+                  AND EXISTS (
+                      SELECT TRUE
+                      FROM (
+                          SELECT request_id, block_hash
+                          FROM sbtc_signer.withdrawal_accept_events
+                          UNION ALL
+                          SELECT request_id, block_hash
+                          FROM sbtc_signer.withdrawal_reject_events
+                      ) AS events
+                      JOIN stacks_blockchain AS sb
+                        ON sb.block_hash = events.block_hash
+                      JOIN sbtc_signer.bitcoin_blocks AS bb
+                        ON bb.block_hash = sb.bitcoin_anchor
+                      WHERE events.request_id = wr.request_id
+                        AND bb.block_height < $1
+                  )
+                "#,
+            )
+            .bind(min_height)
+            .bind(stacks_chain_tip)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::SqlxQuery)?;
+        }
+
+        for table in ["withdrawal_accept_events", "withdrawal_reject_events"] {
+            sqlx::query(&format!(
+                r#"
+                DELETE FROM sbtc_signer.{table} AS events
+                USING sbtc_signer.stacks_blocks AS sb
+                    , sbtc_signer.bitcoin_blocks AS bb
+                WHERE sb.block_hash = events.block_hash
+                  AND bb.block_hash = sb.bitcoin_anchor
+                  AND bb.block_height < $1
+                  AND NOT EXISTS (
+                      SELECT TRUE
+                      FROM sbtc_signer.withdrawal_requests AS wr
+                      WHERE wr.request_id = events.request_id
+                  )
+                "#
+            ))
+            .bind(min_height)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::SqlxQuery)?;
+        }
+
+        // Blocks on the canonical bitcoin blockchain are kept, since we
+        // need them to find the signers' UTXO, among other things. Blocks
+        // from old forks are no longer useful, and neither are the stacks
+        // blocks anchored to them.
+        sqlx::query(
+            r#"
+            WITH canonical_blocks AS (
+                SELECT block_hash
+                FROM sbtc_signer.bitcoin_blockchain_until($1, 0)
+            ),
+            pruned_blocks AS (
+                DELETE FROM sbtc_signer.bitcoin_blocks AS bb
+                WHERE bb.block_height < $2
+                  AND NOT EXISTS (
+                      SELECT TRUE
+                      FROM canonical_blocks AS cb
+                      WHERE cb.block_hash = bb.block_hash
+                  )
+                RETURNING bb.block_hash
+            )
+            DELETE FROM sbtc_signer.stacks_blocks AS sb
+            USING pruned_blocks AS pb
+            WHERE sb.bitcoin_anchor = pb.block_hash
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(min_height)
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        tx.commit().await.map_err(Error::SqlxCommitTransaction)
+    }
+
+    async fn record_failed_webhook_attempt(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+        payload: &str,
+    ) -> Result<u32, Error> {
+        let failed_attempts = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO sbtc_signer.webhook_attempts (
+                index_block_hash
+              , payload
+              , failed_attempts
+            )
+            VALUES ($1, $2, 1)
+            ON CONFLICT (index_block_hash) DO UPDATE
+            SET payload = EXCLUDED.payload
+              , failed_attempts = webhook_attempts.failed_attempts + 1
+              , updated_at = CURRENT_TIMESTAMP
+            RETURNING failed_attempts
+            "#,
+        )
+        .bind(index_block_hash)
+        .bind(payload)
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u32::try_from(failed_attempts).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn mark_webhook_poisoned(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            UPDATE sbtc_signer.webhook_attempts
+            SET is_poisoned = TRUE
+              , updated_at = CURRENT_TIMESTAMP
+            WHERE index_block_hash = $1
+            "#,
+        )
+        .bind(index_block_hash)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn clear_webhook_attempts(
+        &self,
+        index_block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.webhook_attempts
+            WHERE index_block_hash = $1
+            "#,
+        )
+        .bind(index_block_hash)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_failed_event(&self, event: &model::FailedEvent) -> Result<(), Error> {
+        let event_index = i64::try_from(event.event_index).map_err(Error::ConversionDatabaseInt)?;
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.failed_events (
+                block_hash
+              , event_index
+              , txid
+              , event_topic
+              , field
+              , reason
+              , payload
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(event.block_hash)
+        .bind(event_index)
+        .bind(event.txid)
+        .bind(&event.event_topic)
+        .bind(&event.field)
+        .bind(&event.reason)
+        .bind(&event.payload)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_signed_sighash(&self, sighash: &model::SignedSigHash) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.signed_sighashes (
+                sighash
+              , txid
+              , input_index
+              , chain_tip
+              , aggregate_key
+              , origin_public_key
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(sighash.sighash)
+        .bind(sighash.txid)
+        .bind(i32::try_from(sighash.input_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(sighash.chain_tip)
+        .bind(sighash.aggregate_key)
+        .bind(sighash.origin_public_key)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_burn_block(&self, block: &model::BitcoinBlockRef) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stacks_burn_blocks (block_hash, block_height)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(block.block_hash)
+        .bind(i64::try_from(block.block_height).map_err(Error::ConversionDatabaseInt)?)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_pending_deposit_signer_decision(
+        &self,
+        decision: &model::DepositSigner,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.pending_deposit_signers
+              ( txid
+              , output_index
+              , signer_pub_key
+              , can_accept
+              , can_sign
+              )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(decision.txid)
+        .bind(i32::try_from(decision.output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(decision.signer_pub_key)
+        .bind(decision.can_accept)
+        .bind(decision.can_sign)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_pending_withdrawal_signer_decision(
+        &self,
+        decision: &model::WithdrawalSigner,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.pending_withdrawal_signers
+              ( request_id
+              , txid
+              , block_hash
+              , signer_pub_key
+              , is_accepted
+              )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(i64::try_from(decision.request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(decision.txid)
+        .bind(decision.block_hash)
+        .bind(decision.signer_pub_key)
+        .bind(decision.is_accepted)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn replay_pending_signer_decisions(&self) -> Result<u64, Error> {
+        let deposits = sqlx::query(
+            r#"
+            WITH replayed AS (
+                DELETE FROM sbtc_signer.pending_deposit_signers AS pds
+                USING sbtc_signer.deposit_requests AS dr
+                WHERE pds.txid = dr.txid
+                  AND pds.output_index = dr.output_index
+                RETURNING
+                    pds.txid
+                  , pds.output_index
+                  , pds.signer_pub_key
+                  , pds.can_accept
+                  , pds.can_sign
+            )
+            INSERT INTO sbtc_signer.deposit_signers
+              ( txid
+              , output_index
+              , signer_pub_key
+              , can_accept
+              , can_sign
+              )
+            SELECT
+                txid
+              , output_index
+              , signer_pub_key
+              , can_accept
+              , can_sign
+            FROM replayed
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        let withdrawals = sqlx::query(
+            r#"
+            WITH replayed AS (
+                DELETE FROM sbtc_signer.pending_withdrawal_signers AS pws
+                USING sbtc_signer.withdrawal_requests AS wr
+                WHERE pws.request_id = wr.request_id
+                  AND pws.block_hash = wr.block_hash
+                RETURNING
+                    pws.request_id
+                  , pws.txid
+                  , pws.block_hash
+                  , pws.signer_pub_key
+                  , pws.is_accepted
+            )
+            INSERT INTO sbtc_signer.withdrawal_signers
+              ( request_id
+              , txid
+              , block_hash
+              , signer_pub_key
+              , is_accepted
+              )
+            SELECT
+                request_id
+              , txid
+              , block_hash
+              , signer_pub_key
+              , is_accepted
+            FROM replayed
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(deposits.rows_affected() + withdrawals.rows_affected())
+    }
+
+    async fn delete_expired_pending_signer_decisions(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<u64, Error> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            WITH expired_deposits AS (
+                DELETE FROM sbtc_signer.pending_deposit_signers
+                WHERE created_at < CURRENT_TIMESTAMP - make_interval(secs => $1)
+                RETURNING 1
+            ),
+            expired_withdrawals AS (
+                DELETE FROM sbtc_signer.pending_withdrawal_signers
+                WHERE created_at < CURRENT_TIMESTAMP - make_interval(secs => $1)
+                RETURNING 1
+            )
+            SELECT
+                (SELECT COUNT(*) FROM expired_deposits)
+              + (SELECT COUNT(*) FROM expired_withdrawals)
+            "#,
+        )
+        .bind(max_age.as_secs_f64())
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u64::try_from(count).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn next_p2p_message_sequence(&self, public_key: &PublicKey) -> Result<u64, Error> {
+        let sequence = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO sbtc_signer.p2p_message_sequences (signer_pub_key, sequence)
+            VALUES ($1, 1)
+            ON CONFLICT (signer_pub_key) DO UPDATE
+            SET sequence = p2p_message_sequences.sequence + 1
+              , updated_at = CURRENT_TIMESTAMP
+            RETURNING sequence
+            "#,
+        )
+        .bind(public_key)
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u64::try_from(sequence).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn upsert_p2p_peer(&self, peer_id: &PeerId, address: &Multiaddr) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.p2p_peers (peer_id, address)
+            VALUES ($1, $2)
+            ON CONFLICT (peer_id, address) DO UPDATE
+            SET last_seen = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(peer_id.to_string())
+        .bind(address.to_string())
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn prune_p2p_peers(&self, max_age: std::time::Duration) -> Result<u64, Error> {
+        let pruned = sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.p2p_peers
+            WHERE last_seen < CURRENT_TIMESTAMP - make_interval(secs => $1)
+            "#,
+        )
+        .bind(max_age.as_secs_f64())
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(pruned.rows_affected())
+    }
+
+    async fn update_encrypted_private_shares(
+        &self,
+        shares: &[(PublicKey, Vec<u8>)],
+    ) -> Result<(), Error> {
+        let mut tx = self.0.begin().await.map_err(Error::SqlxBeginTransaction)?;
+
+        for (aggregate_key, encrypted_private_shares) in shares {
+            sqlx::query(
+                r#"
+                UPDATE sbtc_signer.dkg_shares
+                SET encrypted_private_shares = $2
+                WHERE aggregate_key = $1
+                "#,
+            )
+            .bind(aggregate_key)
+            .bind(encrypted_private_shares)
+            .execute(&mut *tx)
+            .await
+            .map_err(Error::SqlxQuery)?;
+        }
+
+        tx.commit().await.map_err(Error::SqlxCommitTransaction)
+    }
+
     async fn revoke_dkg_shares<X>(&self, aggregate_key: X) -> Result<bool, Error>
     where
         X: Into<PublicKeyXOnly> + Send,
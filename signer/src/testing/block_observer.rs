@@ -36,6 +36,7 @@ use crate::bitcoin::rpc::GetTxResponse;
 use crate::bitcoin::utxo;
 use crate::context::SbtcLimits;
 use crate::emily_client::EmilyInteract;
+use crate::emily_client::FetchedDeposits;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::stacks::api::AccountInfo;
@@ -62,6 +63,9 @@ pub struct TestHarness {
     /// This represents deposit requests that have not been processed, i.e.
     /// they are received from the Emily API.
     pending_deposits: Vec<CreateDepositRequest>,
+    /// How long each `get_tx` call takes, simulating the latency of
+    /// bitcoin-core's RPC interface.
+    get_tx_latency: std::time::Duration,
 }
 
 impl TestHarness {
@@ -116,6 +120,11 @@ impl TestHarness {
         }
     }
 
+    /// Set how long each `BitcoinInteract::get_tx` call should take.
+    pub fn set_get_tx_latency(&mut self, latency: std::time::Duration) {
+        self.get_tx_latency = latency;
+    }
+
     /// Get the pending deposit requests in the test harness.
     pub fn pending_deposits(&self) -> &[CreateDepositRequest] {
         &self.pending_deposits
@@ -182,6 +191,7 @@ impl TestHarness {
             stacks_blocks,
             deposits: HashMap::new(),
             pending_deposits: Vec::new(),
+            get_tx_latency: std::time::Duration::ZERO,
         }
     }
 
@@ -216,6 +226,7 @@ impl TryFrom<TestHarness> for ApiFallbackClient<TestHarness> {
 
 impl BitcoinInteract for TestHarness {
     async fn get_tx(&self, txid: &bitcoin::Txid) -> Result<Option<GetTxResponse>, Error> {
+        tokio::time::sleep(self.get_tx_latency).await;
         Ok(self.deposits.get(txid).cloned().map(|(resp, _)| resp))
     }
 
@@ -299,7 +310,10 @@ impl BitcoinInteract for TestHarness {
     async fn get_blockchain_info(
         &self,
     ) -> Result<bitcoincore_rpc_json::GetBlockchainInfoResult, Error> {
-        unimplemented!()
+        // The block observer only uses this to catch up on blocks before
+        // reading the block stream, and it carries on if this fails. We
+        // want the test harness blocks to come from the stream.
+        Err(Error::NoChainTip)
     }
 
     async fn get_network_info(&self) -> Result<bitcoincore_rpc_json::GetNetworkInfoResult, Error> {
@@ -495,18 +509,30 @@ impl EmilyInteract for TestHarness {
             .cloned();
         Ok(deposit)
     }
-    async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
-        Ok(self.pending_deposits.clone())
+    async fn get_deposits(&self) -> Result<FetchedDeposits, Error> {
+        self.get_deposits_with_status(Status::Pending).await
+    }
+
+    async fn get_deposits_with_status(&self, status: Status) -> Result<FetchedDeposits, Error> {
+        let deposits = match status {
+            Status::Pending => self.pending_deposits.clone(),
+            _ => Vec::new(),
+        };
+        Ok(FetchedDeposits { deposits, truncated: false })
+    }
+
+    async fn get_withdrawal(
+        &self,
+        _request_id: u64,
+    ) -> Result<Option<emily_client::models::Withdrawal>, Error> {
+        Ok(None)
     }
 
-    async fn get_deposits_with_status(
+    async fn get_withdrawals(
         &self,
-        status: Status,
-    ) -> Result<Vec<CreateDepositRequest>, Error> {
-        match status {
-            Status::Pending => Ok(self.pending_deposits.clone()),
-            _ => Ok(Vec::new()),
-        }
+        _status: Status,
+    ) -> Result<Vec<emily_client::models::WithdrawalInfo>, Error> {
+        Ok(Vec::new())
     }
 
     async fn update_deposits(
@@ -1,6 +1,7 @@
 //! Helper functions for the bitcoin module
 //!
 use bitcoin::Amount;
+use bitcoin::Block;
 use bitcoin::BlockHash;
 use bitcoin::OutPoint;
 use bitcoin::ScriptBuf;
@@ -9,14 +10,28 @@ use bitcoin::Transaction;
 use bitcoin::TxIn;
 use bitcoin::TxOut;
 use bitcoin::Witness;
+use bitcoin::XOnlyPublicKey;
+use bitcoin::absolute::LockTime;
 use bitcoin::consensus::encode::serialize_hex;
+use bitcoin::transaction::Version;
 
+use bitvec::array::BitArray;
+use clarity::vm::types::PrincipalData;
 use emily_client::models::CreateDepositRequestBody;
 use futures::StreamExt as _;
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositInfo;
+use sbtc::deposits::DepositScriptInputs;
+use sbtc::deposits::ReclaimScriptInputs;
+use sbtc::testing::regtest;
+use sbtc::testing::regtest::AsUtxo;
+use sbtc::testing::regtest::Recipient;
+use stacks_common::types::chainstate::StacksAddress;
 use tokio_stream::wrappers::ReceiverStream;
 
 use crate::bitcoin::utxo;
 use crate::bitcoin::zmq::BitcoinCoreMessageStream;
+use crate::config::BitcoinConfig;
 use crate::error::Error;
 
 /// Return a transaction that is kinda like the signers' transaction,
@@ -64,6 +79,79 @@ impl utxo::DepositRequest {
     }
 }
 
+/// Create and sign a transaction that deposits the given amount from the
+/// given UTXO of the depositor, returning the transaction along with the
+/// deposit request that it creates.
+///
+/// The deposit has a lock time of 50 blocks, and the change, less a fixed
+/// fee, goes back to the depositor.
+pub fn make_deposit_request<U>(
+    depositor: &Recipient,
+    amount: u64,
+    utxo: U,
+    max_fee: u64,
+    signers_public_key: XOnlyPublicKey,
+) -> (Transaction, utxo::DepositRequest, DepositInfo)
+where
+    U: AsUtxo,
+{
+    let fee = regtest::BITCOIN_CORE_FALLBACK_FEE.to_sat();
+    let deposit_inputs = DepositScriptInputs {
+        signers_public_key,
+        max_fee,
+        recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+    };
+    let reclaim_inputs = ReclaimScriptInputs::try_new(50, ScriptBuf::new()).unwrap();
+
+    let deposit_script = deposit_inputs.deposit_script();
+    let reclaim_script = reclaim_inputs.reclaim_script();
+
+    let mut deposit_tx = Transaction {
+        version: Version::ONE,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(utxo.txid(), utxo.vout()),
+            sequence: Sequence::ZERO,
+            script_sig: ScriptBuf::new(),
+            witness: Witness::new(),
+        }],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(amount),
+                script_pubkey: sbtc::deposits::to_script_pubkey(
+                    deposit_script.clone(),
+                    reclaim_script.clone(),
+                ),
+            },
+            TxOut {
+                value: utxo.amount() - Amount::from_sat(amount + fee),
+                script_pubkey: depositor.address.script_pubkey(),
+            },
+        ],
+    };
+
+    regtest::p2tr_sign_transaction(&mut deposit_tx, 0, &[utxo], &depositor.keypair);
+
+    let create_req = CreateDepositRequest {
+        outpoint: OutPoint::new(deposit_tx.compute_txid(), 0),
+        deposit_script,
+        reclaim_script,
+    };
+
+    let dep = create_req.validate_tx(&deposit_tx, false).unwrap();
+
+    let req = utxo::DepositRequest {
+        outpoint: dep.outpoint,
+        max_fee: dep.max_fee,
+        signer_bitmap: BitArray::ZERO,
+        amount: dep.amount,
+        deposit_script: dep.deposit_script.clone(),
+        reclaim_script: dep.reclaim_script.clone(),
+        signers_public_key: dep.signers_public_key,
+    };
+    (deposit_tx, req, dep)
+}
+
 /// Create a new BlockHash stream for messages from bitcoin core over the
 /// ZMQ interface.
 ///
@@ -74,8 +162,10 @@ impl utxo::DepositRequest {
 ///
 /// This function panics if it cannot establish a connection the bitcoin
 /// core in 10 seconds.
-pub async fn new_zmq_block_hash_stream(endpoint: &str) -> ReceiverStream<Result<BlockHash, Error>> {
-    let zmq_stream = BitcoinCoreMessageStream::new_from_endpoint(endpoint)
+pub async fn new_zmq_block_hash_stream(
+    config: &BitcoinConfig,
+) -> ReceiverStream<Result<BlockHash, Error>> {
+    let zmq_stream = BitcoinCoreMessageStream::new_block_hash_stream(config)
         .await
         .unwrap();
 
@@ -89,3 +179,29 @@ pub async fn new_zmq_block_hash_stream(endpoint: &str) -> ReceiverStream<Result<
 
     ReceiverStream::new(receiver)
 }
+
+/// Create a new Block stream for messages from bitcoin core over the ZMQ
+/// interface.
+///
+/// The returned object implements Stream + Send + Sync, which is sometimes
+/// needed in our integration tests.
+///
+/// # Notes
+///
+/// This function panics if it cannot establish a connection the bitcoin
+/// core in 10 seconds.
+pub async fn new_zmq_block_stream(config: &BitcoinConfig) -> ReceiverStream<Result<Block, Error>> {
+    let zmq_stream = BitcoinCoreMessageStream::new_block_stream(config)
+        .await
+        .unwrap();
+
+    let (sender, receiver) = tokio::sync::mpsc::channel(100);
+    tokio::spawn(async move {
+        let mut stream = zmq_stream.to_block_stream();
+        while let Some(block) = stream.next().await {
+            sender.send(block).await.unwrap();
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
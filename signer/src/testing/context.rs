@@ -28,11 +28,12 @@ use crate::{
     bitcoin::{
         BitcoinInteract, MockBitcoinInteract, rpc::GetTxResponse, utxo::UnsignedTransaction,
     },
-    config::Settings,
+    config::{ReloadableConfig, Settings},
     context::{Context, SignerContext, SignerSignal, SignerState, TerminationHandle},
-    emily_client::{EmilyInteract, MockEmilyInteract},
+    emily_client::{EmilyInteract, FetchedDeposits, MockEmilyInteract},
     error::Error,
     keys::PublicKey,
+    network::libp2p::MessageDispatcher,
     stacks::{
         api::{AccountInfo, FeePriority, MockStacksInteract, StacksInteract, SubmitTxResponse},
         contracts::AsTxPayload,
@@ -217,6 +218,10 @@ where
         self.inner.config()
     }
 
+    fn reloadable_config(&self) -> &ReloadableConfig {
+        self.inner.reloadable_config()
+    }
+
     fn state(&self) -> &SignerState {
         self.inner.state()
     }
@@ -237,6 +242,10 @@ where
         self.inner.get_termination_handle()
     }
 
+    fn get_message_dispatcher(&self) -> MessageDispatcher {
+        self.inner.get_message_dispatcher()
+    }
+
     fn get_storage(&self) -> impl DbRead + Clone + Sync + Send + 'static {
         self.inner.get_storage()
     }
@@ -511,14 +520,11 @@ impl EmilyInteract for WrappedMock<MockEmilyInteract> {
             .await
     }
 
-    async fn get_deposits(&self) -> Result<Vec<sbtc::deposits::CreateDepositRequest>, Error> {
+    async fn get_deposits(&self) -> Result<FetchedDeposits, Error> {
         self.inner.lock().await.get_deposits().await
     }
 
-    async fn get_deposits_with_status(
-        &self,
-        status: Status,
-    ) -> Result<Vec<sbtc::deposits::CreateDepositRequest>, Error> {
+    async fn get_deposits_with_status(&self, status: Status) -> Result<FetchedDeposits, Error> {
         self.inner
             .lock()
             .await
@@ -526,6 +532,20 @@ impl EmilyInteract for WrappedMock<MockEmilyInteract> {
             .await
     }
 
+    async fn get_withdrawal(
+        &self,
+        request_id: u64,
+    ) -> Result<Option<emily_client::models::Withdrawal>, Error> {
+        self.inner.lock().await.get_withdrawal(request_id).await
+    }
+
+    async fn get_withdrawals(
+        &self,
+        status: Status,
+    ) -> Result<Vec<emily_client::models::WithdrawalInfo>, Error> {
+        self.inner.lock().await.get_withdrawals(status).await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<emily_client::models::DepositUpdate>,
@@ -724,13 +744,15 @@ where
     }
 
     /// Configure the context to use a [`BitcoinCoreClient`](crate::bitcoin::rpc::BitcoinCoreClient)
-    /// with the first RPC endpoint from the settings.
+    /// with the first RPC endpoint, and its credentials, from the settings.
     fn with_first_bitcoin_core_client(
         self,
     ) -> ContextBuilder<Storage, crate::bitcoin::rpc::BitcoinCoreClient, Stacks, Emily> {
         let config = self.get_config();
-        let url = config.settings.bitcoin.rpc_endpoints.first().unwrap();
-        let bitcoin_client = crate::bitcoin::rpc::BitcoinCoreClient::try_from(url).unwrap();
+        let bitcoin = &config.settings.bitcoin;
+        let url = bitcoin.rpc_endpoints.first().unwrap();
+        let bitcoin_client =
+            crate::bitcoin::rpc::BitcoinCoreClient::new_from_config(bitcoin, url).unwrap();
         ContextBuilder {
             config: ContextConfig {
                 settings: config.settings,
@@ -818,6 +840,18 @@ where
         }
     }
 
+    /// Configure the context to use an [`EmilyClient`](crate::emily_client::EmilyClient)
+    /// for the Emily API at the given URL, such as the one of an
+    /// [`EmilyHandler`](crate::testing::emily::EmilyHandler).
+    fn with_emily_client_url(
+        self,
+        url: &url::Url,
+    ) -> ContextBuilder<Storage, Bitcoin, Stacks, crate::emily_client::EmilyClient> {
+        let emily_client =
+            crate::emily_client::EmilyClient::try_new(url, Duration::from_secs(1), None).unwrap();
+        self.with_emily_client(emily_client)
+    }
+
     /// Configure the context with a mocked Emily client.
     fn with_mocked_emily_client(
         self,
@@ -48,6 +48,8 @@ use wsts::traits::SignerState;
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::utxo::SignerBtcState;
 use crate::bitcoin::utxo::SignerUtxo;
+use crate::bitcoin::validation::BitcoinSweepErrorMsg;
+use crate::bitcoin::validation::BitcoinValidationFailure;
 use crate::bitcoin::validation::TxRequestIds;
 use crate::codec::Encode;
 use crate::ecdsa::Signed;
@@ -55,8 +57,10 @@ use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::keys::SignerScriptPubKey as _;
+use crate::keys::share_encryption;
 use crate::message::BitcoinPreSignAck;
 use crate::message::BitcoinPreSignRequest;
+use crate::message::BitcoinTxRejection;
 use crate::message::SignerMessage;
 use crate::stacks::contracts::AcceptWithdrawalV1;
 use crate::stacks::contracts::CompleteDepositV1;
@@ -257,8 +261,10 @@ pub fn encrypted_dkg_shares<R: rand::RngCore + rand::CryptoRng>(
 
     let encoded = signer_state.encode_to_vec();
 
+    let private_key = PrivateKey::from_slice(signer_private_key).expect("invalid private key");
     let encrypted_private_shares =
-        wsts::util::encrypt(signer_private_key, &encoded, rng).expect("failed to encrypt");
+        share_encryption::encrypt_shares(&private_key, &group_key, &encoded, rng)
+            .expect("failed to encrypt");
     let public_shares: BTreeMap<u32, wsts::net::DkgPublicShares> = BTreeMap::new();
     let public_shares = public_shares.encode_to_vec();
 
@@ -582,6 +588,7 @@ impl fake::Dummy<fake::Faker> for Signed<SignerMessage> {
             inner: config.fake_with_rng(rng),
             signature: pk.sign_ecdsa(&secp256k1::Message::from_digest(digest)),
             signer_public_key: PublicKey::from_private_key(&pk),
+            sequence: config.fake_with_rng(rng),
         }
     }
 }
@@ -704,8 +711,39 @@ impl fake::Dummy<fake::Faker> for BitcoinPreSignRequest {
 }
 
 impl fake::Dummy<fake::Faker> for BitcoinPreSignAck {
-    fn dummy_with_rng<R: rand::RngCore + ?Sized>(_config: &fake::Faker, _rng: &mut R) -> Self {
-        BitcoinPreSignAck {}
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let num_rejected_txs = rng.gen_range(0..5);
+        BitcoinPreSignAck {
+            rejected_txs: (0..num_rejected_txs)
+                .map(|_| config.fake_with_rng(rng))
+                .collect(),
+        }
+    }
+}
+
+impl fake::Dummy<fake::Faker> for BitcoinTxRejection {
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let num_failures = rng.gen_range(0..10);
+        BitcoinTxRejection {
+            txid: config.fake_with_rng::<BitcoinTxId, _>(rng).into(),
+            failures: (0..num_failures)
+                .map(|_| config.fake_with_rng(rng))
+                .collect(),
+        }
+    }
+}
+
+impl fake::Dummy<fake::Faker> for BitcoinValidationFailure {
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let error = if rng.gen_bool(0.5) {
+            BitcoinSweepErrorMsg::Deposit(config.fake_with_rng(rng))
+        } else {
+            BitcoinSweepErrorMsg::Withdrawal(config.fake_with_rng(rng))
+        };
+        BitcoinValidationFailure {
+            index: rng.gen_range(0..1000),
+            error,
+        }
     }
 }
 /// A struct to help with creating dummy values for testing
@@ -0,0 +1,128 @@
+//! Helpers for running the Emily API in process.
+//!
+//! [`EmilyHandler`] serves the routes of the actual `emily-handler` crate
+//! on an ephemeral local port, so tests can exercise the real
+//! [`EmilyClient`] against the real API without a full deployment. The
+//! handler keeps its data in DynamoDB, so these tests still need the
+//! local DynamoDB instance, with the Emily tables, that is started by
+//! `make integration-env-up`.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use emily_client::models::CreateDepositRequestBody;
+use emily_client::models::Deposit;
+use emily_handler::api;
+use emily_handler::context::EmilyContext;
+use testing_emily_client::apis::configuration::ApiKey as TestingEmilyApiKey;
+use testing_emily_client::apis::configuration::Configuration as TestingEmilyApiConfig;
+use testing_emily_client::models::Chainstate;
+use tokio::sync::oneshot;
+use url::Url;
+use warp::Filter as _;
+
+use crate::emily_client::EmilyClient;
+
+/// The endpoint of the local DynamoDB instance, the same one that is used
+/// by the integration tests of Emily itself.
+pub const DYNAMODB_LOCAL_ENDPOINT: &str = "http://localhost:8000";
+
+/// The API key of a local Emily handler. It has the admin role, so it may
+/// call every endpoint, including the testing ones.
+pub const EMILY_API_KEY: &str = "testApiKey";
+
+/// An Emily API server that runs in the current tokio runtime. The server
+/// is shut down when this is dropped.
+#[derive(Debug)]
+pub struct EmilyHandler {
+    /// The address that the server listens on.
+    addr: SocketAddr,
+    /// Sends the signal for the server to shut down.
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl EmilyHandler {
+    /// Start an Emily server backed by the local DynamoDB instance.
+    pub async fn start() -> Self {
+        Self::start_with_dynamodb(DYNAMODB_LOCAL_ENDPOINT).await
+    }
+
+    /// Start an Emily server backed by the DynamoDB instance at the given
+    /// endpoint. The tables are found by name, the same way that the local
+    /// Emily server binary finds them.
+    pub async fn start_with_dynamodb(dynamodb_endpoint: &str) -> Self {
+        let context = EmilyContext::local_instance(dynamodb_endpoint)
+            .await
+            .expect("failed to set up the Emily context");
+
+        let routes = api::routes::routes(context).recover(api::handlers::handle_rejection);
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let signal = async {
+            shutdown_rx.await.ok();
+        };
+        let (addr, server) =
+            warp::serve(routes).bind_with_graceful_shutdown(([127, 0, 0, 1], 0), signal);
+        tokio::spawn(server);
+
+        Self { addr, shutdown: Some(shutdown) }
+    }
+
+    /// The URL of the server, including the API key.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{EMILY_API_KEY}@{}", self.addr))
+            .expect("the server address is a valid URL")
+    }
+
+    /// Create a client for the server.
+    pub fn client(&self) -> EmilyClient {
+        EmilyClient::try_new(&self.url(), Duration::from_secs(1), None)
+            .expect("the server URL is a valid Emily URL")
+    }
+
+    /// The configuration for the testing client of the server, which can
+    /// call the endpoints that the signer's client does not use.
+    pub fn testing_config(&self) -> TestingEmilyApiConfig {
+        TestingEmilyApiConfig {
+            base_path: format!("http://{}", self.addr),
+            api_key: Some(TestingEmilyApiKey {
+                prefix: None,
+                key: EMILY_API_KEY.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Remove everything from the Emily tables.
+    pub async fn wipe_databases(&self) {
+        testing_emily_client::apis::testing_api::wipe_databases(&self.testing_config())
+            .await
+            .expect("failed to wipe the Emily tables");
+    }
+
+    /// Tell Emily about the given deposit.
+    pub async fn seed_deposit(&self, body: CreateDepositRequestBody) -> Deposit {
+        emily_client::apis::deposit_api::create_deposit(self.client().config(), body)
+            .await
+            .expect("failed to create the deposit in Emily")
+    }
+
+    /// Tell Emily about the given chainstate.
+    pub async fn seed_chainstate(&self, chainstate: Chainstate) -> Chainstate {
+        testing_emily_client::apis::chainstate_api::set_chainstate(
+            &self.testing_config(),
+            chainstate,
+        )
+        .await
+        .expect("failed to set the chainstate in Emily")
+    }
+}
+
+impl Drop for EmilyHandler {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            // The server may already be gone, which is fine.
+            let _ = shutdown.send(());
+        }
+    }
+}
@@ -0,0 +1,578 @@
+//! A harness for end-to-end tests with several signers.
+//!
+//! A [`SignerCluster`] runs the block observer, transaction coordinator,
+//! transaction signer and request decider event loops of each signer in
+//! the current tokio runtime. Each signer has its own postgres database,
+//! the signers talk to each other over an in-process network, they all
+//! use the bitcoin-core node of the local devnet and an in-process
+//! [`EmilyHandler`], and they share a mocked stacks node.
+//!
+//! A typical scenario looks like this:
+//!
+//! ```ignore
+//! let cluster = SignerCluster::builder().start().await;
+//! cluster.run_dkg().await;
+//! cluster.fund_signers(100_000).await;
+//!
+//! let deposit = cluster.make_deposit(2_500_000).await;
+//! cluster.mine(1).await;
+//! let sweep_tx = cluster.wait_for_sweep().await;
+//!
+//! cluster.shutdown().await;
+//! ```
+//!
+//! These tests need the bitcoin-core node and the DynamoDB and postgres
+//! instances that are started by `make integration-env-up`.
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use bitcoin::Address;
+use bitcoin::AddressType;
+use bitcoin::Amount;
+use bitcoin::BlockHash;
+use bitcoin::OutPoint;
+use bitcoin::Transaction;
+use bitcoincore_rpc::RpcApi as _;
+use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
+use blockstack_lib::chainstate::nakamoto::NakamotoBlockHeader;
+use blockstack_lib::chainstate::stacks::StacksTransaction;
+use blockstack_lib::net::api::getpoxinfo::RPCPoxInfoData;
+use blockstack_lib::net::api::getsortition::SortitionInfo;
+use lru::LruCache;
+use sbtc::testing::regtest;
+use sbtc::testing::regtest::Faucet;
+use sbtc::testing::regtest::Recipient;
+use secp256k1::Keypair;
+use stacks_common::types::chainstate::BurnchainHeaderHash;
+use stacks_common::types::chainstate::ConsensusHash;
+use stacks_common::types::chainstate::SortitionId;
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+
+use crate::bitcoin::rpc::BitcoinCoreClient;
+use crate::bitcoin::utxo;
+use crate::bitcoin::validation::ReportCache;
+use crate::block_observer::BlockObserver;
+use crate::context::Context as _;
+use crate::context::SignerSignal;
+use crate::context::TxCoordinatorEvent;
+use crate::emily_client::EmilyClient;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
+use crate::network::in_memory2::SignerNetwork;
+use crate::network::in_memory2::WanNetwork;
+use crate::request_decider::RequestDeciderEventLoop;
+use crate::stacks::api::AccountInfo;
+use crate::stacks::api::MockStacksInteract;
+use crate::stacks::api::SubmitTxResponse;
+use crate::stacks::api::TenureBlocks;
+use crate::storage::DbRead as _;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::postgres::PgStore;
+use crate::testing;
+use crate::testing::context::*;
+use crate::testing::emily::EmilyHandler;
+use crate::testing::stacks::DUMMY_TENURE_INFO;
+use crate::transaction_coordinator::TxCoordinatorEventLoop;
+use crate::transaction_signer::TxSignerEventLoop;
+use crate::wsts_state_machine::RoundTracker;
+
+const GET_POX_INFO_JSON: &str =
+    include_str!("../../tests/fixtures/stacksapi-get-pox-info-test-data.json");
+
+/// How long to wait for the signers to do something before giving up.
+const CLUSTER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The context of each signer in a [`SignerCluster`].
+pub type ClusterContext =
+    TestContext<PgStore, BitcoinCoreClient, WrappedMock<MockStacksInteract>, EmilyClient>;
+
+/// A signer in a [`SignerCluster`].
+pub struct ClusterSigner {
+    /// The context of the signer.
+    pub context: ClusterContext,
+    /// The database of the signer.
+    pub storage: PgStore,
+    /// The keypair of the signer.
+    pub keypair: Keypair,
+    /// The connection of the signer to the other signers.
+    network: SignerNetwork,
+}
+
+/// A builder for a [`SignerCluster`].
+#[derive(Debug, Clone)]
+pub struct SignerClusterBuilder {
+    keypairs: Vec<Keypair>,
+    signatures_required: u16,
+}
+
+impl Default for SignerClusterBuilder {
+    /// The signers of the bootstrap wallet in the default config, so three
+    /// signers where two signatures are required.
+    fn default() -> Self {
+        let (wallet, keypairs) = testing::wallet::regtest_bootstrap_wallet();
+        Self {
+            keypairs: keypairs.to_vec(),
+            signatures_required: wallet.signatures_required(),
+        }
+    }
+}
+
+impl SignerClusterBuilder {
+    /// Use the given number of signers with random keys.
+    pub fn num_signers(mut self, num_signers: usize) -> Self {
+        self.keypairs = std::iter::repeat_with(|| Keypair::new_global(&mut rand::rngs::OsRng))
+            .take(num_signers)
+            .collect();
+        self
+    }
+
+    /// Set the number of signatures that are required to sign a
+    /// transaction.
+    pub fn signatures_required(mut self, signatures_required: u16) -> Self {
+        self.signatures_required = signatures_required;
+        self
+    }
+
+    /// Set up the signers and start all of their event loops.
+    ///
+    /// Each signer starts off with the canonical bitcoin blockchain in its
+    /// database. The signers have not run DKG, and they see the sBTC
+    /// contracts as deployed.
+    pub async fn start(self) -> SignerCluster {
+        let (rpc, faucet) = regtest::initialize_blockchain();
+
+        let emily = EmilyHandler::start().await;
+        emily.wipe_databases().await;
+
+        let chain_tip_info = rpc.get_chain_tips().unwrap().pop().unwrap();
+        let (stacks_txs, _) = broadcast::channel(100);
+        let wan = WanNetwork::default();
+
+        let signing_set: Vec<PublicKey> = self
+            .keypairs
+            .iter()
+            .map(|keypair| keypair.public_key().into())
+            .collect();
+
+        let mut signers = Vec::new();
+        for keypair in self.keypairs {
+            let storage = testing::storage::new_test_database().await;
+            let mut context = TestContext::builder()
+                .with_storage(storage.clone())
+                .with_first_bitcoin_core_client()
+                .with_emily_client_url(&emily.url())
+                .with_mocked_stacks_client()
+                .modify_settings(|settings| {
+                    settings.signer.private_key = keypair.secret_key().into();
+                    settings.signer.bootstrap_signing_set = signing_set.clone();
+                    settings.signer.bootstrap_signatures_required = self.signatures_required;
+                })
+                .build();
+
+            testing::storage::backfill_bitcoin_blocks(&storage, rpc, &chain_tip_info.hash).await;
+
+            let chain_tip = BitcoinBlockHash::from(chain_tip_info.hash);
+            mock_stacks_client(&mut context, chain_tip, chain_tip_info.height, &stacks_txs).await;
+            context.state().set_sbtc_contracts_deployed();
+
+            let network = wan.connect(&context);
+            signers.push(ClusterSigner {
+                context,
+                storage,
+                keypair,
+                network,
+            });
+        }
+
+        let mut cluster = SignerCluster {
+            rpc,
+            faucet,
+            emily,
+            signers,
+            stacks_txs,
+            tasks: JoinSet::new(),
+            _wan: wan,
+        };
+        cluster.spawn_event_loops().await;
+        cluster
+    }
+}
+
+/// A set of signers that run in the current tokio runtime, see the
+/// [module docs](self) for an example.
+///
+/// The event loops of the signers are aborted when this is dropped, but
+/// the databases are only dropped by [`SignerCluster::shutdown`].
+pub struct SignerCluster {
+    rpc: &'static bitcoincore_rpc::Client,
+    faucet: &'static Faucet,
+    emily: EmilyHandler,
+    signers: Vec<ClusterSigner>,
+    stacks_txs: broadcast::Sender<StacksTransaction>,
+    tasks: JoinSet<()>,
+    /// The signers' networks only need the sender of this, but we keep it
+    /// around for as long as the signers are running.
+    _wan: WanNetwork,
+}
+
+impl SignerCluster {
+    /// Create a new builder for a cluster.
+    pub fn builder() -> SignerClusterBuilder {
+        SignerClusterBuilder::default()
+    }
+
+    /// The signers in the cluster.
+    pub fn signers(&self) -> &[ClusterSigner] {
+        &self.signers
+    }
+
+    /// The RPC client of the bitcoin-core node.
+    pub fn rpc(&self) -> &'static bitcoincore_rpc::Client {
+        self.rpc
+    }
+
+    /// The faucet that funds everything in the cluster.
+    pub fn faucet(&self) -> &'static Faucet {
+        self.faucet
+    }
+
+    /// The Emily server that the signers use.
+    pub fn emily(&self) -> &EmilyHandler {
+        &self.emily
+    }
+
+    /// Subscribe to the stacks transactions that the signers submit to
+    /// the mocked stacks node.
+    pub fn stacks_transactions(&self) -> broadcast::Receiver<StacksTransaction> {
+        self.stacks_txs.subscribe()
+    }
+
+    async fn spawn_event_loops(&mut self) {
+        let start_count = Arc::new(AtomicUsize::new(0));
+
+        for signer in self.signers.iter() {
+            let ctx = &signer.context;
+            let private_key = signer.keypair.secret_key().into();
+            let threshold = ctx.config().signer.bootstrap_signatures_required;
+
+            let ev = TxCoordinatorEventLoop {
+                network: signer.network.spawn(),
+                context: ctx.clone(),
+                context_window: 10000,
+                private_key,
+                signing_round_max_duration: Duration::from_secs(10),
+                bitcoin_presign_request_max_duration: Duration::from_secs(10),
+                threshold,
+                dkg_max_duration: Duration::from_secs(10),
+                is_epoch3: true,
+            };
+            let counter = start_count.clone();
+            self.tasks.spawn(async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                ev.run().await.unwrap();
+            });
+
+            let ev = TxSignerEventLoop {
+                network: signer.network.spawn(),
+                threshold: threshold as u32,
+                context: ctx.clone(),
+                context_window: 10000,
+                wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+                signer_private_key: private_key,
+                rng: rand::rngs::OsRng,
+                dkg_begin_pause: None,
+                dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+                report_cache: ReportCache::default(),
+                round_tracker: RoundTracker::default(),
+                presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            };
+            let counter = start_count.clone();
+            self.tasks.spawn(async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                ev.run().await.unwrap();
+            });
+
+            let ev = RequestDeciderEventLoop {
+                network: signer.network.spawn(),
+                context: ctx.clone(),
+                context_window: 10000,
+                deposit_decisions_retry_window: 1,
+                withdrawal_decisions_retry_window: 1,
+                blocklist_checker: Some(()),
+                signer_private_key: private_key,
+            };
+            let counter = start_count.clone();
+            self.tasks.spawn(async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                ev.run().await.unwrap();
+            });
+
+            let block_observer = BlockObserver {
+                context: ctx.clone(),
+                bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin)
+                    .await,
+            };
+            let counter = start_count.clone();
+            self.tasks.spawn(async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+                block_observer.run().await.unwrap();
+            });
+        }
+
+        while start_count.load(Ordering::SeqCst) < self.signers.len() * 4 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Mine the given number of blocks, and wait for every signer to
+    /// process the new chain tip and to complete its tenure.
+    pub async fn mine(&self, num_blocks: u64) -> Vec<BlockHash> {
+        // We subscribe before mining so that we do not miss any signals.
+        let receivers = self
+            .signers
+            .iter()
+            .map(|signer| signer.context.get_signal_receiver())
+            .collect::<Vec<_>>();
+
+        let block_hashes = self.faucet.generate_blocks(num_blocks);
+        let chain_tip = BitcoinBlockHash::from(*block_hashes.last().unwrap());
+
+        let futures = self
+            .signers
+            .iter()
+            .map(|signer| testing::storage::wait_for_chain_tip(&signer.storage, chain_tip));
+        futures::future::join_all(futures).await;
+
+        futures::future::join_all(receivers.into_iter().map(wait_for_tenure)).await;
+        block_hashes
+    }
+
+    /// Mine a block to kick off DKG, wait for every signer to store the
+    /// resulting shares, and return the new aggregate key.
+    pub async fn run_dkg(&self) -> PublicKey {
+        let dkg_shares_count = self.signers[0]
+            .storage
+            .get_encrypted_dkg_shares_count()
+            .await
+            .unwrap();
+
+        self.mine(1).await;
+
+        let futures = self
+            .signers
+            .iter()
+            .map(|signer| testing::storage::wait_for_dkg(&signer.storage, dkg_shares_count + 1));
+        futures::future::join_all(futures).await;
+
+        self.aggregate_key().await
+    }
+
+    /// The aggregate key from the latest DKG run of the first signer.
+    ///
+    /// This panics if the signers have not run DKG.
+    pub async fn aggregate_key(&self) -> PublicKey {
+        self.signers[0]
+            .storage
+            .get_latest_encrypted_dkg_shares()
+            .await
+            .unwrap()
+            .expect("the signers have not run DKG")
+            .aggregate_key
+    }
+
+    /// Send the given amount to the signers' current aggregate key, so
+    /// that they have a UTXO to sweep funds into. The transaction is
+    /// confirmed in the next mined block.
+    pub async fn fund_signers(&self, amount: u64) -> OutPoint {
+        let script_pubkey = self.aggregate_key().await.signers_script_pubkey();
+        let address = Address::from_script(&script_pubkey, bitcoin::Network::Regtest).unwrap();
+        self.faucet.send_to(amount, &address)
+    }
+
+    /// Make a deposit of the given amount to the signers' current
+    /// aggregate key, and tell Emily about it.
+    ///
+    /// This mines a block to confirm the funds of a new depositor. The
+    /// deposit itself is left in the mempool, so the signers pick it up
+    /// once another block is mined.
+    pub async fn make_deposit(&self, amount: u64) -> utxo::DepositRequest {
+        let depositor = Recipient::new(AddressType::P2tr);
+        self.faucet.send_to(amount + 100_000, &depositor.address);
+        self.mine(1).await;
+
+        let utxo = depositor.get_utxos(self.rpc, None).pop().unwrap();
+        let signers_public_key = self.aggregate_key().await.into();
+        let (deposit_tx, deposit_request, _) = testing::btc::make_deposit_request(
+            &depositor,
+            amount,
+            utxo,
+            amount / 2,
+            signers_public_key,
+        );
+        self.rpc.send_raw_transaction(&deposit_tx).unwrap();
+
+        self.emily
+            .seed_deposit(deposit_request.as_emily_request(&deposit_tx))
+            .await;
+        deposit_request
+    }
+
+    /// Wait for a sweep transaction of the signers to show up in the
+    /// mempool and return it.
+    ///
+    /// A sweep transaction is one where the first output is locked by the
+    /// signers' current aggregate key and the second output is the
+    /// OP_RETURN output, which rules out plain donations.
+    pub async fn wait_for_sweep(&self) -> Transaction {
+        let script_pubkey = self.aggregate_key().await.signers_script_pubkey();
+        let is_sweep = |tx: &Transaction| {
+            tx.output.first().map(|out| &out.script_pubkey) == Some(&script_pubkey)
+                && tx
+                    .output
+                    .get(1)
+                    .is_some_and(|out| out.script_pubkey.is_op_return())
+        };
+
+        let waiting_fut = async {
+            loop {
+                for txid in self.rpc.get_raw_mempool().unwrap() {
+                    let tx = self.rpc.get_raw_transaction(&txid, None).unwrap();
+                    if is_sweep(&tx) {
+                        return tx;
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        };
+
+        tokio::time::timeout(CLUSTER_TIMEOUT, waiting_fut)
+            .await
+            .expect("the signers did not broadcast a sweep transaction")
+    }
+
+    /// Stop the event loops of the signers and drop their databases.
+    pub async fn shutdown(mut self) {
+        self.tasks.shutdown().await;
+        for signer in std::mem::take(&mut self.signers) {
+            testing::storage::drop_db(signer.storage).await;
+        }
+    }
+}
+
+/// Wait for the signer of the given receiver to complete its tenure.
+async fn wait_for_tenure(mut receiver: broadcast::Receiver<SignerSignal>) {
+    let expected: SignerSignal = TxCoordinatorEvent::TenureCompleted.into();
+    let waiting_fut = async {
+        loop {
+            match receiver.recv().await {
+                Ok(signal) if signal == expected => break,
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => panic!("the signal channel closed"),
+            }
+        }
+    };
+
+    tokio::time::timeout(CLUSTER_TIMEOUT, waiting_fut)
+        .await
+        .expect("the signer did not complete its tenure");
+}
+
+/// Set up the mocked stacks client of a signer so that the signers can
+/// run DKG, rotate their keys and sweep deposits.
+///
+/// The stacks node has no record of a rotate keys transaction, so the
+/// coordinator submits one, and all submitted transactions are sent to
+/// the given channel.
+async fn mock_stacks_client(
+    context: &mut ClusterContext,
+    chain_tip: BitcoinBlockHash,
+    chain_tip_height: u64,
+    stacks_txs: &broadcast::Sender<StacksTransaction>,
+) {
+    let stacks_txs = stacks_txs.clone();
+
+    context
+        .with_stacks_client(|client| {
+            client
+                .expect_get_tenure_info()
+                .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+            client.expect_get_block().returning(|_| {
+                let response = Ok(NakamotoBlock {
+                    header: NakamotoBlockHeader::empty(),
+                    txs: vec![],
+                });
+                Box::pin(std::future::ready(response))
+            });
+
+            client.expect_get_tenure().returning(move |_| {
+                let mut tenure = TenureBlocks::nearly_empty().unwrap();
+                tenure.anchor_block_hash = chain_tip;
+                Box::pin(std::future::ready(Ok(tenure)))
+            });
+
+            client.expect_get_pox_info().returning(|| {
+                let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                    .map_err(Error::JsonSerialize);
+                Box::pin(std::future::ready(response))
+            });
+
+            client
+                .expect_estimate_fees()
+                .returning(|_, _, _| Box::pin(std::future::ready(Ok(25))));
+
+            client.expect_get_account().returning(|_| {
+                let response = Ok(AccountInfo {
+                    balance: 0,
+                    locked: 0,
+                    unlock_height: 0u64.into(),
+                    nonce: 12,
+                });
+                Box::pin(std::future::ready(response))
+            });
+
+            client.expect_get_sortition_info().returning(move |_| {
+                let response = Ok(SortitionInfo {
+                    burn_block_hash: BurnchainHeaderHash::from(chain_tip),
+                    burn_block_height: chain_tip_height,
+                    burn_header_timestamp: 0,
+                    sortition_id: SortitionId([0; 32]),
+                    parent_sortition_id: SortitionId([0; 32]),
+                    consensus_hash: ConsensusHash([0; 20]),
+                    was_sortition: true,
+                    miner_pk_hash160: None,
+                    stacks_parent_ch: None,
+                    last_sortition_ch: None,
+                    committed_block_hash: None,
+                });
+                Box::pin(std::future::ready(response))
+            });
+
+            client
+                .expect_get_current_signers_aggregate_key()
+                .returning(move |_| Box::pin(std::future::ready(Ok(None))));
+
+            client.expect_submit_tx().returning(move |tx| {
+                let tx = tx.clone();
+                let txid = tx.txid();
+                // Nobody may be listening, which is fine.
+                let _ = stacks_txs.send(tx);
+                Box::pin(std::future::ready(Ok(SubmitTxResponse::Acceptance(txid))))
+            });
+
+            client
+                .expect_get_sbtc_total_supply()
+                .returning(move |_| Box::pin(std::future::ready(Ok(Amount::ZERO))));
+
+            client
+                .expect_is_deposit_completed()
+                .returning(move |_, _| Box::pin(std::future::ready(Ok(false))));
+        })
+        .await;
+}
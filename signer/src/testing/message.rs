@@ -52,6 +52,7 @@ impl fake::Dummy<fake::Faker> for message::Payload {
             dummy_payload::<message::StacksTransactionSignature, _>,
             dummy_payload::<message::WstsMessage, _>,
             dummy_payload::<message::BitcoinPreSignRequest, _>,
+            dummy_payload::<message::RoundAbort, _>,
         ];
         variants.choose(rng).unwrap()(config, rng)
     }
@@ -113,11 +114,28 @@ impl fake::Dummy<fake::Faker> for message::WstsMessage {
 
         Self {
             id: dummy::txid(config, rng).into(),
+            session_id: config.fake_with_rng(rng),
+            sighash: config.fake_with_rng(rng),
             inner: wsts::net::Message::DkgEndBegin(dkg_end_begin),
         }
     }
 }
 
+impl fake::Dummy<fake::Faker> for message::RoundAbort {
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let round_id = match rng.next_u32() % 3 {
+            0 => dummy::txid(config, rng).into(),
+            1 => message::WstsMessageId::DkgVerification(config.fake_with_rng(rng)),
+            _ => message::WstsMessageId::Dkg(config.fake_with_rng(rng)),
+        };
+
+        Self {
+            round_id,
+            reason: message::RoundAbortReason::Timeout,
+        }
+    }
+}
+
 fn dummy_payload<P, R>(config: &fake::Faker, rng: &mut R) -> message::Payload
 where
     P: Into<message::Payload> + fake::Dummy<fake::Faker>,
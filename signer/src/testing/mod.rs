@@ -8,11 +8,14 @@ pub mod blocks;
 pub mod btc;
 pub mod context;
 pub mod dummy;
+pub mod emily;
+pub mod harness;
 pub mod message;
 pub mod network;
 pub mod request_decider;
 pub mod stacks;
 pub mod storage;
+pub mod sweep;
 pub mod transaction_coordinator;
 pub mod transaction_signer;
 pub mod wallet;
@@ -31,14 +34,11 @@ use rand::rngs::{OsRng, StdRng};
 use crate::bitcoin::utxo::UnsignedTransaction;
 use crate::config::Settings;
 
-/// The path for the configuration file that we should use during testing.
-pub const DEFAULT_CONFIG_PATH: Option<&str> = Some("./src/config/default");
-
 impl Settings {
     /// Create a new `Settings` instance from the default configuration file.
     /// This is useful for testing.
     pub fn new_from_default_config() -> Result<Self, config::ConfigError> {
-        Self::new(DEFAULT_CONFIG_PATH)
+        Self::new_from_embedded_default()
     }
 }
 
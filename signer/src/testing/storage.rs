@@ -3,12 +3,16 @@
 use std::future::Future;
 use std::time::Duration;
 
+use bitcoincore_rpc::RpcApi as _;
+use sbtc::testing::regtest;
+
 use crate::storage::model::{
     BitcoinBlock, BitcoinBlockHash, BitcoinBlockRef, StacksBlock, StacksBlockHash,
 };
 use crate::storage::postgres::PgStore;
 use crate::storage::{DbRead, DbWrite};
 
+pub mod conformance;
 pub mod model;
 pub mod postgres;
 
@@ -105,6 +109,31 @@ pub async fn drop_db(store: PgStore) {
     }
 }
 
+/// Fetch all block headers from bitcoin-core and store it in the database.
+pub async fn backfill_bitcoin_blocks(
+    db: &PgStore,
+    rpc: &bitcoincore_rpc::Client,
+    chain_tip: &bitcoin::BlockHash,
+) {
+    let mut block_header = rpc.get_block_header_info(chain_tip).unwrap();
+
+    // There are no non-coinbase transactions below this height.
+    while block_header.height as u64 >= regtest::MIN_BLOCKCHAIN_HEIGHT {
+        let parent_header_hash = block_header.previous_block_hash.unwrap();
+        let bitcoin_block = BitcoinBlock {
+            block_hash: block_header.hash.into(),
+            block_height: (block_header.height as u64).into(),
+            parent_hash: parent_header_hash.into(),
+        };
+
+        db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        block_header = rpc.get_block_header_info(&parent_header_hash).unwrap();
+    }
+
+    let block_hash = db.get_bitcoin_canonical_chain_tip().await.unwrap().unwrap();
+    assert_eq!(*block_hash, *chain_tip);
+}
+
 /// This is a helper function for waiting for the database to be up-to-date
 /// with the chain-tip of the bitcoin blockchain.
 ///
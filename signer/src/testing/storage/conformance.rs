@@ -0,0 +1,572 @@
+//! A storage conformance suite shared by all [`DbRead`] and [`DbWrite`]
+//! implementations.
+//!
+//! Each function in this module sets up some state in an empty store and
+//! asserts on what the store returns. They are run against both the
+//! in-memory store and postgres, so that tests using the in-memory store
+//! exercise the same canonical-chain semantics as the signer does in
+//! production.
+
+use std::collections::HashSet;
+
+use bitcoin::hashes::Hash as _;
+use fake::Fake as _;
+use fake::Faker;
+
+use crate::keys::PublicKey;
+use crate::storage::DbRead;
+use crate::storage::DbWrite;
+use crate::storage::model;
+use crate::storage::model::BitcoinBlock;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::StacksBlock;
+use crate::testing::blocks::BitcoinChain;
+use crate::testing::blocks::StacksChain;
+use crate::testing::storage::DbWriteTestExt as _;
+
+/// Write a transaction confirmed in the given bitcoin block.
+async fn confirm_bitcoin_tx<D>(db: &D, txid: BitcoinTxId, block_hash: BitcoinBlockHash)
+where
+    D: DbWrite,
+{
+    let tx = model::Transaction {
+        tx_type: model::TransactionType::SbtcTransaction,
+        txid: txid.to_byte_array(),
+        block_hash: block_hash.to_byte_array(),
+    };
+    db.write_transaction(&tx).await.unwrap();
+    let tx_ref = model::BitcoinTxRef { txid, block_hash };
+    db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+}
+
+/// Write a deposit request that is confirmed in the given bitcoin block
+/// and return it.
+async fn write_deposit_request<D>(db: &D, block_hash: BitcoinBlockHash) -> model::DepositRequest
+where
+    D: DbWrite,
+{
+    let deposit: model::DepositRequest = Faker.fake();
+    confirm_bitcoin_tx(db, deposit.txid, block_hash).await;
+    db.write_deposit_request(&deposit).await.unwrap();
+    deposit
+}
+
+/// Write a withdrawal request that was created in the given stacks block
+/// and return it.
+async fn write_withdrawal_request<D>(db: &D, block: &StacksBlock) -> model::WithdrawalRequest
+where
+    D: DbWrite,
+{
+    let withdrawal = model::WithdrawalRequest {
+        block_hash: block.block_hash,
+        ..Faker.fake()
+    };
+    db.write_withdrawal_request(&withdrawal).await.unwrap();
+    withdrawal
+}
+
+/// The canonical bitcoin chain tip is the block with the greatest height.
+/// Ties are broken by taking the block with the greatest hash, where
+/// hashes are compared by their bytes.
+pub async fn bitcoin_chain_tip_ties_break_on_block_hash<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let chain = BitcoinChain::new_with_length(3);
+    db.write_bitcoin_blocks(&chain).await;
+
+    let fork_a = chain.chain_tip().new_child();
+    let fork_b = chain.chain_tip().new_child();
+    db.write_bitcoin_blocks([&fork_a, &fork_b]).await;
+
+    let expected = std::cmp::max_by_key(&fork_a, &fork_b, |block| *block.block_hash.as_ref());
+
+    let chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap();
+    assert_eq!(chain_tip, Some(expected.block_hash));
+
+    let chain_tip_ref = db.get_bitcoin_canonical_chain_tip_ref().await.unwrap();
+    assert_eq!(chain_tip_ref, Some(model::BitcoinBlockRef::from(expected)));
+}
+
+/// The stacks chain tip follows the bitcoin fork that it is asked about,
+/// even when another fork has a taller stacks chain.
+pub async fn stacks_chain_tip_follows_bitcoin_fork<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    // The bitcoin chain forks right after genesis. Fork `a` has two blocks
+    // and fork `b` has three blocks, so `b` is canonical.
+    let genesis = BitcoinBlock::new_genesis();
+    let bitcoin_1a = genesis.new_child();
+    let bitcoin_2a = bitcoin_1a.new_child();
+    let bitcoin_1b = genesis.new_child();
+    let bitcoin_2b = bitcoin_1b.new_child();
+    let bitcoin_3b = bitcoin_2b.new_child();
+
+    // The stacks chain on fork `a` is taller than the one on fork `b`.
+    let stacks_genesis = StacksBlock::new_genesis().anchored_to(&genesis);
+    let stacks_1a = stacks_genesis.new_child().anchored_to(&bitcoin_1a);
+    let stacks_2a = stacks_1a.new_child().anchored_to(&bitcoin_2a);
+    let stacks_3a = stacks_2a.new_child().anchored_to(&bitcoin_2a);
+    let stacks_4a = stacks_3a.new_child().anchored_to(&bitcoin_2a);
+    let stacks_1b = stacks_genesis.new_child().anchored_to(&bitcoin_3b);
+
+    db.write_blocks(
+        [
+            &genesis,
+            &bitcoin_1a,
+            &bitcoin_2a,
+            &bitcoin_1b,
+            &bitcoin_2b,
+            &bitcoin_3b,
+        ],
+        [
+            &stacks_genesis,
+            &stacks_1a,
+            &stacks_2a,
+            &stacks_3a,
+            &stacks_4a,
+            &stacks_1b,
+        ],
+    )
+    .await;
+
+    let chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap();
+    assert_eq!(chain_tip, Some(bitcoin_3b.block_hash));
+
+    let stacks_tip = db.get_stacks_chain_tip(&bitcoin_3b.block_hash).await;
+    assert_eq!(stacks_tip.unwrap(), Some(stacks_1b));
+
+    let stacks_tip = db.get_stacks_chain_tip(&bitcoin_2a.block_hash).await;
+    assert_eq!(stacks_tip.unwrap(), Some(stacks_4a));
+
+    let stacks_tip = db.get_stacks_chain_tip(&genesis.block_hash).await;
+    assert_eq!(stacks_tip.unwrap(), Some(stacks_genesis));
+}
+
+/// Pending deposit requests are those confirmed in the last
+/// `context_window` blocks of the canonical chain that the signer has not
+/// voted on yet.
+pub async fn pending_deposit_requests_respect_context_window<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let chain = BitcoinChain::new_with_length(5);
+    db.write_bitcoin_blocks(&chain).await;
+
+    let mut deposits = Vec::new();
+    for block in &chain {
+        deposits.push(write_deposit_request(db, block.block_hash).await);
+    }
+
+    // A deposit request confirmed on a stale fork is never pending.
+    let fork = chain.nth_block(2u64.into()).new_child();
+    db.write_bitcoin_block(&fork).await.unwrap();
+    write_deposit_request(db, fork.block_hash).await;
+
+    // Writing the same transaction twice should not make the deposit
+    // request show up twice.
+    let tip_deposit = deposits.last().unwrap();
+    confirm_bitcoin_tx(db, tip_deposit.txid, chain.chain_tip().block_hash).await;
+
+    let signer_public_key: PublicKey = Faker.fake();
+    let voted = &deposits[3];
+    let decision = model::DepositSigner {
+        txid: voted.txid,
+        output_index: voted.output_index,
+        signer_pub_key: signer_public_key,
+        ..Faker.fake()
+    };
+    db.write_deposit_signer_decision(&decision).await.unwrap();
+
+    let chain_tip = chain.chain_tip().block_hash;
+    let context_window = 3;
+    let pending = db
+        .get_pending_deposit_requests(&chain_tip, context_window, &signer_public_key)
+        .await
+        .unwrap();
+
+    assert_eq!(pending.len(), 2);
+    let actual: HashSet<_> = pending
+        .iter()
+        .map(|req| (req.txid, req.output_index))
+        .collect();
+    let expected: HashSet<_> = [&deposits[2], &deposits[4]]
+        .into_iter()
+        .map(|req| (req.txid, req.output_index))
+        .collect();
+    assert_eq!(actual, expected);
+}
+
+/// Pending withdrawal requests are those created in stacks blocks on the
+/// canonical stacks chain that are anchored to the last
+/// `context_window + 1` blocks of the canonical bitcoin chain, and that
+/// the signer has not voted on yet.
+pub async fn pending_withdrawal_requests_respect_context_window<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let bitcoin_chain = BitcoinChain::new_with_length(6);
+    let stacks_chain = StacksChain::new_anchored(&bitcoin_chain);
+    db.write_blocks(&bitcoin_chain, &stacks_chain).await;
+
+    let mut withdrawals = Vec::new();
+    for block in &stacks_chain {
+        withdrawals.push(write_withdrawal_request(db, block).await);
+    }
+
+    let signer_public_key: PublicKey = Faker.fake();
+    let voted = &withdrawals[5];
+    let decision = model::WithdrawalSigner {
+        request_id: voted.request_id,
+        txid: voted.txid,
+        block_hash: voted.block_hash,
+        signer_pub_key: signer_public_key,
+        ..Faker.fake()
+    };
+    db.write_withdrawal_signer_decision(&decision)
+        .await
+        .unwrap();
+
+    let chain_tip = bitcoin_chain.chain_tip().block_hash;
+    let context_window = 2;
+    let pending = db
+        .get_pending_withdrawal_requests(&chain_tip, context_window, &signer_public_key)
+        .await
+        .unwrap();
+
+    let actual: HashSet<_> = pending.iter().map(|req| req.qualified_id()).collect();
+    let expected: HashSet<_> = [&withdrawals[3], &withdrawals[4]]
+        .into_iter()
+        .map(|req| req.qualified_id())
+        .collect();
+    assert_eq!(pending.len(), 2);
+    assert_eq!(actual, expected);
+}
+
+/// Writing the same stacks block events more than once has the same
+/// effect as writing them once.
+pub async fn stacks_block_events_are_idempotent<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let bitcoin_chain = BitcoinChain::new_with_length(2);
+    let stacks_chain = StacksChain::new_anchored(&bitcoin_chain);
+    db.write_blocks(&bitcoin_chain, &stacks_chain).await;
+
+    let withdrawal = model::WithdrawalRequest {
+        block_hash: stacks_chain.chain_tip().block_hash,
+        ..Faker.fake()
+    };
+    let events = model::StacksBlockEvents {
+        withdrawal_requests: vec![withdrawal.clone()],
+        ..Default::default()
+    };
+
+    db.write_stacks_block_events(&events).await.unwrap();
+    db.write_stacks_block_events(&events).await.unwrap();
+
+    let signer_public_key: PublicKey = Faker.fake();
+    let chain_tip = bitcoin_chain.chain_tip().block_hash;
+    let pending = db
+        .get_pending_withdrawal_requests(&chain_tip, 10, &signer_public_key)
+        .await
+        .unwrap();
+
+    assert_eq!(pending, vec![withdrawal]);
+}
+
+/// Signer decisions buffered before we have a record of their request are
+/// moved into the signers tables once the request is written, and buffered
+/// decisions can be expired.
+pub async fn pending_signer_decisions_are_replayed_once_requests_exist<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let bitcoin_chain = BitcoinChain::new_with_length(2);
+    let stacks_chain = StacksChain::new_anchored(&bitcoin_chain);
+    db.write_blocks(&bitcoin_chain, &stacks_chain).await;
+
+    let deposit: model::DepositRequest = Faker.fake();
+    let deposit_decision = model::DepositSigner {
+        txid: deposit.txid,
+        output_index: deposit.output_index,
+        ..Faker.fake()
+    };
+    let withdrawal = model::WithdrawalRequest {
+        block_hash: stacks_chain.chain_tip().block_hash,
+        ..Faker.fake()
+    };
+    let withdrawal_decision = model::WithdrawalSigner {
+        request_id: withdrawal.request_id,
+        txid: withdrawal.txid,
+        block_hash: withdrawal.block_hash,
+        ..Faker.fake()
+    };
+
+    // Writing the same decision twice only buffers it once.
+    for _ in 0..2 {
+        db.write_pending_deposit_signer_decision(&deposit_decision)
+            .await
+            .unwrap();
+        db.write_pending_withdrawal_signer_decision(&withdrawal_decision)
+            .await
+            .unwrap();
+    }
+    assert_eq!(db.get_pending_signer_decisions_count().await.unwrap(), 2);
+
+    // Nothing is replayed while we do not have a record of the requests.
+    assert_eq!(db.replay_pending_signer_decisions().await.unwrap(), 0);
+    assert_eq!(db.get_pending_signer_decisions_count().await.unwrap(), 2);
+
+    confirm_bitcoin_tx(db, deposit.txid, bitcoin_chain.chain_tip().block_hash).await;
+    db.write_deposit_request(&deposit).await.unwrap();
+    db.write_withdrawal_request(&withdrawal).await.unwrap();
+
+    assert_eq!(db.replay_pending_signer_decisions().await.unwrap(), 2);
+    assert_eq!(db.get_pending_signer_decisions_count().await.unwrap(), 0);
+
+    let deposit_signers = db
+        .get_deposit_signers(&deposit.txid, deposit.output_index)
+        .await
+        .unwrap();
+    assert_eq!(deposit_signers, vec![deposit_decision]);
+
+    let withdrawal_signers = db
+        .get_withdrawal_signers(withdrawal.request_id, &withdrawal.block_hash)
+        .await
+        .unwrap();
+    assert_eq!(withdrawal_signers, vec![withdrawal_decision]);
+
+    // Buffered decisions older than the maximum age are deleted.
+    let stale_decision: model::DepositSigner = Faker.fake();
+    db.write_pending_deposit_signer_decision(&stale_decision)
+        .await
+        .unwrap();
+
+    let max_age = std::time::Duration::from_secs(3600);
+    let expired = db.delete_expired_pending_signer_decisions(max_age).await;
+    assert_eq!(expired.unwrap(), 0);
+
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let max_age = std::time::Duration::from_millis(1);
+    let expired = db.delete_expired_pending_signer_decisions(max_age).await;
+    assert_eq!(expired.unwrap(), 1);
+    assert_eq!(db.get_pending_signer_decisions_count().await.unwrap(), 0);
+}
+
+/// Write a rotate-keys transaction confirmed in the given stacks block and
+/// return its aggregate key.
+async fn write_key_rotation<D>(db: &D, block: &StacksBlock) -> PublicKey
+where
+    D: DbWrite,
+{
+    let key_rotation = model::KeyRotationEvent {
+        block_hash: block.block_hash,
+        ..Faker.fake()
+    };
+    db.write_rotate_keys_transaction(&key_rotation)
+        .await
+        .unwrap();
+    key_rotation.aggregate_key
+}
+
+/// The signer set aggregate keys are taken from rotate-keys transactions
+/// on the canonical stacks chain, most recently rotated first.
+pub async fn signer_set_aggregate_keys_follow_canonical_chain<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let bitcoin_chain = BitcoinChain::new_with_length(4);
+    let stacks_chain = StacksChain::new_anchored(&bitcoin_chain);
+    db.write_blocks(&bitcoin_chain, &stacks_chain).await;
+
+    // A stale bitcoin fork with a stacks block anchored to it.
+    let bitcoin_fork = bitcoin_chain.nth_block(1u64.into()).new_child();
+    let stacks_fork = stacks_chain
+        .nth_block(1u64.into())
+        .new_child()
+        .anchored_to(&bitcoin_fork);
+    db.write_blocks([&bitcoin_fork], [&stacks_fork]).await;
+
+    let old_key = write_key_rotation(db, stacks_chain.nth_block(0u64.into())).await;
+    let new_key = write_key_rotation(db, stacks_chain.nth_block(2u64.into())).await;
+    let fork_key = write_key_rotation(db, &stacks_fork).await;
+
+    let chain_tip = bitcoin_chain.chain_tip().block_hash;
+    let keys = db.get_signer_set_aggregate_keys(&chain_tip, 10).await;
+    assert_eq!(keys.unwrap(), vec![new_key, old_key]);
+
+    let keys = db.get_signer_set_aggregate_keys(&chain_tip, 1).await;
+    assert_eq!(keys.unwrap(), vec![new_key]);
+
+    let keys = db
+        .get_signer_set_aggregate_keys(&bitcoin_fork.block_hash, 10)
+        .await;
+    assert_eq!(keys.unwrap(), vec![fork_key, old_key]);
+
+    // Before any stacks block with a key rotation there are no keys.
+    let genesis = BitcoinBlock::new_genesis();
+    db.write_bitcoin_block(&genesis).await.unwrap();
+    let keys = db
+        .get_signer_set_aggregate_keys(&genesis.block_hash, 10)
+        .await;
+    assert!(keys.unwrap().is_empty());
+}
+
+/// The P2P message sequence number starts at one and increases by one for
+/// each call, independently for each signer.
+pub async fn p2p_message_sequences_increase_per_signer<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let signer_public_key: PublicKey = Faker.fake();
+    let other_public_key: PublicKey = Faker.fake();
+
+    for expected in 1..=3 {
+        let sequence = db.next_p2p_message_sequence(&signer_public_key).await;
+        assert_eq!(sequence.unwrap(), expected);
+    }
+
+    let sequence = db.next_p2p_message_sequence(&other_public_key).await;
+    assert_eq!(sequence.unwrap(), 1);
+
+    let sequence = db.next_p2p_message_sequence(&signer_public_key).await;
+    assert_eq!(sequence.unwrap(), 4);
+}
+
+/// Peer addresses are returned while they have been seen recently, and
+/// are pruned once they are stale.
+pub async fn p2p_peers_expire_unless_seen_again<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let peer_id = libp2p::PeerId::random();
+    let old_address: libp2p::Multiaddr = "/ip4/10.0.0.1/tcp/4122".parse().unwrap();
+    let new_address: libp2p::Multiaddr = "/dns4/signer.example/udp/4122/quic-v1".parse().unwrap();
+
+    db.upsert_p2p_peer(&peer_id, &old_address).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    db.upsert_p2p_peer(&peer_id, &new_address).await.unwrap();
+
+    // The most recently seen address comes first.
+    let max_age = std::time::Duration::from_secs(3600);
+    let peers = db.get_recent_p2p_peers(max_age).await.unwrap();
+    let expected = vec![
+        (peer_id, new_address.clone()),
+        (peer_id, old_address.clone()),
+    ];
+    assert_eq!(peers, expected);
+    assert_eq!(db.prune_p2p_peers(max_age).await.unwrap(), 0);
+
+    // Seeing the old address again refreshes it.
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    db.upsert_p2p_peer(&peer_id, &old_address).await.unwrap();
+    let max_age = std::time::Duration::from_millis(50);
+    let peers = db.get_recent_p2p_peers(max_age).await.unwrap();
+    assert_eq!(peers, vec![(peer_id, old_address.clone())]);
+
+    assert_eq!(db.prune_p2p_peers(max_age).await.unwrap(), 1);
+    let max_age = std::time::Duration::from_secs(3600);
+    let peers = db.get_recent_p2p_peers(max_age).await.unwrap();
+    assert_eq!(peers, vec![(peer_id, old_address)]);
+}
+
+/// The encrypted private shares of stored DKG shares can be replaced,
+/// leaving the rest of the shares, and any other shares, untouched.
+pub async fn encrypted_private_shares_can_be_replaced<D>(db: &D)
+where
+    D: DbRead + DbWrite + Send + Sync + 'static,
+{
+    let mut rng = crate::testing::get_rng();
+    let shares1: model::EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
+    let shares2: model::EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
+    db.write_encrypted_dkg_shares(&shares1).await.unwrap();
+    db.write_encrypted_dkg_shares(&shares2).await.unwrap();
+
+    let all_shares = db.get_all_encrypted_dkg_shares().await.unwrap();
+    assert_eq!(all_shares.len(), 2);
+    assert!(all_shares.contains(&shares1));
+    assert!(all_shares.contains(&shares2));
+
+    let replacement = vec![1, 2, 3];
+    db.update_encrypted_private_shares(&[(shares1.aggregate_key, replacement.clone())])
+        .await
+        .unwrap();
+
+    let stored1 = db
+        .get_encrypted_dkg_shares(shares1.aggregate_key)
+        .await
+        .unwrap()
+        .unwrap();
+    let expected1 = model::EncryptedDkgShares {
+        encrypted_private_shares: replacement,
+        ..shares1
+    };
+    assert_eq!(stored1, expected1);
+
+    let stored2 = db
+        .get_encrypted_dkg_shares(shares2.aggregate_key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored2, shares2);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::in_memory::Store;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_bitcoin_chain_tip_ties_break_on_block_hash() {
+        bitcoin_chain_tip_ties_break_on_block_hash(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_stacks_chain_tip_follows_bitcoin_fork() {
+        stacks_chain_tip_follows_bitcoin_fork(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_pending_deposit_requests_respect_context_window() {
+        pending_deposit_requests_respect_context_window(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_pending_withdrawal_requests_respect_context_window() {
+        pending_withdrawal_requests_respect_context_window(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_stacks_block_events_are_idempotent() {
+        stacks_block_events_are_idempotent(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_signer_set_aggregate_keys_follow_canonical_chain() {
+        signer_set_aggregate_keys_follow_canonical_chain(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_pending_signer_decisions_are_replayed_once_requests_exist() {
+        pending_signer_decisions_are_replayed_once_requests_exist(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_p2p_message_sequences_increase_per_signer() {
+        p2p_message_sequences_increase_per_signer(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_p2p_peers_expire_unless_seen_again() {
+        p2p_peers_expire_unless_seen_again(&Store::new_shared()).await;
+    }
+
+    #[tokio::test]
+    async fn in_memory_encrypted_private_shares_can_be_replaced() {
+        encrypted_private_shares_can_be_replaced(&Store::new_shared()).await;
+    }
+}
@@ -2,8 +2,13 @@
 
 use std::collections::HashSet;
 
+use bitcoin::ScriptBuf;
 use bitcoin::hashes::Hash as _;
+use bitcoin::opcodes::all as opcodes;
+use clarity::vm::types::PrincipalData;
 use fake::Fake;
+use sbtc::deposits::DepositScriptInputs;
+use sbtc::deposits::ReclaimScriptInputs;
 
 use crate::keys::PublicKey;
 use crate::storage::DbWrite;
@@ -99,6 +104,7 @@ impl TestData {
             &block,
             params.num_deposit_requests_per_block,
             params.num_signers_per_request,
+            params.deposit_scripts.as_ref(),
         );
 
         let withdraw_data = WithdrawData::generate(
@@ -388,6 +394,7 @@ impl DepositData {
         bitcoin_block: &model::BitcoinBlock,
         num_deposit_requests: usize,
         num_signers_per_request: usize,
+        deposit_scripts: Option<&DepositScriptParams>,
     ) -> Self {
         (0..num_deposit_requests).fold(Self::new(), |mut deposit_data, _| {
             let mut deposit_request: model::DepositRequest = fake::Faker.fake_with_rng(rng);
@@ -402,6 +409,9 @@ impl DepositData {
             raw_transaction.block_hash = *bitcoin_block.block_hash.as_ref();
             deposit_request.txid = raw_transaction.txid.into();
             deposit_request.signers_public_key = deposit_config.aggregate_key.into();
+            if let Some(script_params) = deposit_scripts {
+                script_params.set_scripts(rng, &mut deposit_request);
+            }
 
             let deposit_signers: Vec<_> = signer_keys
                 .iter()
@@ -524,6 +534,78 @@ pub struct Params {
     pub num_signers_per_request: usize,
     /// Wheter to generate consecutive blocks or not
     pub consecutive_blocks: bool,
+    /// How to build the scripts of the deposit requests. If this is
+    /// `None` then the scripts are random bytes, which cannot be parsed.
+    pub deposit_scripts: Option<DepositScriptParams>,
+}
+
+/// Parameters for generating valid deposit and reclaim scripts for
+/// deposit requests.
+#[derive(Debug, Clone)]
+pub struct DepositScriptParams {
+    /// The range of the lock times in the reclaim scripts, in blocks.
+    pub lock_time: std::ops::Range<u32>,
+    /// The kind of principal that receives the sBTC.
+    pub recipient: RecipientKind,
+}
+
+/// The kind of principal that receives the sBTC of a deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientKind {
+    /// A standard principal, the address of an account.
+    Standard,
+    /// A contract principal.
+    Contract,
+}
+
+impl Default for DepositScriptParams {
+    fn default() -> Self {
+        Self {
+            lock_time: 6..u16::MAX as u32,
+            recipient: RecipientKind::Standard,
+        }
+    }
+}
+
+impl DepositScriptParams {
+    /// Replace the scripts of the given deposit request with valid ones,
+    /// and update the fields that are derived from them. The scripts are
+    /// built the same way as in the [`sbtc::deposits`] module, and the
+    /// reclaim script locks the funds with a single key after the lock
+    /// time.
+    fn set_scripts<R>(&self, rng: &mut R, deposit_request: &mut model::DepositRequest)
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        let recipient: model::StacksPrincipal = fake::Faker.fake_with_rng(rng);
+        let recipient = match self.recipient {
+            RecipientKind::Standard => PrincipalData::from(recipient),
+            RecipientKind::Contract => PrincipalData::parse(&format!("{recipient}.sbtc-recipient"))
+                .expect("a contract name appended to an address is a valid principal"),
+        };
+
+        let deposit_inputs = DepositScriptInputs {
+            signers_public_key: deposit_request.signers_public_key.into(),
+            max_fee: deposit_request.max_fee,
+            recipient: recipient.clone(),
+        };
+
+        let reclaim_key: PublicKey = fake::Faker.fake_with_rng(rng);
+        let user_script = ScriptBuf::builder()
+            .push_opcode(opcodes::OP_DROP)
+            .push_slice(secp256k1::XOnlyPublicKey::from(reclaim_key).serialize())
+            .push_opcode(opcodes::OP_CHECKSIG)
+            .into_script();
+        let lock_time = self.lock_time.clone().fake_with_rng(rng);
+        let reclaim_inputs = ReclaimScriptInputs::try_new(lock_time, user_script)
+            .expect("the lock time range must only contain valid lock times");
+
+        deposit_request.spend_script = deposit_inputs.deposit_script().into_bytes();
+        deposit_request.reclaim_script = reclaim_inputs.reclaim_script().into_bytes();
+        deposit_request.recipient = recipient.into();
+        deposit_request.lock_time = reclaim_inputs.lock_time();
+        deposit_request.reclaim_script_kind = reclaim_inputs.kind().into();
+    }
 }
 
 impl BitcoinBlockRef {
@@ -581,6 +663,7 @@ impl From<&bitcoin::Block> for crate::storage::model::BitcoinBlockRef {
 #[cfg(test)]
 mod tests {
     use more_asserts::assert_ge;
+    use test_case::test_case;
 
     use crate::testing::get_rng;
     use crate::{
@@ -602,6 +685,7 @@ mod tests {
             num_withdraw_requests_per_block: 0,
             num_signers_per_request: 0,
             consecutive_blocks: true,
+            deposit_scripts: None,
         };
         let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
 
@@ -637,4 +721,63 @@ mod tests {
         // bitcoin chain itself will be fork-less because of consecutive_blocks
         assert_ge!(walk.len(), 10);
     }
+
+    #[test_case(RecipientKind::Standard; "standard recipient")]
+    #[test_case(RecipientKind::Contract; "contract recipient")]
+    #[tokio::test]
+    async fn generated_deposit_scripts_can_be_parsed(recipient: RecipientKind) {
+        let mut store = storage::in_memory::Store::new_shared();
+        let mut rng = get_rng();
+
+        let test_model_params = Params {
+            num_bitcoin_blocks: 5,
+            num_stacks_blocks_per_bitcoin_block: 1,
+            num_deposit_requests_per_block: 4,
+            num_withdraw_requests_per_block: 0,
+            num_signers_per_request: 0,
+            consecutive_blocks: true,
+            deposit_scripts: Some(DepositScriptParams { lock_time: 10..20, recipient }),
+        };
+        let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
+
+        let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
+        test_data.write_to(&mut store).await;
+
+        let chain_tip = store
+            .get_bitcoin_canonical_chain_tip()
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(test_data.deposit_requests.len(), 20);
+        for deposit in test_data.deposit_requests.iter() {
+            let report = store
+                .get_deposit_request_report(
+                    &chain_tip,
+                    &deposit.txid,
+                    deposit.output_index,
+                    &signer_set[0],
+                )
+                .await
+                .unwrap()
+                .unwrap();
+
+            // The lock time in the report comes from the row in the
+            // database, and it has to match the one in the script.
+            let reclaim = ReclaimScriptInputs::parse(&report.reclaim_script).unwrap();
+            assert_eq!(report.lock_time.to_consensus_u32(), reclaim.lock_time());
+            assert!((10..20).contains(&reclaim.lock_time()));
+            assert_eq!(
+                deposit.reclaim_script_kind,
+                model::ReclaimScriptKind::from(reclaim.kind())
+            );
+
+            let deposit_inputs = DepositScriptInputs::parse(&report.deposit_script).unwrap();
+            assert_eq!(deposit_inputs.max_fee, report.max_fee);
+            assert_eq!(deposit_inputs.signers_public_key, report.signers_public_key);
+            assert_eq!(deposit_inputs.recipient, *deposit.recipient);
+            let is_contract = matches!(deposit_inputs.recipient, PrincipalData::Contract(_));
+            assert_eq!(is_contract, recipient == RecipientKind::Contract);
+        }
+    }
 }
@@ -24,6 +24,7 @@ impl PgStore {
               , dr.amount
               , dr.max_fee
               , dr.lock_time
+              , dr.reclaim_script_kind
               , dr.signers_public_key
               , dr.sender_script_pub_keys
             FROM sbtc_signer.bitcoin_blockchain_of($1, $2)
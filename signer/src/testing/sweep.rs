@@ -0,0 +1,205 @@
+//! Proptest strategies for the inputs to sweep transaction construction.
+//!
+//! These generate [`SbtcRequests`] with random deposits, withdrawals, votes
+//! against, fee rates and signer balances, so that tests in any module can
+//! check properties of the transactions returned by
+//! [`SbtcRequests::construct_transactions`].
+
+use bitcoin::CompressedPublicKey;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Txid;
+use bitcoin::hashes::Hash as _;
+use bitvec::array::BitArray;
+use clarity::vm::types::PrincipalData;
+use fake::Fake as _;
+use proptest::prelude::*;
+use rand::RngCore as _;
+use rand::SeedableRng as _;
+use rand::rngs::StdRng;
+use sbtc::deposits::DepositScriptInputs;
+use secp256k1::SECP256K1;
+use secp256k1::SecretKey;
+use secp256k1::XOnlyPublicKey;
+use stacks_common::types::chainstate::StacksAddress;
+
+use crate::bitcoin::utxo::DepositRequest;
+use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::utxo::SbtcRequests;
+use crate::bitcoin::utxo::SignerBtcState;
+use crate::bitcoin::utxo::SignerUtxo;
+use crate::bitcoin::utxo::WithdrawalRequest;
+use crate::context::SbtcLimits;
+
+/// The maximum number of signers in the signer sets that are generated.
+pub const MAX_NUM_SIGNERS: u16 = 16;
+
+/// The probability that a signer votes against any one request.
+const VOTE_AGAINST_PROBABILITY: f64 = 0.1;
+
+fn x_only_public_key(rng: &mut StdRng) -> XOnlyPublicKey {
+    SecretKey::new(rng).x_only_public_key(SECP256K1).0
+}
+
+fn outpoint(rng: &mut StdRng, vout: u32) -> OutPoint {
+    let mut txid = [0; 32];
+    rng.fill_bytes(&mut txid);
+    OutPoint {
+        txid: Txid::from_byte_array(txid),
+        vout,
+    }
+}
+
+/// Generate the bitmap of votes against a request, where each of the
+/// `num_signers` signers votes against the request with a small
+/// probability.
+pub fn arb_signer_bitmap(num_signers: u16) -> impl Strategy<Value = BitArray<[u8; 16]>> {
+    prop::collection::vec(
+        prop::bool::weighted(VOTE_AGAINST_PROBABILITY),
+        num_signers as usize,
+    )
+    .prop_map(|votes| {
+        let mut bitmap = BitArray::ZERO;
+        for (index, vote_against) in votes.into_iter().enumerate() {
+            bitmap.set(index, vote_against);
+        }
+        bitmap
+    })
+}
+
+/// Generate a deposit request from a signer set with `num_signers`
+/// signers.
+///
+/// The amounts and max fees range from zero to well above the minimum
+/// fees, so some of the requests are expected to be filtered out before
+/// packaging.
+pub fn arb_deposit_request(num_signers: u16) -> impl Strategy<Value = DepositRequest> {
+    (
+        0..2_000_000_u64,
+        0..100_000_u64,
+        arb_signer_bitmap(num_signers),
+        any::<u64>(),
+    )
+        .prop_map(|(amount, max_fee, signer_bitmap, seed)| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let signers_public_key = x_only_public_key(&mut rng);
+            let deposit_inputs = DepositScriptInputs {
+                signers_public_key,
+                max_fee,
+                recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+            };
+
+            DepositRequest {
+                outpoint: outpoint(&mut rng, 0),
+                max_fee,
+                signer_bitmap,
+                amount,
+                deposit_script: deposit_inputs.deposit_script(),
+                reclaim_script: ScriptBuf::new(),
+                signers_public_key,
+            }
+        })
+}
+
+/// Generate a withdrawal request to a P2WPKH address from a signer set
+/// with `num_signers` signers.
+///
+/// Like with [`arb_deposit_request`], the amounts and max fees include
+/// ones that are too small to be serviced. All generated requests have a
+/// request ID of zero, see [`arb_sbtc_requests`] for making them unique.
+pub fn arb_withdrawal_request(num_signers: u16) -> impl Strategy<Value = WithdrawalRequest> {
+    (
+        0..200_000_u64,
+        0..100_000_u64,
+        arb_signer_bitmap(num_signers),
+        any::<u64>(),
+    )
+        .prop_map(|(amount, max_fee, signer_bitmap, seed)| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let public_key = CompressedPublicKey(SecretKey::new(&mut rng).public_key(SECP256K1));
+
+            WithdrawalRequest {
+                request_id: 0,
+                txid: fake::Faker.fake_with_rng(&mut rng),
+                block_hash: fake::Faker.fake_with_rng(&mut rng),
+                amount,
+                max_fee,
+                script_pubkey: ScriptBuf::new_p2wpkh(&public_key.wpubkey_hash()).into(),
+                signer_bitmap,
+            }
+        })
+}
+
+/// Generate the bitcoin state of the signers, with a fee rate between 1
+/// and 50 sats per vbyte and, sometimes, the fees of a transaction that
+/// is being replaced.
+///
+/// The balance of the signers' UTXO is large enough to pay for the
+/// withdrawals and fees of any [`arb_sbtc_requests`] requests.
+pub fn arb_signer_btc_state() -> impl Strategy<Value = SignerBtcState> {
+    (
+        5_000_000..100_000_000_u64,
+        1.0..50.0_f64,
+        prop::option::of((1_000..100_000_u64, 1.0..50.0_f64)),
+        any::<u64>(),
+    )
+        .prop_map(|(amount, fee_rate, last_fees, seed)| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let public_key = x_only_public_key(&mut rng);
+
+            SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: outpoint(&mut rng, 0),
+                    amount,
+                    public_key,
+                },
+                fee_rate,
+                public_key,
+                last_fees: last_fees.map(|(total, rate)| Fees { total, rate }),
+                magic_bytes: [b'T', b'3'],
+            }
+        })
+}
+
+/// Generate a set of requests for the signers to sweep, with up to 30
+/// deposits and 30 withdrawals and no sBTC limits.
+///
+/// The withdrawal requests have unique request IDs.
+pub fn arb_sbtc_requests() -> impl Strategy<Value = SbtcRequests> {
+    (1..=MAX_NUM_SIGNERS)
+        .prop_flat_map(|num_signers| {
+            (
+                prop::collection::vec(arb_deposit_request(num_signers), 0..30),
+                prop::collection::vec(arb_withdrawal_request(num_signers), 0..30),
+                arb_signer_btc_state(),
+                Just(num_signers),
+                1..=num_signers,
+                1..=25_u16,
+            )
+        })
+        .prop_map(
+            |(
+                deposits,
+                mut withdrawals,
+                signer_state,
+                num_signers,
+                accept_threshold,
+                max_deposits_per_bitcoin_tx,
+            )| {
+                for (request_id, withdrawal) in withdrawals.iter_mut().enumerate() {
+                    withdrawal.request_id = request_id as u64;
+                }
+
+                SbtcRequests {
+                    deposits,
+                    withdrawals,
+                    signer_state,
+                    accept_threshold,
+                    num_signers,
+                    sbtc_limits: SbtcLimits::unlimited(),
+                    max_deposits_per_bitcoin_tx,
+                    max_requests_per_bitcoin_tx: None,
+                }
+            },
+        )
+}
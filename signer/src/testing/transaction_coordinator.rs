@@ -66,9 +66,11 @@ const EMPTY_BITCOIN_TX: bitcoin::Transaction = bitcoin::Transaction {
 };
 
 /// Method which gets the coordinator private key based on the given list
-/// of `SignerInfo`.
+/// of `SignerInfo`, using the coordinator election rule that is in effect
+/// at the given chain tip for the given activation height.
 pub fn select_coordinator(
-    bitcoin_chain_tip: &model::BitcoinBlockHash,
+    bitcoin_chain_tip: &model::BitcoinBlockRef,
+    activation_height: Option<model::BitcoinBlockHeight>,
     signer_info: &[testing::wsts::SignerInfo],
 ) -> keys::PrivateKey {
     // Ensure signer_info is not empty and grab the first one.
@@ -79,8 +81,9 @@ pub fn select_coordinator(
     let signer_public_keys = &first_signer_info.signer_public_keys;
 
     // Determine the coordinator's public key.
-    let coordinator_pub_key = coordinator_public_key(bitcoin_chain_tip, signer_public_keys)
-        .expect("couldn't determine coordinator");
+    let coordinator_pub_key =
+        coordinator_public_key(bitcoin_chain_tip, signer_public_keys, activation_height)
+            .expect("couldn't determine coordinator");
 
     // Find the coordinator's private key from the signer info based on the
     // public key we just determined.
@@ -225,10 +228,14 @@ where
         context.state().set_sbtc_contracts_deployed();
         let signer_network = SignerNetwork::single(&context);
 
+        let activation_height = context
+            .config()
+            .signer
+            .coordinator_election_activation_height;
         let coordinator = TxCoordinatorEventLoop {
             context: self.context,
             network: signer_network.spawn(),
-            private_key: select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info),
+            private_key: select_coordinator(&bitcoin_chain_tip, activation_height, &signer_info),
             threshold: self.signing_threshold,
             context_window: self.context_window,
             signing_round_max_duration: Duration::from_millis(500),
@@ -419,7 +426,12 @@ where
             .await;
 
         // Get the private key of the coordinator of the signer set.
-        let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+        let activation_height = self
+            .context
+            .config()
+            .signer
+            .coordinator_election_activation_height;
+        let private_key = select_coordinator(&bitcoin_chain_tip, activation_height, &signer_info);
 
         // Bootstrap the tx coordinator within an event loop harness.
         self.context.state().set_sbtc_contracts_deployed();
@@ -606,7 +618,12 @@ where
             .await;
 
         // Get the private key of the coordinator of the signer set.
-        let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+        let activation_height = self
+            .context
+            .config()
+            .signer
+            .coordinator_election_activation_height;
+        let private_key = select_coordinator(&bitcoin_chain_tip, activation_height, &signer_info);
 
         // Bootstrap the tx coordinator within an event loop harness.
         // We don't `set_sbtc_contracts_deployed` to force the coordinator to deploy the contracts
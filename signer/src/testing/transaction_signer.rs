@@ -4,6 +4,7 @@ use std::collections::BTreeSet;
 use std::num::NonZeroUsize;
 use std::time::Duration;
 
+use crate::bitcoin::validation::ReportCache;
 use crate::blocklist_client;
 use crate::context::Context;
 use crate::context::SignerEvent;
@@ -22,6 +23,7 @@ use crate::testing;
 use crate::testing::get_rng;
 use crate::testing::storage::model::TestData;
 use crate::transaction_signer;
+use crate::wsts_state_machine::RoundTracker;
 
 use lru::LruCache;
 use tokio::sync::broadcast;
@@ -61,6 +63,9 @@ where
                 rng,
                 dkg_begin_pause: None,
                 dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+                report_cache: ReportCache::default(),
+                round_tracker: RoundTracker::default(),
+                presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
             },
             context,
         }
@@ -174,20 +179,25 @@ where
             Self::write_test_data(&handle.context.get_storage_mut(), &test_data).await;
         }
 
-        let bitcoin_chain_tip = event_loop_handles
-            .first()
-            .unwrap()
-            .context
+        let context = &event_loop_handles.first().unwrap().context;
+        let bitcoin_chain_tip = context
             .get_storage()
-            .get_bitcoin_canonical_chain_tip()
+            .get_bitcoin_canonical_chain_tip_ref()
             .await
             .expect("storage error")
             .expect("no chain tip");
+        let activation_height = context
+            .config()
+            .signer
+            .coordinator_election_activation_height;
 
         // now that we have a chain tip, get the real coordinator
-        let coordinator_public_key =
-            crate::transaction_coordinator::coordinator_public_key(&bitcoin_chain_tip, signer_set)
-                .unwrap();
+        let coordinator_public_key = crate::transaction_coordinator::coordinator_public_key(
+            &bitcoin_chain_tip,
+            signer_set,
+            activation_height,
+        )
+        .unwrap();
         let coordinator_signer_info = signer_info
             .iter()
             .find(|signer| {
@@ -198,7 +208,7 @@ where
 
         run_dkg_and_store_results_for_signers(
             &signer_info,
-            &bitcoin_chain_tip,
+            &bitcoin_chain_tip.block_hash,
             self.signing_threshold,
             event_loop_handles
                 .iter_mut()
@@ -215,7 +225,7 @@ where
             self.signing_threshold,
         );
         let aggregate_key = coordinator
-            .run_dkg(bitcoin_chain_tip, dummy_txid.into())
+            .run_dkg(bitcoin_chain_tip.block_hash, dummy_txid.into())
             .await;
 
         for handle in event_loop_handles.into_iter() {
@@ -4,6 +4,8 @@ use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::time::Duration;
 
+use bitcoin::TapSighash;
+use bitcoin::hashes::Hash as _;
 use clarity::util::secp256k1::Secp256k1PublicKey;
 use clarity::vm::types::PrincipalData;
 use fake::Fake;
@@ -27,6 +29,7 @@ use crate::network::MessageTransfer as _;
 use crate::storage;
 use crate::storage::model;
 use crate::storage::model::EncryptedDkgShares;
+use crate::storage::model::SigHash;
 use crate::storage::model::StacksPrincipal;
 use crate::wsts_state_machine;
 
@@ -149,9 +152,14 @@ impl Coordinator {
             .start_public_shares()
             .expect("failed to start public shares");
 
-        self.send_packet(bitcoin_chain_tip, id, outbound).await;
+        let session_id = fake::Faker.fake_with_rng(&mut OsRng);
+        self.send_packet(bitcoin_chain_tip, id, session_id, None, outbound)
+            .await;
 
-        match self.loop_until_result(bitcoin_chain_tip, id).await {
+        match self
+            .loop_until_result(bitcoin_chain_tip, id, session_id, None)
+            .await
+        {
             wsts::state_machine::OperationResult::Dkg(aggregate_key) => {
                 PublicKey::try_from(&aggregate_key).expect("Got the point at infinity")
             }
@@ -172,9 +180,17 @@ impl Coordinator {
             .start_signing_round(msg, signature_type)
             .expect("failed to start signing round");
 
-        self.send_packet(bitcoin_chain_tip, id, outbound).await;
+        let session_id = fake::Faker.fake_with_rng(&mut OsRng);
+        let sighash = TapSighash::from_slice(msg)
+            .expect("the message to sign is not a sighash")
+            .into();
+        self.send_packet(bitcoin_chain_tip, id, session_id, Some(sighash), outbound)
+            .await;
 
-        match self.loop_until_result(bitcoin_chain_tip, id).await {
+        match self
+            .loop_until_result(bitcoin_chain_tip, id, session_id, Some(sighash))
+            .await
+        {
             wsts::state_machine::OperationResult::SignTaproot(signature)
             | wsts::state_machine::OperationResult::SignSchnorr(signature) => signature,
             _ => panic!("unexpected operation result"),
@@ -185,6 +201,8 @@ impl Coordinator {
         &mut self,
         bitcoin_chain_tip: model::BitcoinBlockHash,
         id: WstsMessageId,
+        session_id: [u8; 16],
+        sighash: Option<SigHash>,
     ) -> wsts::state_machine::OperationResult {
         let future = async move {
             loop {
@@ -206,7 +224,8 @@ impl Coordinator {
 
                 if let Some(packet) = outbound_packet {
                     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    self.send_packet(bitcoin_chain_tip, id, packet).await;
+                    self.send_packet(bitcoin_chain_tip, id, session_id, sighash, packet)
+                        .await;
                 }
 
                 if let Some(result) = operation_result {
@@ -275,8 +294,14 @@ impl Signer {
                         .process_inbound_messages(&[packet.clone()], &mut rng)
                         .expect("message processing failed");
 
-                    self.send_packet(bitcoin_chain_tip, wsts_msg.id, packet.clone())
-                        .await;
+                    self.send_packet(
+                        bitcoin_chain_tip,
+                        wsts_msg.id,
+                        wsts_msg.session_id,
+                        wsts_msg.sighash,
+                        packet.clone(),
+                    )
+                    .await;
 
                     if let wsts::net::Message::DkgEnd(_) = packet.msg {
                         return self;
@@ -316,8 +341,14 @@ impl Signer {
                         .process_inbound_messages(&[packet.clone()], &mut rng)
                         .expect("message processing failed");
 
-                    self.send_packet(bitcoin_chain_tip, wsts_msg.id, packet.clone())
-                        .await;
+                    self.send_packet(
+                        bitcoin_chain_tip,
+                        wsts_msg.id,
+                        wsts_msg.session_id,
+                        wsts_msg.sighash,
+                        packet.clone(),
+                    )
+                    .await;
 
                     if let wsts::net::Message::SignatureShareResponse(_) = packet.msg {
                         return self;
@@ -344,9 +375,17 @@ trait WstsEntity {
         &mut self,
         bitcoin_chain_tip: model::BitcoinBlockHash,
         id: WstsMessageId,
+        session_id: [u8; 16],
+        sighash: Option<SigHash>,
         packet: wsts::net::Packet,
     ) {
-        let payload: message::Payload = message::WstsMessage { id, inner: packet.msg }.into();
+        let payload: message::Payload = message::WstsMessage {
+            id,
+            session_id,
+            sighash,
+            inner: packet.msg,
+        }
+        .into();
 
         let msg = payload
             .to_message(bitcoin_chain_tip)
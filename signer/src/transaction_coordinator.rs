@@ -6,7 +6,9 @@
 //! For more details, see the [`TxCoordinatorEventLoop`] documentation.
 
 use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::num::NonZeroU16;
 use std::time::Duration;
 
 use blockstack_lib::chainstate::stacks::StacksTransaction;
@@ -24,6 +26,7 @@ use crate::bitcoin::TransactionLookupHint;
 use crate::bitcoin::utxo;
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::utxo::UnsignedMockTransaction;
+use crate::bitcoin::validation::max_acceptable_fee_rate;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::RequestDeciderEvent;
@@ -33,7 +36,6 @@ use crate::context::SignerEvent;
 use crate::context::SignerSignal;
 use crate::context::TxCoordinatorEvent;
 use crate::context::TxSignerEvent;
-use crate::ecdsa::SignEcdsa as _;
 use crate::ecdsa::Signed;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
@@ -42,6 +44,7 @@ use crate::keys::PublicKey;
 use crate::message;
 use crate::message::BitcoinPreSignRequest;
 use crate::message::Payload;
+use crate::message::RoundAbortReason;
 use crate::message::SignerMessage;
 use crate::message::StacksTransactionSignRequest;
 use crate::message::WstsMessageId;
@@ -62,16 +65,20 @@ use crate::stacks::contracts::RejectWithdrawalV1;
 use crate::stacks::contracts::RotateKeysV1;
 use crate::stacks::contracts::SMART_CONTRACTS;
 use crate::stacks::contracts::SmartContract;
+use crate::stacks::contracts::recorded_deposit_fees;
 use crate::stacks::wallet::MultisigTx;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::DbRead;
+use crate::storage::DbWrite as _;
 use crate::storage::model;
 use crate::storage::model::StacksTxId;
 use crate::wsts_state_machine::FireCoordinator;
 use crate::wsts_state_machine::FrostCoordinator;
 use crate::wsts_state_machine::WstsCoordinator;
 
+use bitcoin::TapSighash;
 use bitcoin::hashes::Hash as _;
+use rand::RngCore as _;
 use wsts::net::SignatureType;
 use wsts::state_machine::OperationResult as WstsOperationResult;
 use wsts::state_machine::StateMachine as _;
@@ -225,12 +232,17 @@ where
     #[tracing::instrument(skip_all, name = "tx-coordinator")]
     pub async fn run(mut self) -> Result<(), Error> {
         tracing::info!("starting transaction coordinator event loop");
+        let _participant = self
+            .context
+            .get_termination_handle()
+            .register_participant("transaction-coordinator");
         let mut signal_stream = self.context.as_signal_stream(run_loop_message_filter);
 
         while let Some(message) = signal_stream.next().await {
             match message {
                 SignerSignal::Command(SignerCommand::Shutdown) => break,
-                SignerSignal::Command(SignerCommand::P2PPublish(_)) => {}
+                SignerSignal::Command(SignerCommand::P2PPublish(_))
+                | SignerSignal::Command(SignerCommand::P2PRequestDecisions(_)) => {}
                 SignerSignal::Event(event) => {
                     if let SignerEvent::RequestDecider(RequestDeciderEvent::NewRequestsHandled) =
                         event
@@ -326,7 +338,7 @@ where
         // If we are not the coordinator, then we have no business
         // coordinating DKG or constructing bitcoin and stacks
         // transactions, might as well return early.
-        if !self.is_coordinator(bitcoin_chain_tip.as_ref(), &signer_public_keys) {
+        if !self.is_coordinator(&bitcoin_chain_tip, &signer_public_keys) {
             // Before returning, we also check if all the smart contracts are
             // deployed: we do this as some other coordinator could have deployed
             // them, in which case we need to updated our state.
@@ -523,13 +535,22 @@ where
                             inner:
                                 SignerMessage {
                                     bitcoin_chain_tip,
-                                    payload: Payload::BitcoinPreSignAck(_),
+                                    payload: Payload::BitcoinPreSignAck(ack),
                                     ..
                                 },
                             signer_public_key,
                             ..
                         }) => {
                             if bitcoin_chain_tip == target_tip {
+                                for rejection in ack.rejected_txs {
+                                    tracing::warn!(
+                                        signer = %signer_public_key,
+                                        txid = %rejection.txid,
+                                        failures = %serde_json::to_string(&rejection.failures)
+                                            .unwrap_or_default(),
+                                        "signer rejected a bitcoin transaction in the pre-sign request"
+                                    );
+                                }
                                 acknowledged_signers.insert(signer_public_key);
                             } else {
                                 tracing::warn!(
@@ -632,21 +653,41 @@ where
 
         // Send the pre-sign request to the signers and wait for their
         // acknowledgments.
-        self.construct_and_send_bitcoin_presign_request(
-            bitcoin_chain_tip.as_ref(),
-            &pending_requests.signer_state,
-            &transaction_package,
-        )
-        .await?;
+        let presign_result = self
+            .construct_and_send_bitcoin_presign_request(
+                bitcoin_chain_tip.as_ref(),
+                &pending_requests.signer_state,
+                &transaction_package,
+            )
+            .await;
+
+        for transaction in transaction_package.iter() {
+            self.record_sweep_attempt(
+                transaction,
+                model::SweepAttemptOutcome::Proposed,
+                presign_result.as_ref().err(),
+            )
+            .await;
+        }
+        presign_result?;
 
         // Construct, sign and broadcast the bitcoin transactions.
         for mut transaction in transaction_package {
-            self.sign_and_broadcast(
-                bitcoin_chain_tip.as_ref(),
-                signer_public_keys,
-                &mut transaction,
+            let result = self
+                .sign_and_broadcast(
+                    bitcoin_chain_tip.as_ref(),
+                    signer_public_keys,
+                    &mut transaction,
+                )
+                .await;
+
+            self.record_sweep_attempt(
+                &transaction,
+                model::SweepAttemptOutcome::Broadcast,
+                result.as_ref().err(),
             )
-            .await?;
+            .await;
+            result?;
 
             // TODO: if this (considering also fallback clients) fails, we will
             // need to handle the inconsistency of having the sweep tx confirmed
@@ -1216,9 +1257,11 @@ where
     /// Transform the swept deposit request into a Stacks sign request
     /// object.
     ///
-    /// This function uses bitcoin-core to help with the fee assessment of
-    /// the deposit request, and stacks-core for fee estimation of the
-    /// transaction.
+    /// The amount to mint is computed from the confirmed sweep
+    /// transaction, as reported by bitcoin-core, and the fees recorded for
+    /// the deposits that it swept in, and it is checked against the
+    /// bitcoin that the signers received from the sweep. It uses
+    /// stacks-core for fee estimation of the transaction.
     #[tracing::instrument(skip_all)]
     async fn construct_deposit_stacks_sign_request(
         &self,
@@ -1226,9 +1269,6 @@ where
         bitcoin_aggregate_key: &PublicKey,
         wallet: &SignerWallet,
     ) -> Result<(StacksTransactionSignRequest, MultisigTx), Error> {
-        // Retrieve the Bitcoin sweep transaction from the Bitcoin node. We
-        // can't get it from the database because the transaction is
-        // only in the node's mempool at this point.
         let tx_info = self
             .context
             .get_bitcoin_client()
@@ -1238,15 +1278,16 @@ where
                 Error::BitcoinTxMissing(req.sweep_txid.into(), Some(req.sweep_block_hash.into()))
             })?;
 
+        let storage = self.context.get_storage();
+        let recorded_fees = recorded_deposit_fees(&storage, &tx_info).await?;
+
         let outpoint = req.deposit_outpoint();
-        let assessed_bitcoin_fee = tx_info
-            .assess_input_fee(&outpoint)
-            .ok_or_else(|| Error::OutPointMissing(outpoint))?;
+        let amount = utxo::deposit_mint_amount(&tx_info, &recorded_fees, &outpoint, req.amount)?;
 
         // TODO: we should validate the contract call before asking others
         // to sign it.
         let contract_call = ContractCall::CompleteDepositV1(CompleteDepositV1 {
-            amount: req.amount - assessed_bitcoin_fee.to_sat(),
+            amount,
             outpoint,
             recipient: req.recipient.into(),
             deployer: self.context.config().signer.deployer,
@@ -1435,6 +1476,40 @@ where
             .map_err(|_| Error::SignatureTimeout(txid))?
     }
 
+    /// Record an attempt at fulfilling each of the requests in the given
+    /// transaction. The attempt is recorded as rejected if an error is
+    /// given, and with the given outcome otherwise. Failing to record the
+    /// attempt is logged and otherwise ignored, since the history is only
+    /// used to prioritize requests.
+    async fn record_sweep_attempt(
+        &self,
+        transaction: &utxo::UnsignedTransaction<'_>,
+        outcome: model::SweepAttemptOutcome,
+        error: Option<&Error>,
+    ) {
+        let storage = self.context.get_storage_mut();
+        let sweep_txid = transaction.tx.compute_txid().into();
+        let (outcome, error_detail) = match error {
+            Some(error) => (
+                model::SweepAttemptOutcome::Rejected,
+                Some(error.to_string()),
+            ),
+            None => (outcome, None),
+        };
+
+        for request in transaction.requests.iter() {
+            let attempt = model::SweepAttempt {
+                request: request.sweep_request_id(),
+                sweep_txid,
+                outcome,
+                error_detail: error_detail.clone(),
+            };
+            if let Err(error) = storage.write_sweep_attempt(&attempt).await {
+                tracing::warn!(%error, request = %attempt.request, "could not record the sweep attempt");
+            }
+        }
+    }
+
     /// Coordinate a signing round for the given request
     /// and broadcast it once it's signed.
     #[tracing::instrument(skip_all)]
@@ -1540,6 +1615,9 @@ where
                 tx_in.witness = witness;
             });
 
+        self.record_sweep_attempt(transaction, model::SweepAttemptOutcome::Signed, None)
+            .await;
+
         tracing::info!("broadcasting bitcoin transaction");
         // Broadcast the transaction to the Bitcoin network.
         let response = self
@@ -1577,6 +1655,9 @@ where
     where
         Coordinator: WstsCoordinator,
     {
+        let sighash: model::SigHash = TapSighash::from_slice(msg)
+            .map_err(Error::SigHashConversion)?
+            .into();
         let outbound = coordinator.start_signing_round(msg, signature_type)?;
 
         // We create a signal stream before sending a message so that there
@@ -1586,16 +1667,33 @@ where
             .as_signal_stream(signed_message_filter)
             .filter_map(Self::to_signed_message);
 
-        let msg = message::WstsMessage { id, inner: outbound.msg };
+        let session_id = new_session_id();
+        let msg = message::WstsMessage {
+            id,
+            session_id,
+            sighash: Some(sighash),
+            inner: outbound.msg,
+        };
         self.send_message(msg, bitcoin_chain_tip).await?;
 
         let max_duration = self.signing_round_max_duration;
-        let run_signing_round =
-            self.drive_wsts_state_machine(signal_stream, bitcoin_chain_tip, coordinator, id);
+        let run_signing_round = self.drive_wsts_state_machine(
+            signal_stream,
+            bitcoin_chain_tip,
+            coordinator,
+            id,
+            session_id,
+            Some(sighash),
+        );
 
-        let operation_result = tokio::time::timeout(max_duration, run_signing_round)
-            .await
-            .map_err(|_| Error::CoordinatorTimeout(max_duration.as_secs()))??;
+        let operation_result = match tokio::time::timeout(max_duration, run_signing_round).await {
+            Ok(result) => result?,
+            Err(_) => {
+                self.abort_round(id, RoundAbortReason::Timeout, bitcoin_chain_tip)
+                    .await;
+                return Err(Error::CoordinatorTimeout(max_duration.as_secs()));
+            }
+        };
 
         match operation_result {
             WstsOperationResult::SignTaproot(sig) | WstsOperationResult::SignSchnorr(sig) => {
@@ -1640,7 +1738,13 @@ where
         // identity and current bitcoin chain tip.
         let identifier = self.coordinator_id(chain_tip);
         let id = WstsMessageId::Dkg(identifier);
-        let msg = message::WstsMessage { id, inner: outbound.msg };
+        let session_id = new_session_id();
+        let msg = message::WstsMessage {
+            id,
+            session_id,
+            sighash: None,
+            inner: outbound.msg,
+        };
 
         // We create a signal stream before sending a message so that there
         // is no race condition with the steam and the getting a response.
@@ -1657,12 +1761,23 @@ where
 
         // Now that DKG has "begun" we need to drive it to completion.
         let max_duration = self.dkg_max_duration;
-        let dkg_fut =
-            self.drive_wsts_state_machine(signal_stream, chain_tip, &mut state_machine, id);
+        let dkg_fut = self.drive_wsts_state_machine(
+            signal_stream,
+            chain_tip,
+            &mut state_machine,
+            id,
+            session_id,
+            None,
+        );
 
-        let operation_result = tokio::time::timeout(max_duration, dkg_fut)
-            .await
-            .map_err(|_| Error::CoordinatorTimeout(max_duration.as_secs()))??;
+        let operation_result = match tokio::time::timeout(max_duration, dkg_fut).await {
+            Ok(result) => result?,
+            Err(_) => {
+                self.abort_round(id, RoundAbortReason::Timeout, chain_tip)
+                    .await;
+                return Err(Error::CoordinatorTimeout(max_duration.as_secs()));
+            }
+        };
 
         match operation_result {
             WstsOperationResult::Dkg(aggregate_key) => PublicKey::try_from(&aggregate_key),
@@ -1670,6 +1785,30 @@ where
         }
     }
 
+    /// Tell the signers that we have given up on the WSTS round with the
+    /// given id, so that they can drop their state machines for it and
+    /// ignore any stragglers. Failing to send the message is only logged,
+    /// since the signers will drop the state machines eventually anyway.
+    #[tracing::instrument(skip_all, fields(%round_id, %reason))]
+    async fn abort_round(
+        &mut self,
+        round_id: WstsMessageId,
+        reason: RoundAbortReason,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) {
+        tracing::warn!("aborting the WSTS round");
+        metrics::counter!(
+            Metrics::WstsRoundsAbortedTotal,
+            "reason" => reason.to_string(),
+        )
+        .increment(1);
+
+        let abort = message::RoundAbort { round_id, reason };
+        if let Err(error) = self.send_message(abort, bitcoin_chain_tip).await {
+            tracing::warn!(%error, "could not send the round abort message");
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     async fn drive_wsts_state_machine<S, Coordinator>(
         &mut self,
@@ -1677,6 +1816,8 @@ where
         bitcoin_chain_tip: &model::BitcoinBlockHash,
         coordinator: &mut Coordinator,
         id: WstsMessageId,
+        session_id: [u8; 16],
+        sighash: Option<model::SigHash>,
     ) -> Result<WstsOperationResult, Error>
     where
         S: Stream<Item = Signed<SignerMessage>>,
@@ -1686,6 +1827,13 @@ where
         // but we're already assuming that the bitcoin chain tip doesn't change
         // alternately we could hit the DB every time we get a new message
         let signer_set = self.context.state().current_signer_public_keys();
+        let chain_tip_block = self
+            .context
+            .get_storage()
+            .get_bitcoin_block(bitcoin_chain_tip)
+            .await?
+            .ok_or(Error::MissingBitcoinBlock(*bitcoin_chain_tip))?;
+        let round_coordinator = self.coordinator_public_key(&chain_tip_block.into(), &signer_set);
         tokio::pin!(signal_stream);
 
         // Let's get the next message from the network or the
@@ -1705,10 +1853,29 @@ where
                 continue;
             };
 
+            // Replies must echo the session of this round, otherwise they
+            // belong to some other round, possibly one with the same WSTS
+            // ids, and processing them here would corrupt this one.
+            let is_same_session = wsts_msg.id == id
+                && wsts_msg.session_id == session_id
+                && wsts_msg.sighash == sighash;
+            if !is_same_session {
+                tracing::warn!(
+                    sender = %msg.signer_public_key,
+                    round_id = %wsts_msg.id,
+                    "ignoring WSTS message from a different session"
+                );
+                metrics::counter!(
+                    Metrics::WstsSessionMismatchesTotal,
+                    "role" => "coordinator",
+                )
+                .increment(1);
+                continue;
+            }
+
             let msg_public_key = msg.signer_public_key;
 
-            let sender_is_coordinator =
-                given_key_is_coordinator(msg_public_key, bitcoin_chain_tip, &signer_set);
+            let sender_is_coordinator = round_coordinator == Some(msg_public_key);
 
             let public_keys = &coordinator.get_config().signer_public_keys;
             let public_key_point = p256k1::point::Point::from(msg_public_key);
@@ -1736,7 +1903,12 @@ where
             };
 
             if let Some(packet) = outbound_packet {
-                let msg = message::WstsMessage { id, inner: packet.msg };
+                let msg = message::WstsMessage {
+                    id,
+                    session_id,
+                    sighash,
+                    inner: packet.msg,
+                };
                 self.send_message(msg, bitcoin_chain_tip).await?;
             }
 
@@ -1813,9 +1985,8 @@ where
 
     // Determine if the current coordinator is the coordinator.
     //
-    // The coordinator is decided using the hash of the bitcoin
-    // chain tip. We don't use the chain tip directly because
-    // it typically starts with a lot of leading zeros.
+    // The coordinator is elected using the bitcoin chain tip and the
+    // public keys of the signer set, see `crate::coordinator::elect_at`.
     //
     // Note that this function is technically not fallible,
     // but for now we have chosen to return phantom errors
@@ -1825,14 +1996,26 @@ where
     // expressions. However, that is left for future work.
     fn is_coordinator(
         &self,
-        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        bitcoin_chain_tip: &model::BitcoinBlockRef,
         signer_public_keys: &BTreeSet<PublicKey>,
     ) -> bool {
-        given_key_is_coordinator(
-            self.signer_public_key(),
-            bitcoin_chain_tip,
-            signer_public_keys,
-        )
+        self.coordinator_public_key(bitcoin_chain_tip, signer_public_keys)
+            == Some(self.signer_public_key())
+    }
+
+    /// Find the coordinator public key for the given bitcoin chain tip,
+    /// using the election rule that is in effect at the chain tip.
+    fn coordinator_public_key(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockRef,
+        signer_public_keys: &BTreeSet<PublicKey>,
+    ) -> Option<PublicKey> {
+        let activation_height = self
+            .context
+            .config()
+            .signer
+            .coordinator_election_activation_height;
+        coordinator_public_key(bitcoin_chain_tip, signer_public_keys, activation_height)
     }
 
     /// Constructs a new [`utxo::SignerBtcState`] based on the current market
@@ -1844,7 +2027,12 @@ where
         aggregate_key: &PublicKey,
     ) -> Result<utxo::SignerBtcState, Error> {
         let bitcoin_client = self.context.get_bitcoin_client();
-        let fee_rate = bitcoin_client.estimate_fee_rate().await?;
+        // Cap the fee rate to the maximum that the signers accept, since
+        // they will reject any transaction with a higher fee rate.
+        let market_fee_rate = bitcoin_client.estimate_fee_rate().await?;
+        let reloadable_config = self.context.reloadable_config().get();
+        let max_fee_rate = max_acceptable_fee_rate(&reloadable_config, Some(market_fee_rate));
+        let fee_rate = market_fee_rate.min(max_fee_rate);
 
         // Retrieve the signer's current UTXO.
         let utxo = self
@@ -1856,6 +2044,19 @@ where
 
         let last_fees = self.assess_mempool_sweep_transaction_fees(&utxo).await?;
 
+        // A transaction replacing the one in the mempool must pay a higher
+        // fee rate than it did, whatever our fee rate estimate is. If that
+        // is above the maximum then the other signers would reject the
+        // replacement, so we do not propose one until the transaction in
+        // the mempool confirms or the maximum goes up.
+        let min_fee_rate = utxo::min_sweep_fee_rate(fee_rate, last_fees);
+        if min_fee_rate > max_fee_rate {
+            return Err(Error::ReplacementFeeRateTooHigh {
+                fee_rate: min_fee_rate,
+                max_fee_rate,
+            });
+        }
+
         Ok(utxo::SignerBtcState {
             fee_rate,
             utxo,
@@ -1918,6 +2119,7 @@ where
         const SKIP_REASON_INSUFFICIENT_CONFIRMATIONS: &str = "insufficient_confirmations";
         const SKIP_REASON_INSUFFICIENT_VOTES: &str = "insufficient_votes";
         const SKIP_REASON_SOFT_EXPIRY: &str = "soft_expiry";
+        const SKIP_REASON_VOTES_MISMATCH: &str = "signer_votes_mismatch";
 
         let mut eligible_withdrawals = Vec::new();
 
@@ -2058,8 +2260,19 @@ where
                 continue;
             }
 
-            let withdrawal = utxo::WithdrawalRequest::from_model(req, votes);
-            eligible_withdrawals.push(withdrawal);
+            // Ensure that the votes are keyed to the current signer set, so
+            // that we do not misweight the request when packaging.
+            let request_id = req.request_id;
+            let num_signers = params.signer_public_keys.len();
+            match utxo::WithdrawalRequest::from_model(req, votes, num_signers) {
+                Ok(withdrawal) => eligible_withdrawals.push(withdrawal),
+                Err(error) => tracing::warn!(
+                    request_id,
+                    %error,
+                    reason = SKIP_REASON_VOTES_MISMATCH,
+                    message = REQUEST_SKIPPED_MESSAGE
+                ),
+            }
         }
 
         Ok(eligible_withdrawals)
@@ -2078,6 +2291,10 @@ where
     where
         DB: DbRead,
     {
+        // Constants used for logging (local to this method).
+        const REQUEST_SKIPPED_MESSAGE: &str = "skipping deposit request";
+        const SKIP_REASON_VOTES_MISMATCH: &str = "signer_votes_mismatch";
+
         tracing::debug!("fetching eligible deposit requests");
         let mut eligible_deposits: Vec<utxo::DepositRequest> = Vec::new();
 
@@ -2100,15 +2317,51 @@ where
         // Iterate through each deposit request, fetch its votes from storage
         // for the public keys of the signers in the current signing set, based
         // on the current signers' aggregate key.
+        let mut failed_attempts = HashMap::new();
         for req in pending_deposit_requests {
             let votes = storage
                 .get_deposit_request_signer_votes(&req.txid, req.output_index, params.aggregate_key)
                 .await?;
 
-            let deposit = utxo::DepositRequest::from_model(req, votes);
-            eligible_deposits.push(deposit);
+            // The votes must be keyed to the current signer set, otherwise
+            // we would misweight the request when packaging.
+            let outpoint = req.outpoint();
+            let num_signers = params.signer_public_keys.len();
+            match utxo::DepositRequest::from_model(req, votes, num_signers) {
+                Ok(deposit) => eligible_deposits.push(deposit),
+                Err(error) => {
+                    tracing::warn!(
+                        %outpoint,
+                        %error,
+                        reason = SKIP_REASON_VOTES_MISMATCH,
+                        message = REQUEST_SKIPPED_MESSAGE
+                    );
+                    continue;
+                }
+            }
+
+            let history = storage
+                .get_request_attempt_history(&model::SweepRequestId::from(&outpoint))
+                .await?;
+            let num_failed = history
+                .iter()
+                .filter(|attempt| attempt.outcome == model::SweepAttemptOutcome::Rejected)
+                .count();
+            failed_attempts.insert(outpoint, num_failed);
         }
 
+        // Deposits that chronically fail to be swept go last, so that
+        // they are the first to be left out of the transaction package
+        // when it fills up or when the sBTC caps are hit. The sort is
+        // stable, so deposits with the same number of failed attempts
+        // keep their relative order.
+        eligible_deposits.sort_by_key(|deposit| {
+            failed_attempts
+                .get(&deposit.outpoint)
+                .copied()
+                .unwrap_or_default()
+        });
+
         Ok(eligible_deposits)
     }
 
@@ -2174,6 +2427,10 @@ where
             .map_err(|_| Error::TypeConversion)?;
 
         let max_deposits_per_bitcoin_tx = config.signer.max_deposits_per_bitcoin_tx.get();
+        let max_requests_per_bitcoin_tx = config
+            .signer
+            .max_requests_per_bitcoin_tx
+            .map(NonZeroU16::get);
 
         // Construct and return the `utxo::SbtcRequests` object.
         Ok(Some(utxo::SbtcRequests {
@@ -2184,6 +2441,7 @@ where
             num_signers,
             sbtc_limits,
             max_deposits_per_bitcoin_tx,
+            max_requests_per_bitcoin_tx,
         }))
     }
 
@@ -2208,10 +2466,12 @@ where
         msg: impl Into<Payload>,
         bitcoin_chain_tip: &model::BitcoinBlockHash,
     ) -> Result<(), Error> {
-        let msg = msg
-            .into()
-            .to_message(*bitcoin_chain_tip)
-            .sign_ecdsa(&self.private_key);
+        let msg = network::sign_with_next_sequence(
+            &self.context.get_storage_mut(),
+            msg.into().to_message(*bitcoin_chain_tip),
+            &self.private_key,
+        )
+        .await?;
 
         self.network.broadcast(msg.clone()).await?;
         self.context
@@ -2478,7 +2738,12 @@ where
         T: AsTxPayload + Send + Sync,
     {
         // Get the configured max Stacks transaction fee in microSTX.
-        let stacks_fees_max_ustx = self.context.config().signer.stacks_fees_max_ustx.get();
+        let stacks_fees_max_ustx = self
+            .context
+            .reloadable_config()
+            .get()
+            .stacks_fees_max_ustx
+            .get();
 
         // Calculate the stacks fee for the contract call and cap it to the configured maximum.
         let tx_fee = self
@@ -2493,42 +2758,34 @@ where
 }
 
 /// Check if the provided public key is the coordinator for the provided chain
-/// tip
+/// tip, given the activation height of the coordinator election rule in
+/// [`crate::coordinator::elect`].
 pub fn given_key_is_coordinator(
     pub_key: PublicKey,
-    bitcoin_chain_tip: &model::BitcoinBlockHash,
+    bitcoin_chain_tip: &model::BitcoinBlockRef,
     signer_public_keys: &BTreeSet<PublicKey>,
+    activation_height: Option<model::BitcoinBlockHeight>,
 ) -> bool {
-    coordinator_public_key(bitcoin_chain_tip, signer_public_keys) == Some(pub_key)
+    coordinator_public_key(bitcoin_chain_tip, signer_public_keys, activation_height)
+        == Some(pub_key)
 }
 
-/// Find the coordinator public key
+/// Find the coordinator public key for the given bitcoin chain tip, using
+/// the election rule that is in effect at the chain tip given the
+/// activation height of the rule in [`crate::coordinator::elect`].
 pub fn coordinator_public_key(
-    bitcoin_chain_tip: &model::BitcoinBlockHash,
+    bitcoin_chain_tip: &model::BitcoinBlockRef,
     signer_public_keys: &BTreeSet<PublicKey>,
+    activation_height: Option<model::BitcoinBlockHeight>,
 ) -> Option<PublicKey> {
-    // Create a hash of the bitcoin chain tip. SHA256 will always result in
-    // a 32 byte digest.
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(bitcoin_chain_tip.into_bytes());
-    let digest: [u8; 32] = hasher.finalize().into();
-
-    // Use the first 4 bytes of the digest to create a u32 index. Since `digest`
-    // is 32 bytes and we explicitly take the first 4 bytes, this is safe.
-    #[allow(clippy::expect_used)]
-    let u32_bytes = digest[..4]
-        .try_into()
-        .expect("BUG: failed to take first 4 bytes of digest");
-
-    // Convert the first 4 bytes of the digest to a u32 index.
-    let index = u32::from_be_bytes(u32_bytes);
-
-    let num_signers = signer_public_keys.len();
-
-    signer_public_keys
-        .iter()
-        .nth((index as usize) % num_signers)
-        .copied()
+    crate::coordinator::elect_at(bitcoin_chain_tip, signer_public_keys, activation_height)
+}
+
+/// Pick a random session id for a new DKG or signing round.
+fn new_session_id() -> [u8; 16] {
+    let mut session_id = [0; 16];
+    rand::rngs::OsRng.fill_bytes(&mut session_id);
+    session_id
 }
 
 /// Determine, according to the current state of the signer and configuration,
@@ -2634,6 +2891,7 @@ mod tests {
             num_withdraw_requests_per_block: 5,
             num_signers_per_request: 7,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
 
         let context = TestContext::builder()
@@ -9,8 +9,10 @@ use std::collections::BTreeSet;
 use std::num::NonZeroUsize;
 use std::time::Duration;
 
+use crate::bitcoin::utxo::SignatureHash;
 use crate::bitcoin::utxo::UnsignedMockTransaction;
 use crate::bitcoin::validation::BitcoinTxContext;
+use crate::bitcoin::validation::ReportCache;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::SignerCommand;
@@ -19,13 +21,13 @@ use crate::context::SignerSignal;
 use crate::context::TxCoordinatorEvent;
 use crate::context::TxSignerEvent;
 use crate::dkg;
-use crate::ecdsa::SignEcdsa as _;
 use crate::error::Error;
 use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::message;
 use crate::message::BitcoinPreSignAck;
+use crate::message::BitcoinTxRejection;
 use crate::message::Payload;
 use crate::message::StacksTransactionSignRequest;
 use crate::message::WstsMessageId;
@@ -45,6 +47,7 @@ use crate::storage::model;
 use crate::storage::model::DkgSharesStatus;
 use crate::storage::model::SigHash;
 use crate::wsts_state_machine::FrostCoordinator;
+use crate::wsts_state_machine::RoundTracker;
 use crate::wsts_state_machine::SignerStateMachine;
 use crate::wsts_state_machine::StateMachineId;
 use crate::wsts_state_machine::WstsCoordinator;
@@ -107,6 +110,9 @@ use wsts::net::Message as WstsNetMessage;
 /// - **WSTS message**: When receiving a WSTS message, the signer will look
 ///   up the corresponding state machine and dispatch the WSTS message to
 ///   it.
+/// - **Round abort**: When the coordinator aborts a DKG or signing round,
+///   the signer drops the state machines for the round and ignores any
+///   messages for it that arrive afterwards.
 ///
 /// The following flowchart illustrates the process.
 ///
@@ -122,6 +128,8 @@ use wsts::net::Message as WstsNetMessage;
 ///     WSM --> ACK(Send Ack message)
 ///
 ///     SM --> |WSTS message| RWSM(Relay to WSTS state machine)
+///
+///     SM --> |Round abort| DWSM(Drop WSTS state machines)
 /// ```
 #[derive(Debug)]
 pub struct TxSignerEventLoop<Context, Network, Rng> {
@@ -146,6 +154,16 @@ pub struct TxSignerEventLoop<Context, Network, Rng> {
     /// during DKG using the FROST algorithm. This is then used during the
     /// verification of the Stacks rotate-keys transaction.
     pub dkg_verification_state_machines: LruCache<StateMachineId, dkg::verification::StateMachine>,
+    /// Deposit and withdrawal request reports fetched while validating
+    /// bitcoin transaction packages for the current chain tip.
+    pub report_cache: ReportCache,
+    /// The state machines used by each WSTS round, and the rounds that the
+    /// coordinator has aborted.
+    pub round_tracker: RoundTracker,
+    /// The signature hashes constructed while validating bitcoin pre-sign
+    /// requests. These are recorded in the database when we emit a
+    /// signature share for one of them.
+    pub presign_sighashes: LruCache<SigHash, SignatureHash>,
 }
 
 /// This struct represents a signature hash and the public key that locks
@@ -195,6 +213,8 @@ where
         // running this binary on 32 or 64-bit CPUs.
         let max_state_machines = NonZeroUsize::new(crate::MAX_SIGNER_STATE_MACHINES as usize)
             .ok_or(Error::TypeConversion)?;
+        let max_cached_reports = NonZeroUsize::new(crate::MAX_CACHED_REQUEST_REPORTS as usize)
+            .ok_or(Error::TypeConversion)?;
 
         let config = context.config();
         let signer_private_key = config.signer.private_key;
@@ -214,6 +234,9 @@ where
             dkg_verification_state_machines: LruCache::new(
                 NonZeroUsize::new(5).ok_or(Error::TypeConversion)?,
             ),
+            report_cache: ReportCache::new(max_cached_reports),
+            round_tracker: RoundTracker::new(max_state_machines),
+            presign_sighashes: LruCache::new(max_state_machines),
         })
     }
 
@@ -233,7 +256,8 @@ where
         while let Some(message) = signal_stream.next().await {
             match message {
                 SignerSignal::Command(SignerCommand::Shutdown) => break,
-                SignerSignal::Command(SignerCommand::P2PPublish(_)) => {}
+                SignerSignal::Command(SignerCommand::P2PPublish(_))
+                | SignerSignal::Command(SignerCommand::P2PRequestDecisions(_)) => {}
                 SignerSignal::Event(event) => match event {
                     SignerEvent::TxCoordinator(TxCoordinatorEvent::MessageGenerated(msg))
                     | SignerEvent::P2P(P2PEvent::MessageReceived(msg)) => {
@@ -313,8 +337,15 @@ where
                     "status" => status,
                 )
                 .increment(1);
+                if let Err(error) = &pre_validation_status {
+                    Metrics::increment_validation_rejections(BITCOIN_BLOCKCHAIN, error);
+                }
                 pre_validation_status?;
             }
+
+            (Payload::RoundAbort(abort), true, ChainTipStatus::Canonical) => {
+                self.handle_round_abort(abort);
+            }
             // Message types ignored by the transaction signer
             (Payload::StacksTransactionSignature(_), _, _)
             | (Payload::SignerDepositDecision(_), _, _)
@@ -350,11 +381,14 @@ where
         let is_canonical = msg_bitcoin_chain_tip == &chain_tip.block_hash;
 
         let signer_set = self.context.state().current_signer_public_keys();
-        let sender_is_coordinator = crate::transaction_coordinator::given_key_is_coordinator(
-            msg_sender,
-            &chain_tip.block_hash,
-            &signer_set,
-        );
+        let activation_height = self
+            .context
+            .config()
+            .signer
+            .coordinator_election_activation_height;
+        let sender_is_coordinator =
+            crate::coordinator::elect_at(&chain_tip, &signer_set, activation_height)
+                == Some(msg_sender);
 
         let chain_tip_status = match (is_known, is_canonical) {
             (true, true) => ChainTipStatus::Canonical,
@@ -409,12 +443,37 @@ where
 
         tracing::debug!("validating bitcoin transaction pre-sign");
         let sighashes = request
-            .construct_package_sighashes(&self.context, &btc_ctx)
+            .construct_package_sighashes_cached(&self.context, &btc_ctx, &mut self.report_cache)
             .await?;
 
+        let mut rejected_txs = Vec::new();
+        for validation_data in sighashes.iter() {
+            let report = validation_data.validation_report();
+            if report.is_valid_tx {
+                continue;
+            }
+            let txid = validation_data.tx.compute_txid();
+            tracing::warn!(
+                %txid,
+                report = %serde_json::to_string(&report).unwrap_or_default(),
+                "bitcoin transaction failed validation"
+            );
+            rejected_txs.push(BitcoinTxRejection {
+                txid,
+                failures: report.failures,
+            });
+        }
+
         let deposits_sighashes: Vec<model::BitcoinTxSigHash> =
             sighashes.iter().flat_map(|s| s.to_input_rows()).collect();
 
+        let input_sighashes = sighashes
+            .iter()
+            .flat_map(|s| std::iter::once(&s.signer_sighash).chain(&s.deposit_sighashes));
+        for sighash in input_sighashes {
+            self.presign_sighashes.put(sighash.sighash.into(), *sighash);
+        }
+
         let withdrawals_outputs: Vec<model::BitcoinWithdrawalOutput> = sighashes
             .iter()
             .flat_map(|s| s.to_withdrawal_rows())
@@ -426,7 +485,7 @@ where
         db.write_bitcoin_withdrawals_outputs(&withdrawals_outputs)
             .await?;
 
-        self.send_message(BitcoinPreSignAck, &chain_tip.block_hash)
+        self.send_message(BitcoinPreSignAck { rejected_txs }, &chain_tip.block_hash)
             .await?;
         Ok(())
     }
@@ -456,6 +515,9 @@ where
             "status" => if validation_status.is_ok() { "success" } else { "failed" },
         )
         .increment(1);
+        if let Err(error) = &validation_status {
+            Metrics::increment_validation_rejections(STACKS_BLOCKCHAIN, error);
+        }
         validation_status?;
 
         // We need to set the nonce in order to get the exact transaction
@@ -489,7 +551,12 @@ where
         origin_public_key: &PublicKey,
     ) -> Result<(), Error> {
         // Ensure that the Stacks fee is within the acceptable range.
-        let highest_acceptable_fee = self.context.config().signer.stacks_fees_max_ustx.get();
+        let highest_acceptable_fee = self
+            .context
+            .reloadable_config()
+            .get()
+            .stacks_fees_max_ustx
+            .get();
         if request.tx_fee > highest_acceptable_fee {
             return Err(Error::StacksFeeLimitExceeded(
                 request.tx_fee,
@@ -575,6 +642,45 @@ where
 
         let MsgChainTipReport { chain_tip, .. } = chain_tip_report;
 
+        // Messages for a round that the coordinator has aborted are late
+        // and get ignored, unless the coordinator is starting the round
+        // again.
+        let starts_round = matches!(
+            msg.inner,
+            WstsNetMessage::DkgBegin(_) | WstsNetMessage::NonceRequest(_)
+        ) && chain_tip_report.is_from_canonical_coordinator();
+
+        if !starts_round && self.round_tracker.record_late_message(&msg.id) {
+            tracing::debug!(
+                round_id = %msg.id,
+                message_type = msg.type_id(),
+                "ignoring message for an aborted round"
+            );
+            metrics::counter!(
+                Metrics::WstsLateMessagesTotal,
+                "message_type" => msg.type_id(),
+            )
+            .increment(1);
+            return Ok(());
+        }
+
+        // The sighash in the session has to be the data that the WSTS
+        // message is about, otherwise the message could be attributed to
+        // the wrong signing round.
+        if !msg.sighash_matches_inner() {
+            tracing::warn!(
+                round_id = %msg.id,
+                message_type = msg.type_id(),
+                "ignoring WSTS message with a mismatched sighash"
+            );
+            metrics::counter!(
+                Metrics::WstsSessionMismatchesTotal,
+                "role" => "signer",
+            )
+            .increment(1);
+            return Ok(());
+        }
+
         match &msg.inner {
             // === DKG BEGIN ===
             WstsNetMessage::DkgBegin(request) => {
@@ -603,6 +709,8 @@ where
                 let state_machine_id = StateMachineId::Dkg(*chain_tip);
                 self.wsts_state_machines
                     .put(state_machine_id, state_machine);
+                self.round_tracker
+                    .start(msg.id, state_machine_id, msg.session_id);
 
                 // If a DKG-begin pause is configured, sleep for a bit before
                 // processing the message and broadcasting our responses.
@@ -616,10 +724,9 @@ where
                 // Process the message.
                 self.relay_message(
                     &state_machine_id,
-                    msg.id,
+                    msg,
                     msg_public_key,
                     None,
-                    &msg.inner,
                     &chain_tip.block_hash,
                 )
                 .await?;
@@ -641,10 +748,9 @@ where
                 let state_machine_id = StateMachineId::Dkg(*chain_tip);
                 self.relay_message(
                     &state_machine_id,
-                    msg.id,
+                    msg,
                     msg_public_key,
                     None,
-                    &msg.inner,
                     &chain_tip.block_hash,
                 )
                 .await?;
@@ -659,10 +765,9 @@ where
                 let state_machine_id = StateMachineId::Dkg(*chain_tip);
                 self.relay_message(
                     &state_machine_id,
-                    msg.id,
+                    msg,
                     msg_public_key,
                     Some(request.signer_id),
-                    &msg.inner,
                     &chain_tip.block_hash,
                 )
                 .await?;
@@ -677,10 +782,9 @@ where
                 let state_machine_id = StateMachineId::Dkg(*chain_tip);
                 self.relay_message(
                     &state_machine_id,
-                    msg.id,
+                    msg,
                     msg_public_key,
                     Some(request.signer_id),
-                    &msg.inner,
                     &chain_tip.block_hash,
                 )
                 .await?;
@@ -702,10 +806,9 @@ where
                 let state_machine_id = StateMachineId::Dkg(*chain_tip);
                 self.relay_message(
                     &state_machine_id,
-                    msg.id,
+                    msg,
                     msg_public_key,
                     None,
-                    &msg.inner,
                     &chain_tip.block_hash,
                 )
                 .await?;
@@ -824,14 +927,15 @@ where
                 // Put the state machine into the cache.
                 self.wsts_state_machines
                     .put(state_machine_id, state_machine);
+                self.round_tracker
+                    .start(msg.id, state_machine_id, msg.session_id);
 
                 // Process the message.
                 self.relay_message(
                     &state_machine_id,
-                    msg.id,
+                    msg,
                     msg_public_key,
                     None,
-                    &msg.inner,
                     &chain_tip.block_hash,
                 )
                 .await?;
@@ -870,10 +974,15 @@ where
 
                         // Validate the sighash and upon success, convert it to
                         // a state machine ID.
-                        Self::validate_bitcoin_sign_request(&db, &request.message)
+                        let sighash = Self::validate_bitcoin_sign_request(&db, &request.message)
                             .await?
-                            .sighash
-                            .into()
+                            .sighash;
+
+                        // We record what we are about to sign before we
+                        // emit the signature share.
+                        self.record_signed_sighash(&sighash, msg_public_key, chain_tip)
+                            .await?;
+                        sighash.into()
                     }
 
                     // This is a DKG verification signing round. The data
@@ -908,10 +1017,9 @@ where
                 let response = self
                     .relay_message(
                         &state_machine_id,
-                        msg.id,
+                        msg,
                         msg_public_key,
                         None,
-                        &msg.inner,
                         &chain_tip.block_hash,
                     )
                     .await;
@@ -962,6 +1070,11 @@ where
                 let state_machine_id = self
                     .ensure_dkg_verification_state_machine(new_key, chain_tip)
                     .await?;
+                self.round_tracker.track(msg.id, state_machine_id);
+
+                if !self.is_current_session(&state_machine_id, msg) {
+                    return Ok(());
+                }
 
                 // Process the message. We do not use `relay_message()` here
                 // because we do not need to respond; we only want to process
@@ -1010,6 +1123,10 @@ where
                 // it is in a valid state for use.
                 self.assert_dkg_verification_state_machine_state(&state_machine_id)?;
 
+                if !self.is_current_session(&state_machine_id, msg) {
+                    return Ok(());
+                }
+
                 // Process the message. We do not use `relay_message()` here
                 // because we do not need to respond; we only want to process
                 // it in our DKG verification state machine for tracking purposes.
@@ -1026,6 +1143,54 @@ where
         Ok(())
     }
 
+    /// Whether the given message belongs to the session that the given
+    /// state machine was started for. Messages from another session are
+    /// counted and should be dropped, since they belong to a different
+    /// round even if their WSTS ids match those of the current one.
+    fn is_current_session(
+        &self,
+        state_machine_id: &StateMachineId,
+        msg: &message::WstsMessage,
+    ) -> bool {
+        match self.round_tracker.session(state_machine_id) {
+            Some(session_id) if session_id != msg.session_id => {
+                tracing::warn!(
+                    %state_machine_id,
+                    round_id = %msg.id,
+                    message_type = msg.type_id(),
+                    "ignoring WSTS message from a different session"
+                );
+                metrics::counter!(
+                    Metrics::WstsSessionMismatchesTotal,
+                    "role" => "signer",
+                )
+                .increment(1);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Drop the state machines of a round that the coordinator has
+    /// aborted, so that a new round can start from a clean slate. Any
+    /// messages for the round that arrive afterwards are ignored. Handling
+    /// the same abort more than once has no further effect.
+    pub fn handle_round_abort(&mut self, abort: &message::RoundAbort) {
+        let state_machine_ids = self.round_tracker.abort(abort.round_id);
+
+        tracing::info!(
+            round_id = %abort.round_id,
+            reason = %abort.reason,
+            num_state_machines = state_machine_ids.len(),
+            "coordinator aborted the WSTS round"
+        );
+
+        for state_machine_id in state_machine_ids {
+            self.wsts_state_machines.pop(&state_machine_id);
+            self.dkg_verification_state_machines.pop(&state_machine_id);
+        }
+    }
+
     /// Validate a DKG verification message, asserting that:
     /// - The new key provided by the sender matches our view of the latest
     ///   aggregate key (not the _current_ key, but the key which we intend to
@@ -1146,6 +1311,31 @@ where
         }
     }
 
+    /// Record that we are emitting a signature share for the given bitcoin
+    /// sighash, at the request of the signer with the given public key.
+    ///
+    /// The record is made from the signature hash that we constructed
+    /// while validating the bitcoin pre-sign request, so that it matches
+    /// exactly what we sign.
+    async fn record_signed_sighash(
+        &mut self,
+        sighash: &SigHash,
+        origin_public_key: PublicKey,
+        chain_tip: &model::BitcoinBlockRef,
+    ) -> Result<(), Error> {
+        let Some(signature_hash) = self.presign_sighashes.get(sighash) else {
+            tracing::warn!(%sighash, "signing a sighash that was not in a pre-sign request we saw");
+            return Ok(());
+        };
+
+        let signed_sighash =
+            signature_hash.to_signed_sighash(chain_tip.block_hash, origin_public_key);
+        self.context
+            .get_storage_mut()
+            .write_signed_sighash(&signed_sighash)
+            .await
+    }
+
     /// Persists the encrypted DKG shares stored in the state machine identified
     /// by the given state machine id.
     #[tracing::instrument(skip(self))]
@@ -1415,13 +1605,17 @@ where
     async fn relay_message(
         &mut self,
         state_machine_id: &StateMachineId,
-        wsts_id: WstsMessageId,
+        wsts_msg: &message::WstsMessage,
         sender: PublicKey,
         signer_id: Option<u32>,
-        msg: &WstsNetMessage,
         bitcoin_chain_tip: &model::BitcoinBlockHash,
     ) -> Result<(), Error> {
         let mut rng = OsRng;
+        let msg = &wsts_msg.inner;
+
+        if !self.is_current_session(state_machine_id, wsts_msg) {
+            return Ok(());
+        }
 
         // Validate that the sender is a valid member of the signing set and
         // has the correct id according to the signer state machine.
@@ -1492,7 +1686,12 @@ where
             }
 
             // Publish the message to the network.
-            let msg = message::WstsMessage { id: wsts_id, inner: outbound };
+            let msg = message::WstsMessage {
+                id: wsts_msg.id,
+                session_id: wsts_msg.session_id,
+                sighash: wsts_msg.sighash,
+                inner: outbound,
+            };
             self.send_message(msg, bitcoin_chain_tip).await?;
         }
 
@@ -1508,9 +1707,12 @@ where
         let payload: message::Payload = msg.into();
         tracing::trace!(%payload, "broadcasting message");
 
-        let msg = payload
-            .to_message(*bitcoin_chain_tip)
-            .sign_ecdsa(&self.signer_private_key);
+        let msg = network::sign_with_next_sequence(
+            &self.context.get_storage_mut(),
+            payload.to_message(*bitcoin_chain_tip),
+            &self.signer_private_key,
+        )
+        .await?;
 
         self.network.broadcast(msg.clone()).await?;
         self.context
@@ -1636,12 +1838,14 @@ mod tests {
 
     use crate::bitcoin::MockBitcoinInteract;
     use crate::context::Context;
+    use crate::ecdsa::SignEcdsa as _;
     use crate::emily_client::MockEmilyInteract;
     use crate::stacks::api::MockStacksInteract;
     use crate::storage::in_memory::SharedStore;
     use crate::storage::{DbWrite, model};
     use crate::testing;
     use crate::testing::context::*;
+    use crate::testing::dummy::Unit;
 
     use super::*;
 
@@ -1660,6 +1864,7 @@ mod tests {
             num_withdraw_requests_per_block: 5,
             num_signers_per_request: 0,
             consecutive_blocks: false,
+            deposit_scripts: None,
         };
 
         let context = TestContext::builder()
@@ -1791,11 +1996,16 @@ mod tests {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
 
         // Create a DkgBegin message to be handled by the signer.
         let msg = message::WstsMessage {
             id: WstsMessageId::Dkg(Faker.fake()),
+            session_id: Faker.fake(),
+            sighash: None,
             inner: WstsNetMessage::DkgBegin(wsts::net::DkgBegin { dkg_id: 0 }),
         };
 
@@ -1858,11 +2068,16 @@ mod tests {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
 
         // Create a DkgBegin message to be handled by the signer.
         let msg = message::WstsMessage {
             id: Txid::all_zeros().into(),
+            session_id: [0; 16],
+            sighash: None,
             inner: WstsNetMessage::DkgBegin(wsts::net::DkgBegin { dkg_id: 0 }),
         };
 
@@ -1898,7 +2113,7 @@ mod tests {
             dkg_id: 0,
             sign_id: 0,
             sign_iter_id: 0,
-            message: vec![],
+            message: vec![0; 32],
             signature_type: wsts::net::SignatureType::Schnorr,
         }); "NonceRequest")]
     #[test_case(
@@ -1906,7 +2121,7 @@ mod tests {
             dkg_id: 0,
             sign_id: 0,
             sign_iter_id: 0,
-            message: vec![],
+            message: vec![0; 32],
             signature_type: wsts::net::SignatureType::Schnorr,
             nonce_responses: vec![],
         }); "SignatureShareRequest")]
@@ -1943,10 +2158,15 @@ mod tests {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
 
         let msg = message::WstsMessage {
             id: Txid::all_zeros().into(),
+            session_id: [0; 16],
+            sighash: Some(TapSighash::all_zeros().into()),
             inner: wsts_message,
         };
 
@@ -1964,4 +2184,283 @@ mod tests {
             .await
             .expect("expected success");
     }
+
+    /// A signer that goes silent during DKG leaves the round hanging until
+    /// the coordinator aborts it. The other signers should drop their
+    /// state for the round, ignore late messages for it, and be able to
+    /// take part in the next round.
+    #[tokio::test]
+    async fn round_abort_clears_state_and_allows_a_new_round() {
+        let network = InMemoryNetwork::new();
+        let chain_tip = model::BitcoinBlockRef {
+            block_hash: Faker.fake(),
+            block_height: 100u64.into(),
+        };
+
+        let private_keys: Vec<PrivateKey> = std::iter::repeat_with(|| PrivateKey::new(&mut OsRng))
+            .take(3)
+            .collect();
+        let signer_set: BTreeSet<PublicKey> = private_keys
+            .iter()
+            .map(PublicKey::from_private_key)
+            .collect();
+        // The test contexts use the default config, which leaves the
+        // activation height of the new coordinator election unset.
+        let coordinator_public_key =
+            crate::coordinator::elect_at(&chain_tip, &signer_set, None).unwrap();
+        let coordinator_private_key = *private_keys
+            .iter()
+            .find(|key| PublicKey::from_private_key(key) == coordinator_public_key)
+            .unwrap();
+
+        // Signals fail to send without a receiver, so we hold on to one
+        // for each signer.
+        let mut signers = Vec::new();
+        let mut signal_receivers = Vec::new();
+        for private_key in private_keys.iter() {
+            let context = TestContext::builder()
+                .with_in_memory_storage()
+                .with_mocked_clients()
+                .build();
+            signal_receivers.push(context.get_signal_receiver());
+            context
+                .state()
+                .update_current_signer_set(signer_set.clone());
+            context
+                .get_storage_mut()
+                .write_bitcoin_block(&model::BitcoinBlock {
+                    block_height: chain_tip.block_height,
+                    parent_hash: Faker.fake(),
+                    block_hash: chain_tip.block_hash,
+                })
+                .await
+                .unwrap();
+
+            signers.push(TxSignerEventLoop {
+                context,
+                network: network.connect(),
+                signer_private_key: *private_key,
+                context_window: 1,
+                wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+                threshold: 2,
+                rng: OsRng,
+                dkg_begin_pause: None,
+                dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+                report_cache: ReportCache::default(),
+                round_tracker: RoundTracker::default(),
+                presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            });
+        }
+
+        let round_id = WstsMessageId::Dkg(Faker.fake());
+        let state_machine_id = StateMachineId::Dkg(chain_tip);
+        let from_coordinator = |payload: Payload| {
+            payload
+                .to_message(chain_tip.block_hash)
+                .sign_ecdsa(&coordinator_private_key)
+        };
+        let dkg_begin = from_coordinator(
+            message::WstsMessage {
+                id: round_id,
+                session_id: Faker.fake(),
+                sighash: None,
+                inner: WstsNetMessage::DkgBegin(wsts::net::DkgBegin { dkg_id: 1 }),
+            }
+            .into(),
+        );
+
+        // The last signer never hears about the round, so it never sends
+        // its public shares and the round cannot complete.
+        let (silent, active) = signers.split_last_mut().unwrap();
+        for signer in active.iter_mut() {
+            signer.handle_signer_message(&dkg_begin).await.unwrap();
+            assert!(signer.wsts_state_machines.contains(&state_machine_id));
+        }
+        assert!(!silent.wsts_state_machines.contains(&state_machine_id));
+
+        // The coordinator's timer fires and it aborts the round. Handling
+        // the abort twice should be the same as handling it once.
+        let abort = from_coordinator(
+            message::RoundAbort {
+                round_id,
+                reason: message::RoundAbortReason::Timeout,
+            }
+            .into(),
+        );
+        for signer in signers.iter_mut() {
+            signer.handle_signer_message(&abort).await.unwrap();
+            signer.handle_signer_message(&abort).await.unwrap();
+            assert!(!signer.wsts_state_machines.contains(&state_machine_id));
+            assert!(signer.round_tracker.is_aborted(&round_id));
+            assert_eq!(signer.round_tracker.late_messages(&round_id), 0);
+        }
+
+        // The silent signer finally wakes up and sends its public shares,
+        // which are late and should be ignored by everyone.
+        let late_shares = message::WstsMessage {
+            id: round_id,
+            session_id: Faker.fake(),
+            sighash: None,
+            inner: WstsNetMessage::DkgPublicShares(Unit.fake_with_rng(&mut OsRng)),
+        };
+        let late_shares: network::Msg = Payload::from(late_shares)
+            .to_message(chain_tip.block_hash)
+            .sign_ecdsa(private_keys.last().unwrap());
+        for signer in signers.iter_mut().take(2) {
+            signer.handle_signer_message(&late_shares).await.unwrap();
+            assert!(!signer.wsts_state_machines.contains(&state_machine_id));
+            assert_eq!(signer.round_tracker.late_messages(&round_id), 1);
+        }
+
+        // The coordinator starts a new round, and this time every signer
+        // takes part.
+        for signer in signers.iter_mut() {
+            signer.handle_signer_message(&dkg_begin).await.unwrap();
+            assert!(signer.wsts_state_machines.contains(&state_machine_id));
+            assert!(!signer.round_tracker.is_aborted(&round_id));
+        }
+    }
+
+    /// The coordinator can start a round again with the same WSTS ids, for
+    /// example after it restarts. Messages from the earlier session must
+    /// not leak into the state machine of the later one, even though they
+    /// look the same to WSTS.
+    #[tokio::test]
+    async fn wsts_messages_from_another_session_are_ignored() {
+        use crate::network::MessageTransfer as _;
+
+        let network = InMemoryNetwork::new();
+        let mut observer = network.connect();
+        let chain_tip = model::BitcoinBlockRef {
+            block_hash: Faker.fake(),
+            block_height: 100u64.into(),
+        };
+
+        let private_keys: Vec<PrivateKey> = std::iter::repeat_with(|| PrivateKey::new(&mut OsRng))
+            .take(2)
+            .collect();
+        let signer_set: BTreeSet<PublicKey> = private_keys
+            .iter()
+            .map(PublicKey::from_private_key)
+            .collect();
+        // The test contexts use the default config, which leaves the
+        // activation height of the new coordinator election unset.
+        let coordinator_public_key =
+            crate::coordinator::elect_at(&chain_tip, &signer_set, None).unwrap();
+        let coordinator_private_key = *private_keys
+            .iter()
+            .find(|key| PublicKey::from_private_key(key) == coordinator_public_key)
+            .unwrap();
+
+        let mut signers = Vec::new();
+        let mut signal_receivers = Vec::new();
+        for private_key in private_keys.iter() {
+            let context = TestContext::builder()
+                .with_in_memory_storage()
+                .with_mocked_clients()
+                .build();
+            signal_receivers.push(context.get_signal_receiver());
+            context
+                .state()
+                .update_current_signer_set(signer_set.clone());
+            context
+                .get_storage_mut()
+                .write_bitcoin_block(&model::BitcoinBlock {
+                    block_height: chain_tip.block_height,
+                    parent_hash: Faker.fake(),
+                    block_hash: chain_tip.block_hash,
+                })
+                .await
+                .unwrap();
+
+            signers.push(TxSignerEventLoop {
+                context,
+                network: network.connect(),
+                signer_private_key: *private_key,
+                context_window: 1,
+                wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+                threshold: 2,
+                rng: OsRng,
+                dkg_begin_pause: None,
+                dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+                report_cache: ReportCache::default(),
+                round_tracker: RoundTracker::default(),
+                presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            });
+        }
+
+        let round_id = WstsMessageId::Dkg(Faker.fake());
+        let state_machine_id = StateMachineId::Dkg(chain_tip);
+        let other_public_key = PublicKey::from_private_key(&private_keys[1]);
+
+        // Start the same round in two sessions, one after the other, and
+        // grab the public shares that the second signer sends in each.
+        let mut public_shares = Vec::new();
+        for session_id in [[1; 16], [2; 16]] {
+            let dkg_begin: network::Msg = Payload::from(message::WstsMessage {
+                id: round_id,
+                session_id,
+                sighash: None,
+                inner: WstsNetMessage::DkgBegin(wsts::net::DkgBegin { dkg_id: 1 }),
+            })
+            .to_message(chain_tip.block_hash)
+            .sign_ecdsa(&coordinator_private_key);
+
+            for signer in signers.iter_mut() {
+                signer.handle_signer_message(&dkg_begin).await.unwrap();
+            }
+
+            let shares = loop {
+                let msg = tokio::time::timeout(Duration::from_secs(5), observer.receive())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                let is_public_shares = matches!(
+                    &msg.inner.payload,
+                    Payload::WstsMessage(message::WstsMessage {
+                        inner: WstsNetMessage::DkgPublicShares(_),
+                        ..
+                    })
+                );
+                if is_public_shares && msg.signer_public_key == other_public_key {
+                    break msg;
+                }
+            };
+            public_shares.push(shares);
+        }
+
+        let other_signer_id = match &public_shares[0].inner.payload {
+            Payload::WstsMessage(message::WstsMessage {
+                inner: WstsNetMessage::DkgPublicShares(shares),
+                ..
+            }) => shares.signer_id,
+            _ => unreachable!(),
+        };
+
+        // The shares from the first session are dropped by the first
+        // signer, which is now in the second session.
+        let signer = &mut signers[0];
+        signer
+            .handle_signer_message(&public_shares[0])
+            .await
+            .unwrap();
+        let state_machine = signer.wsts_state_machines.get(&state_machine_id).unwrap();
+        assert!(
+            !state_machine
+                .dkg_public_shares
+                .contains_key(&other_signer_id)
+        );
+
+        // The shares from the second session are accepted.
+        signer
+            .handle_signer_message(&public_shares[1])
+            .await
+            .unwrap();
+        let state_machine = signer.wsts_state_machines.get(&state_machine_id).unwrap();
+        assert!(
+            state_machine
+                .dkg_public_shares
+                .contains_key(&other_signer_id)
+        );
+    }
 }
@@ -180,8 +180,11 @@ impl<T> InnerApiFallbackClient<T> {
             if let Err(error) = result {
                 tracing::warn!(%error, retry_num=i, max_retries=retry_count, "failover client call failed");
 
-                if retry_ctx.is_aborted() {
-                    return Err(error.into());
+                // Errors that are not retryable would happen again with
+                // the next client, so we give up on them right away.
+                let error: Error = error.into();
+                if retry_ctx.is_aborted() || !error.is_retryable() {
+                    return Err(error);
                 }
 
                 self.last_client_index.store(
@@ -226,18 +229,19 @@ mod tests {
 
     #[derive(Debug, PartialEq, Eq)]
     struct MockClient {
-        should_succeed: bool,
+        host: String,
         url: String,
     }
 
     impl MockClient {
         pub async fn call(&self) -> Result<(), Error> {
-            if self.should_succeed {
-                Ok(())
-            } else {
-                // Just picked a random error here (which isn't
-                // a AllFailoverClientsFailed)
-                Err(Error::Encryption)
+            match self.host.as_str() {
+                "ok" => Ok(()),
+                // Just picked a random terminal error here.
+                "terminal" => Err(Error::Encryption),
+                // And a random retryable one here (which isn't a
+                // AllFailoverClientsFailed).
+                _ => Err(Error::TokioIo(std::io::ErrorKind::ConnectionRefused.into())),
             }
         }
     }
@@ -245,7 +249,7 @@ mod tests {
     impl From<Url> for MockClient {
         fn from(url: Url) -> Self {
             Self {
-                should_succeed: url.host_str().unwrap() == "ok",
+                host: url.host_str().unwrap().to_string(),
                 url: url.to_string(),
             }
         }
@@ -431,6 +435,32 @@ mod tests {
 
         // Assert that the error is the error that the mock client returns
         // (which was just randomly chosen, it has no significance)
+        assert!(matches!(result.unwrap_err(), Error::TokioIo(_)));
+    }
+
+    #[tokio::test]
+    async fn returns_err_early_on_terminal_errors() {
+        let client = ApiFallbackClient::<MockClient>::from(
+            &[
+                Url::parse("http://terminal/1").unwrap(),
+                Url::parse("http://ok/2").unwrap(),
+            ][..],
+        );
+
+        // We'll use this to count how many times the closure is called
+        let call_count = AtomicUsize::new(0);
+
+        let result = client
+            .exec(|client, _| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                client.call()
+            })
+            .await;
+
+        // The error would happen again with the other client, so it is
+        // returned without failing over.
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
         assert!(matches!(result.unwrap_err(), Error::Encryption));
+        assert_eq!(client.last_client_index.load(Ordering::Relaxed), 0);
     }
 }
@@ -1,7 +1,9 @@
 //! Utilities for constructing and loading WSTS state machines
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::future::Future;
+use std::num::NonZeroUsize;
 
 use crate::codec::Decode as _;
 use crate::codec::Encode as _;
@@ -11,6 +13,8 @@ use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
 use crate::keys::PublicKeyXOnly;
 use crate::keys::SignerScriptPubKey as _;
+use crate::keys::share_encryption;
+use crate::message::WstsMessageId;
 use crate::storage;
 use crate::storage::model;
 use crate::storage::model::DkgSharesStatus;
@@ -18,6 +22,7 @@ use crate::storage::model::SigHash;
 
 use hashbrown::HashMap;
 use hashbrown::HashSet;
+use lru::LruCache;
 use rand::rngs::OsRng;
 use wsts::common::PolyCommitment;
 use wsts::net::Message;
@@ -79,6 +84,115 @@ impl From<SigHash> for StateMachineId {
     }
 }
 
+/// Tracks which signer state machines belong to which WSTS round, the
+/// session that each state machine was started for, and which rounds the
+/// coordinator has aborted.
+///
+/// A round is identified by the [`WstsMessageId`] of its messages, and a
+/// single round may use several state machines, for example one for each
+/// input of a sweep transaction. Each time the coordinator starts a state
+/// machine it picks a new session id, and only messages with that session
+/// id may be processed by the state machine. When a round is aborted its
+/// state machines are handed back for removal, and any further messages
+/// for the round are counted as late until the coordinator starts it
+/// again. All maps are bounded and evict the least recently used entries.
+#[derive(Debug)]
+pub struct RoundTracker {
+    /// The state machines that were created for each active round.
+    rounds: LruCache<WstsMessageId, BTreeSet<StateMachineId>>,
+    /// The session id that each state machine was started for.
+    sessions: LruCache<StateMachineId, [u8; 16]>,
+    /// The aborted rounds, with the number of late messages received for
+    /// each of them.
+    aborted: LruCache<WstsMessageId, u64>,
+}
+
+impl Default for RoundTracker {
+    fn default() -> Self {
+        let capacity = NonZeroUsize::new(crate::MAX_SIGNER_STATE_MACHINES as usize)
+            .unwrap_or(NonZeroUsize::MIN);
+        Self::new(capacity)
+    }
+}
+
+impl RoundTracker {
+    /// Create a new tracker that remembers at most `capacity` active
+    /// rounds, sessions and aborted rounds each.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            rounds: LruCache::new(capacity),
+            sessions: LruCache::new(capacity),
+            aborted: LruCache::new(capacity),
+        }
+    }
+
+    /// Record that the coordinator started the given state machine for
+    /// the given round and session. This replaces any earlier session of
+    /// the state machine and clears any earlier abort of the round, since
+    /// the coordinator has started it again.
+    pub fn start(
+        &mut self,
+        round_id: WstsMessageId,
+        state_machine_id: StateMachineId,
+        session_id: [u8; 16],
+    ) {
+        self.aborted.pop(&round_id);
+        self.sessions.put(state_machine_id, session_id);
+        self.track(round_id, state_machine_id);
+    }
+
+    /// Record that the given state machine is used for the given round,
+    /// without starting a new session for it.
+    pub fn track(&mut self, round_id: WstsMessageId, state_machine_id: StateMachineId) {
+        self.rounds
+            .get_or_insert_mut(round_id, BTreeSet::new)
+            .insert(state_machine_id);
+    }
+
+    /// The session id that the given state machine was last started for.
+    pub fn session(&self, state_machine_id: &StateMachineId) -> Option<[u8; 16]> {
+        self.sessions.peek(state_machine_id).copied()
+    }
+
+    /// Mark the given round as aborted and return the state machines that
+    /// were created for it. Aborting a round more than once is fine, the
+    /// later calls return an empty set.
+    pub fn abort(&mut self, round_id: WstsMessageId) -> BTreeSet<StateMachineId> {
+        if !self.aborted.contains(&round_id) {
+            self.aborted.put(round_id, 0);
+        }
+        let state_machine_ids = self.rounds.pop(&round_id).unwrap_or_default();
+        for state_machine_id in state_machine_ids.iter() {
+            self.sessions.pop(state_machine_id);
+        }
+        state_machine_ids
+    }
+
+    /// Whether the given round has been aborted.
+    pub fn is_aborted(&self, round_id: &WstsMessageId) -> bool {
+        self.aborted.contains(round_id)
+    }
+
+    /// Count a message that arrived for the given round. Returns `true`
+    /// if the round has been aborted, in which case the message is late
+    /// and should be ignored.
+    pub fn record_late_message(&mut self, round_id: &WstsMessageId) -> bool {
+        match self.aborted.get_mut(round_id) {
+            Some(count) => {
+                *count = count.saturating_add(1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The number of late messages received for the given round since it
+    /// was aborted.
+    pub fn late_messages(&self, round_id: &WstsMessageId) -> u64 {
+        self.aborted.peek(round_id).copied().unwrap_or_default()
+    }
+}
+
 /// A trait for converting a message into another type.
 pub trait FromMessage {
     /// Convert the given message into the implementing type.
@@ -498,11 +612,11 @@ impl SignerStateMachine {
             .await?
             .ok_or_else(|| error::Error::MissingDkgShares(aggregate_key))?;
 
-        let decrypted = wsts::util::decrypt(
-            &signer_private_key.to_bytes(),
+        let decrypted = share_encryption::decrypt_shares(
+            &signer_private_key,
+            &encrypted_shares.aggregate_key,
             &encrypted_shares.encrypted_private_shares,
-        )
-        .map_err(|_| error::Error::Encryption)?;
+        )?;
 
         let saved_state = wsts::traits::SignerState::decode(decrypted.as_slice())?;
 
@@ -546,9 +660,9 @@ impl SignerStateMachine {
         let public_shares = self.dkg_public_shares.clone().encode_to_vec();
 
         // After DKG, each of the signers will have "new public keys".
+        let network_private_key = PrivateKey::try_from(&self.0.network_private_key)?;
         let encrypted_private_shares =
-            wsts::util::encrypt(&self.0.network_private_key.to_bytes(), &encoded, rng)
-                .map_err(|_| error::Error::Encryption)?;
+            share_encryption::encrypt_shares(&network_private_key, &aggregate_key, &encoded, rng)?;
 
         let signature_share_threshold: u16 = self
             .threshold
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::ops::Deref;
+use std::time::Duration;
 
 use bitcoin::hashes::Hash as _;
 use rand::rngs::OsRng;
@@ -17,8 +18,10 @@ use signer::bitcoin::validation::TxRequestIds;
 use signer::bitcoin::validation::WithdrawalValidationResult;
 use signer::context::Context;
 use signer::context::SbtcLimits;
+use signer::error::Error;
 use signer::message::BitcoinPreSignRequest;
 use signer::storage::DbRead as _;
+use signer::storage::DbWrite as _;
 use signer::storage::model::TxPrevoutType;
 use signer::testing;
 use signer::testing::context::TestContext;
@@ -137,6 +140,9 @@ async fn one_tx_per_request_set() {
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_block.block_hash,
         chain_tip_height: chain_tip_block.block_height,
@@ -242,6 +248,9 @@ async fn one_invalid_deposit_invalidates_tx() {
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_block.block_hash,
         chain_tip_height: chain_tip_block.block_height,
@@ -408,6 +417,9 @@ async fn withdrawals_and_deposits_can_pass_validation(amounts: Vec<SweepAmounts>
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_ref.block_hash,
         chain_tip_height: chain_tip_ref.block_height,
@@ -514,6 +526,9 @@ async fn swept_withdrawals_fail_validation() {
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_ref.block_hash,
         chain_tip_height: chain_tip_ref.block_height,
@@ -636,6 +651,9 @@ async fn cannot_sign_deposit_is_ok() {
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_block.block_hash,
         chain_tip_height: chain_tip_block.block_height,
@@ -706,6 +724,7 @@ async fn cannot_sign_deposit_is_ok() {
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        max_requests_per_bitcoin_tx: None,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -774,6 +793,9 @@ async fn sighashes_match_from_sbtc_requests_object() {
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_block.block_hash,
         chain_tip_height: chain_tip_block.block_height,
@@ -840,6 +862,7 @@ async fn sighashes_match_from_sbtc_requests_object() {
         num_signers: 3,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        max_requests_per_bitcoin_tx: None,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -918,6 +941,9 @@ async fn outcome_is_independent_of_input_order() {
         last_fees: None,
     };
 
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
     let btc_ctx = BitcoinTxContext {
         chain_tip: chain_tip_block.block_hash,
         chain_tip_height: chain_tip_block.block_height,
@@ -944,3 +970,203 @@ async fn outcome_is_independent_of_input_order() {
 
     testing::storage::drop_db(db).await;
 }
+
+/// Test that once a signer has agreed to sign a sweep transaction spending
+/// the signers' UTXO, it rejects a competing proposal spending the same
+/// UTXO while the chain tip is the same, but accepts the same proposal
+/// again.
+#[tokio::test]
+async fn competing_proposals_for_signer_utxo_are_rejected() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_stacks_client()
+        .with_mocked_emily_client()
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    let signers = TestSignerSet::new(&mut rng);
+    let amounts = [SweepAmounts {
+        amount: 1_000_000,
+        max_fee: 500_000,
+        is_deposit: true,
+    }];
+
+    let mut setup = TestSweepSetup2::new_setup(signers, faucet, &amounts);
+    setup.deposits.sort_by_key(|(x, _, _)| x.outpoint);
+    backfill_bitcoin_blocks(&db, rpc, &setup.deposit_block_hash).await;
+
+    setup.store_stacks_genesis_block(&db).await;
+    setup.store_dkg_shares(&db).await;
+    setup.store_donation(&db).await;
+    setup.store_deposit_txs(&db).await;
+    setup.store_deposit_request(&db).await;
+    setup.store_deposit_decisions(&db).await;
+
+    let chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap().unwrap();
+    let chain_tip_block = db.get_bitcoin_block(&chain_tip).await.unwrap().unwrap();
+
+    let aggregate_key = setup.signers.signer.keypair.public_key().into();
+
+    let mut request = BitcoinPreSignRequest {
+        request_package: vec![TxRequestIds {
+            deposits: setup.deposit_outpoints(),
+            withdrawals: Vec::new(),
+        }],
+        fee_rate: TEST_FEE_RATE,
+        last_fees: None,
+    };
+
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
+    let btc_ctx = BitcoinTxContext {
+        chain_tip: chain_tip_block.block_hash,
+        chain_tip_height: chain_tip_block.block_height,
+        signer_public_key: setup.signers.keys[0],
+        aggregate_key,
+    };
+
+    // The first proposal is fine, and we record our intent to sign it,
+    // just like the transaction signer does.
+    let validation_data = request
+        .construct_package_sighashes(&ctx, &btc_ctx)
+        .await
+        .unwrap();
+    let input_rows = validation_data[0].to_input_rows();
+    assert!(input_rows.iter().all(|row| row.will_sign));
+    db.write_bitcoin_txs_sighashes(&input_rows).await.unwrap();
+
+    let donation = setup.donation;
+    let max_age = ctx.config().signer.in_flight_signer_utxo_timeout;
+    let in_flight = db
+        .get_in_flight_signer_prevout_txids(&chain_tip, &donation, max_age)
+        .await
+        .unwrap();
+    assert_eq!(in_flight, vec![input_rows[0].txid]);
+
+    // The coordinator may resend the same proposal, and that results in
+    // the same transaction, so it is still fine.
+    request
+        .construct_package_sighashes(&ctx, &btc_ctx)
+        .await
+        .unwrap();
+
+    // Now a competing proposal comes in. It spends the same signers' UTXO
+    // but with a different fee rate, so the transaction is different.
+    request.fee_rate = TEST_FEE_RATE * 2.0;
+    let result = request.construct_package_sighashes(&ctx, &btc_ctx).await;
+    match result {
+        Err(Error::SignerUtxoAlreadyInFlight { outpoint, txid }) => {
+            assert_eq!(outpoint, donation);
+            assert_eq!(txid, input_rows[0].txid);
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Test that a sweep transaction that the signer agreed to sign stops
+/// being in-flight once the configured timeout passes, even if the chain
+/// tip has not changed. This is what happens when a signing round fails:
+/// the signer wrote its sighashes, but the transaction was never
+/// broadcast, and the coordinator needs to propose another one.
+#[tokio::test]
+async fn in_flight_signer_utxo_expires_after_timeout() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    let timeout = Duration::from_secs(2);
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_stacks_client()
+        .with_mocked_emily_client()
+        .modify_settings(|settings| {
+            settings.signer.in_flight_signer_utxo_timeout = timeout;
+        })
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    let signers = TestSignerSet::new(&mut rng);
+    let amounts = [SweepAmounts {
+        amount: 1_000_000,
+        max_fee: 500_000,
+        is_deposit: true,
+    }];
+
+    let mut setup = TestSweepSetup2::new_setup(signers, faucet, &amounts);
+    setup.deposits.sort_by_key(|(x, _, _)| x.outpoint);
+    backfill_bitcoin_blocks(&db, rpc, &setup.deposit_block_hash).await;
+
+    setup.store_stacks_genesis_block(&db).await;
+    setup.store_dkg_shares(&db).await;
+    setup.store_donation(&db).await;
+    setup.store_deposit_txs(&db).await;
+    setup.store_deposit_request(&db).await;
+    setup.store_deposit_decisions(&db).await;
+
+    let chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap().unwrap();
+    let chain_tip_block = db.get_bitcoin_block(&chain_tip).await.unwrap().unwrap();
+
+    let aggregate_key = setup.signers.signer.keypair.public_key().into();
+
+    let mut request = BitcoinPreSignRequest {
+        request_package: vec![TxRequestIds {
+            deposits: setup.deposit_outpoints(),
+            withdrawals: Vec::new(),
+        }],
+        fee_rate: TEST_FEE_RATE,
+        last_fees: None,
+    };
+
+    let signer_set = setup.signers.keys.iter().copied().collect();
+    ctx.state().update_current_signer_set(signer_set);
+
+    let btc_ctx = BitcoinTxContext {
+        chain_tip: chain_tip_block.block_hash,
+        chain_tip_height: chain_tip_block.block_height,
+        signer_public_key: setup.signers.keys[0],
+        aggregate_key,
+    };
+
+    // We agree to sign the first proposal, but the signing round fails
+    // and the transaction is never broadcast.
+    let validation_data = request
+        .construct_package_sighashes(&ctx, &btc_ctx)
+        .await
+        .unwrap();
+    let input_rows = validation_data[0].to_input_rows();
+    db.write_bitcoin_txs_sighashes(&input_rows).await.unwrap();
+
+    // Right away, a different proposal for the same UTXO is rejected.
+    request.fee_rate = TEST_FEE_RATE * 2.0;
+    let result = request.construct_package_sighashes(&ctx, &btc_ctx).await;
+    assert!(matches!(
+        result,
+        Err(Error::SignerUtxoAlreadyInFlight { .. })
+    ));
+
+    // Once the timeout passes the first transaction is no longer
+    // in-flight, so the new proposal is accepted with the same chain tip.
+    tokio::time::sleep(timeout + Duration::from_secs(1)).await;
+
+    let in_flight = db
+        .get_in_flight_signer_prevout_txids(&chain_tip, &setup.donation, timeout)
+        .await
+        .unwrap();
+    assert!(in_flight.is_empty());
+
+    request
+        .construct_package_sighashes(&ctx, &btc_ctx)
+        .await
+        .unwrap();
+
+    testing::storage::drop_db(db).await;
+}
@@ -19,6 +19,10 @@ use clarity::types::chainstate::StacksAddress;
 use clarity::vm::types::PrincipalData;
 use emily_client::apis::deposit_api;
 use emily_client::models::CreateDepositRequestBody;
+use emily_client::models::Status;
+use emily_client::models::Withdrawal;
+use emily_client::models::WithdrawalInfo;
+use emily_client::models::WithdrawalParameters;
 use fake::Fake as _;
 use fake::Faker;
 use sbtc::deposits::CreateDepositRequest;
@@ -31,6 +35,7 @@ use signer::bitcoin::utxo::SignerBtcState;
 use signer::block_observer::get_signer_set_and_aggregate_key;
 use signer::context::SbtcLimits;
 use signer::emily_client::EmilyClient;
+use signer::emily_client::FetchedDeposits;
 use signer::error::Error;
 use signer::keys::PublicKey;
 use signer::keys::SignerScriptPubKey as _;
@@ -60,6 +65,7 @@ use signer::storage::DbRead as _;
 use signer::testing;
 use signer::testing::context::TestContext;
 use signer::testing::context::*;
+use signer::testing::emily::EmilyHandler;
 use signer::testing::get_rng;
 use signer::testing::storage::model::TestData;
 use signer::transaction_coordinator::should_coordinate_dkg;
@@ -70,7 +76,6 @@ use crate::setup::IntoEmilyTestingConfig as _;
 use crate::setup::TestSweepSetup;
 use crate::transaction_coordinator::mock_reqwests_status_code_error;
 use crate::utxo_construction::make_deposit_request;
-use crate::zmq::BITCOIN_CORE_ZMQ_ENDPOINT;
 
 pub const GET_POX_INFO_JSON: &str =
     include_str!("../../tests/fixtures/stacksapi-get-pox-info-test-data.json");
@@ -104,14 +109,20 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
 
     // Let's prep Emily with information about these deposits.
     ctx.with_emily_client(|client| {
-        let emily_client_response = vec![
-            setup0.emily_deposit_request(),
-            setup1.emily_deposit_request(),
-        ];
+        let emily_client_response = FetchedDeposits {
+            deposits: vec![
+                setup0.emily_deposit_request(),
+                setup1.emily_deposit_request(),
+            ],
+            truncated: false,
+        };
         client
             .expect_get_deposits()
             .times(1..)
             .returning(move || Box::pin(std::future::ready(Ok(emily_client_response.clone()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
 
         client
             .expect_get_limits()
@@ -161,7 +172,7 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     // We need at least one receiver
@@ -237,6 +248,540 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
     testing::storage::drop_db(db).await;
 }
 
+/// This is the same as the test above, except that the deposits come from
+/// the actual Emily API, served in process, instead of a mocked client.
+///
+/// To run the test first do:
+/// - make integration-env-up-ci
+///
+/// Then you should be good to go.
+#[test_log::test(tokio::test)]
+async fn block_observer_persists_deposit_requests_from_emily_handler() {
+    let mut rng = get_rng();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    let emily = EmilyHandler::start().await;
+    emily.wipe_databases().await;
+
+    let db = testing::storage::new_test_database().await;
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_emily_client_url(&emily.url())
+        .with_mocked_stacks_client()
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    // Emily validates the deposit transactions when they are created, so
+    // we need deposits that are actually on our regtest chain.
+    let setup0 = TestSweepSetup::new_setup(rpc, faucet, 100_000, &mut rng);
+    let setup1 = TestSweepSetup::new_setup(rpc, faucet, 200_000, &mut rng);
+
+    for setup in [&setup0, &setup1] {
+        let body = setup
+            .deposit_request
+            .as_emily_request(&setup.deposit_tx_info.tx);
+        emily.seed_deposit(body).await;
+    }
+
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_tenure()
+            .returning(|_| Box::pin(std::future::ready(TenureBlocks::nearly_empty())));
+
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_sortition_info()
+            .returning(move |_| Box::pin(std::future::ready(Ok(DUMMY_SORTITION_INFO.clone()))));
+    })
+    .await;
+
+    let start_flag = Arc::new(AtomicBool::new(false));
+    let flag = start_flag.clone();
+
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
+    };
+
+    // We need at least one receiver
+    let _signal = ctx.get_signal_receiver();
+
+    tokio::spawn(async move {
+        flag.store(true, Ordering::Relaxed);
+        block_observer.run().await
+    });
+
+    while !start_flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The block observer fetches the deposits from Emily when it processes
+    // a new block, so we wait for it to catch up with a new chain tip.
+    let chain_tip: BitcoinBlockHash = faucet.generate_blocks(1).pop().unwrap().into();
+
+    let waiting_fut = async {
+        let mut current_chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap();
+        while current_chain_tip != Some(chain_tip) {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            current_chain_tip = db.get_bitcoin_canonical_chain_tip().await.unwrap();
+        }
+    };
+
+    tokio::time::timeout(Duration::from_secs(3), waiting_fut)
+        .await
+        .unwrap();
+
+    let deposit_requests = db.get_deposit_requests(&chain_tip, 100).await.unwrap();
+
+    assert_eq!(deposit_requests.len(), 2);
+    let req_outpoints: HashSet<OutPoint> =
+        deposit_requests.iter().map(|req| req.outpoint()).collect();
+
+    assert!(req_outpoints.contains(&setup0.deposit_info.outpoint));
+    assert!(req_outpoints.contains(&setup1.deposit_info.outpoint));
+
+    testing::storage::drop_db(db).await;
+}
+
+/// The [`BlockObserver::load_latest_withdrawal_requests`] function is
+/// supposed to fetch pending withdrawal requests from Emily and persist
+/// the ones that were created in a stacks block anchored to a bitcoin
+/// block within the signer's context window.
+#[test_case::test_case(1, true; "one block ago")]
+#[test_case::test_case(5, false; "outside the context window")]
+#[test_log::test(tokio::test)]
+async fn load_latest_withdrawal_requests_persists_requests_from_past(
+    blocks_ago: u64,
+    persisted: bool,
+) {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let db = testing::storage::new_test_database().await;
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .modify_settings(|settings| settings.signer.context_window = 3)
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    // The withdrawal request is created in a stacks block anchored to the
+    // current bitcoin chain tip. We write both blocks to the database
+    // before the block observer starts, as if we had observed them
+    // without receiving the new_block webhook with the withdrawal event.
+    let anchor_hash = rpc.get_best_block_hash().unwrap();
+    let anchor_header = rpc.get_block_header_info(&anchor_hash).unwrap();
+    let anchor = model::BitcoinBlock {
+        block_hash: anchor_hash.into(),
+        block_height: (anchor_header.height as u64).into(),
+        parent_hash: anchor_header.previous_block_hash.unwrap().into(),
+    };
+    let stacks_block = StacksBlock::new_genesis().anchored_to(&anchor);
+    db.write_bitcoin_block(&anchor).await.unwrap();
+    db.write_stacks_block(&stacks_block).await.unwrap();
+
+    let withdrawal = Withdrawal {
+        amount: 10_000,
+        fulfillment: None,
+        last_update_block_hash: stacks_block.block_hash.to_string(),
+        last_update_height: *stacks_block.block_height,
+        parameters: Box::new(WithdrawalParameters { max_fee: 500 }),
+        recipient: "0014abababababababababababababababababababab".to_string(),
+        request_id: 1,
+        sender: "ST000000000000000000002AMW42H".to_string(),
+        stacks_block_hash: stacks_block.block_hash.to_string(),
+        stacks_block_height: *stacks_block.block_height,
+        status: Status::Pending,
+        status_message: String::new(),
+        txid: "11".repeat(32),
+    };
+    let withdrawal_info = WithdrawalInfo {
+        amount: withdrawal.amount,
+        last_update_block_hash: withdrawal.last_update_block_hash.clone(),
+        last_update_height: withdrawal.last_update_height,
+        recipient: withdrawal.recipient.clone(),
+        request_id: withdrawal.request_id,
+        sender: withdrawal.sender.clone(),
+        stacks_block_hash: withdrawal.stacks_block_hash.clone(),
+        stacks_block_height: withdrawal.stacks_block_height,
+        status: withdrawal.status,
+        txid: withdrawal.txid.clone(),
+    };
+
+    ctx.with_emily_client(|client| {
+        client
+            .expect_get_deposits()
+            .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .times(1..)
+            .returning(move |_| Box::pin(std::future::ready(Ok(vec![withdrawal_info.clone()]))));
+        client
+            .expect_get_withdrawal()
+            .returning(move |_| Box::pin(std::future::ready(Ok(Some(withdrawal.clone())))));
+        client
+            .expect_get_limits()
+            .returning(|| Box::pin(async { Ok(SbtcLimits::unlimited()) }));
+    })
+    .await;
+
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_tenure()
+            .returning(|_| Box::pin(std::future::ready(TenureBlocks::nearly_empty())));
+
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_sortition_info()
+            .returning(move |_| Box::pin(std::future::ready(Ok(DUMMY_SORTITION_INFO.clone()))));
+    })
+    .await;
+
+    faucet.generate_blocks(blocks_ago);
+
+    let start_flag = Arc::new(AtomicBool::new(false));
+    let flag = start_flag.clone();
+
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
+    };
+
+    let mut signal_rx = ctx.get_signal_receiver();
+
+    tokio::spawn(async move {
+        flag.store(true, Ordering::Relaxed);
+        block_observer.run().await
+    });
+
+    while !start_flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Each new block triggers a fetch from Emily, and fetching the same
+    // withdrawal request more than once must not create duplicates.
+    for _ in 0..2 {
+        faucet.generate_blocks(1);
+        let waiting_fut = async {
+            loop {
+                if let Ok(SignerSignal::Event(SignerEvent::BitcoinBlockObserved)) =
+                    signal_rx.recv().await
+                {
+                    break;
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(3), waiting_fut)
+            .await
+            .unwrap();
+    }
+
+    let withdrawal_requests = sqlx::query_as::<_, (i64, [u8; 32], i64, i64)>(
+        r#"
+        SELECT request_id
+             , block_hash
+             , max_fee
+             , bitcoin_block_height
+        FROM sbtc_signer.withdrawal_requests"#,
+    )
+    .fetch_all(db.pool())
+    .await
+    .unwrap();
+
+    if persisted {
+        let expected_height = i64::try_from(anchor.block_height).unwrap();
+        let expected = (
+            1,
+            stacks_block.block_hash.into_bytes(),
+            500,
+            expected_height,
+        );
+        assert_eq!(withdrawal_requests, vec![expected]);
+    } else {
+        assert!(withdrawal_requests.is_empty());
+    }
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the block observer detects a bitcoin reorg, signals the
+/// fork point along with the orphaned blocks, and marks the orphaned
+/// blocks in the database so that they are no longer considered
+/// canonical.
+#[test_log::test(tokio::test)]
+async fn block_observer_signals_bitcoin_reorgs() {
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let db = testing::storage::new_test_database().await;
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    ctx.with_emily_client(|client| {
+        client
+            .expect_get_deposits()
+            .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
+        client
+            .expect_get_limits()
+            .returning(|| Box::pin(async { Ok(SbtcLimits::unlimited()) }));
+    })
+    .await;
+
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_tenure()
+            .returning(|_| Box::pin(std::future::ready(TenureBlocks::nearly_empty())));
+
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_sortition_info()
+            .returning(move |_| Box::pin(std::future::ready(Ok(DUMMY_SORTITION_INFO.clone()))));
+    })
+    .await;
+
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
+    };
+
+    let mut signal_rx = ctx.get_signal_receiver();
+    let block_observer_handle = tokio::spawn(async move { block_observer.run().await });
+
+    // Let the block observer store a new chain tip.
+    let orphaned_block_hash = faucet.generate_block();
+    let waiting_fut = async {
+        while db.get_bitcoin_canonical_chain_tip().await.unwrap()
+            != Some(orphaned_block_hash.into())
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(3), waiting_fut)
+        .await
+        .unwrap();
+
+    let orphaned_block = rpc.get_block_header_info(&orphaned_block_hash).unwrap();
+    let fork_point = orphaned_block.previous_block_hash.unwrap();
+
+    // Now we replace that block with a competing chain. We mine the new
+    // blocks to a different address so that they differ from the
+    // invalidated one.
+    rpc.invalidate_block(&orphaned_block_hash).unwrap();
+    let recipient = Recipient::new(AddressType::P2wpkh);
+    let new_chain_tip = rpc
+        .generate_to_address(2, &recipient.address)
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let waiting_fut = async {
+        loop {
+            if let Ok(SignerSignal::Event(SignerEvent::BitcoinReorgObserved {
+                fork_point,
+                orphaned,
+            })) = signal_rx.recv().await
+            {
+                break (fork_point, orphaned);
+            }
+        }
+    };
+    let (signalled_fork_point, orphaned) =
+        tokio::time::timeout(Duration::from_secs(5), waiting_fut)
+            .await
+            .unwrap();
+
+    assert_eq!(signalled_fork_point.block_hash, fork_point.into());
+    assert_eq!(
+        *signalled_fork_point.block_height,
+        orphaned_block.height as u64 - 1
+    );
+    assert_eq!(orphaned, vec![orphaned_block_hash.into()]);
+
+    // The orphaned block is no longer the canonical chain tip, even once
+    // the new chain has caught up with its height.
+    let waiting_fut = async {
+        while db.get_bitcoin_canonical_chain_tip().await.unwrap() != Some(new_chain_tip.into()) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(3), waiting_fut)
+        .await
+        .unwrap();
+
+    block_observer_handle.abort();
+    rpc.reconsider_block(&orphaned_block_hash).unwrap();
+    testing::storage::drop_db(db).await;
+}
+
+/// Check that the block observer fetches the bitcoin blocks that were
+/// mined while it was not running when it starts up, before it receives
+/// any new block notifications.
+#[test_log::test(tokio::test)]
+async fn block_observer_catches_up_on_missed_blocks_on_startup() {
+    let (_, faucet) = regtest::initialize_blockchain();
+    let db = testing::storage::new_test_database().await;
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+    ctx.state().update_current_limits(SbtcLimits::unlimited());
+
+    ctx.with_emily_client(|client| {
+        client
+            .expect_get_deposits()
+            .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
+        client
+            .expect_get_limits()
+            .returning(|| Box::pin(async { Ok(SbtcLimits::unlimited()) }));
+    })
+    .await;
+
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_tenure()
+            .returning(|_| Box::pin(std::future::ready(TenureBlocks::nearly_empty())));
+
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+
+        client
+            .expect_get_sortition_info()
+            .returning(move |_| Box::pin(std::future::ready(Ok(DUMMY_SORTITION_INFO.clone()))));
+    })
+    .await;
+
+    let wait_for_chain_tip = |chain_tip: BitcoinBlockHash| {
+        let db = db.clone();
+        async move {
+            while db.get_bitcoin_canonical_chain_tip().await.unwrap() != Some(chain_tip) {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    };
+
+    // Let the block observer store the current chain tip and then stop
+    // it.
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
+    };
+    let block_observer_handle = tokio::spawn(async move { block_observer.run().await });
+
+    let last_observed_block: BitcoinBlockHash = faucet.generate_block().into();
+    tokio::time::timeout(
+        Duration::from_secs(3),
+        wait_for_chain_tip(last_observed_block),
+    )
+    .await
+    .unwrap();
+    block_observer_handle.abort();
+
+    // Now some blocks get mined while the block observer is down.
+    let chain_tip: BitcoinBlockHash = faucet.generate_blocks(20).pop().unwrap().into();
+
+    // When we restart the block observer it should fetch all of the blocks
+    // that it missed, even though no new blocks have been mined.
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
+    };
+    let block_observer_handle = tokio::spawn(async move { block_observer.run().await });
+
+    tokio::time::timeout(Duration::from_secs(10), wait_for_chain_tip(chain_tip))
+        .await
+        .unwrap();
+    block_observer_handle.abort();
+
+    // The stored chain links up with the block we observed before
+    // stopping the block observer, without any gaps.
+    let mut block_hash = chain_tip;
+    for _ in 0..20 {
+        let block = db.get_bitcoin_block(&block_hash).await.unwrap().unwrap();
+        block_hash = block.parent_hash;
+    }
+    assert_eq!(block_hash, last_observed_block);
+
+    testing::storage::drop_db(db).await;
+}
+
 /// Integration test for bitcoin and stack blocks link.
 ///
 /// To run this test first run:
@@ -260,13 +805,16 @@ async fn link_blocks() {
     ctx.with_emily_client(|client| {
         client
             .expect_get_deposits()
-            .returning(move || Box::pin(std::future::ready(Ok(vec![]))));
+            .returning(move || Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
     })
     .await;
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     let mut signal_rx = ctx.get_signal_receiver();
@@ -423,7 +971,7 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     tokio::spawn(async move {
@@ -564,6 +1112,7 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: ctx.config().signer.max_deposits_per_bitcoin_tx.get(),
+        max_requests_per_bitcoin_tx: None,
     };
 
     let mut transactions = requests.construct_transactions().unwrap();
@@ -692,7 +1241,10 @@ async fn block_observer_handles_update_limits(deployed: bool, sbtc_limits: SbtcL
     ctx.with_emily_client(|client| {
         client
             .expect_get_deposits()
-            .returning(move || Box::pin(std::future::ready(Ok(vec![]))));
+            .returning(move || Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
     })
     .await;
 
@@ -739,7 +1291,7 @@ async fn block_observer_handles_update_limits(deployed: bool, sbtc_limits: SbtcL
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     let mut signal_receiver = ctx.get_signal_receiver();
@@ -911,6 +1463,7 @@ async fn get_signer_public_keys_and_aggregate_key_falls_back() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_params);
     test_data.write_to(&db).await;
@@ -1031,7 +1584,10 @@ async fn block_observer_updates_state_after_observing_bitcoin_block() {
     ctx.with_emily_client(|client| {
         client
             .expect_get_deposits()
-            .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+            .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
 
         client
             .expect_get_limits()
@@ -1046,7 +1602,7 @@ async fn block_observer_updates_state_after_observing_bitcoin_block() {
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     // In this test the signer set public keys start empty. When running
@@ -1257,7 +1813,10 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
     ctx.with_emily_client(|client| {
         client
             .expect_get_deposits()
-            .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+            .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
 
         client
             .expect_get_limits()
@@ -1272,7 +1831,7 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     // In this test the signer set public keys start empty. When running
@@ -1425,6 +1984,122 @@ async fn block_observer_updates_dkg_shares_after_observing_bitcoin_block() {
     testing::storage::drop_db(db).await;
 }
 
+/// Check that the block observer processes blocks when it receives full
+/// blocks over the ZMQ `rawblock` topic, instead of just their hashes.
+#[test_log::test(tokio::test)]
+async fn block_observer_processes_blocks_from_raw_block_stream() {
+    let mut rng = get_rng();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+
+    // Generate a block to ensure we start with an empty mempool
+    faucet.generate_block();
+
+    let chain_tip_info = rpc.get_chain_tips().unwrap().pop().unwrap();
+
+    let db = testing::storage::new_test_database().await;
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .modify_settings(|settings| {
+            // Our bitcoin-core publishes raw blocks on the same endpoint
+            // as block hashes.
+            settings.bitcoin.block_stream_endpoints =
+                settings.bitcoin.block_hash_stream_endpoints.clone()
+        })
+        .with_first_bitcoin_core_client()
+        .with_mocked_emily_client()
+        .with_mocked_stacks_client()
+        .build();
+
+    let mut signal_receiver = ctx.get_signal_receiver();
+
+    ctx.with_emily_client(|client| {
+        client
+            .expect_get_deposits()
+            .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+        client
+            .expect_get_withdrawals()
+            .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
+        client
+            .expect_get_limits()
+            .returning(|| Box::pin(std::future::ready(Ok(SbtcLimits::unlimited()))));
+    })
+    .await;
+
+    // The block observer reaches out to the stacks node to get the most
+    // up-to-date information. We don't have stacks-core running so we mock
+    // these calls.
+    ctx.with_stacks_client(|client| {
+        client
+            .expect_get_tenure_info()
+            .returning(move || Box::pin(std::future::ready(Ok(DUMMY_TENURE_INFO.clone()))));
+
+        let chain_tip = BitcoinBlockHash::from(chain_tip_info.hash);
+        client.expect_get_tenure().returning(move |_| {
+            let mut tenure = TenureBlocks::nearly_empty().unwrap();
+            tenure.anchor_block_hash = chain_tip;
+            Box::pin(std::future::ready(Ok(tenure)))
+        });
+
+        client.expect_get_pox_info().returning(|| {
+            let response = serde_json::from_str::<RPCPoxInfoData>(GET_POX_INFO_JSON)
+                .map_err(Error::JsonSerialize);
+            Box::pin(std::future::ready(response))
+        });
+    })
+    .await;
+
+    // We need to have run DKG in order for the block observer to know
+    // which addresses to filter on.
+    let signer = Recipient::new(AddressType::P2tr);
+    let mut shares: EncryptedDkgShares = Faker.fake_with_rng(&mut rng);
+    shares.aggregate_key = signer.keypair.public_key().into();
+    shares.script_pubkey = shares.aggregate_key.signers_script_pubkey().into();
+    shares.dkg_shares_status = model::DkgSharesStatus::Verified;
+    db.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+    let start_flag = Arc::new(AtomicBool::new(false));
+    let flag = start_flag.clone();
+
+    let block_observer = BlockObserver {
+        context: ctx.clone(),
+        bitcoin_blocks: testing::btc::new_zmq_block_stream(&ctx.config().bitcoin).await,
+    };
+
+    tokio::spawn(async move {
+        flag.store(true, Ordering::Relaxed);
+        block_observer.run().await
+    });
+
+    while !start_flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Now we send a donation to the signers and mine a block with it.
+    let script_pub_key = shares.script_pubkey.deref();
+    let address = Address::from_script(script_pub_key, bitcoin::Network::Regtest).unwrap();
+
+    let donation_amount = 123_456;
+    let donation_outpoint = faucet.send_to(donation_amount, &address);
+    let block_hash = faucet.generate_block();
+
+    let signal = signal_receiver.recv();
+    let Ok(SignerSignal::Event(SignerEvent::BitcoinBlockObserved)) = signal.await else {
+        panic!("Not the right signal")
+    };
+
+    // The block should be in the database, and the donation within the
+    // block should have been extracted from the full block.
+    let db_block = db.get_bitcoin_block(&block_hash.into()).await.unwrap();
+    assert!(db_block.is_some());
+
+    let donations = fetch_output(&db, TxOutputType::Donation).await;
+    assert_eq!(donations.len(), 1);
+    assert_eq!(donations[0].amount, donation_amount);
+    assert_eq!(donations[0].txid.deref(), &donation_outpoint.txid);
+
+    testing::storage::drop_db(db).await;
+}
+
 #[test_log::test(tokio::test)]
 async fn block_observer_ignores_coinbase() {
     let mut rng = get_rng();
@@ -1492,7 +2167,7 @@ async fn block_observer_ignores_coinbase() {
 
     let block_observer = BlockObserver {
         context: ctx.clone(),
-        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT).await,
+        bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
     };
 
     tokio::spawn(async move {
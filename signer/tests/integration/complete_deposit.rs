@@ -7,9 +7,11 @@ use signer::stacks::contracts::AsContractCall as _;
 use signer::stacks::contracts::CompleteDepositV1;
 use signer::stacks::contracts::DepositErrorMsg;
 use signer::stacks::contracts::ReqContext;
+use signer::storage::DbWrite as _;
 use signer::storage::model::BitcoinBlockRef;
 use signer::storage::model::BitcoinTxId;
 use signer::storage::model::StacksPrincipal;
+use signer::storage::model::SweptDeposit;
 use signer::testing;
 use signer::testing::context::*;
 use signer::testing::get_rng;
@@ -943,6 +945,85 @@ async fn complete_deposit_validation_deposit_incorrect_fee() {
     testing::storage::drop_db(db).await;
 }
 
+/// For this test we check that the `CompleteDepositV1::validate` function
+/// returns a deposit validation error with a MintExceedsSweep message when
+/// the fee recorded for the deposit would have the signers mint more sBTC
+/// than the bitcoin that they kept from the sweep transaction, even though
+/// the contract call agrees with the recorded fee.
+#[tokio::test]
+async fn complete_deposit_validation_mint_exceeds_sweep() {
+    // Normal: this generates the blockchain as well as deposit request
+    // transactions and a transaction sweeping in the deposited funds.
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+    let (rpc, faucet) = regtest::initialize_blockchain();
+    let setup = TestSweepSetup::new_setup(&rpc, &faucet, 1_000_000, &mut rng);
+
+    // Normal: the signers' block observer should be getting new block
+    // events from bitcoin-core. We haven't hooked up our block observer,
+    // so we need to manually update the database with new bitcoin block
+    // headers and at least one stacks block.
+    backfill_bitcoin_blocks(&db, rpc, &setup.sweep_block_hash).await;
+    // Normal: This stores a genesis stacks block anchored to the bitcoin
+    // blockchain identified by setup.sweep_block_hash.
+    setup.store_stacks_genesis_block(&db).await;
+
+    // Normal: we take the deposit transaction as is from the test setup
+    // and store it in the database. This is necessary for when we fetch
+    // outstanding unfulfilled deposit requests.
+    setup.store_deposit_tx(&db).await;
+
+    // Normal: we take the sweep transaction as is from the test setup and
+    // store it in the database.
+    setup.store_sweep_tx(&db).await;
+
+    // Normal: we need to store a row in the dkg_shares table so that we
+    // have a record of the scriptPubKey that the signers control.
+    setup.store_dkg_shares(&db).await;
+
+    // Normal: the request and how the signers voted needs to be added to
+    // the database. Here the bitmap in the deposit request object
+    // corresponds to how the signers voted.
+    setup.store_deposit_request(&db).await;
+    setup.store_deposit_decisions(&db).await;
+
+    // Different: the fee recorded for the deposit when the sweep was
+    // confirmed is zero, so the signers would mint the full deposit
+    // amount even though part of it went to the bitcoin miners.
+    let swept_deposit = SweptDeposit {
+        sweep_txid: setup.sweep_tx_info.txid.into(),
+        txid: setup.deposit_request.outpoint.txid.into(),
+        output_index: setup.deposit_request.outpoint.vout,
+        assessed_fee: 0,
+    };
+    db.write_swept_deposit(&swept_deposit).await.unwrap();
+
+    // Different: the contract call mints the full deposit amount, in line
+    // with the recorded fee.
+    let (mut complete_deposit_tx, req_ctx) = make_complete_deposit(&setup);
+    complete_deposit_tx.amount = setup.deposit_request.amount;
+
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_first_bitcoin_core_client()
+        .with_mocked_stacks_client()
+        .with_mocked_emily_client()
+        .build();
+
+    // Normal: the request is not completed in the smart contract.
+    set_deposit_incomplete(&mut ctx).await;
+
+    let validation_result = complete_deposit_tx.validate(&ctx, &req_ctx).await;
+    match validation_result.unwrap_err() {
+        Error::DepositValidation(ref err) => {
+            assert_eq!(err.error, DepositErrorMsg::MintExceedsSweep)
+        }
+        err => panic!("unexpected error during validation {err}"),
+    }
+
+    testing::storage::drop_db(db).await;
+}
+
 /// For this test we check that the `CompleteDepositV1::validate` function
 /// returns a deposit validation error with a InvalidSweep message when the
 /// sweep transaction does not have a prevout with a scriptPubKey that the
@@ -87,6 +87,7 @@ where
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(rng, &signer_keys, &test_model_parameters);
     test_data.write_to(&storage).await;
@@ -261,6 +262,12 @@ async fn deposit_flow() {
                 .once()
                 // Dummy value
                 .returning(|| Box::pin(async { Ok(1.3) }));
+
+            // The block observer tries to catch up on bitcoin blocks when
+            // it starts, we want it to only use the block stream.
+            client
+                .expect_get_blockchain_info()
+                .returning(|| Box::pin(async { Err(Error::NoChainTip) }));
         })
         .await;
 
@@ -418,7 +425,15 @@ async fn deposit_flow() {
     let block_observer_handle = tokio::spawn(async move { block_observer.run().await });
 
     // Get the private key of the coordinator of the signer set.
-    let private_key = select_coordinator(&deposit_block_hash.into(), &signer_info);
+    let deposit_block_ref = model::BitcoinBlockRef {
+        block_hash: deposit_block_hash.into(),
+        block_height: bitcoin_chain_tip.block_height + 1,
+    };
+    let activation_height = context
+        .config()
+        .signer
+        .coordinator_election_activation_height;
+    let private_key = select_coordinator(&deposit_block_ref, activation_height, &signer_info);
 
     // Bootstrap the tx coordinator event loop
     context.state().set_sbtc_contracts_deployed();
@@ -680,7 +695,8 @@ async fn test_get_deposits_with_status_request_paging(
     let deposits = emily_client
         .get_deposits_with_status(Status::Pending)
         .await
-        .unwrap();
+        .unwrap()
+        .deposits;
     assert_eq!(deposits.len(), expected_result);
 }
 
@@ -742,6 +758,7 @@ async fn test_get_deposits_returns_pending_and_accepted() {
             fulfillment: None,
             status: Status::Accepted,
             status_message: "accepted".to_string(),
+            updated_by: None,
         })
         .collect();
 
@@ -753,15 +770,17 @@ async fn test_get_deposits_returns_pending_and_accepted() {
     .expect("cannot update deposits");
 
     // Check that we get all deposits
-    let deposits = emily_client.get_deposits().await.unwrap();
+    let deposits = emily_client.get_deposits().await.unwrap().deposits;
     let accepted_deposits = emily_client
         .get_deposits_with_status(Status::Accepted)
         .await
-        .unwrap();
+        .unwrap()
+        .deposits;
     let pending_deposits = emily_client
         .get_deposits_with_status(Status::Pending)
         .await
-        .unwrap();
+        .unwrap()
+        .deposits;
 
     assert_eq!(deposits.len(), num_deposits);
     assert_eq!(accepted_deposits.len(), num_accepted);
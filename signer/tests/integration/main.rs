@@ -14,6 +14,8 @@ mod rbf;
 mod request_decider;
 mod rotate_keys;
 mod setup;
+mod signer_cluster;
+mod storage_conformance;
 mod tls_checking;
 mod transaction_coordinator;
 mod transaction_signer;
@@ -1,9 +1,12 @@
 use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::io::Read as _;
+use std::num::NonZeroU32;
 use std::ops::Deref;
 use std::time::Duration;
 
+use axum::extract::State;
+use axum::http::StatusCode;
 use bitcoin::hashes::Hash as _;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::clarity::vm::Value as ClarityValue;
@@ -18,6 +21,8 @@ use more_asserts::assert_le;
 use rand::seq::IteratorRandom as _;
 use rand::seq::SliceRandom as _;
 use signer::WITHDRAWAL_BLOCKS_EXPIRY;
+use signer::api::ApiState;
+use signer::api::new_block_handler;
 use signer::bitcoin::validation::WithdrawalRequestStatus;
 use signer::bitcoin::validation::WithdrawalValidationResult;
 use signer::storage::model::BitcoinBlockHeight;
@@ -96,6 +101,7 @@ async fn should_be_able_to_query_bitcoin_blocks() {
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
@@ -350,6 +356,7 @@ async fn should_return_the_same_pending_deposit_requests_as_in_memory_store() {
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -523,6 +530,7 @@ async fn should_return_the_same_pending_withdraw_requests_as_in_memory_store() {
         num_withdraw_requests_per_block: 1,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -600,6 +608,7 @@ async fn should_return_the_same_pending_accepted_deposit_requests_as_in_memory_s
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let threshold = 4;
 
@@ -739,6 +748,7 @@ async fn should_return_only_accepted_pending_deposits_that_are_within_reclaim_bo
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let threshold = 4;
 
@@ -924,6 +934,7 @@ async fn should_return_the_same_last_key_rotation_as_in_memory_store() {
         num_withdraw_requests_per_block: 1,
         num_signers_per_request: 7,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let num_signers = 7;
     let threshold = 4;
@@ -1257,6 +1268,396 @@ async fn writing_withdrawal_reject_requests_postgres() {
     signer::testing::storage::drop_db(store).await;
 }
 
+/// The stacks node can deliver the same webhook more than once, so here
+/// we check that writing the same stacks block events twice leaves the
+/// database unchanged the second time.
+#[tokio::test]
+async fn writing_stacks_block_events_is_idempotent() {
+    let store = testing::storage::new_test_database().await;
+
+    let mut rng = get_rng();
+    let events = model::StacksBlockEvents {
+        completed_deposits: vec![fake::Faker.fake_with_rng(&mut rng)],
+        withdrawal_requests: vec![fake::Faker.fake_with_rng(&mut rng)],
+        withdrawal_accepts: vec![fake::Faker.fake_with_rng(&mut rng)],
+        withdrawal_rejects: vec![fake::Faker.fake_with_rng(&mut rng)],
+        key_rotations: vec![fake::Faker.fake_with_rng(&mut rng)],
+    };
+
+    let tables = [
+        "completed_deposit_events",
+        "withdrawal_requests",
+        "withdrawal_accept_events",
+        "withdrawal_reject_events",
+        "rotate_keys_transactions",
+    ];
+
+    for _ in 0..2 {
+        store.write_stacks_block_events(&events).await.unwrap();
+
+        for table in tables {
+            let count: i64 =
+                sqlx::query_scalar(&format!("SELECT COUNT(*) FROM sbtc_signer.{table}"))
+                    .fetch_one(store.pool())
+                    .await
+                    .unwrap();
+            assert_eq!(count, 1, "unexpected row count in {table}");
+        }
+    }
+
+    signer::testing::storage::drop_db(store).await;
+}
+
+/// Write a transaction confirmed in the given bitcoin block.
+async fn confirm_bitcoin_tx(db: &PgStore, txid: BitcoinTxId, block_hash: BitcoinBlockHash) {
+    let tx = model::Transaction {
+        tx_type: model::TransactionType::SbtcTransaction,
+        txid: txid.to_byte_array(),
+        block_hash: block_hash.to_byte_array(),
+    };
+    db.write_transaction(&tx).await.unwrap();
+    let tx_ref = model::BitcoinTxRef { txid, block_hash };
+    db.write_bitcoin_transaction(&tx_ref).await.unwrap();
+}
+
+/// Here we check that pruning removes deposit requests that have been
+/// swept and completed, along with stale forks, while deposit requests
+/// that have not been swept are kept regardless of their age.
+#[tokio::test]
+async fn prune_below_height_keeps_unswept_deposit_requests() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    // A canonical chain of 20 bitcoin blocks with a stacks chain
+    // anchored to it, and a fork block at height 1.
+    let mut blocks: Vec<BitcoinBlock> = Vec::new();
+    let mut stacks_blocks: Vec<model::StacksBlock> = Vec::new();
+    for height in 0..20u64 {
+        let block = BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: height.into(),
+            parent_hash: blocks
+                .last()
+                .map(|block| block.block_hash)
+                .unwrap_or_else(|| Faker.fake_with_rng(&mut rng)),
+        };
+        db.write_bitcoin_block(&block).await.unwrap();
+
+        let stacks_block = model::StacksBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: height.into(),
+            parent_hash: stacks_blocks
+                .last()
+                .map(|block| block.block_hash)
+                .unwrap_or_else(|| Faker.fake_with_rng(&mut rng)),
+            bitcoin_anchor: block.block_hash,
+        };
+        db.write_stacks_block(&stacks_block).await.unwrap();
+
+        blocks.push(block);
+        stacks_blocks.push(stacks_block);
+    }
+    let fork_block = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 1u64.into(),
+        parent_hash: blocks[0].block_hash,
+    };
+    db.write_bitcoin_block(&fork_block).await.unwrap();
+
+    // Two deposit requests confirmed in the oldest block, only one of
+    // which gets swept and completed.
+    let swept: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    let unswept: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    for request in [&swept, &unswept] {
+        confirm_bitcoin_tx(&db, request.txid, blocks[1].block_hash).await;
+        db.write_deposit_request(request).await.unwrap();
+    }
+
+    let sweep_txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+    confirm_bitcoin_tx(&db, sweep_txid, blocks[2].block_hash).await;
+    let prevout = model::TxPrevout {
+        txid: sweep_txid,
+        prevout_txid: swept.txid,
+        prevout_output_index: swept.output_index,
+        prevout_type: model::TxPrevoutType::Deposit,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_tx_prevout(&prevout).await.unwrap();
+
+    let event = CompletedDepositEvent {
+        block_id: stacks_blocks[3].block_hash,
+        outpoint: swept.outpoint(),
+        sweep_block_hash: blocks[2].block_hash.into(),
+        sweep_block_height: blocks[2].block_height,
+        sweep_txid: sweep_txid.into(),
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_completed_deposit_event(&event).await.unwrap();
+
+    db.prune_below_height(10u64.into()).await.unwrap();
+
+    let unswept_request = db
+        .get_deposit_request(&unswept.txid, unswept.output_index)
+        .await
+        .unwrap();
+    assert!(unswept_request.is_some());
+    let swept_request = db
+        .get_deposit_request(&swept.txid, swept.output_index)
+        .await
+        .unwrap();
+    assert!(swept_request.is_none());
+
+    // Canonical blocks are kept while the fork is removed.
+    let block = db.get_bitcoin_block(&blocks[0].block_hash).await.unwrap();
+    assert!(block.is_some());
+    let block = db.get_bitcoin_block(&fork_block.block_hash).await.unwrap();
+    assert!(block.is_none());
+
+    // Pruning again is harmless, and the unswept request is still there.
+    db.prune_below_height(19u64.into()).await.unwrap();
+    let unswept_request = db
+        .get_deposit_request(&unswept.txid, unswept.output_index)
+        .await
+        .unwrap();
+    assert!(unswept_request.is_some());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Here we check that pruning only treats a deposit request as settled
+/// when it was swept on the canonical bitcoin blockchain and completed on
+/// the canonical stacks blockchain. A sweep or a complete-deposit event
+/// that only happened on a fork must not get the request pruned.
+#[tokio::test]
+async fn prune_below_height_ignores_deposit_sweeps_and_events_on_forks() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    // A canonical bitcoin chain of 20 blocks, with a canonical stacks
+    // chain anchored to it, one stacks block per bitcoin block.
+    let mut blocks: Vec<BitcoinBlock> = Vec::new();
+    let mut stacks_blocks: Vec<model::StacksBlock> = Vec::new();
+    for height in 0..20u64 {
+        let block = BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: height.into(),
+            parent_hash: blocks
+                .last()
+                .map(|block| block.block_hash)
+                .unwrap_or_else(|| Faker.fake_with_rng(&mut rng)),
+        };
+        db.write_bitcoin_block(&block).await.unwrap();
+
+        let stacks_block = model::StacksBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: height.into(),
+            parent_hash: stacks_blocks
+                .last()
+                .map(|block| block.block_hash)
+                .unwrap_or_else(|| Faker.fake_with_rng(&mut rng)),
+            bitcoin_anchor: block.block_hash,
+        };
+        db.write_stacks_block(&stacks_block).await.unwrap();
+
+        blocks.push(block);
+        stacks_blocks.push(stacks_block);
+    }
+
+    // A bitcoin block and a stacks block that fork off of their
+    // canonical chains.
+    let fork_block = BitcoinBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 2u64.into(),
+        parent_hash: blocks[1].block_hash,
+    };
+    db.write_bitcoin_block(&fork_block).await.unwrap();
+    let fork_stacks_block = model::StacksBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 3u64.into(),
+        parent_hash: stacks_blocks[2].block_hash,
+        bitcoin_anchor: blocks[3].block_hash,
+    };
+    db.write_stacks_block(&fork_stacks_block).await.unwrap();
+
+    // Three deposit requests confirmed in the same block. The first is
+    // swept and completed on the canonical chains, the second is only
+    // swept on the bitcoin fork and the third is only completed on the
+    // stacks fork.
+    let canonical: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    let fork_swept: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    let fork_completed: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    let deposits = [
+        (
+            &canonical,
+            blocks[2].block_hash,
+            stacks_blocks[3].block_hash,
+        ),
+        (
+            &fork_swept,
+            fork_block.block_hash,
+            stacks_blocks[3].block_hash,
+        ),
+        (
+            &fork_completed,
+            blocks[2].block_hash,
+            fork_stacks_block.block_hash,
+        ),
+    ];
+
+    for (request, sweep_block_hash, stacks_block_hash) in deposits {
+        confirm_bitcoin_tx(&db, request.txid, blocks[1].block_hash).await;
+        db.write_deposit_request(request).await.unwrap();
+
+        let sweep_txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+        confirm_bitcoin_tx(&db, sweep_txid, sweep_block_hash).await;
+        let prevout = model::TxPrevout {
+            txid: sweep_txid,
+            prevout_txid: request.txid,
+            prevout_output_index: request.output_index,
+            prevout_type: model::TxPrevoutType::Deposit,
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_tx_prevout(&prevout).await.unwrap();
+
+        let event = CompletedDepositEvent {
+            block_id: stacks_block_hash,
+            outpoint: request.outpoint(),
+            sweep_block_hash: sweep_block_hash.into(),
+            sweep_block_height: 2u64.into(),
+            sweep_txid: sweep_txid.into(),
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_completed_deposit_event(&event).await.unwrap();
+    }
+
+    db.prune_below_height(10u64.into()).await.unwrap();
+
+    // Only the deposit that was swept and completed on the canonical
+    // chains is settled.
+    let request = db
+        .get_deposit_request(&canonical.txid, canonical.output_index)
+        .await
+        .unwrap();
+    assert!(request.is_none());
+    let request = db
+        .get_deposit_request(&fork_swept.txid, fork_swept.output_index)
+        .await
+        .unwrap();
+    assert!(request.is_some());
+    let request = db
+        .get_deposit_request(&fork_completed.txid, fork_completed.output_index)
+        .await
+        .unwrap();
+    assert!(request.is_some());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Here we check that pruning only treats a withdrawal request as settled
+/// when its accept or reject event is on the canonical stacks blockchain.
+/// An accept event in a stacks block from a fork must not get the request
+/// pruned.
+#[tokio::test]
+async fn prune_below_height_ignores_withdrawal_events_on_stacks_forks() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    // A canonical bitcoin chain of 20 blocks, with a canonical stacks
+    // chain anchored to it, one stacks block per bitcoin block.
+    let mut blocks: Vec<BitcoinBlock> = Vec::new();
+    let mut stacks_blocks: Vec<model::StacksBlock> = Vec::new();
+    for height in 0..20u64 {
+        let block = BitcoinBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: height.into(),
+            parent_hash: blocks
+                .last()
+                .map(|block| block.block_hash)
+                .unwrap_or_else(|| Faker.fake_with_rng(&mut rng)),
+        };
+        db.write_bitcoin_block(&block).await.unwrap();
+
+        let stacks_block = model::StacksBlock {
+            block_hash: Faker.fake_with_rng(&mut rng),
+            block_height: height.into(),
+            parent_hash: stacks_blocks
+                .last()
+                .map(|block| block.block_hash)
+                .unwrap_or_else(|| Faker.fake_with_rng(&mut rng)),
+            bitcoin_anchor: block.block_hash,
+        };
+        db.write_stacks_block(&stacks_block).await.unwrap();
+
+        blocks.push(block);
+        stacks_blocks.push(stacks_block);
+    }
+
+    // A stacks block that forks off of the canonical stacks chain. It is
+    // anchored to a canonical bitcoin block, but it is not an ancestor of
+    // the stacks chain tip.
+    let fork_stacks_block = model::StacksBlock {
+        block_hash: Faker.fake_with_rng(&mut rng),
+        block_height: 2u64.into(),
+        parent_hash: stacks_blocks[1].block_hash,
+        bitcoin_anchor: blocks[2].block_hash,
+    };
+    db.write_stacks_block(&fork_stacks_block).await.unwrap();
+
+    // Two withdrawal requests created in the same stacks block. One is
+    // accepted on the canonical stacks chain and the other is only
+    // accepted on the fork.
+    let canonical_accept: WithdrawalRequest = WithdrawalRequest {
+        request_id: 1,
+        block_hash: stacks_blocks[1].block_hash,
+        bitcoin_block_height: blocks[1].block_height,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    let fork_accept: WithdrawalRequest = WithdrawalRequest {
+        request_id: 2,
+        block_hash: stacks_blocks[1].block_hash,
+        bitcoin_block_height: blocks[1].block_height,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_request(&canonical_accept)
+        .await
+        .unwrap();
+    db.write_withdrawal_request(&fork_accept).await.unwrap();
+
+    let event = model::WithdrawalAcceptEvent {
+        request_id: canonical_accept.request_id,
+        block_id: stacks_blocks[2].block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_accept_event(&event).await.unwrap();
+    let event = model::WithdrawalAcceptEvent {
+        request_id: fork_accept.request_id,
+        block_id: fork_stacks_block.block_hash,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_accept_event(&event).await.unwrap();
+
+    db.prune_below_height(10u64.into()).await.unwrap();
+
+    let pool = db.pool();
+    let count_requests = move |request_id: u64| {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM sbtc_signer.withdrawal_requests WHERE request_id = $1",
+        )
+        .bind(i64::try_from(request_id).unwrap())
+        .fetch_one(pool)
+    };
+
+    // The request accepted on the canonical stacks chain is settled, but
+    // the one accepted only on the fork is not.
+    assert_eq!(
+        count_requests(canonical_accept.request_id).await.unwrap(),
+        0
+    );
+    assert_eq!(count_requests(fork_accept.request_id).await.unwrap(), 1);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// For this test we check that when we get the votes for a deposit request
 /// for a specific aggregate key, that we get a vote for all public keys
 /// for the specific aggregate key. This includes "implicit" votes where we
@@ -1375,6 +1776,7 @@ async fn fetching_deposit_signer_decisions() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -1488,6 +1890,7 @@ async fn fetching_withdrawal_signer_decisions() {
         num_withdraw_requests_per_block: 1,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -1717,6 +2120,7 @@ async fn block_in_canonical_bitcoin_blockchain_in_other_block_chain() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2032,6 +2436,7 @@ async fn is_known_bitcoin_block_hash_works() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2140,6 +2545,7 @@ async fn get_swept_withdrawal_requests_returns_swept_withdrawal_requests() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2272,6 +2678,7 @@ async fn get_swept_withdrawal_requests_does_not_return_unswept_withdrawal_reques
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2540,6 +2947,7 @@ async fn get_swept_withdrawal_requests_does_not_return_withdrawal_requests_with_
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -2953,6 +3361,7 @@ async fn get_swept_withdrawal_requests_response_tx_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3090,6 +3499,7 @@ async fn transaction_coordinator_test_environment(
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 7,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let context = TestContext::builder()
@@ -3198,6 +3608,7 @@ async fn deposit_report_with_only_deposit_request() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3297,6 +3708,7 @@ async fn deposit_report_with_deposit_request_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3371,6 +3783,7 @@ async fn deposit_report_with_deposit_request_spent() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3467,6 +3880,7 @@ async fn deposit_report_with_deposit_request_swept_but_swept_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3596,6 +4010,7 @@ async fn deposit_report_with_deposit_request_confirmed() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3687,6 +4102,7 @@ async fn withdrawal_report_with_no_withdrawal_request_or_no_block() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3761,6 +4177,7 @@ async fn withdrawal_report_with_no_withdrawal_votes() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3879,6 +4296,7 @@ async fn withdrawal_report_with_withdrawal_request_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -3961,6 +4379,7 @@ async fn withdrawal_report_with_withdrawal_request_fulfilled() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4077,6 +4496,7 @@ async fn withdrawal_report_with_withdrawal_request_swept_but_swept_reorged() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4231,6 +4651,7 @@ async fn withdrawal_report_with_withdrawal_request_swept_but_swept_reorged2() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4395,6 +4816,7 @@ async fn withdrawal_report_with_withdrawal_request_confirmed() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_public_keys = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -4642,6 +5064,7 @@ async fn signer_utxo_reorg_suite<const N: usize>(desc: ReorgDescription<N>) {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     // Let's generate some dummy data and write it into the database.
@@ -5123,6 +5546,7 @@ async fn pending_rejected_withdrawal_no_events() {
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: num_signers,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
@@ -5223,6 +5647,7 @@ async fn pending_rejected_withdrawal_expiration() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -5324,6 +5749,7 @@ async fn pending_rejected_withdrawal_rejected_already_rejected() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -5462,6 +5888,7 @@ async fn pending_rejected_withdrawal_already_accepted() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
     let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
     let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
@@ -5859,6 +6286,7 @@ async fn compute_withdrawn_total_gets_all_amounts_in_chain() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
     // The number of signers does not matter
     let num_signers = 1;
@@ -5957,6 +6385,7 @@ async fn compute_withdrawn_total_ignores_withdrawals_not_identified_blockchain()
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: true,
+        deposit_scripts: None,
     };
     // The number of signers does not matter
     let num_signers = 1;
@@ -7241,3 +7670,314 @@ mod get_pending_accepted_withdrawal_requests {
         assert_eq!(requests.len(), 0);
     }
 }
+
+/// When writing the events of a webhook keeps failing, the new_block
+/// handler should only ask the stacks node to retry a fixed number of
+/// times. After that it gives up on the webhook and marks it as poisoned,
+/// and a poisoned webhook can be reprocessed once the problem is fixed.
+#[tokio::test]
+async fn new_block_handler_gives_up_after_max_webhook_attempts() {
+    const MAX_ATTEMPTS: u32 = 3;
+    let db = testing::storage::new_test_database().await;
+
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_mocked_clients()
+        .modify_settings(|settings| {
+            settings.signer.event_observer.max_webhook_attempts =
+                NonZeroU32::new(MAX_ATTEMPTS).unwrap()
+        })
+        .build();
+
+    // Renaming the table that the events are written to makes every
+    // attempt at processing the webhook fail with a SQL error.
+    sqlx::query("ALTER TABLE sbtc_signer.withdrawal_reject_events RENAME TO renamed_events")
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let body = include_str!("../fixtures/withdrawal-reject-event.json");
+    for _ in 1..MAX_ATTEMPTS {
+        let state = State(ApiState { ctx: ctx.clone() });
+        let status = new_block_handler(state, body.to_string()).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(db.get_poisoned_webhooks().await.unwrap().is_empty());
+    }
+
+    let state = State(ApiState { ctx: ctx.clone() });
+    let status = new_block_handler(state, body.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let poisoned = db.get_poisoned_webhooks().await.unwrap();
+    assert_eq!(poisoned.len(), 1);
+    assert_eq!(poisoned[0].failed_attempts, MAX_ATTEMPTS);
+    assert_eq!(poisoned[0].payload, body);
+
+    // Once the problem is fixed, replaying the poisoned webhook writes
+    // its events and clears the record of the failed attempts.
+    sqlx::query("ALTER TABLE sbtc_signer.renamed_events RENAME TO withdrawal_reject_events")
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+    let state = State(ApiState { ctx: ctx.clone() });
+    let status = new_block_handler(state, poisoned[0].payload.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(db.get_poisoned_webhooks().await.unwrap().is_empty());
+
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM sbtc_signer.withdrawal_reject_events")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+    assert_eq!(count, 1);
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Failed events are returned in the order of their index in the stacks
+/// block, and recording the same event twice keeps the first record.
+#[tokio::test]
+async fn failed_events_round_trip() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let block_hash: model::StacksBlockHash = Faker.fake_with_rng(&mut rng);
+    let events: Vec<model::FailedEvent> = [3, 1]
+        .into_iter()
+        .map(|event_index| model::FailedEvent {
+            block_hash,
+            event_index,
+            txid: Faker.fake_with_rng(&mut rng),
+            event_topic: "withdrawal-reject".to_string(),
+            field: Some("signer-bitmap".to_string()),
+            reason: "the field is missing from the event".to_string(),
+            payload: "{}".to_string(),
+        })
+        .collect();
+
+    for event in events.iter() {
+        db.write_failed_event(event).await.unwrap();
+    }
+
+    let rewritten = model::FailedEvent {
+        reason: "something else".to_string(),
+        ..events[0].clone()
+    };
+    db.write_failed_event(&rewritten).await.unwrap();
+
+    let stored = db.get_failed_events(&block_hash).await.unwrap();
+    assert_eq!(stored, vec![events[1].clone(), events[0].clone()]);
+
+    let other_block: model::StacksBlockHash = Faker.fake_with_rng(&mut rng);
+    assert!(db.get_failed_events(&other_block).await.unwrap().is_empty());
+
+    testing::storage::drop_db(db).await;
+}
+
+/// Withdrawal recipients and deposit senders are stored as the
+/// scriptPubKeys of the addresses, and they round trip through the
+/// database for every kind of address.
+#[test_case(bitcoin::AddressType::P2pkh; "p2pkh")]
+#[test_case(bitcoin::AddressType::P2sh; "p2sh")]
+#[test_case(bitcoin::AddressType::P2wpkh; "p2wpkh")]
+#[test_case(bitcoin::AddressType::P2wsh; "p2wsh")]
+#[test_case(bitcoin::AddressType::P2tr; "p2tr")]
+#[tokio::test]
+async fn bitcoin_addresses_round_trip(address_type: bitcoin::AddressType) {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let network = bitcoin::Network::Regtest;
+    let secret_key = secp256k1::SecretKey::new(&mut rand::rngs::OsRng);
+    let public_key = bitcoin::CompressedPublicKey(secret_key.public_key(secp256k1::SECP256K1));
+    let address = match address_type {
+        bitcoin::AddressType::P2pkh => bitcoin::Address::p2pkh(public_key, network),
+        bitcoin::AddressType::P2sh => bitcoin::Address::p2shwpkh(&public_key, network),
+        bitcoin::AddressType::P2wpkh => bitcoin::Address::p2wpkh(&public_key, network),
+        bitcoin::AddressType::P2wsh => {
+            let script = bitcoin::ScriptBuf::new_p2pk(&bitcoin::PublicKey::new(public_key.0));
+            bitcoin::Address::p2wsh(&script, network)
+        }
+        bitcoin::AddressType::P2tr => {
+            let (x_only_key, _) = public_key.0.x_only_public_key();
+            bitcoin::Address::p2tr(secp256k1::SECP256K1, x_only_key, None, network)
+        }
+        _ => unreachable!("unsupported address type"),
+    };
+    let script_pubkey = ScriptPubKey::from(address.script_pubkey());
+
+    let deposit_request = model::DepositRequest {
+        sender_script_pub_keys: vec![script_pubkey.clone()],
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_deposit_request(&deposit_request).await.unwrap();
+
+    let stored = db
+        .get_deposit_request(&deposit_request.txid, deposit_request.output_index)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, deposit_request);
+
+    let stacks_block: model::StacksBlock = Faker.fake_with_rng(&mut rng);
+    let withdrawal_request = model::WithdrawalRequest {
+        block_hash: stacks_block.block_hash,
+        recipient: script_pubkey.clone(),
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_stacks_block(&stacks_block).await.unwrap();
+    db.write_withdrawal_request(&withdrawal_request)
+        .await
+        .unwrap();
+
+    let recipient = sqlx::query_scalar::<_, ScriptPubKey>(
+        r#"
+        SELECT recipient
+        FROM sbtc_signer.withdrawal_requests
+        WHERE request_id = $1
+          AND block_hash = $2"#,
+    )
+    .bind(i64::try_from(withdrawal_request.request_id).unwrap())
+    .bind(withdrawal_request.block_hash)
+    .fetch_one(db.pool())
+    .await
+    .unwrap();
+    assert_eq!(recipient, script_pubkey);
+
+    // The stored scriptPubKeys turn back into the same addresses.
+    for script in [&stored.sender_script_pub_keys[0], &recipient] {
+        let round_trip = bitcoin::Address::from_script(script, network.params()).unwrap();
+        assert_eq!(round_trip, address);
+        assert_eq!(round_trip.address_type(), Some(address_type));
+    }
+
+    testing::storage::drop_db(db).await;
+}
+
+/// The stacks burn chain tip is the highest bitcoin block that the stacks
+/// node told us about, regardless of the order that we learned of them.
+#[tokio::test]
+async fn stacks_burn_chain_tip_is_the_highest_burn_block() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    assert!(db.get_stacks_burn_chain_tip().await.unwrap().is_none());
+
+    let block = model::BitcoinBlockRef {
+        block_height: 100u64.into(),
+        block_hash: Faker.fake_with_rng(&mut rng),
+    };
+    let lower = model::BitcoinBlockRef {
+        block_height: 99u64.into(),
+        block_hash: Faker.fake_with_rng(&mut rng),
+    };
+
+    db.write_stacks_burn_block(&block).await.unwrap();
+    db.write_stacks_burn_block(&lower).await.unwrap();
+    // Writing the same block again does nothing.
+    db.write_stacks_burn_block(&block).await.unwrap();
+
+    let tip = db.get_stacks_burn_chain_tip().await.unwrap();
+    assert_eq!(tip, Some(block));
+
+    testing::storage::drop_db(db).await;
+}
+
+#[tokio::test]
+async fn swept_deposit_fees_round_trip() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let request: model::DepositRequest = Faker.fake_with_rng(&mut rng);
+    db.write_deposit_request(&request).await.unwrap();
+
+    let swept_deposit = model::SweptDeposit {
+        txid: request.txid,
+        output_index: request.output_index,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_swept_deposit(&swept_deposit).await.unwrap();
+
+    let stored = db
+        .get_swept_deposit(
+            &swept_deposit.sweep_txid,
+            &request.txid,
+            request.output_index,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored, Some(swept_deposit.clone()));
+
+    // The fee assessed by a sweep transaction does not change, so
+    // writing it again is a no-op.
+    let rewritten = model::SweptDeposit {
+        assessed_fee: swept_deposit.assessed_fee + 1,
+        ..swept_deposit.clone()
+    };
+    db.write_swept_deposit(&rewritten).await.unwrap();
+    let stored = db
+        .get_swept_deposit(
+            &swept_deposit.sweep_txid,
+            &request.txid,
+            request.output_index,
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored, Some(swept_deposit.clone()));
+
+    // Another sweep transaction does not have a record of the fee.
+    let other_sweep_txid: BitcoinTxId = Faker.fake_with_rng(&mut rng);
+    let stored = db
+        .get_swept_deposit(&other_sweep_txid, &request.txid, request.output_index)
+        .await
+        .unwrap();
+    assert_eq!(stored, None);
+
+    testing::storage::drop_db(db).await;
+}
+
+#[test_case(model::SweepRequestId::Deposit {
+    txid: BitcoinTxId::from([1; 32]),
+    output_index: 2,
+}; "deposit")]
+#[test_case(model::SweepRequestId::Withdrawal {
+    request_id: 3,
+    block_hash: StacksBlockHash::from([4; 32]),
+}; "withdrawal")]
+#[tokio::test]
+async fn request_attempt_history_is_in_attempt_order(request: model::SweepRequestId) {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let outcomes = [
+        model::SweepAttemptOutcome::Broadcast,
+        model::SweepAttemptOutcome::Rejected,
+        model::SweepAttemptOutcome::Proposed,
+    ];
+    let mut attempts = Vec::new();
+    for outcome in outcomes {
+        let attempt = model::SweepAttempt {
+            request,
+            outcome,
+            error_detail: (outcome == model::SweepAttemptOutcome::Rejected)
+                .then(|| "not enough signatures".to_string()),
+            ..Faker.fake_with_rng(&mut rng)
+        };
+        db.write_sweep_attempt(&attempt).await.unwrap();
+        attempts.push(attempt);
+
+        // Attempts for other requests are not part of the history.
+        let other: model::SweepAttempt = Faker.fake_with_rng(&mut rng);
+        db.write_sweep_attempt(&other).await.unwrap();
+    }
+
+    let history = db.get_request_attempt_history(&request).await.unwrap();
+    assert_eq!(history, attempts);
+
+    let counts = db.get_sweep_attempt_counts().await.unwrap();
+    let total = counts.proposed + counts.signed + counts.broadcast + counts.rejected;
+    assert_eq!(total, 6);
+
+    testing::storage::drop_db(db).await;
+}
@@ -231,6 +231,7 @@ pub fn transaction_with_rbf(
         num_signers: 2 * failure_threshold,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_requests_per_bitcoin_tx: None,
     };
 
     // Okay, lets submit the transaction. We also do a sanity check where
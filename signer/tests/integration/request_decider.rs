@@ -1,9 +1,11 @@
+use std::num::NonZeroU16;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use bitcoin::consensus::encode::serialize_hex;
+use bitcoin::relative::LockTime;
 use fake::Fake;
 use fake::Faker;
 use mockito::Server;
@@ -12,6 +14,8 @@ use url::Url;
 
 use emily_client::apis::deposit_api;
 use emily_client::models::CreateDepositRequestBody;
+use emily_client::models::Status;
+use emily_client::models::UpdateDepositsResponse;
 use signer::bitcoin::MockBitcoinInteract;
 use signer::blocklist_client::BlocklistClient;
 use signer::context::Context;
@@ -25,6 +29,8 @@ use signer::network::in_memory2::SignerNetwork;
 use signer::request_decider::RequestDeciderEventLoop;
 use signer::stacks::api::MockStacksInteract;
 use signer::storage::DbRead as _;
+use signer::storage::DbWrite as _;
+use signer::storage::model;
 use signer::storage::model::BitcoinBlockHash;
 use signer::storage::postgres::PgStore;
 use signer::testing;
@@ -60,6 +66,7 @@ fn test_environment(
         num_withdraw_requests_per_block: 5,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
 
     let context = TestContext::builder()
@@ -726,3 +733,81 @@ async fn do_not_procceed_with_blocked_addresses(is_withdrawal: bool, is_blocked:
 
     testing::storage::drop_db(db).await;
 }
+
+/// Test that [`RequestDeciderEventLoop::handle_pending_deposit_request`]
+/// votes against deposit requests that can never be swept, and marks
+/// them as failed in Emily with the reason.
+#[test_case::test_case(1, true; "lock-time within the buffer")]
+#[test_case::test_case(LockTime::from_512_second_intervals(10).to_consensus_u32(), true; "lock-time in time units")]
+#[test_case::test_case(150, false; "supported lock-time")]
+#[tokio::test]
+async fn deposits_that_can_never_be_accepted_are_declined_in_emily(lock_time: u32, declined: bool) {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = get_rng();
+
+    let mut ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_mocked_clients()
+        .modify_settings(|settings| {
+            settings.signer.deposit_decline_quorum = NonZeroU16::new(1);
+        })
+        .build();
+
+    let bitcoin_block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+    let request = model::DepositRequest {
+        lock_time,
+        ..Faker.fake_with_rng(&mut rng)
+    };
+    db.write_deposit_request(&request).await.unwrap();
+
+    let txid = request.txid.to_string();
+    let output_index = request.output_index;
+    ctx.with_emily_client(|client| {
+        client
+            .expect_update_deposits()
+            .withf(move |updates| {
+                matches!(updates.as_slice(), [update] if update.bitcoin_txid == txid
+                    && update.bitcoin_tx_output_index == output_index
+                    && update.status == Status::Failed
+                    && update.status_message == "unsupported_lock_time")
+            })
+            .times(usize::from(declined))
+            .returning(|_| {
+                Box::pin(std::future::ready(Ok(UpdateDepositsResponse {
+                    deposits: Vec::new(),
+                })))
+            });
+    })
+    .await;
+
+    let network = InMemoryNetwork::new();
+    let mut request_decider = RequestDeciderEventLoop {
+        network: network.connect(),
+        context: ctx.clone(),
+        context_window: 1,
+        deposit_decisions_retry_window: 1,
+        withdrawal_decisions_retry_window: 1,
+        blocklist_checker: Some(()),
+        signer_private_key: PrivateKey::new(&mut rng),
+    };
+
+    // We need this so that there is a live "network". Otherwise,
+    // handle_pending_deposit_request will error when trying to send a
+    // message at the end.
+    let _rec = ctx.get_signal_receiver();
+
+    request_decider
+        .handle_pending_deposit_request(request.clone(), &bitcoin_block.block_hash)
+        .await
+        .unwrap();
+
+    let votes = db
+        .get_deposit_signers(&request.txid, request.output_index)
+        .await
+        .unwrap();
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].can_accept, !declined);
+
+    testing::storage::drop_db(db).await;
+}
@@ -185,6 +185,7 @@ async fn rotate_key_validation_happy_path() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -235,6 +236,7 @@ async fn rotate_key_validation_no_dkg() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -273,6 +275,7 @@ async fn rotate_key_validation_wrong_deployer() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -317,6 +320,7 @@ async fn rotate_key_validation_wrong_signing_set() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -367,6 +371,7 @@ async fn rotate_key_validation_wrong_aggregate_key() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -417,6 +422,7 @@ async fn rotate_key_validation_wrong_signatures_required() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -472,6 +478,7 @@ async fn rotate_key_validation_replay() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -511,6 +518,7 @@ async fn rotate_key_validation_replay() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
@@ -537,6 +545,7 @@ async fn rotate_key_validation_not_verfied() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_model_params);
     test_data.write_to(&mut db).await;
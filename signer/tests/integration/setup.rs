@@ -1,6 +1,5 @@
 use std::collections::BTreeMap;
 use std::collections::HashSet;
-use std::ops::Deref;
 
 use bitcoin::AddressType;
 use bitcoin::Amount;
@@ -18,7 +17,6 @@ use fake::Faker;
 use rand::rngs::OsRng;
 use sbtc::deposits::CreateDepositRequest;
 use sbtc::deposits::DepositInfo;
-use sbtc::testing::regtest;
 use sbtc::testing::regtest::Faucet;
 use sbtc::testing::regtest::Recipient;
 use signer::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
@@ -40,6 +38,7 @@ use signer::context::SbtcLimits;
 use signer::keys::PrivateKey;
 use signer::keys::PublicKey;
 use signer::keys::SignerScriptPubKey;
+use signer::keys::share_encryption;
 use signer::stacks::api::MockStacksInteract;
 use signer::storage::DbRead;
 use signer::storage::DbWrite as _;
@@ -58,6 +57,7 @@ use signer::storage::postgres::PgStore;
 use signer::testing::context::TestContext;
 use signer::testing::context::*;
 use signer::testing::dummy::Unit;
+pub use signer::testing::storage::backfill_bitcoin_blocks;
 use testing_emily_client::apis::configuration::ApiKey as TestingEmilyApiKey;
 use testing_emily_client::apis::configuration::Configuration as TestingEmilyApiConfiguration;
 
@@ -207,6 +207,7 @@ impl TestSweepSetup {
             num_signers: 7,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // There should only be one transaction here since there is only
@@ -423,27 +424,6 @@ impl TestSweepSetup {
     }
 }
 
-/// Fetch all block headers from bitcoin-core and store it in the database.
-pub async fn backfill_bitcoin_blocks(db: &PgStore, rpc: &Client, chain_tip: &bitcoin::BlockHash) {
-    let mut block_header = rpc.get_block_header_info(&chain_tip).unwrap();
-
-    // There are no non-coinbase transactions below this height.
-    while block_header.height as u64 >= regtest::MIN_BLOCKCHAIN_HEIGHT {
-        let parent_header_hash = block_header.previous_block_hash.unwrap();
-        let bitcoin_block = BitcoinBlock {
-            block_hash: block_header.hash.into(),
-            block_height: (block_header.height as u64).into(),
-            parent_hash: parent_header_hash.into(),
-        };
-
-        db.write_bitcoin_block(&bitcoin_block).await.unwrap();
-        block_header = rpc.get_block_header_info(&parent_header_hash).unwrap();
-    }
-
-    let block_hash = db.get_bitcoin_canonical_chain_tip().await.unwrap().unwrap();
-    assert_eq!(block_hash.deref(), chain_tip);
-}
-
 /// Fetch all block headers from bitcoin-core and store it in the database.
 pub async fn fetch_canonical_bitcoin_blockchain(db: &PgStore, rpc: &Client) -> BitcoinBlockHash {
     let chain_tip_info = rpc.get_blockchain_info().unwrap();
@@ -943,6 +923,7 @@ impl TestSweepSetup2 {
             num_signers: 7,
             sbtc_limits: SbtcLimits::unlimited(),
             max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+            max_requests_per_bitcoin_tx: None,
         };
 
         // There should only be one transaction here since there is only
@@ -1226,11 +1207,15 @@ impl TestSweepSetup2 {
             parties: vec![Unit.fake_with_rng(&mut OsRng)],
         };
         let encoded = private_shares.encode_to_vec();
-        let signer_private_key = self.signers.private_key().to_bytes();
-
-        let encrypted_private_shares =
-            wsts::util::encrypt(&signer_private_key, &encoded, &mut OsRng)
-                .expect("failed to encrypt");
+        let signer_private_key = self.signers.private_key();
+
+        let encrypted_private_shares = share_encryption::encrypt_shares(
+            &signer_private_key,
+            &aggregate_key,
+            &encoded,
+            &mut OsRng,
+        )
+        .expect("failed to encrypt");
         let public_shares: BTreeMap<u32, wsts::net::DkgPublicShares> = BTreeMap::new();
 
         let shares = EncryptedDkgShares {
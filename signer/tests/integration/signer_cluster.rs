@@ -0,0 +1,73 @@
+use bitcoincore_rpc::RpcApi as _;
+use signer::bitcoin::validation::DepositConfirmationStatus;
+use signer::keys::SignerScriptPubKey as _;
+use signer::storage::DbRead as _;
+use signer::testing::harness::SignerCluster;
+
+/// Test that a cluster of signers can run DKG and sweep a deposit.
+///
+/// The signers use a real bitcoin-core node, their own postgres
+/// databases, and an in-process Emily server. To start the test
+/// environment do:
+/// ```bash
+/// make integration-env-up-ci
+/// ```
+///
+/// then, once everything is up and running, run the test.
+#[tokio::test]
+async fn signer_cluster_sweeps_a_deposit() {
+    let cluster = SignerCluster::builder().start().await;
+
+    let aggregate_key = cluster.run_dkg().await;
+    cluster.fund_signers(100_000).await;
+
+    let deposit = cluster.make_deposit(2_500_000).await;
+    cluster.mine(1).await;
+
+    // The sweep spends the signers' UTXO and the deposit, and it locks the
+    // swept funds with the signers' aggregate key.
+    let sweep_tx = cluster.wait_for_sweep().await;
+    let outpoints: Vec<_> = sweep_tx
+        .input
+        .iter()
+        .map(|tx_in| tx_in.previous_output)
+        .collect();
+    assert_eq!(outpoints.len(), 2);
+    assert!(outpoints.contains(&deposit.outpoint));
+    assert_eq!(
+        sweep_tx.output[0].script_pubkey,
+        aggregate_key.signers_script_pubkey()
+    );
+
+    // Once the sweep is confirmed, every signer knows about it.
+    let block_hash = cluster.mine(1).await.pop().unwrap();
+    let block = cluster.rpc().get_block(&block_hash).unwrap();
+    assert!(block.txdata.contains(&sweep_tx));
+
+    let sweep_txid = sweep_tx.compute_txid();
+    for signer in cluster.signers() {
+        let chain_tip = signer
+            .storage
+            .get_bitcoin_canonical_chain_tip()
+            .await
+            .unwrap()
+            .unwrap();
+        let report = signer
+            .storage
+            .get_deposit_request_report(
+                &chain_tip,
+                &deposit.outpoint.txid.into(),
+                deposit.outpoint.vout,
+                &signer.keypair.public_key().into(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            report.status,
+            DepositConfirmationStatus::Spent(sweep_txid.into())
+        );
+    }
+
+    cluster.shutdown().await;
+}
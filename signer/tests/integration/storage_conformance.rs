@@ -0,0 +1,76 @@
+//! Runs the storage conformance suite against postgres. The same suite
+//! is run against the in-memory store in the unit tests.
+
+use signer::testing::storage::conformance;
+use signer::testing::storage::drop_db;
+use signer::testing::storage::new_test_database;
+
+#[tokio::test]
+async fn bitcoin_chain_tip_ties_break_on_block_hash() {
+    let db = new_test_database().await;
+    conformance::bitcoin_chain_tip_ties_break_on_block_hash(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn stacks_chain_tip_follows_bitcoin_fork() {
+    let db = new_test_database().await;
+    conformance::stacks_chain_tip_follows_bitcoin_fork(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn pending_deposit_requests_respect_context_window() {
+    let db = new_test_database().await;
+    conformance::pending_deposit_requests_respect_context_window(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn pending_withdrawal_requests_respect_context_window() {
+    let db = new_test_database().await;
+    conformance::pending_withdrawal_requests_respect_context_window(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn stacks_block_events_are_idempotent() {
+    let db = new_test_database().await;
+    conformance::stacks_block_events_are_idempotent(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn signer_set_aggregate_keys_follow_canonical_chain() {
+    let db = new_test_database().await;
+    conformance::signer_set_aggregate_keys_follow_canonical_chain(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn pending_signer_decisions_are_replayed_once_requests_exist() {
+    let db = new_test_database().await;
+    conformance::pending_signer_decisions_are_replayed_once_requests_exist(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn p2p_message_sequences_increase_per_signer() {
+    let db = new_test_database().await;
+    conformance::p2p_message_sequences_increase_per_signer(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn p2p_peers_expire_unless_seen_again() {
+    let db = new_test_database().await;
+    conformance::p2p_peers_expire_unless_seen_again(&db).await;
+    drop_db(db).await;
+}
+
+#[tokio::test]
+async fn encrypted_private_shares_can_be_replaced() {
+    let db = new_test_database().await;
+    conformance::encrypted_private_shares_can_be_replaced(&db).await;
+    drop_db(db).await;
+}
@@ -1,4 +1,5 @@
 use std::collections::BTreeSet;
+use std::num::NonZeroU16;
 use std::num::NonZeroU32;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
@@ -42,6 +43,7 @@ use signer::bitcoin::BitcoinInteract as _;
 use signer::bitcoin::rpc::BitcoinCoreClient;
 use signer::bitcoin::utxo::BitcoinInputsOutputs;
 use signer::bitcoin::utxo::Fees;
+use signer::bitcoin::validation::ReportCache;
 use signer::bitcoin::validation::WithdrawalValidationResult;
 use signer::context::RequestDeciderEvent;
 use signer::message::Payload;
@@ -95,6 +97,7 @@ use url::Url;
 use signer::block_observer::BlockObserver;
 use signer::context::Context;
 use signer::emily_client::EmilyClient;
+use signer::emily_client::FetchedDeposits;
 use signer::error::Error;
 use signer::keys;
 use signer::keys::PublicKey;
@@ -118,6 +121,7 @@ use signer::testing::wsts::SignerSet;
 use signer::transaction_coordinator;
 use signer::transaction_coordinator::TxCoordinatorEventLoop;
 use signer::transaction_signer::TxSignerEventLoop;
+use signer::wsts_state_machine::RoundTracker;
 use tokio::sync::broadcast::Sender;
 
 use crate::complete_deposit::make_complete_deposit;
@@ -134,7 +138,6 @@ use crate::setup::set_deposit_completed;
 use crate::setup::set_deposit_incomplete;
 use crate::utxo_construction::generate_withdrawal;
 use crate::utxo_construction::make_deposit_request;
-use crate::zmq::BITCOIN_CORE_ZMQ_ENDPOINT;
 
 type IntegrationTestContext =
     TestContext<PgStore, BitcoinCoreClient, WrappedMock<MockStacksInteract>, EmilyClient>;
@@ -570,7 +573,15 @@ async fn process_complete_deposit() {
         .await;
 
     // Get the private key of the coordinator of the signer set.
-    let private_key = select_coordinator(&setup.sweep_block_hash.into(), &signer_info);
+    let sweep_block_ref = model::BitcoinBlockRef {
+        block_hash: setup.sweep_block_hash.into(),
+        block_height: setup.sweep_block_height,
+    };
+    let activation_height = context
+        .config()
+        .signer
+        .coordinator_election_activation_height;
+    let private_key = select_coordinator(&sweep_block_ref, activation_height, &signer_info);
 
     // Bootstrap the tx coordinator event loop
     context.state().set_sbtc_contracts_deployed();
@@ -735,7 +746,11 @@ async fn deploy_smart_contracts_coordinator<F>(
         .await;
 
     // Get the private key of the coordinator of the signer set.
-    let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+    let activation_height = context
+        .config()
+        .signer
+        .coordinator_election_activation_height;
+    let private_key = select_coordinator(&bitcoin_chain_tip, activation_height, &signer_info);
 
     // Bootstrap the tx coordinator event loop
     let tx_coordinator = transaction_coordinator::TxCoordinatorEventLoop {
@@ -845,6 +860,7 @@ async fn run_dkg_from_scratch() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_params);
 
@@ -1072,6 +1088,7 @@ async fn run_subsequent_dkg() {
         num_withdraw_requests_per_block: 0,
         num_signers_per_request: 0,
         consecutive_blocks: false,
+        deposit_scripts: None,
     };
     let test_data = TestData::generate(&mut rng, &[], &test_params);
 
@@ -1204,6 +1221,9 @@ async fn run_subsequent_dkg() {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         });
 
     // We only proceed with the test after all processes have started, and
@@ -1527,6 +1547,9 @@ async fn sign_bitcoin_transaction() {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -1551,8 +1574,7 @@ async fn sign_bitcoin_transaction() {
 
         let block_observer = BlockObserver {
             context: ctx.clone(),
-            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT)
-                .await,
+            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -1966,6 +1988,9 @@ async fn sign_bitcoin_transaction_multiple_locking_keys() {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -1990,8 +2015,7 @@ async fn sign_bitcoin_transaction_multiple_locking_keys() {
 
         let block_observer = BlockObserver {
             context: ctx.clone(),
-            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT)
-                .await,
+            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -2550,6 +2574,9 @@ async fn skip_smart_contract_deployment_and_key_rotation_if_up_to_date() {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -2574,8 +2601,7 @@ async fn skip_smart_contract_deployment_and_key_rotation_if_up_to_date() {
 
         let block_observer = BlockObserver {
             context: ctx.clone(),
-            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT)
-                .await,
+            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -2829,11 +2855,17 @@ async fn test_get_btc_state_with_available_sweep_transactions_and_rbf() {
     )
     .unwrap();
 
+    // The transactions below pay fee rates that are far above what
+    // bitcoin-core estimates on regtest, so we lift the market ceiling to
+    // keep get_btc_state from refusing to replace them.
     let context = TestContext::builder()
         .with_storage(db.clone())
         .with_bitcoin_client(client.clone())
         .with_mocked_emily_client()
         .with_mocked_stacks_client()
+        .modify_settings(|settings| {
+            settings.signer.bitcoin_fee_rate_market_multiple = NonZeroU16::MAX;
+        })
         .build();
     let network = SignerNetwork::single(&context);
 
@@ -3183,7 +3215,10 @@ async fn test_conservative_initial_sbtc_limits() {
             // We already stored the deposit, we don't need it from Emily
             client
                 .expect_get_deposits()
-                .returning(|| Box::pin(std::future::ready(Ok(vec![]))));
+                .returning(|| Box::pin(std::future::ready(Ok(FetchedDeposits::default()))));
+            client
+                .expect_get_withdrawals()
+                .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
 
             // We don't care about this
             client.expect_accept_deposits().returning(|_| {
@@ -3352,6 +3387,9 @@ async fn test_conservative_initial_sbtc_limits() {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -3376,8 +3414,7 @@ async fn test_conservative_initial_sbtc_limits() {
 
         let block_observer = BlockObserver {
             context: ctx.clone(),
-            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT)
-                .await,
+            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -3398,10 +3435,26 @@ async fn test_conservative_initial_sbtc_limits() {
     //   coordinator)
     // =========================================================================
     let signers_key = setup.signers.signer_keys().iter().cloned().collect();
+    let activation_height = signers[0]
+        .0
+        .config()
+        .signer
+        .coordinator_election_activation_height;
     loop {
-        let chain_tip: BitcoinBlockHash = faucet.generate_blocks(1).pop().unwrap().into();
+        let block_hash = faucet.generate_blocks(1).pop().unwrap();
+        let block_header = rpc.get_block_header_info(&block_hash).unwrap();
+        let chain_tip = model::BitcoinBlockRef {
+            block_hash: block_hash.into(),
+            block_height: (block_header.height as u64).into(),
+        };
 
-        if given_key_is_coordinator(signers[0].2.public_key().into(), &chain_tip, &signers_key) {
+        let coordinator_public_key = signers[0].2.public_key().into();
+        if given_key_is_coordinator(
+            coordinator_public_key,
+            &chain_tip,
+            &signers_key,
+            activation_height,
+        ) {
             break;
         }
     }
@@ -3654,6 +3707,9 @@ async fn sign_bitcoin_transaction_withdrawals() {
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
             dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+            report_cache: ReportCache::default(),
+            round_tracker: RoundTracker::default(),
+            presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -3678,8 +3734,7 @@ async fn sign_bitcoin_transaction_withdrawals() {
 
         let block_observer = BlockObserver {
             context: ctx.clone(),
-            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(BITCOIN_CORE_ZMQ_ENDPOINT)
-                .await,
+            bitcoin_blocks: testing::btc::new_zmq_block_hash_stream(&ctx.config().bitcoin).await,
         };
         let counter = start_count.clone();
         tokio::spawn(async move {
@@ -4181,7 +4236,11 @@ async fn process_rejected_withdrawal(is_completed: bool, is_in_mempool: bool) {
         .await;
 
     // Get the private key of the coordinator of the signer set.
-    let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+    let activation_height = context
+        .config()
+        .signer
+        .coordinator_election_activation_height;
+    let private_key = select_coordinator(&bitcoin_chain_tip, activation_height, &signer_info);
 
     // Bootstrap the tx coordinator event loop
     context.state().set_sbtc_contracts_deployed();
@@ -20,6 +20,7 @@ use test_case::test_case;
 use signer::bitcoin::utxo::RequestRef;
 use signer::bitcoin::utxo::Requests;
 use signer::bitcoin::utxo::UnsignedTransaction;
+use signer::bitcoin::validation::ReportCache;
 use signer::bitcoin::validation::TxRequestIds;
 use signer::block_observer::get_signer_set_and_aggregate_key;
 use signer::context::Context;
@@ -49,6 +50,7 @@ use signer::testing::get_rng;
 use signer::transaction_signer::ChainTipStatus;
 use signer::transaction_signer::MsgChainTipReport;
 use signer::transaction_signer::TxSignerEventLoop;
+use signer::wsts_state_machine::RoundTracker;
 use signer::wsts_state_machine::StateMachineId;
 use wsts::net::DkgBegin;
 use wsts::net::NonceRequest;
@@ -118,6 +120,9 @@ async fn signing_set_validation_check_for_stacks_transactions() {
         rng: rand::rngs::StdRng::seed_from_u64(51),
         dkg_begin_pause: None,
         dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+        report_cache: ReportCache::default(),
+        round_tracker: RoundTracker::default(),
+        presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
     };
 
     // Let's create a proper sign request.
@@ -213,6 +218,9 @@ async fn signer_rejects_stacks_txns_with_too_high_a_fee(
         rng: rand::rngs::StdRng::seed_from_u64(51),
         dkg_begin_pause: None,
         dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+        report_cache: ReportCache::default(),
+        round_tracker: RoundTracker::default(),
+        presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
     };
 
     // Setup the transaction fee to be the maximum fee configured plus one, so that it
@@ -255,13 +263,19 @@ pub async fn assert_should_be_able_to_handle_sbtc_requests() {
     let mut rng = get_rng();
     let fee_rate = 1.3;
     // Build the test context with mocked clients
-    let ctx = TestContext::builder()
+    let mut ctx = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_bitcoin_client()
         .with_mocked_emily_client()
         .with_mocked_stacks_client()
         .build();
     ctx.state().update_current_limits(SbtcLimits::unlimited());
+    ctx.with_bitcoin_client(|client| {
+        client
+            .expect_estimate_fee_rate()
+            .returning(move || Box::pin(async move { Ok(fee_rate) }));
+    })
+    .await;
 
     let (rpc, faucet) = sbtc::testing::regtest::initialize_blockchain();
 
@@ -310,6 +324,9 @@ pub async fn assert_should_be_able_to_handle_sbtc_requests() {
         rng: rand::rngs::StdRng::seed_from_u64(51),
         dkg_begin_pause: None,
         dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+        report_cache: ReportCache::default(),
+        round_tracker: RoundTracker::default(),
+        presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
     };
 
     let sbtc_requests: TxRequestIds = TxRequestIds {
@@ -382,6 +399,38 @@ pub async fn assert_should_be_able_to_handle_sbtc_requests() {
 
     assert!(will_sign);
 
+    // The signer keeps the constructed sighashes around, so that it can
+    // record exactly what it signs. Recording them gives rows that match
+    // the sighashes that were constructed from the transaction.
+    let origin_public_key = PublicKey::from_private_key(&PrivateKey::new(&mut rng));
+    for digest in [signer_digest, deposit_digest] {
+        let presign_sighash = tx_signer
+            .presign_sighashes
+            .peek(&digest.sighash.into())
+            .copied()
+            .expect("sighash from the pre-sign request not kept");
+        let signed = presign_sighash.to_signed_sighash(chain_tip.block_hash, origin_public_key);
+        db.write_signed_sighash(&signed).await.unwrap();
+    }
+
+    let signed_sighashes = db
+        .get_signed_sighashes(&signer_digest.txid.into())
+        .await
+        .unwrap();
+    assert_eq!(signed_sighashes.len(), 2);
+
+    for (signed, digest) in signed_sighashes.iter().zip([signer_digest, deposit_digest]) {
+        assert_eq!(signed.sighash, digest.sighash.into());
+        assert_eq!(signed.txid, digest.txid.into());
+        assert_eq!(signed.input_index, digest.input_index);
+        assert_eq!(signed.aggregate_key, digest.aggregate_key.into());
+        assert_eq!(signed.chain_tip, chain_tip.block_hash);
+        assert_eq!(signed.origin_public_key, origin_public_key);
+
+        let tx_in = &unsigned_tx.tx.input[signed.input_index as usize];
+        assert_eq!(tx_in.previous_output, digest.outpoint);
+    }
+
     testing::storage::drop_db(db).await;
 }
 
@@ -394,12 +443,18 @@ pub async fn presign_requests_with_dkg_shares_status(status: DkgSharesStatus, is
 
     let mut rng = get_rng();
     // Build the test context with mocked clients
-    let ctx = TestContext::builder()
+    let mut ctx = TestContext::builder()
         .with_storage(db.clone())
         .with_mocked_bitcoin_client()
         .with_mocked_emily_client()
         .with_mocked_stacks_client()
         .build();
+    ctx.with_bitcoin_client(|client| {
+        client
+            .expect_estimate_fee_rate()
+            .returning(|| Box::pin(async { Ok(2.0) }));
+    })
+    .await;
 
     let (rpc, faucet) = sbtc::testing::regtest::initialize_blockchain();
 
@@ -455,6 +510,9 @@ pub async fn presign_requests_with_dkg_shares_status(status: DkgSharesStatus, is
         rng: rand::rngs::StdRng::seed_from_u64(51),
         dkg_begin_pause: None,
         dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+        report_cache: ReportCache::default(),
+        round_tracker: RoundTracker::default(),
+        presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
     };
 
     let sbtc_requests: TxRequestIds = TxRequestIds {
@@ -520,6 +578,9 @@ async fn new_state_machine_per_valid_sighash() {
         rng: rand::rngs::StdRng::seed_from_u64(51),
         dkg_begin_pause: None,
         dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+        report_cache: ReportCache::default(),
+        round_tracker: RoundTracker::default(),
+        presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
     };
 
     // We need to convince the signer event loop that it should accept the
@@ -556,6 +617,8 @@ async fn new_state_machine_per_valid_sighash() {
     // Now for the nonce request message
     let mut nonce_request_msg = WstsMessage {
         id: WstsMessageId::Sweep(*txid),
+        session_id: Faker.fake_with_rng(&mut rng),
+        sighash: Some(sighash),
         inner: wsts::net::Message::NonceRequest(NonceRequest {
             dkg_id: 1,
             sign_id: 1,
@@ -591,6 +654,7 @@ async fn new_state_machine_per_valid_sighash() {
         }
         _ => panic!("You forgot to update the variant"),
     };
+    nonce_request_msg.sighash = Some(random_sighash);
 
     let response = tx_signer
         .handle_wsts_message(&nonce_request_msg, msg_public_key, &report)
@@ -647,6 +711,9 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
         rng: rand::rngs::StdRng::seed_from_u64(51),
         dkg_begin_pause: None,
         dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
+        report_cache: ReportCache::default(),
+        round_tracker: RoundTracker::default(),
+        presign_sighashes: LruCache::new(NonZeroUsize::new(100).unwrap()),
     };
 
     // We need to convince the signer event loop that it should accept the
@@ -663,6 +730,8 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
     let dkg_id = 2;
     let dkg_begin_msg = WstsMessage {
         id: bitcoin::Txid::all_zeros().into(),
+        session_id: Faker.fake_with_rng(&mut rng),
+        sighash: None,
         inner: wsts::net::Message::DkgBegin(DkgBegin { dkg_id }),
     };
     let msg_public_key = PublicKey::from_private_key(&PrivateKey::new(&mut rng));
@@ -688,6 +757,8 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
     let dkg_id = 1234;
     let dkg_begin_msg = WstsMessage {
         id: bitcoin::Txid::from_byte_array(Faker.fake_with_rng(&mut rng)).into(),
+        session_id: Faker.fake_with_rng(&mut rng),
+        sighash: None,
         inner: wsts::net::Message::DkgBegin(DkgBegin { dkg_id }),
     };
 
@@ -12,23 +12,16 @@ use bitcoin::Transaction;
 use bitcoin::TxIn;
 use bitcoin::TxOut;
 use bitcoin::Witness;
-use bitcoin::XOnlyPublicKey;
 use bitcoin::absolute::LockTime;
 use bitcoin::transaction::Version;
 use bitcoincore_rpc::RpcApi as _;
 use bitvec::array::BitArray;
-use clarity::vm::types::PrincipalData;
 use fake::Fake;
 use rand::Rng as _;
 use rand::distributions::Uniform;
 use rand::rngs::OsRng;
-use sbtc::deposits::CreateDepositRequest;
-use sbtc::deposits::DepositInfo;
-use sbtc::deposits::DepositScriptInputs;
-use sbtc::deposits::ReclaimScriptInputs;
 use signer::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
 use signer::bitcoin::rpc::BitcoinCoreClient;
-use signer::bitcoin::utxo::DepositRequest;
 use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
@@ -37,7 +30,7 @@ use signer::bitcoin::utxo::WithdrawalRequest;
 use signer::config::Settings;
 use signer::context::SbtcLimits;
 use signer::keys::SignerScriptPubKey;
-use stacks_common::types::chainstate::StacksAddress;
+pub use signer::testing::btc::make_deposit_request;
 
 use regtest::Recipient;
 use sbtc::testing::regtest;
@@ -66,73 +59,6 @@ pub fn make_withdrawal(amount: u64, max_fee: u64) -> (WithdrawalRequest, Recipie
     (req, recipient)
 }
 
-pub fn make_deposit_request<U>(
-    depositor: &Recipient,
-    amount: u64,
-    utxo: U,
-    max_fee: u64,
-    signers_public_key: XOnlyPublicKey,
-) -> (Transaction, DepositRequest, DepositInfo)
-where
-    U: AsUtxo,
-{
-    let fee = regtest::BITCOIN_CORE_FALLBACK_FEE.to_sat();
-    let deposit_inputs = DepositScriptInputs {
-        signers_public_key,
-        max_fee,
-        recipient: PrincipalData::from(StacksAddress::burn_address(false)),
-    };
-    let reclaim_inputs = ReclaimScriptInputs::try_new(50, ScriptBuf::new()).unwrap();
-
-    let deposit_script = deposit_inputs.deposit_script();
-    let reclaim_script = reclaim_inputs.reclaim_script();
-
-    let mut deposit_tx = Transaction {
-        version: Version::ONE,
-        lock_time: LockTime::ZERO,
-        input: vec![TxIn {
-            previous_output: OutPoint::new(utxo.txid(), utxo.vout()),
-            sequence: Sequence::ZERO,
-            script_sig: ScriptBuf::new(),
-            witness: Witness::new(),
-        }],
-        output: vec![
-            TxOut {
-                value: Amount::from_sat(amount),
-                script_pubkey: sbtc::deposits::to_script_pubkey(
-                    deposit_script.clone(),
-                    reclaim_script.clone(),
-                ),
-            },
-            TxOut {
-                value: utxo.amount() - Amount::from_sat(amount + fee),
-                script_pubkey: depositor.address.script_pubkey(),
-            },
-        ],
-    };
-
-    regtest::p2tr_sign_transaction(&mut deposit_tx, 0, &[utxo], &depositor.keypair);
-
-    let create_req = CreateDepositRequest {
-        outpoint: OutPoint::new(deposit_tx.compute_txid(), 0),
-        deposit_script,
-        reclaim_script,
-    };
-
-    let dep = create_req.validate_tx(&deposit_tx, false).unwrap();
-
-    let req = DepositRequest {
-        outpoint: dep.outpoint,
-        max_fee: dep.max_fee,
-        signer_bitmap: BitArray::ZERO,
-        amount: dep.amount,
-        deposit_script: dep.deposit_script.clone(),
-        reclaim_script: dep.reclaim_script.clone(),
-        signers_public_key: dep.signers_public_key,
-    };
-    (deposit_tx, req, dep)
-}
-
 /// This test just checks that many of the methods on the Recipient struct
 /// work as advertised.
 #[test]
@@ -235,6 +161,7 @@ fn deposits_add_to_controlled_amounts() {
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_requests_per_bitcoin_tx: None,
     };
 
     // There should only be one transaction here since there is only one
@@ -300,6 +227,7 @@ fn withdrawals_reduce_to_signers_amounts() {
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_requests_per_bitcoin_tx: None,
     };
 
     // There should only be one transaction here since there is only one
@@ -445,6 +373,7 @@ fn parse_withdrawal_ids(withdrawal_numbers: u64) {
         num_signers: 7,
         sbtc_limits: SbtcLimits::unlimited(),
         max_deposits_per_bitcoin_tx: DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
+        max_requests_per_bitcoin_tx: None,
     };
 
     // There should only be one transaction here since there are only
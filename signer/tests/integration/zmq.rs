@@ -3,8 +3,7 @@ use bitcoin::BlockHash;
 use futures::StreamExt;
 use sbtc::testing::regtest;
 use signer::bitcoin::zmq::BitcoinCoreMessageStream;
-
-pub const BITCOIN_CORE_ZMQ_ENDPOINT: &str = "tcp://localhost:28332";
+use signer::config::Settings;
 
 /// This tests that out bitcoin block stream receives new blocks from
 /// bitcoin-core as it receives them. We create the stream, generate
@@ -13,7 +12,11 @@ pub const BITCOIN_CORE_ZMQ_ENDPOINT: &str = "tcp://localhost:28332";
 async fn block_stream_streams_blocks() {
     let (_, faucet) = regtest::initialize_blockchain();
 
-    let stream = BitcoinCoreMessageStream::new_from_endpoint(BITCOIN_CORE_ZMQ_ENDPOINT)
+    // Our bitcoin-core publishes raw blocks on the same endpoint as
+    // block hashes.
+    let mut config = Settings::new_from_default_config().unwrap().bitcoin;
+    config.block_stream_endpoints = config.block_hash_stream_endpoints.clone();
+    let stream = BitcoinCoreMessageStream::new_block_stream(&config)
         .await
         .unwrap();
 
@@ -65,7 +68,8 @@ async fn block_stream_streams_blocks() {
 async fn block_hash_stream_streams_block_hashes() {
     let (_, faucet) = regtest::initialize_blockchain();
 
-    let stream = BitcoinCoreMessageStream::new_from_endpoint(BITCOIN_CORE_ZMQ_ENDPOINT)
+    let config = Settings::new_from_default_config().unwrap().bitcoin;
+    let stream = BitcoinCoreMessageStream::new_block_hash_stream(&config)
         .await
         .unwrap();
 